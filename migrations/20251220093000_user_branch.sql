@@ -0,0 +1,11 @@
+-- Add migration script here
+CREATE TABLE user_branches (
+    user_id INTEGER NOT NULL,
+    branch_id INTEGER NOT NULL,
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    PRIMARY KEY (user_id, branch_id),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (branch_id) REFERENCES branches(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_user_branches_branch_id ON user_branches (branch_id);
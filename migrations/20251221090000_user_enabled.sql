@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE users ADD COLUMN is_enabled BOOLEAN NOT NULL DEFAULT 1;
@@ -0,0 +1,3 @@
+-- Add migration script here
+-- Price in minor units (cents); NULL means the variant has no fixed price
+ALTER TABLE product_variants ADD COLUMN price INTEGER;
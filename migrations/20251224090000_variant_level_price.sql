@@ -0,0 +1,15 @@
+-- Add migration script here
+-- Per customer-level price tiers; price is in minor units (cents)
+CREATE TABLE variant_prices (
+    variant_id INTEGER NOT NULL,
+    customer_level INTEGER NOT NULL,
+    price INTEGER NOT NULL,
+    created_at TEXT DEFAULT(
+        strftime ('%Y-%m-%dT%H:%M:%fZ', 'now')
+    ),
+    updated_at TEXT DEFAULT(
+        strftime ('%Y-%m-%dT%H:%M:%fZ', 'now')
+    ),
+    PRIMARY KEY (variant_id, customer_level),
+    FOREIGN KEY (variant_id) REFERENCES product_variants (id) ON DELETE CASCADE
+);
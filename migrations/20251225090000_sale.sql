@@ -0,0 +1,40 @@
+-- Add migration script here
+-- On-hand quantity of each variant per branch
+CREATE TABLE stocks (
+    branch_id INTEGER NOT NULL,
+    variant_id INTEGER NOT NULL,
+    quantity INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT DEFAULT(
+        strftime ('%Y-%m-%dT%H:%M:%fZ', 'now')
+    ),
+    PRIMARY KEY (branch_id, variant_id),
+    FOREIGN KEY (branch_id) REFERENCES branches (id) ON DELETE CASCADE,
+    FOREIGN KEY (variant_id) REFERENCES product_variants (id) ON DELETE CASCADE
+);
+
+-- Amounts are in minor units (cents)
+CREATE TABLE sales (
+    id INTEGER PRIMARY KEY,
+    created_at TEXT DEFAULT(
+        strftime ('%Y-%m-%dT%H:%M:%fZ', 'now')
+    ),
+    branch_id INTEGER NOT NULL,
+    customer_id INTEGER,
+    total INTEGER NOT NULL,
+    FOREIGN KEY (branch_id) REFERENCES branches (id),
+    FOREIGN KEY (customer_id) REFERENCES customers (id)
+);
+
+CREATE INDEX idx_sales_branch_id_created_at ON sales (branch_id, created_at);
+
+CREATE TABLE sale_lines (
+    sale_id INTEGER NOT NULL,
+    line_no INTEGER NOT NULL,
+    variant_id INTEGER NOT NULL,
+    qty INTEGER NOT NULL,
+    unit_price INTEGER NOT NULL,
+    line_total INTEGER NOT NULL,
+    PRIMARY KEY (sale_id, line_no),
+    FOREIGN KEY (sale_id) REFERENCES sales (id) ON DELETE CASCADE,
+    FOREIGN KEY (variant_id) REFERENCES product_variants (id)
+);
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE sales ADD COLUMN payment_method TEXT NOT NULL DEFAULT 'cash';
@@ -0,0 +1,13 @@
+-- Add migration script here
+-- Maps a client supplied Idempotency-Key to the sale it created
+CREATE TABLE idempotency_keys (
+    key TEXT PRIMARY KEY,
+    sale_id INTEGER NOT NULL,
+    created_at TEXT DEFAULT(
+        strftime ('%Y-%m-%dT%H:%M:%fZ', 'now')
+    ),
+    expires_at TEXT NOT NULL,
+    FOREIGN KEY (sale_id) REFERENCES sales (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_idempotency_keys_expires_at ON idempotency_keys (expires_at);
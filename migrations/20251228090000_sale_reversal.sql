@@ -0,0 +1,12 @@
+-- Add migration script here
+-- 'sale' for a regular sale; 'void' and 'refund' rows reverse (part of) the
+-- sale in original_sale_id and hold the returned quantities as positive values
+ALTER TABLE sales ADD COLUMN kind TEXT NOT NULL DEFAULT 'sale';
+ALTER TABLE sales ADD COLUMN original_sale_id INTEGER REFERENCES sales (id);
+
+CREATE INDEX idx_sales_original_sale_id ON sales (original_sale_id);
+
+-- A sale can be voided only once
+CREATE UNIQUE INDEX idx_sales_single_void ON sales (original_sale_id)
+WHERE
+    kind = 'void';
@@ -0,0 +1,5 @@
+-- Add migration script here
+-- User ids taken from the request context; NULL for rows written before
+-- auditing existed or by internal jobs
+ALTER TABLE products ADD COLUMN created_by INTEGER;
+ALTER TABLE products ADD COLUMN updated_by INTEGER;
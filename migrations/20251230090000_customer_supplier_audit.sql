@@ -0,0 +1,6 @@
+-- Add migration script here
+-- Same audit columns as products; NULL when written without an actor
+ALTER TABLE customers ADD COLUMN created_by INTEGER;
+ALTER TABLE customers ADD COLUMN updated_by INTEGER;
+ALTER TABLE suppliers ADD COLUMN created_by INTEGER;
+ALTER TABLE suppliers ADD COLUMN updated_by INTEGER;
@@ -0,0 +1,25 @@
+-- Customer numbers used to be unique only with their exact casing, so live
+-- customers may differ only by case, which would stop the case-insensitive
+-- unique index from being created. Keep the number on the oldest live
+-- customer and suffix the others with their id so they can be told apart and
+-- fixed by hand.
+UPDATE customers
+SET
+    number = number || '#' || id,
+    updated_at = strftime ('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+    is_deleted = 0
+    AND EXISTS (
+        SELECT 1
+        FROM customers older
+        WHERE
+            lower(older.number) = lower(customers.number)
+            AND older.is_deleted = 0
+            AND (
+                older.created_at < customers.created_at
+                OR (
+                    older.created_at = customers.created_at
+                    AND older.id < customers.id
+                )
+            )
+    );
@@ -0,0 +1,7 @@
+-- Customer numbers are unique and looked up case-insensitively; `number` keeps
+-- the casing as entered for display. Numbers that differ only by case are
+-- renamed first by 20251231080000_customer_number_dedupe.sql.
+ALTER TABLE customers ADD COLUMN number_normalized TEXT GENERATED ALWAYS AS (lower(number)) VIRTUAL;
+
+DROP INDEX idx_customers_number_unique;
+CREATE UNIQUE INDEX idx_customers_number_unique ON customers (number_normalized) WHERE is_deleted = 0;
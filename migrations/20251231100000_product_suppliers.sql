@@ -0,0 +1,13 @@
+-- Add migration script here
+-- Which suppliers a product can be bought from; cost is in minor units
+CREATE TABLE product_suppliers (
+    product_id INTEGER NOT NULL,
+    supplier_id INTEGER NOT NULL,
+    supplier_sku TEXT,
+    cost INTEGER,
+    PRIMARY KEY (product_id, supplier_id),
+    FOREIGN KEY (product_id) REFERENCES products (id),
+    FOREIGN KEY (supplier_id) REFERENCES suppliers (id)
+);
+
+CREATE INDEX idx_product_suppliers_supplier_id ON product_suppliers (supplier_id);
@@ -0,0 +1,26 @@
+-- Add migration script here
+-- Stock ordered from a supplier for one branch. Costs are in minor units (cents)
+CREATE TABLE purchase_orders (
+    id INTEGER PRIMARY KEY,
+    created_at TEXT DEFAULT(
+        strftime ('%Y-%m-%dT%H:%M:%fZ', 'now')
+    ),
+    supplier_id INTEGER NOT NULL,
+    branch_id INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'ordered',
+    FOREIGN KEY (supplier_id) REFERENCES suppliers (id),
+    FOREIGN KEY (branch_id) REFERENCES branches (id)
+);
+
+CREATE INDEX idx_purchase_orders_supplier_id ON purchase_orders (supplier_id);
+
+CREATE TABLE purchase_order_lines (
+    purchase_order_id INTEGER NOT NULL,
+    line_no INTEGER NOT NULL,
+    variant_id INTEGER NOT NULL,
+    qty INTEGER NOT NULL,
+    cost INTEGER NOT NULL,
+    PRIMARY KEY (purchase_order_id, line_no),
+    FOREIGN KEY (purchase_order_id) REFERENCES purchase_orders (id) ON DELETE CASCADE,
+    FOREIGN KEY (variant_id) REFERENCES product_variants (id)
+);
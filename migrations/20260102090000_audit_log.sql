@@ -0,0 +1,16 @@
+-- Add migration script here
+-- Security-relevant events, currently permission denials from require_access
+CREATE TABLE audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at TEXT DEFAULT(
+        strftime ('%Y-%m-%dT%H:%M:%fZ', 'now')
+    ),
+    event TEXT NOT NULL,
+    actor_id INTEGER,
+    request_id INTEGER,
+    resource INTEGER NOT NULL,
+    action INTEGER NOT NULL,
+    branch_id INTEGER
+);
+
+CREATE INDEX idx_audit_log_actor_id ON audit_log (actor_id);
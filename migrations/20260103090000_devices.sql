@@ -0,0 +1,16 @@
+-- Add migration script here
+-- Unattended POS kiosks. A device logs in with its key and acts as user_id,
+-- getting short-lived access tokens only (no refresh token)
+CREATE TABLE devices (
+    id INTEGER PRIMARY KEY,
+    created_at TEXT DEFAULT(
+        strftime ('%Y-%m-%dT%H:%M:%fZ', 'now')
+    ),
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    key_hash TEXT NOT NULL UNIQUE,
+    revoked_at TEXT,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_devices_user_id ON devices (user_id);
@@ -0,0 +1,11 @@
+-- Add migration script here
+-- Refresh tokens rotated from one login share a family. A rotated token is
+-- kept with used_at set, so presenting it again is detected as reuse and the
+-- whole family is revoked
+ALTER TABLE refresh_tokens ADD COLUMN family_id TEXT NOT NULL DEFAULT '';
+ALTER TABLE refresh_tokens ADD COLUMN used_at TEXT;
+
+-- Tokens issued before families existed each start their own
+UPDATE refresh_tokens SET family_id = 'legacy-' || id WHERE family_id = '';
+
+CREATE INDEX idx_refresh_tokens_family_id ON refresh_tokens (family_id);
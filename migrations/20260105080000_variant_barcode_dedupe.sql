@@ -0,0 +1,25 @@
+-- Databases that predate the unique barcode index may have live variants
+-- sharing a barcode, which would stop that index from being created. Keep the
+-- barcode on the oldest live variant and suffix the others with their id so
+-- they can be told apart and fixed by hand.
+UPDATE product_variants
+SET
+    barcode = barcode || '#' || id,
+    updated_at = strftime ('%Y-%m-%dT%H:%M:%fZ', 'now')
+WHERE
+    is_deleted = 0
+    AND barcode IS NOT NULL
+    AND EXISTS (
+        SELECT 1
+        FROM product_variants older
+        WHERE
+            older.barcode = product_variants.barcode
+            AND older.is_deleted = 0
+            AND (
+                older.created_at < product_variants.created_at
+                OR (
+                    older.created_at = product_variants.created_at
+                    AND older.id < product_variants.id
+                )
+            )
+    );
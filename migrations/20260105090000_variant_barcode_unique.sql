@@ -0,0 +1,8 @@
+-- A barcode identifies one live variant. Deleted variants keep their barcode
+-- but are left out, so it can be given to a new variant. Live variants that
+-- share a barcode are renamed first by 20260105080000_variant_barcode_dedupe.sql.
+DROP INDEX idx_product_variants_barcode;
+CREATE UNIQUE INDEX idx_product_variants_barcode ON product_variants (barcode)
+WHERE
+    barcode IS NOT NULL
+    AND is_deleted = 0;
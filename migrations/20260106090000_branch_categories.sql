@@ -0,0 +1,13 @@
+-- Add migration script here
+-- Categories a branch sees. Categories stay global; this only narrows reads
+-- made for a branch.
+CREATE TABLE branch_categories (
+    branch_id INTEGER NOT NULL,
+    category_id INTEGER NOT NULL,
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    PRIMARY KEY (branch_id, category_id),
+    FOREIGN KEY (branch_id) REFERENCES branches(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_branch_categories_category_id ON branch_categories (category_id);
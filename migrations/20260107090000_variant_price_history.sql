@@ -0,0 +1,15 @@
+-- Add migration script here
+-- One row per change of a variant's base price, in minor units (cents).
+-- A NULL price records that the price was cleared.
+CREATE TABLE variant_price_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    variant_id INTEGER NOT NULL,
+    price INTEGER,
+    effective_from TEXT NOT NULL DEFAULT(
+        strftime ('%Y-%m-%dT%H:%M:%fZ', 'now')
+    ),
+    changed_by INTEGER,
+    FOREIGN KEY (variant_id) REFERENCES product_variants (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_variant_price_history_variant_id ON variant_price_history (variant_id, id);
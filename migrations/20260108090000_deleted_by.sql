@@ -0,0 +1,10 @@
+-- Add migration script here
+-- User id that soft-deleted the row; NULL for rows deleted before this
+-- existed or by internal jobs
+ALTER TABLE products ADD COLUMN deleted_by INTEGER;
+ALTER TABLE product_variants ADD COLUMN deleted_by INTEGER;
+ALTER TABLE customers ADD COLUMN deleted_by INTEGER;
+ALTER TABLE suppliers ADD COLUMN deleted_by INTEGER;
+ALTER TABLE units ADD COLUMN deleted_by INTEGER;
+ALTER TABLE sell_prices ADD COLUMN deleted_by INTEGER;
+ALTER TABLE sell_discounts ADD COLUMN deleted_by INTEGER;
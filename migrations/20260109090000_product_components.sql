@@ -0,0 +1,13 @@
+-- Add migration script here
+-- Variants a bundle variant is made of. Bundles are expanded one level deep.
+CREATE TABLE product_components (
+    bundle_id INTEGER NOT NULL,
+    component_variant_id INTEGER NOT NULL,
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    PRIMARY KEY (bundle_id, component_variant_id),
+    CHECK (bundle_id <> component_variant_id),
+    FOREIGN KEY (bundle_id) REFERENCES product_variants (id),
+    FOREIGN KEY (component_variant_id) REFERENCES product_variants (id)
+);
+
+CREATE INDEX idx_product_components_component_variant_id ON product_components (component_variant_id);
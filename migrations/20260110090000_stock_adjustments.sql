@@ -0,0 +1,19 @@
+-- Add migration script here
+-- Manual changes to on-hand stock (recounts, damage, shrinkage). Sales and
+-- received purchase orders are recorded in their own tables.
+CREATE TABLE stock_adjustments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    branch_id INTEGER NOT NULL,
+    variant_id INTEGER NOT NULL,
+    delta INTEGER NOT NULL CHECK (delta <> 0),
+    quantity_after INTEGER NOT NULL,
+    reason TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT(
+        strftime ('%Y-%m-%dT%H:%M:%fZ', 'now')
+    ),
+    created_by INTEGER,
+    FOREIGN KEY (branch_id) REFERENCES branches (id) ON DELETE CASCADE,
+    FOREIGN KEY (variant_id) REFERENCES product_variants (id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_stock_adjustments_branch_variant ON stock_adjustments (branch_id, variant_id, id);
@@ -0,0 +1,27 @@
+-- Scope idempotency keys to the branch and user that sent them, so a key
+-- reused elsewhere never resolves to another caller's sale. user_id is 0 for
+-- callers without a user, since NULLs would never conflict in the primary key.
+CREATE TABLE idempotency_keys_scoped (
+    branch_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL DEFAULT 0,
+    key TEXT NOT NULL,
+    sale_id INTEGER NOT NULL,
+    created_at TEXT DEFAULT(
+        strftime ('%Y-%m-%dT%H:%M:%fZ', 'now')
+    ),
+    expires_at TEXT NOT NULL,
+    PRIMARY KEY (branch_id, user_id, key),
+    FOREIGN KEY (sale_id) REFERENCES sales (id) ON DELETE CASCADE
+);
+
+-- Existing keys keep working for the branch of their sale
+INSERT INTO idempotency_keys_scoped (branch_id, user_id, key, sale_id, created_at, expires_at)
+SELECT s.branch_id, 0, k.key, k.sale_id, k.created_at, k.expires_at
+FROM idempotency_keys k
+JOIN sales s ON s.id = k.sale_id;
+
+DROP TABLE idempotency_keys;
+
+ALTER TABLE idempotency_keys_scoped RENAME TO idempotency_keys;
+
+CREATE INDEX idx_idempotency_keys_expires_at ON idempotency_keys (expires_at);
@@ -0,0 +1,30 @@
+-- product_type was unconstrained text, so a bad value made every read of that
+-- product fail. Normalize legacy values, then reject anything outside
+-- 'product', 'service' and 'bundle'. Triggers stand in for a CHECK
+-- constraint, which SQLite can only add by rebuilding the table.
+UPDATE products
+SET
+    product_type = lower(trim(product_type))
+WHERE
+    product_type <> lower(trim(product_type));
+
+-- Anything still unknown is treated as a plain product
+UPDATE products
+SET
+    product_type = 'product'
+WHERE
+    product_type NOT IN ('product', 'service', 'bundle');
+
+CREATE TRIGGER products_product_type_check_insert
+BEFORE INSERT ON products
+WHEN NEW.product_type NOT IN ('product', 'service', 'bundle')
+BEGIN
+    SELECT RAISE(ABORT, 'CHECK constraint failed: product_type');
+END;
+
+CREATE TRIGGER products_product_type_check_update
+BEFORE UPDATE OF product_type ON products
+WHEN NEW.product_type NOT IN ('product', 'service', 'bundle')
+BEGIN
+    SELECT RAISE(ABORT, 'CHECK constraint failed: product_type');
+END;
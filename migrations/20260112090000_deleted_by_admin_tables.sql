@@ -0,0 +1,5 @@
+-- Add migration script here
+-- deleted_by for the remaining soft-deleted tables, see deleted_by.sql
+ALTER TABLE categories ADD COLUMN deleted_by INTEGER;
+ALTER TABLE branches ADD COLUMN deleted_by INTEGER;
+ALTER TABLE users ADD COLUMN deleted_by INTEGER;
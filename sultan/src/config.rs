@@ -1,49 +1,275 @@
-use std::env;
+use std::{
+    env,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+};
+use sultan_core::domain::model::{
+    pagination::DEFAULT_MAX_PAGE_SIZE,
+    phone::{DEFAULT_PHONE_REGION, PhoneRegion, parse_phone_region},
+};
+use thiserror::Error;
 use time::Duration;
 
+/// Largest node id the snowflake generator accepts
+const MAX_NODE_ID: u64 = 255;
+
+/// A configuration variable that is missing or malformed
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("{var} must be set")]
+    Missing { var: &'static str },
+
+    #[error("{var} must be {expected}, got '{value}'")]
+    Invalid {
+        var: &'static str,
+        value: String,
+        expected: String,
+    },
+
+    #[error("{var} is too large to be a duration: {source}")]
+    DurationOutOfRange {
+        var: &'static str,
+        source: time::error::ConversionRange,
+    },
+}
+
+impl ConfigError {
+    /// Name of the offending environment variable
+    pub fn var(&self) -> &'static str {
+        match self {
+            ConfigError::Missing { var }
+            | ConfigError::Invalid { var, .. }
+            | ConfigError::DurationOutOfRange { var, .. } => var,
+        }
+    }
+}
+
+fn required(var: &'static str) -> Result<String, ConfigError> {
+    env::var(var).map_err(|_| ConfigError::Missing { var })
+}
+
+/// Parse `var` if it is set
+fn parse_optional<T: FromStr>(var: &'static str, expected: &str) -> Result<Option<T>, ConfigError> {
+    match env::var(var) {
+        Ok(value) => value.parse().map(Some).map_err(|_| ConfigError::Invalid {
+            var,
+            value,
+            expected: expected.to_string(),
+        }),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Parse `var`, falling back to `default` when it is unset
+fn parse<T: FromStr>(var: &'static str, default: T, expected: &str) -> Result<T, ConfigError> {
+    Ok(parse_optional(var, expected)?.unwrap_or(default))
+}
+
+/// Boolean switch accepting 1/true/yes in any case
+fn flag(var: &str, default: bool) -> bool {
+    match env::var(var) {
+        Ok(value) => matches!(value.to_lowercase().as_str(), "1" | "true" | "yes"),
+        Err(_) => default,
+    }
+}
+
+/// A positive whole number of `unit_secs`-second units
+fn ttl(var: &'static str, default: u64, unit_secs: u64) -> Result<Duration, ConfigError> {
+    let units: u64 = parse(var, default, "a positive whole number")?;
+    if units == 0 {
+        return Err(ConfigError::Invalid {
+            var,
+            value: units.to_string(),
+            expected: "a positive whole number".to_string(),
+        });
+    }
+    // Saturating keeps overflow above what `time::Duration` can hold, so it is
+    // reported by the conversion below
+    let secs = units.saturating_mul(unit_secs);
+    Duration::try_from(std::time::Duration::from_secs(secs))
+        .map_err(|source| ConfigError::DurationOutOfRange { var, source })
+}
+
 #[derive(Clone)]
 pub struct AppConfig {
     pub jwt_secret: String,
     pub access_token_ttl: Duration,
     pub refresh_token_ttl: Duration,
+    /// Lifetime of the access-only tokens issued to kiosk devices
+    pub device_token_ttl: Duration,
+    /// How long an `Idempotency-Key` keeps returning the sale it created
+    pub idempotency_key_ttl: Duration,
     pub database_url: String,
     pub database_max_connections: u32,
+    /// How long a request waits for a free pool connection before failing
+    pub database_acquire_timeout_secs: u64,
+    /// How long an unused pool connection is kept open
+    pub database_idle_timeout_secs: u64,
+    /// Use `journal_mode=WAL` with `synchronous=NORMAL` instead of the rollback journal
+    pub database_wal: bool,
+    /// How long a connection waits on a locked database before returning `SQLITE_BUSY`
+    pub database_busy_timeout_secs: u64,
+    /// How many times a transaction start or commit that still hits
+    /// `SQLITE_BUSY` is retried, with jittered backoff
+    pub database_busy_retries: u32,
+    /// Enforce `FOREIGN KEY` constraints (`PRAGMA foreign_keys=ON`)
+    pub database_foreign_keys: bool,
     pub write_log_to_file: bool,
+    /// IP address the HTTP listener binds to, e.g. `0.0.0.0` or `::1`
+    pub bind_address: String,
+    pub port: u16,
+    /// Origins allowed to call the API from a browser. Empty denies all cross-origin requests.
+    pub cors_allowed_origins: Vec<String>,
+    /// Reflect any origin back (development only; overrides `cors_allowed_origins`)
+    pub cors_dev_mode: bool,
+    /// Largest request body accepted, except for routes with their own limit (CSV import)
+    pub max_body_bytes: usize,
+    /// Largest `page_size` list endpoints accept; larger requests are clamped
+    pub max_page_size: u32,
+    /// Requests still running after this many seconds are answered with 504.
+    /// Export and import are exempt.
+    pub request_timeout_secs: u64,
+    /// How often expired refresh tokens and idempotency keys are purged;
+    /// 0 turns the cleanup off
+    pub cleanup_interval_secs: u64,
+    /// Snowflake node id (0-255), unique per running instance
+    pub node_id: u64,
+    /// Region assumed for customer/supplier phone numbers without a country code
+    pub default_phone_region: PhoneRegion,
+    /// Path to a JSON Schema that product `metadata` must conform to
+    pub product_metadata_schema: Option<String>,
+    /// Path to a JSON Schema that variant `metadata` must conform to
+    pub variant_metadata_schema: Option<String>,
+    /// Path to a JSON Schema that customer `metadata` must conform to
+    pub customer_metadata_schema: Option<String>,
+    /// Queries running at least this many milliseconds are logged at warn;
+    /// `None` disables slow-query logging
+    pub slow_query_ms: Option<u64>,
 }
 
 impl AppConfig {
-    pub fn from_env() -> Self {
-        let jwt_secret = env::var("JWT_SECRET").expect("JWT_SECRET must be set");
-        let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    /// Read the configuration from the environment, reporting the first
+    /// variable that is missing or malformed.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let jwt_secret = required("JWT_SECRET")?;
+        let database_url = required("DATABASE_URL")?;
 
-        let refresh_token_ttl_days: i64 = env::var("REFRESH_TOKEN_TTL_DAYS")
-            .unwrap_or_else(|_| "30".to_string())
-            .parse()
-            .expect("REFRESH_TOKEN_TTL_DAYS must be a valid number");
+        let refresh_token_ttl = ttl("REFRESH_TOKEN_TTL_DAYS", 30, 24 * 60 * 60)?;
+        let access_token_ttl = ttl("ACCESS_TOKEN_TTL_SECS", 900, 1)?;
+        let device_token_ttl = ttl("DEVICE_TOKEN_TTL_SECS", 300, 1)?;
+        let idempotency_key_ttl = ttl("IDEMPOTENCY_KEY_TTL_HOURS", 24, 60 * 60)?;
 
-        let access_token_ttl_secs: i64 = env::var("ACCESS_TOKEN_TTL_SECS")
-            .unwrap_or_else(|_| "900".to_string())
-            .parse()
-            .expect("ACCESS_TOKEN_TTL_SECS must be a valid number");
+        let write_log_to_file = flag("WRITE_LOG_TO_FILE", false);
 
-        let write_log_to_file = env::var("WRITE_LOG_TO_FILE")
-            .unwrap_or_else(|_| "0".to_string())
-            .to_lowercase();
-        let write_log_to_file = matches!(write_log_to_file.as_str(), "1" | "true" | "yes");
+        let database_max_connections: u32 = parse("DATABASE_MAX_CONNECTIONS", 5, "a valid number")?;
+        let database_acquire_timeout_secs: u64 =
+            parse("DATABASE_ACQUIRE_TIMEOUT_SECS", 5, "a valid number")?;
+        let database_idle_timeout_secs: u64 =
+            parse("DATABASE_IDLE_TIMEOUT_SECS", 600, "a valid number")?;
+        let database_wal = flag("DATABASE_WAL", true);
+        let database_busy_timeout_secs: u64 =
+            parse("DATABASE_BUSY_TIMEOUT_SECS", 5, "a valid number")?;
+        let database_busy_retries: u32 = parse("DATABASE_BUSY_RETRIES", 3, "a valid number")?;
+        let database_foreign_keys = flag("DATABASE_FOREIGN_KEYS", true);
 
-        let database_max_connections: u32 = env::var("DATABASE_MAX_CONNECTIONS")
-            .unwrap_or_else(|_| "5".to_string())
-            .parse()
-            .expect("DATABASE_MAX_CONNECTIONS must be a valid number");
+        let bind_address = env::var("BIND_ADDRESS").unwrap_or_else(|_| "0.0.0.0".to_string());
+        if bind_address.parse::<IpAddr>().is_err() {
+            return Err(ConfigError::Invalid {
+                var: "BIND_ADDRESS",
+                value: bind_address,
+                expected: "a valid IP address".to_string(),
+            });
+        }
 
-        Self {
+        let port: u16 = parse("PORT", 8721, "a valid port number (0-65535)")?;
+
+        let cors_allowed_origins = env::var("CORS_ALLOWED_ORIGINS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|origin| origin.trim().to_string())
+            .filter(|origin| !origin.is_empty())
+            .collect();
+        let cors_dev_mode = flag("CORS_DEV_MODE", false);
+
+        let max_body_bytes: usize = parse("MAX_BODY_BYTES", 2 * 1024 * 1024, "a valid number")?;
+        let max_page_size: u32 = parse("MAX_PAGE_SIZE", DEFAULT_MAX_PAGE_SIZE, "a valid number")?;
+        if max_page_size == 0 {
+            return Err(ConfigError::Invalid {
+                var: "MAX_PAGE_SIZE",
+                value: max_page_size.to_string(),
+                expected: "at least 1".to_string(),
+            });
+        }
+        let request_timeout_secs: u64 = parse("REQUEST_TIMEOUT_SECS", 30, "a valid number")?;
+        if request_timeout_secs == 0 {
+            return Err(ConfigError::Invalid {
+                var: "REQUEST_TIMEOUT_SECS",
+                value: request_timeout_secs.to_string(),
+                expected: "at least 1".to_string(),
+            });
+        }
+        let cleanup_interval_secs: u64 =
+            parse("CLEANUP_INTERVAL_SECS", 3600, "a valid number of seconds")?;
+        let node_id: u64 = parse("NODE_ID", 1, "a valid number")?;
+        if node_id > MAX_NODE_ID {
+            return Err(ConfigError::Invalid {
+                var: "NODE_ID",
+                value: node_id.to_string(),
+                expected: format!("between 0 and {}", MAX_NODE_ID),
+            });
+        }
+        let default_phone_region = match env::var("DEFAULT_PHONE_REGION") {
+            Ok(code) => parse_phone_region(&code).map_err(|_| ConfigError::Invalid {
+                var: "DEFAULT_PHONE_REGION",
+                value: code,
+                expected: "a region code like ID or US".to_string(),
+            })?,
+            Err(_) => DEFAULT_PHONE_REGION,
+        };
+        let product_metadata_schema = env::var("PRODUCT_METADATA_SCHEMA").ok();
+        let variant_metadata_schema = env::var("VARIANT_METADATA_SCHEMA").ok();
+        let customer_metadata_schema = env::var("CUSTOMER_METADATA_SCHEMA").ok();
+        let slow_query_ms = parse_optional("SLOW_QUERY_MS", "a valid number of milliseconds")?;
+
+        Ok(Self {
             jwt_secret,
-            access_token_ttl: Duration::seconds(access_token_ttl_secs),
-            refresh_token_ttl: Duration::days(refresh_token_ttl_days),
+            access_token_ttl,
+            refresh_token_ttl,
+            device_token_ttl,
+            idempotency_key_ttl,
             database_url,
             database_max_connections,
+            database_acquire_timeout_secs,
+            database_idle_timeout_secs,
+            database_wal,
+            database_busy_timeout_secs,
+            database_busy_retries,
+            database_foreign_keys,
             write_log_to_file,
-        }
+            bind_address,
+            port,
+            cors_allowed_origins,
+            cors_dev_mode,
+            max_body_bytes,
+            max_page_size,
+            request_timeout_secs,
+            cleanup_interval_secs,
+            node_id,
+            default_phone_region,
+            product_metadata_schema,
+            variant_metadata_schema,
+            customer_metadata_schema,
+            slow_query_ms,
+        })
+    }
+
+    /// Socket address for the HTTP listener
+    pub fn socket_addr(&self) -> anyhow::Result<SocketAddr> {
+        let ip: IpAddr = self
+            .bind_address
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid bind address '{}': {}", self.bind_address, e))?;
+        Ok(SocketAddr::new(ip, self.port))
     }
 }
 
@@ -51,16 +277,42 @@ impl AppConfig {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_app_config_clone() {
-        let config = AppConfig {
+    fn test_config() -> AppConfig {
+        AppConfig {
             jwt_secret: "secret123".to_string(),
             access_token_ttl: Duration::seconds(900),
             refresh_token_ttl: Duration::days(30),
+            device_token_ttl: Duration::seconds(300),
+            idempotency_key_ttl: Duration::hours(24),
             database_url: "sqlite:test.db".to_string(),
             database_max_connections: 5,
+            database_acquire_timeout_secs: 5,
+            database_idle_timeout_secs: 600,
+            database_wal: true,
+            database_busy_timeout_secs: 5,
+            database_busy_retries: 3,
+            database_foreign_keys: true,
             write_log_to_file: false,
-        };
+            bind_address: "127.0.0.1".to_string(),
+            port: 8080,
+            cors_allowed_origins: vec![],
+            cors_dev_mode: false,
+            max_body_bytes: 2 * 1024 * 1024,
+            max_page_size: DEFAULT_MAX_PAGE_SIZE,
+            request_timeout_secs: 30,
+            cleanup_interval_secs: 3600,
+            node_id: 1,
+            default_phone_region: DEFAULT_PHONE_REGION,
+            product_metadata_schema: None,
+            variant_metadata_schema: None,
+            customer_metadata_schema: None,
+            slow_query_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_app_config_clone() {
+        let config = test_config();
 
         let cloned = config.clone();
         assert_eq!(config.jwt_secret, cloned.jwt_secret);
@@ -68,6 +320,42 @@ mod tests {
         assert_eq!(config.write_log_to_file, cloned.write_log_to_file);
         assert_eq!(config.access_token_ttl, cloned.access_token_ttl);
         assert_eq!(config.refresh_token_ttl, cloned.refresh_token_ttl);
+        assert_eq!(
+            cloned.socket_addr().unwrap(),
+            "127.0.0.1:8080".parse::<SocketAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_socket_addr_accepts_ipv6() {
+        let config = AppConfig {
+            bind_address: "::1".to_string(),
+            port: 9000,
+            ..test_config()
+        };
+        assert_eq!(
+            config.socket_addr().unwrap(),
+            "[::1]:9000".parse::<SocketAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_socket_addr_rejects_invalid_address() {
+        for address in ["not-an-ip", "localhost", "127.0.0.1:80", "256.0.0.1"] {
+            let config = AppConfig {
+                bind_address: address.to_string(),
+                ..test_config()
+            };
+            let err = config
+                .socket_addr()
+                .expect_err("Address should be rejected")
+                .to_string();
+            assert!(
+                err.contains(&format!("Invalid bind address '{}'", address)),
+                "{}",
+                err
+            );
+        }
     }
 
     #[test]
@@ -1,2 +1,4 @@
 pub mod config;
 pub mod server;
+pub mod tasks;
+pub mod version;
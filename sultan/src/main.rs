@@ -1,5 +1,5 @@
 use dotenvy::dotenv;
-use sultan::server::create_app;
+use sultan::server::{create_app, shutdown_signal};
 use tracing::info;
 
 #[tokio::main]
@@ -8,11 +8,20 @@ async fn main() -> anyhow::Result<()> {
 
     let app = create_app().await?;
 
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:8721").await?;
+    let listener = tokio::net::TcpListener::bind(app.config.socket_addr()?).await?;
+    let tasks = app.start_tasks();
 
     info!("Server listening on {}", listener.local_addr()?);
 
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app.router.clone())
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    if let Some(tasks) = tasks {
+        tasks.shutdown().await;
+    }
+    app.close().await;
+    info!("Server stopped");
 
     Ok(())
 }
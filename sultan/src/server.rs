@@ -1,28 +1,48 @@
 use axum::{
-    Json, Router,
+    BoxError, Json, Router,
+    error_handling::HandleErrorLayer,
+    extract::DefaultBodyLimit,
     http::{self, StatusCode},
-    middleware::from_fn,
+    middleware::{from_fn, from_fn_with_state},
     response::IntoResponse,
+    routing::get,
 };
-use http::header::{AUTHORIZATION, CONTENT_TYPE};
-use sqlx::{Sqlite, SqlitePool, migrate::MigrateDatabase, sqlite::SqlitePoolOptions};
-use std::{fs::File, sync::Arc};
+use http::header::{AUTHORIZATION, CONTENT_TYPE, ETAG, IF_NONE_MATCH};
+use sqlx::{
+    ConnectOptions, Sqlite, SqlitePool,
+    migrate::MigrateDatabase,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
+};
+use std::{fs::File, future::Future, str::FromStr, sync::Arc, time::Duration};
 use sultan_core::{
     application::{
-        AuthService, AuthServiceTrait, CategoryService, CustomerService, InMemoryCache,
-        SupplierService, UserService,
+        AuthService, AuthServiceTrait, CategoryService, CustomerService, ExportService,
+        InMemoryCache, ProductService, SaleService, SupplierService, UserService,
     },
     crypto::{Argon2PasswordHasher, DefaultJwtManager, JwtConfig, JwtManager},
-    snowflake::SnowflakeGenerator,
+    domain::{Context, model::metadata::MetadataSchema},
+    snowflake::{IdGenerator, SnowflakeGenerator},
     storage::{
-        SqliteUserRepository,
+        SaleRepository, SqliteUserRepository, TokenRepository,
         sqlite::{
-            SqliteCategoryRepository, SqliteCustomerRepository, SqliteSupplierRepository,
-            SqliteTokenRepository,
+            SqliteAuditSink, SqliteBranchRepository, SqliteCategoryRepository,
+            SqliteCustomerRepository, SqliteDeviceRepository, SqliteExportRepository,
+            SqliteProductRepository, SqliteSaleRepository, SqliteStockRepository,
+            SqliteSupplierRepository, SqliteTokenRepository,
+            transaction::{BusyRetry, SqliteTransactionManager},
         },
     },
 };
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
+use tower::{ServiceBuilder, timeout::TimeoutLayer};
+use tower_http::{
+    compression::{
+        CompressionLayer,
+        predicate::{NotForContentType, Predicate, SizeAbove},
+    },
+    cors::{AllowOrigin, CorsLayer},
+    limit::RequestBodyLimitLayer,
+    trace::TraceLayer,
+};
 use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
 use utoipa::OpenApi;
 use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
@@ -30,18 +50,63 @@ use utoipa_swagger_ui::SwaggerUi;
 use uuid::Uuid;
 
 use crate::config::AppConfig;
+use crate::tasks::{TaskHandle, TaskRunner};
+use crate::version::version_handler;
 use sultan_web::{AppState, supplier_routes::supplier_router};
 use sultan_web::{
     handler::{
         auth_router::{AuthApiDoc, auth_router},
         category_router::{CategoryApiDoc, category_router},
-        customer_router::{CustomerApiDoc, customer_router},
-        middleware::{context_middleware, verify_jwt},
+        customer_router::{CustomerApiDoc, IMPORT_BODY_LIMIT, customer_router},
+        export_router::{
+            ExportApiDoc, IMPORT_BODY_LIMIT as FULL_IMPORT_BODY_LIMIT, export_router, import_router,
+        },
+        metrics::{metrics_handler, prometheus_handle, track_metrics},
+        middleware::{context_middleware, payload_too_large_json, verify_jwt},
+        ndjson::NDJSON_CONTENT_TYPE,
+        product_router::{ProductApiDoc, product_router},
+        sale_router::{IDEMPOTENCY_KEY_HEADER, SaleApiDoc, sale_router},
+        stats_router::{StatsApiDoc, stats_router},
+        stock_router::{StockApiDoc, stock_router},
+        token_router::{TokenApiDoc, token_router},
+        total_count::TOTAL_COUNT_HEADER,
     },
     supplier_routes::SupplierApiDoc,
 };
 
-async fn init_sqlite_db(config: &AppConfig) -> anyhow::Result<SqlitePool> {
+/// Pool options derived from the application config
+pub fn sqlite_pool_options(config: &AppConfig) -> SqlitePoolOptions {
+    SqlitePoolOptions::new()
+        .max_connections(config.database_max_connections)
+        .acquire_timeout(Duration::from_secs(config.database_acquire_timeout_secs))
+        .idle_timeout(Duration::from_secs(config.database_idle_timeout_secs))
+}
+
+/// Per-connection pragmas derived from the application config
+pub fn sqlite_connect_options(config: &AppConfig) -> anyhow::Result<SqliteConnectOptions> {
+    let mut options = SqliteConnectOptions::from_str(&config.database_url)?
+        .busy_timeout(Duration::from_secs(config.database_busy_timeout_secs))
+        .foreign_keys(config.database_foreign_keys);
+
+    if config.database_wal {
+        options = options
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal);
+    }
+
+    // sqlx drops slow statements entirely when their level is Off, so
+    // "disabled" means a threshold no query reaches; statements then keep
+    // logging at debug like any other.
+    let slow_query_threshold = config
+        .slow_query_ms
+        .map(Duration::from_millis)
+        .unwrap_or(Duration::MAX);
+    options = options.log_slow_statements(log::LevelFilter::Warn, slow_query_threshold);
+
+    Ok(options)
+}
+
+pub async fn init_sqlite_db(config: &AppConfig) -> anyhow::Result<SqlitePool> {
     let database_url = &config.database_url;
 
     // Create database if it doesn't exist
@@ -51,9 +116,8 @@ async fn init_sqlite_db(config: &AppConfig) -> anyhow::Result<SqlitePool> {
     }
 
     tracing::info!("Connecting to SQLite database");
-    let pool = SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect(database_url)
+    let pool = sqlite_pool_options(config)
+        .connect_with(sqlite_connect_options(config)?)
         .await?;
 
     tracing::info!("Running SQLite migrations");
@@ -63,35 +127,100 @@ async fn init_sqlite_db(config: &AppConfig) -> anyhow::Result<SqlitePool> {
     Ok(pool)
 }
 
-async fn init_app_state(config: &AppConfig) -> anyhow::Result<AppState> {
-    let pool = init_sqlite_db(config).await?;
+/// Read and compile the JSON Schema at `path`, if one is configured
+pub fn load_metadata_schema(path: Option<&str>) -> anyhow::Result<Option<MetadataSchema>> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read metadata schema '{}': {}", path, e))?;
+    let schema: serde_json::Value = serde_json::from_str(&raw)
+        .map_err(|e| anyhow::anyhow!("Metadata schema '{}' is not valid JSON: {}", path, e))?;
+    let schema = MetadataSchema::new(&schema).map_err(|e| anyhow::anyhow!("{}: {}", path, e))?;
+    Ok(Some(schema))
+}
+
+pub async fn init_app_state(config: &AppConfig, pool: SqlitePool) -> anyhow::Result<AppState> {
+    let id_generator: Arc<dyn IdGenerator> = Arc::new(SnowflakeGenerator::new(config.node_id)?);
 
     let user_repository = SqliteUserRepository::new(pool.clone());
     let token_repository = SqliteTokenRepository::new(pool.clone());
+    let branch_repository = SqliteBranchRepository::new(pool.clone());
     let category_repository = SqliteCategoryRepository::new(pool.clone());
     let supplier_repository = SqliteSupplierRepository::new(pool.clone());
-    let customer_repository = SqliteCustomerRepository::new(pool);
+    let customer_repository = SqliteCustomerRepository::new(pool.clone());
+    let product_repository = SqliteProductRepository::new(pool.clone());
+    let sale_repository = SqliteSaleRepository::new(pool.clone());
+    let audit_sink = SqliteAuditSink::new(pool.clone());
+    let busy_retry = BusyRetry::new(config.database_busy_retries, BusyRetry::DEFAULT_BASE_DELAY);
+    let export_repository = SqliteExportRepository::new(pool.clone()).with_busy_retry(busy_retry);
 
     let password_hasher = Argon2PasswordHasher::default();
-    let jwt_manager = DefaultJwtManager::new(JwtConfig::new(
-        config.jwt_secret.clone(),
-        config.access_token_ttl.whole_minutes(),
-    ));
+    let jwt_manager = DefaultJwtManager::new(
+        JwtConfig::new(
+            config.jwt_secret.clone(),
+            config.access_token_ttl.whole_minutes(),
+        )
+        .with_device_expiration_minutes(config.device_token_ttl.whole_minutes()),
+    );
     let permission_cache = InMemoryCache::<i64>::new();
     let auth_service = AuthService::new(
         user_repository.clone(),
         token_repository,
+        branch_repository,
         password_hasher,
         jwt_manager.clone(),
-    );
+    )
+    .with_device_repository(Arc::new(SqliteDeviceRepository::new(pool.clone())));
 
-    let category_service = CategoryService::new(category_repository, SnowflakeGenerator::new(1)?);
-    let customer_service = CustomerService::new(customer_repository, SnowflakeGenerator::new(1)?);
-    let supplier_service = SupplierService::new(supplier_repository, SnowflakeGenerator::new(1)?);
+    let product_metadata_schema = load_metadata_schema(config.product_metadata_schema.as_deref())?;
+    let variant_metadata_schema = load_metadata_schema(config.variant_metadata_schema.as_deref())?;
+    let customer_metadata_schema =
+        load_metadata_schema(config.customer_metadata_schema.as_deref())?;
+
+    let category_service = CategoryService::new(category_repository, id_generator.clone());
+    let mut customer_service = CustomerService::new(customer_repository, id_generator.clone())
+        .with_default_phone_region(config.default_phone_region);
+    if let Some(schema) = customer_metadata_schema.clone() {
+        customer_service = customer_service.with_metadata_schema(schema);
+    }
+    let supplier_service = SupplierService::new(supplier_repository, id_generator.clone())
+        .with_default_phone_region(config.default_phone_region);
+    let mut product_service = ProductService::new(
+        product_repository,
+        SqliteStockRepository::new(pool.clone()),
+        SqliteTransactionManager::new(pool.clone()).with_busy_retry(busy_retry),
+        id_generator.clone(),
+    );
+    if let Some(schema) = product_metadata_schema.clone() {
+        product_service = product_service.with_product_metadata_schema(schema);
+    }
+    if let Some(schema) = variant_metadata_schema.clone() {
+        product_service = product_service.with_variant_metadata_schema(schema);
+    }
+    let sale_service = SaleService::new(
+        sale_repository,
+        SqliteTransactionManager::new(pool).with_busy_retry(busy_retry),
+        id_generator.clone(),
+    )
+    .with_idempotency_ttl(chrono::Duration::seconds(
+        config.idempotency_key_ttl.whole_seconds(),
+    ));
+    let mut export_service = ExportService::new(export_repository)
+        .with_default_phone_region(config.default_phone_region);
+    if let Some(schema) = product_metadata_schema {
+        export_service = export_service.with_product_metadata_schema(schema);
+    }
+    if let Some(schema) = variant_metadata_schema {
+        export_service = export_service.with_variant_metadata_schema(schema);
+    }
+    if let Some(schema) = customer_metadata_schema {
+        export_service = export_service.with_customer_metadata_schema(schema);
+    }
     let user_service = UserService::new(
         user_repository,
         Arc::new(Argon2PasswordHasher::default()),
-        SnowflakeGenerator::new(1)?,
+        id_generator.clone(),
         Arc::new(permission_cache),
     );
 
@@ -100,8 +229,14 @@ async fn init_app_state(config: &AppConfig) -> anyhow::Result<AppState> {
         jwt_manager: Arc::new(jwt_manager) as Arc<dyn JwtManager>,
         category_service: Arc::new(category_service),
         customer_service: Arc::new(customer_service),
+        export_service: Arc::new(export_service),
+        product_service: Arc::new(product_service),
+        sale_service: Arc::new(sale_service),
         supplier_service: Arc::new(supplier_service),
         user_service: Arc::new(user_service),
+        id_generator,
+        audit_sink: Some(Arc::new(audit_sink)),
+        max_page_size: config.max_page_size,
         extensions: Arc::new(std::collections::HashMap::new()),
     })
 }
@@ -109,11 +244,12 @@ async fn init_app_state(config: &AppConfig) -> anyhow::Result<AppState> {
 async fn handle_404() -> impl IntoResponse {
     (
         StatusCode::NOT_FOUND,
-        Json(serde_json::json!({ "error": "no route found" })),
+        Json(serde_json::json!({ "error": "no route found", "code": "not_found" })),
     )
 }
 
-fn init_tracing(write_log_to_file: bool) {
+/// Returns the JSON log file, if any, so it can be synced on shutdown
+fn init_tracing(write_log_to_file: bool) -> Option<Arc<File>> {
     let filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| "clean_architecture=debug,tower_http=debug".into());
 
@@ -129,49 +265,262 @@ fn init_tracing(write_log_to_file: bool) {
 
     if write_log_to_file {
         // File (structured JSON logs)
-        let file = File::create("app.log").expect("Cannot create log file");
+        let file = Arc::new(File::create("app.log").expect("Cannot create log file"));
         let json_layer = tracing_subscriber::fmt::layer()
             .json()
-            .with_writer(file)
+            .with_writer(file.clone())
             .with_current_span(true)
             .with_span_list(true);
 
         registry.with(json_layer).try_init().ok();
+        Some(file)
     } else {
         registry.try_init().ok();
+        None
     }
 }
 
-pub async fn create_app() -> anyhow::Result<Router> {
-    let config = AppConfig::from_env();
-    init_tracing(config.write_log_to_file);
+/// CORS for browser clients. Preflight `OPTIONS` requests are answered by the
+/// layer itself. Origins not in the allow list get no CORS headers, so the
+/// browser blocks the response.
+pub fn cors_layer(config: &AppConfig) -> anyhow::Result<CorsLayer> {
+    let origin = if config.cors_dev_mode {
+        // A wildcard can't be combined with credentials, so echo the caller's origin
+        AllowOrigin::mirror_request()
+    } else {
+        let origins = config
+            .cors_allowed_origins
+            .iter()
+            .map(|origin| {
+                origin
+                    .parse::<http::HeaderValue>()
+                    .map_err(|e| anyhow::anyhow!("Invalid CORS origin '{}': {}", origin, e))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        AllowOrigin::list(origins)
+    };
 
-    let app_state = init_app_state(&config).await?;
+    Ok(CorsLayer::new()
+        .allow_origin(origin)
+        .allow_methods([
+            http::Method::GET,
+            http::Method::POST,
+            http::Method::PUT,
+            http::Method::DELETE,
+        ])
+        .allow_headers([
+            CONTENT_TYPE,
+            AUTHORIZATION,
+            IF_NONE_MATCH,
+            http::HeaderName::from_static(IDEMPOTENCY_KEY_HEADER),
+        ])
+        .expose_headers([ETAG, http::HeaderName::from_static(TOTAL_COUNT_HEADER)])
+        .allow_credentials(true))
+}
+
+/// Body limit for an import route: its own limit, or the global one when
+/// that is larger, so raising `MAX_BODY_BYTES` never lowers an import
+pub fn import_body_limit(route_limit: usize, config: &AppConfig) -> usize {
+    config.max_body_bytes.max(route_limit)
+}
+
+/// Cap request bodies at `config.max_body_bytes`. Extractors enforce the
+/// per-route limit (raised for imports); the outer layer rejects anything
+/// above the largest route limit from `Content-Length` before it is read.
+/// Rejections are answered with a JSON 413.
+pub fn with_body_limits<S>(router: Router<S>, config: &AppConfig) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let largest = import_body_limit(IMPORT_BODY_LIMIT.max(FULL_IMPORT_BODY_LIMIT), config);
+    router
+        .layer(DefaultBodyLimit::max(config.max_body_bytes))
+        .layer(RequestBodyLimitLayer::new(largest))
+        .layer(from_fn(payload_too_large_json))
+}
+
+/// Answer with 504 when `router`'s handlers run longer than `timeout`.
+/// Routes added to the router afterwards are not covered.
+pub fn with_request_timeout<S>(router: Router<S>, timeout: Duration) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    router.layer(
+        ServiceBuilder::new()
+            .layer(HandleErrorLayer::new(request_timed_out))
+            .layer(TimeoutLayer::new(timeout)),
+    )
+}
 
-    let cors = CorsLayer::new()
-        .allow_origin(
-            "http://localhost:5173"
-                .parse::<http::HeaderValue>()
-                .unwrap(),
+async fn request_timed_out(err: BoxError) -> impl IntoResponse {
+    if err.is::<tower::timeout::error::Elapsed>() {
+        (
+            StatusCode::GATEWAY_TIMEOUT,
+            Json(serde_json::json!({ "error": "Request timed out", "code": "timeout" })),
+        )
+    } else {
+        tracing::error!(error = %err, "Unhandled middleware error");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": "Internal error", "code": "internal" })),
         )
-        .allow_methods([http::Method::POST, http::Method::GET])
-        .allow_headers([CONTENT_TYPE, AUTHORIZATION])
-        .allow_credentials(true);
-
-    let protected_router = Router::new()
-        .nest("/category", category_router())
-        .nest("/customer", customer_router())
-        .nest("/supplier", supplier_router())
-        .route_layer(axum::middleware::from_fn_with_state(
-            app_state.clone(),
-            verify_jwt,
-        ));
+    }
+}
+
+/// Responses smaller than this are sent as is; compressing them costs more
+/// than it saves
+pub const COMPRESSION_MIN_BYTES: u16 = 1024;
+
+/// gzip or brotli, whichever the client prefers in `Accept-Encoding`.
+/// Streamed bodies such as the CSV export have no known size and are always
+/// compressed, chunk by chunk. SSE and NDJSON streams are left alone, since
+/// the encoder would hold back each event or row until its buffer fills.
+pub fn compression_layer() -> CompressionLayer<impl Predicate> {
+    CompressionLayer::new().gzip(true).br(true).compress_when(
+        SizeAbove::new(COMPRESSION_MIN_BYTES)
+            .and(NotForContentType::GRPC)
+            .and(NotForContentType::IMAGES)
+            .and(NotForContentType::SSE)
+            .and(NotForContentType::const_new(NDJSON_CONTENT_TYPE)),
+    )
+}
+
+/// The HTTP router together with the resources released on shutdown
+pub struct App {
+    pub router: Router,
+    pub config: AppConfig,
+    pool: SqlitePool,
+    log_file: Option<Arc<File>>,
+}
+
+impl App {
+    /// Start the periodic cleanup jobs, unless `cleanup_interval_secs` is 0
+    pub fn start_tasks(&self) -> Option<TaskHandle> {
+        cleanup_tasks(&self.config, self.pool.clone()).map(TaskRunner::spawn)
+    }
+
+    /// Close the pool so the WAL is checkpointed and sync the JSON log to disk.
+    /// Call after the server has drained its in-flight requests.
+    pub async fn close(self) {
+        tracing::info!("Closing database pool");
+        self.pool.close().await;
+
+        if let Some(file) = self.log_file
+            && let Err(e) = file.sync_all()
+        {
+            eprintln!("Failed to sync log file: {}", e);
+        }
+    }
+}
+
+/// Jobs that purge expired refresh tokens and idempotency keys
+pub fn cleanup_tasks(config: &AppConfig, pool: SqlitePool) -> Option<TaskRunner> {
+    if config.cleanup_interval_secs == 0 {
+        return None;
+    }
+
+    let token_repository = SqliteTokenRepository::new(pool.clone());
+    let sale_repository = SqliteSaleRepository::new(pool);
+    let runner = TaskRunner::new(Duration::from_secs(config.cleanup_interval_secs))
+        .register("expired_tokens", move || {
+            let repository = token_repository.clone();
+            async move {
+                repository
+                    .delete_expired(&Context::system(), chrono::Utc::now())
+                    .await
+            }
+        })
+        .register("expired_idempotency_keys", move || {
+            let repository = sale_repository.clone();
+            async move {
+                repository
+                    .delete_expired_idempotency_keys(&Context::system(), chrono::Utc::now())
+                    .await
+            }
+        });
+    Some(runner)
+}
+
+/// Resolves with the name of whichever trigger fires first
+pub async fn wait_for_shutdown<C, T>(ctrl_c: C, terminate: T) -> &'static str
+where
+    C: Future<Output = ()>,
+    T: Future<Output = ()>,
+{
+    tokio::select! {
+        _ = ctrl_c => "ctrl-c",
+        _ = terminate => "SIGTERM",
+    }
+}
+
+/// Resolves on ctrl-c or, on unix, SIGTERM
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install ctrl-c handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    let signal = wait_for_shutdown(ctrl_c, terminate).await;
+    tracing::info!("Received {}, shutting down", signal);
+}
+
+pub async fn create_app() -> anyhow::Result<App> {
+    let config = AppConfig::from_env()?;
+    let log_file = init_tracing(config.write_log_to_file);
+    // Install the recorder before anything records a metric
+    prometheus_handle();
+
+    let pool = init_sqlite_db(&config).await?;
+    let app_state = init_app_state(&config, pool.clone()).await?;
+
+    let cors = cors_layer(&config)?;
+
+    let request_timeout = Duration::from_secs(config.request_timeout_secs);
+    let protected_router = with_request_timeout(
+        Router::new()
+            .nest("/category", category_router())
+            .nest(
+                "/customer",
+                customer_router(import_body_limit(IMPORT_BODY_LIMIT, &config)),
+            )
+            .nest("/product", product_router())
+            .nest("/sale", sale_router())
+            .nest("/stats", stats_router())
+            .nest("/stock", stock_router())
+            .nest("/supplier", supplier_router())
+            .nest("/tokens", token_router()),
+        request_timeout,
+    )
+    // Full export and import walk every table, so they run without a timeout
+    .nest("/export", export_router())
+    .nest(
+        "/import",
+        import_router(import_body_limit(FULL_IMPORT_BODY_LIMIT, &config)),
+    )
+    .route_layer(from_fn_with_state(app_state.clone(), verify_jwt));
 
     // Merge OpenAPI specs
     let mut openapi = AuthApiDoc::openapi();
     openapi.merge(CategoryApiDoc::openapi());
     openapi.merge(CustomerApiDoc::openapi());
+    openapi.merge(ExportApiDoc::openapi());
+    openapi.merge(ProductApiDoc::openapi());
+    openapi.merge(SaleApiDoc::openapi());
+    openapi.merge(StatsApiDoc::openapi());
+    openapi.merge(StockApiDoc::openapi());
     openapi.merge(SupplierApiDoc::openapi());
+    openapi.merge(TokenApiDoc::openapi());
 
     // Add Bearer token security scheme
     if let Some(components) = openapi.components.as_mut() {
@@ -187,12 +536,20 @@ pub async fn create_app() -> anyhow::Result<Router> {
     }
 
     let router = Router::new()
-        .nest("/api/auth", auth_router())
+        .nest(
+            "/api/auth",
+            with_request_timeout(auth_router(), request_timeout),
+        )
         .nest("/api/", protected_router)
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", openapi))
+        .route("/metrics", get(metrics_handler))
+        .route("/version", get(version_handler))
+        .route_layer(from_fn(track_metrics))
         .fallback(handle_404)
-        .layer(from_fn(context_middleware))
+        .layer(from_fn_with_state(app_state.clone(), context_middleware));
+    let router = with_body_limits(router, &config)
         .with_state(app_state)
+        .layer(compression_layer())
         .layer(cors)
         .layer(
             TraceLayer::new_for_http().make_span_with(|request: &http::Request<_>| {
@@ -207,5 +564,10 @@ pub async fn create_app() -> anyhow::Result<Router> {
             }),
         );
 
-    Ok(router)
+    Ok(App {
+        router,
+        config,
+        pool,
+        log_file,
+    })
 }
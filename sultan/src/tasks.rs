@@ -0,0 +1,92 @@
+//! Periodic background jobs such as purging expired refresh tokens.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use sultan_core::domain::DomainResult;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+
+type JobFuture = Pin<Box<dyn Future<Output = DomainResult<u64>> + Send>>;
+
+struct Job {
+    name: &'static str,
+    run: Box<dyn Fn() -> JobFuture + Send + Sync>,
+}
+
+/// Runs registered jobs one after another every `interval`, starting
+/// immediately. A job returns the number of rows it touched, which is logged;
+/// a failing job is logged and retried on the next tick.
+pub struct TaskRunner {
+    interval: Duration,
+    jobs: Vec<Job>,
+}
+
+impl TaskRunner {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            jobs: Vec::new(),
+        }
+    }
+
+    pub fn register<F, Fut>(mut self, name: &'static str, job: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = DomainResult<u64>> + Send + 'static,
+    {
+        self.jobs.push(Job {
+            name,
+            run: Box::new(move || Box::pin(job())),
+        });
+        self
+    }
+
+    /// Start the loop on the tokio runtime
+    pub fn spawn(self) -> TaskHandle {
+        let shutdown = CancellationToken::new();
+        let handle = tokio::spawn(self.run(shutdown.clone()));
+        TaskHandle { shutdown, handle }
+    }
+
+    async fn run(self, shutdown: CancellationToken) {
+        let mut ticker = tokio::time::interval(self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = ticker.tick() => {}
+            }
+            for job in &self.jobs {
+                // Let a job in progress finish, but don't start the next one
+                if shutdown.is_cancelled() {
+                    break;
+                }
+                match (job.run)().await {
+                    Ok(affected) => tracing::info!(job = job.name, affected, "Task finished"),
+                    Err(e) => tracing::warn!(job = job.name, error = %e, "Task failed"),
+                }
+            }
+        }
+        tracing::debug!("Task runner stopped");
+    }
+}
+
+/// Handle to a running [`TaskRunner`]
+pub struct TaskHandle {
+    shutdown: CancellationToken,
+    handle: JoinHandle<()>,
+}
+
+impl TaskHandle {
+    /// Stop scheduling jobs and wait for the one in progress to finish
+    pub async fn shutdown(self) {
+        self.shutdown.cancel();
+        if let Err(e) = self.handle.await {
+            tracing::error!("Task runner panicked: {}", e);
+        }
+    }
+}
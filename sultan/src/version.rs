@@ -0,0 +1,31 @@
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Build information of the running binary
+#[derive(Debug, Serialize)]
+pub struct VersionResponse {
+    pub version: &'static str,
+    /// Short git commit hash, or `unknown` when built outside a checkout
+    pub commit: &'static str,
+    /// When the binary was built (RFC 3339)
+    pub built_at: Option<String>,
+}
+
+pub fn build_info() -> VersionResponse {
+    let built_at = env!("SULTAN_BUILD_TIMESTAMP")
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0))
+        .map(|built_at| built_at.to_rfc3339());
+    VersionResponse {
+        version: env!("CARGO_PKG_VERSION"),
+        commit: env!("SULTAN_GIT_COMMIT"),
+        built_at,
+    }
+}
+
+/// `GET /version`, open to everyone so deployments can be checked
+pub async fn version_handler() -> Json<VersionResponse> {
+    Json(build_info())
+}
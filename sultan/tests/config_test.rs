@@ -1,6 +1,7 @@
 use serial_test::serial;
 use std::env;
-use sultan::config::AppConfig;
+use sultan::config::{AppConfig, ConfigError};
+use sultan_core::domain::model::phone::PhoneRegion;
 
 /// Helper to set environment variables for tests
 struct EnvGuard {
@@ -37,17 +38,37 @@ fn test_from_env_with_defaults() {
     guard.set("JWT_SECRET", "test_secret_key_123");
     guard.set("DATABASE_URL", "sqlite:test.db");
 
-    let config = AppConfig::from_env();
+    let config = AppConfig::from_env().expect("Config should load");
 
     assert_eq!(config.jwt_secret, "test_secret_key_123");
     assert_eq!(config.database_url, "sqlite:test.db");
     assert_eq!(config.access_token_ttl.as_seconds_f64() as i64, 900);
+    assert_eq!(config.device_token_ttl.as_seconds_f64() as i64, 300);
     assert_eq!(
         config.refresh_token_ttl.as_seconds_f64() as i64,
         30 * 24 * 60 * 60
     );
     assert_eq!(config.database_max_connections, 5);
+    assert_eq!(config.database_acquire_timeout_secs, 5);
+    assert_eq!(config.database_idle_timeout_secs, 600);
+    assert!(config.database_wal);
+    assert_eq!(config.database_busy_timeout_secs, 5);
+    assert_eq!(config.database_busy_retries, 3);
+    assert!(config.database_foreign_keys);
     assert!(!config.write_log_to_file);
+    assert_eq!(config.bind_address, "0.0.0.0");
+    assert_eq!(config.port, 8721);
+    assert!(config.cors_allowed_origins.is_empty());
+    assert!(!config.cors_dev_mode);
+    assert_eq!(config.max_body_bytes, 2 * 1024 * 1024);
+    assert_eq!(config.max_page_size, 200);
+    assert_eq!(config.cleanup_interval_secs, 3600);
+    assert_eq!(config.node_id, 1);
+    assert_eq!(config.default_phone_region, PhoneRegion::ID);
+    assert!(config.product_metadata_schema.is_none());
+    assert!(config.variant_metadata_schema.is_none());
+    assert!(config.customer_metadata_schema.is_none());
+    assert!(config.slow_query_ms.is_none());
 }
 
 #[test]
@@ -58,20 +79,79 @@ fn test_from_env_with_custom_values() {
     guard.set("DATABASE_URL", "sqlite:custom.db");
     guard.set("REFRESH_TOKEN_TTL_DAYS", "60");
     guard.set("ACCESS_TOKEN_TTL_SECS", "1800");
+    guard.set("DEVICE_TOKEN_TTL_SECS", "120");
     guard.set("DATABASE_MAX_CONNECTIONS", "10");
+    guard.set("DATABASE_ACQUIRE_TIMEOUT_SECS", "2");
+    guard.set("DATABASE_IDLE_TIMEOUT_SECS", "60");
+    guard.set("DATABASE_WAL", "0");
+    guard.set("DATABASE_BUSY_TIMEOUT_SECS", "10");
+    guard.set("DATABASE_BUSY_RETRIES", "7");
+    guard.set("DATABASE_FOREIGN_KEYS", "false");
     guard.set("WRITE_LOG_TO_FILE", "1");
+    guard.set("BIND_ADDRESS", "127.0.0.1");
+    guard.set("PORT", "9000");
+    guard.set(
+        "CORS_ALLOWED_ORIGINS",
+        "http://localhost:5173, https://pos.example.com,",
+    );
+    guard.set("CORS_DEV_MODE", "true");
+    guard.set("MAX_BODY_BYTES", "1024");
+    guard.set("MAX_PAGE_SIZE", "50");
+    guard.set("CLEANUP_INTERVAL_SECS", "0");
+    guard.set("NODE_ID", "255");
+    guard.set("DEFAULT_PHONE_REGION", "us");
+    guard.set("PRODUCT_METADATA_SCHEMA", "/etc/sultan/product.schema.json");
+    guard.set("VARIANT_METADATA_SCHEMA", "/etc/sultan/variant.schema.json");
+    guard.set(
+        "CUSTOMER_METADATA_SCHEMA",
+        "/etc/sultan/customer.schema.json",
+    );
+    guard.set("SLOW_QUERY_MS", "250");
 
-    let config = AppConfig::from_env();
+    let config = AppConfig::from_env().expect("Config should load");
 
     assert_eq!(config.jwt_secret, "custom_secret");
     assert_eq!(config.database_url, "sqlite:custom.db");
     assert_eq!(config.access_token_ttl.as_seconds_f64() as i64, 1800);
+    assert_eq!(config.device_token_ttl.as_seconds_f64() as i64, 120);
     assert_eq!(
         config.refresh_token_ttl.as_seconds_f64() as i64,
         60 * 24 * 60 * 60
     );
     assert_eq!(config.database_max_connections, 10);
+    assert_eq!(config.database_acquire_timeout_secs, 2);
+    assert_eq!(config.database_idle_timeout_secs, 60);
+    assert!(!config.database_wal);
+    assert_eq!(config.database_busy_timeout_secs, 10);
+    assert_eq!(config.database_busy_retries, 7);
+    assert!(!config.database_foreign_keys);
     assert!(config.write_log_to_file);
+    assert_eq!(config.bind_address, "127.0.0.1");
+    assert_eq!(config.port, 9000);
+    assert_eq!(config.socket_addr().unwrap().to_string(), "127.0.0.1:9000");
+    assert_eq!(
+        config.cors_allowed_origins,
+        vec!["http://localhost:5173", "https://pos.example.com"]
+    );
+    assert!(config.cors_dev_mode);
+    assert_eq!(config.max_body_bytes, 1024);
+    assert_eq!(config.max_page_size, 50);
+    assert_eq!(config.cleanup_interval_secs, 0);
+    assert_eq!(config.node_id, 255);
+    assert_eq!(config.default_phone_region, PhoneRegion::US);
+    assert_eq!(
+        config.product_metadata_schema.as_deref(),
+        Some("/etc/sultan/product.schema.json")
+    );
+    assert_eq!(
+        config.variant_metadata_schema.as_deref(),
+        Some("/etc/sultan/variant.schema.json")
+    );
+    assert_eq!(
+        config.customer_metadata_schema.as_deref(),
+        Some("/etc/sultan/customer.schema.json")
+    );
+    assert_eq!(config.slow_query_ms, Some(250));
 }
 
 #[test]
@@ -82,7 +162,7 @@ fn test_from_env_write_log_to_file_true() {
     guard.set("DATABASE_URL", "sqlite:test.db");
     guard.set("WRITE_LOG_TO_FILE", "true");
 
-    let config = AppConfig::from_env();
+    let config = AppConfig::from_env().expect("Config should load");
     assert!(config.write_log_to_file);
 }
 
@@ -94,7 +174,7 @@ fn test_from_env_write_log_to_file_yes() {
     guard.set("DATABASE_URL", "sqlite:test.db");
     guard.set("WRITE_LOG_TO_FILE", "yes");
 
-    let config = AppConfig::from_env();
+    let config = AppConfig::from_env().expect("Config should load");
     assert!(config.write_log_to_file);
 }
 
@@ -106,7 +186,7 @@ fn test_from_env_write_log_to_file_uppercase() {
     guard.set("DATABASE_URL", "sqlite:test.db");
     guard.set("WRITE_LOG_TO_FILE", "TRUE");
 
-    let config = AppConfig::from_env();
+    let config = AppConfig::from_env().expect("Config should load");
     assert!(config.write_log_to_file);
 }
 
@@ -118,7 +198,7 @@ fn test_from_env_write_log_to_file_zero() {
     guard.set("DATABASE_URL", "sqlite:test.db");
     guard.set("WRITE_LOG_TO_FILE", "0");
 
-    let config = AppConfig::from_env();
+    let config = AppConfig::from_env().expect("Config should load");
     assert!(!config.write_log_to_file);
 }
 
@@ -130,13 +210,12 @@ fn test_from_env_write_log_to_file_false() {
     guard.set("DATABASE_URL", "sqlite:test.db");
     guard.set("WRITE_LOG_TO_FILE", "false");
 
-    let config = AppConfig::from_env();
+    let config = AppConfig::from_env().expect("Config should load");
     assert!(!config.write_log_to_file);
 }
 
 #[test]
 #[serial]
-#[should_panic(expected = "JWT_SECRET must be set")]
 fn test_from_env_missing_jwt_secret() {
     let mut guard = EnvGuard::new();
     guard.set("DATABASE_URL", "sqlite:test.db");
@@ -146,13 +225,19 @@ fn test_from_env_missing_jwt_secret() {
         env::remove_var("JWT_SECRET");
     }
 
-    // This should panic
-    AppConfig::from_env();
+    let err = AppConfig::from_env()
+        .err()
+        .expect("Config should be rejected");
+    assert_eq!(err.var(), "JWT_SECRET");
+    assert!(
+        err.to_string().contains("JWT_SECRET must be set"),
+        "{}",
+        err
+    );
 }
 
 #[test]
 #[serial]
-#[should_panic(expected = "DATABASE_URL must be set")]
 fn test_from_env_missing_database_url() {
     let mut guard = EnvGuard::new();
     guard.set("JWT_SECRET", "test_secret");
@@ -162,42 +247,249 @@ fn test_from_env_missing_database_url() {
         env::remove_var("DATABASE_URL");
     }
 
-    // This should panic
-    AppConfig::from_env();
+    let err = AppConfig::from_env()
+        .err()
+        .expect("Config should be rejected");
+    assert_eq!(err.var(), "DATABASE_URL");
+    assert!(
+        err.to_string().contains("DATABASE_URL must be set"),
+        "{}",
+        err
+    );
 }
 
 #[test]
 #[serial]
-#[should_panic(expected = "REFRESH_TOKEN_TTL_DAYS must be a valid number")]
 fn test_from_env_invalid_refresh_ttl() {
     let mut guard = EnvGuard::new();
     guard.set("JWT_SECRET", "test_secret");
     guard.set("DATABASE_URL", "sqlite:test.db");
     guard.set("REFRESH_TOKEN_TTL_DAYS", "not_a_number");
 
-    AppConfig::from_env();
+    let err = AppConfig::from_env()
+        .err()
+        .expect("Config should be rejected");
+    assert_eq!(err.var(), "REFRESH_TOKEN_TTL_DAYS");
+    assert!(
+        err.to_string()
+            .contains("REFRESH_TOKEN_TTL_DAYS must be a positive whole number"),
+        "{}",
+        err
+    );
 }
 
 #[test]
 #[serial]
-#[should_panic(expected = "ACCESS_TOKEN_TTL_SECS must be a valid number")]
 fn test_from_env_invalid_access_ttl() {
     let mut guard = EnvGuard::new();
     guard.set("JWT_SECRET", "test_secret");
     guard.set("DATABASE_URL", "sqlite:test.db");
     guard.set("ACCESS_TOKEN_TTL_SECS", "invalid");
 
-    AppConfig::from_env();
+    let err = AppConfig::from_env()
+        .err()
+        .expect("Config should be rejected");
+    assert_eq!(err.var(), "ACCESS_TOKEN_TTL_SECS");
+    assert!(
+        err.to_string()
+            .contains("ACCESS_TOKEN_TTL_SECS must be a positive whole number"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+#[serial]
+fn test_from_env_rejects_non_positive_ttl() {
+    for value in ["0", "-5"] {
+        let mut guard = EnvGuard::new();
+        guard.set("JWT_SECRET", "test_secret");
+        guard.set("DATABASE_URL", "sqlite:test.db");
+        guard.set("ACCESS_TOKEN_TTL_SECS", value);
+
+        let err = AppConfig::from_env()
+            .err()
+            .expect("Config should be rejected");
+        assert!(
+            matches!(
+                &err,
+                ConfigError::Invalid { var: "ACCESS_TOKEN_TTL_SECS", value: v, .. } if v == value
+            ),
+            "{}",
+            err
+        );
+    }
+}
+
+#[test]
+#[serial]
+fn test_from_env_ttl_too_large() {
+    let mut guard = EnvGuard::new();
+    guard.set("JWT_SECRET", "test_secret");
+    guard.set("DATABASE_URL", "sqlite:test.db");
+    guard.set("REFRESH_TOKEN_TTL_DAYS", &u64::MAX.to_string());
+
+    let err = AppConfig::from_env()
+        .err()
+        .expect("Config should be rejected");
+    assert!(matches!(err, ConfigError::DurationOutOfRange { .. }));
+    assert_eq!(err.var(), "REFRESH_TOKEN_TTL_DAYS");
 }
 
 #[test]
 #[serial]
-#[should_panic(expected = "DATABASE_MAX_CONNECTIONS must be a valid number")]
 fn test_from_env_invalid_max_connections() {
     let mut guard = EnvGuard::new();
     guard.set("JWT_SECRET", "test_secret");
     guard.set("DATABASE_URL", "sqlite:test.db");
     guard.set("DATABASE_MAX_CONNECTIONS", "abc");
 
-    AppConfig::from_env();
+    let err = AppConfig::from_env()
+        .err()
+        .expect("Config should be rejected");
+    assert_eq!(err.var(), "DATABASE_MAX_CONNECTIONS");
+    assert!(
+        err.to_string()
+            .contains("DATABASE_MAX_CONNECTIONS must be a valid number"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+#[serial]
+fn test_from_env_invalid_acquire_timeout() {
+    let mut guard = EnvGuard::new();
+    guard.set("JWT_SECRET", "test_secret");
+    guard.set("DATABASE_URL", "sqlite:test.db");
+    guard.set("DATABASE_ACQUIRE_TIMEOUT_SECS", "soon");
+
+    let err = AppConfig::from_env()
+        .err()
+        .expect("Config should be rejected");
+    assert_eq!(err.var(), "DATABASE_ACQUIRE_TIMEOUT_SECS");
+    assert!(
+        err.to_string()
+            .contains("DATABASE_ACQUIRE_TIMEOUT_SECS must be a valid number"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+#[serial]
+fn test_from_env_invalid_port() {
+    let mut guard = EnvGuard::new();
+    guard.set("JWT_SECRET", "test_secret");
+    guard.set("DATABASE_URL", "sqlite:test.db");
+    guard.set("PORT", "70000");
+
+    let err = AppConfig::from_env()
+        .err()
+        .expect("Config should be rejected");
+    assert_eq!(err.var(), "PORT");
+    assert!(
+        err.to_string().contains("PORT must be a valid port number"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+#[serial]
+fn test_from_env_invalid_bind_address() {
+    let mut guard = EnvGuard::new();
+    guard.set("JWT_SECRET", "test_secret");
+    guard.set("DATABASE_URL", "sqlite:test.db");
+    guard.set("BIND_ADDRESS", "not-an-ip");
+
+    let err = AppConfig::from_env()
+        .err()
+        .expect("Config should be rejected");
+    assert_eq!(err.var(), "BIND_ADDRESS");
+    assert!(
+        err.to_string()
+            .contains("BIND_ADDRESS must be a valid IP address, got 'not-an-ip'"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+#[serial]
+fn test_from_env_zero_max_page_size() {
+    let mut guard = EnvGuard::new();
+    guard.set("JWT_SECRET", "test_secret");
+    guard.set("DATABASE_URL", "sqlite:test.db");
+    guard.set("MAX_PAGE_SIZE", "0");
+
+    let err = AppConfig::from_env()
+        .err()
+        .expect("Config should be rejected");
+    assert_eq!(err.var(), "MAX_PAGE_SIZE");
+    assert!(
+        err.to_string().contains("MAX_PAGE_SIZE must be at least 1"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+#[serial]
+fn test_from_env_invalid_slow_query_ms() {
+    let mut guard = EnvGuard::new();
+    guard.set("JWT_SECRET", "test_secret");
+    guard.set("DATABASE_URL", "sqlite:test.db");
+    guard.set("SLOW_QUERY_MS", "fast");
+
+    let err = AppConfig::from_env()
+        .err()
+        .expect("Config should be rejected");
+    assert_eq!(err.var(), "SLOW_QUERY_MS");
+    assert!(
+        err.to_string()
+            .contains("SLOW_QUERY_MS must be a valid number of milliseconds"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+#[serial]
+fn test_from_env_node_id_out_of_range() {
+    let mut guard = EnvGuard::new();
+    guard.set("JWT_SECRET", "test_secret");
+    guard.set("DATABASE_URL", "sqlite:test.db");
+    guard.set("NODE_ID", "256");
+
+    let err = AppConfig::from_env()
+        .err()
+        .expect("Config should be rejected");
+    assert_eq!(err.var(), "NODE_ID");
+    assert!(
+        err.to_string()
+            .contains("NODE_ID must be between 0 and 255, got '256'"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+#[serial]
+fn test_from_env_invalid_phone_region() {
+    let mut guard = EnvGuard::new();
+    guard.set("JWT_SECRET", "test_secret");
+    guard.set("DATABASE_URL", "sqlite:test.db");
+    guard.set("DEFAULT_PHONE_REGION", "XX");
+
+    let err = AppConfig::from_env()
+        .err()
+        .expect("Config should be rejected");
+    assert_eq!(err.var(), "DEFAULT_PHONE_REGION");
+    assert!(
+        err.to_string()
+            .contains("DEFAULT_PHONE_REGION must be a region code like ID or US, got 'XX'"),
+        "{}",
+        err
+    );
 }
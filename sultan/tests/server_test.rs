@@ -0,0 +1,656 @@
+use std::collections::HashSet;
+use sultan::config::AppConfig;
+use sultan::server::{
+    COMPRESSION_MIN_BYTES, compression_layer, cors_layer, import_body_limit, init_app_state,
+    init_sqlite_db, load_metadata_schema, sqlite_pool_options, wait_for_shutdown, with_body_limits,
+    with_request_timeout,
+};
+use sultan_core::domain::{
+    Context, Error,
+    model::{
+        category::category_create_with_name, customer::CustomerCreate,
+        pagination::DEFAULT_MAX_PAGE_SIZE, phone::DEFAULT_PHONE_REGION, supplier::SupplierCreate,
+    },
+};
+use tempfile::TempDir;
+use time::Duration;
+use uuid::Uuid;
+
+/// File database inside `dir`, removed along with it
+fn database_url(dir: &TempDir) -> String {
+    format!("sqlite://{}", dir.path().join("test_server.db").display())
+}
+
+fn test_config(max_connections: u32, acquire_timeout_secs: u64) -> AppConfig {
+    AppConfig {
+        jwt_secret: "test_secret".to_string(),
+        access_token_ttl: Duration::seconds(900),
+        refresh_token_ttl: Duration::days(30),
+        device_token_ttl: Duration::seconds(300),
+        idempotency_key_ttl: Duration::hours(24),
+        database_url: "sqlite::memory:".to_string(),
+        database_max_connections: max_connections,
+        database_acquire_timeout_secs: acquire_timeout_secs,
+        database_idle_timeout_secs: 600,
+        database_wal: true,
+        database_busy_timeout_secs: 5,
+        database_busy_retries: 3,
+        database_foreign_keys: true,
+        write_log_to_file: false,
+        bind_address: "127.0.0.1".to_string(),
+        port: 0,
+        cors_allowed_origins: vec![],
+        cors_dev_mode: false,
+        max_body_bytes: 2 * 1024 * 1024,
+        max_page_size: DEFAULT_MAX_PAGE_SIZE,
+        request_timeout_secs: 30,
+        cleanup_interval_secs: 3600,
+        node_id: 1,
+        default_phone_region: DEFAULT_PHONE_REGION,
+        product_metadata_schema: None,
+        variant_metadata_schema: None,
+        customer_metadata_schema: None,
+        slow_query_ms: None,
+    }
+}
+
+#[tokio::test]
+async fn test_pool_acquire_times_out_when_exhausted() {
+    let config = test_config(1, 1);
+    let pool = sqlite_pool_options(&config)
+        .connect(&config.database_url)
+        .await
+        .expect("Failed to create pool");
+
+    // Hold the only connection for the whole test
+    let _held = pool.acquire().await.expect("First checkout should succeed");
+
+    let started = std::time::Instant::now();
+    let result = pool.acquire().await.map_err(Error::from);
+
+    assert!(matches!(result, Err(Error::Database(_))));
+    assert!(started.elapsed() >= std::time::Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_pool_acquire_succeeds_after_release() {
+    let config = test_config(1, 1);
+    let pool = sqlite_pool_options(&config)
+        .connect(&config.database_url)
+        .await
+        .expect("Failed to create pool");
+
+    let first = pool.acquire().await.expect("First checkout should succeed");
+    drop(first);
+
+    assert!(pool.acquire().await.is_ok());
+}
+
+#[tokio::test]
+async fn test_pool_applies_pragmas() {
+    let dir = TempDir::new().unwrap();
+    let mut config = test_config(2, 5);
+    config.database_url = database_url(&dir);
+    let pool = init_sqlite_db(&config)
+        .await
+        .expect("Failed to initialize database");
+
+    let foreign_keys: i64 = sqlx::query_scalar("PRAGMA foreign_keys")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(foreign_keys, 1);
+
+    let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(journal_mode, "wal");
+
+    let busy_timeout: i64 = sqlx::query_scalar("PRAGMA busy_timeout")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(busy_timeout, 5000);
+
+    // product_categories references both products and categories
+    let result =
+        sqlx::query("INSERT INTO product_categories (product_id, category_id) VALUES (?, ?)")
+            .bind(999_i64)
+            .bind(999_i64)
+            .execute(&pool)
+            .await
+            .map_err(Error::from);
+    assert!(matches!(result, Err(Error::Database(msg)) if msg.contains("FOREIGN KEY")));
+}
+
+#[tokio::test]
+async fn test_shutdown_waits_for_a_trigger() {
+    let (ctrl_c_tx, ctrl_c_rx) = tokio::sync::oneshot::channel::<()>();
+    let (term_tx, term_rx) = tokio::sync::oneshot::channel::<()>();
+
+    let shutdown = tokio::spawn(wait_for_shutdown(
+        async {
+            ctrl_c_rx.await.ok();
+        },
+        async {
+            term_rx.await.ok();
+        },
+    ));
+
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(!shutdown.is_finished());
+
+    term_tx.send(()).unwrap();
+    let signal = tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+        .await
+        .expect("Shutdown future should resolve after the trigger")
+        .unwrap();
+    assert_eq!(signal, "SIGTERM");
+    drop(ctrl_c_tx);
+}
+
+#[tokio::test]
+async fn test_shutdown_drains_in_flight_request() {
+    use axum::{Router, routing::get};
+
+    let (trigger_tx, trigger_rx) = tokio::sync::oneshot::channel::<()>();
+    let app = Router::new().route(
+        "/slow",
+        get(|| async {
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            "done"
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app)
+            .with_graceful_shutdown(async {
+                wait_for_shutdown(
+                    async {
+                        trigger_rx.await.ok();
+                    },
+                    std::future::pending(),
+                )
+                .await;
+            })
+            .await
+    });
+
+    let request = tokio::spawn(async move {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /slow HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    });
+
+    // Shut down while the request is still being handled
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    trigger_tx.send(()).unwrap();
+
+    let response = request.await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"));
+    assert!(response.ends_with("done"));
+    server.await.unwrap().expect("Server should stop cleanly");
+}
+
+async fn cors_request(
+    config: &AppConfig,
+    method: &str,
+    origin: &str,
+) -> axum::http::Response<axum::body::Body> {
+    use axum::{Router, body::Body, http::Request, routing::get};
+    use tower::ServiceExt;
+
+    let app = Router::new()
+        .route("/api/product/1", get(|| async { "ok" }))
+        .layer(cors_layer(config).expect("Failed to build CORS layer"));
+
+    let mut request = Request::builder()
+        .method(method)
+        .uri("/api/product/1")
+        .header("origin", origin);
+    if method == "OPTIONS" {
+        request = request
+            .header("access-control-request-method", "PUT")
+            .header("access-control-request-headers", "authorization");
+    }
+
+    app.oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_cors_allows_listed_origin() {
+    let mut config = test_config(1, 1);
+    config.cors_allowed_origins = vec!["http://localhost:5173".to_string()];
+
+    let response = cors_request(&config, "GET", "http://localhost:5173").await;
+    assert_eq!(
+        response.headers()["access-control-allow-origin"],
+        "http://localhost:5173"
+    );
+
+    // Preflight is answered by the layer
+    let response = cors_request(&config, "OPTIONS", "http://localhost:5173").await;
+    assert!(response.status().is_success());
+    assert_eq!(
+        response.headers()["access-control-allow-origin"],
+        "http://localhost:5173"
+    );
+    let methods = response.headers()["access-control-allow-methods"]
+        .to_str()
+        .unwrap();
+    assert!(methods.contains("PUT"), "{}", methods);
+}
+
+#[tokio::test]
+async fn test_cors_omits_header_for_other_origin() {
+    let mut config = test_config(1, 1);
+    config.cors_allowed_origins = vec!["http://localhost:5173".to_string()];
+
+    let response = cors_request(&config, "GET", "https://evil.example.com").await;
+    assert!(
+        response
+            .headers()
+            .get("access-control-allow-origin")
+            .is_none()
+    );
+
+    // Nothing is allowed by default
+    let response = cors_request(&test_config(1, 1), "OPTIONS", "http://localhost:5173").await;
+    assert!(
+        response
+            .headers()
+            .get("access-control-allow-origin")
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_cors_dev_mode_mirrors_origin() {
+    let mut config = test_config(1, 1);
+    config.cors_dev_mode = true;
+
+    let response = cors_request(&config, "GET", "http://192.168.1.20:3000").await;
+    assert_eq!(
+        response.headers()["access-control-allow-origin"],
+        "http://192.168.1.20:3000"
+    );
+}
+
+#[test]
+fn test_cors_rejects_invalid_origin() {
+    let mut config = test_config(1, 1);
+    config.cors_allowed_origins = vec!["http://bad\norigin".to_string()];
+
+    let err = cors_layer(&config).unwrap_err().to_string();
+    assert!(err.contains("Invalid CORS origin"), "{}", err);
+}
+
+async fn compressed_get(
+    uri: &str,
+    accept_encoding: &str,
+) -> axum::http::Response<axum::body::Body> {
+    use axum::{
+        Json, Router,
+        body::Body,
+        http::{Request, header},
+        response::IntoResponse,
+        routing::get,
+    };
+    use futures::stream;
+    use tower::ServiceExt;
+
+    let app = Router::new()
+        .route(
+            "/api/customer",
+            get(|| async {
+                let customers: Vec<_> = (0..200)
+                    .map(|i| serde_json::json!({"id": i, "name": format!("Customer {}", i)}))
+                    .collect();
+                Json(serde_json::json!({ "customers": customers }))
+            }),
+        )
+        .route(
+            "/api/customer/1",
+            get(|| async { Json(serde_json::json!({"id": 1})) }),
+        )
+        .route(
+            "/api/customer/export.csv",
+            get(|| async {
+                let rows = (0..200)
+                    .map(|i| Ok::<_, std::io::Error>(format!("CUST{:03},Customer {},,,0\n", i, i)));
+                (
+                    [(header::CONTENT_TYPE, "text/csv")],
+                    Body::from_stream(stream::iter(rows)),
+                )
+                    .into_response()
+            }),
+        )
+        .route(
+            "/api/customer/stream",
+            get(|| async {
+                let rows = (0..200).map(|i| {
+                    Ok::<_, std::io::Error>(format!(
+                        "{{\"id\":{},\"name\":\"Customer {}\"}}\n",
+                        i, i
+                    ))
+                });
+                (
+                    [(header::CONTENT_TYPE, "application/x-ndjson")],
+                    Body::from_stream(stream::iter(rows)),
+                )
+                    .into_response()
+            }),
+        )
+        .layer(compression_layer());
+
+    app.oneshot(
+        Request::builder()
+            .uri(uri)
+            .header(header::ACCEPT_ENCODING, accept_encoding)
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+async fn gunzip(response: axum::http::Response<axum::body::Body>) -> String {
+    use std::io::Read;
+
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let mut body = String::new();
+    flate2::read::GzDecoder::new(&bytes[..])
+        .read_to_string(&mut body)
+        .expect("Body should be valid gzip");
+    body
+}
+
+#[tokio::test]
+async fn test_large_list_is_gzip_compressed() {
+    let response = compressed_get("/api/customer", "gzip").await;
+    assert_eq!(response.headers()["content-encoding"], "gzip");
+
+    let json: serde_json::Value = serde_json::from_str(&gunzip(response).await).unwrap();
+    assert_eq!(json["customers"].as_array().unwrap().len(), 200);
+    assert!(json.to_string().len() > COMPRESSION_MIN_BYTES as usize);
+}
+
+#[tokio::test]
+async fn test_compression_negotiates_brotli() {
+    let response = compressed_get("/api/customer", "br").await;
+    assert_eq!(response.headers()["content-encoding"], "br");
+}
+
+#[tokio::test]
+async fn test_small_or_unrequested_responses_are_not_compressed() {
+    let response = compressed_get("/api/customer/1", "gzip").await;
+    assert!(response.headers().get("content-encoding").is_none());
+
+    let response = compressed_get("/api/customer", "identity").await;
+    assert!(response.headers().get("content-encoding").is_none());
+}
+
+#[tokio::test]
+async fn test_streamed_csv_export_is_compressed() {
+    let response = compressed_get("/api/customer/export.csv", "gzip").await;
+    assert_eq!(response.headers()["content-encoding"], "gzip");
+    assert_eq!(response.headers()["content-type"], "text/csv");
+
+    let csv = gunzip(response).await;
+    assert_eq!(csv.lines().count(), 200);
+    assert!(csv.starts_with("CUST000,Customer 0,"));
+}
+
+#[tokio::test]
+async fn test_ndjson_stream_is_not_compressed() {
+    let response = compressed_get("/api/customer/stream", "gzip").await;
+    assert!(response.headers().get("content-encoding").is_none());
+    assert_eq!(response.headers()["content-type"], "application/x-ndjson");
+}
+
+async fn post_json(config: &AppConfig, body: Vec<u8>) -> (axum::http::StatusCode, String, String) {
+    use axum::{Json, Router, body::Body, http::Request, routing::post};
+    use tower::ServiceExt;
+
+    let router = Router::new().route(
+        "/echo",
+        post(|Json(value): Json<serde_json::Value>| async move { Json(value) }),
+    );
+    let app = with_body_limits(router, config);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/echo")
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .map(|v| v.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        content_type,
+        String::from_utf8(bytes.to_vec()).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_oversized_body_is_rejected_with_json_413() {
+    let mut config = test_config(1, 1);
+    config.max_body_bytes = 1024;
+
+    let small = serde_json::to_vec(&serde_json::json!({"name": "ok"})).unwrap();
+    let (status, _, body) = post_json(&config, small).await;
+    assert_eq!(status, axum::http::StatusCode::OK);
+    assert_eq!(body, r#"{"name":"ok"}"#);
+
+    let large = serde_json::to_vec(&serde_json::json!({"name": "x".repeat(4096)})).unwrap();
+    let (status, content_type, body) = post_json(&config, large).await;
+    assert_eq!(status, axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(content_type, "application/json");
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(json["error"], "Request body too large");
+    assert_eq!(json["code"], "payload_too_large");
+}
+
+#[test]
+fn test_import_body_limit_never_below_global_limit() {
+    let mut config = test_config(1, 1);
+    assert_eq!(
+        import_body_limit(10 * 1024 * 1024, &config),
+        10 * 1024 * 1024
+    );
+
+    config.max_body_bytes = 50 * 1024 * 1024;
+    assert_eq!(
+        import_body_limit(10 * 1024 * 1024, &config),
+        50 * 1024 * 1024
+    );
+}
+
+#[tokio::test]
+async fn test_services_share_one_id_generator() {
+    let dir = TempDir::new().unwrap();
+    let mut config = test_config(2, 5);
+    config.database_url = database_url(&dir);
+    let pool = init_sqlite_db(&config)
+        .await
+        .expect("Failed to initialize database");
+    let state = init_app_state(&config, pool)
+        .await
+        .expect("Failed to build app state");
+    let ctx = Context::system();
+
+    // Separate generators on the same node would hand out the same id when
+    // two services create something within the same millisecond
+    let mut ids = HashSet::new();
+    for i in 0..50 {
+        let category_id = state
+            .category_service
+            .create(&ctx, &category_create_with_name(&format!("Category {}", i)))
+            .await
+            .expect("Failed to create category");
+        let supplier_id = state
+            .supplier_service
+            .create(
+                &ctx,
+                &SupplierCreate {
+                    name: format!("Supplier {}", i),
+                    code: None,
+                    address: None,
+                    phone: None,
+                    npwp: None,
+                    npwp_name: None,
+                    email: None,
+                    metadata: None,
+                },
+            )
+            .await
+            .expect("Failed to create supplier");
+        assert!(ids.insert(category_id));
+        assert!(ids.insert(supplier_id));
+    }
+}
+
+#[tokio::test]
+async fn test_app_state_rejects_out_of_range_node_id() {
+    let mut config = test_config(1, 5);
+    config.node_id = 256;
+    let pool = sqlite_pool_options(&config)
+        .connect(&config.database_url)
+        .await
+        .expect("Failed to create pool");
+
+    let result = init_app_state(&config, pool).await;
+
+    let err = result
+        .err()
+        .expect("Node 256 should be rejected")
+        .to_string();
+    assert!(err.contains("Invalid node ID: 256"), "{}", err);
+}
+
+#[test]
+fn test_load_metadata_schema_none_when_unset() {
+    assert!(load_metadata_schema(None).unwrap().is_none());
+}
+
+#[test]
+fn test_load_metadata_schema_reports_bad_file() {
+    let dir = TempDir::new().unwrap();
+    let missing = dir.path().join("missing_schema.json");
+    let missing = missing.to_str().unwrap();
+    let err = load_metadata_schema(Some(missing))
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(err.contains("Failed to read metadata schema"), "{}", err);
+
+    let invalid = dir.path().join("invalid_schema.json");
+    std::fs::write(&invalid, "{not json").unwrap();
+    let err = load_metadata_schema(invalid.to_str())
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(err.contains("is not valid JSON"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_app_state_applies_customer_metadata_schema() {
+    let dir = TempDir::new().unwrap();
+    let schema_path = dir.path().join("customer_schema.json");
+    std::fs::write(
+        &schema_path,
+        r#"{"type": "object", "properties": {"sku": {"type": "string"}}, "required": ["sku"]}"#,
+    )
+    .unwrap();
+    let mut config = test_config(2, 5);
+    config.database_url = database_url(&dir);
+    config.customer_metadata_schema = Some(schema_path.to_str().unwrap().to_string());
+    let pool = init_sqlite_db(&config)
+        .await
+        .expect("Failed to initialize database");
+    let state = init_app_state(&config, pool)
+        .await
+        .expect("Failed to build app state");
+    let ctx = Context::system();
+
+    let customer = |metadata| CustomerCreate {
+        number: format!("C-{}", Uuid::new_v4()),
+        name: "Schema Customer".to_string(),
+        address: None,
+        email: None,
+        phone: None,
+        level: 1,
+        metadata: Some(metadata),
+    };
+    state
+        .customer_service
+        .create(&ctx, &customer(serde_json::json!({"sku": "C-1"})))
+        .await
+        .expect("Metadata with sku should be accepted");
+    let result = state
+        .customer_service
+        .create(&ctx, &customer(serde_json::json!({})))
+        .await;
+    assert!(matches!(result, Err(Error::ValidationError(_))));
+}
+
+#[tokio::test]
+async fn test_slow_request_times_out_with_504() {
+    use axum::{Router, body::Body, http::Request, routing::get};
+    use tower::ServiceExt;
+
+    async fn sleep_for(ms: u64) -> &'static str {
+        tokio::time::sleep(std::time::Duration::from_millis(ms)).await;
+        "done"
+    }
+
+    let timed = Router::new()
+        .route("/slow", get(|| sleep_for(500)))
+        .route("/fast", get(|| sleep_for(0)));
+    // Routes added after the layer, like export and import, are not covered
+    let app = with_request_timeout(timed, std::time::Duration::from_millis(50))
+        .route("/exempt", get(|| sleep_for(100)));
+
+    let request = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+    let response = app.clone().oneshot(request("/slow")).await.unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::GATEWAY_TIMEOUT);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["error"], "Request timed out");
+    assert_eq!(json["code"], "timeout");
+
+    let response = app.clone().oneshot(request("/fast")).await.unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let response = app.oneshot(request("/exempt")).await.unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+}
@@ -0,0 +1,160 @@
+//! Slow-query logging goes through sqlx's statement logger, which runs on the
+//! SQLite worker thread. Capturing it needs a global tracing subscriber, so
+//! these tests live in their own binary and tell their statements apart by a
+//! marker comment.
+
+use std::sync::{Arc, Mutex, OnceLock};
+
+use sultan::{
+    config::AppConfig,
+    server::{sqlite_connect_options, sqlite_pool_options},
+};
+use sultan_core::domain::model::{pagination::DEFAULT_MAX_PAGE_SIZE, phone::DEFAULT_PHONE_REGION};
+use time::Duration;
+use tracing::{
+    Event, Level, Subscriber,
+    field::{Field, Visit},
+};
+use tracing_subscriber::{Layer, layer::Context, prelude::*};
+use uuid::Uuid;
+
+#[derive(Debug, Default, Clone)]
+struct CapturedEvent {
+    level: Option<Level>,
+    message: String,
+    statement: String,
+    has_elapsed: bool,
+}
+
+impl Visit for CapturedEvent {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "db.statement" {
+            self.statement = value.to_string();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        match field.name() {
+            "message" => self.message = format!("{:?}", value),
+            "elapsed" => self.has_elapsed = true,
+            _ => {}
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct CaptureLayer(Arc<Mutex<Vec<CapturedEvent>>>);
+
+impl<S: Subscriber> Layer<S> for CaptureLayer {
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        if event.metadata().target() != "sqlx::query" {
+            return;
+        }
+        let mut captured = CapturedEvent {
+            level: Some(*event.metadata().level()),
+            ..Default::default()
+        };
+        event.record(&mut captured);
+        self.0.lock().unwrap().push(captured);
+    }
+}
+
+fn captured_events() -> &'static CaptureLayer {
+    static CAPTURE: OnceLock<CaptureLayer> = OnceLock::new();
+    CAPTURE.get_or_init(|| {
+        let layer = CaptureLayer::default();
+        tracing_subscriber::registry().with(layer.clone()).init();
+        layer
+    })
+}
+
+fn events_for(marker: &str) -> Vec<CapturedEvent> {
+    captured_events()
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|e| e.statement.contains(marker))
+        .cloned()
+        .collect()
+}
+
+fn test_config(slow_query_ms: Option<u64>) -> AppConfig {
+    AppConfig {
+        jwt_secret: "test_secret".to_string(),
+        access_token_ttl: Duration::seconds(900),
+        refresh_token_ttl: Duration::days(30),
+        device_token_ttl: Duration::seconds(300),
+        idempotency_key_ttl: Duration::hours(24),
+        database_url: "sqlite::memory:".to_string(),
+        database_max_connections: 1,
+        database_acquire_timeout_secs: 5,
+        database_idle_timeout_secs: 600,
+        database_wal: false,
+        database_busy_timeout_secs: 5,
+        database_busy_retries: 3,
+        database_foreign_keys: true,
+        write_log_to_file: false,
+        bind_address: "127.0.0.1".to_string(),
+        port: 0,
+        cors_allowed_origins: vec![],
+        cors_dev_mode: false,
+        max_body_bytes: 2 * 1024 * 1024,
+        max_page_size: DEFAULT_MAX_PAGE_SIZE,
+        request_timeout_secs: 30,
+        cleanup_interval_secs: 3600,
+        node_id: 1,
+        default_phone_region: DEFAULT_PHONE_REGION,
+        product_metadata_schema: None,
+        variant_metadata_schema: None,
+        customer_metadata_schema: None,
+        slow_query_ms,
+    }
+}
+
+/// Counts to a million with a recursive CTE, which takes well over the
+/// thresholds used here
+async fn run_slow_query(config: &AppConfig, marker: &str) {
+    let pool = sqlite_pool_options(config)
+        .connect_with(sqlite_connect_options(config).unwrap())
+        .await
+        .expect("Failed to create pool");
+
+    let sql = format!(
+        "/* {} */ WITH RECURSIVE cnt(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM cnt WHERE x < 1000000) SELECT count(*) FROM cnt",
+        marker
+    );
+    let count: i64 = sqlx::query_scalar(&sql)
+        .fetch_one(&pool)
+        .await
+        .expect("Slow query failed");
+    assert_eq!(count, 1_000_000);
+}
+
+#[tokio::test]
+async fn test_slow_query_logged_at_warn() {
+    captured_events();
+    let marker = format!("slow-query-{}", Uuid::new_v4());
+
+    run_slow_query(&test_config(Some(5)), &marker).await;
+
+    let events = events_for(&marker);
+    assert_eq!(events.len(), 1, "{:?}", events);
+    let event = &events[0];
+    assert_eq!(event.level, Some(Level::WARN));
+    assert!(event.message.contains("slow statement"), "{:?}", event);
+    assert!(event.has_elapsed);
+}
+
+#[tokio::test]
+async fn test_slow_query_logging_off_by_default() {
+    captured_events();
+    let marker = format!("slow-query-off-{}", Uuid::new_v4());
+
+    run_slow_query(&test_config(None), &marker).await;
+
+    // Still logged like any other statement, just not as a slow one
+    let events = events_for(&marker);
+    assert_eq!(events.len(), 1, "{:?}", events);
+    assert_eq!(events[0].level, Some(Level::DEBUG));
+}
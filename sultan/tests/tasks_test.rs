@@ -0,0 +1,56 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use sultan::tasks::TaskRunner;
+use sultan_core::domain::Error;
+
+#[tokio::test]
+async fn test_registered_job_runs_within_interval() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let counter = runs.clone();
+    let tasks = TaskRunner::new(Duration::from_millis(20))
+        .register("noop", move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async { Ok(0) }
+        })
+        .spawn();
+
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while runs.load(Ordering::SeqCst) < 2 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("Job should run on every tick");
+
+    tasks.shutdown().await;
+    let after_shutdown = runs.load(Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(runs.load(Ordering::SeqCst), after_shutdown);
+}
+
+#[tokio::test]
+async fn test_failing_job_does_not_stop_others() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let counter = runs.clone();
+    let tasks = TaskRunner::new(Duration::from_millis(20))
+        .register("failing", || async {
+            Err(Error::Internal("boom".to_string()))
+        })
+        .register("noop", move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async { Ok(0) }
+        })
+        .spawn();
+
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while runs.load(Ordering::SeqCst) < 2 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("Later jobs should keep running after a failure");
+
+    tasks.shutdown().await;
+}
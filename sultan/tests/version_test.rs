@@ -0,0 +1,25 @@
+use axum::{Router, body::Body, http::Request, http::StatusCode, routing::get};
+use http_body_util::BodyExt;
+use sultan::version::version_handler;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_version_reports_crate_version() {
+    let app = Router::new().route("/version", get(version_handler));
+
+    let response = app
+        .oneshot(Request::get("/version").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let version = json["version"]
+        .as_str()
+        .expect("version should be a string");
+    assert!(!version.is_empty());
+    assert_eq!(version, env!("CARGO_PKG_VERSION"));
+    assert!(!json["commit"].as_str().unwrap().is_empty());
+    assert!(json["built_at"].is_string());
+}
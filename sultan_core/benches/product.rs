@@ -0,0 +1,110 @@
+//! Throughput of the product hot path against an in-memory SQLite pool.
+//!
+//! Run with `cargo bench -p sultan_core --bench product`. CI only builds the
+//! benches (`cargo bench --no-run`) so they keep compiling.
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use sultan_core::{
+    domain::{
+        Context,
+        model::product::{ProductCreate, ProductType},
+    },
+    storage::{
+        ProductRepository,
+        sqlite::{SqliteProductRepository, transaction::SqliteTransactionManager},
+        transaction::TransactionManager,
+    },
+    testing::storage::{generate_test_id, init_memory_sqlite_pool},
+};
+use tokio::runtime::Runtime;
+
+/// Products written per transaction in the batch baseline
+const BATCH_SIZE: u64 = 100;
+
+struct Fixture {
+    ctx: Context,
+    tx_manager: SqliteTransactionManager,
+    repo: SqliteProductRepository,
+}
+
+async fn fixture() -> Fixture {
+    let pool = init_memory_sqlite_pool().await;
+    Fixture {
+        ctx: Context::new(),
+        tx_manager: SqliteTransactionManager::new(pool.clone()),
+        repo: SqliteProductRepository::new(pool),
+    }
+}
+
+fn product() -> ProductCreate {
+    ProductCreate {
+        name: "Kopi Susu".to_string(),
+        description: Some("Iced coffee with palm sugar".to_string()),
+        product_type: ProductType::Product,
+        main_image: None,
+        sellable: true,
+        buyable: true,
+        editable_price: false,
+        has_variant: false,
+        metadata: None,
+        category_ids: vec![],
+    }
+}
+
+impl Fixture {
+    /// Insert `count` products in a single transaction
+    async fn create_products(&self, product: &ProductCreate, count: u64) -> Vec<i64> {
+        let mut ids = Vec::with_capacity(count as usize);
+        let mut tx = self.tx_manager.begin().await.unwrap();
+        for _ in 0..count {
+            let id = generate_test_id().await;
+            self.repo
+                .create_product(&self.ctx, id, product, &mut tx)
+                .await
+                .unwrap();
+            ids.push(id);
+        }
+        self.tx_manager.commit(tx).await.unwrap();
+        ids
+    }
+}
+
+fn bench_create_product(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let fixture = rt.block_on(fixture());
+    let product = product();
+
+    let mut group = c.benchmark_group("create_product");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("single", |b| {
+        b.to_async(&rt)
+            .iter(|| fixture.create_products(&product, 1));
+    });
+    group.throughput(Throughput::Elements(BATCH_SIZE));
+    group.bench_function("batch", |b| {
+        b.to_async(&rt)
+            .iter(|| fixture.create_products(&product, BATCH_SIZE));
+    });
+    group.finish();
+}
+
+fn bench_get_by_id(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let fixture = rt.block_on(fixture());
+    let ids = rt.block_on(fixture.create_products(&product(), BATCH_SIZE));
+
+    let mut next = ids.iter().cycle();
+    c.bench_function("get_by_id", |b| {
+        b.to_async(&rt).iter(|| {
+            let id = *next.next().unwrap();
+            let fixture = &fixture;
+            async move {
+                let found = fixture.repo.get_by_id(&fixture.ctx, id).await.unwrap();
+                assert!(found.is_some());
+            }
+        });
+    });
+}
+
+criterion_group!(benches, bench_create_product, bench_get_by_id);
+criterion_main!(benches);
@@ -1,20 +1,81 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use rand::RngCore;
 
-use crate::crypto::{JwtManager, PasswordHash};
+use crate::crypto::{JwtManager, PasswordHash, PasswordPolicy};
+use crate::domain::model::device::{Device, hash_device_key};
+use crate::domain::model::permission::{action, resource};
 use crate::domain::model::token::Token;
-use crate::domain::{Context, DomainResult, Error};
-use crate::storage::{TokenRepository, UserRepository};
+use crate::domain::{BranchContext, Context, DomainResult, Error, User};
+use crate::storage::{BranchRepository, DeviceRepository, TokenRepository, UserRepository};
 
 /// Default refresh token expiry in days
 const DEFAULT_REFRESH_TOKEN_EXPIRY_DAYS: i64 = 30;
 
+/// Default maximum number of branch ids embedded in the access token
+const DEFAULT_MAX_EMBEDDED_BRANCHES: usize = 32;
+
+/// Default number of consecutive bad passwords before the account is locked
+const DEFAULT_MAX_FAILED_LOGINS: i32 = 5;
+
+/// Default account lockout duration in minutes
+const DEFAULT_LOCKOUT_MINUTES: i64 = 15;
+
+/// Why a login attempt was rejected.
+///
+/// Only used for server-side logging; the caller always receives
+/// [`Error::InvalidCredentials`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginFailure {
+    UserNotFound,
+    BadPassword,
+    UserDisabled,
+    UserLocked,
+    DeviceNotFound,
+    DeviceRevoked,
+}
+
+impl LoginFailure {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoginFailure::UserNotFound => "user_not_found",
+            LoginFailure::BadPassword => "bad_password",
+            LoginFailure::UserDisabled => "user_disabled",
+            LoginFailure::UserLocked => "user_locked",
+            LoginFailure::DeviceNotFound => "device_not_found",
+            LoginFailure::DeviceRevoked => "device_revoked",
+        }
+    }
+}
+
+pub const LOGIN_FAILURES_TOTAL: &str = "auth_login_failures_total";
+
+/// Count a rejected login and turn it into the error the caller sees
+fn login_rejected(reason: LoginFailure) -> Error {
+    metrics::counter!(LOGIN_FAILURES_TOTAL, "reason" => reason.as_str()).increment(1);
+    match reason {
+        LoginFailure::UserLocked => Error::Forbidden("Account is temporarily locked".to_string()),
+        _ => Error::InvalidCredentials,
+    }
+}
+
+/// Users may manage their own sessions and password; anyone else needs admin
+fn require_self_or_admin(ctx: &Context, user_id: i64) -> DomainResult<()> {
+    if ctx.user_id() == Some(user_id) {
+        return Ok(());
+    }
+    ctx.require_access(None, resource::ADMIN, action::UPDATE)
+}
+
 /// Response containing access token and refresh token
 #[derive(Debug, Clone)]
 pub struct AuthTokens {
     pub access_token: String,
     pub refresh_token: String,
+    pub access_expires_at: DateTime<Utc>,
+    pub refresh_expires_at: DateTime<Utc>,
 }
 
 #[async_trait]
@@ -27,29 +88,64 @@ pub trait AuthServiceTrait: Send + Sync {
     ) -> DomainResult<AuthTokens>;
     async fn refresh(&self, ctx: &Context, refresh_token: &str) -> DomainResult<AuthTokens>;
     async fn logout(&self, ctx: &Context, refresh_token: &str) -> DomainResult<()>;
+    /// Revoke every refresh token of a user, signing them out on all devices.
+    /// Access tokens already issued stay valid until they expire. Allowed for
+    /// the user themselves and for admins.
+    async fn logout_all(&self, ctx: &Context, user_id: i64) -> DomainResult<()>;
+    /// Log a kiosk in with its device key. Returns an access token only;
+    /// devices never get a refresh token and log in again when it expires.
+    async fn login_device(&self, ctx: &Context, device_key: &str) -> DomainResult<String>;
+    /// Fails with `Unauthorized` unless the device exists and is not revoked
+    async fn check_device(&self, ctx: &Context, device_id: i64) -> DomainResult<()>;
+    /// Resolve the branch memberships of a user
+    async fn branch_context(&self, ctx: &Context, user_id: i64) -> DomainResult<BranchContext>;
+    /// Replace a password after checking the current one. Users may only
+    /// change their own password unless they are admins. Existing refresh
+    /// tokens are revoked unless that was turned off with
+    /// `with_revoke_tokens_on_password_change`.
+    async fn change_password(
+        &self,
+        ctx: &Context,
+        user_id: i64,
+        old_password: &str,
+        new_password: &str,
+    ) -> DomainResult<()>;
+    /// Delete refresh tokens past their expiry. Admin only. Returns how many
+    /// were removed.
+    async fn purge_expired_tokens(&self, ctx: &Context) -> DomainResult<u64>;
 }
 
 /// Auth service handles authentication operations
-pub struct AuthService<U, T, P, J, Tx>
+pub struct AuthService<U, T, B, P, J, Tx>
 where
     U: UserRepository<Tx>,
     T: TokenRepository,
+    B: BranchRepository,
     P: PasswordHash,
     J: JwtManager,
     Tx: Send + Sync,
 {
     user_repo: U,
     token_repo: T,
+    branch_repo: B,
     password_hasher: P,
     jwt_manager: J,
     refresh_token_expiry_days: i64,
+    max_embedded_branches: usize,
+    max_failed_logins: i32,
+    lockout_duration: Duration,
+    password_policy: PasswordPolicy,
+    revoke_tokens_on_password_change: bool,
+    detect_refresh_token_reuse: bool,
+    device_repo: Option<Arc<dyn DeviceRepository>>,
     _phantom: std::marker::PhantomData<Tx>,
 }
 
-impl<U, T, P, J, Tx> AuthService<U, T, P, J, Tx>
+impl<U, T, B, P, J, Tx> AuthService<U, T, B, P, J, Tx>
 where
     U: UserRepository<Tx>,
     T: TokenRepository,
+    B: BranchRepository,
     P: PasswordHash,
     J: JwtManager,
     Tx: Send + Sync,
@@ -57,17 +153,41 @@ where
     /// Creates a new AuthService with default configuration.
     ///
     /// The default refresh token expiry is [`DEFAULT_REFRESH_TOKEN_EXPIRY_DAYS`] (30 days).
-    pub fn new(user_repo: U, token_repo: T, password_hasher: P, jwt_manager: J) -> Self {
+    pub fn new(
+        user_repo: U,
+        token_repo: T,
+        branch_repo: B,
+        password_hasher: P,
+        jwt_manager: J,
+    ) -> Self {
         Self {
             user_repo,
             token_repo,
+            branch_repo,
             password_hasher,
             jwt_manager,
             refresh_token_expiry_days: DEFAULT_REFRESH_TOKEN_EXPIRY_DAYS,
+            max_embedded_branches: DEFAULT_MAX_EMBEDDED_BRANCHES,
+            max_failed_logins: DEFAULT_MAX_FAILED_LOGINS,
+            lockout_duration: Duration::minutes(DEFAULT_LOCKOUT_MINUTES),
+            password_policy: PasswordPolicy::default(),
+            revoke_tokens_on_password_change: true,
+            detect_refresh_token_reuse: true,
+            device_repo: None,
             _phantom: std::marker::PhantomData,
         }
     }
 
+    /// Set the maximum number of branches embedded in the access token.
+    ///
+    /// Users with more memberships get a token without branches and the
+    /// middleware looks them up instead. The default value is
+    /// [`DEFAULT_MAX_EMBEDDED_BRANCHES`] (32).
+    pub fn with_max_embedded_branches(mut self, max: usize) -> Self {
+        self.max_embedded_branches = max;
+        self
+    }
+
     /// Set custom refresh token expiry.
     ///
     /// The default value is [`DEFAULT_REFRESH_TOKEN_EXPIRY_DAYS`] (30 days).
@@ -76,17 +196,162 @@ where
         self
     }
 
-    /// Generate access token and refresh token
+    /// Set how many consecutive bad passwords lock the account, and for how long.
+    ///
+    /// The defaults are [`DEFAULT_MAX_FAILED_LOGINS`] (5) attempts and
+    /// [`DEFAULT_LOCKOUT_MINUTES`] (15 minutes).
+    pub fn with_lockout(mut self, max_failed_logins: i32, duration: Duration) -> Self {
+        self.max_failed_logins = max_failed_logins;
+        self.lockout_duration = duration;
+        self
+    }
+
+    /// Override the policy new passwords are checked against in `change_password`.
+    pub fn with_password_policy(mut self, policy: PasswordPolicy) -> Self {
+        self.password_policy = policy;
+        self
+    }
+
+    /// Whether `change_password` signs the user out everywhere by revoking
+    /// their refresh tokens. On by default.
+    pub fn with_revoke_tokens_on_password_change(mut self, revoke: bool) -> Self {
+        self.revoke_tokens_on_password_change = revoke;
+        self
+    }
+
+    /// Whether refresh rotation keeps used tokens to catch them being replayed.
+    ///
+    /// When on (the default), presenting a refresh token that was already
+    /// exchanged revokes its whole family, the legitimate latest token
+    /// included, since one of the two holders must have stolen it. When off,
+    /// rotated tokens are simply deleted.
+    pub fn with_refresh_token_reuse_detection(mut self, enabled: bool) -> Self {
+        self.detect_refresh_token_reuse = enabled;
+        self
+    }
+
+    /// Enable device login for kiosks.
+    ///
+    /// Without a device repository `login_device` and `check_device` always fail.
+    pub fn with_device_repository(mut self, repo: Arc<dyn DeviceRepository>) -> Self {
+        self.device_repo = Some(repo);
+        self
+    }
+
+    fn device_repo(&self) -> DomainResult<&dyn DeviceRepository> {
+        self.device_repo
+            .as_deref()
+            .ok_or_else(|| Error::Unauthorized("Device login is not enabled".to_string()))
+    }
+
+    /// Look up the device by key, returning why it can't log in if it can't
+    async fn verify_device(
+        &self,
+        ctx: &Context,
+        device_key: &str,
+    ) -> DomainResult<Result<(Device, User), LoginFailure>> {
+        let device_repo = self.device_repo()?;
+        let Some(device) = device_repo
+            .get_by_key_hash(ctx, &hash_device_key(device_key))
+            .await?
+        else {
+            return Ok(Err(LoginFailure::DeviceNotFound));
+        };
+        if device.is_revoked() {
+            return Ok(Err(LoginFailure::DeviceRevoked));
+        }
+        match self.user_repo.get_by_id(ctx, device.user_id).await? {
+            Some(user) if user.is_enabled => Ok(Ok((device, user))),
+            Some(_) => Ok(Err(LoginFailure::UserDisabled)),
+            None => Ok(Err(LoginFailure::UserNotFound)),
+        }
+    }
+
+    /// Look up the user and check the password.
+    ///
+    /// Returns the user when the credentials are accepted, or the typed reason
+    /// they were not. Repository and hashing failures are returned as errors.
+    ///
+    /// Bad passwords are counted on the user record and lock the account once
+    /// the threshold is reached; a successful login clears the counter.
+    pub async fn verify_and_upgrade(
+        &self,
+        ctx: &Context,
+        username: &str,
+        password: &str,
+    ) -> DomainResult<Result<User, LoginFailure>> {
+        let Some(user) = self.user_repo.get_user_by_username(ctx, username).await? else {
+            return Ok(Err(LoginFailure::UserNotFound));
+        };
+
+        let now = Utc::now();
+        if user.locked_until.is_some_and(|until| until > now) {
+            return Ok(Err(LoginFailure::UserLocked));
+        }
+
+        if !self
+            .password_hasher
+            .verify_password(password, &user.password)?
+        {
+            self.user_repo
+                .record_failed_login(
+                    ctx,
+                    user.id,
+                    self.max_failed_logins,
+                    now + self.lockout_duration,
+                )
+                .await?;
+            return Ok(Err(LoginFailure::BadPassword));
+        }
+
+        // Checked after the password so a disabled account is not revealed
+        // to someone guessing passwords
+        if !user.is_enabled {
+            return Ok(Err(LoginFailure::UserDisabled));
+        }
+
+        if user.failed_login_count > 0 || user.locked_until.is_some() {
+            self.user_repo.reset_failed_logins(ctx, user.id).await?;
+        }
+
+        Ok(Ok(user))
+    }
+
+    /// Branches to embed in an access token, unless the list would bloat it
+    fn embeddable(&self, branch_ctx: &BranchContext) -> bool {
+        branch_ctx.branch_ids.len() <= self.max_embedded_branches
+    }
+
+    async fn resolve_branch_context(
+        &self,
+        ctx: &Context,
+        user_id: i64,
+    ) -> DomainResult<BranchContext> {
+        let branches = self
+            .branch_repo
+            .list_branches_for_user(ctx, user_id)
+            .await?;
+        Ok(BranchContext::from_branches(&branches))
+    }
+
+    /// Generate access token and refresh token. The refresh token joins
+    /// `family_id`, which is new on login and carried over on refresh.
     async fn generate_tokens(
         &self,
         ctx: &Context,
         user_id: i64,
         username: &str,
+        family_id: &str,
     ) -> DomainResult<AuthTokens> {
+        // Embed branch memberships unless the list would bloat the token
+        let branch_ctx = self.resolve_branch_context(ctx, user_id).await?;
+        let embedded = self.embeddable(&branch_ctx).then_some(&branch_ctx);
+
         // Generate access token (JWT)
+        let access_expires_at = Utc::now() + self.jwt_manager.token_lifetime();
         let access_token = self
             .jwt_manager
-            .generate_token(user_id, username)
+            .generate_token(user_id, username, embedded)
             .map_err(|e| Error::Internal(e.to_string()))?;
 
         // Generate refresh token (random string)
@@ -102,6 +367,8 @@ where
             user_id,
             token: refresh_token_hash,
             expired_at,
+            family_id: family_id.to_string(),
+            used_at: None,
         };
 
         self.token_repo.save(ctx, &token).await?;
@@ -109,6 +376,8 @@ where
         Ok(AuthTokens {
             access_token,
             refresh_token,
+            access_expires_at,
+            refresh_expires_at: expired_at,
         })
     }
 
@@ -120,6 +389,14 @@ where
         hex::encode(bytes)
     }
 
+    /// Generate a random id for a new token family
+    fn generate_family_id() -> String {
+        let mut rng = rand::thread_rng();
+        let mut bytes = [0u8; 16];
+        rng.fill_bytes(&mut bytes);
+        hex::encode(bytes)
+    }
+
     /// Hash a token using MD5
     fn hash_token(token: &str) -> String {
         let digest = md5::compute(token.as_bytes());
@@ -128,10 +405,11 @@ where
 }
 
 #[async_trait]
-impl<U, T, P, J, Tx> AuthServiceTrait for AuthService<U, T, P, J, Tx>
+impl<U, T, B, P, J, Tx> AuthServiceTrait for AuthService<U, T, B, P, J, Tx>
 where
     U: UserRepository<Tx>,
     T: TokenRepository,
+    B: BranchRepository,
     P: PasswordHash + Send + Sync,
     J: JwtManager + Send + Sync,
     Tx: Send + Sync,
@@ -144,27 +422,18 @@ where
         username: &str,
         password: &str,
     ) -> DomainResult<AuthTokens> {
-        // Get user by username
-        let user = self
-            .user_repo
-            .get_user_by_username(ctx, username)
-            .await?
-            .ok_or(Error::Unauthorized(
-                "Invalid username or password".to_string(),
-            ))?;
-
-        // Verify password
-        let is_valid = self
-            .password_hasher
-            .verify_password(password, &user.password)?;
-        if !is_valid {
-            return Err(Error::Unauthorized(
-                "Invalid username or password".to_string(),
-            ));
-        }
+        let user = match self.verify_and_upgrade(ctx, username, password).await? {
+            Ok(user) => user,
+            Err(reason) => {
+                // The reason stays server-side; callers only see a generic error
+                tracing::warn!(username, reason = reason.as_str(), "Login failed");
+                return Err(login_rejected(reason));
+            }
+        };
 
         // Generate tokens
-        self.generate_tokens(ctx, user.id, &user.username).await
+        self.generate_tokens(ctx, user.id, &user.username, &Self::generate_family_id())
+            .await
     }
 
     /// Login with refresh token
@@ -180,6 +449,24 @@ where
             .await?
             .ok_or_else(|| Error::Unauthorized("Invalid refresh token".to_string()))?;
 
+        // A token is exchanged once. Seeing it again means it was copied, so
+        // neither holder can be trusted with the family
+        if self.detect_refresh_token_reuse
+            && (stored_token.used_at.is_some()
+                || !self.token_repo.mark_used(ctx, stored_token.id).await?)
+        {
+            self.token_repo
+                .delete_family(ctx, &stored_token.family_id)
+                .await?;
+            tracing::warn!(
+                user_id = stored_token.user_id,
+                "Refresh token reuse detected, revoked its family"
+            );
+            return Err(Error::Unauthorized(
+                "Refresh token has already been used".to_string(),
+            ));
+        }
+
         // Check if token is expired
         if stored_token.expired_at < Utc::now() {
             // Delete expired token
@@ -193,32 +480,118 @@ where
             .get_by_id(ctx, stored_token.user_id)
             .await?
             .ok_or_else(|| Error::Unauthorized("User not found".to_string()))?;
+        if !user.is_enabled {
+            return Err(Error::Unauthorized("User is disabled".to_string()));
+        }
 
-        // Delete old refresh token
-        self.token_repo.delete(ctx, stored_token.id).await?;
+        // Without reuse detection the old token is not needed any more
+        if !self.detect_refresh_token_reuse {
+            self.token_repo.delete(ctx, stored_token.id).await?;
+        }
 
         // Generate new tokens
-        self.generate_tokens(ctx, user.id, &user.username).await
+        self.generate_tokens(ctx, user.id, &user.username, &stored_token.family_id)
+            .await
     }
 
     /// Logout - invalidate refresh token
     async fn logout(&self, ctx: &Context, refresh_token: &str) -> DomainResult<()> {
         let token_hash = Self::hash_token(refresh_token);
 
+        // Drop the rotated ancestors of the session too
         if let Some(stored_token) = self.token_repo.get_by_token(ctx, &token_hash).await? {
-            self.token_repo.delete(ctx, stored_token.id).await?;
+            self.token_repo
+                .delete_family(ctx, &stored_token.family_id)
+                .await?;
         }
 
         Ok(())
     }
+
+    async fn logout_all(&self, ctx: &Context, user_id: i64) -> DomainResult<()> {
+        require_self_or_admin(ctx, user_id)?;
+        let revoked = self.token_repo.delete_by_user_id(ctx, user_id).await?;
+        tracing::info!(user_id, revoked, "Revoked all sessions");
+        Ok(())
+    }
+
+    async fn login_device(&self, ctx: &Context, device_key: &str) -> DomainResult<String> {
+        let (device, user) = match self.verify_device(ctx, device_key).await? {
+            Ok(found) => found,
+            Err(reason) => {
+                tracing::warn!(reason = reason.as_str(), "Device login failed");
+                return Err(login_rejected(reason));
+            }
+        };
+
+        let branch_ctx = self.resolve_branch_context(ctx, user.id).await?;
+        let embedded = self.embeddable(&branch_ctx).then_some(&branch_ctx);
+        self.jwt_manager
+            .generate_device_token(user.id, &user.username, device.id, embedded)
+            .map_err(|e| Error::Internal(e.to_string()))
+    }
+
+    async fn check_device(&self, ctx: &Context, device_id: i64) -> DomainResult<()> {
+        match self.device_repo()?.get_by_id(ctx, device_id).await? {
+            Some(device) if !device.is_revoked() => Ok(()),
+            _ => Err(Error::Unauthorized("Device has been revoked".to_string())),
+        }
+    }
+
+    async fn branch_context(&self, ctx: &Context, user_id: i64) -> DomainResult<BranchContext> {
+        self.resolve_branch_context(ctx, user_id).await
+    }
+
+    async fn change_password(
+        &self,
+        ctx: &Context,
+        user_id: i64,
+        old_password: &str,
+        new_password: &str,
+    ) -> DomainResult<()> {
+        require_self_or_admin(ctx, user_id)?;
+
+        let user = self
+            .user_repo
+            .get_by_id(ctx, user_id)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("User with id {} not found", user_id)))?;
+        if !self
+            .password_hasher
+            .verify_password(old_password, &user.password)?
+        {
+            return Err(Error::InvalidCredentials);
+        }
+
+        self.password_policy.validate(new_password)?;
+        let password_hash = self.password_hasher.hash_password(new_password)?;
+        self.user_repo
+            .update_password(ctx, user_id, &password_hash)
+            .await?;
+
+        if self.revoke_tokens_on_password_change {
+            self.token_repo.delete_by_user_id(ctx, user_id).await?;
+        }
+        Ok(())
+    }
+
+    async fn purge_expired_tokens(&self, ctx: &Context) -> DomainResult<u64> {
+        ctx.require_access(None, resource::ADMIN, action::UPDATE)?;
+        let purged = self.token_repo.delete_expired(ctx, Utc::now()).await?;
+        tracing::info!(purged, "Purged expired refresh tokens");
+        Ok(purged)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::crypto::password::PasswordHash;
+    use crate::crypto::{DefaultJwtManager, JwtConfig};
+    use crate::domain::model::branch::{Branch, BranchCreate, BranchUpdate};
     use crate::domain::model::user::{User, UserCreate, UserUpdate};
     use async_trait::async_trait;
+    use std::collections::HashMap;
 
     // Mock User Repository
     struct MockUserRepo {
@@ -327,36 +700,170 @@ mod tests {
         ) -> DomainResult<Vec<crate::domain::model::permission::Permission>> {
             Ok(vec![])
         }
+
+        async fn record_failed_login(
+            &self,
+            _ctx: &Context,
+            _user_id: i64,
+            _max_attempts: i32,
+            _lock_until: chrono::DateTime<Utc>,
+        ) -> DomainResult<()> {
+            Ok(())
+        }
+
+        async fn reset_failed_logins(&self, _ctx: &Context, _user_id: i64) -> DomainResult<()> {
+            Ok(())
+        }
     }
 
     // Mock Token Repository
     struct MockTokenRepo {
-        stored_token: std::sync::Mutex<Option<Token>>,
+        stored_tokens: std::sync::Mutex<Vec<Token>>,
     }
 
     impl MockTokenRepo {
         fn new() -> Self {
             Self {
-                stored_token: std::sync::Mutex::new(None),
+                stored_tokens: std::sync::Mutex::new(Vec::new()),
             }
         }
+
+        fn remove_where(&self, f: impl Fn(&Token) -> bool) -> u64 {
+            let mut stored = self.stored_tokens.lock().unwrap();
+            let before = stored.len();
+            stored.retain(|t| !f(t));
+            (before - stored.len()) as u64
+        }
     }
 
     #[async_trait]
     impl TokenRepository for MockTokenRepo {
         async fn save(&self, _ctx: &Context, token: &Token) -> DomainResult<()> {
-            *self.stored_token.lock().unwrap() = Some(token.clone());
+            let mut stored = self.stored_tokens.lock().unwrap();
+            let id = stored.len() as i64 + 1;
+            stored.push(Token {
+                id,
+                ..token.clone()
+            });
             Ok(())
         }
 
-        async fn delete(&self, _ctx: &Context, _id: i64) -> DomainResult<()> {
-            *self.stored_token.lock().unwrap() = None;
+        async fn delete(&self, _ctx: &Context, id: i64) -> DomainResult<()> {
+            self.remove_where(|t| t.id == id);
             Ok(())
         }
 
         async fn get_by_token(&self, _ctx: &Context, token: &str) -> DomainResult<Option<Token>> {
-            let stored = self.stored_token.lock().unwrap();
-            Ok(stored.as_ref().filter(|t| t.token == token).cloned())
+            let stored = self.stored_tokens.lock().unwrap();
+            Ok(stored.iter().find(|t| t.token == token).cloned())
+        }
+
+        async fn delete_by_user_id(&self, _ctx: &Context, user_id: i64) -> DomainResult<u64> {
+            Ok(self.remove_where(|t| t.user_id == user_id))
+        }
+
+        async fn mark_used(&self, _ctx: &Context, id: i64) -> DomainResult<bool> {
+            let mut stored = self.stored_tokens.lock().unwrap();
+            match stored
+                .iter_mut()
+                .find(|t| t.id == id && t.used_at.is_none())
+            {
+                Some(token) => {
+                    token.used_at = Some(Utc::now());
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        }
+
+        async fn delete_family(&self, _ctx: &Context, family_id: &str) -> DomainResult<u64> {
+            Ok(self.remove_where(|t| t.family_id == family_id))
+        }
+
+        async fn delete_expired(&self, _ctx: &Context, now: DateTime<Utc>) -> DomainResult<u64> {
+            Ok(self.remove_where(|t| t.expired_at < now))
+        }
+    }
+
+    // Mock Branch Repository
+    #[derive(Default)]
+    struct MockBranchRepo {
+        branches: Vec<Branch>,
+    }
+
+    #[async_trait]
+    impl BranchRepository for MockBranchRepo {
+        async fn create(
+            &self,
+            _ctx: &Context,
+            _id: i64,
+            _branch: &BranchCreate,
+        ) -> DomainResult<()> {
+            Ok(())
+        }
+
+        async fn update(
+            &self,
+            _ctx: &Context,
+            _id: i64,
+            _branch: &BranchUpdate,
+        ) -> DomainResult<()> {
+            Ok(())
+        }
+
+        async fn delete(&self, _ctx: &Context, _id: i64) -> DomainResult<()> {
+            Ok(())
+        }
+
+        async fn get_all(&self, _ctx: &Context) -> DomainResult<Vec<Branch>> {
+            Ok(self.branches.clone())
+        }
+
+        async fn get_by_id(&self, _ctx: &Context, id: i64) -> DomainResult<Option<Branch>> {
+            Ok(self.branches.iter().find(|b| b.id == id).cloned())
+        }
+
+        async fn assign_user(
+            &self,
+            _ctx: &Context,
+            _branch_id: i64,
+            _user_id: i64,
+        ) -> DomainResult<()> {
+            Ok(())
+        }
+
+        async fn unassign_user(
+            &self,
+            _ctx: &Context,
+            _branch_id: i64,
+            _user_id: i64,
+        ) -> DomainResult<()> {
+            Ok(())
+        }
+
+        async fn list_branches_for_user(
+            &self,
+            _ctx: &Context,
+            _user_id: i64,
+        ) -> DomainResult<Vec<Branch>> {
+            Ok(self.branches.clone())
+        }
+    }
+
+    fn create_test_branch(id: i64, is_main: bool) -> Branch {
+        Branch {
+            id,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            deleted_at: None,
+            is_deleted: false,
+            is_main,
+            name: format!("Branch {}", id),
+            code: format!("B{}", id),
+            address: None,
+            phone: None,
+            npwp: None,
+            image: None,
         }
     }
 
@@ -379,19 +886,47 @@ mod tests {
     struct MockJwtManager;
 
     impl JwtManager for MockJwtManager {
-        fn generate_token(&self, user_id: i64, username: &str) -> crate::crypto::JwtResult<String> {
+        fn generate_token(
+            &self,
+            user_id: i64,
+            username: &str,
+            _branches: Option<&BranchContext>,
+        ) -> crate::crypto::JwtResult<String> {
             Ok(format!("jwt_{}_{}", user_id, username))
         }
 
+        fn generate_device_token(
+            &self,
+            user_id: i64,
+            username: &str,
+            device_id: i64,
+            _branches: Option<&BranchContext>,
+        ) -> crate::crypto::JwtResult<String> {
+            Ok(format!("jwt_{}_{}_device_{}", user_id, username, device_id))
+        }
+
         fn validate_token(&self, _token: &str) -> crate::crypto::JwtResult<crate::crypto::Claims> {
             Ok(crate::crypto::Claims {
                 sub: "1".to_string(),
                 exp: 0,
                 iat: 0,
+                iss: None,
+                aud: None,
                 user_id: 1,
                 username: "test".to_string(),
+                branch_ids: None,
+                default_branch_id: None,
+                device_id: None,
             })
         }
+
+        fn token_lifetime(&self) -> Duration {
+            Duration::minutes(15)
+        }
+
+        fn device_token_lifetime(&self) -> Duration {
+            Duration::minutes(5)
+        }
     }
 
     fn create_test_user(password_hash: &str) -> User {
@@ -409,6 +944,9 @@ mod tests {
             pin: None,
             address: None,
             phone: None,
+            is_enabled: true,
+            failed_login_count: 0,
+            locked_until: None,
             permissions: None,
         }
     }
@@ -423,7 +961,13 @@ mod tests {
         };
         let jwt_manager = MockJwtManager;
 
-        let service = AuthService::new(user_repo, token_repo, password_hasher, jwt_manager);
+        let service = AuthService::new(
+            user_repo,
+            token_repo,
+            MockBranchRepo::default(),
+            password_hasher,
+            jwt_manager,
+        );
 
         let ctx = Context::new();
         let result = service.login(&ctx, "testuser", "password123").await;
@@ -434,6 +978,41 @@ mod tests {
         assert!(!tokens.refresh_token.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_login_returns_expiry_from_config() {
+        use crate::crypto::{DefaultJwtManager, JwtConfig};
+
+        let user = create_test_user("hashed_password123");
+        let service = AuthService::new(
+            MockUserRepo { user: Some(user) },
+            MockTokenRepo::new(),
+            MockBranchRepo::default(),
+            MockPasswordHasher {
+                valid_password: "password123".to_string(),
+            },
+            DefaultJwtManager::new(JwtConfig::new("test-secret", 30)),
+        )
+        .with_refresh_token_expiry_days(7);
+
+        let before = Utc::now();
+        let tokens = service
+            .login(&Context::new(), "testuser", "password123")
+            .await
+            .unwrap();
+        let after = Utc::now();
+
+        assert!(tokens.access_expires_at >= before + Duration::minutes(30));
+        assert!(tokens.access_expires_at <= after + Duration::minutes(30));
+        assert!(tokens.refresh_expires_at >= before + Duration::days(7));
+        assert!(tokens.refresh_expires_at <= after + Duration::days(7));
+
+        // The JWT's own exp agrees, to the second
+        let claims = DefaultJwtManager::new(JwtConfig::new("test-secret", 30))
+            .validate_token(&tokens.access_token)
+            .unwrap();
+        assert!((claims.exp - tokens.access_expires_at.timestamp()).abs() <= 1);
+    }
+
     #[tokio::test]
     async fn test_login_invalid_username() {
         let user_repo = MockUserRepo { user: None };
@@ -443,12 +1022,18 @@ mod tests {
         };
         let jwt_manager = MockJwtManager;
 
-        let service = AuthService::new(user_repo, token_repo, password_hasher, jwt_manager);
+        let service = AuthService::new(
+            user_repo,
+            token_repo,
+            MockBranchRepo::default(),
+            password_hasher,
+            jwt_manager,
+        );
 
         let ctx = Context::new();
         let result = service.login(&ctx, "nonexistent", "password123").await;
 
-        assert!(matches!(result, Err(Error::Unauthorized(_))));
+        assert!(matches!(result, Err(Error::InvalidCredentials)));
     }
 
     #[tokio::test]
@@ -461,11 +1046,117 @@ mod tests {
         };
         let jwt_manager = MockJwtManager;
 
-        let service = AuthService::new(user_repo, token_repo, password_hasher, jwt_manager);
+        let service = AuthService::new(
+            user_repo,
+            token_repo,
+            MockBranchRepo::default(),
+            password_hasher,
+            jwt_manager,
+        );
 
         let ctx = Context::new();
         let result = service.login(&ctx, "testuser", "wrong_password").await;
 
+        assert!(matches!(result, Err(Error::InvalidCredentials)));
+    }
+
+    fn create_login_service(
+        user: Option<User>,
+    ) -> AuthService<
+        MockUserRepo,
+        MockTokenRepo,
+        MockBranchRepo,
+        MockPasswordHasher,
+        MockJwtManager,
+        (),
+    > {
+        AuthService::new(
+            MockUserRepo { user },
+            MockTokenRepo::new(),
+            MockBranchRepo::default(),
+            MockPasswordHasher {
+                valid_password: "password123".to_string(),
+            },
+            MockJwtManager,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_login_disabled_user() {
+        let mut user = create_test_user("hashed_password123");
+        user.is_enabled = false;
+        let service = create_login_service(Some(user));
+
+        let ctx = Context::new();
+        let result = service.login(&ctx, "testuser", "password123").await;
+
+        assert!(matches!(result, Err(Error::InvalidCredentials)));
+    }
+
+    #[tokio::test]
+    async fn test_login_failure_reasons_are_internal_only() {
+        let mut disabled = create_test_user("hashed_password123");
+        disabled.is_enabled = false;
+        let cases = [
+            (None, "password123", LoginFailure::UserNotFound),
+            (
+                Some(create_test_user("hashed_password123")),
+                "wrong_password",
+                LoginFailure::BadPassword,
+            ),
+            (Some(disabled), "password123", LoginFailure::UserDisabled),
+        ];
+
+        let ctx = Context::new();
+        for (user, password, expected) in cases {
+            let service = create_login_service(user);
+
+            let reason = service
+                .verify_and_upgrade(&ctx, "testuser", password)
+                .await
+                .unwrap()
+                .unwrap_err();
+            assert_eq!(reason, expected);
+
+            let result = service.login(&ctx, "testuser", password).await;
+            assert!(matches!(result, Err(Error::InvalidCredentials)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_login_locked_user() {
+        let mut user = create_test_user("hashed_password123");
+        user.failed_login_count = 5;
+        user.locked_until = Some(Utc::now() + Duration::minutes(5));
+        let service = create_login_service(Some(user));
+
+        let ctx = Context::new();
+        let result = service.login(&ctx, "testuser", "password123").await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_disabled_user() {
+        let user = create_test_user("hashed_password123");
+        let service = create_login_service(Some(user));
+
+        let ctx = Context::new();
+        let tokens = service
+            .login(&ctx, "testuser", "password123")
+            .await
+            .unwrap();
+
+        let mut disabled = create_test_user("hashed_password123");
+        disabled.is_enabled = false;
+        let service = AuthService {
+            user_repo: MockUserRepo {
+                user: Some(disabled),
+            },
+            ..service
+        };
+        let result = service.refresh(&ctx, &tokens.refresh_token).await;
+
         assert!(matches!(result, Err(Error::Unauthorized(_))));
     }
 
@@ -481,7 +1172,13 @@ mod tests {
         };
         let jwt_manager = MockJwtManager;
 
-        let service = AuthService::new(user_repo, token_repo, password_hasher, jwt_manager);
+        let service = AuthService::new(
+            user_repo,
+            token_repo,
+            MockBranchRepo::default(),
+            password_hasher,
+            jwt_manager,
+        );
 
         let ctx = Context::new();
 
@@ -506,7 +1203,13 @@ mod tests {
         };
         let jwt_manager = MockJwtManager;
 
-        let service = AuthService::new(user_repo, token_repo, password_hasher, jwt_manager);
+        let service = AuthService::new(
+            user_repo,
+            token_repo,
+            MockBranchRepo::default(),
+            password_hasher,
+            jwt_manager,
+        );
 
         let ctx = Context::new();
         let result = service.refresh(&ctx, "invalid_refresh_token").await;
@@ -524,7 +1227,13 @@ mod tests {
         };
         let jwt_manager = MockJwtManager;
 
-        let service = AuthService::new(user_repo, token_repo, password_hasher, jwt_manager);
+        let service = AuthService::new(
+            user_repo,
+            token_repo,
+            MockBranchRepo::default(),
+            password_hasher,
+            jwt_manager,
+        );
 
         let ctx = Context::new();
 
@@ -540,12 +1249,281 @@ mod tests {
         assert!(matches!(refresh_result, Err(Error::Unauthorized(_))));
     }
 
+    fn create_password_auth_service() -> AuthService<
+        MockUserRepo,
+        MockTokenRepo,
+        MockBranchRepo,
+        MockPasswordHasher,
+        MockJwtManager,
+        (),
+    > {
+        let user = create_test_user("hashed_password");
+        AuthService::new(
+            MockUserRepo { user: Some(user) },
+            MockTokenRepo::new(),
+            MockBranchRepo::default(),
+            MockPasswordHasher {
+                valid_password: "password".to_string(),
+            },
+            MockJwtManager,
+        )
+    }
+
+    fn user_context(user_id: i64) -> Context {
+        Context::new_with_all(Some(user_id), HashMap::new(), HashMap::new())
+    }
+
+    #[tokio::test]
+    async fn test_refresh_token_reuse_revokes_family() {
+        let service = create_password_auth_service();
+        let ctx = Context::new();
+        let first = service.login(&ctx, "testuser", "password").await.unwrap();
+        let other_session = service.login(&ctx, "testuser", "password").await.unwrap();
+
+        let second = service.refresh(&ctx, &first.refresh_token).await.unwrap();
+
+        // Replaying the rotated token burns the whole family
+        let result = service.refresh(&ctx, &first.refresh_token).await;
+        assert!(matches!(result, Err(Error::Unauthorized(_))));
+        let result = service.refresh(&ctx, &second.refresh_token).await;
+        assert!(matches!(result, Err(Error::Unauthorized(_))));
+
+        // Sessions from other logins are separate families
+        assert!(
+            service
+                .refresh(&ctx, &other_session.refresh_token)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refresh_without_reuse_detection_deletes_rotated_token() {
+        let service = create_password_auth_service().with_refresh_token_reuse_detection(false);
+        let ctx = Context::new();
+        let first = service.login(&ctx, "testuser", "password").await.unwrap();
+        let second = service.refresh(&ctx, &first.refresh_token).await.unwrap();
+
+        let result = service.refresh(&ctx, &first.refresh_token).await;
+        assert!(matches!(result, Err(Error::Unauthorized(_))));
+        assert!(service.refresh(&ctx, &second.refresh_token).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_logout_all_requires_self_or_admin() {
+        let service = create_password_auth_service();
+        let tokens = service
+            .login(&Context::new(), "testuser", "password")
+            .await
+            .unwrap();
+
+        let result = service.logout_all(&user_context(2), 1).await;
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+        assert!(
+            service
+                .refresh(&Context::new(), &tokens.refresh_token)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_change_password_revokes_refresh_tokens() {
+        let service = create_password_auth_service();
+        let tokens = service
+            .login(&Context::new(), "testuser", "password")
+            .await
+            .unwrap();
+
+        let ctx = user_context(1);
+        service
+            .change_password(&ctx, 1, "password", "new-password-1")
+            .await
+            .expect("Password change should succeed");
+
+        let result = service.refresh(&ctx, &tokens.refresh_token).await;
+        assert!(matches!(result, Err(Error::Unauthorized(_))));
+    }
+
+    #[tokio::test]
+    async fn test_change_password_can_keep_sessions() {
+        let service = create_password_auth_service().with_revoke_tokens_on_password_change(false);
+        let tokens = service
+            .login(&Context::new(), "testuser", "password")
+            .await
+            .unwrap();
+
+        let ctx = user_context(1);
+        service
+            .change_password(&ctx, 1, "password", "new-password-1")
+            .await
+            .expect("Password change should succeed");
+
+        assert!(service.refresh(&ctx, &tokens.refresh_token).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_change_password_wrong_old_password() {
+        let service = create_password_auth_service();
+        let tokens = service
+            .login(&Context::new(), "testuser", "password")
+            .await
+            .unwrap();
+
+        let ctx = user_context(1);
+        let result = service
+            .change_password(&ctx, 1, "wrong_password", "new-password-1")
+            .await;
+        assert!(matches!(result, Err(Error::InvalidCredentials)));
+
+        // Sessions survive a rejected attempt
+        assert!(service.refresh(&ctx, &tokens.refresh_token).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_change_password_checks_policy() {
+        let service = create_password_auth_service();
+
+        let result = service
+            .change_password(&user_context(1), 1, "password", "short1")
+            .await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_change_password_of_other_user_requires_admin() {
+        let service = create_password_auth_service();
+
+        let result = service
+            .change_password(&user_context(2), 1, "password", "new-password-1")
+            .await;
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+
+        let admin = Context::new_with_all(
+            Some(2),
+            HashMap::from([((resource::ADMIN, None), action::UPDATE)]),
+            HashMap::new(),
+        );
+        service
+            .change_password(&admin, 1, "password", "new-password-1")
+            .await
+            .expect("Admin should be able to change the password");
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired_tokens() {
+        let service = create_password_auth_service();
+        let tokens = service
+            .login(&Context::new(), "testuser", "password")
+            .await
+            .unwrap();
+        service
+            .token_repo
+            .save(
+                &Context::new(),
+                &Token {
+                    id: 0,
+                    user_id: 1,
+                    expired_at: Utc::now() - Duration::hours(1),
+                    token: "expired".to_string(),
+                    family_id: "expired_family".to_string(),
+                    used_at: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let result = service.purge_expired_tokens(&user_context(1)).await;
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+
+        let admin = Context::new_with_all(
+            Some(2),
+            HashMap::from([((resource::ADMIN, None), action::UPDATE)]),
+            HashMap::new(),
+        );
+        assert_eq!(service.purge_expired_tokens(&admin).await.unwrap(), 1);
+        assert_eq!(service.purge_expired_tokens(&admin).await.unwrap(), 0);
+        // The live session survives the sweep
+        assert!(
+            service
+                .refresh(&Context::new(), &tokens.refresh_token)
+                .await
+                .is_ok()
+        );
+    }
+
+    fn create_branch_auth_service(
+        branches: Vec<Branch>,
+    ) -> AuthService<
+        MockUserRepo,
+        MockTokenRepo,
+        MockBranchRepo,
+        MockPasswordHasher,
+        DefaultJwtManager,
+        (),
+    > {
+        let user = create_test_user("hashed_password");
+        AuthService::new(
+            MockUserRepo { user: Some(user) },
+            MockTokenRepo::new(),
+            MockBranchRepo { branches },
+            MockPasswordHasher {
+                valid_password: "password".to_string(),
+            },
+            DefaultJwtManager::new(JwtConfig::new("test_secret_key_for_testing_only", 60)),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_login_embeds_branches_in_claims() {
+        let service = create_branch_auth_service(vec![
+            create_test_branch(10, false),
+            create_test_branch(20, true),
+        ]);
+
+        let ctx = Context::new();
+        let tokens = service.login(&ctx, "testuser", "password").await.unwrap();
+        let claims = service
+            .jwt_manager
+            .validate_token(&tokens.access_token)
+            .unwrap();
+
+        assert_eq!(claims.branch_ids, Some(vec![10, 20]));
+        assert_eq!(claims.default_branch_id, Some(20));
+    }
+
+    #[tokio::test]
+    async fn test_login_skips_branches_over_cap() {
+        let service = create_branch_auth_service(vec![
+            create_test_branch(10, false),
+            create_test_branch(20, false),
+            create_test_branch(30, false),
+        ])
+        .with_max_embedded_branches(2);
+
+        let ctx = Context::new();
+        let tokens = service.login(&ctx, "testuser", "password").await.unwrap();
+        let claims = service
+            .jwt_manager
+            .validate_token(&tokens.access_token)
+            .unwrap();
+
+        assert_eq!(claims.branch_ids, None);
+        assert_eq!(claims.default_branch_id, None);
+
+        // The middleware falls back to this lookup
+        let branch_ctx = service.branch_context(&ctx, 1).await.unwrap();
+        assert_eq!(branch_ctx.branch_ids, vec![10, 20, 30]);
+        assert_eq!(branch_ctx.default_branch_id, Some(10));
+    }
+
     #[test]
     fn test_hash_token() {
         let token = "test_token";
         let hash1 = AuthService::<
             MockUserRepo,
             MockTokenRepo,
+            MockBranchRepo,
             MockPasswordHasher,
             MockJwtManager,
             (),
@@ -553,6 +1531,7 @@ mod tests {
         let hash2 = AuthService::<
             MockUserRepo,
             MockTokenRepo,
+            MockBranchRepo,
             MockPasswordHasher,
             MockJwtManager,
             (),
@@ -2,6 +2,7 @@ use async_trait::async_trait;
 
 use crate::domain::Context;
 use crate::domain::DomainResult;
+use crate::domain::Error;
 use crate::domain::model::branch::{Branch, BranchCreate, BranchUpdate};
 use crate::domain::model::permission::action;
 use crate::domain::model::permission::resource;
@@ -15,6 +16,13 @@ pub trait BranchServiceTrait: Send + Sync {
     async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()>;
     async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Branch>>;
     async fn get_all(&self, ctx: &Context) -> DomainResult<Vec<Branch>>;
+    async fn assign_user(&self, ctx: &Context, branch_id: i64, user_id: i64) -> DomainResult<()>;
+    async fn unassign_user(&self, ctx: &Context, branch_id: i64, user_id: i64) -> DomainResult<()>;
+    async fn list_branches_for_user(
+        &self,
+        ctx: &Context,
+        user_id: i64,
+    ) -> DomainResult<Vec<Branch>>;
 }
 
 pub struct BranchService<R, I> {
@@ -59,6 +67,34 @@ impl<R: BranchRepository, I: IdGenerator> BranchServiceTrait for BranchService<R
         ctx.require_access(None, resource::BRANCH, action::READ)?;
         self.repository.get_all(ctx).await
     }
+
+    async fn assign_user(&self, ctx: &Context, branch_id: i64, user_id: i64) -> DomainResult<()> {
+        ctx.require_access(Some(branch_id), resource::BRANCH, action::UPDATE)?;
+        if self.repository.get_by_id(ctx, branch_id).await?.is_none() {
+            return Err(Error::NotFound(format!(
+                "Branch with id {} not found",
+                branch_id
+            )));
+        }
+        self.repository.assign_user(ctx, branch_id, user_id).await
+    }
+
+    async fn unassign_user(&self, ctx: &Context, branch_id: i64, user_id: i64) -> DomainResult<()> {
+        ctx.require_access(Some(branch_id), resource::BRANCH, action::UPDATE)?;
+        self.repository.unassign_user(ctx, branch_id, user_id).await
+    }
+
+    async fn list_branches_for_user(
+        &self,
+        ctx: &Context,
+        user_id: i64,
+    ) -> DomainResult<Vec<Branch>> {
+        // Users may always see their own memberships
+        if ctx.user_id() != Some(user_id) {
+            ctx.require_access(None, resource::BRANCH, action::READ)?;
+        }
+        self.repository.list_branches_for_user(ctx, user_id).await
+    }
 }
 
 #[cfg(test)]
@@ -81,6 +117,9 @@ mod tests {
             async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()>;
             async fn get_all(&self, ctx: &Context) -> DomainResult<Vec<Branch>>;
             async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Branch>>;
+            async fn assign_user(&self, ctx: &Context, branch_id: i64, user_id: i64) -> DomainResult<()>;
+            async fn unassign_user(&self, ctx: &Context, branch_id: i64, user_id: i64) -> DomainResult<()>;
+            async fn list_branches_for_user(&self, ctx: &Context, user_id: i64) -> DomainResult<Vec<Branch>>;
         }
     }
     /// Creates a test context with full permissions for BRANCH resource
@@ -356,4 +395,182 @@ mod tests {
 
         assert!(matches!(result, Err(Error::Database(msg)) if msg == "DB Error"));
     }
+
+    fn sample_branch(id: i64) -> Branch {
+        Branch {
+            id,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            deleted_at: None,
+            is_deleted: false,
+            is_main: false,
+            name: "Test Branch".to_string(),
+            code: "TEST".to_string(),
+            address: None,
+            phone: None,
+            npwp: None,
+            image: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_assign_user_success() {
+        let mut mock_repo = MockBranchRepo::new();
+        let mock_id_gen = create_mock_id_gen(1);
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_by_id()
+            .with(mockall::predicate::always(), mockall::predicate::eq(1))
+            .times(1)
+            .returning(|_, id| Ok(Some(sample_branch(id))));
+        mock_repo
+            .expect_assign_user()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(1),
+                mockall::predicate::eq(10),
+            )
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = BranchService::new(mock_repo, mock_id_gen);
+        let result = service.assign_user(&ctx, 1, 10).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_assign_user_branch_not_found() {
+        let mut mock_repo = MockBranchRepo::new();
+        let mock_id_gen = create_mock_id_gen(1);
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_by_id()
+            .times(1)
+            .returning(|_, _| Ok(None));
+        mock_repo.expect_assign_user().times(0);
+
+        let service = BranchService::new(mock_repo, mock_id_gen);
+        let result = service.assign_user(&ctx, 1, 10).await;
+
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_assign_user_forbidden() {
+        let mut mock_repo = MockBranchRepo::new();
+        let mock_id_gen = create_mock_id_gen(1);
+        let mut permissions = HashMap::new();
+        permissions.insert((resource::BRANCH, None), action::READ);
+        let ctx = Context::new_with_all(None, permissions, HashMap::new());
+
+        mock_repo.expect_get_by_id().times(0);
+        mock_repo.expect_assign_user().times(0);
+
+        let service = BranchService::new(mock_repo, mock_id_gen);
+        let result = service.assign_user(&ctx, 1, 10).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_assign_user_branch_scoped_permission() {
+        let mut mock_repo = MockBranchRepo::new();
+        let mock_id_gen = create_mock_id_gen(1);
+        let mut permissions = HashMap::new();
+        permissions.insert((resource::BRANCH, Some(1)), action::UPDATE);
+        let ctx = Context::new_with_all(None, permissions, HashMap::new());
+
+        mock_repo
+            .expect_get_by_id()
+            .returning(|_, id| Ok(Some(sample_branch(id))));
+        mock_repo
+            .expect_assign_user()
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = BranchService::new(mock_repo, mock_id_gen);
+
+        assert!(service.assign_user(&ctx, 1, 10).await.is_ok());
+        assert!(matches!(
+            service.assign_user(&ctx, 2, 10).await,
+            Err(Error::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unassign_user_success() {
+        let mut mock_repo = MockBranchRepo::new();
+        let mock_id_gen = create_mock_id_gen(1);
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_unassign_user()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(1),
+                mockall::predicate::eq(10),
+            )
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = BranchService::new(mock_repo, mock_id_gen);
+        let result = service.unassign_user(&ctx, 1, 10).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unassign_user_not_assigned() {
+        let mut mock_repo = MockBranchRepo::new();
+        let mock_id_gen = create_mock_id_gen(1);
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_unassign_user()
+            .times(1)
+            .returning(|_, _, _| Err(Error::NotFound("not assigned".to_string())));
+
+        let service = BranchService::new(mock_repo, mock_id_gen);
+        let result = service.unassign_user(&ctx, 1, 10).await;
+
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_branches_for_self_without_permission() {
+        let mut mock_repo = MockBranchRepo::new();
+        let mock_id_gen = create_mock_id_gen(1);
+        let ctx = Context::new_with_all(Some(10), HashMap::new(), HashMap::new());
+
+        mock_repo
+            .expect_list_branches_for_user()
+            .with(mockall::predicate::always(), mockall::predicate::eq(10))
+            .times(1)
+            .returning(|_, _| Ok(vec![sample_branch(1)]));
+
+        let service = BranchService::new(mock_repo, mock_id_gen);
+        let branches = service
+            .list_branches_for_user(&ctx, 10)
+            .await
+            .expect("Failed to list branches");
+
+        assert_eq!(branches.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_list_branches_for_other_user_forbidden() {
+        let mut mock_repo = MockBranchRepo::new();
+        let mock_id_gen = create_mock_id_gen(1);
+        let ctx = Context::new_with_all(Some(10), HashMap::new(), HashMap::new());
+
+        mock_repo.expect_list_branches_for_user().times(0);
+
+        let service = BranchService::new(mock_repo, mock_id_gen);
+        let result = service.list_branches_for_user(&ctx, 11).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
 }
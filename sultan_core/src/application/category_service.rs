@@ -1,9 +1,10 @@
 use crate::{
     domain::{
-        Context, DomainResult,
+        BranchScope, Context, DomainResult,
         model::{
             category::{Category, CategoryCreate, CategoryUpdate},
             permission::{action, resource},
+            validate::Validate,
         },
     },
     snowflake::IdGenerator,
@@ -16,8 +17,24 @@ pub trait CategoryServiceTrait: Send + Sync {
     async fn create(&self, ctx: &Context, category: &CategoryCreate) -> DomainResult<i64>;
     async fn update(&self, ctx: &Context, id: i64, category: &CategoryUpdate) -> DomainResult<()>;
     async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()>;
-    async fn get_all(&self, ctx: &Context) -> DomainResult<Vec<Category>>;
-    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Category>>;
+    /// With a branch, only the categories assigned to it. An omitted branch
+    /// follows [`Context::resolve_branch`]: the caller's only branch, or
+    /// else every branch it can read, merged into one tree.
+    async fn get_all(&self, ctx: &Context, branch_id: Option<i64>) -> DomainResult<Vec<Category>>;
+    /// Number of categories in the tree `get_all` returns
+    async fn count(&self, ctx: &Context, branch_id: Option<i64>) -> DomainResult<u64>;
+    async fn get_by_id(
+        &self,
+        ctx: &Context,
+        id: i64,
+        branch_id: Option<i64>,
+    ) -> DomainResult<Option<Category>>;
+    /// Make a category visible in a branch. Needs category update access in
+    /// that branch. Assigning it again is a no-op.
+    async fn assign_to_branch(&self, ctx: &Context, id: i64, branch_id: i64) -> DomainResult<()>;
+    /// Hide a category from a branch. Needs category update access in that
+    /// branch.
+    async fn remove_from_branch(&self, ctx: &Context, id: i64, branch_id: i64) -> DomainResult<()>;
 }
 
 pub struct CategoryService<R, I> {
@@ -33,6 +50,36 @@ where
     pub fn new(repo: R, id_generator: I) -> Self {
         Self { repo, id_generator }
     }
+
+    /// Categories of several branches, merged into one tree
+    async fn get_all_in(&self, ctx: &Context, branch_ids: &[i64]) -> DomainResult<Vec<Category>> {
+        let mut tree = Vec::new();
+        for &branch_id in branch_ids {
+            merge_category_trees(&mut tree, self.repo.get_all(ctx, Some(branch_id)).await?);
+        }
+        Ok(tree)
+    }
+}
+
+/// Add `other` to `tree`. A category present in both keeps the first copy,
+/// with the children of both merged.
+fn merge_category_trees(tree: &mut Vec<Category>, other: Vec<Category>) {
+    for category in other {
+        match tree.iter_mut().find(|c| c.id == category.id) {
+            Some(existing) => {
+                if let Some(children) = category.children {
+                    merge_category_trees(existing.children.get_or_insert_with(Vec::new), children);
+                }
+            }
+            None => tree.push(category),
+        }
+    }
+}
+
+fn count_categories(tree: &[Category]) -> u64 {
+    tree.iter()
+        .map(|c| 1 + c.children.as_deref().map_or(0, count_categories))
+        .sum()
 }
 
 #[async_trait]
@@ -43,6 +90,7 @@ where
 {
     async fn create(&self, ctx: &Context, category: &CategoryCreate) -> DomainResult<i64> {
         ctx.require_access(None, resource::CATEGORY, action::CREATE)?;
+        category.validate()?;
         let id = self.id_generator.generate()?;
         self.repo.create(ctx, id, category).await?;
         Ok(id)
@@ -50,6 +98,7 @@ where
 
     async fn update(&self, ctx: &Context, id: i64, category: &CategoryUpdate) -> DomainResult<()> {
         ctx.require_access(None, resource::CATEGORY, action::UPDATE)?;
+        category.validate()?;
         self.repo.update(ctx, id, category).await
     }
 
@@ -58,14 +107,54 @@ where
         self.repo.delete(ctx, id).await
     }
 
-    async fn get_all(&self, ctx: &Context) -> DomainResult<Vec<Category>> {
-        ctx.require_access(None, resource::CATEGORY, action::READ)?;
-        self.repo.get_all(ctx).await
+    async fn get_all(&self, ctx: &Context, branch_id: Option<i64>) -> DomainResult<Vec<Category>> {
+        match ctx.branch_scope(branch_id, resource::CATEGORY, action::READ)? {
+            BranchScope::Single(branch_id) => self.repo.get_all(ctx, branch_id).await,
+            BranchScope::Branches(branch_ids) => self.get_all_in(ctx, &branch_ids).await,
+        }
+    }
+
+    async fn count(&self, ctx: &Context, branch_id: Option<i64>) -> DomainResult<u64> {
+        match ctx.branch_scope(branch_id, resource::CATEGORY, action::READ)? {
+            BranchScope::Single(branch_id) => {
+                ctx.cancellable(self.repo.count(ctx, branch_id)).await
+            }
+            // Counted from the merged tree so shared categories count once
+            BranchScope::Branches(branch_ids) => {
+                let tree = ctx.cancellable(self.get_all_in(ctx, &branch_ids)).await?;
+                Ok(count_categories(&tree))
+            }
+        }
+    }
+
+    async fn get_by_id(
+        &self,
+        ctx: &Context,
+        id: i64,
+        branch_id: Option<i64>,
+    ) -> DomainResult<Option<Category>> {
+        match ctx.branch_scope(branch_id, resource::CATEGORY, action::READ)? {
+            BranchScope::Single(branch_id) => self.repo.get_by_id(ctx, id, branch_id).await,
+            BranchScope::Branches(branch_ids) => {
+                let mut found = Vec::new();
+                for branch_id in branch_ids {
+                    if let Some(category) = self.repo.get_by_id(ctx, id, Some(branch_id)).await? {
+                        merge_category_trees(&mut found, vec![category]);
+                    }
+                }
+                Ok(found.pop())
+            }
+        }
+    }
+
+    async fn assign_to_branch(&self, ctx: &Context, id: i64, branch_id: i64) -> DomainResult<()> {
+        ctx.require_access(Some(branch_id), resource::CATEGORY, action::UPDATE)?;
+        self.repo.assign_to_branch(ctx, id, branch_id).await
     }
 
-    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Category>> {
-        ctx.require_access(None, resource::CATEGORY, action::READ)?;
-        self.repo.get_by_id(ctx, id).await
+    async fn remove_from_branch(&self, ctx: &Context, id: i64, branch_id: i64) -> DomainResult<()> {
+        ctx.require_access(Some(branch_id), resource::CATEGORY, action::UPDATE)?;
+        self.repo.remove_from_branch(ctx, id, branch_id).await
     }
 }
 
@@ -73,24 +162,11 @@ where
 mod tests {
     use super::*;
     use crate::application::create_mock_id_gen;
-    use crate::domain::Error;
     use crate::domain::model::Update;
-    use async_trait::async_trait;
-    use mockall::mock;
+    use crate::domain::{BranchContext, Error};
+    use crate::testing::mocks::MockCategoryRepo;
     use std::collections::HashMap;
 
-    mock! {
-        pub CategoryRepo {}
-        #[async_trait]
-        impl CategoryRepository for CategoryRepo {
-            async fn create(&self, ctx: &Context, id: i64, category: &CategoryCreate) -> DomainResult<()>;
-            async fn update(&self, ctx: &Context, id: i64, category: &CategoryUpdate) -> DomainResult<()>;
-            async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()>;
-            async fn get_all(&self, ctx: &Context) -> DomainResult<Vec<Category>>;
-            async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Category>>;
-        }
-    }
-
     /// Creates a test context with full permissions for CATEGORY resource
     fn create_test_context() -> Context {
         let mut permissions = HashMap::new();
@@ -317,10 +393,10 @@ mod tests {
         mock_repo
             .expect_get_all()
             .times(1)
-            .returning(move |_| Ok(categories_clone.clone()));
+            .returning(move |_, _| Ok(categories_clone.clone()));
 
         let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
-        let result = service.get_all(&ctx).await;
+        let result = service.get_all(&ctx, None).await;
 
         assert!(result.is_ok());
         let cats = result.unwrap();
@@ -336,10 +412,10 @@ mod tests {
         mock_repo
             .expect_get_all()
             .times(1)
-            .returning(|_| Ok(vec![]));
+            .returning(|_, _| Ok(vec![]));
 
         let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
-        let result = service.get_all(&ctx).await;
+        let result = service.get_all(&ctx, None).await;
 
         assert!(result.is_ok());
         assert!(result.unwrap().is_empty());
@@ -353,14 +429,47 @@ mod tests {
         mock_repo
             .expect_get_all()
             .times(1)
-            .returning(|_| Err(Error::Database("DB Error".to_string())));
+            .returning(|_, _| Err(Error::Database("DB Error".to_string())));
 
         let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
-        let result = service.get_all(&ctx).await;
+        let result = service.get_all(&ctx, None).await;
 
         assert!(matches!(result, Err(Error::Database(_))));
     }
 
+    #[tokio::test]
+    async fn test_count_categories_for_branch() {
+        let mut mock_repo = MockCategoryRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_count()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(Some(5)),
+            )
+            .times(1)
+            .returning(|_, _| Ok(4));
+
+        let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
+        let result = service.count(&ctx, Some(5)).await;
+
+        assert_eq!(result.unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_count_categories_no_permission() {
+        let mut mock_repo = MockCategoryRepo::new();
+        let ctx = create_no_permission_context();
+
+        mock_repo.expect_count().never();
+
+        let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
+        let result = service.count(&ctx, None).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
     // ==================== Get By ID Tests ====================
 
     #[tokio::test]
@@ -373,12 +482,16 @@ mod tests {
 
         mock_repo
             .expect_get_by_id()
-            .with(mockall::predicate::always(), mockall::predicate::eq(1))
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(1),
+                mockall::predicate::eq(None),
+            )
             .times(1)
-            .returning(move |_, _| Ok(Some(category_clone.clone())));
+            .returning(move |_, _, _| Ok(Some(category_clone.clone())));
 
         let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
-        let result = service.get_by_id(&ctx, 1).await;
+        let result = service.get_by_id(&ctx, 1, None).await;
 
         assert!(result.is_ok());
         let category = result.unwrap();
@@ -394,10 +507,10 @@ mod tests {
         mock_repo
             .expect_get_by_id()
             .times(1)
-            .returning(|_, _| Ok(None));
+            .returning(|_, _, _| Ok(None));
 
         let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
-        let result = service.get_by_id(&ctx, 999).await;
+        let result = service.get_by_id(&ctx, 999, None).await;
 
         assert!(result.is_ok());
         assert!(result.unwrap().is_none());
@@ -411,14 +524,243 @@ mod tests {
         mock_repo
             .expect_get_by_id()
             .times(1)
-            .returning(|_, _| Err(Error::Database("DB Error".to_string())));
+            .returning(|_, _, _| Err(Error::Database("DB Error".to_string())));
 
         let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
-        let result = service.get_by_id(&ctx, 1).await;
+        let result = service.get_by_id(&ctx, 1, None).await;
 
         assert!(matches!(result, Err(Error::Database(_))));
     }
 
+    // ==================== Branch Tests ====================
+
+    /// Context that may read categories on branch 10 only
+    fn create_branch_context() -> Context {
+        let mut permissions = HashMap::new();
+        permissions.insert((resource::CATEGORY, Some(10)), action::READ);
+        Context::new_with_all(None, permissions, HashMap::new())
+    }
+
+    #[tokio::test]
+    async fn test_get_all_categories_passes_branch() {
+        let mut mock_repo = MockCategoryRepo::new();
+
+        mock_repo
+            .expect_get_all()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(Some(10)),
+            )
+            .times(1)
+            .returning(|_, _| Ok(vec![create_full_category()]));
+
+        let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
+        let result = service.get_all(&create_branch_context(), Some(10)).await;
+
+        assert_eq!(result.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_categories_other_branch_forbidden() {
+        let mock_repo = MockCategoryRepo::new();
+        let ctx = create_branch_context();
+
+        let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
+
+        let result = service.get_all(&ctx, Some(20)).await;
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+        let result = service.get_by_id(&ctx, 1, None).await;
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    fn create_multi_branch_context(branch_ids: Vec<i64>) -> Context {
+        let permissions = branch_ids
+            .iter()
+            .map(|&id| ((resource::CATEGORY, Some(id)), action::READ))
+            .collect();
+        let mut extensions = HashMap::new();
+        extensions.insert(
+            std::any::TypeId::of::<BranchContext>(),
+            std::sync::Arc::new(BranchContext::new(branch_ids, None))
+                as std::sync::Arc<dyn std::any::Any + Send + Sync>,
+        );
+        Context::new_with_all(Some(1), permissions, extensions)
+    }
+
+    fn category(id: i64, children: Option<Vec<Category>>) -> Category {
+        Category {
+            id,
+            children,
+            ..create_full_category()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_all_categories_defaults_to_single_branch() {
+        let mut mock_repo = MockCategoryRepo::new();
+
+        mock_repo
+            .expect_get_all()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(Some(10)),
+            )
+            .times(1)
+            .returning(|_, _| Ok(vec![create_full_category()]));
+        mock_repo
+            .expect_count()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(Some(10)),
+            )
+            .times(1)
+            .returning(|_, _| Ok(1));
+
+        let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
+        let ctx = create_multi_branch_context(vec![10]);
+
+        assert_eq!(service.get_all(&ctx, None).await.unwrap().len(), 1);
+        assert_eq!(service.count(&ctx, None).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_all_categories_fans_out_over_branches() {
+        let mut mock_repo = MockCategoryRepo::new();
+
+        // Category 1 is shared, with a different child visible in each branch
+        mock_repo
+            .expect_get_all()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(Some(10)),
+            )
+            .returning(|_, _| Ok(vec![category(1, Some(vec![category(2, None)]))]));
+        mock_repo
+            .expect_get_all()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(Some(20)),
+            )
+            .returning(|_, _| {
+                Ok(vec![
+                    category(1, Some(vec![category(3, None)])),
+                    category(4, None),
+                ])
+            });
+        mock_repo.expect_count().times(0);
+
+        let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
+        let ctx = create_multi_branch_context(vec![10, 20]);
+
+        let tree = service.get_all(&ctx, None).await.unwrap();
+        let roots: Vec<i64> = tree.iter().map(|c| c.id).collect();
+        assert_eq!(roots, vec![1, 4]);
+        let children: Vec<i64> = tree[0]
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(children, vec![2, 3]);
+        assert_eq!(service.count(&ctx, None).await.unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_get_by_id_fans_out_over_branches() {
+        let mut mock_repo = MockCategoryRepo::new();
+
+        mock_repo
+            .expect_get_by_id()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(1),
+                mockall::predicate::eq(Some(10)),
+            )
+            .returning(|_, _, _| Ok(None));
+        mock_repo
+            .expect_get_by_id()
+            .with(
+                mockall::predicate::always(),
+                mockall::predicate::eq(1),
+                mockall::predicate::eq(Some(20)),
+            )
+            .returning(|_, _, _| Ok(Some(create_full_category())));
+
+        let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
+        let ctx = create_multi_branch_context(vec![10, 20]);
+
+        let found = service.get_by_id(&ctx, 1, None).await.unwrap();
+        assert_eq!(found.unwrap().id, 1);
+    }
+
+    // ==================== Branch Assignment Tests ====================
+
+    /// Context that may update categories on branch 10 only
+    fn create_branch_manager_context() -> Context {
+        let mut permissions = HashMap::new();
+        permissions.insert((resource::CATEGORY, Some(10)), action::UPDATE);
+        Context::new_with_all(None, permissions, HashMap::new())
+    }
+
+    #[tokio::test]
+    async fn test_assign_to_branch_success() {
+        let mut mock_repo = MockCategoryRepo::new();
+        mock_repo
+            .expect_assign_to_branch()
+            .withf(|_, id, branch_id| *id == 1 && *branch_id == 10)
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
+        let result = service
+            .assign_to_branch(&create_branch_manager_context(), 1, 10)
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_remove_from_branch_success() {
+        let mut mock_repo = MockCategoryRepo::new();
+        mock_repo
+            .expect_remove_from_branch()
+            .withf(|_, id, branch_id| *id == 1 && *branch_id == 10)
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
+        let result = service
+            .remove_from_branch(&create_branch_manager_context(), 1, 10)
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_branch_assignment_other_branch_forbidden() {
+        let mock_repo = MockCategoryRepo::new();
+        let ctx = create_branch_manager_context();
+
+        let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
+
+        let result = service.assign_to_branch(&ctx, 1, 20).await;
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+        let result = service.remove_from_branch(&ctx, 1, 20).await;
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_branch_assignment_read_only_forbidden() {
+        let mock_repo = MockCategoryRepo::new();
+        let ctx = create_branch_context();
+
+        let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
+
+        let result = service.assign_to_branch(&ctx, 1, 10).await;
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
     // ==================== No Permission Tests ====================
 
     #[tokio::test]
@@ -462,7 +804,7 @@ mod tests {
         let ctx = create_no_permission_context();
 
         let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
-        let result = service.get_all(&ctx).await;
+        let result = service.get_all(&ctx, None).await;
 
         assert!(matches!(result, Err(Error::Forbidden(_))));
     }
@@ -473,7 +815,7 @@ mod tests {
         let ctx = create_no_permission_context();
 
         let service = CategoryService::new(mock_repo, create_mock_id_gen(1));
-        let result = service.get_by_id(&ctx, 1).await;
+        let result = service.get_by_id(&ctx, 1, None).await;
 
         assert!(matches!(result, Err(Error::Forbidden(_))));
     }
@@ -1,10 +1,17 @@
 use crate::{
     domain::{
-        Context, DomainResult,
+        Context, DomainResult, Error,
         model::{
+            bulk::{BulkResult, MAX_BULK_IDS},
             customer::{Customer, CustomerCreate, CustomerFilter, CustomerUpdate},
+            metadata::{MetadataSchema, validate_metadata_update, validate_optional_metadata},
             pagination::PaginationOptions,
             permission::{action, resource},
+            phone::{
+                DEFAULT_PHONE_REGION, PhoneRegion, normalize_optional_phone,
+                normalize_phone_filter, normalize_phone_update,
+            },
+            validate::Validate,
         },
     },
     snowflake::IdGenerator,
@@ -17,6 +24,14 @@ pub trait CustomerServiceTrait: Send + Sync {
     async fn create(&self, ctx: &Context, customer: &CustomerCreate) -> DomainResult<i64>;
     async fn update(&self, ctx: &Context, id: i64, customer: &CustomerUpdate) -> DomainResult<()>;
     async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()>;
+    /// Delete up to [`MAX_BULK_IDS`] customers, skipping ids that do not
+    /// exist unless `all_or_nothing` is set. Repeated ids count once.
+    async fn delete_many(
+        &self,
+        ctx: &Context,
+        ids: &[i64],
+        all_or_nothing: bool,
+    ) -> DomainResult<BulkResult>;
     async fn get_by_number(&self, ctx: &Context, number: &str) -> DomainResult<Option<Customer>>;
     async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Customer>>;
     async fn get_all(
@@ -25,11 +40,23 @@ pub trait CustomerServiceTrait: Send + Sync {
         filter: &CustomerFilter,
         pagination: &PaginationOptions,
     ) -> DomainResult<Vec<Customer>>;
+    /// Number of customers matching `filter`, for paginated responses
+    async fn count(&self, ctx: &Context, filter: &CustomerFilter) -> DomainResult<u64>;
+    /// A page of customers with the number matching `filter`, read together
+    /// so the page and the total agree under concurrent writes
+    async fn get_all_with_total(
+        &self,
+        ctx: &Context,
+        filter: &CustomerFilter,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<(Vec<Customer>, u64)>;
 }
 
 pub struct CustomerService<R, I> {
     repository: R,
     id_generator: I,
+    phone_region: PhoneRegion,
+    metadata_schema: Option<MetadataSchema>,
 }
 
 impl<R, I> CustomerService<R, I>
@@ -41,6 +68,40 @@ where
         Self {
             repository,
             id_generator,
+            phone_region: DEFAULT_PHONE_REGION,
+            metadata_schema: None,
+        }
+    }
+
+    /// Set the region used to read phone numbers without a country code.
+    ///
+    /// Default: `ID`
+    pub fn with_default_phone_region(mut self, region: PhoneRegion) -> Self {
+        self.phone_region = region;
+        self
+    }
+
+    /// Require customer `metadata` to conform to `schema`.
+    ///
+    /// Default: no schema, any JSON is accepted
+    pub fn with_metadata_schema(mut self, schema: MetadataSchema) -> Self {
+        self.metadata_schema = Some(schema);
+        self
+    }
+
+    /// `filter` with its phone, and the search term as a phone, normalized
+    /// like stored phones, so a number typed in local format still matches
+    fn normalize_filter(&self, filter: &CustomerFilter) -> CustomerFilter {
+        CustomerFilter {
+            phone: filter
+                .phone
+                .as_deref()
+                .map(|phone| normalize_phone_filter(phone, self.phone_region)),
+            search_phone: filter
+                .search
+                .as_deref()
+                .map(|search| normalize_phone_filter(search, self.phone_region)),
+            ..filter.clone()
         }
     }
 }
@@ -53,14 +114,26 @@ where
 {
     async fn create(&self, ctx: &Context, customer: &CustomerCreate) -> DomainResult<i64> {
         ctx.require_access(None, resource::CUSTOMER, action::CREATE)?;
+        customer.validate()?;
+        validate_optional_metadata(self.metadata_schema.as_ref(), customer.metadata.as_ref())?;
+        let customer = CustomerCreate {
+            phone: normalize_optional_phone(customer.phone.as_deref(), self.phone_region)?,
+            ..customer.clone()
+        };
         let id = self.id_generator.generate()?;
-        self.repository.create(ctx, id, customer).await?;
+        self.repository.create(ctx, id, &customer).await?;
         Ok(id)
     }
 
     async fn update(&self, ctx: &Context, id: i64, customer: &CustomerUpdate) -> DomainResult<()> {
         ctx.require_access(None, resource::CUSTOMER, action::UPDATE)?;
-        self.repository.update(ctx, id, customer).await
+        customer.validate()?;
+        validate_metadata_update(self.metadata_schema.as_ref(), &customer.metadata)?;
+        let customer = CustomerUpdate {
+            phone: normalize_phone_update(&customer.phone, self.phone_region)?,
+            ..customer.clone()
+        };
+        self.repository.update(ctx, id, &customer).await
     }
 
     async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()> {
@@ -68,6 +141,30 @@ where
         self.repository.delete(ctx, id).await
     }
 
+    async fn delete_many(
+        &self,
+        ctx: &Context,
+        ids: &[i64],
+        all_or_nothing: bool,
+    ) -> DomainResult<BulkResult> {
+        ctx.require_access(None, resource::CUSTOMER, action::DELETE)?;
+        if ids.is_empty() || ids.len() > MAX_BULK_IDS {
+            return Err(Error::ValidationError(format!(
+                "Between 1 and {} ids can be deleted at once",
+                MAX_BULK_IDS
+            )));
+        }
+        let mut unique = Vec::with_capacity(ids.len());
+        for &id in ids {
+            if !unique.contains(&id) {
+                unique.push(id);
+            }
+        }
+        self.repository
+            .delete_many(ctx, &unique, all_or_nothing)
+            .await
+    }
+
     async fn get_by_number(&self, ctx: &Context, number: &str) -> DomainResult<Option<Customer>> {
         ctx.require_access(None, resource::CUSTOMER, action::READ)?;
         self.repository.get_by_number(ctx, number).await
@@ -85,7 +182,27 @@ where
         pagination: &PaginationOptions,
     ) -> DomainResult<Vec<Customer>> {
         ctx.require_access(None, resource::CUSTOMER, action::READ)?;
-        self.repository.get_all(ctx, filter, pagination).await
+        let filter = self.normalize_filter(filter);
+        ctx.cancellable(self.repository.get_all(ctx, &filter, pagination))
+            .await
+    }
+
+    async fn count(&self, ctx: &Context, filter: &CustomerFilter) -> DomainResult<u64> {
+        ctx.require_access(None, resource::CUSTOMER, action::READ)?;
+        let filter = self.normalize_filter(filter);
+        ctx.cancellable(self.repository.count(ctx, &filter)).await
+    }
+
+    async fn get_all_with_total(
+        &self,
+        ctx: &Context,
+        filter: &CustomerFilter,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<(Vec<Customer>, u64)> {
+        ctx.require_access(None, resource::CUSTOMER, action::READ)?;
+        let filter = self.normalize_filter(filter);
+        ctx.cancellable(self.repository.get_all_with_total(ctx, &filter, pagination))
+            .await
     }
 }
 
@@ -95,24 +212,11 @@ mod tests {
     use crate::application::create_mock_id_gen;
     use crate::domain::Error;
     use crate::domain::model::Update;
-    use async_trait::async_trait;
+    use crate::testing::metadata::sku_schema;
+    use crate::testing::mocks::MockCustomerRepo;
     use chrono::Utc;
-    use mockall::mock;
     use std::collections::HashMap;
 
-    mock! {
-        pub CustomerRepo {}
-        #[async_trait]
-        impl CustomerRepository for CustomerRepo {
-            async fn create(&self, ctx: &Context, id: i64, customer: &CustomerCreate) -> DomainResult<()>;
-            async fn update(&self, ctx: &Context, id: i64, customer: &CustomerUpdate) -> DomainResult<()>;
-            async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()>;
-            async fn get_all(&self, ctx: &Context, filter: &CustomerFilter, pagination: &PaginationOptions) -> DomainResult<Vec<Customer>>;
-            async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Customer>>;
-            async fn get_by_number(&self, ctx: &Context, number: &str) -> DomainResult<Option<Customer>>;
-        }
-    }
-
     /// Creates a test context with full permissions for CUSTOMER resource
     fn create_test_context() -> Context {
         let mut permissions = HashMap::new();
@@ -131,7 +235,7 @@ mod tests {
             name: "Test Customer".to_string(),
             address: Some("123 Test St".to_string()),
             email: Some("test@customer.com".to_string()),
-            phone: Some("555-1234".to_string()),
+            phone: Some("0812-3456-789".to_string()),
             level: 1,
             metadata: None,
         }
@@ -148,9 +252,11 @@ mod tests {
             name: "Test Customer".to_string(),
             address: Some("123 Test St".to_string()),
             email: Some("test@customer.com".to_string()),
-            phone: Some("555-1234".to_string()),
+            phone: Some("0812-3456-789".to_string()),
             level: 1,
             metadata: None,
+            created_by: None,
+            updated_by: None,
         }
     }
 
@@ -173,6 +279,11 @@ mod tests {
             email: None,
             phone: None,
             level: None,
+            level_min: None,
+            level_max: None,
+            search: None,
+            search_phone: None,
+            expressions: vec![],
         }
     }
 
@@ -255,6 +366,143 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_create_customer_invalid_email() {
+        let ctx = create_test_context();
+        let service = CustomerService::new(MockCustomerRepo::new(), create_mock_id_gen(1));
+        let mut customer = create_test_customer_create();
+        customer.email = Some("foo@".to_string());
+
+        let result = service.create(&ctx, &customer).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_customer_invalid_email() {
+        let ctx = create_test_context();
+        let service = CustomerService::new(MockCustomerRepo::new(), create_mock_id_gen(1));
+        let update = CustomerUpdate {
+            email: Update::Set("not-an-email".to_string()),
+            ..Default::default()
+        };
+
+        let result = service.update(&ctx, 1, &update).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_customer_clear_email_skips_validation() {
+        let mut mock_repo = MockCustomerRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_update()
+            .withf(|_, _, update| update.email == Update::Clear)
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = CustomerService::new(mock_repo, create_mock_id_gen(1));
+        let update = CustomerUpdate {
+            email: Update::Clear,
+            ..Default::default()
+        };
+
+        assert!(service.update(&ctx, 1, &update).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_customer_metadata_matches_schema() {
+        let mut mock_repo = MockCustomerRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_create()
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = CustomerService::new(mock_repo, create_mock_id_gen(1))
+            .with_metadata_schema(sku_schema());
+        let mut customer = create_test_customer_create();
+        customer.metadata = Some(serde_json::json!({"sku": "CUST-1"}));
+
+        assert!(service.create(&ctx, &customer).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_customer_metadata_missing_sku() {
+        let ctx = create_test_context();
+        let service = CustomerService::new(MockCustomerRepo::new(), create_mock_id_gen(1))
+            .with_metadata_schema(sku_schema());
+        let mut customer = create_test_customer_create();
+        customer.metadata = Some(serde_json::json!({"note": "vip"}));
+
+        let result = service.create(&ctx, &customer).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_customer_metadata_missing_sku() {
+        let ctx = create_test_context();
+        let service = CustomerService::new(MockCustomerRepo::new(), create_mock_id_gen(1))
+            .with_metadata_schema(sku_schema());
+        let update = CustomerUpdate {
+            metadata: Update::Set(serde_json::json!({"sku": 7})),
+            ..Default::default()
+        };
+
+        let result = service.update(&ctx, 1, &update).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_customer_normalizes_phone() {
+        let mut mock_repo = MockCustomerRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_create()
+            .withf(|_, _, customer| customer.phone.as_deref() == Some("+628123456789"))
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = CustomerService::new(mock_repo, create_mock_id_gen(1));
+        let result = service.create(&ctx, &create_test_customer_create()).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_customer_invalid_phone() {
+        let ctx = create_test_context();
+        let service = CustomerService::new(MockCustomerRepo::new(), create_mock_id_gen(1));
+        let mut customer = create_test_customer_create();
+        customer.phone = Some("not a phone".to_string());
+
+        let result = service.create(&ctx, &customer).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_customer_normalizes_phone_with_region() {
+        let mut mock_repo = MockCustomerRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_update()
+            .withf(|_, _, update| update.phone == Update::Set("+12015550123".to_string()))
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = CustomerService::new(mock_repo, create_mock_id_gen(1))
+            .with_default_phone_region(PhoneRegion::US);
+        let update = CustomerUpdate {
+            phone: Update::Set("(201) 555-0123".to_string()),
+            ..Default::default()
+        };
+
+        assert!(service.update(&ctx, 1, &update).await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_update_customer_no_permission() {
         let ctx = create_no_permission_context();
@@ -354,6 +602,55 @@ mod tests {
         assert!(matches!(result, Err(Error::NotFound(_))));
     }
 
+    #[tokio::test]
+    async fn test_delete_many_deduplicates_ids() {
+        let mut mock_repo = MockCustomerRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_delete_many()
+            .withf(|_, ids, all_or_nothing| ids == [3, 1, 2] && *all_or_nothing)
+            .times(1)
+            .returning(|_, _, _| {
+                Ok(BulkResult {
+                    deleted: vec![3, 1],
+                    not_found: vec![2],
+                })
+            });
+
+        let service = CustomerService::new(mock_repo, create_mock_id_gen(1));
+        let result = service.delete_many(&ctx, &[3, 1, 3, 2, 1], true).await;
+        assert_eq!(
+            result.unwrap(),
+            BulkResult {
+                deleted: vec![3, 1],
+                not_found: vec![2],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_many_rejects_empty_and_oversized_batches() {
+        let ctx = create_test_context();
+        let service = CustomerService::new(MockCustomerRepo::new(), create_mock_id_gen(1));
+
+        let result = service.delete_many(&ctx, &[], false).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+
+        let ids: Vec<i64> = (1..=MAX_BULK_IDS as i64 + 1).collect();
+        let result = service.delete_many(&ctx, &ids, false).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_many_no_permission() {
+        let ctx = create_no_permission_context();
+        let service = CustomerService::new(MockCustomerRepo::new(), create_mock_id_gen(1));
+
+        let result = service.delete_many(&ctx, &[1, 2], false).await;
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
     // =============================================================================
     // Get By Number Tests
     // =============================================================================
@@ -555,6 +852,78 @@ mod tests {
         assert!(matches!(result, Err(Error::Forbidden(_))));
     }
 
+    #[tokio::test]
+    async fn test_get_all_cancelled() {
+        let mut mock_repo = MockCustomerRepo::new();
+        mock_repo.expect_get_all().never();
+
+        let token = tokio_util::sync::CancellationToken::new();
+        let ctx = create_test_context().with_cancellation_token(token.clone());
+        token.cancel();
+        let service = CustomerService::new(mock_repo, create_mock_id_gen(1));
+
+        let result = service
+            .get_all(&ctx, &create_default_filter(), &create_default_pagination())
+            .await;
+        assert!(matches!(result, Err(Error::Cancelled(_))));
+    }
+
+    #[tokio::test]
+    async fn test_count_success() {
+        let mut mock_repo = MockCustomerRepo::new();
+        mock_repo.expect_count().times(1).returning(|_, _| Ok(42));
+
+        let ctx = create_test_context();
+        let service = CustomerService::new(mock_repo, create_mock_id_gen(1));
+
+        let result = service.count(&ctx, &create_default_filter()).await;
+        assert_eq!(result.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn test_count_no_permission() {
+        let ctx = create_no_permission_context();
+        let service = CustomerService::new(MockCustomerRepo::new(), create_mock_id_gen(1));
+
+        let result = service.count(&ctx, &create_default_filter()).await;
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_all_with_total_success() {
+        let mut mock_repo = MockCustomerRepo::new();
+        mock_repo
+            .expect_get_all_with_total()
+            .withf(|_, filter, _| filter.phone == Some("+628123456789".to_string()))
+            .times(1)
+            .returning(|_, _, _| Ok((vec![], 42)));
+
+        let ctx = create_test_context();
+        let service = CustomerService::new(mock_repo, create_mock_id_gen(1));
+        let filter = CustomerFilter {
+            phone: Some("0812-3456-789".to_string()),
+            ..Default::default()
+        };
+
+        let (customers, total) = service
+            .get_all_with_total(&ctx, &filter, &create_default_pagination())
+            .await
+            .unwrap();
+        assert!(customers.is_empty());
+        assert_eq!(total, 42);
+    }
+
+    #[tokio::test]
+    async fn test_get_all_with_total_no_permission() {
+        let ctx = create_no_permission_context();
+        let service = CustomerService::new(MockCustomerRepo::new(), create_mock_id_gen(1));
+
+        let result = service
+            .get_all_with_total(&ctx, &create_default_filter(), &create_default_pagination())
+            .await;
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
     #[tokio::test]
     async fn test_get_all_repo_error() {
         let mut mock_repo = MockCustomerRepo::new();
@@ -593,6 +962,11 @@ mod tests {
             email: None,
             phone: None,
             level: None,
+            level_min: None,
+            level_max: None,
+            search: None,
+            search_phone: None,
+            expressions: vec![],
         };
         let pagination = create_default_pagination();
         let result = service.get_all(&ctx, &filter, &pagination).await;
@@ -602,6 +976,44 @@ mod tests {
         assert_eq!(result_customers.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_get_all_normalizes_phone_filter() {
+        let mut mock_repo = MockCustomerRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_all()
+            .withf(|_, filter, _| {
+                filter.phone == Some("+628123456789".to_string())
+                    && filter.search == Some("0812-3456-789".to_string())
+                    && filter.search_phone == Some("+628123456789".to_string())
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(vec![]));
+        mock_repo
+            .expect_count()
+            .withf(|_, filter| filter.phone == Some("3456789".to_string()))
+            .times(1)
+            .returning(|_, _| Ok(0));
+
+        let service = CustomerService::new(mock_repo, create_mock_id_gen(1));
+        let filter = CustomerFilter {
+            phone: Some("0812-3456-789".to_string()),
+            search: Some("0812-3456-789".to_string()),
+            ..Default::default()
+        };
+        let result = service
+            .get_all(&ctx, &filter, &create_default_pagination())
+            .await;
+        assert!(result.is_ok());
+
+        let filter = CustomerFilter {
+            phone: Some("3456 789".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(service.count(&ctx, &filter).await.unwrap(), 0);
+    }
+
     #[tokio::test]
     async fn test_get_all_with_pagination() {
         let mut mock_repo = MockCustomerRepo::new();
@@ -0,0 +1,359 @@
+use crate::{
+    domain::{
+        Context, DomainResult, Error,
+        model::{
+            export::{ExportTable, ImportMode, Snapshot},
+            metadata::MetadataSchema,
+            permission::{action, resource},
+            phone::{DEFAULT_PHONE_REGION, PhoneRegion, normalize_phone},
+        },
+    },
+    storage::{ExportReader, ExportRepository},
+};
+use async_trait::async_trait;
+use serde_json::Value;
+
+#[async_trait]
+pub trait ExportServiceTrait: Send + Sync {
+    /// Start a full data export. Pages read from the returned reader all
+    /// come from one snapshot. Admins only, as the export covers every entity.
+    async fn begin_export(&self, ctx: &Context) -> DomainResult<Box<dyn ExportReader>>;
+
+    /// Restore a full snapshot in one transaction. Phones are normalized and
+    /// metadata checked against the configured schemas, as on create.
+    /// Admins only.
+    async fn import_snapshot(
+        &self,
+        ctx: &Context,
+        snapshot: Snapshot,
+        mode: ImportMode,
+    ) -> DomainResult<()>;
+}
+
+pub struct ExportService<R> {
+    repository: R,
+    phone_region: PhoneRegion,
+    product_metadata_schema: Option<MetadataSchema>,
+    variant_metadata_schema: Option<MetadataSchema>,
+    customer_metadata_schema: Option<MetadataSchema>,
+}
+
+impl<R> ExportService<R>
+where
+    R: ExportRepository,
+{
+    pub fn new(repository: R) -> Self {
+        Self {
+            repository,
+            phone_region: DEFAULT_PHONE_REGION,
+            product_metadata_schema: None,
+            variant_metadata_schema: None,
+            customer_metadata_schema: None,
+        }
+    }
+
+    /// Set the region used to read imported phone numbers without a country
+    /// code.
+    ///
+    /// Default: `ID`
+    pub fn with_default_phone_region(mut self, region: PhoneRegion) -> Self {
+        self.phone_region = region;
+        self
+    }
+
+    /// Require imported product `metadata` to conform to `schema`.
+    ///
+    /// Default: no schema, any JSON is accepted
+    pub fn with_product_metadata_schema(mut self, schema: MetadataSchema) -> Self {
+        self.product_metadata_schema = Some(schema);
+        self
+    }
+
+    /// Require imported variant `metadata` to conform to `schema`.
+    ///
+    /// Default: no schema, any JSON is accepted
+    pub fn with_variant_metadata_schema(mut self, schema: MetadataSchema) -> Self {
+        self.variant_metadata_schema = Some(schema);
+        self
+    }
+
+    /// Require imported customer `metadata` to conform to `schema`.
+    ///
+    /// Default: no schema, any JSON is accepted
+    pub fn with_customer_metadata_schema(mut self, schema: MetadataSchema) -> Self {
+        self.customer_metadata_schema = Some(schema);
+        self
+    }
+
+    /// Normalize the `phone` of each row in place
+    fn normalize_phones(&self, table: ExportTable, rows: &mut [Value]) -> DomainResult<()> {
+        for row in rows {
+            let id = row["id"].clone();
+            if let Some(Value::String(phone)) = row.get_mut("phone") {
+                *phone = normalize_phone(phone, self.phone_region)
+                    .map_err(|e| row_error(table, &id, e))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check the `metadata` of each row; rows without metadata pass
+fn validate_metadata(
+    schema: Option<&MetadataSchema>,
+    table: ExportTable,
+    rows: &[Value],
+) -> DomainResult<()> {
+    let Some(schema) = schema else {
+        return Ok(());
+    };
+    for row in rows {
+        match row.get("metadata") {
+            None | Some(Value::Null) => {}
+            Some(metadata) => schema
+                .validate(metadata)
+                .map_err(|e| row_error(table, &row["id"], e))?,
+        }
+    }
+    Ok(())
+}
+
+/// Name the row a validation failed on
+fn row_error(table: ExportTable, id: &Value, error: Error) -> Error {
+    match error {
+        Error::ValidationError(msg) => {
+            Error::ValidationError(format!("Invalid {} row {}: {}", table.key(), id, msg))
+        }
+        other => other,
+    }
+}
+
+#[async_trait]
+impl<R> ExportServiceTrait for ExportService<R>
+where
+    R: ExportRepository,
+{
+    async fn begin_export(&self, ctx: &Context) -> DomainResult<Box<dyn ExportReader>> {
+        ctx.require_access(None, resource::ADMIN, action::READ)?;
+        self.repository.begin_export(ctx).await
+    }
+
+    async fn import_snapshot(
+        &self,
+        ctx: &Context,
+        mut snapshot: Snapshot,
+        mode: ImportMode,
+    ) -> DomainResult<()> {
+        ctx.require_access(None, resource::ADMIN, action::UPDATE)?;
+        self.normalize_phones(ExportTable::Customers, &mut snapshot.customers)?;
+        self.normalize_phones(ExportTable::Suppliers, &mut snapshot.suppliers)?;
+        validate_metadata(
+            self.product_metadata_schema.as_ref(),
+            ExportTable::Products,
+            &snapshot.products,
+        )?;
+        validate_metadata(
+            self.variant_metadata_schema.as_ref(),
+            ExportTable::Variants,
+            &snapshot.variants,
+        )?;
+        validate_metadata(
+            self.customer_metadata_schema.as_ref(),
+            ExportTable::Customers,
+            &snapshot.customers,
+        )?;
+        self.repository.import_snapshot(ctx, &snapshot, mode).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        Error,
+        model::export::{ExportPage, ExportTable},
+    };
+    use crate::testing::metadata::sku_schema;
+    use mockall::{mock, predicate::eq};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    mock! {
+        pub ExportRepo {}
+        #[async_trait]
+        impl ExportRepository for ExportRepo {
+            async fn begin_export(&self, ctx: &Context) -> DomainResult<Box<dyn ExportReader>>;
+            async fn import_snapshot(&self, ctx: &Context, snapshot: &Snapshot, mode: ImportMode) -> DomainResult<()>;
+        }
+    }
+
+    mock! {
+        pub Reader {}
+        #[async_trait]
+        impl ExportReader for Reader {
+            async fn export_rows(&mut self, table: ExportTable, include_deleted: bool, after_id: i64, limit: u32) -> DomainResult<ExportPage>;
+        }
+    }
+
+    fn create_admin_context() -> Context {
+        Context::new_with_all(
+            None,
+            HashMap::from([((resource::ADMIN, None), action::READ)]),
+            HashMap::new(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_begin_export_success() {
+        let mut repo = MockExportRepo::new();
+        repo.expect_begin_export().times(1).returning(|_| {
+            let mut reader = MockReader::new();
+            reader
+                .expect_export_rows()
+                .with(eq(ExportTable::Customers), eq(true), eq(5), eq(100))
+                .times(1)
+                .returning(|_, _, _, _| {
+                    Ok(ExportPage {
+                        rows: vec![json!({"id": 6})],
+                        next_after_id: None,
+                    })
+                });
+            Ok(Box::new(reader))
+        });
+
+        let service = ExportService::new(repo);
+        let mut reader = service.begin_export(&create_admin_context()).await.unwrap();
+        let page = reader
+            .export_rows(ExportTable::Customers, true, 5, 100)
+            .await
+            .unwrap();
+
+        assert_eq!(page.rows, vec![json!({"id": 6})]);
+        assert_eq!(page.next_after_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_begin_export_requires_admin() {
+        let repo = MockExportRepo::new();
+        let ctx = Context::new_with_all(
+            None,
+            HashMap::from([((resource::CUSTOMER, None), action::READ)]),
+            HashMap::new(),
+        );
+
+        let service = ExportService::new(repo);
+        let result = service.begin_export(&ctx).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_import_snapshot_success() {
+        let mut repo = MockExportRepo::new();
+        repo.expect_import_snapshot()
+            .withf(|_, snapshot, mode| {
+                snapshot.units == vec![json!({"id": 1, "name": "pcs"})]
+                    && *mode == ImportMode::Upsert
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let snapshot = Snapshot {
+            units: vec![json!({"id": 1, "name": "pcs"})],
+            ..Default::default()
+        };
+
+        let service = ExportService::new(repo);
+        let result = service
+            .import_snapshot(&create_admin_context(), snapshot, ImportMode::Upsert)
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_import_snapshot_requires_admin() {
+        let repo = MockExportRepo::new();
+        let ctx = Context::new_with_all(
+            None,
+            HashMap::from([((resource::PRODUCT, None), action::UPDATE)]),
+            HashMap::new(),
+        );
+
+        let service = ExportService::new(repo);
+        let result = service
+            .import_snapshot(&ctx, Snapshot::default(), ImportMode::Insert)
+            .await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_import_snapshot_normalizes_phones() {
+        let mut repo = MockExportRepo::new();
+        repo.expect_import_snapshot()
+            .withf(|_, snapshot, _| {
+                snapshot.customers[0]["phone"] == "+628123456789"
+                    && snapshot.suppliers[0]["phone"] == "+12015550123"
+                    && snapshot.suppliers[1]["phone"].is_null()
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let snapshot = Snapshot {
+            customers: vec![json!({"id": 1, "phone": "0812-3456-789"})],
+            suppliers: vec![
+                json!({"id": 2, "phone": "+1 201 555 0123"}),
+                json!({"id": 3, "phone": null}),
+            ],
+            ..Default::default()
+        };
+
+        let service = ExportService::new(repo);
+        let result = service
+            .import_snapshot(&create_admin_context(), snapshot, ImportMode::Insert)
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_import_snapshot_rejects_invalid_phone() {
+        let repo = MockExportRepo::new();
+        let snapshot = Snapshot {
+            customers: vec![json!({"id": 1, "phone": "not a phone"})],
+            ..Default::default()
+        };
+
+        let service = ExportService::new(repo);
+        let result = service
+            .import_snapshot(&create_admin_context(), snapshot, ImportMode::Insert)
+            .await;
+
+        assert!(
+            matches!(result, Err(Error::ValidationError(msg)) if msg.starts_with("Invalid customers row 1"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_snapshot_validates_metadata() {
+        let repo = MockExportRepo::new();
+        let snapshot = Snapshot {
+            products: vec![
+                json!({"id": 1, "metadata": {"sku": "A-1"}}),
+                json!({"id": 2, "metadata": null}),
+                json!({"id": 3, "metadata": {"color": "red"}}),
+            ],
+            ..Default::default()
+        };
+
+        let service = ExportService::new(repo).with_product_metadata_schema(sku_schema());
+        let result = service
+            .import_snapshot(&create_admin_context(), snapshot, ImportMode::Insert)
+            .await;
+
+        assert!(
+            matches!(result, Err(Error::ValidationError(msg)) if msg.starts_with("Invalid products row 3"))
+        );
+    }
+}
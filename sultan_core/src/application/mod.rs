@@ -3,16 +3,22 @@ pub mod branch_service;
 pub mod cache;
 pub mod category_service;
 pub mod customer_service;
+pub mod export_service;
 pub mod product_service;
+pub mod purchase_order_service;
+pub mod sale_service;
 pub mod supplier_service;
 pub mod user_service;
 
-pub use auth_service::{AuthService, AuthServiceTrait, AuthTokens};
+pub use auth_service::{AuthService, AuthServiceTrait, AuthTokens, LoginFailure};
 pub use branch_service::{BranchService, BranchServiceTrait};
 pub use cache::{CacheService, InMemoryCache};
 pub use category_service::{CategoryService, CategoryServiceTrait};
 pub use customer_service::{CustomerService, CustomerServiceTrait};
+pub use export_service::{ExportService, ExportServiceTrait};
 pub use product_service::{ProductService, ProductServiceTrait};
+pub use purchase_order_service::{PurchaseOrderService, PurchaseOrderServiceTrait};
+pub use sale_service::{SaleService, SaleServiceTrait};
 pub use supplier_service::{SupplierService, SupplierServiceTrait};
 pub use user_service::{UserService, UserServiceTrait};
 
@@ -1,21 +1,32 @@
 use crate::snowflake::IdGenerator;
 use crate::{
     domain::{
-        Context, DomainResult,
+        Context, DomainResult, Error,
         model::{
+            metadata::{MetadataSchema, validate_metadata_update, validate_optional_metadata},
+            money::Money,
+            pagination::PaginationOptions,
             permission::{action, resource},
             product::{
-                Product, ProductCreate, ProductUpdate, ProductVariant, ProductVariantCreate,
-                ProductVariantUpdate,
+                BundleComponent, Product, ProductComponent, ProductCreate, ProductDetail,
+                ProductFilter, ProductSupplier, ProductType, ProductUpdate, ProductVariant,
+                ProductVariantCreate, ProductVariantFilter, ProductVariantUpdate,
+                ProductVariantUpsert, ProductWithVariants, VariantPriceChange,
             },
+            stock::{StockAdjustment, StockLevel},
+            validate::Validate,
         },
     },
-    storage::{ProductRepository, transaction::TransactionManager},
+    storage::{ProductRepository, StockRepository, transaction::TransactionManager},
 };
 use async_trait::async_trait;
+use std::collections::HashMap;
 
 #[async_trait]
 pub trait ProductServiceTrait: Send + Sync {
+    /// Create the product with its variants and their `initial_stock` in one
+    /// transaction. The variants' `product_id` is replaced with the new
+    /// product's id. Opening stock needs product update access in its branch.
     async fn create_product(
         &self,
         ctx: &Context,
@@ -29,7 +40,103 @@ pub trait ProductServiceTrait: Send + Sync {
         product: &ProductUpdate,
     ) -> DomainResult<()>;
     async fn delete_product(&self, ctx: &Context, id: i64) -> DomainResult<()>;
+    /// Link categories without touching the product's other categories.
+    async fn add_categories(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+        category_ids: &[i64],
+    ) -> DomainResult<()>;
+    /// Unlink categories without touching the product's other categories.
+    async fn remove_categories(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+        category_ids: &[i64],
+    ) -> DomainResult<()>;
+    /// Replace the suppliers the product is bought from. Allowed with either
+    /// PRODUCT or SUPPLIER update access.
+    async fn set_suppliers(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+        suppliers: &[ProductSupplier],
+    ) -> DomainResult<()>;
+    /// Readable with either PRODUCT or SUPPLIER read access.
+    async fn get_suppliers(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+    ) -> DomainResult<Vec<ProductSupplier>>;
+    /// Replace the components of a bundle variant. The variant must belong
+    /// to a `Bundle` product and cannot contain itself, directly or through
+    /// a component bundle.
+    async fn set_components(
+        &self,
+        ctx: &Context,
+        bundle_variant_id: i64,
+        components: &[ProductComponent],
+    ) -> DomainResult<()>;
+    /// The component variants of a bundle variant with their quantities.
+    /// Only one level is expanded; a component that is itself a bundle is
+    /// returned as is. Fails with NotFound if the variant is missing or not
+    /// part of a bundle product, or if any component is missing or deleted.
+    async fn explode_bundle(
+        &self,
+        ctx: &Context,
+        bundle_variant_id: i64,
+    ) -> DomainResult<Vec<BundleComponent>>;
+    /// Add `delta` units of a variant to a branch's stock, or remove them
+    /// when negative, recording `reason`. Returns the quantity on hand
+    /// afterwards.
+    async fn adjust_stock(
+        &self,
+        ctx: &Context,
+        variant_id: i64,
+        branch_id: i64,
+        delta: i64,
+        reason: &str,
+    ) -> DomainResult<i64>;
+    /// Variants stocked at the branch with at most `threshold` units on hand,
+    /// lowest first. Requires product read access for that branch.
+    async fn list_low_stock(
+        &self,
+        ctx: &Context,
+        branch_id: i64,
+        threshold: i64,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<StockLevel>>;
     async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Product>>;
+    /// The product with its live variants and category ids, or None if the
+    /// product does not exist or is deleted
+    async fn get_product_with_variants(
+        &self,
+        ctx: &Context,
+        id: i64,
+    ) -> DomainResult<Option<ProductDetail>>;
+    /// Products linked to a category, newest first. As with [`get_all`],
+    /// callers who can't update products only see sellable ones.
+    ///
+    /// [`get_all`]: ProductServiceTrait::get_all
+    async fn get_by_category(
+        &self,
+        ctx: &Context,
+        category_id: i64,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<Product>>;
+    async fn count_by_category(&self, ctx: &Context, category_id: i64) -> DomainResult<u64>;
+    /// Products matching `filter` with their live variants, newest first.
+    /// Callers who can't update products, such as cashiers, only see
+    /// sellable products whatever the filter says.
+    async fn get_all(
+        &self,
+        ctx: &Context,
+        filter: &ProductFilter,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<ProductWithVariants>>;
+    /// Number of products matching `filter`, with the same sellable rule as
+    /// `get_all`
+    async fn count(&self, ctx: &Context, filter: &ProductFilter) -> DomainResult<u64>;
     async fn create_variant(
         &self,
         ctx: &Context,
@@ -41,7 +148,22 @@ pub trait ProductServiceTrait: Send + Sync {
         id: i64,
         variant: &ProductVariantUpdate,
     ) -> DomainResult<()>;
+    /// Create the variant if its barcode is new, otherwise update its name
+    /// and metadata. Returns the variant id and whether it was created.
+    async fn upsert_variant_by_barcode(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+        variant: &ProductVariantUpsert,
+    ) -> DomainResult<(i64, bool)>;
     async fn delete_variant(&self, ctx: &Context, id: i64) -> DomainResult<()>;
+    /// Move a variant to another product without recreating it.
+    async fn reassign_variant(
+        &self,
+        ctx: &Context,
+        variant_id: i64,
+        new_product_id: i64,
+    ) -> DomainResult<()>;
     async fn delete_variants_by_product_id(
         &self,
         ctx: &Context,
@@ -62,32 +184,124 @@ pub trait ProductServiceTrait: Send + Sync {
         ctx: &Context,
         product_id: i64,
     ) -> DomainResult<Vec<ProductVariant>>;
+    /// Live variants across products, newest first
+    async fn get_variants(
+        &self,
+        ctx: &Context,
+        filter: &ProductVariantFilter,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<ProductVariant>>;
+    async fn count_variants(&self, ctx: &Context, product_id: i64) -> DomainResult<u64>;
+    /// Ids of the categories a product is linked to
+    async fn get_categories(&self, ctx: &Context, product_id: i64) -> DomainResult<Vec<i64>>;
+    /// Price of a variant for the given customer level, falling back to the
+    /// variant's base price when no tier is defined for that level.
+    async fn price_for(&self, ctx: &Context, variant_id: i64, level: i32) -> DomainResult<Money>;
+    /// Base price changes of a variant, oldest first
+    async fn get_price_history(
+        &self,
+        ctx: &Context,
+        variant_id: i64,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<VariantPriceChange>>;
 }
 
-pub struct ProductService<R, T, I> {
+pub struct ProductService<R, S, T, I> {
     repository: R,
+    stock_repository: S,
     tx_manager: T,
     id_generator: I,
+    product_metadata_schema: Option<MetadataSchema>,
+    variant_metadata_schema: Option<MetadataSchema>,
 }
 
-impl<R, T, I> ProductService<R, T, I>
+impl<R, S, T, I> ProductService<R, S, T, I>
 where
     T: TransactionManager,
     I: IdGenerator,
 {
-    pub fn new(repository: R, tx_manager: T, id_generator: I) -> Self {
+    pub fn new(repository: R, stock_repository: S, tx_manager: T, id_generator: I) -> Self {
         Self {
             repository,
+            stock_repository,
             tx_manager,
             id_generator,
+            product_metadata_schema: None,
+            variant_metadata_schema: None,
+        }
+    }
+
+    /// Require product `metadata` to conform to `schema`.
+    ///
+    /// Default: no schema, any JSON is accepted
+    pub fn with_product_metadata_schema(mut self, schema: MetadataSchema) -> Self {
+        self.product_metadata_schema = Some(schema);
+        self
+    }
+
+    /// Require variant `metadata` to conform to `schema`.
+    ///
+    /// Default: no schema, any JSON is accepted
+    pub fn with_variant_metadata_schema(mut self, schema: MetadataSchema) -> Self {
+        self.variant_metadata_schema = Some(schema);
+        self
+    }
+}
+
+/// Product listings for callers who can't manage products (cashiers) are
+/// limited to sellable products
+fn sellable_only(ctx: &Context) -> bool {
+    !ctx.has_any_access(resource::PRODUCT, action::UPDATE)
+}
+
+fn visible_products(ctx: &Context, filter: &ProductFilter) -> ProductFilter {
+    let mut filter = filter.clone();
+    if sellable_only(ctx) {
+        filter.sellable = Some(true);
+    }
+    filter
+}
+
+fn validate_price(price: Option<&Money>) -> DomainResult<()> {
+    price.map_or(Ok(()), |p| p.ensure_non_negative("price"))
+}
+
+/// Reason recorded for the opening stock of a new variant
+const INITIAL_STOCK_REASON: &str = "initial stock";
+
+/// Opening stock needs the same access as adjusting stock in each branch
+fn validate_initial_stock(ctx: &Context, variant: &ProductVariantCreate) -> DomainResult<()> {
+    for stock in &variant.initial_stock {
+        ctx.require_access(Some(stock.branch_id), resource::PRODUCT, action::UPDATE)?;
+        if stock.quantity <= 0 {
+            return Err(Error::ValidationError(
+                "Initial stock must be greater than zero".to_string(),
+            ));
         }
     }
+    Ok(())
+}
+
+fn initial_stock_adjustments(
+    variant_id: i64,
+    variant: &ProductVariantCreate,
+) -> impl Iterator<Item = StockAdjustment> + '_ {
+    variant
+        .initial_stock
+        .iter()
+        .map(move |stock| StockAdjustment {
+            branch_id: stock.branch_id,
+            variant_id,
+            delta: stock.quantity,
+            reason: INITIAL_STOCK_REASON.to_string(),
+        })
 }
 
 #[async_trait]
-impl<R, T, I> ProductServiceTrait for ProductService<R, T, I>
+impl<R, S, T, I> ProductServiceTrait for ProductService<R, S, T, I>
 where
     for<'a> R: ProductRepository<T::Transaction<'a>>,
+    for<'a> S: StockRepository<T::Transaction<'a>>,
     for<'a> T::Transaction<'a>: Send,
     T: TransactionManager,
     I: IdGenerator,
@@ -99,6 +313,19 @@ where
         variants: &[ProductVariantCreate],
     ) -> DomainResult<i64> {
         ctx.require_access(None, resource::PRODUCT, action::CREATE)?;
+        product.validate()?;
+        validate_optional_metadata(
+            self.product_metadata_schema.as_ref(),
+            product.metadata.as_ref(),
+        )?;
+        for variant in variants {
+            validate_price(variant.price.as_ref())?;
+            validate_optional_metadata(
+                self.variant_metadata_schema.as_ref(),
+                variant.metadata.as_ref(),
+            )?;
+            validate_initial_stock(ctx, variant)?;
+        }
         let mut tx = self.tx_manager.begin().await?;
 
         let id = self.id_generator.generate()?;
@@ -111,9 +338,14 @@ where
             return Err(e);
         }
 
-        // Insert all variants
+        // Insert all variants with their opening stock
         for variant in variants {
             let variant_id = self.id_generator.generate()?;
+            // The product id is only known now
+            let variant = &ProductVariantCreate {
+                product_id: id,
+                ..variant.clone()
+            };
             if let Err(e) = self
                 .repository
                 .create_variant(ctx, variant_id, variant, &mut tx)
@@ -122,6 +354,16 @@ where
                 let _ = self.tx_manager.rollback(tx).await;
                 return Err(e);
             }
+            for adjustment in initial_stock_adjustments(variant_id, variant) {
+                if let Err(e) = self
+                    .stock_repository
+                    .adjust(ctx, &adjustment, &mut tx)
+                    .await
+                {
+                    let _ = self.tx_manager.rollback(tx).await;
+                    return Err(e);
+                }
+            }
         }
 
         self.tx_manager.commit(tx).await?;
@@ -135,6 +377,8 @@ where
         product: &ProductUpdate,
     ) -> DomainResult<()> {
         ctx.require_access(None, resource::PRODUCT, action::UPDATE)?;
+        product.validate()?;
+        validate_metadata_update(self.product_metadata_schema.as_ref(), &product.metadata)?;
         let mut tx = self.tx_manager.begin().await?;
         match self
             .repository
@@ -159,9 +403,130 @@ where
             let _ = self.tx_manager.rollback(tx).await;
             return Err(e);
         }
+        self.tx_manager.commit(tx).await?;
+        Ok(())
+    }
+
+    async fn add_categories(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+        category_ids: &[i64],
+    ) -> DomainResult<()> {
+        ctx.require_access(None, resource::PRODUCT, action::UPDATE)?;
+        let mut tx = self.tx_manager.begin().await?;
+        if let Err(e) = self
+            .repository
+            .add_categories(ctx, product_id, category_ids, &mut tx)
+            .await
+        {
+            let _ = self.tx_manager.rollback(tx).await;
+            return Err(e);
+        }
+        self.tx_manager.commit(tx).await?;
+        Ok(())
+    }
+
+    async fn remove_categories(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+        category_ids: &[i64],
+    ) -> DomainResult<()> {
+        ctx.require_access(None, resource::PRODUCT, action::UPDATE)?;
+        let mut tx = self.tx_manager.begin().await?;
+        if let Err(e) = self
+            .repository
+            .remove_categories(ctx, product_id, category_ids, &mut tx)
+            .await
+        {
+            let _ = self.tx_manager.rollback(tx).await;
+            return Err(e);
+        }
+        self.tx_manager.commit(tx).await?;
+        Ok(())
+    }
+
+    async fn set_suppliers(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+        suppliers: &[ProductSupplier],
+    ) -> DomainResult<()> {
+        ctx.require_any(
+            None,
+            &[
+                (resource::PRODUCT, action::UPDATE),
+                (resource::SUPPLIER, action::UPDATE),
+            ],
+        )?;
+        for supplier in suppliers {
+            if let Some(cost) = &supplier.cost {
+                cost.ensure_non_negative("cost")?;
+            }
+        }
+        let mut tx = self.tx_manager.begin().await?;
+        if let Err(e) = self
+            .repository
+            .set_suppliers(ctx, product_id, suppliers, &mut tx)
+            .await
+        {
+            let _ = self.tx_manager.rollback(tx).await;
+            return Err(e);
+        }
+        self.tx_manager.commit(tx).await?;
+        Ok(())
+    }
+
+    async fn get_suppliers(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+    ) -> DomainResult<Vec<ProductSupplier>> {
+        // Checked with has_access so a supplier-only reader isn't audited as denied
+        if !ctx.has_access(None, resource::PRODUCT, action::READ) {
+            ctx.require_access(None, resource::SUPPLIER, action::READ)?;
+        }
+        self.repository.get_suppliers(ctx, product_id).await
+    }
+
+    async fn set_components(
+        &self,
+        ctx: &Context,
+        bundle_variant_id: i64,
+        components: &[ProductComponent],
+    ) -> DomainResult<()> {
+        ctx.require_access(None, resource::PRODUCT, action::UPDATE)?;
+        for component in components {
+            if component.component_variant_id == bundle_variant_id {
+                return Err(Error::ValidationError(
+                    "A bundle cannot contain itself".to_string(),
+                ));
+            }
+            if component.quantity <= 0 {
+                return Err(Error::ValidationError(
+                    "Component quantity must be greater than zero".to_string(),
+                ));
+            }
+        }
+        let bundle = self
+            .repository
+            .get_variant_by_id(ctx, bundle_variant_id)
+            .await?
+            .ok_or_else(|| {
+                Error::NotFound(format!("Variant with id {} not found", bundle_variant_id))
+            })?;
+        if bundle.product.product_type != ProductType::Bundle {
+            return Err(Error::ValidationError(format!(
+                "Variant {} does not belong to a bundle product",
+                bundle_variant_id
+            )));
+        }
+
+        let mut tx = self.tx_manager.begin().await?;
         if let Err(e) = self
             .repository
-            .delete_variants_by_product_id(ctx, id, &mut tx)
+            .set_components(ctx, bundle_variant_id, components, &mut tx)
             .await
         {
             let _ = self.tx_manager.rollback(tx).await;
@@ -171,33 +536,228 @@ where
         Ok(())
     }
 
+    async fn explode_bundle(
+        &self,
+        ctx: &Context,
+        bundle_variant_id: i64,
+    ) -> DomainResult<Vec<BundleComponent>> {
+        ctx.require_access(None, resource::PRODUCT, action::READ)?;
+        let bundle = self
+            .repository
+            .get_variant_by_id(ctx, bundle_variant_id)
+            .await?;
+        if !bundle.is_some_and(|b| b.product.product_type == ProductType::Bundle) {
+            return Err(Error::NotFound(format!(
+                "Bundle variant with id {} not found",
+                bundle_variant_id
+            )));
+        }
+
+        let components = self
+            .repository
+            .get_components(ctx, bundle_variant_id)
+            .await?;
+        if components
+            .iter()
+            .any(|c| c.component_variant_id == bundle_variant_id)
+        {
+            return Err(Error::ValidationError(format!(
+                "Bundle {} contains itself",
+                bundle_variant_id
+            )));
+        }
+
+        let ids: Vec<i64> = components.iter().map(|c| c.component_variant_id).collect();
+        let mut variants: HashMap<i64, ProductVariant> = self
+            .repository
+            .get_variants_by_ids(ctx, &ids)
+            .await?
+            .into_iter()
+            .map(|v| (v.id, v))
+            .collect();
+        let mut exploded = Vec::with_capacity(components.len());
+        let mut missing = Vec::new();
+        for component in components {
+            match variants.remove(&component.component_variant_id) {
+                Some(variant) => exploded.push(BundleComponent {
+                    variant,
+                    quantity: component.quantity,
+                }),
+                None => missing.push(component.component_variant_id.to_string()),
+            }
+        }
+        if !missing.is_empty() {
+            return Err(Error::NotFound(format!(
+                "Components {} of bundle {} not found",
+                missing.join(", "),
+                bundle_variant_id
+            )));
+        }
+        Ok(exploded)
+    }
+
+    async fn adjust_stock(
+        &self,
+        ctx: &Context,
+        variant_id: i64,
+        branch_id: i64,
+        delta: i64,
+        reason: &str,
+    ) -> DomainResult<i64> {
+        ctx.require_access(Some(branch_id), resource::PRODUCT, action::UPDATE)?;
+        let adjustment = StockAdjustment {
+            branch_id,
+            variant_id,
+            delta,
+            reason: reason.trim().to_string(),
+        };
+        adjustment.validate()?;
+        if self
+            .repository
+            .get_variant_by_id(ctx, variant_id)
+            .await?
+            .is_none()
+        {
+            return Err(Error::NotFound(format!(
+                "Variant with id {} not found",
+                variant_id
+            )));
+        }
+
+        let mut tx = self.tx_manager.begin().await?;
+        let quantity = match self
+            .stock_repository
+            .adjust(ctx, &adjustment, &mut tx)
+            .await
+        {
+            Ok(quantity) => quantity,
+            Err(e) => {
+                let _ = self.tx_manager.rollback(tx).await;
+                return Err(e);
+            }
+        };
+        self.tx_manager.commit(tx).await?;
+        Ok(quantity)
+    }
+
+    async fn list_low_stock(
+        &self,
+        ctx: &Context,
+        branch_id: i64,
+        threshold: i64,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<StockLevel>> {
+        ctx.require_access(Some(branch_id), resource::PRODUCT, action::READ)?;
+        ctx.cancellable(
+            self.stock_repository
+                .list_low(ctx, branch_id, threshold, pagination),
+        )
+        .await
+    }
+
     async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Product>> {
         ctx.require_access(None, resource::PRODUCT, action::READ)?;
         self.repository.get_by_id(ctx, id).await
     }
 
+    async fn get_product_with_variants(
+        &self,
+        ctx: &Context,
+        id: i64,
+    ) -> DomainResult<Option<ProductDetail>> {
+        ctx.require_access(None, resource::PRODUCT, action::READ)?;
+        let Some(product) = self.repository.get_by_id(ctx, id).await? else {
+            return Ok(None);
+        };
+        let variants = self.repository.get_variant_by_product_id(ctx, id).await?;
+        let category_ids = self.repository.get_product_category(ctx, id).await?;
+        Ok(Some(ProductDetail {
+            product,
+            variants,
+            category_ids,
+        }))
+    }
+
+    async fn get_by_category(
+        &self,
+        ctx: &Context,
+        category_id: i64,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<Product>> {
+        ctx.require_access(None, resource::PRODUCT, action::READ)?;
+        ctx.cancellable(self.repository.get_by_category(
+            ctx,
+            category_id,
+            sellable_only(ctx),
+            pagination,
+        ))
+        .await
+    }
+
+    async fn count_by_category(&self, ctx: &Context, category_id: i64) -> DomainResult<u64> {
+        ctx.require_access(None, resource::PRODUCT, action::READ)?;
+        ctx.cancellable(
+            self.repository
+                .count_by_category(ctx, category_id, sellable_only(ctx)),
+        )
+        .await
+    }
+
+    async fn get_all(
+        &self,
+        ctx: &Context,
+        filter: &ProductFilter,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<ProductWithVariants>> {
+        ctx.require_access(None, resource::PRODUCT, action::READ)?;
+        let filter = visible_products(ctx, filter);
+        ctx.cancellable(
+            self.repository
+                .get_all_with_variants(ctx, &filter, pagination),
+        )
+        .await
+    }
+
+    async fn count(&self, ctx: &Context, filter: &ProductFilter) -> DomainResult<u64> {
+        ctx.require_access(None, resource::PRODUCT, action::READ)?;
+        let filter = visible_products(ctx, filter);
+        ctx.cancellable(self.repository.count(ctx, &filter)).await
+    }
+
     async fn create_variant(
         &self,
         ctx: &Context,
         variant: &ProductVariantCreate,
     ) -> DomainResult<i64> {
         ctx.require_access(None, resource::PRODUCT, action::CREATE)?;
+        validate_price(variant.price.as_ref())?;
+        validate_optional_metadata(
+            self.variant_metadata_schema.as_ref(),
+            variant.metadata.as_ref(),
+        )?;
+        validate_initial_stock(ctx, variant)?;
         let mut tx = self.tx_manager.begin().await?;
         let variant_id = self.id_generator.generate()?;
-        match self
+        if let Err(e) = self
             .repository
             .create_variant(ctx, variant_id, variant, &mut tx)
             .await
         {
-            Ok(_) => {
-                self.tx_manager.commit(tx).await?;
-                Ok(variant_id)
-            }
-            Err(e) => {
+            let _ = self.tx_manager.rollback(tx).await;
+            return Err(e);
+        }
+        for adjustment in initial_stock_adjustments(variant_id, variant) {
+            if let Err(e) = self
+                .stock_repository
+                .adjust(ctx, &adjustment, &mut tx)
+                .await
+            {
                 let _ = self.tx_manager.rollback(tx).await;
-                Err(e)
+                return Err(e);
             }
         }
+        self.tx_manager.commit(tx).await?;
+        Ok(variant_id)
     }
 
     async fn update_variant(
@@ -207,7 +767,53 @@ where
         variant: &ProductVariantUpdate,
     ) -> DomainResult<()> {
         ctx.require_access(None, resource::PRODUCT, action::UPDATE)?;
-        self.repository.update_variant(ctx, id, variant).await
+        validate_price(variant.price.as_value())?;
+        validate_metadata_update(self.variant_metadata_schema.as_ref(), &variant.metadata)?;
+        let mut tx = self.tx_manager.begin().await?;
+        if let Err(e) = self
+            .repository
+            .update_variant(ctx, id, variant, &mut tx)
+            .await
+        {
+            let _ = self.tx_manager.rollback(tx).await;
+            return Err(e);
+        }
+        self.tx_manager.commit(tx).await?;
+        Ok(())
+    }
+
+    async fn upsert_variant_by_barcode(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+        variant: &ProductVariantUpsert,
+    ) -> DomainResult<(i64, bool)> {
+        ctx.require_access(None, resource::PRODUCT, action::CREATE | action::UPDATE)?;
+        if variant.barcode.trim().is_empty() {
+            return Err(Error::ValidationError(
+                "Barcode cannot be empty".to_string(),
+            ));
+        }
+        validate_optional_metadata(
+            self.variant_metadata_schema.as_ref(),
+            variant.metadata.as_ref(),
+        )?;
+        let mut tx = self.tx_manager.begin().await?;
+        let id = self.id_generator.generate()?;
+        match self
+            .repository
+            .upsert_variant_by_barcode(ctx, id, product_id, variant, &mut tx)
+            .await
+        {
+            Ok(result) => {
+                self.tx_manager.commit(tx).await?;
+                Ok(result)
+            }
+            Err(e) => {
+                let _ = self.tx_manager.rollback(tx).await;
+                Err(e)
+            }
+        }
     }
 
     async fn delete_variant(&self, ctx: &Context, id: i64) -> DomainResult<()> {
@@ -225,16 +831,17 @@ where
         }
     }
 
-    async fn delete_variants_by_product_id(
+    async fn reassign_variant(
         &self,
         ctx: &Context,
-        product_id: i64,
+        variant_id: i64,
+        new_product_id: i64,
     ) -> DomainResult<()> {
-        ctx.require_access(None, resource::PRODUCT, action::DELETE)?;
+        ctx.require_access(None, resource::PRODUCT, action::UPDATE)?;
         let mut tx = self.tx_manager.begin().await?;
         match self
             .repository
-            .delete_variants_by_product_id(ctx, product_id, &mut tx)
+            .reassign_variant(ctx, variant_id, new_product_id, &mut tx)
             .await
         {
             Ok(_) => {
@@ -248,19 +855,42 @@ where
         }
     }
 
-    async fn get_variant_by_barcode(
-        &self,
-        ctx: &Context,
-        barcode: &str,
-    ) -> DomainResult<Option<ProductVariant>> {
-        ctx.require_access(None, resource::PRODUCT, action::READ)?;
-        self.repository.get_variant_by_barcode(ctx, barcode).await
-    }
-
-    async fn get_variant_by_id(
+    async fn delete_variants_by_product_id(
         &self,
         ctx: &Context,
-        id: i64,
+        product_id: i64,
+    ) -> DomainResult<()> {
+        ctx.require_access(None, resource::PRODUCT, action::DELETE)?;
+        let mut tx = self.tx_manager.begin().await?;
+        match self
+            .repository
+            .delete_variants_by_product_id(ctx, product_id, &mut tx)
+            .await
+        {
+            Ok(_) => {
+                self.tx_manager.commit(tx).await?;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = self.tx_manager.rollback(tx).await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn get_variant_by_barcode(
+        &self,
+        ctx: &Context,
+        barcode: &str,
+    ) -> DomainResult<Option<ProductVariant>> {
+        ctx.require_access(None, resource::PRODUCT, action::READ)?;
+        self.repository.get_variant_by_barcode(ctx, barcode).await
+    }
+
+    async fn get_variant_by_id(
+        &self,
+        ctx: &Context,
+        id: i64,
     ) -> DomainResult<Option<ProductVariant>> {
         ctx.require_access(None, resource::PRODUCT, action::READ)?;
         self.repository.get_variant_by_id(ctx, id).await
@@ -276,18 +906,79 @@ where
             .get_variant_by_product_id(ctx, product_id)
             .await
     }
+
+    async fn get_variants(
+        &self,
+        ctx: &Context,
+        filter: &ProductVariantFilter,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<ProductVariant>> {
+        ctx.require_access(None, resource::PRODUCT, action::READ)?;
+        ctx.cancellable(self.repository.get_variants(ctx, filter, pagination))
+            .await
+    }
+
+    async fn count_variants(&self, ctx: &Context, product_id: i64) -> DomainResult<u64> {
+        ctx.require_access(None, resource::PRODUCT, action::READ)?;
+        self.repository.count_variants(ctx, product_id).await
+    }
+
+    async fn get_categories(&self, ctx: &Context, product_id: i64) -> DomainResult<Vec<i64>> {
+        ctx.require_access(None, resource::PRODUCT, action::READ)?;
+        self.repository.get_product_category(ctx, product_id).await
+    }
+
+    async fn price_for(&self, ctx: &Context, variant_id: i64, level: i32) -> DomainResult<Money> {
+        ctx.require_access(None, resource::PRODUCT, action::READ)?;
+        let variant = self
+            .repository
+            .get_variant_by_id(ctx, variant_id)
+            .await?
+            .ok_or_else(|| {
+                Error::NotFound(format!("ProductVariant with id {} not found", variant_id))
+            })?;
+
+        if let Some(price) = self
+            .repository
+            .get_level_price(ctx, variant_id, level)
+            .await?
+        {
+            return Ok(price);
+        }
+
+        variant.price.ok_or_else(|| {
+            Error::NotFound(format!(
+                "ProductVariant with id {} has no base price",
+                variant_id
+            ))
+        })
+    }
+
+    async fn get_price_history(
+        &self,
+        ctx: &Context,
+        variant_id: i64,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<VariantPriceChange>> {
+        ctx.require_access(None, resource::PRODUCT, action::READ)?;
+        ctx.cancellable(
+            self.repository
+                .get_price_history(ctx, variant_id, pagination),
+        )
+        .await
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::application::{MockIdGen, create_mock_id_gen};
-    use crate::domain::Error;
     use crate::domain::model::Update;
+    use crate::domain::model::stock::InitialStock;
+    use crate::testing::metadata::sku_schema;
     use async_trait::async_trait;
     use chrono::Utc;
     use mockall::mock;
-    use std::collections::HashMap;
 
     // Mock transaction type - simple unit struct for testing
     #[derive(Debug)]
@@ -300,15 +991,44 @@ mod tests {
             async fn create_product(&self, ctx: &Context, id: i64, product: &ProductCreate, tx: &mut MockTx) -> DomainResult<()>;
             async fn update_product(&self, ctx: &Context, id: i64, product: &ProductUpdate, tx: &mut MockTx) -> DomainResult<()>;
             async fn delete_product(&self, ctx: &Context, id: i64, tx: &mut MockTx) -> DomainResult<()>;
+            async fn touch(&self, ctx: &Context, id: i64, tx: &mut MockTx) -> DomainResult<()>;
             async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Product>>;
+            async fn get_by_category(&self, ctx: &Context, category_id: i64, sellable_only: bool, pagination: &PaginationOptions) -> DomainResult<Vec<Product>>;
+            async fn count_by_category(&self, ctx: &Context, category_id: i64, sellable_only: bool) -> DomainResult<u64>;
+            async fn get_all_with_variants(&self, ctx: &Context, filter: &ProductFilter, pagination: &PaginationOptions) -> DomainResult<Vec<ProductWithVariants>>;
+            async fn count(&self, ctx: &Context, filter: &ProductFilter) -> DomainResult<u64>;
             async fn create_variant(&self, ctx: &Context, id: i64, variant: &ProductVariantCreate, tx: &mut MockTx) -> DomainResult<()>;
-            async fn update_variant(&self, ctx: &Context, id: i64, variant: &ProductVariantUpdate) -> DomainResult<()>;
+            async fn update_variant(&self, ctx: &Context, id: i64, variant: &ProductVariantUpdate, tx: &mut MockTx) -> DomainResult<()>;
+            async fn upsert_variant_by_barcode(&self, ctx: &Context, id: i64, product_id: i64, variant: &ProductVariantUpsert, tx: &mut MockTx) -> DomainResult<(i64, bool)>;
             async fn delete_variant(&self, ctx: &Context, id: i64, tx: &mut MockTx) -> DomainResult<()>;
+            async fn reassign_variant(&self, ctx: &Context, variant_id: i64, new_product_id: i64, tx: &mut MockTx) -> DomainResult<()>;
             async fn delete_variants_by_product_id(&self, ctx: &Context, product_id: i64, tx: &mut MockTx) -> DomainResult<()>;
             async fn get_variant_by_barcode(&self, ctx: &Context, barcode: &str) -> DomainResult<Option<ProductVariant>>;
+            async fn get_variants_by_barcode(&self, ctx: &Context, barcode: &str) -> DomainResult<Vec<ProductVariant>>;
             async fn get_variant_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<ProductVariant>>;
+            async fn get_variants_by_ids(&self, ctx: &Context, ids: &[i64]) -> DomainResult<Vec<ProductVariant>>;
             async fn get_variant_by_product_id(&self, ctx: &Context, product_id: i64) -> DomainResult<Vec<ProductVariant>>;
+            async fn get_variants(&self, ctx: &Context, filter: &ProductVariantFilter, pagination: &PaginationOptions) -> DomainResult<Vec<ProductVariant>>;
+            async fn count_variants(&self, ctx: &Context, product_id: i64) -> DomainResult<u64>;
             async fn get_product_category(&self, ctx: &Context, product_id: i64) -> DomainResult<Vec<i64>>;
+            async fn add_categories(&self, ctx: &Context, product_id: i64, category_ids: &[i64], tx: &mut MockTx) -> DomainResult<()>;
+            async fn remove_categories(&self, ctx: &Context, product_id: i64, category_ids: &[i64], tx: &mut MockTx) -> DomainResult<()>;
+            async fn set_suppliers(&self, ctx: &Context, product_id: i64, suppliers: &[ProductSupplier], tx: &mut MockTx) -> DomainResult<()>;
+            async fn get_suppliers(&self, ctx: &Context, product_id: i64) -> DomainResult<Vec<ProductSupplier>>;
+            async fn set_components(&self, ctx: &Context, bundle_id: i64, components: &[ProductComponent], tx: &mut MockTx) -> DomainResult<()>;
+            async fn get_components(&self, ctx: &Context, bundle_id: i64) -> DomainResult<Vec<ProductComponent>>;
+            async fn set_level_price(&self, ctx: &Context, variant_id: i64, customer_level: i32, price: &Money) -> DomainResult<()>;
+            async fn get_level_price(&self, ctx: &Context, variant_id: i64, customer_level: i32) -> DomainResult<Option<Money>>;
+            async fn get_price_history(&self, ctx: &Context, variant_id: i64, pagination: &PaginationOptions) -> DomainResult<Vec<VariantPriceChange>>;
+        }
+    }
+
+    mock! {
+        pub StockRepo {}
+        #[async_trait]
+        impl StockRepository<MockTx> for StockRepo {
+            async fn adjust(&self, ctx: &Context, adjustment: &StockAdjustment, tx: &mut MockTx) -> DomainResult<i64>;
+            async fn list_low(&self, ctx: &Context, branch_id: i64, threshold: i64, pagination: &PaginationOptions) -> DomainResult<Vec<StockLevel>>;
         }
     }
 
@@ -351,17 +1071,25 @@ mod tests {
         }
     }
 
+    type TestProductService =
+        ProductService<MockProductRepo, MockStockRepo, MockTxManager, MockIdGen>;
+
     /// Helper to create the service with correct types
     fn create_service(
         mock_repo: MockProductRepo,
         mock_tx: MockTxManager,
         mock_id_generator: MockIdGen,
-    ) -> ProductService<MockProductRepo, MockTxManager, MockIdGen> {
-        ProductService {
-            repository: mock_repo,
-            tx_manager: mock_tx,
-            id_generator: mock_id_generator,
-        }
+    ) -> TestProductService {
+        create_service_with_stock(mock_repo, MockStockRepo::new(), mock_tx, mock_id_generator)
+    }
+
+    fn create_service_with_stock(
+        mock_repo: MockProductRepo,
+        mock_stock_repo: MockStockRepo,
+        mock_tx: MockTxManager,
+        mock_id_generator: MockIdGen,
+    ) -> TestProductService {
+        ProductService::new(mock_repo, mock_stock_repo, mock_tx, mock_id_generator)
     }
 
     /// Creates a test context with full permissions for PRODUCT resource
@@ -380,7 +1108,7 @@ mod tests {
         ProductCreate {
             name: "Test Product".to_string(),
             description: Some("A test product".to_string()),
-            product_type: "product".to_string(),
+            product_type: ProductType::Product,
             main_image: Some("https://example.com/image.jpg".to_string()),
             sellable: true,
             buyable: true,
@@ -396,7 +1124,9 @@ mod tests {
             product_id,
             barcode: Some("1234567890".to_string()),
             name: Some("Default Variant".to_string()),
+            price: None,
             metadata: None,
+            initial_stock: vec![],
         }
     }
 
@@ -409,13 +1139,15 @@ mod tests {
             is_deleted: false,
             name: "Test Product".to_string(),
             description: Some("A test product".to_string()),
-            product_type: "product".to_string(),
+            product_type: ProductType::Product,
             main_image: Some("https://example.com/image.jpg".to_string()),
             sellable: true,
             buyable: true,
             editable_price: false,
             has_variant: true,
             metadata: None,
+            created_by: None,
+            updated_by: None,
         }
     }
 
@@ -429,6 +1161,7 @@ mod tests {
             product: create_test_product(),
             barcode: Some("1234567890".to_string()),
             name: Some("Default Variant".to_string()),
+            price: None,
             metadata: None,
         }
     }
@@ -452,6 +1185,7 @@ mod tests {
         ProductVariantUpdate {
             barcode: Update::Set("9876543210".to_string()),
             name: Update::Unchanged,
+            price: Update::Unchanged,
             metadata: Update::Unchanged,
         }
     }
@@ -509,7 +1243,9 @@ mod tests {
                 product_id: 1,
                 barcode: Some("0987654321".to_string()),
                 name: Some("Second Variant".to_string()),
+                price: None,
                 metadata: None,
+                initial_stock: vec![],
             },
         ];
         let result = service.create_product(&ctx, &product, &variants).await;
@@ -517,6 +1253,162 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_create_product_metadata_matches_schema() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_create_product()
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+        mock_repo
+            .expect_create_variant()
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1))
+            .with_product_metadata_schema(sku_schema())
+            .with_variant_metadata_schema(sku_schema());
+        let product = ProductCreate {
+            metadata: Some(serde_json::json!({"sku": "P-1"})),
+            ..create_test_product_create()
+        };
+        let variant = ProductVariantCreate {
+            metadata: Some(serde_json::json!({"sku": "P-1-RED"})),
+            ..create_test_variant_create(1)
+        };
+        let result = service.create_product(&ctx, &product, &[variant]).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_product_metadata_missing_sku() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo.expect_create_product().times(0);
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1))
+            .with_product_metadata_schema(sku_schema());
+        let product = ProductCreate {
+            metadata: Some(serde_json::json!({"brand": "Acme"})),
+            ..create_test_product_create()
+        };
+        let result = service.create_product(&ctx, &product, &[]).await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_product_variant_metadata_missing_sku() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo.expect_create_product().times(0);
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1))
+            .with_variant_metadata_schema(sku_schema());
+        let product = create_test_product_create();
+        let variant = ProductVariantCreate {
+            metadata: Some(serde_json::json!({"color": "red"})),
+            ..create_test_variant_create(1)
+        };
+        let result = service.create_product(&ctx, &product, &[variant]).await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_product_invalid_input() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo.expect_create_product().times(0);
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let product = ProductCreate {
+            name: " ".to_string(),
+            ..create_test_product_create()
+        };
+        let result = service.create_product(&ctx, &product, &[]).await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_product_writes_initial_stock() {
+        let mut mock_repo = MockProductRepo::new();
+        let mut mock_stock_repo = MockStockRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_create_product()
+            .returning(|_, _, _, _| Ok(()));
+        mock_repo
+            .expect_create_variant()
+            .returning(|_, _, _, _| Ok(()));
+        mock_stock_repo
+            .expect_adjust()
+            .withf(|_, adjustment, _| {
+                adjustment.delta == 4 && adjustment.reason == INITIAL_STOCK_REASON
+            })
+            .times(2)
+            .returning(|_, _, _| Ok(4));
+
+        let service = create_service_with_stock(
+            mock_repo,
+            mock_stock_repo,
+            MockTxManager::new(),
+            create_mock_id_gen(1),
+        );
+        let variant = ProductVariantCreate {
+            initial_stock: vec![
+                InitialStock {
+                    branch_id: 7,
+                    quantity: 4,
+                },
+                InitialStock {
+                    branch_id: 8,
+                    quantity: 4,
+                },
+            ],
+            ..create_test_variant_create(0)
+        };
+        let result = service
+            .create_product(&ctx, &create_test_product_create(), &[variant])
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_product_rejects_invalid_initial_stock() {
+        let mut mock_repo = MockProductRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo.expect_create_product().times(0);
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let variant = ProductVariantCreate {
+            initial_stock: vec![InitialStock {
+                branch_id: 7,
+                quantity: 0,
+            }],
+            ..create_test_variant_create(0)
+        };
+        let result = service
+            .create_product(&ctx, &create_test_product_create(), &[variant])
+            .await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
     #[tokio::test]
     async fn test_create_product_no_permission() {
         let mock_repo = MockProductRepo::new();
@@ -641,235 +1533,1242 @@ mod tests {
     }
 
     // =============================================================================
-    // Delete Product Tests
+    // Product Category Tests
     // =============================================================================
 
     #[tokio::test]
-    async fn test_delete_product_success() {
+    async fn test_add_categories_success() {
         let mut mock_repo = MockProductRepo::new();
-        let mock_tx = MockTxManager::new();
         let ctx = create_test_context();
 
-        // begin returns MockTx by default
-        // commit returns Ok by default
+        mock_repo
+            .expect_add_categories()
+            .withf(|_, product_id, category_ids, _| *product_id == 1 && category_ids == [5])
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service.add_categories(&ctx, 1, &[5]).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_add_categories_no_permission() {
+        let mock_repo = MockProductRepo::new();
+        let ctx = create_no_permission_context();
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service.add_categories(&ctx, 1, &[5]).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_remove_categories_product_not_found() {
+        let mut mock_repo = MockProductRepo::new();
+        let ctx = create_test_context();
 
         mock_repo
-            .expect_delete_product()
-            .withf(|_, id, _| *id == 1)
+            .expect_remove_categories()
             .times(1)
-            .returning(|_, _, _| Ok(()));
+            .returning(|_, _, _, _| Err(Error::NotFound("Product not found".to_string())));
+
+        let service = create_service(
+            mock_repo,
+            MockTxManager::new().expect_rollback(),
+            create_mock_id_gen(1),
+        );
+        let result = service.remove_categories(&ctx, 999, &[5]).await;
+
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    // =============================================================================
+    // Product Supplier Tests
+    // =============================================================================
+
+    fn create_test_product_supplier(supplier_id: i64, cost: i64) -> ProductSupplier {
+        ProductSupplier {
+            supplier_id,
+            supplier_sku: Some(format!("SKU-{}", supplier_id)),
+            cost: Some(Money::from_minor(cost)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_suppliers_success() {
+        let mut mock_repo = MockProductRepo::new();
+        let ctx = create_test_context();
 
         mock_repo
-            .expect_delete_variants_by_product_id()
-            .withf(|_, id, _| *id == 1)
+            .expect_set_suppliers()
+            .withf(|_, product_id, suppliers, _| *product_id == 1 && suppliers.len() == 2)
             .times(1)
-            .returning(|_, _, _| Ok(()));
+            .returning(|_, _, _, _| Ok(()));
 
-        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
-        let result = service.delete_product(&ctx, 1).await;
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let suppliers = [
+            create_test_product_supplier(10, 5000),
+            create_test_product_supplier(11, 4800),
+        ];
+        let result = service.set_suppliers(&ctx, 1, &suppliers).await;
 
         assert!(result.is_ok());
     }
 
     #[tokio::test]
-    async fn test_delete_product_no_permission() {
+    async fn test_set_suppliers_with_supplier_permission() {
+        let mut mock_repo = MockProductRepo::new();
+        let mut permissions = HashMap::new();
+        permissions.insert((resource::SUPPLIER, None), action::UPDATE);
+        let ctx = Context::new_with_all(None, permissions, HashMap::new());
+
+        mock_repo
+            .expect_set_suppliers()
+            .withf(|_, product_id, suppliers, _| *product_id == 1 && suppliers.len() == 1)
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service
+            .set_suppliers(&ctx, 1, &[create_test_product_supplier(10, 5000)])
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_set_suppliers_negative_cost() {
+        let mock_repo = MockProductRepo::new();
+        let ctx = create_test_context();
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service
+            .set_suppliers(&ctx, 1, &[create_test_product_supplier(10, -1)])
+            .await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_set_suppliers_no_permission() {
         let mock_repo = MockProductRepo::new();
-        let mock_tx = MockTxManager::new();
         let ctx = create_no_permission_context();
 
-        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
-        let result = service.delete_product(&ctx, 1).await;
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service
+            .set_suppliers(&ctx, 1, &[create_test_product_supplier(10, 5000)])
+            .await;
 
         assert!(matches!(result, Err(Error::Forbidden(_))));
     }
 
     #[tokio::test]
-    async fn test_delete_product_not_found() {
+    async fn test_get_suppliers_with_supplier_permission() {
         let mut mock_repo = MockProductRepo::new();
-        let mock_tx = MockTxManager::new();
-        let ctx = create_test_context();
-
-        // begin returns MockTx by default
-        let mock_tx = mock_tx.expect_rollback();
+        let mut permissions = HashMap::new();
+        permissions.insert((resource::SUPPLIER, None), action::READ);
+        let ctx = Context::new_with_all(None, permissions, HashMap::new());
 
         mock_repo
-            .expect_delete_product()
+            .expect_get_suppliers()
+            .withf(|_, product_id| *product_id == 1)
             .times(1)
-            .returning(|_, _, _| Err(Error::NotFound("Product not found".to_string())));
+            .returning(|_, _| Ok(vec![create_test_product_supplier(10, 5000)]));
 
-        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
-        let result = service.delete_product(&ctx, 999).await;
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service.get_suppliers(&ctx, 1).await.unwrap();
 
-        assert!(matches!(result, Err(Error::NotFound(_))));
+        assert_eq!(result, vec![create_test_product_supplier(10, 5000)]);
+    }
+
+    #[tokio::test]
+    async fn test_get_suppliers_no_permission() {
+        let mock_repo = MockProductRepo::new();
+        let ctx = create_no_permission_context();
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service.get_suppliers(&ctx, 1).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
     }
 
     // =============================================================================
-    // Get Product Tests
+    // Bundle Component Tests
     // =============================================================================
 
+    fn create_test_bundle_variant() -> ProductVariant {
+        let mut variant = create_test_variant();
+        variant.product.product_type = ProductType::Bundle;
+        variant
+    }
+
+    fn component(component_variant_id: i64, quantity: i64) -> ProductComponent {
+        ProductComponent {
+            component_variant_id,
+            quantity,
+        }
+    }
+
     #[tokio::test]
-    async fn test_get_by_id_success() {
+    async fn test_set_components_success() {
         let mut mock_repo = MockProductRepo::new();
-        let mock_tx = MockTxManager::new();
         let ctx = create_test_context();
 
         mock_repo
-            .expect_get_by_id()
-            .withf(|_, id| *id == 1)
+            .expect_get_variant_by_id()
+            .returning(|_, _| Ok(Some(create_test_bundle_variant())));
+        mock_repo
+            .expect_set_components()
+            .withf(|_, bundle_id, components, _| *bundle_id == 100 && components.len() == 2)
             .times(1)
-            .returning(|_, _| Ok(Some(create_test_product())));
+            .returning(|_, _, _, _| Ok(()));
 
-        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
-        let result = service.get_by_id(&ctx, 1).await;
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service
+            .set_components(&ctx, 100, &[component(101, 2), component(102, 1)])
+            .await;
 
         assert!(result.is_ok());
-        let product = result.unwrap();
-        assert!(product.is_some());
-        assert_eq!(product.unwrap().name, "Test Product");
     }
 
     #[tokio::test]
-    async fn test_get_by_id_not_found() {
+    async fn test_set_components_rejects_self_reference() {
+        let mut mock_repo = MockProductRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo.expect_set_components().times(0);
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service
+            .set_components(&ctx, 100, &[component(101, 1), component(100, 1)])
+            .await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_set_components_requires_bundle_product() {
+        let mut mock_repo = MockProductRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_variant_by_id()
+            .returning(|_, _| Ok(Some(create_test_variant())));
+        mock_repo.expect_set_components().times(0);
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service
+            .set_components(&ctx, 100, &[component(101, 1)])
+            .await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_explode_bundle() {
+        let mut mock_repo = MockProductRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_variant_by_id()
+            .withf(|_, id| *id == 100)
+            .returning(|_, _| Ok(Some(create_test_bundle_variant())));
+        mock_repo
+            .expect_get_components()
+            .withf(|_, bundle_id| *bundle_id == 100)
+            .returning(|_, _| Ok(vec![component(101, 2), component(102, 1)]));
+        mock_repo
+            .expect_get_variants_by_ids()
+            .withf(|_, ids| ids == [101, 102])
+            .times(1)
+            .returning(|_, ids| {
+                Ok(ids
+                    .iter()
+                    .map(|&id| ProductVariant {
+                        id,
+                        ..create_test_variant()
+                    })
+                    .collect())
+            });
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let exploded = service.explode_bundle(&ctx, 100).await.unwrap();
+
+        let exploded: Vec<(i64, i64)> = exploded
+            .iter()
+            .map(|c| (c.variant.id, c.quantity))
+            .collect();
+        assert_eq!(exploded, vec![(101, 2), (102, 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_explode_bundle_fails_on_deleted_components() {
+        let mut mock_repo = MockProductRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_variant_by_id()
+            .returning(|_, _| Ok(Some(create_test_bundle_variant())));
+        mock_repo
+            .expect_get_components()
+            .returning(|_, _| Ok(vec![component(101, 2), component(102, 1)]));
+        // 101 has been deleted since it was added to the bundle
+        mock_repo.expect_get_variants_by_ids().returning(|_, _| {
+            Ok(vec![ProductVariant {
+                id: 102,
+                ..create_test_variant()
+            }])
+        });
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service.explode_bundle(&ctx, 100).await;
+
+        match result {
+            Err(Error::NotFound(message)) => {
+                assert_eq!(message, "Components 101 of bundle 100 not found")
+            }
+            other => panic!("Expected NotFound, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_explode_bundle_not_found() {
+        let mut mock_repo = MockProductRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_variant_by_id()
+            .returning(|_, _| Ok(None));
+        mock_repo.expect_get_components().times(0);
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service.explode_bundle(&ctx, 100).await;
+
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_explode_bundle_requires_bundle_product() {
+        let mut mock_repo = MockProductRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_variant_by_id()
+            .returning(|_, _| Ok(Some(create_test_variant())));
+        mock_repo.expect_get_components().times(0);
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service.explode_bundle(&ctx, 100).await;
+
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_explode_bundle_rejects_self_reference() {
+        let mut mock_repo = MockProductRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_variant_by_id()
+            .returning(|_, _| Ok(Some(create_test_bundle_variant())));
+        mock_repo
+            .expect_get_components()
+            .returning(|_, _| Ok(vec![component(100, 1)]));
+        mock_repo.expect_get_variants_by_ids().times(0);
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service.explode_bundle(&ctx, 100).await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_explode_bundle_no_permission() {
+        let mock_repo = MockProductRepo::new();
+        let ctx = create_no_permission_context();
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service.explode_bundle(&ctx, 100).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    // =============================================================================
+    // Stock Adjustment Tests
+    // =============================================================================
+
+    #[tokio::test]
+    async fn test_adjust_stock_success() {
+        let mut mock_repo = MockProductRepo::new();
+        let mut mock_stock_repo = MockStockRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_variant_by_id()
+            .withf(|_, id| *id == 100)
+            .returning(|_, _| Ok(Some(create_test_variant())));
+        mock_stock_repo
+            .expect_adjust()
+            .withf(|_, adjustment, _| {
+                *adjustment
+                    == StockAdjustment {
+                        branch_id: 7,
+                        variant_id: 100,
+                        delta: -2,
+                        reason: "damaged".to_string(),
+                    }
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(8));
+
+        let service = create_service_with_stock(
+            mock_repo,
+            mock_stock_repo,
+            MockTxManager::new(),
+            create_mock_id_gen(1),
+        );
+        let quantity = service
+            .adjust_stock(&ctx, 100, 7, -2, " damaged ")
+            .await
+            .unwrap();
+
+        assert_eq!(quantity, 8);
+    }
+
+    #[tokio::test]
+    async fn test_adjust_stock_no_permission() {
+        let mut mock_repo = MockProductRepo::new();
+        let mut mock_stock_repo = MockStockRepo::new();
+        let ctx = create_no_permission_context();
+
+        mock_repo.expect_get_variant_by_id().times(0);
+        mock_stock_repo.expect_adjust().times(0);
+
+        let service = create_service_with_stock(
+            mock_repo,
+            mock_stock_repo,
+            MockTxManager::new(),
+            create_mock_id_gen(1),
+        );
+        let result = service.adjust_stock(&ctx, 100, 7, 5, "recount").await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_adjust_stock_variant_not_found() {
+        let mut mock_repo = MockProductRepo::new();
+        let mut mock_stock_repo = MockStockRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_variant_by_id()
+            .returning(|_, _| Ok(None));
+        mock_stock_repo.expect_adjust().times(0);
+
+        let service = create_service_with_stock(
+            mock_repo,
+            mock_stock_repo,
+            MockTxManager::new(),
+            create_mock_id_gen(1),
+        );
+        let result = service.adjust_stock(&ctx, 999, 7, 5, "recount").await;
+
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_low_stock_delegates() {
+        let mut mock_stock_repo = MockStockRepo::new();
+        let ctx = create_test_context();
+
+        mock_stock_repo
+            .expect_list_low()
+            .withf(|_, branch_id, threshold, _| *branch_id == 7 && *threshold == 3)
+            .times(1)
+            .returning(|_, _, _, _| Ok(vec![]));
+
+        let service = create_service_with_stock(
+            MockProductRepo::new(),
+            mock_stock_repo,
+            MockTxManager::new(),
+            create_mock_id_gen(1),
+        );
+        let result = service
+            .list_low_stock(&ctx, 7, 3, &PaginationOptions::new(1, 20, None))
+            .await;
+
+        assert!(result.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_low_stock_respects_branch_permission() {
+        let mut mock_stock_repo = MockStockRepo::new();
+        // Product read only in branch 7
+        let ctx = Context::new_with_all(
+            None,
+            HashMap::from([((resource::PRODUCT, Some(7)), action::READ)]),
+            HashMap::new(),
+        );
+
+        mock_stock_repo
+            .expect_list_low()
+            .withf(|_, branch_id, _, _| *branch_id == 7)
+            .times(1)
+            .returning(|_, _, _, _| Ok(vec![]));
+
+        let service = create_service_with_stock(
+            MockProductRepo::new(),
+            mock_stock_repo,
+            MockTxManager::new(),
+            create_mock_id_gen(1),
+        );
+        let pagination = PaginationOptions::new(1, 20, None);
+        assert!(
+            service
+                .list_low_stock(&ctx, 7, 3, &pagination)
+                .await
+                .is_ok()
+        );
+        let result = service.list_low_stock(&ctx, 8, 3, &pagination).await;
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_adjust_stock_invalid_input() {
+        let mut mock_stock_repo = MockStockRepo::new();
+        let ctx = create_test_context();
+
+        mock_stock_repo.expect_adjust().times(0);
+
+        let service = create_service_with_stock(
+            MockProductRepo::new(),
+            mock_stock_repo,
+            MockTxManager::new(),
+            create_mock_id_gen(1),
+        );
+        for (delta, reason) in [(0, "recount"), (5, " ")] {
+            let result = service.adjust_stock(&ctx, 100, 7, delta, reason).await;
+            assert!(matches!(result, Err(Error::ValidationError(_))));
+        }
+    }
+
+    // =============================================================================
+    // Delete Product Tests
+    // =============================================================================
+
+    #[tokio::test]
+    async fn test_delete_product_success() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        // begin returns MockTx by default
+        // commit returns Ok by default
+
+        mock_repo
+            .expect_delete_product()
+            .withf(|_, id, _| *id == 1)
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        // The repository cascades to the variants itself
+        mock_repo.expect_delete_variants_by_product_id().never();
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.delete_product(&ctx, 1).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_delete_product_no_permission() {
+        let mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_no_permission_context();
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.delete_product(&ctx, 1).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_product_not_found() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        // begin returns MockTx by default
+        let mock_tx = mock_tx.expect_rollback();
+
+        mock_repo
+            .expect_delete_product()
+            .times(1)
+            .returning(|_, _, _| Err(Error::NotFound("Product not found".to_string())));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.delete_product(&ctx, 999).await;
+
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    // =============================================================================
+    // Get Product Tests
+    // =============================================================================
+
+    #[tokio::test]
+    async fn test_get_by_id_success() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_by_id()
+            .withf(|_, id| *id == 1)
+            .times(1)
+            .returning(|_, _| Ok(Some(create_test_product())));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.get_by_id(&ctx, 1).await;
+
+        assert!(result.is_ok());
+        let product = result.unwrap();
+        assert!(product.is_some());
+        assert_eq!(product.unwrap().name, "Test Product");
+    }
+
+    #[tokio::test]
+    async fn test_get_by_id_not_found() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_by_id()
+            .times(1)
+            .returning(|_, _| Ok(None));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.get_by_id(&ctx, 999).await;
+
+        assert!(result.is_ok());
+        assert!(result.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_by_id_no_permission() {
+        let mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_no_permission_context();
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.get_by_id(&ctx, 1).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_product_with_variants_success() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_by_id()
+            .withf(|_, id| *id == 1)
+            .times(1)
+            .returning(|_, _| Ok(Some(create_test_product())));
+        mock_repo
+            .expect_get_variant_by_product_id()
+            .withf(|_, product_id| *product_id == 1)
+            .times(1)
+            .returning(|_, _| Ok(vec![create_test_variant()]));
+        mock_repo
+            .expect_get_product_category()
+            .withf(|_, product_id| *product_id == 1)
+            .times(1)
+            .returning(|_, _| Ok(vec![3, 7]));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let detail = service
+            .get_product_with_variants(&ctx, 1)
+            .await
+            .unwrap()
+            .expect("Product should be found");
+
+        assert_eq!(detail.product.id, 1);
+        assert_eq!(detail.variants.len(), 1);
+        assert_eq!(detail.variants[0].id, 100);
+        assert_eq!(detail.category_ids, vec![3, 7]);
+    }
+
+    #[tokio::test]
+    async fn test_get_product_with_variants_not_found() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_by_id()
+            .times(1)
+            .returning(|_, _| Ok(None));
+        mock_repo.expect_get_variant_by_product_id().never();
+        mock_repo.expect_get_product_category().never();
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.get_product_with_variants(&ctx, 999).await;
+
+        assert!(result.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_product_with_variants_no_permission() {
+        let mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_no_permission_context();
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.get_product_with_variants(&ctx, 1).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_by_category_success() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_by_category()
+            .withf(|_, category_id, sellable_only, pagination| {
+                *category_id == 7 && !*sellable_only && pagination.page == 2
+            })
+            .times(1)
+            .returning(|_, _, _, _| Ok(vec![create_test_product()]));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service
+            .get_by_category(&ctx, 7, &PaginationOptions::new(2, 10, None))
+            .await;
+
+        assert_eq!(result.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_by_category_no_permission() {
+        let mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_no_permission_context();
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service
+            .get_by_category(&ctx, 7, &PaginationOptions::new(1, 10, None))
+            .await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_count_success() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_count()
+            .withf(|_, filter| filter.product_type == Some(ProductType::Service))
+            .times(1)
+            .returning(|_, _| Ok(3));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let filter = ProductFilter {
+            product_type: Some(ProductType::Service),
+            ..Default::default()
+        };
+        let result = service.count(&ctx, &filter).await;
+
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_count_no_permission() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_no_permission_context();
+
+        mock_repo.expect_count().never();
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.count(&ctx, &ProductFilter::default()).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    // =============================================================================
+    // Sellable Visibility Tests
+    // =============================================================================
+
+    /// Can read products but not manage them
+    fn create_cashier_context() -> Context {
+        let permissions = HashMap::from([((resource::PRODUCT, None), action::READ)]);
+        Context::new_with_all(None, permissions, HashMap::new())
+    }
+
+    #[tokio::test]
+    async fn test_get_all_cashier_only_sees_sellable() {
+        let mut mock_repo = MockProductRepo::new();
+        let ctx = create_cashier_context();
+
+        mock_repo
+            .expect_get_all_with_variants()
+            .withf(|_, filter, _| filter.sellable == Some(true) && filter.name.is_some())
+            .times(1)
+            .returning(|_, _, _| Ok(vec![]));
+        mock_repo
+            .expect_count()
+            .withf(|_, filter| filter.sellable == Some(true))
+            .times(1)
+            .returning(|_, _| Ok(0));
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        // Asking for non-sellable products does not get around the rule
+        let filter = ProductFilter {
+            name: Some("coffee".to_string()),
+            sellable: Some(false),
+            ..Default::default()
+        };
+        let pagination = PaginationOptions::new(1, 20, None);
+        assert!(service.get_all(&ctx, &filter, &pagination).await.is_ok());
+        assert!(service.count(&ctx, &filter).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_by_category_cashier_only_sees_sellable() {
+        let mut mock_repo = MockProductRepo::new();
+        let ctx = create_cashier_context();
+
+        mock_repo
+            .expect_get_by_category()
+            .withf(|_, category_id, sellable_only, _| *category_id == 7 && *sellable_only)
+            .times(1)
+            .returning(|_, _, _, _| Ok(vec![]));
+        mock_repo
+            .expect_count_by_category()
+            .withf(|_, category_id, sellable_only| *category_id == 7 && *sellable_only)
+            .times(1)
+            .returning(|_, _, _| Ok(0));
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let products = service
+            .get_by_category(&ctx, 7, &PaginationOptions::new(1, 10, None))
+            .await
+            .unwrap();
+        let total = service.count_by_category(&ctx, 7).await.unwrap();
+
+        assert!(products.is_empty());
+        assert_eq!(total, 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_all_manager_sees_all_products() {
+        let mut mock_repo = MockProductRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_all_with_variants()
+            .withf(|_, filter, _| filter.sellable.is_none())
+            .times(1)
+            .returning(|_, _, _| {
+                Ok(vec![ProductWithVariants {
+                    product: Product {
+                        sellable: false,
+                        ..create_test_product()
+                    },
+                    variants: vec![],
+                }])
+            });
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let products = service
+            .get_all(
+                &ctx,
+                &ProductFilter::default(),
+                &PaginationOptions::new(1, 20, None),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(products.len(), 1);
+        assert!(!products[0].product.sellable);
+    }
+
+    #[tokio::test]
+    async fn test_get_all_no_permission() {
+        let mut mock_repo = MockProductRepo::new();
+        let ctx = create_no_permission_context();
+
+        mock_repo.expect_get_all_with_variants().never();
+
+        let service = create_service(mock_repo, MockTxManager::new(), create_mock_id_gen(1));
+        let result = service
+            .get_all(
+                &ctx,
+                &ProductFilter::default(),
+                &PaginationOptions::new(1, 20, None),
+            )
+            .await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    // =============================================================================
+    // Create Variant Tests
+    // =============================================================================
+
+    #[tokio::test]
+    async fn test_create_variant_success() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        // begin returns MockTx by default
+        // commit returns Ok by default
+
+        mock_repo
+            .expect_create_variant()
+            .withf(|_, _, variant, _| variant.barcode == Some("1234567890".to_string()))
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let variant = create_test_variant_create(1);
+        let result = service.create_variant(&ctx, &variant).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_variant_no_permission() {
+        let mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_no_permission_context();
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let variant = create_test_variant_create(1);
+        let result = service.create_variant(&ctx, &variant).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_variant_negative_price() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo.expect_create_variant().times(0);
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let variant = ProductVariantCreate {
+            price: Some("-0.01".parse().unwrap()),
+            ..create_test_variant_create(1)
+        };
+        let result = service.create_variant(&ctx, &variant).await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_product_negative_variant_price() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo.expect_create_product().times(0);
+        mock_repo.expect_create_variant().times(0);
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let variants = vec![
+            create_test_variant_create(0),
+            ProductVariantCreate {
+                price: Some(Money::from_minor(-100)),
+                ..create_test_variant_create(0)
+            },
+        ];
+        let result = service
+            .create_product(&ctx, &create_test_product_create(), &variants)
+            .await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    // =============================================================================
+    // Upsert Variant Tests
+    // =============================================================================
+
+    fn create_test_variant_upsert() -> ProductVariantUpsert {
+        ProductVariantUpsert {
+            barcode: "8991234567890".to_string(),
+            name: Some("Feed Variant".to_string()),
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upsert_variant_by_barcode_success() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_upsert_variant_by_barcode()
+            .withf(|_, id, product_id, variant, _| {
+                *id == 7 && *product_id == 1 && variant.barcode == "8991234567890"
+            })
+            .times(1)
+            .returning(|_, id, _, _, _| Ok((id, true)));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(7));
+        let result = service
+            .upsert_variant_by_barcode(&ctx, 1, &create_test_variant_upsert())
+            .await;
+
+        assert_eq!(result.unwrap(), (7, true));
+    }
+
+    #[tokio::test]
+    async fn test_upsert_variant_by_barcode_conflict_rolls_back() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new().expect_rollback();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_upsert_variant_by_barcode()
+            .times(1)
+            .returning(|_, _, _, _, _| Err(Error::Conflict("Barcode in use".to_string())));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(7));
+        let result = service
+            .upsert_variant_by_barcode(&ctx, 1, &create_test_variant_upsert())
+            .await;
+
+        assert!(matches!(result, Err(Error::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_upsert_variant_by_barcode_empty_barcode() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo.expect_upsert_variant_by_barcode().times(0);
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(7));
+        let variant = ProductVariantUpsert {
+            barcode: "  ".to_string(),
+            ..create_test_variant_upsert()
+        };
+        let result = service.upsert_variant_by_barcode(&ctx, 1, &variant).await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_upsert_variant_by_barcode_no_permission() {
+        let mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_no_permission_context();
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(7));
+        let result = service
+            .upsert_variant_by_barcode(&ctx, 1, &create_test_variant_upsert())
+            .await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    // =============================================================================
+    // Update Variant Tests
+    // =============================================================================
+
+    #[tokio::test]
+    async fn test_update_variant_success() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_update_variant()
+            .withf(|_, id, _, _| *id == 100)
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let update = create_test_variant_update();
+        let result = service.update_variant(&ctx, 100, &update).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_update_variant_rolls_back_on_error() {
         let mut mock_repo = MockProductRepo::new();
-        let mock_tx = MockTxManager::new();
+        let mock_tx = MockTxManager::new().expect_rollback();
         let ctx = create_test_context();
 
         mock_repo
-            .expect_get_by_id()
+            .expect_update_variant()
             .times(1)
-            .returning(|_, _| Ok(None));
+            .returning(|_, _, _, _| Err(Error::Conflict("Barcode taken".to_string())));
 
         let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
-        let result = service.get_by_id(&ctx, 999).await;
+        let update = create_test_variant_update();
+        let result = service.update_variant(&ctx, 100, &update).await;
 
-        assert!(result.is_ok());
-        assert!(result.unwrap().is_none());
+        assert!(matches!(result, Err(Error::Conflict(_))));
     }
 
     #[tokio::test]
-    async fn test_get_by_id_no_permission() {
+    async fn test_update_variant_no_permission() {
         let mock_repo = MockProductRepo::new();
         let mock_tx = MockTxManager::new();
         let ctx = create_no_permission_context();
 
         let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
-        let result = service.get_by_id(&ctx, 1).await;
+        let update = create_test_variant_update();
+        let result = service.update_variant(&ctx, 100, &update).await;
 
         assert!(matches!(result, Err(Error::Forbidden(_))));
     }
 
-    // =============================================================================
-    // Create Variant Tests
-    // =============================================================================
-
     #[tokio::test]
-    async fn test_create_variant_success() {
+    async fn test_update_variant_negative_price() {
         let mut mock_repo = MockProductRepo::new();
         let mock_tx = MockTxManager::new();
         let ctx = create_test_context();
 
-        // begin returns MockTx by default
-        // commit returns Ok by default
-
-        mock_repo
-            .expect_create_variant()
-            .withf(|_, _, variant, _| variant.barcode == Some("1234567890".to_string()))
-            .times(1)
-            .returning(|_, _, _, _| Ok(()));
+        mock_repo.expect_update_variant().times(0);
 
         let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
-        let variant = create_test_variant_create(1);
-        let result = service.create_variant(&ctx, &variant).await;
+        let update = ProductVariantUpdate {
+            price: Update::Set(Money::from_minor(-1)),
+            ..create_test_variant_update()
+        };
+        let result = service.update_variant(&ctx, 100, &update).await;
 
-        assert!(result.is_ok());
+        assert!(matches!(result, Err(Error::ValidationError(_))));
     }
 
     #[tokio::test]
-    async fn test_create_variant_no_permission() {
-        let mock_repo = MockProductRepo::new();
+    async fn test_update_variant_metadata_missing_sku() {
+        let mut mock_repo = MockProductRepo::new();
         let mock_tx = MockTxManager::new();
-        let ctx = create_no_permission_context();
+        let ctx = create_test_context();
 
-        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
-        let variant = create_test_variant_create(1);
-        let result = service.create_variant(&ctx, &variant).await;
+        mock_repo.expect_update_variant().times(0);
 
-        assert!(matches!(result, Err(Error::Forbidden(_))));
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1))
+            .with_variant_metadata_schema(sku_schema());
+        let update = ProductVariantUpdate {
+            metadata: Update::Set(serde_json::json!({"color": "red"})),
+            ..create_test_variant_update()
+        };
+        let result = service.update_variant(&ctx, 100, &update).await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
     }
 
     // =============================================================================
-    // Update Variant Tests
+    // Delete Variant Tests
     // =============================================================================
 
     #[tokio::test]
-    async fn test_update_variant_success() {
+    async fn test_delete_variant_success() {
         let mut mock_repo = MockProductRepo::new();
         let mock_tx = MockTxManager::new();
         let ctx = create_test_context();
 
+        // begin returns MockTx by default
+        // commit returns Ok by default
+
         mock_repo
-            .expect_update_variant()
+            .expect_delete_variant()
             .withf(|_, id, _| *id == 100)
             .times(1)
             .returning(|_, _, _| Ok(()));
 
         let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
-        let update = create_test_variant_update();
-        let result = service.update_variant(&ctx, 100, &update).await;
+        let result = service.delete_variant(&ctx, 100).await;
 
         assert!(result.is_ok());
     }
 
     #[tokio::test]
-    async fn test_update_variant_no_permission() {
+    async fn test_delete_variant_no_permission() {
         let mock_repo = MockProductRepo::new();
         let mock_tx = MockTxManager::new();
         let ctx = create_no_permission_context();
 
         let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
-        let update = create_test_variant_update();
-        let result = service.update_variant(&ctx, 100, &update).await;
+        let result = service.delete_variant(&ctx, 100).await;
 
         assert!(matches!(result, Err(Error::Forbidden(_))));
     }
 
     // =============================================================================
-    // Delete Variant Tests
+    // Reassign Variant Tests
     // =============================================================================
 
     #[tokio::test]
-    async fn test_delete_variant_success() {
+    async fn test_reassign_variant_success() {
         let mut mock_repo = MockProductRepo::new();
         let mock_tx = MockTxManager::new();
         let ctx = create_test_context();
 
-        // begin returns MockTx by default
-        // commit returns Ok by default
-
         mock_repo
-            .expect_delete_variant()
-            .withf(|_, id, _| *id == 100)
+            .expect_reassign_variant()
+            .withf(|_, variant_id, product_id, _| *variant_id == 100 && *product_id == 2)
             .times(1)
-            .returning(|_, _, _| Ok(()));
+            .returning(|_, _, _, _| Ok(()));
 
         let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
-        let result = service.delete_variant(&ctx, 100).await;
+        let result = service.reassign_variant(&ctx, 100, 2).await;
 
         assert!(result.is_ok());
     }
 
     #[tokio::test]
-    async fn test_delete_variant_no_permission() {
+    async fn test_reassign_variant_no_permission() {
         let mock_repo = MockProductRepo::new();
         let mock_tx = MockTxManager::new();
         let ctx = create_no_permission_context();
 
         let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
-        let result = service.delete_variant(&ctx, 100).await;
+        let result = service.reassign_variant(&ctx, 100, 2).await;
 
         assert!(matches!(result, Err(Error::Forbidden(_))));
     }
 
+    #[tokio::test]
+    async fn test_reassign_variant_to_missing_product_rolls_back() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new().expect_rollback();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_reassign_variant()
+            .times(1)
+            .returning(|_, _, _, _| Err(Error::NotFound("Product not found".to_string())));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.reassign_variant(&ctx, 100, 999).await;
+
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
     // =============================================================================
     // Delete Variants by Product ID Tests
     // =============================================================================
@@ -1042,6 +2941,68 @@ mod tests {
         assert_eq!(variants[0].id, 100);
     }
 
+    #[tokio::test]
+    async fn test_count_variants_success() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_count_variants()
+            .withf(|_, id| *id == 1)
+            .times(1)
+            .returning(|_, _| Ok(2));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.count_variants(&ctx, 1).await;
+
+        assert_eq!(result.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_count_variants_no_permission() {
+        let mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_no_permission_context();
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.count_variants(&ctx, 1).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_categories_success() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_product_category()
+            .withf(|_, product_id| *product_id == 1)
+            .times(1)
+            .returning(|_, _| Ok(vec![3, 7]));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.get_categories(&ctx, 1).await;
+
+        assert_eq!(result.unwrap(), vec![3, 7]);
+    }
+
+    #[tokio::test]
+    async fn test_get_categories_no_permission() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_no_permission_context();
+
+        mock_repo.expect_get_product_category().never();
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.get_categories(&ctx, 1).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
     #[tokio::test]
     async fn test_get_variant_by_product_id_empty() {
         let mut mock_repo = MockProductRepo::new();
@@ -1071,4 +3032,199 @@ mod tests {
 
         assert!(matches!(result, Err(Error::Forbidden(_))));
     }
+
+    #[tokio::test]
+    async fn test_get_variants_success() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_variants()
+            .withf(|_, filter, pagination| {
+                filter.name.as_deref() == Some("Large") && pagination.page == 2
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(vec![create_test_variant()]));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let filter = ProductVariantFilter {
+            name: Some("Large".to_string()),
+            ..Default::default()
+        };
+        let result = service
+            .get_variants(&ctx, &filter, &PaginationOptions::new(2, 10, None))
+            .await;
+
+        assert_eq!(result.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_variants_no_permission() {
+        let mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_no_permission_context();
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service
+            .get_variants(
+                &ctx,
+                &ProductVariantFilter::default(),
+                &PaginationOptions::new(1, 10, None),
+            )
+            .await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    // =============================================================================
+    // Price Lookup Tests
+    // =============================================================================
+
+    fn create_priced_variant() -> ProductVariant {
+        ProductVariant {
+            price: Some(Money::from_major(10)),
+            ..create_test_variant()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_price_for_matching_tier() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_variant_by_id()
+            .withf(|_, id| *id == 100)
+            .times(1)
+            .returning(|_, _| Ok(Some(create_priced_variant())));
+        mock_repo
+            .expect_get_level_price()
+            .withf(|_, id, level| *id == 100 && *level == 2)
+            .times(1)
+            .returning(|_, _, _| Ok(Some(Money::from_minor(850))));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.price_for(&ctx, 100, 2).await;
+
+        assert_eq!(result.unwrap(), Money::from_minor(850));
+    }
+
+    #[tokio::test]
+    async fn test_price_for_falls_back_to_base_price() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_variant_by_id()
+            .times(1)
+            .returning(|_, _| Ok(Some(create_priced_variant())));
+        mock_repo
+            .expect_get_level_price()
+            .times(1)
+            .returning(|_, _, _| Ok(None));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.price_for(&ctx, 100, 5).await;
+
+        assert_eq!(result.unwrap(), Money::from_major(10));
+    }
+
+    #[tokio::test]
+    async fn test_price_for_without_base_price() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_variant_by_id()
+            .times(1)
+            .returning(|_, _| Ok(Some(create_test_variant())));
+        mock_repo
+            .expect_get_level_price()
+            .times(1)
+            .returning(|_, _, _| Ok(None));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.price_for(&ctx, 100, 1).await;
+
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_price_for_missing_variant() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_variant_by_id()
+            .times(1)
+            .returning(|_, _| Ok(None));
+        mock_repo.expect_get_level_price().never();
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.price_for(&ctx, 999, 1).await;
+
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_price_for_no_permission() {
+        let mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_no_permission_context();
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service.price_for(&ctx, 100, 1).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_price_history_success() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_price_history()
+            .withf(|_, variant_id, pagination| *variant_id == 100 && pagination.page == 1)
+            .times(1)
+            .returning(|_, variant_id, _| {
+                Ok(vec![VariantPriceChange {
+                    variant_id,
+                    price: Some(Money::from_major(10)),
+                    effective_from: Utc::now(),
+                    changed_by: Some(1),
+                }])
+            });
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service
+            .get_price_history(&ctx, 100, &PaginationOptions::new(1, 10, None))
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].price, Some(Money::from_major(10)));
+    }
+
+    #[tokio::test]
+    async fn test_get_price_history_no_permission() {
+        let mut mock_repo = MockProductRepo::new();
+        let mock_tx = MockTxManager::new();
+        let ctx = create_no_permission_context();
+
+        mock_repo.expect_get_price_history().never();
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(1));
+        let result = service
+            .get_price_history(&ctx, 100, &PaginationOptions::new(1, 10, None))
+            .await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
 }
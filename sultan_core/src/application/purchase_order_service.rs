@@ -0,0 +1,329 @@
+use crate::snowflake::IdGenerator;
+use crate::{
+    domain::{
+        Context, DomainResult, Error,
+        model::{
+            permission::{action, resource},
+            purchase_order::{PurchaseOrder, PurchaseOrderCreate, PurchaseOrderStatus},
+            validate::Validate,
+        },
+    },
+    storage::{PurchaseOrderRepository, transaction::TransactionManager},
+};
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait PurchaseOrderServiceTrait: Send + Sync {
+    async fn create(&self, ctx: &Context, order: &PurchaseOrderCreate) -> DomainResult<i64>;
+    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<PurchaseOrder>>;
+    /// Add the ordered quantities to the branch stock and mark the purchase
+    /// order `Received`. Fails with `Conflict` when it is not `Ordered`.
+    async fn receive(&self, ctx: &Context, id: i64) -> DomainResult<()>;
+}
+
+pub struct PurchaseOrderService<R, T, I> {
+    repository: R,
+    tx_manager: T,
+    id_generator: I,
+}
+
+impl<R, T, I> PurchaseOrderService<R, T, I>
+where
+    T: TransactionManager,
+    I: IdGenerator,
+{
+    pub fn new(repository: R, tx_manager: T, id_generator: I) -> Self {
+        Self {
+            repository,
+            tx_manager,
+            id_generator,
+        }
+    }
+}
+
+#[async_trait]
+impl<R, T, I> PurchaseOrderServiceTrait for PurchaseOrderService<R, T, I>
+where
+    for<'a> R: PurchaseOrderRepository<T::Transaction<'a>>,
+    for<'a> T::Transaction<'a>: Send,
+    T: TransactionManager,
+    I: IdGenerator,
+{
+    async fn create(&self, ctx: &Context, order: &PurchaseOrderCreate) -> DomainResult<i64> {
+        ctx.require_access(
+            Some(order.branch_id),
+            resource::PURCHASE_ORDER,
+            action::CREATE,
+        )?;
+        order.validate()?;
+
+        let mut tx = self.tx_manager.begin().await?;
+        let id = self.id_generator.generate()?;
+        if let Err(e) = self.repository.create(ctx, id, order, &mut tx).await {
+            let _ = self.tx_manager.rollback(tx).await;
+            return Err(e);
+        }
+        self.tx_manager.commit(tx).await?;
+        Ok(id)
+    }
+
+    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<PurchaseOrder>> {
+        let order = self.repository.get_by_id(ctx, id).await?;
+        if let Some(order) = &order {
+            ctx.require_access(
+                Some(order.branch_id),
+                resource::PURCHASE_ORDER,
+                action::READ,
+            )?;
+        }
+        Ok(order)
+    }
+
+    async fn receive(&self, ctx: &Context, id: i64) -> DomainResult<()> {
+        let order =
+            self.repository.get_by_id(ctx, id).await?.ok_or_else(|| {
+                Error::NotFound(format!("Purchase order with id {} not found", id))
+            })?;
+        ctx.require_access(
+            Some(order.branch_id),
+            resource::PURCHASE_ORDER,
+            action::UPDATE,
+        )?;
+
+        let mut tx = self.tx_manager.begin().await?;
+        let result = async {
+            // The guarded status change goes first so a concurrent receive
+            // fails before adding stock a second time
+            self.repository
+                .update_status(
+                    ctx,
+                    id,
+                    PurchaseOrderStatus::Ordered,
+                    PurchaseOrderStatus::Received,
+                    &mut tx,
+                )
+                .await?;
+            self.repository
+                .add_stock(ctx, order.branch_id, &order.lines, &mut tx)
+                .await
+        }
+        .await;
+
+        match result {
+            Ok(()) => self.tx_manager.commit(tx).await,
+            Err(e) => {
+                let _ = self.tx_manager.rollback(tx).await;
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::{MockIdGen, create_mock_id_gen};
+    use crate::domain::model::{money::Money, purchase_order::PurchaseOrderLine};
+    use chrono::Utc;
+    use mockall::mock;
+    use std::collections::HashMap;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
+
+    #[derive(Debug)]
+    struct MockTx;
+
+    mock! {
+        pub PurchaseOrderRepo {}
+        #[async_trait]
+        impl PurchaseOrderRepository<MockTx> for PurchaseOrderRepo {
+            async fn create(&self, ctx: &Context, id: i64, order: &PurchaseOrderCreate, tx: &mut MockTx) -> DomainResult<()>;
+            async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<PurchaseOrder>>;
+            async fn update_status(&self, ctx: &Context, id: i64, from: PurchaseOrderStatus, to: PurchaseOrderStatus, tx: &mut MockTx) -> DomainResult<()>;
+            async fn add_stock(&self, ctx: &Context, branch_id: i64, lines: &[PurchaseOrderLine], tx: &mut MockTx) -> DomainResult<()>;
+        }
+    }
+
+    #[derive(Default)]
+    struct MockTxManager {
+        committed: Arc<AtomicBool>,
+        rolled_back: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl TransactionManager for MockTxManager {
+        type Transaction<'a> = MockTx;
+
+        async fn begin(&self) -> DomainResult<MockTx> {
+            Ok(MockTx)
+        }
+
+        async fn commit<'a>(&self, _tx: MockTx) -> DomainResult<()> {
+            self.committed.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn rollback<'a>(&self, _tx: MockTx) -> DomainResult<()> {
+            self.rolled_back.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn create_service(
+        mock_repo: MockPurchaseOrderRepo,
+        mock_tx: MockTxManager,
+        mock_id_generator: MockIdGen,
+    ) -> PurchaseOrderService<MockPurchaseOrderRepo, MockTxManager, MockIdGen> {
+        PurchaseOrderService::new(mock_repo, mock_tx, mock_id_generator)
+    }
+
+    /// Context with full PURCHASE_ORDER permissions on branch 1 only
+    fn create_branch_context() -> Context {
+        let mut permissions = HashMap::new();
+        permissions.insert((resource::PURCHASE_ORDER, Some(1)), 0b1111);
+        Context::new_with_all(None, permissions, HashMap::new())
+    }
+
+    fn create_test_lines() -> Vec<PurchaseOrderLine> {
+        vec![PurchaseOrderLine {
+            variant_id: 10,
+            qty: 24,
+            cost: Money::from_major(3),
+        }]
+    }
+
+    fn create_test_order(branch_id: i64, status: PurchaseOrderStatus) -> PurchaseOrder {
+        PurchaseOrder {
+            id: 1,
+            supplier_id: 5,
+            branch_id,
+            lines: create_test_lines(),
+            status,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_purchase_order_success() {
+        let mut mock_repo = MockPurchaseOrderRepo::new();
+        let mock_tx = MockTxManager::default();
+        let committed = mock_tx.committed.clone();
+
+        mock_repo
+            .expect_create()
+            .withf(|_, id, order, _| *id == 42 && order.supplier_id == 5)
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(42));
+        let order = PurchaseOrderCreate {
+            supplier_id: 5,
+            branch_id: 1,
+            lines: create_test_lines(),
+        };
+        let result = service.create(&create_branch_context(), &order).await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert!(committed.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_create_purchase_order_other_branch_forbidden() {
+        let mut mock_repo = MockPurchaseOrderRepo::new();
+        mock_repo.expect_create().never();
+
+        let service = create_service(mock_repo, MockTxManager::default(), MockIdGen::new());
+        let order = PurchaseOrderCreate {
+            supplier_id: 5,
+            branch_id: 2,
+            lines: create_test_lines(),
+        };
+        let result = service.create(&create_branch_context(), &order).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_receive_purchase_order_adds_stock() {
+        let mut mock_repo = MockPurchaseOrderRepo::new();
+        let mock_tx = MockTxManager::default();
+        let committed = mock_tx.committed.clone();
+
+        mock_repo
+            .expect_get_by_id()
+            .returning(|_, _| Ok(Some(create_test_order(1, PurchaseOrderStatus::Ordered))));
+        mock_repo
+            .expect_update_status()
+            .withf(|_, id, from, to, _| {
+                *id == 1
+                    && *from == PurchaseOrderStatus::Ordered
+                    && *to == PurchaseOrderStatus::Received
+            })
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+        mock_repo
+            .expect_add_stock()
+            .withf(|_, branch_id, lines, _| *branch_id == 1 && lines == create_test_lines())
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let service = create_service(mock_repo, mock_tx, MockIdGen::new());
+        let result = service.receive(&create_branch_context(), 1).await;
+
+        assert!(result.is_ok());
+        assert!(committed.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_receive_already_received_conflicts() {
+        let mut mock_repo = MockPurchaseOrderRepo::new();
+        let mock_tx = MockTxManager::default();
+        let rolled_back = mock_tx.rolled_back.clone();
+
+        mock_repo
+            .expect_get_by_id()
+            .returning(|_, _| Ok(Some(create_test_order(1, PurchaseOrderStatus::Received))));
+        mock_repo
+            .expect_update_status()
+            .times(1)
+            .returning(|_, _, _, _, _| {
+                Err(Error::Conflict(
+                    "Purchase order 1 is already received".to_string(),
+                ))
+            });
+        mock_repo.expect_add_stock().never();
+
+        let service = create_service(mock_repo, mock_tx, MockIdGen::new());
+        let result = service.receive(&create_branch_context(), 1).await;
+
+        assert!(matches!(result, Err(Error::Conflict(_))));
+        assert!(rolled_back.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_receive_purchase_order_not_found() {
+        let mut mock_repo = MockPurchaseOrderRepo::new();
+        mock_repo.expect_get_by_id().returning(|_, _| Ok(None));
+
+        let service = create_service(mock_repo, MockTxManager::default(), MockIdGen::new());
+        let result = service.receive(&create_branch_context(), 999).await;
+
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_receive_other_branch_forbidden() {
+        let mut mock_repo = MockPurchaseOrderRepo::new();
+        mock_repo
+            .expect_get_by_id()
+            .returning(|_, _| Ok(Some(create_test_order(2, PurchaseOrderStatus::Ordered))));
+        mock_repo.expect_update_status().never();
+
+        let service = create_service(mock_repo, MockTxManager::default(), MockIdGen::new());
+        let result = service.receive(&create_branch_context(), 1).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+}
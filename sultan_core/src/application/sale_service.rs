@@ -0,0 +1,710 @@
+use crate::snowflake::IdGenerator;
+use crate::{
+    domain::{
+        Context, DomainResult, Error,
+        model::{
+            permission::{action, resource},
+            sale::{
+                ReversedLine, Sale, SaleCreate, SaleKind, SaleLine, SaleRefundLine, SalesSummary,
+            },
+        },
+    },
+    storage::{SaleRepository, transaction::TransactionManager},
+};
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, Utc};
+
+const DEFAULT_IDEMPOTENCY_TTL_HOURS: i64 = 24;
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
+
+#[async_trait]
+pub trait SaleServiceTrait: Send + Sync {
+    /// Create a sale. When `idempotency_key` is given and was already used for
+    /// a sale within the TTL, that sale's id is returned and nothing is written.
+    async fn create(
+        &self,
+        ctx: &Context,
+        sale: &SaleCreate,
+        idempotency_key: Option<&str>,
+    ) -> DomainResult<i64>;
+    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Sale>>;
+    /// Reverse everything on a sale that has not been refunded yet and put it
+    /// back into stock. Returns the id of the void record.
+    async fn void(&self, ctx: &Context, sale_id: i64) -> DomainResult<i64>;
+    /// Refund part of a sale and put the refunded quantities back into stock.
+    /// Returns the id of the refund record.
+    async fn refund_lines(
+        &self,
+        ctx: &Context,
+        sale_id: i64,
+        lines: &[SaleRefundLine],
+    ) -> DomainResult<i64>;
+    async fn summary(
+        &self,
+        ctx: &Context,
+        branch_id: i64,
+        date: NaiveDate,
+    ) -> DomainResult<SalesSummary>;
+}
+
+pub struct SaleService<R, T, I> {
+    repository: R,
+    tx_manager: T,
+    id_generator: I,
+    idempotency_ttl: Duration,
+}
+
+impl<R, T, I> SaleService<R, T, I>
+where
+    T: TransactionManager,
+    I: IdGenerator,
+{
+    pub fn new(repository: R, tx_manager: T, id_generator: I) -> Self {
+        Self {
+            repository,
+            tx_manager,
+            id_generator,
+            idempotency_ttl: Duration::hours(DEFAULT_IDEMPOTENCY_TTL_HOURS),
+        }
+    }
+
+    /// Set how long an idempotency key keeps pointing at its sale.
+    ///
+    /// Default: 24 hours
+    pub fn with_idempotency_ttl(mut self, ttl: Duration) -> Self {
+        self.idempotency_ttl = ttl;
+        self
+    }
+}
+
+impl<R, T, I> SaleService<R, T, I>
+where
+    for<'a> R: SaleRepository<T::Transaction<'a>>,
+    for<'a> T::Transaction<'a>: Send,
+    T: TransactionManager,
+    I: IdGenerator,
+{
+    /// Shared flow for voids and refunds. `reverse` picks the lines to put
+    /// back from what has already been reversed, and runs inside the same
+    /// transaction as the write so concurrent refunds cannot overshoot.
+    async fn reverse<F>(
+        &self,
+        ctx: &Context,
+        sale_id: i64,
+        kind: SaleKind,
+        reverse: F,
+    ) -> DomainResult<i64>
+    where
+        F: FnOnce(&Sale, &[ReversedLine]) -> DomainResult<Vec<SaleLine>> + Send,
+    {
+        let original = self
+            .repository
+            .get_by_id(ctx, sale_id)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("Sale with id {} not found", sale_id)))?;
+        ctx.require_access(Some(original.branch_id), resource::SALE, action::UPDATE)?;
+
+        let mut tx = self.tx_manager.begin().await?;
+        let result = async {
+            let reversed = self
+                .repository
+                .reversed_lines(ctx, sale_id, &mut tx)
+                .await?;
+            let lines = reverse(&original, &reversed)?;
+            let id = self.id_generator.generate()?;
+            self.repository
+                .create_reversal(ctx, id, &original, kind, &lines, &mut tx)
+                .await?;
+            Ok(id)
+        }
+        .await;
+
+        match result {
+            Ok(id) => {
+                self.tx_manager.commit(tx).await?;
+                Ok(id)
+            }
+            Err(e) => {
+                let _ = self.tx_manager.rollback(tx).await;
+                Err(e)
+            }
+        }
+    }
+}
+
+fn validate_idempotency_key(key: &str) -> DomainResult<()> {
+    if key.trim().is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LEN {
+        return Err(Error::ValidationError(format!(
+            "Idempotency key must be between 1 and {} characters",
+            MAX_IDEMPOTENCY_KEY_LEN
+        )));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl<R, T, I> SaleServiceTrait for SaleService<R, T, I>
+where
+    for<'a> R: SaleRepository<T::Transaction<'a>>,
+    for<'a> T::Transaction<'a>: Send,
+    T: TransactionManager,
+    I: IdGenerator,
+{
+    async fn create(
+        &self,
+        ctx: &Context,
+        sale: &SaleCreate,
+        idempotency_key: Option<&str>,
+    ) -> DomainResult<i64> {
+        ctx.require_access(Some(sale.branch_id), resource::SALE, action::CREATE)?;
+        sale.priced_lines()?;
+
+        let now = Utc::now();
+        if let Some(key) = idempotency_key {
+            validate_idempotency_key(key)?;
+            if let Some(id) = self
+                .repository
+                .find_by_idempotency_key(ctx, sale.branch_id, key, now)
+                .await?
+            {
+                return Ok(id);
+            }
+        }
+
+        let mut tx = self.tx_manager.begin().await?;
+        let id = self.id_generator.generate()?;
+        if let Err(e) = self.repository.create(ctx, id, sale, &mut tx).await {
+            let _ = self.tx_manager.rollback(tx).await;
+            return Err(e);
+        }
+
+        if let Some(key) = idempotency_key {
+            let expires_at = now + self.idempotency_ttl;
+            match self
+                .repository
+                .save_idempotency_key(ctx, key, id, now, expires_at, &mut tx)
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => {
+                    // A concurrent request with the same key committed first
+                    let _ = self.tx_manager.rollback(tx).await;
+                    return self
+                        .repository
+                        .find_by_idempotency_key(ctx, sale.branch_id, key, now)
+                        .await?
+                        .ok_or_else(|| {
+                            Error::Internal(format!(
+                                "Idempotency key '{}' could not be resolved",
+                                key
+                            ))
+                        });
+                }
+                Err(e) => {
+                    let _ = self.tx_manager.rollback(tx).await;
+                    return Err(e);
+                }
+            }
+        }
+
+        self.tx_manager.commit(tx).await?;
+        Ok(id)
+    }
+
+    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Sale>> {
+        let sale = self.repository.get_by_id(ctx, id).await?;
+        if let Some(sale) = &sale {
+            ctx.require_access(Some(sale.branch_id), resource::SALE, action::READ)?;
+        }
+        Ok(sale)
+    }
+
+    async fn void(&self, ctx: &Context, sale_id: i64) -> DomainResult<i64> {
+        self.reverse(ctx, sale_id, SaleKind::Void, |sale, reversed| {
+            sale.void_lines(reversed)
+        })
+        .await
+    }
+
+    async fn refund_lines(
+        &self,
+        ctx: &Context,
+        sale_id: i64,
+        lines: &[SaleRefundLine],
+    ) -> DomainResult<i64> {
+        self.reverse(ctx, sale_id, SaleKind::Refund, |sale, reversed| {
+            sale.refund_lines(reversed, lines)
+        })
+        .await
+    }
+
+    async fn summary(
+        &self,
+        ctx: &Context,
+        branch_id: i64,
+        date: NaiveDate,
+    ) -> DomainResult<SalesSummary> {
+        ctx.require_access(Some(branch_id), resource::SALE, action::READ)?;
+        ctx.cancellable(self.repository.summary(ctx, branch_id, date))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::{MockIdGen, create_mock_id_gen};
+    use crate::domain::Error;
+    use crate::domain::model::{money::Money, sale::SaleLineCreate};
+    use chrono::DateTime;
+    use mockall::mock;
+    use std::collections::HashMap;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
+
+    #[derive(Debug)]
+    struct MockTx;
+
+    mock! {
+        pub SaleRepo {}
+        #[async_trait]
+        impl SaleRepository<MockTx> for SaleRepo {
+            async fn create(&self, ctx: &Context, id: i64, sale: &SaleCreate, tx: &mut MockTx) -> DomainResult<()>;
+            async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Sale>>;
+            async fn create_reversal(&self, ctx: &Context, id: i64, original: &Sale, kind: SaleKind, lines: &[SaleLine], tx: &mut MockTx) -> DomainResult<()>;
+            async fn reversed_lines(&self, ctx: &Context, sale_id: i64, tx: &mut MockTx) -> DomainResult<Vec<ReversedLine>>;
+            async fn summary(&self, ctx: &Context, branch_id: i64, date: NaiveDate) -> DomainResult<SalesSummary>;
+            async fn find_by_idempotency_key(&self, ctx: &Context, branch_id: i64, key: &str, now: DateTime<Utc>) -> DomainResult<Option<i64>>;
+            async fn save_idempotency_key(&self, ctx: &Context, key: &str, sale_id: i64, now: DateTime<Utc>, expires_at: DateTime<Utc>, tx: &mut MockTx) -> DomainResult<bool>;
+            async fn delete_expired_idempotency_keys(&self, ctx: &Context, now: DateTime<Utc>) -> DomainResult<u64>;
+        }
+    }
+
+    #[derive(Default)]
+    struct MockTxManager {
+        committed: Arc<AtomicBool>,
+        rolled_back: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl TransactionManager for MockTxManager {
+        type Transaction<'a> = MockTx;
+
+        async fn begin(&self) -> DomainResult<MockTx> {
+            Ok(MockTx)
+        }
+
+        async fn commit<'a>(&self, _tx: MockTx) -> DomainResult<()> {
+            self.committed.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn rollback<'a>(&self, _tx: MockTx) -> DomainResult<()> {
+            self.rolled_back.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn create_service(
+        mock_repo: MockSaleRepo,
+        mock_tx: MockTxManager,
+        mock_id_generator: MockIdGen,
+    ) -> SaleService<MockSaleRepo, MockTxManager, MockIdGen> {
+        SaleService {
+            repository: mock_repo,
+            tx_manager: mock_tx,
+            id_generator: mock_id_generator,
+            idempotency_ttl: Duration::hours(DEFAULT_IDEMPOTENCY_TTL_HOURS),
+        }
+    }
+
+    /// Context with full SALE permissions on branch 1 only
+    fn create_branch_context() -> Context {
+        let mut permissions = HashMap::new();
+        permissions.insert((resource::SALE, Some(1)), 0b1111);
+        Context::new_with_all(None, permissions, HashMap::new())
+    }
+
+    fn create_test_sale_create(branch_id: i64) -> SaleCreate {
+        SaleCreate {
+            branch_id,
+            customer_id: None,
+            payment_method: "cash".to_string(),
+            lines: vec![SaleLineCreate {
+                variant_id: 10,
+                qty: 2,
+                unit_price: Money::from_major(5),
+            }],
+        }
+    }
+
+    fn create_test_sale(branch_id: i64) -> Sale {
+        Sale {
+            id: 1,
+            branch_id,
+            customer_id: None,
+            payment_method: "cash".to_string(),
+            kind: SaleKind::Sale,
+            original_sale_id: None,
+            lines: vec![SaleLine {
+                variant_id: 10,
+                qty: 2,
+                unit_price: Money::from_major(5),
+                line_total: Money::from_major(10),
+            }],
+            total: Money::from_major(10),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_sale_success() {
+        let mut mock_repo = MockSaleRepo::new();
+        let mock_tx = MockTxManager::default();
+        let committed = mock_tx.committed.clone();
+
+        mock_repo
+            .expect_create()
+            .withf(|_, id, sale, _| *id == 42 && sale.branch_id == 1)
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(42));
+        let result = service
+            .create(&create_branch_context(), &create_test_sale_create(1), None)
+            .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert!(committed.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_create_sale_records_idempotency_key() {
+        let mut mock_repo = MockSaleRepo::new();
+        let mock_tx = MockTxManager::default();
+        let committed = mock_tx.committed.clone();
+
+        mock_repo
+            .expect_find_by_idempotency_key()
+            .times(1)
+            .returning(|_, _, _, _| Ok(None));
+        mock_repo
+            .expect_create()
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+        mock_repo
+            .expect_save_idempotency_key()
+            .withf(|_, key, sale_id, now, expires_at, _| {
+                key == "retry-1" && *sale_id == 42 && *expires_at - *now == Duration::hours(24)
+            })
+            .times(1)
+            .returning(|_, _, _, _, _, _| Ok(true));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(42));
+        let result = service
+            .create(
+                &create_branch_context(),
+                &create_test_sale_create(1),
+                Some("retry-1"),
+            )
+            .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert!(committed.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_create_sale_repeated_idempotency_key() {
+        let mut mock_repo = MockSaleRepo::new();
+        mock_repo
+            .expect_find_by_idempotency_key()
+            .times(1)
+            .returning(|_, _, _, _| Ok(Some(7)));
+        mock_repo.expect_create().never();
+
+        let service = create_service(mock_repo, MockTxManager::default(), MockIdGen::new());
+        let result = service
+            .create(
+                &create_branch_context(),
+                &create_test_sale_create(1),
+                Some("retry-1"),
+            )
+            .await;
+
+        assert_eq!(result.unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_create_sale_idempotency_race_returns_winner() {
+        let mut mock_repo = MockSaleRepo::new();
+        let mock_tx = MockTxManager::default();
+        let committed = mock_tx.committed.clone();
+        let rolled_back = mock_tx.rolled_back.clone();
+
+        let mut lookups = 0;
+        mock_repo
+            .expect_find_by_idempotency_key()
+            .times(2)
+            .returning(move |_, _, _, _| {
+                lookups += 1;
+                Ok(if lookups == 1 { None } else { Some(7) })
+            });
+        mock_repo
+            .expect_create()
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+        mock_repo
+            .expect_save_idempotency_key()
+            .times(1)
+            .returning(|_, _, _, _, _, _| Ok(false));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(42));
+        let result = service
+            .create(
+                &create_branch_context(),
+                &create_test_sale_create(1),
+                Some("retry-1"),
+            )
+            .await;
+
+        assert_eq!(result.unwrap(), 7);
+        assert!(rolled_back.load(Ordering::SeqCst));
+        assert!(!committed.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_create_sale_blank_idempotency_key() {
+        let mut mock_repo = MockSaleRepo::new();
+        mock_repo.expect_create().never();
+
+        let service = create_service(mock_repo, MockTxManager::default(), MockIdGen::new());
+        let result = service
+            .create(
+                &create_branch_context(),
+                &create_test_sale_create(1),
+                Some(" "),
+            )
+            .await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_sale_other_branch_forbidden() {
+        let mut mock_repo = MockSaleRepo::new();
+        mock_repo.expect_create().never();
+
+        let service = create_service(mock_repo, MockTxManager::default(), MockIdGen::new());
+        let result = service
+            .create(&create_branch_context(), &create_test_sale_create(2), None)
+            .await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_sale_without_lines() {
+        let mut mock_repo = MockSaleRepo::new();
+        mock_repo.expect_create().never();
+
+        let sale = SaleCreate {
+            lines: vec![],
+            ..create_test_sale_create(1)
+        };
+        let service = create_service(mock_repo, MockTxManager::default(), MockIdGen::new());
+        let result = service.create(&create_branch_context(), &sale, None).await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_sale_repo_error_rollback() {
+        let mut mock_repo = MockSaleRepo::new();
+        let mock_tx = MockTxManager::default();
+        let rolled_back = mock_tx.rolled_back.clone();
+
+        mock_repo
+            .expect_create()
+            .times(1)
+            .returning(|_, _, _, _| Err(Error::ValidationError("Insufficient stock".to_string())));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(42));
+        let result = service
+            .create(&create_branch_context(), &create_test_sale_create(1), None)
+            .await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+        assert!(rolled_back.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_get_sale_other_branch_forbidden() {
+        let mut mock_repo = MockSaleRepo::new();
+        mock_repo
+            .expect_get_by_id()
+            .times(1)
+            .returning(|_, _| Ok(Some(create_test_sale(2))));
+
+        let service = create_service(mock_repo, MockTxManager::default(), MockIdGen::new());
+        let result = service.get_by_id(&create_branch_context(), 1).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_void_sale_success() {
+        let mut mock_repo = MockSaleRepo::new();
+        let mock_tx = MockTxManager::default();
+        let committed = mock_tx.committed.clone();
+
+        mock_repo
+            .expect_get_by_id()
+            .times(1)
+            .returning(|_, _| Ok(Some(create_test_sale(1))));
+        mock_repo
+            .expect_reversed_lines()
+            .times(1)
+            .returning(|_, _, _| Ok(vec![]));
+        mock_repo
+            .expect_create_reversal()
+            .withf(|_, id, original, kind, lines, _| {
+                *id == 43
+                    && original.id == 1
+                    && *kind == SaleKind::Void
+                    && lines.len() == 1
+                    && lines[0].qty == 2
+            })
+            .times(1)
+            .returning(|_, _, _, _, _, _| Ok(()));
+
+        let service = create_service(mock_repo, mock_tx, create_mock_id_gen(43));
+        let result = service.void(&create_branch_context(), 1).await;
+
+        assert_eq!(result.unwrap(), 43);
+        assert!(committed.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_void_sale_twice_conflict() {
+        let mut mock_repo = MockSaleRepo::new();
+        let mock_tx = MockTxManager::default();
+        let rolled_back = mock_tx.rolled_back.clone();
+
+        mock_repo
+            .expect_get_by_id()
+            .times(1)
+            .returning(|_, _| Ok(Some(create_test_sale(1))));
+        mock_repo
+            .expect_reversed_lines()
+            .times(1)
+            .returning(|_, _, _| {
+                Ok(vec![ReversedLine {
+                    kind: SaleKind::Void,
+                    variant_id: 10,
+                    qty: 2,
+                }])
+            });
+        mock_repo.expect_create_reversal().never();
+
+        let service = create_service(mock_repo, mock_tx, MockIdGen::new());
+        let result = service.void(&create_branch_context(), 1).await;
+
+        assert!(matches!(result, Err(Error::Conflict(_))));
+        assert!(rolled_back.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_refund_more_than_sold() {
+        let mut mock_repo = MockSaleRepo::new();
+        mock_repo
+            .expect_get_by_id()
+            .times(1)
+            .returning(|_, _| Ok(Some(create_test_sale(1))));
+        mock_repo
+            .expect_reversed_lines()
+            .times(1)
+            .returning(|_, _, _| Ok(vec![]));
+        mock_repo.expect_create_reversal().never();
+
+        let service = create_service(mock_repo, MockTxManager::default(), MockIdGen::new());
+        let result = service
+            .refund_lines(
+                &create_branch_context(),
+                1,
+                &[SaleRefundLine {
+                    variant_id: 10,
+                    qty: 3,
+                }],
+            )
+            .await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_void_sale_not_found() {
+        let mut mock_repo = MockSaleRepo::new();
+        mock_repo
+            .expect_get_by_id()
+            .times(1)
+            .returning(|_, _| Ok(None));
+
+        let service = create_service(mock_repo, MockTxManager::default(), MockIdGen::new());
+        let result = service.void(&create_branch_context(), 1).await;
+
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_void_sale_other_branch_forbidden() {
+        let mut mock_repo = MockSaleRepo::new();
+        mock_repo
+            .expect_get_by_id()
+            .times(1)
+            .returning(|_, _| Ok(Some(create_test_sale(2))));
+        mock_repo.expect_reversed_lines().never();
+
+        let service = create_service(mock_repo, MockTxManager::default(), MockIdGen::new());
+        let result = service.void(&create_branch_context(), 1).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_summary_success() {
+        let date = NaiveDate::from_ymd_opt(2025, 12, 1).unwrap();
+        let mut mock_repo = MockSaleRepo::new();
+        mock_repo
+            .expect_summary()
+            .withf(move |_, branch_id, d| *branch_id == 1 && *d == date)
+            .times(1)
+            .returning(move |_, branch_id, date| {
+                Ok(SalesSummary {
+                    branch_id,
+                    date,
+                    sale_count: 0,
+                    gross_total: Money::ZERO,
+                    by_payment_method: vec![],
+                })
+            });
+
+        let service = create_service(mock_repo, MockTxManager::default(), MockIdGen::new());
+        let result = service.summary(&create_branch_context(), 1, date).await;
+
+        assert_eq!(result.unwrap().branch_id, 1);
+    }
+
+    #[tokio::test]
+    async fn test_summary_other_branch_forbidden() {
+        let mut mock_repo = MockSaleRepo::new();
+        mock_repo.expect_summary().never();
+
+        let date = NaiveDate::from_ymd_opt(2025, 12, 1).unwrap();
+        let service = create_service(mock_repo, MockTxManager::default(), MockIdGen::new());
+        let result = service.summary(&create_branch_context(), 2, date).await;
+
+        assert!(matches!(result, Err(Error::Forbidden(_))));
+    }
+}
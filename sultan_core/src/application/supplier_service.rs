@@ -4,7 +4,12 @@ use crate::{
         model::{
             pagination::PaginationOptions,
             permission::{action, resource},
+            phone::{
+                DEFAULT_PHONE_REGION, PhoneRegion, normalize_optional_phone,
+                normalize_phone_filter, normalize_phone_update,
+            },
             supplier::{Supplier, SupplierCreate, SupplierFilter, SupplierUpdate},
+            validate::Validate,
         },
     },
     snowflake::IdGenerator,
@@ -24,11 +29,14 @@ pub trait SupplierServiceTrait: Send + Sync {
         filter: &SupplierFilter,
         pagination: &PaginationOptions,
     ) -> DomainResult<Vec<Supplier>>;
+    /// Number of suppliers matching `filter`, for paginated responses
+    async fn count(&self, ctx: &Context, filter: &SupplierFilter) -> DomainResult<u64>;
 }
 
 pub struct SupplierService<R, I> {
     repository: R,
     id_generator: I,
+    phone_region: PhoneRegion,
 }
 
 impl<R, I> SupplierService<R, I>
@@ -40,6 +48,27 @@ where
         Self {
             repository,
             id_generator,
+            phone_region: DEFAULT_PHONE_REGION,
+        }
+    }
+
+    /// Set the region used to read phone numbers without a country code.
+    ///
+    /// Default: `ID`
+    pub fn with_default_phone_region(mut self, region: PhoneRegion) -> Self {
+        self.phone_region = region;
+        self
+    }
+
+    /// `filter` with its phone normalized like stored phones, so a number
+    /// typed in local format still matches
+    fn normalize_filter(&self, filter: &SupplierFilter) -> SupplierFilter {
+        SupplierFilter {
+            phone: filter
+                .phone
+                .as_deref()
+                .map(|phone| normalize_phone_filter(phone, self.phone_region)),
+            ..filter.clone()
         }
     }
 }
@@ -52,14 +81,24 @@ where
 {
     async fn create(&self, ctx: &Context, supplier: &SupplierCreate) -> DomainResult<i64> {
         ctx.require_access(None, resource::SUPPLIER, action::CREATE)?;
+        supplier.validate()?;
+        let supplier = SupplierCreate {
+            phone: normalize_optional_phone(supplier.phone.as_deref(), self.phone_region)?,
+            ..supplier.clone()
+        };
         let id = self.id_generator.generate()?;
-        self.repository.create(ctx, id, supplier).await?;
+        self.repository.create(ctx, id, &supplier).await?;
         Ok(id)
     }
 
     async fn update(&self, ctx: &Context, id: i64, supplier: &SupplierUpdate) -> DomainResult<()> {
         ctx.require_access(None, resource::SUPPLIER, action::UPDATE)?;
-        self.repository.update(ctx, id, supplier).await
+        supplier.validate()?;
+        let supplier = SupplierUpdate {
+            phone: normalize_phone_update(&supplier.phone, self.phone_region)?,
+            ..supplier.clone()
+        };
+        self.repository.update(ctx, id, &supplier).await
     }
 
     async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()> {
@@ -79,7 +118,15 @@ where
         pagination: &PaginationOptions,
     ) -> DomainResult<Vec<Supplier>> {
         ctx.require_access(None, resource::SUPPLIER, action::READ)?;
-        self.repository.get_all(ctx, filter, pagination).await
+        let filter = self.normalize_filter(filter);
+        ctx.cancellable(self.repository.get_all(ctx, &filter, pagination))
+            .await
+    }
+
+    async fn count(&self, ctx: &Context, filter: &SupplierFilter) -> DomainResult<u64> {
+        ctx.require_access(None, resource::SUPPLIER, action::READ)?;
+        let filter = self.normalize_filter(filter);
+        ctx.cancellable(self.repository.count(ctx, &filter)).await
     }
 }
 
@@ -89,23 +136,10 @@ mod tests {
     use crate::application::create_mock_id_gen;
     use crate::domain::Error;
     use crate::domain::model::Update;
-    use async_trait::async_trait;
+    use crate::testing::mocks::MockSupplierRepo;
     use chrono::Utc;
-    use mockall::mock;
     use std::collections::HashMap;
 
-    mock! {
-        pub SupplierRepo {}
-        #[async_trait]
-        impl SupplierRepository for SupplierRepo {
-            async fn create(&self, ctx: &Context, id: i64,supplier: &SupplierCreate) -> DomainResult<()>;
-            async fn update(&self, ctx: &Context, id: i64, supplier: &SupplierUpdate) -> DomainResult<()>;
-            async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()>;
-            async fn get_all(&self, ctx: &Context, filter: &SupplierFilter, pagination: &PaginationOptions) -> DomainResult<Vec<Supplier>>;
-            async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Supplier>>;
-        }
-    }
-
     /// Creates a test context with full permissions for SUPPLIER resource
     fn create_test_context() -> Context {
         let mut permissions = HashMap::new();
@@ -124,7 +158,7 @@ mod tests {
             code: Some("TEST001".to_string()),
             email: Some("test@supplier.com".to_string()),
             address: Some("123 Test St".to_string()),
-            phone: Some("555-1234".to_string()),
+            phone: Some("0812-3456-789".to_string()),
             npwp: Some("12345678901234".to_string()),
             npwp_name: Some("PT Test Supplier".to_string()),
             metadata: None,
@@ -142,10 +176,12 @@ mod tests {
             code: Some("TEST001".to_string()),
             email: Some("test@supplier.com".to_string()),
             address: Some("123 Test St".to_string()),
-            phone: Some("555-1234".to_string()),
+            phone: Some("0812-3456-789".to_string()),
             npwp: Some("12345678901234".to_string()),
             npwp_name: Some("PT Test Supplier".to_string()),
             metadata: None,
+            created_by: None,
+            updated_by: None,
         }
     }
 
@@ -253,6 +289,99 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_create_supplier_invalid_email() {
+        let ctx = create_test_context();
+        let service = SupplierService::new(MockSupplierRepo::new(), create_mock_id_gen(1));
+        let mut supplier = create_test_supplier_create();
+        supplier.email = Some("foo@".to_string());
+
+        let result = service.create(&ctx, &supplier).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_supplier_invalid_email() {
+        let ctx = create_test_context();
+        let service = SupplierService::new(MockSupplierRepo::new(), create_mock_id_gen(1));
+        let update = SupplierUpdate {
+            email: Update::Set("not-an-email".to_string()),
+            ..Default::default()
+        };
+
+        let result = service.update(&ctx, 1, &update).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_supplier_clear_email_skips_validation() {
+        let mut mock_repo = MockSupplierRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_update()
+            .withf(|_, _, update| update.email == Update::Clear)
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = SupplierService::new(mock_repo, create_mock_id_gen(1));
+        let update = SupplierUpdate {
+            email: Update::Clear,
+            ..Default::default()
+        };
+
+        assert!(service.update(&ctx, 1, &update).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_supplier_normalizes_phone() {
+        let mut mock_repo = MockSupplierRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_create()
+            .withf(|_, _, supplier| supplier.phone.as_deref() == Some("+628123456789"))
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = SupplierService::new(mock_repo, create_mock_id_gen(1));
+        let result = service.create(&ctx, &create_test_supplier_create()).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_supplier_invalid_phone() {
+        let ctx = create_test_context();
+        let service = SupplierService::new(MockSupplierRepo::new(), create_mock_id_gen(1));
+        let mut supplier = create_test_supplier_create();
+        supplier.phone = Some("not a phone".to_string());
+
+        let result = service.create(&ctx, &supplier).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_supplier_normalizes_phone_with_region() {
+        let mut mock_repo = MockSupplierRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_update()
+            .withf(|_, _, update| update.phone == Update::Set("+12015550123".to_string()))
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let service = SupplierService::new(mock_repo, create_mock_id_gen(1))
+            .with_default_phone_region(PhoneRegion::US);
+        let update = SupplierUpdate {
+            phone: Update::Set("(201) 555-0123".to_string()),
+            ..Default::default()
+        };
+
+        assert!(service.update(&ctx, 1, &update).await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_update_supplier_no_permission() {
         let mock_repo = MockSupplierRepo::new();
@@ -549,6 +678,39 @@ mod tests {
         assert_eq!(result_suppliers.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_get_all_normalizes_phone_filter() {
+        let mut mock_repo = MockSupplierRepo::new();
+        let ctx = create_test_context();
+
+        mock_repo
+            .expect_get_all()
+            .withf(|_, filter, _| filter.phone == Some("+628123456789".to_string()))
+            .times(1)
+            .returning(|_, _, _| Ok(vec![]));
+        mock_repo
+            .expect_count()
+            .withf(|_, filter| filter.phone == Some("3456789".to_string()))
+            .times(1)
+            .returning(|_, _| Ok(0));
+
+        let service = SupplierService::new(mock_repo, create_mock_id_gen(1));
+        let filter = SupplierFilter {
+            phone: Some("0812-3456-789".to_string()),
+            ..Default::default()
+        };
+        let result = service
+            .get_all(&ctx, &filter, &create_default_pagination())
+            .await;
+        assert!(result.is_ok());
+
+        let filter = SupplierFilter {
+            phone: Some("3456 789".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(service.count(&ctx, &filter).await.unwrap(), 0);
+    }
+
     #[tokio::test]
     async fn test_get_all_with_pagination() {
         let mut mock_repo = MockSupplierRepo::new();
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use crate::application::cache::CacheService;
-use crate::crypto::password::PasswordHash;
+use crate::crypto::password::{PasswordHash, PasswordPolicy};
 use crate::domain::model::permission::{Permission, action, resource};
 use crate::domain::model::user::{UserCreate, UserUpdate};
 use crate::domain::{Context, DomainResult, User};
@@ -34,6 +34,7 @@ pub struct UserService<R, P, I, C, Tx> {
     repository: R,
     id_generator: I,
     cache: Arc<C>,
+    password_policy: PasswordPolicy,
     _phantom: std::marker::PhantomData<Tx>,
 }
 
@@ -46,9 +47,16 @@ impl<R: UserRepository<Tx>, P: PasswordHash, I: IdGenerator, C: CacheService<i64
             password_hasher,
             id_generator,
             cache,
+            password_policy: PasswordPolicy::default(),
             _phantom: std::marker::PhantomData,
         }
     }
+
+    /// Override the password policy checked on create and reset.
+    pub fn with_password_policy(mut self, policy: PasswordPolicy) -> Self {
+        self.password_policy = policy;
+        self
+    }
 }
 
 #[async_trait]
@@ -62,6 +70,7 @@ where
 {
     async fn create(&self, ctx: &Context, user: &UserCreate) -> DomainResult<()> {
         ctx.require_access(None, resource::USER, action::CREATE)?;
+        self.password_policy.validate(&user.password)?;
         let password_hash = self.password_hasher.hash_password(&user.password)?;
         let mut user_with_password = user.clone();
         let id = self.id_generator.generate()?;
@@ -98,6 +107,7 @@ where
         new_password: String,
     ) -> DomainResult<()> {
         ctx.require_access(None, resource::USER, action::UPDATE)?;
+        self.password_policy.validate(&new_password)?;
         let password_hash = self.password_hasher.hash_password(&new_password)?;
         self.repository
             .update_password(ctx, user_id, &password_hash)
@@ -149,34 +159,12 @@ mod tests {
     use super::*;
     use crate::application::InMemoryCache;
     use crate::domain::Error;
-    use crate::domain::model::pagination::PaginationOptions;
     use crate::domain::model::permission::Permission;
-    use crate::domain::model::user::UserFilter;
-    use async_trait::async_trait;
+    use crate::testing::mocks::MockUserRepo;
     use chrono::Utc;
     use mockall::mock;
     use std::collections::HashMap;
 
-    // Use a unit type for mock transaction since mocks don't use real transactions
-    mock! {
-        pub UserRepo {}
-        #[async_trait]
-        impl UserRepository<()> for UserRepo {
-            async fn create_user(&self, ctx: &Context, id: i64, user: &UserCreate) -> DomainResult<()>;
-            async fn create_user_tx(&self, ctx: &Context, id: i64, user: &UserCreate, tx: &mut ()) -> DomainResult<()>;
-            async fn get_user_by_username(&self, ctx: &Context, username: &str) -> DomainResult<Option<User>>;
-            async fn update_user(&self, ctx: &Context, id: i64, user: &UserUpdate) -> DomainResult<()>;
-            async fn update_password(&self, ctx: &Context, id: i64, password_hash: &str) -> DomainResult<()>;
-            async fn delete_user(&self, ctx: &Context, user_id: i64) -> DomainResult<()>;
-            async fn delete_user_tx(&self, ctx: &Context, user_id: i64, tx: &mut ()) -> DomainResult<()>;
-            async fn get_all(&self, ctx: &Context, filter: UserFilter, pagination: PaginationOptions) -> DomainResult<Vec<User>>;
-            async fn get_by_id(&self, ctx: &Context, user_id: i64) -> DomainResult<Option<User>>;
-            async fn save_user_permission(&self, ctx: &Context, user_id: i64, branch_id: Option<i64>, permission: i32, action: i32) -> DomainResult<()>;
-            async fn delete_user_permission(&self, ctx: &Context, user_id: i64, branch_id: Option<i64>, permission: i32) -> DomainResult<()>;
-            async fn get_user_permission(&self, ctx: &Context, user_id: i64) -> DomainResult<Vec<Permission>>;
-        }
-    }
-
     mock! {
         pub Hasher {}
         impl PasswordHash for Hasher {
@@ -215,7 +203,7 @@ mod tests {
             username: "testuser".to_string(),
             name: "Test User".to_string(),
             email: Some("test@example.com".to_string()),
-            password: "plainpassword".to_string(),
+            password: "plainpassword1".to_string(),
             photo: None,
             pin: None,
             address: None,
@@ -238,6 +226,9 @@ mod tests {
             pin: None,
             address: None,
             phone: None,
+            is_enabled: true,
+            failed_login_count: 0,
+            locked_until: None,
             permissions: None,
         }
     }
@@ -252,6 +243,7 @@ mod tests {
             pin: Update::Unchanged,
             address: Update::Unchanged,
             phone: Update::Unchanged,
+            is_enabled: None,
         }
     }
 
@@ -263,7 +255,7 @@ mod tests {
 
         mock_hasher
             .expect_hash_password()
-            .withf(|p| p == "plainpassword")
+            .withf(|p| p == "plainpassword1")
             .times(1)
             .returning(|_| Ok("hashed_password".to_string()));
 
@@ -354,6 +346,54 @@ mod tests {
         assert!(matches!(result, Err(Error::Database(_))));
     }
 
+    #[tokio::test]
+    async fn test_create_user_weak_password() {
+        let mut mock_repo = MockUserRepo::new();
+        let mut mock_hasher = MockHasher::new();
+        let ctx = create_test_context();
+
+        mock_hasher.expect_hash_password().times(0);
+        mock_repo.expect_create_user().times(0);
+
+        let service = UserService::new(
+            mock_repo,
+            Arc::new(mock_hasher),
+            create_mock_id_gen(),
+            Arc::new(InMemoryCache::<i64>::new()),
+        );
+        let mut user = create_test_user();
+        user.password = "short".to_string();
+        let result = service.create(&ctx, &user).await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_user_custom_password_policy() {
+        let mut mock_repo = MockUserRepo::new();
+        let mut mock_hasher = MockHasher::new();
+        let ctx = create_test_context();
+
+        mock_hasher.expect_hash_password().times(0);
+        mock_repo.expect_create_user().times(0);
+
+        let service = UserService::new(
+            mock_repo,
+            Arc::new(mock_hasher),
+            create_mock_id_gen(),
+            Arc::new(InMemoryCache::<i64>::new()),
+        )
+        .with_password_policy(PasswordPolicy {
+            min_length: 20,
+            ..Default::default()
+        });
+        let result = service.create(&ctx, &create_test_user()).await;
+
+        assert!(
+            matches!(result, Err(Error::ValidationError(msg)) if msg.contains("at least 20 characters"))
+        );
+    }
+
     #[tokio::test]
     async fn test_update_user_success() {
         let mut mock_repo = MockUserRepo::new();
@@ -477,7 +517,7 @@ mod tests {
 
         mock_hasher
             .expect_hash_password()
-            .withf(|p| p == "newpassword")
+            .withf(|p| p == "newpassword1")
             .times(1)
             .returning(|_| Ok("new_hashed_password".to_string()));
 
@@ -498,7 +538,7 @@ mod tests {
             Arc::new(InMemoryCache::<i64>::new()),
         );
         let result = service
-            .reset_password(&ctx, 1, "newpassword".to_string())
+            .reset_password(&ctx, 1, "newpassword1".to_string())
             .await;
 
         assert!(result.is_ok());
@@ -517,12 +557,34 @@ mod tests {
             Arc::new(InMemoryCache::<i64>::new()),
         );
         let result = service
-            .reset_password(&ctx, 1, "newpassword".to_string())
+            .reset_password(&ctx, 1, "newpassword1".to_string())
             .await;
 
         assert!(matches!(result, Err(Error::Forbidden(_))));
     }
 
+    #[tokio::test]
+    async fn test_reset_password_weak_password() {
+        let mut mock_repo = MockUserRepo::new();
+        let mut mock_hasher = MockHasher::new();
+        let ctx = create_test_context();
+
+        mock_hasher.expect_hash_password().times(0);
+        mock_repo.expect_update_password().times(0);
+
+        let service = UserService::new(
+            mock_repo,
+            Arc::new(mock_hasher),
+            create_mock_id_gen(),
+            Arc::new(InMemoryCache::<i64>::new()),
+        );
+        let result = service
+            .reset_password(&ctx, 1, "nodigitshere".to_string())
+            .await;
+
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
     #[tokio::test]
     async fn test_reset_password_hash_error() {
         let mock_repo = MockUserRepo::new();
@@ -541,7 +603,7 @@ mod tests {
             Arc::new(InMemoryCache::<i64>::new()),
         );
         let result = service
-            .reset_password(&ctx, 1, "newpassword".to_string())
+            .reset_password(&ctx, 1, "newpassword1".to_string())
             .await;
 
         assert!(matches!(result, Err(Error::Internal(_))));
@@ -779,7 +841,9 @@ mod tests {
         );
 
         // Reset password - should invalidate cache
-        let result = service.reset_password(&ctx, 1, "newpass".to_string()).await;
+        let result = service
+            .reset_password(&ctx, 1, "newpass123".to_string())
+            .await;
         assert!(result.is_ok());
 
         // Cache should be cleared
@@ -1,8 +1,12 @@
 use chrono::{Duration, Utc};
-use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
 
+use crate::domain::BranchContext;
+
 /// JWT error types
 #[derive(Debug)]
 pub enum JwtError {
@@ -12,6 +16,12 @@ pub enum JwtError {
     Invalid(String),
     /// Failed to encode token
     EncodingFailed(String),
+    /// A configured key could not be parsed
+    InvalidKey(String),
+    /// Token was issued for a different audience, or names none
+    InvalidAudience,
+    /// Token was issued by a different issuer, or names none
+    InvalidIssuer,
 }
 
 impl fmt::Display for JwtError {
@@ -20,6 +30,9 @@ impl fmt::Display for JwtError {
             JwtError::Expired => write!(f, "Token has expired"),
             JwtError::Invalid(msg) => write!(f, "Invalid token: {}", msg),
             JwtError::EncodingFailed(msg) => write!(f, "Failed to encode token: {}", msg),
+            JwtError::InvalidKey(msg) => write!(f, "Invalid key: {}", msg),
+            JwtError::InvalidAudience => write!(f, "Token audience is not accepted"),
+            JwtError::InvalidIssuer => write!(f, "Token issuer is not accepted"),
         }
     }
 }
@@ -38,90 +51,379 @@ pub struct Claims {
     pub exp: i64,
     /// Issued at (as Unix timestamp)
     pub iat: i64,
+    /// Issuer, set when the manager is configured with one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    /// Audience, set when the manager is configured with one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
     /// User ID
     pub user_id: i64,
     /// Username
     pub username: String,
+    /// Branch ids the user belongs to. `None` when the memberships were not
+    /// embedded and have to be looked up.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_ids: Option<Vec<i64>>,
+    /// Default branch for the user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_branch_id: Option<i64>,
+    /// Kiosk device the token was issued to. The device can be revoked, so
+    /// these tokens are re-checked on every request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<i64>,
+}
+
+impl Claims {
+    /// Branch context carried by the token, if it was embedded at login.
+    pub fn branch_context(&self) -> Option<BranchContext> {
+        self.branch_ids
+            .as_ref()
+            .map(|ids| BranchContext::new(ids.clone(), self.default_branch_id))
+    }
+}
+
+/// Algorithm tokens are signed and verified with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JwtAlgorithm {
+    /// HMAC with a secret shared by every service that verifies tokens
+    #[default]
+    HS256,
+    /// RSA key pair. Services that only verify tokens need just the public key.
+    RS256,
 }
 
+impl From<JwtAlgorithm> for Algorithm {
+    fn from(algorithm: JwtAlgorithm) -> Self {
+        match algorithm {
+            JwtAlgorithm::HS256 => Algorithm::HS256,
+            JwtAlgorithm::RS256 => Algorithm::RS256,
+        }
+    }
+}
+
+/// Key id used when a config does not name one
+pub const DEFAULT_KEY_ID: &str = "default";
+
+/// Clock skew tolerated between the issuing and verifying machines
+pub const DEFAULT_LEEWAY_SECS: u64 = 30;
+
+/// Lifetime of kiosk device tokens. Devices hold no refresh token and log in
+/// again with their key, so their tokens can be short.
+pub const DEFAULT_DEVICE_EXPIRATION_MINUTES: i64 = 5;
+
 /// Configuration for JWT
 #[derive(Clone)]
 pub struct JwtConfig {
-    /// Secret key for signing tokens
-    secret: String,
+    /// Written to the `kid` header so verifiers can pick the matching key
+    key_id: String,
+    algorithm: JwtAlgorithm,
+    /// Key for signing tokens; None when tokens are only verified
+    signing_key: Option<EncodingKey>,
+    /// Key for verifying tokens
+    verification_key: DecodingKey,
     /// Token expiration in minutes
     expiration_minutes: i64,
+    /// Device token expiration in minutes
+    device_expiration_minutes: i64,
+    /// Seconds of clock skew allowed when checking `exp`, `nbf` and `iat`
+    leeway_secs: u64,
+    /// Issuer written to and required from tokens
+    issuer: Option<String>,
+    /// Audience written to and required from tokens
+    audience: Option<String>,
 }
 
 impl JwtConfig {
+    /// HS256 with a shared secret
     pub fn new(secret: impl Into<String>, expiration_minutes: i64) -> Self {
+        let secret = secret.into();
         Self {
-            secret: secret.into(),
+            key_id: DEFAULT_KEY_ID.to_string(),
+            algorithm: JwtAlgorithm::HS256,
+            signing_key: Some(EncodingKey::from_secret(secret.as_bytes())),
+            verification_key: DecodingKey::from_secret(secret.as_bytes()),
             expiration_minutes,
+            device_expiration_minutes: DEFAULT_DEVICE_EXPIRATION_MINUTES,
+            leeway_secs: DEFAULT_LEEWAY_SECS,
+            issuer: None,
+            audience: None,
         }
     }
 
+    /// RS256 with PEM encoded keys. Without a private key the config can
+    /// verify tokens but not issue them.
+    pub fn rs256(
+        private_key_pem: Option<&str>,
+        public_key_pem: &str,
+        expiration_minutes: i64,
+    ) -> JwtResult<Self> {
+        let signing_key = private_key_pem
+            .map(|pem| EncodingKey::from_rsa_pem(pem.as_bytes()))
+            .transpose()
+            .map_err(|e| JwtError::InvalidKey(format!("private key: {}", e)))?;
+        let verification_key = DecodingKey::from_rsa_pem(public_key_pem.as_bytes())
+            .map_err(|e| JwtError::InvalidKey(format!("public key: {}", e)))?;
+        Ok(Self {
+            key_id: DEFAULT_KEY_ID.to_string(),
+            algorithm: JwtAlgorithm::RS256,
+            signing_key,
+            verification_key,
+            expiration_minutes,
+            device_expiration_minutes: DEFAULT_DEVICE_EXPIRATION_MINUTES,
+            leeway_secs: DEFAULT_LEEWAY_SECS,
+            issuer: None,
+            audience: None,
+        })
+    }
+
+    /// Name this key. Needed when several keys are live during rotation.
+    pub fn with_key_id(mut self, key_id: impl Into<String>) -> Self {
+        self.key_id = key_id.into();
+        self
+    }
+
+    /// Override how long kiosk device tokens stay valid
+    pub fn with_device_expiration_minutes(mut self, device_expiration_minutes: i64) -> Self {
+        self.device_expiration_minutes = device_expiration_minutes;
+        self
+    }
+
+    /// Override the clock skew allowed when validating tokens
+    pub fn with_leeway_secs(mut self, leeway_secs: u64) -> Self {
+        self.leeway_secs = leeway_secs;
+        self
+    }
+
+    pub fn leeway_secs(&self) -> u64 {
+        self.leeway_secs
+    }
+
+    /// Stamp tokens with `iss` and reject tokens from any other issuer
+    pub fn with_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// Stamp tokens with `aud` and reject tokens meant for anyone else
+    pub fn with_audience(mut self, audience: impl Into<String>) -> Self {
+        self.audience = Some(audience.into());
+        self
+    }
+
+    pub fn issuer(&self) -> Option<&str> {
+        self.issuer.as_deref()
+    }
+
+    pub fn audience(&self) -> Option<&str> {
+        self.audience.as_deref()
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    pub fn algorithm(&self) -> JwtAlgorithm {
+        self.algorithm
+    }
+
     pub fn expiration_minutes(&self) -> i64 {
         self.expiration_minutes
     }
+
+    pub fn device_expiration_minutes(&self) -> i64 {
+        self.device_expiration_minutes
+    }
 }
 
 /// JWT token manager
 pub trait JwtManager: Send + Sync {
-    /// Generate a JWT token for a user
-    fn generate_token(&self, user_id: i64, username: &str) -> JwtResult<String>;
+    /// Generate a JWT token for a user, optionally embedding branch memberships
+    fn generate_token(
+        &self,
+        user_id: i64,
+        username: &str,
+        branches: Option<&BranchContext>,
+    ) -> JwtResult<String>;
+
+    /// Generate a JWT token for a kiosk device acting as `user_id`
+    fn generate_device_token(
+        &self,
+        user_id: i64,
+        username: &str,
+        device_id: i64,
+        branches: Option<&BranchContext>,
+    ) -> JwtResult<String>;
 
     /// Validate and decode a JWT token
     fn validate_token(&self, token: &str) -> JwtResult<Claims>;
+
+    /// How long a generated token stays valid
+    fn token_lifetime(&self) -> Duration;
+
+    /// How long a generated device token stays valid
+    fn device_token_lifetime(&self) -> Duration;
 }
 
 /// Default JWT manager implementation
+///
+/// Tokens are always signed with the current key. Retired keys can be kept
+/// for verification so rotating the key does not log everyone out at once.
 #[derive(Clone)]
 pub struct DefaultJwtManager {
     config: JwtConfig,
-    encoding_key: EncodingKey,
-    decoding_key: DecodingKey,
+    /// Keys accepted for verification by `kid`, including the current one
+    verification_keys: HashMap<String, JwtConfig>,
 }
 
 impl DefaultJwtManager {
     pub fn new(config: JwtConfig) -> Self {
-        let encoding_key = EncodingKey::from_secret(config.secret.as_bytes());
-        let decoding_key = DecodingKey::from_secret(config.secret.as_bytes());
-
+        let verification_keys = HashMap::from([(config.key_id.clone(), config.clone())]);
         Self {
             config,
-            encoding_key,
-            decoding_key,
+            verification_keys,
         }
     }
-}
 
-impl JwtManager for DefaultJwtManager {
-    fn generate_token(&self, user_id: i64, username: &str) -> JwtResult<String> {
+    /// Keep accepting tokens signed with a retired key until it is dropped
+    /// from the configuration. The current key wins on a `kid` clash.
+    pub fn with_previous_key(mut self, config: JwtConfig) -> Self {
+        self.verification_keys
+            .entry(config.key_id.clone())
+            .or_insert(config);
+        self
+    }
+
+    /// Pick the verification key named by the token's `kid`. Tokens issued
+    /// before key ids were added carry none and fall back to the current key.
+    fn verification_config(&self, token: &str) -> JwtResult<&JwtConfig> {
+        let header =
+            jsonwebtoken::decode_header(token).map_err(|e| JwtError::Invalid(e.to_string()))?;
+        match header.kid {
+            None => Ok(&self.config),
+            Some(kid) => self
+                .verification_keys
+                .get(&kid)
+                .ok_or_else(|| JwtError::Invalid(format!("Unknown key id '{}'", kid))),
+        }
+    }
+
+    fn encode_claims(
+        &self,
+        user_id: i64,
+        username: &str,
+        device_id: Option<i64>,
+        branches: Option<&BranchContext>,
+        lifetime: Duration,
+    ) -> JwtResult<String> {
         let now = Utc::now();
-        let exp = now + Duration::minutes(self.config.expiration_minutes);
+        let exp = now + lifetime;
 
         let claims = Claims {
             sub: user_id.to_string(),
             exp: exp.timestamp(),
             iat: now.timestamp(),
+            iss: self.config.issuer.clone(),
+            aud: self.config.audience.clone(),
             user_id,
             username: username.to_string(),
+            branch_ids: branches.map(|b| b.branch_ids.clone()),
+            default_branch_id: branches.and_then(|b| b.default_branch_id),
+            device_id,
         };
 
-        encode(&Header::default(), &claims, &self.encoding_key)
-            .map_err(|e| JwtError::EncodingFailed(e.to_string()))
+        let signing_key = self
+            .config
+            .signing_key
+            .as_ref()
+            .ok_or_else(|| JwtError::EncodingFailed("No signing key configured".to_string()))?;
+        let mut header = Header::new(self.config.algorithm.into());
+        header.kid = Some(self.config.key_id.clone());
+        encode(&header, &claims, signing_key).map_err(|e| JwtError::EncodingFailed(e.to_string()))
+    }
+}
+
+impl JwtManager for DefaultJwtManager {
+    fn generate_token(
+        &self,
+        user_id: i64,
+        username: &str,
+        branches: Option<&BranchContext>,
+    ) -> JwtResult<String> {
+        self.encode_claims(user_id, username, None, branches, self.token_lifetime())
+    }
+
+    fn generate_device_token(
+        &self,
+        user_id: i64,
+        username: &str,
+        device_id: i64,
+        branches: Option<&BranchContext>,
+    ) -> JwtResult<String> {
+        self.encode_claims(
+            user_id,
+            username,
+            Some(device_id),
+            branches,
+            self.device_token_lifetime(),
+        )
     }
 
     fn validate_token(&self, token: &str) -> JwtResult<Claims> {
-        let validation = Validation::default();
+        let config = self.verification_config(token)?;
+        // Only the key's own algorithm is accepted, so an HS256 verifier
+        // rejects RS256 tokens and the other way around
+        let mut validation = Validation::new(config.algorithm.into());
+        // Leeway is a property of this verifier, not of the key that signed
+        validation.leeway = self.config.leeway_secs;
+        validation.validate_nbf = true;
+        // A configured issuer or audience must be present, not just match
+        // when present
+        match &self.config.issuer {
+            Some(issuer) => {
+                validation.set_issuer(&[issuer]);
+                validation.required_spec_claims.insert("iss".to_string());
+            }
+            None => validation.iss = None,
+        }
+        match &self.config.audience {
+            Some(audience) => {
+                validation.set_audience(&[audience]);
+                validation.required_spec_claims.insert("aud".to_string());
+            }
+            None => validation.validate_aud = false,
+        }
 
-        decode::<Claims>(token, &self.decoding_key, &validation)
+        let claims = decode::<Claims>(token, &config.verification_key, &validation)
             .map(|data| data.claims)
             .map_err(|e| match e.kind() {
-                jsonwebtoken::errors::ErrorKind::ExpiredSignature => JwtError::Expired,
+                ErrorKind::ExpiredSignature => JwtError::Expired,
+                ErrorKind::InvalidAudience => JwtError::InvalidAudience,
+                ErrorKind::InvalidIssuer => JwtError::InvalidIssuer,
+                ErrorKind::MissingRequiredClaim(claim) if claim == "aud" => {
+                    JwtError::InvalidAudience
+                }
+                ErrorKind::MissingRequiredClaim(claim) if claim == "iss" => JwtError::InvalidIssuer,
                 _ => JwtError::Invalid(e.to_string()),
-            })
+            })?;
+
+        // jsonwebtoken does not look at `iat`, so a token from a signer whose
+        // clock runs ahead is caught here
+        let latest_iat = Utc::now().timestamp() + self.config.leeway_secs as i64;
+        if claims.iat > latest_iat {
+            return Err(JwtError::Invalid(
+                "token is issued in the future".to_string(),
+            ));
+        }
+        Ok(claims)
+    }
+
+    fn token_lifetime(&self) -> Duration {
+        Duration::minutes(self.config.expiration_minutes)
+    }
+
+    fn device_token_lifetime(&self) -> Duration {
+        Duration::minutes(self.config.device_expiration_minutes)
     }
 }
 
@@ -139,7 +441,7 @@ mod tests {
         let manager = create_jwt_manager();
 
         let token = manager
-            .generate_token(123, "testuser")
+            .generate_token(123, "testuser", None)
             .expect("Failed to generate token");
 
         let claims = manager
@@ -149,6 +451,298 @@ mod tests {
         assert_eq!(claims.user_id, 123);
         assert_eq!(claims.username, "testuser");
         assert_eq!(claims.sub, "123");
+        assert!(claims.branch_context().is_none());
+    }
+
+    #[test]
+    fn test_token_carries_branches() {
+        let manager = create_jwt_manager();
+        let branches = BranchContext::new(vec![10, 20], Some(20));
+
+        let token = manager
+            .generate_token(123, "testuser", Some(&branches))
+            .expect("Failed to generate token");
+
+        let claims = manager
+            .validate_token(&token)
+            .expect("Failed to validate token");
+
+        assert_eq!(claims.branch_ids, Some(vec![10, 20]));
+        assert_eq!(claims.default_branch_id, Some(20));
+        assert_eq!(claims.branch_context(), Some(branches));
+    }
+
+    #[test]
+    fn test_device_token_carries_device_id() {
+        let manager = create_jwt_manager();
+
+        let token = manager
+            .generate_device_token(123, "kiosk", 77, None)
+            .expect("Failed to generate token");
+        let claims = manager
+            .validate_token(&token)
+            .expect("Failed to validate token");
+
+        assert_eq!(claims.user_id, 123);
+        assert_eq!(claims.device_id, Some(77));
+
+        // Regular tokens carry no device
+        let token = manager.generate_token(123, "kiosk", None).unwrap();
+        assert_eq!(manager.validate_token(&token).unwrap().device_id, None);
+    }
+
+    #[test]
+    fn test_device_token_uses_device_lifetime() {
+        let config = JwtConfig::new("test_secret_key_for_testing_only", 60)
+            .with_device_expiration_minutes(2);
+        let manager = DefaultJwtManager::new(config);
+
+        let token = manager
+            .generate_device_token(123, "kiosk", 77, None)
+            .expect("Failed to generate token");
+        let claims = manager
+            .validate_token(&token)
+            .expect("Failed to validate token");
+        assert_eq!(claims.exp - claims.iat, 2 * 60);
+
+        let token = manager.generate_token(123, "kiosk", None).unwrap();
+        let claims = manager.validate_token(&token).unwrap();
+        assert_eq!(claims.exp - claims.iat, 60 * 60);
+    }
+
+    const RS256_PRIVATE_KEY: &str = include_str!("testdata/rs256_private.pem");
+    const RS256_PUBLIC_KEY: &str = include_str!("testdata/rs256_public.pem");
+
+    #[test]
+    fn test_rs256_generate_and_validate_token() {
+        let config = JwtConfig::rs256(Some(RS256_PRIVATE_KEY), RS256_PUBLIC_KEY, 60).unwrap();
+        assert_eq!(config.algorithm(), JwtAlgorithm::RS256);
+        let manager = DefaultJwtManager::new(config);
+
+        let token = manager
+            .generate_token(123, "testuser", None)
+            .expect("Failed to generate token");
+        let header = jsonwebtoken::decode_header(&token).unwrap();
+        assert_eq!(header.alg, Algorithm::RS256);
+
+        // A service holding only the public key verifies it
+        let verifier =
+            DefaultJwtManager::new(JwtConfig::rs256(None, RS256_PUBLIC_KEY, 60).unwrap());
+        let claims = verifier
+            .validate_token(&token)
+            .expect("Failed to validate token");
+        assert_eq!(claims.user_id, 123);
+        assert!(matches!(
+            verifier.generate_token(123, "testuser", None),
+            Err(JwtError::EncodingFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_hs256_verifier_rejects_rs256_token() {
+        let rs256 = DefaultJwtManager::new(
+            JwtConfig::rs256(Some(RS256_PRIVATE_KEY), RS256_PUBLIC_KEY, 60).unwrap(),
+        );
+        let hs256 = create_jwt_manager();
+        assert_eq!(
+            JwtConfig::new("secret", 60).algorithm(),
+            JwtAlgorithm::HS256
+        );
+
+        let token = rs256.generate_token(123, "testuser", None).unwrap();
+        assert!(matches!(
+            hs256.validate_token(&token),
+            Err(JwtError::Invalid(_))
+        ));
+
+        let token = hs256.generate_token(123, "testuser", None).unwrap();
+        assert!(matches!(
+            rs256.validate_token(&token),
+            Err(JwtError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_rs256_rejects_malformed_keys() {
+        assert!(matches!(
+            JwtConfig::rs256(None, "not a key", 60),
+            Err(JwtError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            JwtConfig::rs256(Some("not a key"), RS256_PUBLIC_KEY, 60),
+            Err(JwtError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_token_carries_key_id() {
+        let manager = DefaultJwtManager::new(JwtConfig::new("secret", 60).with_key_id("2026-10"));
+
+        let token = manager.generate_token(123, "testuser", None).unwrap();
+        let header = jsonwebtoken::decode_header(&token).unwrap();
+        assert_eq!(header.kid.as_deref(), Some("2026-10"));
+    }
+
+    #[test]
+    fn test_rotated_key_still_verifies() {
+        let old_key = JwtConfig::new("old_secret", 60).with_key_id("old");
+        let old_manager = DefaultJwtManager::new(old_key.clone());
+        let old_token = old_manager.generate_token(123, "testuser", None).unwrap();
+
+        let manager = DefaultJwtManager::new(JwtConfig::new("new_secret", 60).with_key_id("new"))
+            .with_previous_key(old_key);
+
+        let claims = manager
+            .validate_token(&old_token)
+            .expect("Token signed with a previous key should verify");
+        assert_eq!(claims.user_id, 123);
+
+        // New tokens are signed with the current key only
+        let new_token = manager.generate_token(456, "testuser", None).unwrap();
+        let header = jsonwebtoken::decode_header(&new_token).unwrap();
+        assert_eq!(header.kid.as_deref(), Some("new"));
+        assert!(manager.validate_token(&new_token).is_ok());
+        assert!(old_manager.validate_token(&new_token).is_err());
+    }
+
+    #[test]
+    fn test_unknown_key_id_rejected() {
+        let retired = DefaultJwtManager::new(JwtConfig::new("old_secret", 60).with_key_id("old"));
+        let token = retired.generate_token(123, "testuser", None).unwrap();
+
+        // The old key has been dropped from the configuration
+        let manager = DefaultJwtManager::new(JwtConfig::new("new_secret", 60).with_key_id("new"));
+
+        match manager.validate_token(&token) {
+            Err(JwtError::Invalid(msg)) => assert!(msg.contains("old")),
+            other => panic!("Expected unknown key id error, got {:?}", other),
+        }
+    }
+
+    /// Sign claims expiring at `exp` without a `kid`, bypassing the manager
+    fn encode_with_exp(secret: &str, exp: i64) -> String {
+        encode_with_times(secret, exp, Utc::now().timestamp())
+    }
+
+    /// Sign claims issued at `iat` and expiring at `exp` without a `kid`
+    fn encode_with_times(secret: &str, exp: i64, iat: i64) -> String {
+        let claims = Claims {
+            sub: "123".to_string(),
+            exp,
+            iat,
+            iss: None,
+            aud: None,
+            user_id: 123,
+            username: "testuser".to_string(),
+            branch_ids: None,
+            default_branch_id: None,
+            device_id: None,
+        };
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_token_without_key_id_uses_current_key() {
+        let token = encode_with_exp(
+            "test_secret_key_for_testing_only",
+            (Utc::now() + Duration::minutes(5)).timestamp(),
+        );
+
+        let claims = create_jwt_manager().validate_token(&token).unwrap();
+        assert_eq!(claims.user_id, 123);
+    }
+
+    #[test]
+    fn test_leeway_tolerates_clock_skew() {
+        let token = encode_with_exp("secret", Utc::now().timestamp() - 10);
+
+        let config = JwtConfig::new("secret", 60);
+        assert_eq!(config.leeway_secs(), DEFAULT_LEEWAY_SECS);
+        let lenient = DefaultJwtManager::new(config.clone().with_leeway_secs(30));
+        assert!(lenient.validate_token(&token).is_ok());
+
+        let strict = DefaultJwtManager::new(config.with_leeway_secs(0));
+        assert!(matches!(
+            strict.validate_token(&token),
+            Err(JwtError::Expired)
+        ));
+    }
+
+    #[test]
+    fn test_token_issued_in_the_future_rejected() {
+        let now = Utc::now().timestamp();
+        let exp = now + 3600;
+        let manager = DefaultJwtManager::new(JwtConfig::new("secret", 60).with_leeway_secs(30));
+
+        // Within the leeway the signer's clock is only slightly ahead
+        let skewed = encode_with_times("secret", exp, now + 10);
+        assert!(manager.validate_token(&skewed).is_ok());
+
+        let future = encode_with_times("secret", exp, now + 120);
+        match manager.validate_token(&future) {
+            Err(JwtError::Invalid(msg)) => assert!(msg.contains("issued in the future")),
+            other => panic!("Expected future iat to be rejected, got {:?}", other),
+        }
+    }
+
+    fn scoped_config(audience: &str) -> JwtConfig {
+        JwtConfig::new("secret", 60)
+            .with_issuer("sultan-auth")
+            .with_audience(audience)
+    }
+
+    #[test]
+    fn test_matching_audience_and_issuer() {
+        let manager = DefaultJwtManager::new(scoped_config("sultan-pos"));
+
+        let token = manager.generate_token(123, "testuser", None).unwrap();
+        let claims = manager.validate_token(&token).unwrap();
+
+        assert_eq!(claims.iss.as_deref(), Some("sultan-auth"));
+        assert_eq!(claims.aud.as_deref(), Some("sultan-pos"));
+    }
+
+    #[test]
+    fn test_mismatched_audience_rejected() {
+        let issuer = DefaultJwtManager::new(scoped_config("sultan-reporting"));
+        let verifier = DefaultJwtManager::new(scoped_config("sultan-pos"));
+
+        let token = issuer.generate_token(123, "testuser", None).unwrap();
+        assert!(matches!(
+            verifier.validate_token(&token),
+            Err(JwtError::InvalidAudience)
+        ));
+
+        // A token minted without an audience is not accepted either
+        let token = create_jwt_manager()
+            .generate_token(123, "testuser", None)
+            .unwrap();
+        let verifier = DefaultJwtManager::new(
+            JwtConfig::new("test_secret_key_for_testing_only", 60).with_audience("sultan-pos"),
+        );
+        assert!(matches!(
+            verifier.validate_token(&token),
+            Err(JwtError::InvalidAudience)
+        ));
+    }
+
+    #[test]
+    fn test_mismatched_issuer_rejected() {
+        let issuer =
+            DefaultJwtManager::new(JwtConfig::new("secret", 60).with_issuer("other-tenant"));
+        let verifier =
+            DefaultJwtManager::new(JwtConfig::new("secret", 60).with_issuer("sultan-auth"));
+
+        let token = issuer.generate_token(123, "testuser", None).unwrap();
+        assert!(matches!(
+            verifier.validate_token(&token),
+            Err(JwtError::InvalidIssuer)
+        ));
     }
 
     #[test]
@@ -172,8 +766,13 @@ mod tests {
             sub: "123".to_string(),
             exp: 0, // Unix timestamp 0 = 1970, definitely expired
             iat: 0,
+            iss: None,
+            aud: None,
             user_id: 123,
             username: "testuser".to_string(),
+            branch_ids: None,
+            default_branch_id: None,
+            device_id: None,
         };
 
         let token = encode(
@@ -1,5 +1,7 @@
 pub mod jwt;
 pub mod password;
 
-pub use jwt::{Claims, DefaultJwtManager, JwtConfig, JwtError, JwtManager, JwtResult};
-pub use password::{Argon2PasswordHasher, PasswordHash};
+pub use jwt::{
+    Claims, DefaultJwtManager, JwtAlgorithm, JwtConfig, JwtError, JwtManager, JwtResult,
+};
+pub use password::{Argon2PasswordHasher, PasswordHash, PasswordPolicy};
@@ -38,10 +38,103 @@ impl PasswordHash for Argon2PasswordHasher {
     }
 }
 
+/// Strength rules applied to plain-text passwords before hashing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_letter: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            require_letter: true,
+            require_digit: true,
+            require_symbol: false,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    pub fn validate(&self, password: &str) -> DomainResult<()> {
+        if password.chars().count() < self.min_length {
+            return Err(Error::ValidationError(format!(
+                "Password must be at least {} characters long",
+                self.min_length
+            )));
+        }
+
+        let mut missing = Vec::new();
+        if self.require_letter && !password.chars().any(|c| c.is_alphabetic()) {
+            missing.push("a letter");
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            missing.push("a digit");
+        }
+        if self.require_symbol && password.chars().all(|c| c.is_alphanumeric()) {
+            missing.push("a symbol");
+        }
+
+        if !missing.is_empty() {
+            return Err(Error::ValidationError(format!(
+                "Password must contain {}",
+                missing.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_password_policy_rejects_short_password() {
+        let result = PasswordPolicy::default().validate("abc12");
+
+        assert!(
+            matches!(result, Err(Error::ValidationError(msg)) if msg == "Password must be at least 8 characters long")
+        );
+    }
+
+    #[test]
+    fn test_password_policy_rejects_missing_digit() {
+        let result = PasswordPolicy::default().validate("onlyletters");
+
+        assert!(
+            matches!(result, Err(Error::ValidationError(msg)) if msg == "Password must contain a digit")
+        );
+    }
+
+    #[test]
+    fn test_password_policy_rejects_missing_classes() {
+        let policy = PasswordPolicy {
+            require_symbol: true,
+            ..Default::default()
+        };
+        let result = policy.validate("12345678");
+
+        assert!(
+            matches!(result, Err(Error::ValidationError(msg)) if msg == "Password must contain a letter, a symbol")
+        );
+    }
+
+    #[test]
+    fn test_password_policy_accepts_strong_password() {
+        assert!(PasswordPolicy::default().validate("secret123").is_ok());
+
+        let policy = PasswordPolicy {
+            require_symbol: true,
+            ..Default::default()
+        };
+        assert!(policy.validate("secret-123").is_ok());
+    }
+
     #[test]
     fn test_hash_password_returns_hash() {
         let hasher = Argon2PasswordHasher::default();
@@ -0,0 +1,21 @@
+/// Event name stored for a denied `require_access` check
+pub const PERMISSION_DENIED: &str = "permission_denied";
+
+/// A `require_access` check that returned `Forbidden`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionDenial {
+    /// The user the request acts for, if authenticated
+    pub actor_id: Option<i64>,
+    pub request_id: Option<i64>,
+    pub resource: i32,
+    pub action: i32,
+    pub branch_id: Option<i64>,
+}
+
+/// Receives permission denials from `Context::require_access`.
+///
+/// Called synchronously on the request path, so implementations must not
+/// block; hand slow work (e.g. a database write) off to a task.
+pub trait AuditSink: Send + Sync {
+    fn permission_denied(&self, denial: &PermissionDenial);
+}
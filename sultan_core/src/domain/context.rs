@@ -1,12 +1,24 @@
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 
+use tokio_util::sync::CancellationToken;
+
+use crate::domain::{
+    DomainResult, Error,
+    audit::{AuditSink, PermissionDenial},
+    model::branch::Branch,
+};
+
 /// Context provides request-scoped state for operations.
 ///
 /// It stores:
 /// - User ID (optional)
+/// - Request and actor ids for auditing (optional)
+/// - The caller's preferred locale for messages (optional)
 /// - Permissions (resource + branch access)
+/// - A cancellation token, tripped when the caller goes away
 /// - Arbitrary typed extensions via `get`
 ///
 /// # Examples
@@ -27,24 +39,41 @@ use std::sync::Arc;
 ///
 /// // Retrieve typed data
 /// let request_id: &String = ctx.get::<String>().unwrap();
+///
+/// // Attach audit metadata
+/// let ctx = Context::new().with_request_id(7).with_actor_id(123);
+/// assert_eq!(ctx.actor_id(), Some(123));
 /// ```
 #[derive(Clone)]
 pub struct Context {
     user_id: Option<i64>,
+    request_id: Option<i64>,
+    // Who performs the writes, recorded in created_by / updated_by
+    actor_id: Option<i64>,
+    // Language tag negotiated from Accept-Language, e.g. "id"
+    locale: Option<String>,
     // (resource, branch_id) -> permission
     permission: HashMap<(i32, Option<i64>), i32>,
     // Type-erased storage for arbitrary values using Arc for cheap cloning
     extensions: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
     internal: bool,
+    cancellation_token: CancellationToken,
+    // Told about every denied require_access, on top of the warn event
+    audit_sink: Option<Arc<dyn AuditSink>>,
 }
 
 impl Context {
     pub fn new() -> Self {
         Self {
             user_id: None,
+            request_id: None,
+            actor_id: None,
+            locale: None,
             permission: HashMap::new(),
             extensions: HashMap::new(),
             internal: false,
+            cancellation_token: CancellationToken::new(),
+            audit_sink: None,
         }
     }
 
@@ -55,18 +84,101 @@ impl Context {
     ) -> Self {
         Self {
             user_id,
+            request_id: None,
+            actor_id: None,
+            locale: None,
             permission,
             extensions,
             internal: false,
+            cancellation_token: CancellationToken::new(),
+            audit_sink: None,
         }
     }
 
-    pub fn new_internal() -> Self {
+    /// Privileged context for background jobs such as imports, data
+    /// migrations and scheduled stock recounts. `require_access` always
+    /// succeeds for it.
+    ///
+    /// Internal use only: never build one from request input. Handlers must
+    /// use the context the auth middleware resolved for the caller.
+    pub fn system() -> Self {
         Self {
             user_id: None,
+            request_id: None,
+            actor_id: None,
+            locale: None,
             permission: HashMap::new(),
             extensions: HashMap::new(),
             internal: true,
+            cancellation_token: CancellationToken::new(),
+            audit_sink: None,
+        }
+    }
+
+    /// Same as [`Context::system`].
+    pub fn new_internal() -> Self {
+        Self::system()
+    }
+
+    pub fn is_system(&self) -> bool {
+        self.internal
+    }
+
+    pub fn with_request_id(mut self, request_id: i64) -> Self {
+        self.request_id = Some(request_id);
+        self
+    }
+
+    pub fn with_actor_id(mut self, actor_id: i64) -> Self {
+        self.actor_id = Some(actor_id);
+        self
+    }
+
+    pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Share `token` so cancelling it stops work done with this context.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = token;
+        self
+    }
+
+    /// Report denied `require_access` checks to `sink`.
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
+    }
+
+    pub fn audit_sink(&self) -> Option<&Arc<dyn AuditSink>> {
+        self.audit_sink.as_ref()
+    }
+
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation_token
+    }
+
+    /// Fails with `Error::Cancelled` once the token has been cancelled.
+    pub fn check_cancelled(&self) -> DomainResult<()> {
+        if self.cancellation_token.is_cancelled() {
+            return Err(Error::Cancelled("Request cancelled".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Run `fut` until it finishes or the token is cancelled, whichever comes
+    /// first. On cancellation `fut` is dropped, which aborts a running query.
+    pub async fn cancellable<T, F>(&self, fut: F) -> DomainResult<T>
+    where
+        F: Future<Output = DomainResult<T>>,
+    {
+        tokio::select! {
+            biased;
+            _ = self.cancellation_token.cancelled() => {
+                Err(Error::Cancelled("Request cancelled".to_string()))
+            }
+            result = fut => result,
         }
     }
 
@@ -82,18 +194,96 @@ impl Context {
         self.user_id
     }
 
+    pub fn request_id(&self) -> Option<i64> {
+        self.request_id
+    }
+
+    pub fn actor_id(&self) -> Option<i64> {
+        self.actor_id
+    }
+
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    /// Branch memberships resolved for the current user, if any.
+    pub fn branch_context(&self) -> Option<&BranchContext> {
+        self.get::<BranchContext>()
+    }
+
+    /// Branch a call with an optional `branch_id` applies to.
+    ///
+    /// Services resolve an omitted branch with this rule:
+    /// - an explicit branch is kept as is;
+    /// - when it is omitted and the caller belongs to exactly one branch,
+    ///   that branch is used for scoping and permission checks alike;
+    /// - otherwise it stays `None`, which means every branch the caller can
+    ///   access (see [`Context::branch_scope`]).
+    pub fn resolve_branch(&self, branch_id: Option<i64>) -> Option<i64> {
+        branch_id.or_else(|| match self.branch_context()?.branch_ids.as_slice() {
+            [only] => Some(*only),
+            _ => None,
+        })
+    }
+
+    /// Branches of the caller's [`BranchContext`] it may perform `action` on
+    /// `resource` in, in membership order.
+    pub fn accessible_branches(&self, resource: i32, action: i32) -> Vec<i64> {
+        self.branch_context()
+            .map(|branches| {
+                branches
+                    .branch_ids
+                    .iter()
+                    .copied()
+                    .filter(|&id| self.has_access(Some(id), resource, action))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Resolve `branch_id` with [`Context::resolve_branch`] and check access.
+    ///
+    /// A resolved branch, or no branch for a caller with global access, runs
+    /// once as [`BranchScope::Single`]. A caller spanning several branches
+    /// with only branch-level permissions gets [`BranchScope::Branches`],
+    /// the branches to fan the call out to. Without access to any branch the
+    /// check fails with `Forbidden`.
+    pub fn branch_scope(
+        &self,
+        branch_id: Option<i64>,
+        resource: i32,
+        action: i32,
+    ) -> DomainResult<BranchScope> {
+        let branch_id = self.resolve_branch(branch_id);
+        if branch_id.is_some() || self.internal || self.has_access(None, resource, action) {
+            self.require_access(branch_id, resource, action)?;
+            return Ok(BranchScope::Single(branch_id));
+        }
+        let branch_ids = self.accessible_branches(resource, action);
+        if branch_ids.is_empty() {
+            // Fails, and is audited like any other denial
+            self.require_access(None, resource, action)?;
+        }
+        self.check_cancelled()?;
+        Ok(BranchScope::Branches(branch_ids))
+    }
+
+    /// Every service entry point goes through here, so a cancelled request
+    /// also stops here before it touches the database.
     pub fn require_access(
         &self,
         branch_id: Option<i64>,
         resource: i32,
         action: i32,
     ) -> Result<(), crate::domain::Error> {
+        self.check_cancelled()?;
         if self.internal {
             return Ok(());
         }
         if self.has_access(branch_id, resource, action) {
             Ok(())
         } else {
+            self.audit_denial(branch_id, resource, action);
             Err(crate::domain::Error::Forbidden(format!(
                 "Access denied for resource {} with action {}",
                 resource, action
@@ -101,6 +291,81 @@ impl Context {
         }
     }
 
+    /// Passes when at least one `(resource, action)` pair is allowed on
+    /// `branch_id`. Each pair is audited when all are denied; an empty list
+    /// is always denied.
+    pub fn require_any(
+        &self,
+        branch_id: Option<i64>,
+        checks: &[(i32, i32)],
+    ) -> Result<(), crate::domain::Error> {
+        self.check_cancelled()?;
+        if self.internal {
+            return Ok(());
+        }
+        if checks
+            .iter()
+            .any(|&(resource, action)| self.has_access(branch_id, resource, action))
+        {
+            return Ok(());
+        }
+        for &(resource, action) in checks {
+            self.audit_denial(branch_id, resource, action);
+        }
+        let denied: Vec<String> = checks
+            .iter()
+            .map(|(resource, action)| format!("resource {} with action {}", resource, action))
+            .collect();
+        Err(crate::domain::Error::Forbidden(format!(
+            "Access denied for any of: {}",
+            denied.join(", ")
+        )))
+    }
+
+    /// Passes when every `(resource, action)` pair is allowed on
+    /// `branch_id`, failing on the first denied one.
+    pub fn require_all(
+        &self,
+        branch_id: Option<i64>,
+        checks: &[(i32, i32)],
+    ) -> Result<(), crate::domain::Error> {
+        self.check_cancelled()?;
+        checks
+            .iter()
+            .try_for_each(|&(resource, action)| self.require_access(branch_id, resource, action))
+    }
+
+    fn audit_denial(&self, branch_id: Option<i64>, resource: i32, action: i32) {
+        let actor_id = self.actor_id.or(self.user_id);
+        tracing::warn!(
+            target: "audit",
+            event = crate::domain::audit::PERMISSION_DENIED,
+            actor_id,
+            request_id = self.request_id,
+            resource,
+            action,
+            branch_id,
+            "Permission denied"
+        );
+        if let Some(sink) = &self.audit_sink {
+            sink.permission_denied(&PermissionDenial {
+                actor_id,
+                request_id: self.request_id,
+                resource,
+                action,
+                branch_id,
+            });
+        }
+    }
+
+    /// Whether the caller may perform `action` on `resource` anywhere: system
+    /// contexts, global access, or access in at least one of its branches.
+    pub fn has_any_access(&self, resource: i32, action: i32) -> bool {
+        self.internal
+            || self.has_access(None, resource, action)
+            || !self.accessible_branches(resource, action).is_empty()
+    }
+
     pub fn has_access(&self, branch_id: Option<i64>, resource: i32, action: i32) -> bool {
         use crate::domain::model::permission::resource as res;
 
@@ -131,6 +396,52 @@ impl Context {
     }
 }
 
+/// Where a call with an optional branch runs, see [`Context::branch_scope`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BranchScope {
+    /// One branch, or all branches when `None`
+    Single(Option<i64>),
+    /// Each of these branches, with the results combined
+    Branches(Vec<i64>),
+}
+
+/// Branches the current user belongs to.
+///
+/// Resolved once at login and carried in the access token, then stored as a
+/// [`Context`] extension by the auth middleware.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BranchContext {
+    pub branch_ids: Vec<i64>,
+    pub default_branch_id: Option<i64>,
+}
+
+impl BranchContext {
+    pub fn new(branch_ids: Vec<i64>, default_branch_id: Option<i64>) -> Self {
+        Self {
+            branch_ids,
+            default_branch_id,
+        }
+    }
+
+    /// Builds the context from a membership list. The main branch is the
+    /// default when the user belongs to it, otherwise the first branch.
+    pub fn from_branches(branches: &[Branch]) -> Self {
+        let default_branch_id = branches
+            .iter()
+            .find(|b| b.is_main)
+            .or(branches.first())
+            .map(|b| b.id);
+        Self {
+            branch_ids: branches.iter().map(|b| b.id).collect(),
+            default_branch_id,
+        }
+    }
+
+    pub fn contains(&self, branch_id: i64) -> bool {
+        self.branch_ids.contains(&branch_id)
+    }
+}
+
 impl Default for Context {
     fn default() -> Self {
         Self::new()
@@ -140,6 +451,234 @@ impl Default for Context {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        denials: Mutex<Vec<PermissionDenial>>,
+    }
+
+    impl AuditSink for RecordingSink {
+        fn permission_denied(&self, denial: &PermissionDenial) {
+            self.denials.lock().unwrap().push(denial.clone());
+        }
+    }
+
+    #[test]
+    fn test_require_access_reports_denial_to_sink() {
+        use crate::domain::model::permission::{action, resource};
+
+        let sink = Arc::new(RecordingSink::default());
+        let permissions = HashMap::from([((resource::PRODUCT, None), action::READ)]);
+        let ctx = Context::new_with_all(Some(9), permissions, HashMap::new())
+            .with_request_id(11)
+            .with_audit_sink(sink.clone());
+
+        assert!(
+            ctx.require_access(None, resource::PRODUCT, action::READ)
+                .is_ok()
+        );
+        assert!(
+            ctx.require_access(Some(4), resource::PRODUCT, action::CREATE)
+                .is_err()
+        );
+
+        let denials = sink.denials.lock().unwrap();
+        assert_eq!(
+            *denials,
+            vec![PermissionDenial {
+                actor_id: Some(9),
+                request_id: Some(11),
+                resource: resource::PRODUCT,
+                action: action::CREATE,
+                branch_id: Some(4),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_require_any() {
+        use crate::domain::model::permission::{action, resource};
+
+        let permissions = HashMap::from([
+            ((resource::PRODUCT, None), action::READ),
+            ((resource::SALE, Some(2)), action::READ),
+        ]);
+        let ctx = Context::new_with_all(None, permissions, HashMap::new());
+        let product_or_sale = [
+            (resource::PRODUCT, action::READ),
+            (resource::SALE, action::READ),
+        ];
+        let product_or_supplier = [
+            (resource::PRODUCT, action::UPDATE),
+            (resource::SUPPLIER, action::READ),
+        ];
+
+        // Both allowed, only the first, only the second, neither
+        assert!(ctx.require_any(Some(2), &product_or_sale).is_ok());
+        assert!(ctx.require_any(Some(1), &product_or_sale).is_ok());
+        assert!(
+            ctx.require_any(
+                Some(2),
+                &[
+                    (resource::PRODUCT, action::UPDATE),
+                    (resource::SALE, action::READ)
+                ]
+            )
+            .is_ok()
+        );
+        assert!(matches!(
+            ctx.require_any(None, &product_or_supplier),
+            Err(crate::domain::Error::Forbidden(_))
+        ));
+        assert!(matches!(
+            ctx.require_any(None, &[]),
+            Err(crate::domain::Error::Forbidden(_))
+        ));
+
+        assert!(Context::system().require_any(None, &[]).is_ok());
+        assert!(Context::new().require_any(None, &product_or_sale).is_err());
+    }
+
+    #[test]
+    fn test_require_any_audits_every_denied_pair() {
+        use crate::domain::model::permission::{action, resource};
+
+        let sink = Arc::new(RecordingSink::default());
+        let permissions = HashMap::from([((resource::PRODUCT, None), action::READ)]);
+        let ctx = Context::new_with_all(Some(3), permissions, HashMap::new())
+            .with_audit_sink(sink.clone());
+
+        let checks = [
+            (resource::PRODUCT, action::UPDATE),
+            (resource::SUPPLIER, action::READ),
+        ];
+        assert!(ctx.require_any(Some(1), &checks).is_err());
+        assert_eq!(
+            sink.denials
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|d| (d.resource, d.action, d.branch_id))
+                .collect::<Vec<_>>(),
+            vec![
+                (resource::PRODUCT, action::UPDATE, Some(1)),
+                (resource::SUPPLIER, action::READ, Some(1)),
+            ]
+        );
+
+        // A passing check records nothing
+        sink.denials.lock().unwrap().clear();
+        assert!(
+            ctx.require_any(Some(1), &[(resource::PRODUCT, action::READ)])
+                .is_ok()
+        );
+        assert!(sink.denials.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_require_all() {
+        use crate::domain::model::permission::{action, resource};
+
+        let sink = Arc::new(RecordingSink::default());
+        let permissions = HashMap::from([
+            ((resource::PRODUCT, None), action::READ | action::UPDATE),
+            ((resource::SALE, Some(2)), action::READ),
+        ]);
+        let ctx =
+            Context::new_with_all(None, permissions, HashMap::new()).with_audit_sink(sink.clone());
+
+        // All allowed, the first denied, the second denied, all denied
+        assert!(
+            ctx.require_all(
+                Some(2),
+                &[
+                    (resource::PRODUCT, action::UPDATE),
+                    (resource::SALE, action::READ)
+                ]
+            )
+            .is_ok()
+        );
+        assert!(
+            ctx.require_all(
+                Some(2),
+                &[
+                    (resource::PRODUCT, action::DELETE),
+                    (resource::SALE, action::READ)
+                ]
+            )
+            .is_err()
+        );
+        assert!(
+            ctx.require_all(
+                Some(1),
+                &[
+                    (resource::PRODUCT, action::READ),
+                    (resource::SALE, action::READ)
+                ]
+            )
+            .is_err()
+        );
+        assert!(matches!(
+            ctx.require_all(
+                None,
+                &[
+                    (resource::SUPPLIER, action::READ),
+                    (resource::CUSTOMER, action::READ)
+                ]
+            ),
+            Err(crate::domain::Error::Forbidden(_))
+        ));
+        // Nothing to check
+        assert!(ctx.require_all(None, &[]).is_ok());
+
+        // Only the first failing pair of each call is audited
+        let denied: Vec<i32> = sink
+            .denials
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|d| d.resource)
+            .collect();
+        assert_eq!(
+            denied,
+            vec![resource::PRODUCT, resource::SALE, resource::SUPPLIER]
+        );
+
+        assert!(
+            Context::system()
+                .require_all(None, &[(resource::ADMIN, action::DELETE)])
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_require_any_and_all_fail_when_cancelled() {
+        use crate::domain::model::permission::{action, resource};
+
+        let token = CancellationToken::new();
+        let ctx = Context::system().with_cancellation_token(token.clone());
+        token.cancel();
+
+        let checks = [(resource::PRODUCT, action::READ)];
+        assert!(matches!(
+            ctx.require_any(None, &checks),
+            Err(crate::domain::Error::Cancelled(_))
+        ));
+        assert!(matches!(
+            ctx.require_all(None, &checks),
+            Err(crate::domain::Error::Cancelled(_))
+        ));
+    }
+
+    #[test]
+    fn test_system_context_is_never_audited() {
+        let sink = Arc::new(RecordingSink::default());
+        let ctx = Context::system().with_audit_sink(sink.clone());
+
+        assert!(ctx.require_access(None, 1, 8).is_ok());
+        assert!(sink.denials.lock().unwrap().is_empty());
+    }
 
     #[test]
     fn test_has_access_global_permission() {
@@ -253,6 +792,64 @@ mod tests {
         assert!(!ctx.has_access(None, resource::BRANCH, 0b0001)); // CREATE with no branch
     }
 
+    #[test]
+    fn test_system_context_passes_access_checks() {
+        use crate::domain::model::permission::{action, resource};
+
+        let ctx = Context::system();
+        assert!(ctx.is_system());
+        assert!(
+            ctx.require_access(None, resource::PRODUCT, action::CREATE)
+                .is_ok()
+        );
+        assert!(
+            ctx.require_access(Some(5), resource::SALE, action::DELETE)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_empty_context_fails_access_checks() {
+        use crate::domain::model::permission::{action, resource};
+
+        let ctx = Context::new();
+        assert!(!ctx.is_system());
+        assert!(matches!(
+            ctx.require_access(None, resource::PRODUCT, action::CREATE),
+            Err(crate::domain::Error::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn test_cancelled_context_fails_access_checks() {
+        use crate::domain::model::permission::{action, resource};
+
+        let token = CancellationToken::new();
+        let ctx = Context::system().with_cancellation_token(token.clone());
+        assert!(ctx.check_cancelled().is_ok());
+
+        token.cancel();
+        assert!(matches!(ctx.check_cancelled(), Err(Error::Cancelled(_))));
+        // Cancellation wins even for a privileged context
+        assert!(matches!(
+            ctx.require_access(None, resource::PRODUCT, action::READ),
+            Err(Error::Cancelled(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cancellable_aborts_pending_future() {
+        let ctx = Context::new();
+        assert_eq!(ctx.cancellable(async { Ok(1) }).await.unwrap(), 1);
+
+        let cancelled = ctx.clone();
+        tokio::spawn(async move { cancelled.cancellation_token().cancel() });
+        let result = ctx
+            .cancellable(std::future::pending::<DomainResult<()>>())
+            .await;
+        assert!(matches!(result, Err(Error::Cancelled(_))));
+    }
+
     #[test]
     fn test_user_id_default_is_none() {
         let ctx = Context::new();
@@ -456,4 +1053,156 @@ mod tests {
         assert_eq!(*ctx.get::<i64>().unwrap(), 42);
         assert!(ctx.has_access(None, 1, 0b0001));
     }
+
+    fn make_branch(id: i64, is_main: bool) -> Branch {
+        Branch {
+            id,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            deleted_at: None,
+            is_deleted: false,
+            is_main,
+            name: format!("Branch {}", id),
+            code: format!("B{}", id),
+            address: None,
+            phone: None,
+            npwp: None,
+            image: None,
+        }
+    }
+
+    #[test]
+    fn test_branch_context_prefers_main_branch_as_default() {
+        let branches = vec![make_branch(5, false), make_branch(7, true)];
+        let branch_ctx = BranchContext::from_branches(&branches);
+
+        assert_eq!(branch_ctx.branch_ids, vec![5, 7]);
+        assert_eq!(branch_ctx.default_branch_id, Some(7));
+        assert!(branch_ctx.contains(5));
+        assert!(!branch_ctx.contains(6));
+    }
+
+    #[test]
+    fn test_branch_context_defaults_to_first_branch() {
+        let branches = vec![make_branch(5, false), make_branch(7, false)];
+        let branch_ctx = BranchContext::from_branches(&branches);
+
+        assert_eq!(branch_ctx.default_branch_id, Some(5));
+        assert_eq!(BranchContext::from_branches(&[]), BranchContext::default());
+    }
+
+    #[test]
+    fn test_branch_context_from_extensions() {
+        let mut extensions = HashMap::new();
+        extensions.insert(
+            TypeId::of::<BranchContext>(),
+            Arc::new(BranchContext::new(vec![1, 2], Some(1))) as Arc<dyn Any + Send + Sync>,
+        );
+        let ctx = Context::new_with_all(Some(1), HashMap::new(), extensions);
+
+        assert_eq!(ctx.branch_context().unwrap().branch_ids, vec![1, 2]);
+        assert!(Context::new().branch_context().is_none());
+    }
+
+    fn branch_ctx(branch_ids: Vec<i64>, permissions: HashMap<(i32, Option<i64>), i32>) -> Context {
+        let mut extensions = HashMap::new();
+        extensions.insert(
+            TypeId::of::<BranchContext>(),
+            Arc::new(BranchContext::new(branch_ids, None)) as Arc<dyn Any + Send + Sync>,
+        );
+        Context::new_with_all(Some(1), permissions, extensions)
+    }
+
+    #[test]
+    fn test_resolve_branch_defaults_to_single_branch() {
+        let single = branch_ctx(vec![5], HashMap::new());
+        assert_eq!(single.resolve_branch(None), Some(5));
+        assert_eq!(single.resolve_branch(Some(9)), Some(9));
+
+        let multi = branch_ctx(vec![5, 6], HashMap::new());
+        assert_eq!(multi.resolve_branch(None), None);
+        assert_eq!(Context::new().resolve_branch(None), None);
+    }
+
+    #[test]
+    fn test_branch_scope_single_branch() {
+        use crate::domain::model::permission::{action, resource};
+
+        let permissions = HashMap::from([((resource::CATEGORY, Some(5)), action::READ)]);
+        let ctx = branch_ctx(vec![5], permissions);
+
+        // The branch-level permission applies once the branch is defaulted
+        assert_eq!(
+            ctx.branch_scope(None, resource::CATEGORY, action::READ)
+                .unwrap(),
+            BranchScope::Single(Some(5))
+        );
+        assert!(matches!(
+            ctx.branch_scope(Some(6), resource::CATEGORY, action::READ),
+            Err(Error::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn test_has_any_access() {
+        use crate::domain::model::permission::{action, resource};
+
+        let permissions = HashMap::from([((resource::PRODUCT, Some(6)), action::UPDATE)]);
+        let ctx = branch_ctx(vec![5, 6], permissions);
+        assert!(ctx.has_any_access(resource::PRODUCT, action::UPDATE));
+        assert!(!ctx.has_any_access(resource::PRODUCT, action::DELETE));
+
+        // Branch-level access outside the caller's branches does not count
+        let permissions = HashMap::from([((resource::PRODUCT, Some(9)), action::UPDATE)]);
+        let ctx = branch_ctx(vec![5], permissions);
+        assert!(!ctx.has_any_access(resource::PRODUCT, action::UPDATE));
+
+        assert!(Context::system().has_any_access(resource::PRODUCT, action::UPDATE));
+    }
+
+    #[test]
+    fn test_branch_scope_fans_out_over_accessible_branches() {
+        use crate::domain::model::permission::{action, resource};
+
+        let permissions = HashMap::from([
+            ((resource::CATEGORY, Some(5)), action::READ),
+            ((resource::CATEGORY, Some(7)), action::READ),
+        ]);
+        let ctx = branch_ctx(vec![5, 6, 7], permissions);
+        assert_eq!(
+            ctx.accessible_branches(resource::CATEGORY, action::READ),
+            vec![5, 7]
+        );
+        assert_eq!(
+            ctx.branch_scope(None, resource::CATEGORY, action::READ)
+                .unwrap(),
+            BranchScope::Branches(vec![5, 7])
+        );
+        assert!(matches!(
+            ctx.branch_scope(None, resource::CATEGORY, action::UPDATE),
+            Err(Error::Forbidden(_))
+        ));
+
+        // Global access keeps a single unscoped call
+        let permissions = HashMap::from([((resource::CATEGORY, None), action::READ)]);
+        let ctx = branch_ctx(vec![5, 6], permissions);
+        assert_eq!(
+            ctx.branch_scope(None, resource::CATEGORY, action::READ)
+                .unwrap(),
+            BranchScope::Single(None)
+        );
+    }
+
+    #[test]
+    fn test_request_metadata_builder() {
+        let ctx = Context::new();
+        assert_eq!(ctx.request_id(), None);
+        assert_eq!(ctx.actor_id(), None);
+
+        let ctx = ctx.with_request_id(7).with_actor_id(42);
+        assert_eq!(ctx.request_id(), Some(7));
+        assert_eq!(ctx.actor_id(), Some(42));
+        // The metadata does not grant anything on its own
+        assert!(!ctx.has_access(None, 1, 0b0001));
+    }
 }
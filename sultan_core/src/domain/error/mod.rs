@@ -26,6 +26,9 @@ pub enum Error {
 
     #[error("Cancelled: {0}")]
     Cancelled(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
 }
 
 impl From<SnowflakeError> for Error {
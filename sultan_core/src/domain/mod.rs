@@ -1,8 +1,10 @@
+pub mod audit;
 pub mod context;
 pub mod error;
 pub mod model;
 
-pub use context::Context;
+pub use audit::{AuditSink, PermissionDenial};
+pub use context::{BranchContext, BranchScope, Context};
 
 pub use error::DomainResult;
 pub use error::Error;
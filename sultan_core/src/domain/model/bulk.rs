@@ -0,0 +1,10 @@
+/// Most ids a single bulk request may touch
+pub const MAX_BULK_IDS: usize = 500;
+
+/// Per-id outcome of a bulk delete. With all-or-nothing requested and any id
+/// missing, `deleted` is empty and nothing was changed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BulkResult {
+    pub deleted: Vec<i64>,
+    pub not_found: Vec<i64>,
+}
@@ -1,5 +1,8 @@
 use chrono::Utc;
 
+use super::validate::{Validate, validate_name, validate_optional_name};
+use crate::domain::DomainResult;
+
 #[derive(Debug, Clone)]
 pub struct Category {
     pub id: i64,
@@ -26,6 +29,18 @@ pub struct CategoryUpdate {
     pub description: super::Update<String>,
 }
 
+impl Validate for CategoryCreate {
+    fn validate(&self) -> DomainResult<()> {
+        validate_name("Name", &self.name)
+    }
+}
+
+impl Validate for CategoryUpdate {
+    fn validate(&self) -> DomainResult<()> {
+        validate_optional_name("Name", self.name.as_deref())
+    }
+}
+
 /* Fixture */
 
 pub fn category_create_with_name(name: &str) -> CategoryCreate {
@@ -35,3 +50,30 @@ pub fn category_create_with_name(name: &str) -> CategoryCreate {
         description: None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Error, model::Update};
+
+    #[test]
+    fn test_category_validate() {
+        assert!(category_create_with_name("Drinks").validate().is_ok());
+        assert!(matches!(
+            category_create_with_name("").validate(),
+            Err(Error::ValidationError(_))
+        ));
+
+        let update = CategoryUpdate {
+            parent_id: Update::Unchanged,
+            name: None,
+            description: Update::Unchanged,
+        };
+        assert!(update.validate().is_ok());
+        let update = CategoryUpdate {
+            name: Some(" ".to_string()),
+            ..update
+        };
+        assert!(matches!(update.validate(), Err(Error::ValidationError(_))));
+    }
+}
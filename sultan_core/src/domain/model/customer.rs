@@ -1,7 +1,14 @@
 use chrono::Utc;
 use serde_json::Value;
 
-use super::Update;
+use crate::domain::DomainResult;
+
+use super::{
+    Update,
+    email::{validate_email_update, validate_optional_email},
+    filter::{FilterExpr, FilterField},
+    validate::{Validate, validate_name, validate_non_negative, validate_optional_name},
+};
 
 #[derive(Debug, Clone)]
 pub struct Customer {
@@ -17,6 +24,8 @@ pub struct Customer {
     pub phone: Option<String>,
     pub level: i32,
     pub metadata: Option<Value>,
+    pub created_by: Option<i64>,
+    pub updated_by: Option<i64>,
 }
 
 #[derive(Debug, Clone)]
@@ -30,6 +39,14 @@ pub struct CustomerCreate {
     pub metadata: Option<Value>,
 }
 
+impl Validate for CustomerCreate {
+    fn validate(&self) -> DomainResult<()> {
+        validate_name("Name", &self.name)?;
+        validate_non_negative("Level", self.level)?;
+        validate_optional_email(self.email.as_deref())
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct CustomerUpdate {
     pub number: Option<String>,
@@ -41,11 +58,116 @@ pub struct CustomerUpdate {
     pub metadata: Update<Value>,
 }
 
+impl Validate for CustomerUpdate {
+    fn validate(&self) -> DomainResult<()> {
+        validate_optional_name("Name", self.name.as_deref())?;
+        if let Some(level) = self.level {
+            validate_non_negative("Level", level)?;
+        }
+        validate_email_update(&self.email)
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct CustomerFilter {
     pub number: Option<String>,
     pub name: Option<String>,
     pub phone: Option<String>,
     pub email: Option<String>,
+    /// Exact level; same as setting both bounds to it
     pub level: Option<i32>,
+    /// Inclusive lower bound on level, e.g. 2 for "gold and above"
+    pub level_min: Option<i32>,
+    /// Inclusive upper bound on level
+    pub level_max: Option<i32>,
+    /// Matches any of name, phone or email, for type-ahead lookups
+    pub search: Option<String>,
+    /// `search` as a phone number, matched against phone in place of `search`
+    pub search_phone: Option<String>,
+    /// Filter DSL expressions, ANDed with the fields above. Only fields in
+    /// [`CUSTOMER_FILTER_FIELDS`] are accepted.
+    pub expressions: Vec<FilterExpr>,
+}
+
+/// Fields customers can be filtered on with [`FilterExpr`]
+pub const CUSTOMER_FILTER_FIELDS: &[FilterField] = &[
+    FilterField::integer("id"),
+    FilterField::text("number"),
+    FilterField::text("name"),
+    FilterField::text("address"),
+    FilterField::text("email"),
+    FilterField::text("phone"),
+    FilterField::integer("level"),
+    FilterField::text("created_at"),
+    FilterField::text("updated_at"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Error;
+
+    fn customer_create() -> CustomerCreate {
+        CustomerCreate {
+            number: "C-001".to_string(),
+            name: "Budi".to_string(),
+            address: None,
+            email: Some("budi@example.com".to_string()),
+            phone: None,
+            level: 0,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_customer_create_validate() {
+        assert!(customer_create().validate().is_ok());
+        let invalid = [
+            CustomerCreate {
+                name: "".to_string(),
+                ..customer_create()
+            },
+            CustomerCreate {
+                level: -1,
+                ..customer_create()
+            },
+            CustomerCreate {
+                email: Some("budi".to_string()),
+                ..customer_create()
+            },
+        ];
+        for customer in invalid {
+            assert!(matches!(
+                customer.validate(),
+                Err(Error::ValidationError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_customer_update_validate() {
+        assert!(CustomerUpdate::default().validate().is_ok());
+        let valid = CustomerUpdate {
+            name: Some("Budi".to_string()),
+            level: Some(2),
+            ..Default::default()
+        };
+        assert!(valid.validate().is_ok());
+        let invalid = [
+            CustomerUpdate {
+                name: Some(" ".to_string()),
+                ..Default::default()
+            },
+            CustomerUpdate {
+                level: Some(-1),
+                ..Default::default()
+            },
+        ];
+        for customer in invalid {
+            assert!(matches!(
+                customer.validate(),
+                Err(Error::ValidationError(_))
+            ));
+        }
+    }
 }
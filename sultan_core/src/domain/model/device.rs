@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+/// A registered kiosk that logs in with a device key instead of a password
+#[derive(Debug, Clone)]
+pub struct Device {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    /// The user the device acts as
+    pub user_id: i64,
+    pub name: String,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl Device {
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeviceCreate {
+    pub user_id: i64,
+    pub name: String,
+    /// Hash of the device key, see [`hash_device_key`]
+    pub key_hash: String,
+}
+
+/// Device keys are long-lived credentials, so they are stored as a SHA-256
+/// hex digest
+pub fn hash_device_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
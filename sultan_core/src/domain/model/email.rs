@@ -0,0 +1,64 @@
+use validator::ValidateEmail;
+
+use crate::domain::{DomainResult, Error};
+
+use super::Update;
+
+/// Checks that `email` looks like a deliverable address (`local@domain`)
+pub fn validate_email(email: &str) -> DomainResult<()> {
+    if email.validate_email() {
+        Ok(())
+    } else {
+        Err(Error::ValidationError(format!(
+            "Invalid email address: {}",
+            email
+        )))
+    }
+}
+
+/// Validates an optional email, skipping it when absent
+pub fn validate_optional_email(email: Option<&str>) -> DomainResult<()> {
+    email.map_or(Ok(()), validate_email)
+}
+
+/// Validates an email update; only `Update::Set` carries a value to check
+pub fn validate_email_update(email: &Update<String>) -> DomainResult<()> {
+    match email {
+        Update::Set(email) => validate_email(email),
+        Update::Clear | Update::Unchanged => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_email_accepts_valid_addresses() {
+        for email in [
+            "test@example.com",
+            "first.last+tag@sub.example.co.id",
+            "a@b.co",
+        ] {
+            assert!(validate_email(email).is_ok(), "{email} should be valid");
+        }
+    }
+
+    #[test]
+    fn test_validate_email_rejects_malformed_addresses() {
+        for email in ["", "foo", "foo@", "@example.com", "foo bar@example.com"] {
+            assert!(
+                matches!(validate_email(email), Err(Error::ValidationError(_))),
+                "{email} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_email_update_only_checks_set() {
+        assert!(validate_email_update(&Update::Unchanged).is_ok());
+        assert!(validate_email_update(&Update::Clear).is_ok());
+        assert!(validate_email_update(&Update::Set("ok@example.com".to_string())).is_ok());
+        assert!(validate_email_update(&Update::Set("foo@".to_string())).is_err());
+    }
+}
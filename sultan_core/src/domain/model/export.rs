@@ -0,0 +1,98 @@
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::domain::Error;
+
+/// Tables written by a full export, in the order they appear in the document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTable {
+    Products,
+    Variants,
+    Categories,
+    Customers,
+    Suppliers,
+    Units,
+}
+
+impl ExportTable {
+    pub const ALL: [ExportTable; 6] = [
+        ExportTable::Products,
+        ExportTable::Variants,
+        ExportTable::Categories,
+        ExportTable::Customers,
+        ExportTable::Suppliers,
+        ExportTable::Units,
+    ];
+
+    /// Key of the table's array in the export document
+    pub fn key(&self) -> &'static str {
+        match self {
+            ExportTable::Products => "products",
+            ExportTable::Variants => "variants",
+            ExportTable::Categories => "categories",
+            ExportTable::Customers => "customers",
+            ExportTable::Suppliers => "suppliers",
+            ExportTable::Units => "units",
+        }
+    }
+}
+
+/// A batch of exported rows, in id order
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportPage {
+    pub rows: Vec<Value>,
+    /// Id to continue after, or None once the table is exhausted
+    pub next_after_id: Option<i64>,
+}
+
+/// A full export read back for import: one list of rows per table, each row a
+/// JSON object keyed by column name as written by the export. Products may
+/// carry `category_ids`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    pub products: Vec<Value>,
+    pub variants: Vec<Value>,
+    pub categories: Vec<Value>,
+    pub customers: Vec<Value>,
+    pub suppliers: Vec<Value>,
+    pub units: Vec<Value>,
+}
+
+impl Snapshot {
+    pub fn rows(&self, table: ExportTable) -> &[Value] {
+        match table {
+            ExportTable::Products => &self.products,
+            ExportTable::Variants => &self.variants,
+            ExportTable::Categories => &self.categories,
+            ExportTable::Customers => &self.customers,
+            ExportTable::Suppliers => &self.suppliers,
+            ExportTable::Units => &self.units,
+        }
+    }
+}
+
+/// What an import does with a row whose id already exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImportMode {
+    /// Fail the whole import with `Conflict`
+    #[default]
+    Insert,
+    /// Overwrite the existing row with the snapshot's columns
+    Upsert,
+}
+
+impl FromStr for ImportMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "insert" => Ok(ImportMode::Insert),
+            "upsert" => Ok(ImportMode::Upsert),
+            _ => Err(Error::ValidationError(format!(
+                "Unknown import mode '{}'",
+                s
+            ))),
+        }
+    }
+}
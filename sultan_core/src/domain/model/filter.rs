@@ -0,0 +1,293 @@
+use std::str::FromStr;
+
+use crate::domain::{DomainResult, Error};
+
+/// Comparison applied by a [`FilterExpr`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    Eq,
+    /// Case-insensitive substring match; text fields only
+    Like,
+    Gt,
+    Lt,
+    /// Matches any of a list of values
+    In,
+}
+
+impl FilterOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilterOp::Eq => "eq",
+            FilterOp::Like => "like",
+            FilterOp::Gt => "gt",
+            FilterOp::Lt => "lt",
+            FilterOp::In => "in",
+        }
+    }
+}
+
+impl FromStr for FilterOp {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "eq" => Ok(FilterOp::Eq),
+            "like" => Ok(FilterOp::Like),
+            "gt" => Ok(FilterOp::Gt),
+            "lt" => Ok(FilterOp::Lt),
+            "in" => Ok(FilterOp::In),
+            _ => Err(Error::ValidationError(format!(
+                "Unknown filter operator '{}'",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue {
+    Text(String),
+    Integer(i64),
+    /// Only valid with [`FilterOp::In`]
+    List(Vec<FilterValue>),
+}
+
+/// A single `field op value` condition. Expressions on a filter are ANDed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterExpr {
+    pub field: String,
+    pub op: FilterOp,
+    pub value: FilterValue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Text,
+    Integer,
+}
+
+/// A field an entity allows filtering on. `name` is also the column, so only
+/// names from a whitelist ever reach the SQL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FilterField {
+    pub name: &'static str,
+    pub kind: FieldKind,
+}
+
+impl FilterField {
+    pub const fn text(name: &'static str) -> Self {
+        Self {
+            name,
+            kind: FieldKind::Text,
+        }
+    }
+
+    pub const fn integer(name: &'static str) -> Self {
+        Self {
+            name,
+            kind: FieldKind::Integer,
+        }
+    }
+
+    /// Convert a scalar to this field's type
+    fn coerce(&self, value: &FilterValue) -> DomainResult<FilterValue> {
+        match (self.kind, value) {
+            (FieldKind::Text, FilterValue::Text(s)) => Ok(FilterValue::Text(s.clone())),
+            (FieldKind::Text, FilterValue::Integer(i)) => Ok(FilterValue::Text(i.to_string())),
+            (FieldKind::Integer, FilterValue::Integer(i)) => Ok(FilterValue::Integer(*i)),
+            (FieldKind::Integer, FilterValue::Text(s)) => {
+                s.trim().parse().map(FilterValue::Integer).map_err(|_| {
+                    Error::ValidationError(format!(
+                        "Filter on '{}' needs a whole number, got '{}'",
+                        self.name, s
+                    ))
+                })
+            }
+            (_, FilterValue::List(_)) => Err(Error::ValidationError(format!(
+                "A list of values on '{}' needs the 'in' operator",
+                self.name
+            ))),
+        }
+    }
+}
+
+/// Most values a single `in` expression may list
+pub const MAX_FILTER_IN_VALUES: usize = 100;
+
+impl FilterExpr {
+    pub fn new(field: impl Into<String>, op: FilterOp, value: FilterValue) -> Self {
+        Self {
+            field: field.into(),
+            op,
+            value,
+        }
+    }
+
+    /// Check the expression against an entity's whitelist and convert its
+    /// value to the field's type. Unknown fields, operators that don't fit the
+    /// field and malformed values are rejected with `ValidationError`.
+    pub fn resolve(&self, fields: &[FilterField]) -> DomainResult<(FilterField, FilterValue)> {
+        let field = *fields
+            .iter()
+            .find(|f| f.name == self.field)
+            .ok_or_else(|| {
+                Error::ValidationError(format!("Cannot filter on field '{}'", self.field))
+            })?;
+
+        let value = match (self.op, &self.value) {
+            (FilterOp::In, FilterValue::List(values)) => {
+                if values.is_empty() || values.len() > MAX_FILTER_IN_VALUES {
+                    return Err(Error::ValidationError(format!(
+                        "Filter 'in' takes between 1 and {} values",
+                        MAX_FILTER_IN_VALUES
+                    )));
+                }
+                FilterValue::List(
+                    values
+                        .iter()
+                        .map(|v| field.coerce(v))
+                        .collect::<DomainResult<_>>()?,
+                )
+            }
+            (FilterOp::In, scalar) => FilterValue::List(vec![field.coerce(scalar)?]),
+            (FilterOp::Like, _) if field.kind != FieldKind::Text => {
+                return Err(Error::ValidationError(format!(
+                    "Filter 'like' only works on text fields, not '{}'",
+                    field.name
+                )));
+            }
+            (_, value) => field.coerce(value)?,
+        };
+        Ok((field, value))
+    }
+
+    /// Parse `field:op:value` expressions separated by `;`. The values of an
+    /// `in` expression are separated by `|`, e.g.
+    /// `level:gt:1;name:like:warung;number:in:C001|C002`. Values are read as
+    /// text and converted to the field's type by [`FilterExpr::resolve`].
+    pub fn parse_list(input: &str) -> DomainResult<Vec<FilterExpr>> {
+        input
+            .split(';')
+            .filter(|part| !part.trim().is_empty())
+            .map(|part| {
+                let mut pieces = part.splitn(3, ':');
+                let (Some(field), Some(op), Some(value)) =
+                    (pieces.next(), pieces.next(), pieces.next())
+                else {
+                    return Err(Error::ValidationError(format!(
+                        "Filter '{}' must look like field:op:value",
+                        part
+                    )));
+                };
+                let op: FilterOp = op.trim().parse()?;
+                let value = if op == FilterOp::In {
+                    FilterValue::List(
+                        value
+                            .split('|')
+                            .map(|v| FilterValue::Text(v.to_string()))
+                            .collect(),
+                    )
+                } else {
+                    FilterValue::Text(value.to_string())
+                };
+                Ok(FilterExpr::new(field.trim(), op, value))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELDS: &[FilterField] = &[FilterField::text("name"), FilterField::integer("level")];
+
+    #[test]
+    fn test_parse_list() {
+        let exprs = FilterExpr::parse_list("level:gt:1;name:like:a:b;level:in:1|2").unwrap();
+        assert_eq!(
+            exprs,
+            vec![
+                FilterExpr::new("level", FilterOp::Gt, FilterValue::Text("1".into())),
+                // Only the first two colons separate
+                FilterExpr::new("name", FilterOp::Like, FilterValue::Text("a:b".into())),
+                FilterExpr::new(
+                    "level",
+                    FilterOp::In,
+                    FilterValue::List(vec![
+                        FilterValue::Text("1".into()),
+                        FilterValue::Text("2".into())
+                    ])
+                ),
+            ]
+        );
+        assert!(FilterExpr::parse_list("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_list_rejects_malformed() {
+        assert!(matches!(
+            FilterExpr::parse_list("name=warung"),
+            Err(Error::ValidationError(_))
+        ));
+        assert!(matches!(
+            FilterExpr::parse_list("name:contains:warung"),
+            Err(Error::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_coerces_to_field_kind() {
+        let expr = FilterExpr::new("level", FilterOp::Gt, FilterValue::Text("2".into()));
+        assert_eq!(
+            expr.resolve(FIELDS).unwrap(),
+            (FilterField::integer("level"), FilterValue::Integer(2))
+        );
+
+        let expr = FilterExpr::new("name", FilterOp::Eq, FilterValue::Integer(7));
+        assert_eq!(
+            expr.resolve(FIELDS).unwrap().1,
+            FilterValue::Text("7".into())
+        );
+
+        let expr = FilterExpr::new("level", FilterOp::Eq, FilterValue::Text("gold".into()));
+        assert!(matches!(
+            expr.resolve(FIELDS),
+            Err(Error::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_rejects_unknown_field() {
+        let expr = FilterExpr::new(
+            "name; DROP TABLE customers",
+            FilterOp::Eq,
+            FilterValue::Text("x".into()),
+        );
+        assert!(matches!(
+            expr.resolve(FIELDS),
+            Err(Error::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_checks_operator_fits() {
+        let expr = FilterExpr::new("level", FilterOp::Like, FilterValue::Text("1".into()));
+        assert!(matches!(
+            expr.resolve(FIELDS),
+            Err(Error::ValidationError(_))
+        ));
+
+        let expr = FilterExpr::new("level", FilterOp::Eq, FilterValue::List(vec![]));
+        assert!(matches!(
+            expr.resolve(FIELDS),
+            Err(Error::ValidationError(_))
+        ));
+
+        let expr = FilterExpr::new("level", FilterOp::In, FilterValue::List(vec![]));
+        assert!(matches!(
+            expr.resolve(FIELDS),
+            Err(Error::ValidationError(_))
+        ));
+    }
+}
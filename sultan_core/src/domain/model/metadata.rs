@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use jsonschema::Validator;
+use serde_json::Value;
+
+use crate::domain::{DomainResult, Error};
+
+use super::Update;
+
+/// JSON Schema that an entity's free-form `metadata` has to conform to
+#[derive(Clone)]
+pub struct MetadataSchema {
+    validator: Arc<Validator>,
+}
+
+impl MetadataSchema {
+    /// Compiles `schema`, failing when it is not a valid JSON Schema
+    pub fn new(schema: &Value) -> DomainResult<Self> {
+        let validator = jsonschema::validator_for(schema)
+            .map_err(|e| Error::ValidationError(format!("Invalid metadata schema: {}", e)))?;
+        Ok(Self {
+            validator: Arc::new(validator),
+        })
+    }
+
+    /// Checks `metadata` against the schema, reporting the first failing path
+    pub fn validate(&self, metadata: &Value) -> DomainResult<()> {
+        self.validator.validate(metadata).map_err(|e| {
+            let path = e.instance_path.to_string();
+            let path = if path.is_empty() { "/" } else { path.as_str() };
+            Error::ValidationError(format!("Invalid metadata at {}: {}", path, e))
+        })
+    }
+}
+
+/// Validates optional metadata; anything goes when no schema is configured
+pub fn validate_optional_metadata(
+    schema: Option<&MetadataSchema>,
+    metadata: Option<&Value>,
+) -> DomainResult<()> {
+    match (schema, metadata) {
+        (Some(schema), Some(metadata)) => schema.validate(metadata),
+        _ => Ok(()),
+    }
+}
+
+/// Validates a metadata update; only `Update::Set` carries a value to check
+pub fn validate_metadata_update(
+    schema: Option<&MetadataSchema>,
+    metadata: &Update<Value>,
+) -> DomainResult<()> {
+    validate_optional_metadata(schema, metadata.as_value())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::metadata::sku_schema;
+    use serde_json::json;
+
+    #[test]
+    fn test_valid_metadata_passes() {
+        assert!(sku_schema().validate(&json!({"sku": "ABC-1"})).is_ok());
+    }
+
+    #[test]
+    fn test_missing_required_property_fails() {
+        let err = sku_schema().validate(&json!({"color": "red"})).unwrap_err();
+        match err {
+            Error::ValidationError(msg) => {
+                assert!(msg.starts_with("Invalid metadata at /:"), "{msg}");
+                assert!(msg.contains("sku"), "{msg}");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_error_reports_failing_path() {
+        let err = sku_schema().validate(&json!({"sku": 42})).unwrap_err();
+        match err {
+            Error::ValidationError(msg) => {
+                assert!(msg.starts_with("Invalid metadata at /sku:"), "{msg}")
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_invalid_schema_is_rejected() {
+        assert!(matches!(
+            MetadataSchema::new(&json!({"type": "not-a-type"})),
+            Err(Error::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_without_schema_anything_goes() {
+        assert!(validate_optional_metadata(None, Some(&json!({"x": 1}))).is_ok());
+        assert!(validate_metadata_update(None, &Update::Set(json!([1, 2]))).is_ok());
+    }
+
+    #[test]
+    fn test_metadata_update_only_checks_set() {
+        let schema = sku_schema();
+        assert!(validate_metadata_update(Some(&schema), &Update::Unchanged).is_ok());
+        assert!(validate_metadata_update(Some(&schema), &Update::Clear).is_ok());
+        assert!(validate_metadata_update(Some(&schema), &Update::Set(json!({}))).is_err());
+    }
+}
@@ -1,13 +1,26 @@
 pub mod branch;
+pub mod bulk;
 pub mod category;
 pub mod customer;
+pub mod device;
+pub mod email;
+pub mod export;
+pub mod filter;
+pub mod metadata;
+pub mod money;
 pub mod pagination;
 pub mod permission;
+pub mod phone;
 pub mod product;
+pub mod purchase_order;
+pub mod sale;
 pub mod sell_price;
+pub mod stock;
 pub mod supplier;
 pub mod token;
 pub mod update;
 pub mod user;
+pub mod validate;
 
 pub use update::Update;
+pub use validate::Validate;
@@ -0,0 +1,264 @@
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::domain::{DomainResult, Error};
+
+/// Number of decimal places kept for every amount.
+pub const MONEY_SCALE: u32 = 2;
+
+const MINOR_PER_MAJOR: i64 = 10_i64.pow(MONEY_SCALE);
+
+/// A currency amount stored as an integer count of minor units (cents).
+///
+/// Amounts never go through floating point: they are parsed from and
+/// serialized to decimal strings such as `"12.50"`, and stored in the
+/// database as `INTEGER` minor units.
+///
+/// ```rust
+/// use sultan_core::domain::model::money::Money;
+///
+/// let a: Money = "0.1".parse().unwrap();
+/// let b: Money = "0.2".parse().unwrap();
+/// assert_eq!(a + b, "0.3".parse().unwrap());
+/// assert_eq!((a + b).to_string(), "0.30");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub fn from_minor(minor: i64) -> Self {
+        Self(minor)
+    }
+
+    pub fn from_major(major: i64) -> Self {
+        Self(major * MINOR_PER_MAJOR)
+    }
+
+    pub fn minor_units(&self) -> i64 {
+        self.0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn checked_add(self, other: Money) -> Option<Money> {
+        self.0.checked_add(other.0).map(Money)
+    }
+
+    pub fn checked_sub(self, other: Money) -> Option<Money> {
+        self.0.checked_sub(other.0).map(Money)
+    }
+
+    /// Multiply by a whole quantity, e.g. unit price times items sold.
+    pub fn checked_mul(self, quantity: i64) -> Option<Money> {
+        self.0.checked_mul(quantity).map(Money)
+    }
+
+    /// Reject negative amounts, naming the offending field in the error.
+    pub fn ensure_non_negative(&self, field: &str) -> DomainResult<()> {
+        if self.is_negative() {
+            return Err(Error::ValidationError(format!(
+                "{} must not be negative",
+                field
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        Money(self.0 + other.0)
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        Money(self.0 - other.0)
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        let per_major = MINOR_PER_MAJOR as u64;
+        write!(
+            f,
+            "{}{}.{:0width$}",
+            sign,
+            abs / per_major,
+            abs % per_major,
+            width = MONEY_SCALE as usize
+        )
+    }
+}
+
+impl FromStr for Money {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::ValidationError(format!("Invalid amount: '{}'", s));
+
+        let trimmed = s.trim();
+        let (negative, digits) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed),
+        };
+        let (major, minor) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if major.is_empty() || !all_digits(major) || !all_digits(minor) {
+            return Err(invalid());
+        }
+        if minor.len() > MONEY_SCALE as usize {
+            return Err(Error::ValidationError(format!(
+                "Amount '{}' has more than {} decimal places",
+                s, MONEY_SCALE
+            )));
+        }
+
+        let major: i64 = major.parse().map_err(|_| invalid())?;
+        let minor: i64 = if minor.is_empty() {
+            0
+        } else {
+            // Right-pad so "1.5" means 150 minor units
+            format!("{:0<width$}", minor, width = MONEY_SCALE as usize)
+                .parse()
+                .map_err(|_| invalid())?
+        };
+
+        let value = major
+            .checked_mul(MINOR_PER_MAJOR)
+            .and_then(|v| v.checked_add(minor))
+            .ok_or_else(invalid)?;
+
+        Ok(Money(if negative { -value } else { value }))
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MoneyVisitor;
+
+        impl de::Visitor<'_> for MoneyVisitor {
+            type Value = Money;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a decimal string or an integer amount")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Money, E> {
+                v.parse().map_err(|e: Error| E::custom(e.to_string()))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Money, E> {
+                v.checked_mul(MINOR_PER_MAJOR)
+                    .map(Money)
+                    .ok_or_else(|| E::custom("amount out of range"))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Money, E> {
+                i64::try_from(v)
+                    .map_err(|_| E::custom("amount out of range"))
+                    .and_then(|v| self.visit_i64(v))
+            }
+        }
+
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!("12.34".parse::<Money>().unwrap(), Money::from_minor(1234));
+        assert_eq!("1.5".parse::<Money>().unwrap(), Money::from_minor(150));
+        assert_eq!("7".parse::<Money>().unwrap(), Money::from_major(7));
+        assert_eq!("-0.05".parse::<Money>().unwrap(), Money::from_minor(-5));
+
+        assert_eq!(Money::from_minor(1234).to_string(), "12.34");
+        assert_eq!(Money::from_minor(5).to_string(), "0.05");
+        assert_eq!(Money::from_minor(-150).to_string(), "-1.50");
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_amounts() {
+        for input in ["", "abc", "1.234", "1.2.3", "-", ".5", "1e3"] {
+            assert!(
+                matches!(input.parse::<Money>(), Err(Error::ValidationError(_))),
+                "expected '{}' to be rejected",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_decimal_arithmetic_is_exact() {
+        let a: Money = "0.1".parse().unwrap();
+        let b: Money = "0.2".parse().unwrap();
+
+        assert_eq!(a + b, "0.3".parse().unwrap());
+        assert_eq!(
+            Money::from_minor(10).checked_mul(3),
+            Some(Money::from_minor(30))
+        );
+        assert_eq!(
+            Money::from_minor(i64::MAX).checked_add(Money::from_minor(1)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let price = Money::from_minor(199_900);
+
+        let json = serde_json::to_string(&price).unwrap();
+        assert_eq!(json, "\"1999.00\"");
+        assert_eq!(serde_json::from_str::<Money>(&json).unwrap(), price);
+
+        // Whole numbers are accepted as major units
+        assert_eq!(
+            serde_json::from_str::<Money>("25").unwrap(),
+            Money::from_major(25)
+        );
+        // Floats are refused to avoid rounding surprises
+        assert!(serde_json::from_str::<Money>("0.1").is_err());
+    }
+
+    #[test]
+    fn test_ensure_non_negative() {
+        assert!(Money::ZERO.ensure_non_negative("price").is_ok());
+        assert!(matches!(
+            Money::from_minor(-1).ensure_non_negative("price"),
+            Err(Error::ValidationError(msg)) if msg == "price must not be negative"
+        ));
+    }
+}
@@ -1,3 +1,10 @@
+use crate::domain::{DomainResult, Error};
+
+/// Page size used when a client does not ask for one
+pub const DEFAULT_PAGE_SIZE: u32 = 20;
+/// Largest page size a client may request unless configured otherwise
+pub const DEFAULT_MAX_PAGE_SIZE: u32 = 200;
+
 #[derive(Debug, Clone)]
 pub struct PaginationOrder {
     pub field: String,
@@ -20,6 +27,25 @@ impl PaginationOptions {
         }
     }
 
+    /// Checks client-supplied paging before it reaches a query. Pages start at
+    /// 1, so `page = 0` is rejected; `page_size` is capped at `max_page_size`
+    /// and a zero `page_size` falls back to [`DEFAULT_PAGE_SIZE`].
+    pub fn normalize(self, max_page_size: u32) -> DomainResult<Self> {
+        if self.page == 0 {
+            return Err(Error::ValidationError(
+                "page must be at least 1".to_string(),
+            ));
+        }
+        let page_size = match self.page_size {
+            0 => DEFAULT_PAGE_SIZE,
+            size => size,
+        };
+        Ok(Self {
+            page_size: page_size.min(max_page_size.max(1)),
+            ..self
+        })
+    }
+
     pub fn offset(&self) -> u32 {
         (self.page - 1) * self.page_size
     }
@@ -28,3 +54,47 @@ impl PaginationOptions {
         self.page_size
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_clamps_page_size() {
+        let pagination = PaginationOptions::new(3, 1_000_000, None)
+            .normalize(DEFAULT_MAX_PAGE_SIZE)
+            .unwrap();
+        assert_eq!(pagination.page, 3);
+        assert_eq!(pagination.limit(), DEFAULT_MAX_PAGE_SIZE);
+
+        let pagination = PaginationOptions::new(1, 50, None).normalize(10).unwrap();
+        assert_eq!(pagination.limit(), 10);
+    }
+
+    #[test]
+    fn test_normalize_keeps_page_size_within_bounds() {
+        let pagination = PaginationOptions::new(2, 50, None)
+            .normalize(DEFAULT_MAX_PAGE_SIZE)
+            .unwrap();
+        assert_eq!(pagination.limit(), 50);
+        assert_eq!(pagination.offset(), 50);
+    }
+
+    #[test]
+    fn test_normalize_rejects_page_zero() {
+        let result = PaginationOptions::new(0, 20, None).normalize(DEFAULT_MAX_PAGE_SIZE);
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_normalize_zero_page_size_uses_default() {
+        let pagination = PaginationOptions::new(1, 0, None)
+            .normalize(DEFAULT_MAX_PAGE_SIZE)
+            .unwrap();
+        assert_eq!(pagination.limit(), DEFAULT_PAGE_SIZE);
+
+        // The default still respects a smaller cap
+        let pagination = PaginationOptions::new(1, 0, None).normalize(5).unwrap();
+        assert_eq!(pagination.limit(), 5);
+    }
+}
@@ -0,0 +1,142 @@
+use phonenumber::Mode;
+
+use crate::domain::{DomainResult, Error};
+
+use super::Update;
+
+/// ISO 3166 region used to read phone numbers written without a country code
+pub use phonenumber::country::Id as PhoneRegion;
+
+/// Region assumed when none is configured
+pub const DEFAULT_PHONE_REGION: PhoneRegion = PhoneRegion::ID;
+
+/// Parses a region code such as `ID` or `US`
+pub fn parse_phone_region(code: &str) -> DomainResult<PhoneRegion> {
+    code.trim()
+        .to_uppercase()
+        .parse()
+        .map_err(|_| Error::ValidationError(format!("Unknown phone region: {}", code)))
+}
+
+/// Normalizes `raw` to E.164 (`+628123456789`).
+///
+/// Numbers without a leading `+` are read as local numbers of `default_region`.
+pub fn normalize_phone(raw: &str, default_region: PhoneRegion) -> DomainResult<String> {
+    let invalid = || Error::ValidationError(format!("Invalid phone number: {}", raw));
+    let number = phonenumber::parse(Some(default_region), raw).map_err(|_| invalid())?;
+    if !number.is_valid() {
+        return Err(invalid());
+    }
+    Ok(number.format().mode(Mode::E164).to_string())
+}
+
+/// Normalizes an optional phone, leaving `None` as is
+pub fn normalize_optional_phone(
+    raw: Option<&str>,
+    default_region: PhoneRegion,
+) -> DomainResult<Option<String>> {
+    raw.map(|raw| normalize_phone(raw, default_region))
+        .transpose()
+}
+
+/// Normalizes a phone typed into a filter so it matches stored numbers.
+///
+/// Partial numbers that don't parse fall back to their digits, so `0812-3456`
+/// still matches as a substring; input without digits is returned as is.
+pub fn normalize_phone_filter(raw: &str, default_region: PhoneRegion) -> String {
+    if let Ok(phone) = normalize_phone(raw, default_region) {
+        return phone;
+    }
+    let digits: String = raw.chars().filter(char::is_ascii_digit).collect();
+    if digits.is_empty() {
+        raw.to_string()
+    } else {
+        digits
+    }
+}
+
+/// Normalizes the value of `Update::Set`; `Clear` and `Unchanged` pass through
+pub fn normalize_phone_update(
+    update: &Update<String>,
+    default_region: PhoneRegion,
+) -> DomainResult<Update<String>> {
+    Ok(match update {
+        Update::Set(raw) => Update::Set(normalize_phone(raw, default_region)?),
+        Update::Clear => Update::Clear,
+        Update::Unchanged => Update::Unchanged,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_local_formats() {
+        for raw in [
+            "08123456789",
+            "0812-3456-789",
+            "(0812) 3456 789",
+            "+62 812 3456 789",
+        ] {
+            assert_eq!(
+                normalize_phone(raw, PhoneRegion::ID).unwrap(),
+                "+628123456789",
+                "{raw}"
+            );
+        }
+        assert_eq!(
+            normalize_phone("(201) 555-0123", PhoneRegion::US).unwrap(),
+            "+12015550123"
+        );
+        assert_eq!(
+            normalize_phone("+1 201 555 0123", PhoneRegion::ID).unwrap(),
+            "+12015550123"
+        );
+    }
+
+    #[test]
+    fn test_normalize_rejects_gibberish() {
+        for raw in ["", "abc", "555", "0812-hello", "+999 1234"] {
+            assert!(
+                matches!(
+                    normalize_phone(raw, PhoneRegion::ID),
+                    Err(Error::ValidationError(_))
+                ),
+                "{raw} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalize_phone_filter() {
+        assert_eq!(
+            normalize_phone_filter("0812-3456-789", PhoneRegion::ID),
+            "+628123456789"
+        );
+        assert_eq!(
+            normalize_phone_filter("3456-789", PhoneRegion::ID),
+            "3456789"
+        );
+        assert_eq!(normalize_phone_filter("budi", PhoneRegion::ID), "budi");
+    }
+
+    #[test]
+    fn test_normalize_phone_update_keeps_clear() {
+        assert_eq!(
+            normalize_phone_update(&Update::Clear, PhoneRegion::ID).unwrap(),
+            Update::Clear
+        );
+        assert_eq!(
+            normalize_phone_update(&Update::Set("0812 3456 789".to_string()), PhoneRegion::ID)
+                .unwrap(),
+            Update::Set("+628123456789".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_phone_region() {
+        assert_eq!(parse_phone_region("id").unwrap(), PhoneRegion::ID);
+        assert!(parse_phone_region("XX").is_err());
+    }
+}
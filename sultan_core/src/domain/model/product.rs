@@ -1,7 +1,50 @@
+use std::str::FromStr;
+
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use utoipa::ToSchema;
 
 use super::Update;
+use super::money::Money;
+use super::stock::InitialStock;
+use super::validate::{Validate, validate_name, validate_optional_name};
+use crate::domain::{DomainResult, Error};
+
+/// Kind of product. Stored as its lowercase name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ProductType {
+    Product,
+    Service,
+    Bundle,
+}
+
+impl ProductType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProductType::Product => "product",
+            ProductType::Service => "service",
+            ProductType::Bundle => "bundle",
+        }
+    }
+}
+
+impl FromStr for ProductType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "product" => Ok(ProductType::Product),
+            "service" => Ok(ProductType::Service),
+            "bundle" => Ok(ProductType::Bundle),
+            _ => Err(Error::ValidationError(format!(
+                "Unknown product type '{}'",
+                s
+            ))),
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct UnitOfMeasure {
@@ -35,13 +78,15 @@ pub struct Product {
     pub is_deleted: bool,
     pub name: String,
     pub description: Option<String>,
-    pub product_type: String,
+    pub product_type: ProductType,
     pub main_image: Option<String>,
     pub sellable: bool,
     pub buyable: bool,
     pub editable_price: bool,
     pub has_variant: bool,
     pub metadata: Option<Value>,
+    pub created_by: Option<i64>,
+    pub updated_by: Option<i64>,
 }
 
 #[derive(Debug, Clone)]
@@ -54,22 +99,42 @@ pub struct ProductVariant {
     pub product: Product,
     pub barcode: Option<String>,
     pub name: Option<String>,
+    pub price: Option<Money>,
     pub metadata: Option<Value>,
 }
 
+/// A product together with its live variants
+#[derive(Debug, Clone)]
+pub struct ProductWithVariants {
+    pub product: Product,
+    pub variants: Vec<ProductVariant>,
+}
+
+/// A product with its live variants and the ids of its categories
+#[derive(Debug, Clone)]
+pub struct ProductDetail {
+    pub product: Product,
+    pub variants: Vec<ProductVariant>,
+    pub category_ids: Vec<i64>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ProductVariantCreate {
     pub product_id: i64,
     pub barcode: Option<String>,
     pub name: Option<String>,
+    pub price: Option<Money>,
     pub metadata: Option<Value>,
+    /// Opening stock per branch, written by the product service in the same
+    /// transaction as the variant. Repositories ignore it.
+    pub initial_stock: Vec<InitialStock>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ProductCreate {
     pub name: String,
     pub description: Option<String>,
-    pub product_type: String,
+    pub product_type: ProductType,
     pub main_image: Option<String>,
     pub sellable: bool,
     pub buyable: bool,
@@ -83,7 +148,7 @@ pub struct ProductCreate {
 pub struct ProductUpdate {
     pub name: Option<String>,
     pub description: Update<String>,
-    pub product_type: Option<String>,
+    pub product_type: Option<ProductType>,
     pub main_image: Update<String>,
     pub sellable: Option<bool>,
     pub buyable: Option<bool>,
@@ -93,22 +158,189 @@ pub struct ProductUpdate {
     pub category_ids: Option<Vec<i64>>,
 }
 
+impl Validate for ProductCreate {
+    fn validate(&self) -> DomainResult<()> {
+        validate_name("Name", &self.name)
+    }
+}
+
+impl Validate for ProductUpdate {
+    fn validate(&self) -> DomainResult<()> {
+        validate_optional_name("Name", self.name.as_deref())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ProductVariantUpdate {
     pub barcode: Update<String>,
     pub name: Update<String>,
+    pub price: Update<Money>,
     pub metadata: Update<Value>,
 }
 
+/// A variant keyed by barcode, as delivered by a catalog feed
+#[derive(Debug, Clone)]
+pub struct ProductVariantUpsert {
+    pub barcode: String,
+    pub name: Option<String>,
+    pub metadata: Option<Value>,
+}
+
+/// One change of a variant's base price. `price` is None when the price was
+/// cleared.
+#[derive(Debug, Clone)]
+pub struct VariantPriceChange {
+    pub variant_id: i64,
+    pub price: Option<Money>,
+    pub effective_from: chrono::DateTime<Utc>,
+    pub changed_by: Option<i64>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ProductCategory {
     pub product_id: i64,
     pub category_id: i64,
 }
 
+/// A supplier the product can be purchased from
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductSupplier {
+    pub supplier_id: i64,
+    /// The supplier's own code for the product
+    pub supplier_sku: Option<String>,
+    /// Last agreed purchase cost per unit
+    pub cost: Option<Money>,
+}
+
+/// A variant contained in a bundle variant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductComponent {
+    pub component_variant_id: i64,
+    /// Units of the component in one bundle
+    pub quantity: i64,
+}
+
+/// A component of an exploded bundle, with the variant loaded
 #[derive(Debug, Clone)]
+pub struct BundleComponent {
+    pub variant: ProductVariant,
+    pub quantity: i64,
+}
+
+/// Filter for browsing variants across products
+#[derive(Debug, Clone, Default)]
+pub struct ProductVariantFilter {
+    pub product_id: Option<i64>,
+    pub barcode: Option<String>,
+    /// Partial match on the variant name
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct ProductFilter {
     pub name: Option<String>,
-    pub product_type: Option<String>,
+    pub product_type: Option<ProductType>,
     pub category_id: Option<i64>,
+    pub sellable: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn product_create() -> ProductCreate {
+        ProductCreate {
+            name: "Coffee".to_string(),
+            description: None,
+            product_type: ProductType::Product,
+            main_image: None,
+            sellable: true,
+            buyable: true,
+            editable_price: false,
+            has_variant: false,
+            metadata: None,
+            category_ids: vec![],
+        }
+    }
+
+    fn product_update() -> ProductUpdate {
+        ProductUpdate {
+            name: None,
+            description: Update::Unchanged,
+            product_type: None,
+            main_image: Update::Unchanged,
+            sellable: None,
+            buyable: None,
+            editable_price: None,
+            has_variant: None,
+            metadata: Update::Unchanged,
+            category_ids: None,
+        }
+    }
+
+    #[test]
+    fn test_product_type_round_trip() {
+        for product_type in [
+            ProductType::Product,
+            ProductType::Service,
+            ProductType::Bundle,
+        ] {
+            assert_eq!(
+                product_type.as_str().parse::<ProductType>().unwrap(),
+                product_type
+            );
+            assert_eq!(
+                serde_json::to_value(product_type).unwrap(),
+                serde_json::json!(product_type.as_str())
+            );
+        }
+        assert_eq!(
+            serde_json::from_str::<ProductType>("\"service\"").unwrap(),
+            ProductType::Service
+        );
+    }
+
+    #[test]
+    fn test_product_type_rejects_unknown() {
+        for value in ["prodcut", "Product", ""] {
+            assert!(matches!(
+                value.parse::<ProductType>(),
+                Err(Error::ValidationError(_))
+            ));
+        }
+        assert!(serde_json::from_str::<ProductType>("\"gift\"").is_err());
+    }
+
+    #[test]
+    fn test_product_create_validate() {
+        assert!(product_create().validate().is_ok());
+        let empty_name = ProductCreate {
+            name: "".to_string(),
+            ..product_create()
+        };
+        assert!(matches!(
+            empty_name.validate(),
+            Err(Error::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_product_update_validate() {
+        assert!(product_update().validate().is_ok());
+        let valid = ProductUpdate {
+            name: Some("Tea".to_string()),
+            product_type: Some(ProductType::Service),
+            ..product_update()
+        };
+        assert!(valid.validate().is_ok());
+
+        let empty_name = ProductUpdate {
+            name: Some("  ".to_string()),
+            ..product_update()
+        };
+        assert!(matches!(
+            empty_name.validate(),
+            Err(Error::ValidationError(_))
+        ));
+    }
 }
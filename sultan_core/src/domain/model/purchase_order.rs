@@ -0,0 +1,142 @@
+use std::str::FromStr;
+
+use chrono::Utc;
+
+use super::money::Money;
+use super::validate::Validate;
+use crate::domain::{DomainResult, Error};
+
+/// Where a purchase order is in its lifecycle. Stock is only added when an
+/// `Ordered` purchase order becomes `Received`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PurchaseOrderStatus {
+    Ordered,
+    Received,
+    Cancelled,
+}
+
+impl PurchaseOrderStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PurchaseOrderStatus::Ordered => "ordered",
+            PurchaseOrderStatus::Received => "received",
+            PurchaseOrderStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl FromStr for PurchaseOrderStatus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ordered" => Ok(PurchaseOrderStatus::Ordered),
+            "received" => Ok(PurchaseOrderStatus::Received),
+            "cancelled" => Ok(PurchaseOrderStatus::Cancelled),
+            _ => Err(Error::ValidationError(format!(
+                "Unknown purchase order status '{}'",
+                s
+            ))),
+        }
+    }
+}
+
+/// Stock ordered from a supplier for one branch.
+#[derive(Debug, Clone)]
+pub struct PurchaseOrder {
+    pub id: i64,
+    pub supplier_id: i64,
+    pub branch_id: i64,
+    pub lines: Vec<PurchaseOrderLine>,
+    pub status: PurchaseOrderStatus,
+    pub created_at: chrono::DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PurchaseOrderLine {
+    pub variant_id: i64,
+    pub qty: i64,
+    /// Cost per unit
+    pub cost: Money,
+}
+
+#[derive(Debug, Clone)]
+pub struct PurchaseOrderCreate {
+    pub supplier_id: i64,
+    pub branch_id: i64,
+    pub lines: Vec<PurchaseOrderLine>,
+}
+
+impl Validate for PurchaseOrderCreate {
+    fn validate(&self) -> DomainResult<()> {
+        if self.lines.is_empty() {
+            return Err(Error::ValidationError(
+                "Purchase order must have at least one line".to_string(),
+            ));
+        }
+        for line in &self.lines {
+            if line.qty <= 0 {
+                return Err(Error::ValidationError(
+                    "Purchase order line quantity must be greater than zero".to_string(),
+                ));
+            }
+            line.cost.ensure_non_negative("cost")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(variant_id: i64, qty: i64, cost: i64) -> PurchaseOrderLine {
+        PurchaseOrderLine {
+            variant_id,
+            qty,
+            cost: Money::from_minor(cost),
+        }
+    }
+
+    fn order(lines: Vec<PurchaseOrderLine>) -> PurchaseOrderCreate {
+        PurchaseOrderCreate {
+            supplier_id: 1,
+            branch_id: 1,
+            lines,
+        }
+    }
+
+    #[test]
+    fn test_validate_accepts_valid_lines() {
+        assert!(
+            order(vec![line(10, 5, 1200), line(11, 1, 0)])
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_lines() {
+        for lines in [vec![], vec![line(10, 0, 1200)], vec![line(10, 1, -1)]] {
+            assert!(matches!(
+                order(lines).validate(),
+                Err(Error::ValidationError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [
+            PurchaseOrderStatus::Ordered,
+            PurchaseOrderStatus::Received,
+            PurchaseOrderStatus::Cancelled,
+        ] {
+            assert_eq!(
+                status.as_str().parse::<PurchaseOrderStatus>().unwrap(),
+                status
+            );
+        }
+        assert!("shipped".parse::<PurchaseOrderStatus>().is_err());
+    }
+}
@@ -0,0 +1,428 @@
+use std::str::FromStr;
+
+use chrono::{NaiveDate, Utc};
+
+use super::money::Money;
+use crate::domain::{DomainResult, Error};
+
+/// What a row in the sales table records.
+///
+/// `Void` and `Refund` records point at the sale they reverse and hold the
+/// returned quantities and amounts as positive values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaleKind {
+    Sale,
+    Void,
+    Refund,
+}
+
+impl SaleKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SaleKind::Sale => "sale",
+            SaleKind::Void => "void",
+            SaleKind::Refund => "refund",
+        }
+    }
+}
+
+impl FromStr for SaleKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sale" => Ok(SaleKind::Sale),
+            "void" => Ok(SaleKind::Void),
+            "refund" => Ok(SaleKind::Refund),
+            _ => Err(Error::ValidationError(format!("Unknown sale kind '{}'", s))),
+        }
+    }
+}
+
+/// A completed point-of-sale transaction.
+#[derive(Debug, Clone)]
+pub struct Sale {
+    pub id: i64,
+    pub branch_id: i64,
+    pub customer_id: Option<i64>,
+    pub payment_method: String,
+    pub kind: SaleKind,
+    /// The sale a void or refund reverses
+    pub original_sale_id: Option<i64>,
+    pub lines: Vec<SaleLine>,
+    pub total: Money,
+    pub created_at: chrono::DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaleLine {
+    pub variant_id: i64,
+    pub qty: i64,
+    pub unit_price: Money,
+    pub line_total: Money,
+}
+
+#[derive(Debug, Clone)]
+pub struct SaleCreate {
+    pub branch_id: i64,
+    pub customer_id: Option<i64>,
+    pub payment_method: String,
+    pub lines: Vec<SaleLineCreate>,
+}
+
+/// Requested refund of `qty` units of a variant from an earlier sale.
+#[derive(Debug, Clone)]
+pub struct SaleRefundLine {
+    pub variant_id: i64,
+    pub qty: i64,
+}
+
+/// Quantity of a variant already given back by a void or refund.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReversedLine {
+    pub kind: SaleKind,
+    pub variant_id: i64,
+    pub qty: i64,
+}
+
+/// End-of-day totals for one branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SalesSummary {
+    pub branch_id: i64,
+    pub date: NaiveDate,
+    pub sale_count: i64,
+    pub gross_total: Money,
+    pub by_payment_method: Vec<PaymentMethodTotal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentMethodTotal {
+    pub payment_method: String,
+    pub sale_count: i64,
+    pub total: Money,
+}
+
+#[derive(Debug, Clone)]
+pub struct SaleLineCreate {
+    pub variant_id: i64,
+    pub qty: i64,
+    pub unit_price: Money,
+}
+
+impl SaleLineCreate {
+    /// Validate the line and compute its total.
+    pub fn to_line(&self) -> DomainResult<SaleLine> {
+        if self.qty <= 0 {
+            return Err(Error::ValidationError(
+                "Sale line quantity must be greater than zero".to_string(),
+            ));
+        }
+        self.unit_price.ensure_non_negative("unit_price")?;
+
+        let line_total = self
+            .unit_price
+            .checked_mul(self.qty)
+            .ok_or_else(|| Error::ValidationError("Sale line total is out of range".to_string()))?;
+
+        Ok(SaleLine {
+            variant_id: self.variant_id,
+            qty: self.qty,
+            unit_price: self.unit_price,
+            line_total,
+        })
+    }
+}
+
+impl SaleCreate {
+    /// Validate every line and return the priced lines together with the sale total.
+    pub fn priced_lines(&self) -> DomainResult<(Vec<SaleLine>, Money)> {
+        if self.lines.is_empty() {
+            return Err(Error::ValidationError(
+                "Sale must have at least one line".to_string(),
+            ));
+        }
+
+        let lines = self
+            .lines
+            .iter()
+            .map(SaleLineCreate::to_line)
+            .collect::<DomainResult<Vec<_>>>()?;
+
+        let total = lines
+            .iter()
+            .try_fold(Money::ZERO, |acc, line| acc.checked_add(line.line_total))
+            .ok_or_else(|| Error::ValidationError("Sale total is out of range".to_string()))?;
+
+        Ok((lines, total))
+    }
+}
+
+impl Sale {
+    /// Quantity still available for reversal per variant, with the unit
+    /// price of the variant's first line.
+    fn remaining_by_variant(&self, reversed: &[ReversedLine]) -> DomainResult<Vec<SaleLine>> {
+        if self.kind != SaleKind::Sale {
+            return Err(Error::ValidationError(format!(
+                "Sale {} is a {} and cannot be reversed",
+                self.id,
+                self.kind.as_str()
+            )));
+        }
+        if reversed.iter().any(|r| r.kind == SaleKind::Void) {
+            return Err(Error::Conflict(format!(
+                "Sale {} is already voided",
+                self.id
+            )));
+        }
+
+        let mut remaining: Vec<SaleLine> = Vec::new();
+        for line in &self.lines {
+            match remaining
+                .iter_mut()
+                .find(|r| r.variant_id == line.variant_id)
+            {
+                Some(r) => r.qty += line.qty,
+                None => remaining.push(SaleLine {
+                    line_total: Money::ZERO,
+                    ..line.clone()
+                }),
+            }
+        }
+        for r in reversed {
+            if let Some(line) = remaining.iter_mut().find(|l| l.variant_id == r.variant_id) {
+                line.qty -= r.qty;
+            }
+        }
+        Ok(remaining)
+    }
+
+    /// Lines that reverse everything not yet refunded.
+    pub fn void_lines(&self, reversed: &[ReversedLine]) -> DomainResult<Vec<SaleLine>> {
+        let lines: Vec<SaleLine> = self
+            .remaining_by_variant(reversed)?
+            .into_iter()
+            .filter(|line| line.qty > 0)
+            .map(|line| priced_reversal(line.variant_id, line.qty, line.unit_price))
+            .collect::<DomainResult<_>>()?;
+
+        if lines.is_empty() {
+            return Err(Error::Conflict(format!(
+                "Sale {} has already been fully refunded",
+                self.id
+            )));
+        }
+        Ok(lines)
+    }
+
+    /// Lines for a partial refund, checked against what is left to refund.
+    pub fn refund_lines(
+        &self,
+        reversed: &[ReversedLine],
+        requested: &[SaleRefundLine],
+    ) -> DomainResult<Vec<SaleLine>> {
+        if requested.is_empty() {
+            return Err(Error::ValidationError(
+                "Refund must have at least one line".to_string(),
+            ));
+        }
+
+        let mut remaining = self.remaining_by_variant(reversed)?;
+        let mut lines = Vec::with_capacity(requested.len());
+        for request in requested {
+            if request.qty <= 0 {
+                return Err(Error::ValidationError(
+                    "Refund quantity must be greater than zero".to_string(),
+                ));
+            }
+            let available = remaining
+                .iter_mut()
+                .find(|l| l.variant_id == request.variant_id)
+                .ok_or_else(|| {
+                    Error::ValidationError(format!(
+                        "Variant {} was not sold in sale {}",
+                        request.variant_id, self.id
+                    ))
+                })?;
+            if request.qty > available.qty {
+                return Err(Error::ValidationError(format!(
+                    "Refund quantity {} for variant {} exceeds the {} left to refund",
+                    request.qty,
+                    request.variant_id,
+                    available.qty.max(0)
+                )));
+            }
+            available.qty -= request.qty;
+            lines.push(priced_reversal(
+                request.variant_id,
+                request.qty,
+                available.unit_price,
+            )?);
+        }
+        Ok(lines)
+    }
+}
+
+fn priced_reversal(variant_id: i64, qty: i64, unit_price: Money) -> DomainResult<SaleLine> {
+    SaleLineCreate {
+        variant_id,
+        qty,
+        unit_price,
+    }
+    .to_line()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(variant_id: i64, qty: i64, unit_price: &str) -> SaleLineCreate {
+        SaleLineCreate {
+            variant_id,
+            qty,
+            unit_price: unit_price.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_priced_lines_computes_totals() {
+        let sale = SaleCreate {
+            branch_id: 1,
+            customer_id: None,
+            payment_method: "cash".to_string(),
+            lines: vec![line(10, 3, "1.10"), line(11, 1, "2.50")],
+        };
+
+        let (lines, total) = sale.priced_lines().unwrap();
+
+        assert_eq!(lines[0].line_total, Money::from_minor(330));
+        assert_eq!(lines[1].line_total, Money::from_minor(250));
+        assert_eq!(total, Money::from_minor(580));
+    }
+
+    #[test]
+    fn test_priced_lines_rejects_invalid_lines() {
+        let empty = SaleCreate {
+            branch_id: 1,
+            customer_id: None,
+            payment_method: "cash".to_string(),
+            lines: vec![],
+        };
+        assert!(matches!(
+            empty.priced_lines(),
+            Err(Error::ValidationError(_))
+        ));
+
+        for bad in [line(10, 0, "1.00"), line(10, 1, "-1.00")] {
+            let sale = SaleCreate {
+                branch_id: 1,
+                customer_id: None,
+                payment_method: "cash".to_string(),
+                lines: vec![bad],
+            };
+            assert!(matches!(
+                sale.priced_lines(),
+                Err(Error::ValidationError(_))
+            ));
+        }
+    }
+
+    fn sold_sale() -> Sale {
+        Sale {
+            id: 1,
+            branch_id: 1,
+            customer_id: None,
+            payment_method: "cash".to_string(),
+            kind: SaleKind::Sale,
+            original_sale_id: None,
+            lines: vec![
+                line(10, 3, "2.00").to_line().unwrap(),
+                line(11, 1, "5.00").to_line().unwrap(),
+            ],
+            total: Money::from_major(11),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn reversed(kind: SaleKind, variant_id: i64, qty: i64) -> ReversedLine {
+        ReversedLine {
+            kind,
+            variant_id,
+            qty,
+        }
+    }
+
+    #[test]
+    fn test_void_lines_reverse_what_is_left() {
+        let sale = sold_sale();
+
+        let lines = sale.void_lines(&[]).unwrap();
+        assert_eq!(lines, sale.lines);
+
+        let lines = sale
+            .void_lines(&[reversed(SaleKind::Refund, 10, 1)])
+            .unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].qty, 2);
+        assert_eq!(lines[0].line_total, Money::from_major(4));
+    }
+
+    #[test]
+    fn test_void_lines_conflicts() {
+        let sale = sold_sale();
+
+        assert!(matches!(
+            sale.void_lines(&[reversed(SaleKind::Void, 10, 3)]),
+            Err(Error::Conflict(_))
+        ));
+        assert!(matches!(
+            sale.void_lines(&[
+                reversed(SaleKind::Refund, 10, 3),
+                reversed(SaleKind::Refund, 11, 1)
+            ]),
+            Err(Error::Conflict(_))
+        ));
+    }
+
+    #[test]
+    fn test_refund_lines_limits_quantity() {
+        let sale = sold_sale();
+        let refund = |variant_id, qty| SaleRefundLine { variant_id, qty };
+
+        let lines = sale.refund_lines(&[], &[refund(10, 2)]).unwrap();
+        assert_eq!(lines[0].qty, 2);
+        assert_eq!(lines[0].line_total, Money::from_major(4));
+
+        // One already refunded, so only two are left
+        assert!(matches!(
+            sale.refund_lines(&[reversed(SaleKind::Refund, 10, 1)], &[refund(10, 3)]),
+            Err(Error::ValidationError(_))
+        ));
+        // The same variant twice in one request counts together
+        assert!(matches!(
+            sale.refund_lines(&[], &[refund(10, 2), refund(10, 2)]),
+            Err(Error::ValidationError(_))
+        ));
+        assert!(matches!(
+            sale.refund_lines(&[], &[refund(99, 1)]),
+            Err(Error::ValidationError(_))
+        ));
+        assert!(matches!(
+            sale.refund_lines(&[], &[refund(10, 0)]),
+            Err(Error::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_reversal_records_cannot_be_reversed() {
+        let refund = Sale {
+            kind: SaleKind::Refund,
+            original_sale_id: Some(1),
+            ..sold_sale()
+        };
+
+        assert!(matches!(
+            refund.void_lines(&[]),
+            Err(Error::ValidationError(_))
+        ));
+    }
+}
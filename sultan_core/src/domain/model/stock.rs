@@ -0,0 +1,67 @@
+use super::validate::{Validate, validate_name};
+use crate::domain::{DomainResult, Error};
+
+/// A manual change to the on-hand quantity of a variant in one branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StockAdjustment {
+    pub branch_id: i64,
+    pub variant_id: i64,
+    /// Units added, or removed when negative
+    pub delta: i64,
+    /// Why the stock changed, e.g. "recount" or "damaged"
+    pub reason: String,
+}
+
+/// Quantity on hand of a variant in one branch, with the names needed to
+/// show it in a report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StockLevel {
+    pub branch_id: i64,
+    pub variant_id: i64,
+    pub product_id: i64,
+    pub product_name: String,
+    pub variant_name: Option<String>,
+    pub barcode: Option<String>,
+    pub quantity: i64,
+}
+
+/// Opening stock of a new variant in one branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InitialStock {
+    pub branch_id: i64,
+    pub quantity: i64,
+}
+
+impl Validate for StockAdjustment {
+    fn validate(&self) -> DomainResult<()> {
+        if self.delta == 0 {
+            return Err(Error::ValidationError(
+                "Stock adjustment cannot be zero".to_string(),
+            ));
+        }
+        validate_name("Reason", &self.reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adjustment(delta: i64, reason: &str) -> StockAdjustment {
+        StockAdjustment {
+            branch_id: 1,
+            variant_id: 2,
+            delta,
+            reason: reason.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_stock_adjustment_validate() {
+        assert!(adjustment(5, "recount").validate().is_ok());
+        assert!(adjustment(-3, "damaged").validate().is_ok());
+        for invalid in [adjustment(0, "recount"), adjustment(1, "  ")] {
+            assert!(matches!(invalid.validate(), Err(Error::ValidationError(_))));
+        }
+    }
+}
@@ -1,7 +1,13 @@
 use chrono::Utc;
 use serde_json::Value;
 
-use super::Update;
+use crate::domain::DomainResult;
+
+use super::{
+    Update,
+    email::{validate_email_update, validate_optional_email},
+    validate::{Validate, validate_name, validate_optional_name},
+};
 
 #[derive(Debug, Clone)]
 pub struct Supplier {
@@ -18,6 +24,8 @@ pub struct Supplier {
     pub npwp: Option<String>,
     pub npwp_name: Option<String>,
     pub metadata: Option<Value>,
+    pub created_by: Option<i64>,
+    pub updated_by: Option<i64>,
 }
 
 #[derive(Debug, Clone)]
@@ -32,6 +40,13 @@ pub struct SupplierCreate {
     pub metadata: Option<Value>,
 }
 
+impl Validate for SupplierCreate {
+    fn validate(&self) -> DomainResult<()> {
+        validate_name("Name", &self.name)?;
+        validate_optional_email(self.email.as_deref())
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct SupplierUpdate {
     pub name: Option<String>,
@@ -44,7 +59,14 @@ pub struct SupplierUpdate {
     pub metadata: Update<Value>,
 }
 
-#[derive(Debug, Clone)]
+impl Validate for SupplierUpdate {
+    fn validate(&self) -> DomainResult<()> {
+        validate_optional_name("Name", self.name.as_deref())?;
+        validate_email_update(&self.email)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct SupplierFilter {
     pub name: Option<String>,
     pub code: Option<String>,
@@ -52,3 +74,38 @@ pub struct SupplierFilter {
     pub npwp: Option<String>,
     pub email: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Error;
+
+    fn supplier_create(name: &str) -> SupplierCreate {
+        SupplierCreate {
+            name: name.to_string(),
+            code: None,
+            email: None,
+            address: None,
+            phone: None,
+            npwp: None,
+            npwp_name: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_supplier_validate() {
+        assert!(supplier_create("PT Maju").validate().is_ok());
+        assert!(matches!(
+            supplier_create("").validate(),
+            Err(Error::ValidationError(_))
+        ));
+
+        assert!(SupplierUpdate::default().validate().is_ok());
+        let update = SupplierUpdate {
+            name: Some("".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(update.validate(), Err(Error::ValidationError(_))));
+    }
+}
@@ -6,4 +6,8 @@ pub struct Token {
     pub expired_at: chrono::DateTime<Utc>,
     pub user_id: i64,
     pub token: String,
+    /// Shared by every token rotated from the same login
+    pub family_id: String,
+    /// Set once the token has been exchanged for a new one
+    pub used_at: Option<chrono::DateTime<Utc>>,
 }
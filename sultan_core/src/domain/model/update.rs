@@ -14,6 +14,14 @@ use validator::Validate;
 /// - `null` value → `Clear` (field explicitly set to null)
 /// - Value present → `Set(value)` (field has new value)
 ///
+/// The value itself is untagged: a PATCH body carries `"address": "..."` or
+/// `"address": null`, never `{"Set": ...}`.
+///
+/// Every `Update<T>` field in a deserialized struct **must** carry
+/// `#[serde(default)]`. Serde reads a missing field through the same
+/// `visit_none` path as an explicit `null`, so without the attribute an
+/// omitted field silently becomes `Clear` and wipes the stored value.
+///
 /// # Example
 /// ```ignore
 /// use sultan_core::domain::model::Update;
@@ -116,6 +124,33 @@ impl<T> Update<T> {
             Update::Set(v) => Update::Set(f(v)),
         }
     }
+
+    /// Converts from `&Update<T>` to `Update<&T>`.
+    pub fn as_ref(&self) -> Update<&T> {
+        match self {
+            Update::Unchanged => Update::Unchanged,
+            Update::Clear => Update::Clear,
+            Update::Set(v) => Update::Set(v),
+        }
+    }
+
+    /// Returns `self` unless it is `Unchanged`, in which case `other` is returned.
+    ///
+    /// Useful for layering a fallback update under an explicit one.
+    pub fn or(self, other: Update<T>) -> Update<T> {
+        match self {
+            Update::Unchanged => other,
+            update => update,
+        }
+    }
+
+    /// Returns the contained value if `Set`, or `default` for `Unchanged` and `Clear`.
+    pub fn unwrap_or(self, default: T) -> T {
+        match self {
+            Update::Set(v) => v,
+            _ => default,
+        }
+    }
 }
 
 impl<T: Clone> Update<T> {
@@ -165,7 +200,8 @@ impl<'de, T: Deserialize<'de>> Deserialize<'de> for Update<T> {
     where
         D: Deserializer<'de>,
     {
-        // This is called ONLY when the field is present in JSON
+        // With `#[serde(default)]` on the field this only runs when the key
+        // is present; a missing key never reaches here and stays `Unchanged`
         let opt = Option::<T>::deserialize(deserializer)?;
         Ok(match opt {
             Some(v) => Update::Set(v),
@@ -255,6 +291,40 @@ mod tests {
         assert_eq!(mapped_unchanged, Update::Unchanged);
     }
 
+    #[test]
+    fn test_as_ref() {
+        let unchanged: Update<String> = Update::Unchanged;
+        let clear: Update<String> = Update::Clear;
+        let set: Update<String> = Update::Set("value".to_string());
+
+        assert_eq!(unchanged.as_ref(), Update::Unchanged);
+        assert_eq!(clear.as_ref(), Update::Clear);
+        assert_eq!(set.as_ref(), Update::Set(&"value".to_string()));
+        assert_eq!(set.as_ref().map(|v| v.len()), Update::Set(5));
+        // The original is still usable after borrowing
+        assert!(set.is_set());
+    }
+
+    #[test]
+    fn test_or() {
+        let fallback = || Update::Set(1);
+
+        assert_eq!(Update::<i32>::Unchanged.or(fallback()), Update::Set(1));
+        assert_eq!(Update::<i32>::Clear.or(fallback()), Update::Clear);
+        assert_eq!(Update::Set(5).or(fallback()), Update::Set(5));
+        assert_eq!(
+            Update::<i32>::Unchanged.or(Update::Unchanged),
+            Update::Unchanged
+        );
+    }
+
+    #[test]
+    fn test_unwrap_or() {
+        assert_eq!(Update::<i32>::Unchanged.unwrap_or(0), 0);
+        assert_eq!(Update::<i32>::Clear.unwrap_or(0), 0);
+        assert_eq!(Update::Set(5).unwrap_or(0), 5);
+    }
+
     #[test]
     fn test_into_bind_value() {
         let clear: Update<String> = Update::Clear;
@@ -354,6 +424,38 @@ mod serde_tests {
         assert_eq!(result.set_field, Update::Set("value".to_string()));
     }
 
+    #[test]
+    fn test_deserialize_patch_body() {
+        #[derive(Debug, Deserialize)]
+        struct ContactPatch {
+            #[serde(default)]
+            address: Update<String>,
+            #[serde(default)]
+            email: Update<String>,
+            #[serde(default)]
+            phone: Update<String>,
+        }
+
+        let json = r#"{"email": null, "phone": "+6281234567890"}"#;
+        let result: ContactPatch = serde_json::from_str(json).unwrap();
+
+        assert_eq!(result.address, Update::Unchanged);
+        assert_eq!(result.email, Update::Clear);
+        assert_eq!(result.phone, Update::Set("+6281234567890".to_string()));
+    }
+
+    #[test]
+    fn test_deserialize_without_serde_default_clears_missing_field() {
+        // Documents why `#[serde(default)]` is required on every field
+        #[derive(Debug, Deserialize)]
+        struct NoDefault {
+            name: Update<String>,
+        }
+
+        let result: NoDefault = serde_json::from_str("{}").unwrap();
+        assert_eq!(result.name, Update::Clear);
+    }
+
     #[test]
     fn test_deserialize_empty_string_is_set() {
         // Empty string should be Set(""), not Clear
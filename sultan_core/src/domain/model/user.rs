@@ -17,6 +17,9 @@ pub struct User {
     pub pin: Option<String>,
     pub address: Option<String>,
     pub phone: Option<String>,
+    pub is_enabled: bool,
+    pub failed_login_count: i32,
+    pub locked_until: Option<chrono::DateTime<Utc>>,
     pub permissions: Option<Vec<Permission>>,
 }
 
@@ -41,6 +44,7 @@ pub struct UserUpdate {
     pub pin: super::Update<String>,
     pub address: super::Update<String>,
     pub phone: super::Update<String>,
+    pub is_enabled: Option<bool>,
 }
 
 #[derive(Debug, Clone, Default)]
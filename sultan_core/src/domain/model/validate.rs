@@ -0,0 +1,58 @@
+use crate::domain::{DomainResult, Error};
+
+/// Checks a create or update input before a service touches storage.
+/// Failures are reported as [`Error::ValidationError`].
+pub trait Validate {
+    fn validate(&self) -> DomainResult<()>;
+}
+
+/// Rejects a name that is empty or only whitespace
+pub fn validate_name(field: &str, name: &str) -> DomainResult<()> {
+    if name.trim().is_empty() {
+        return Err(Error::ValidationError(format!("{} cannot be empty", field)));
+    }
+    Ok(())
+}
+
+/// Validates an optional name, skipping it when absent
+pub fn validate_optional_name(field: &str, name: Option<&str>) -> DomainResult<()> {
+    name.map_or(Ok(()), |name| validate_name(field, name))
+}
+
+/// Rejects a negative value such as a customer level
+pub fn validate_non_negative(field: &str, value: i32) -> DomainResult<()> {
+    if value < 0 {
+        return Err(Error::ValidationError(format!(
+            "{} cannot be negative",
+            field
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name_rejects_blank() {
+        assert!(validate_name("Name", "Coffee").is_ok());
+        for name in ["", "   "] {
+            assert!(matches!(
+                validate_name("Name", name),
+                Err(Error::ValidationError(_))
+            ));
+        }
+        assert!(validate_optional_name("Name", None).is_ok());
+        assert!(validate_optional_name("Name", Some(" ")).is_err());
+    }
+
+    #[test]
+    fn test_validate_non_negative() {
+        assert!(validate_non_negative("Level", 0).is_ok());
+        assert!(matches!(
+            validate_non_negative("Level", -1),
+            Err(Error::ValidationError(_))
+        ));
+    }
+}
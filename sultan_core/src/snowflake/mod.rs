@@ -11,7 +11,7 @@
 //! By keeping the most significant bit as 0, the generated IDs are always
 //! positive when stored as i64 in databases like SQLite/PostgreSQL.
 
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 const UNUSED_BITS: u8 = 1;
@@ -170,6 +170,13 @@ impl IdGenerator for SnowflakeGenerator {
     }
 }
 
+/// Lets services share one generator, e.g. `Arc<dyn IdGenerator>`
+impl<T: IdGenerator + ?Sized> IdGenerator for Arc<T> {
+    fn generate(&self) -> Result<i64, SnowflakeError> {
+        (**self).generate()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -11,4 +11,13 @@ pub trait BranchRepository: Send + Sync {
     async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()>;
     async fn get_all(&self, ctx: &Context) -> DomainResult<Vec<Branch>>;
     async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Branch>>;
+    /// Add a user to a branch. Assigning an existing member is a no-op;
+    /// an unknown or deleted user is `NotFound`.
+    async fn assign_user(&self, ctx: &Context, branch_id: i64, user_id: i64) -> DomainResult<()>;
+    async fn unassign_user(&self, ctx: &Context, branch_id: i64, user_id: i64) -> DomainResult<()>;
+    async fn list_branches_for_user(
+        &self,
+        ctx: &Context,
+        user_id: i64,
+    ) -> DomainResult<Vec<Branch>>;
 }
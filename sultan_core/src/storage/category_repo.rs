@@ -10,6 +10,22 @@ pub trait CategoryRepository: Send + Sync {
     async fn create(&self, ctx: &Context, id: i64, category: &CategoryCreate) -> DomainResult<()>;
     async fn update(&self, ctx: &Context, id: i64, category: &CategoryUpdate) -> DomainResult<()>;
     async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()>;
-    async fn get_all(&self, ctx: &Context) -> DomainResult<Vec<Category>>;
-    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Category>>;
+    /// Category tree. With a branch, only categories assigned to it are
+    /// included, and a category whose parent is not assigned is left out too.
+    async fn get_all(&self, ctx: &Context, branch_id: Option<i64>) -> DomainResult<Vec<Category>>;
+    /// Number of categories `get_all` returns for the same branch, counting
+    /// every level of the tree
+    async fn count(&self, ctx: &Context, branch_id: Option<i64>) -> DomainResult<u64>;
+    /// With a branch, None unless the category is assigned to it; children not
+    /// assigned to it are left out.
+    async fn get_by_id(
+        &self,
+        ctx: &Context,
+        id: i64,
+        branch_id: Option<i64>,
+    ) -> DomainResult<Option<Category>>;
+    /// Make a category visible to a branch. Assigning it again is a no-op.
+    /// Fails with NotFound if the category does not exist or is deleted.
+    async fn assign_to_branch(&self, ctx: &Context, id: i64, branch_id: i64) -> DomainResult<()>;
+    async fn remove_from_branch(&self, ctx: &Context, id: i64, branch_id: i64) -> DomainResult<()>;
 }
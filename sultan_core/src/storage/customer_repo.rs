@@ -3,6 +3,7 @@ use async_trait::async_trait;
 use crate::domain::{
     Context, DomainResult,
     model::{
+        bulk::BulkResult,
         customer::{Customer, CustomerCreate, CustomerFilter, CustomerUpdate},
         pagination::PaginationOptions,
     },
@@ -13,12 +14,33 @@ pub trait CustomerRepository: Send + Sync {
     async fn create(&self, ctx: &Context, id: i64, customer: &CustomerCreate) -> DomainResult<()>;
     async fn update(&self, ctx: &Context, id: i64, customer: &CustomerUpdate) -> DomainResult<()>;
     async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()>;
+    /// Soft-delete each id in one transaction, reporting the ids that were
+    /// not found. With `all_or_nothing`, any missing id rolls back the rest.
+    async fn delete_many(
+        &self,
+        ctx: &Context,
+        ids: &[i64],
+        all_or_nothing: bool,
+    ) -> DomainResult<BulkResult>;
     async fn get_by_number(&self, ctx: &Context, number: &str) -> DomainResult<Option<Customer>>;
     async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Customer>>;
+    /// Live customers among `ids`, in id order. Deleted and unknown ids are
+    /// left out rather than reported.
+    async fn get_by_ids(&self, ctx: &Context, ids: &[i64]) -> DomainResult<Vec<Customer>>;
     async fn get_all(
         &self,
         ctx: &Context,
         filter: &CustomerFilter,
         pagination: &PaginationOptions,
     ) -> DomainResult<Vec<Customer>>;
+    /// Number of customers matching `filter`, ignoring pagination
+    async fn count(&self, ctx: &Context, filter: &CustomerFilter) -> DomainResult<u64>;
+    /// A page of customers together with the number matching `filter`, read
+    /// in one query so the two cannot disagree under concurrent writes
+    async fn get_all_with_total(
+        &self,
+        ctx: &Context,
+        filter: &CustomerFilter,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<(Vec<Customer>, u64)>;
 }
@@ -0,0 +1,16 @@
+use async_trait::async_trait;
+
+use crate::domain::{
+    Context, DomainResult,
+    model::device::{Device, DeviceCreate},
+};
+
+#[async_trait]
+pub trait DeviceRepository: Send + Sync {
+    async fn create(&self, ctx: &Context, id: i64, device: &DeviceCreate) -> DomainResult<()>;
+    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Device>>;
+    async fn get_by_key_hash(&self, ctx: &Context, key_hash: &str) -> DomainResult<Option<Device>>;
+    /// Marks the device revoked; its tokens stop working on the next request.
+    /// Revoking twice keeps the first `revoked_at`.
+    async fn revoke(&self, ctx: &Context, id: i64) -> DomainResult<()>;
+}
@@ -0,0 +1,38 @@
+use async_trait::async_trait;
+
+use crate::domain::{
+    Context, DomainResult,
+    model::export::{ExportPage, ExportTable, ImportMode, Snapshot},
+};
+
+/// Reads export pages inside one read transaction, so every page comes from
+/// the same state of the database even while writes go on. The transaction
+/// ends when the reader is dropped.
+#[async_trait]
+pub trait ExportReader: Send {
+    /// Up to `limit` rows of `table` with an id above `after_id`, as stored.
+    /// Soft-deleted rows are skipped unless `include_deleted` is set.
+    async fn export_rows(
+        &mut self,
+        table: ExportTable,
+        include_deleted: bool,
+        after_id: i64,
+        limit: u32,
+    ) -> DomainResult<ExportPage>;
+}
+
+#[async_trait]
+pub trait ExportRepository: Send + Sync {
+    /// Open a read transaction for a full export
+    async fn begin_export(&self, ctx: &Context) -> DomainResult<Box<dyn ExportReader>>;
+    /// Write every row of `snapshot` in one transaction, keeping the ids.
+    /// References to categories and products must resolve to rows in the
+    /// snapshot or already stored, otherwise nothing is written and the
+    /// dangling references are reported as a `ValidationError`.
+    async fn import_snapshot(
+        &self,
+        ctx: &Context,
+        snapshot: &Snapshot,
+        mode: ImportMode,
+    ) -> DomainResult<()>;
+}
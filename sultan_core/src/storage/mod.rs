@@ -1,9 +1,15 @@
 pub mod branch_repo;
 pub mod category_repo;
 pub mod customer_repo;
+pub mod device_repo;
+pub mod export_repo;
 pub mod product_repo;
+pub mod purchase_order_repo;
+pub mod read_repo;
+pub mod sale_repo;
 pub mod sell_price_repo;
 pub mod sqlite;
+pub mod stock_repo;
 pub mod supplier_repo;
 pub mod token_repo;
 pub mod transaction;
@@ -13,8 +19,14 @@ pub mod user_repo;
 pub use branch_repo::BranchRepository;
 pub use category_repo::CategoryRepository;
 pub use customer_repo::CustomerRepository;
+pub use device_repo::DeviceRepository;
+pub use export_repo::{ExportReader, ExportRepository};
 pub use product_repo::ProductRepository;
+pub use purchase_order_repo::PurchaseOrderRepository;
+pub use read_repo::ReadRepository;
+pub use sale_repo::SaleRepository;
 pub use sqlite::SqliteUserRepository;
+pub use stock_repo::StockRepository;
 pub use supplier_repo::SupplierRepository;
 pub use token_repo::TokenRepository;
 pub use unit::UnitOfMeasureRepository;
@@ -2,9 +2,14 @@ use async_trait::async_trait;
 
 use crate::domain::{
     Context, DomainResult,
-    model::product::{
-        Product, ProductCreate, ProductUpdate, ProductVariant, ProductVariantCreate,
-        ProductVariantUpdate,
+    model::{
+        money::Money,
+        pagination::PaginationOptions,
+        product::{
+            Product, ProductComponent, ProductCreate, ProductFilter, ProductSupplier,
+            ProductUpdate, ProductVariant, ProductVariantCreate, ProductVariantFilter,
+            ProductVariantUpdate, ProductVariantUpsert, ProductWithVariants, VariantPriceChange,
+        },
     },
 };
 
@@ -24,9 +29,41 @@ pub trait ProductRepository<Tx>: Send + Sync {
         product: &ProductUpdate,
         tx: &mut Tx,
     ) -> DomainResult<()>;
+    /// Soft-delete a product together with all of its live variants.
     async fn delete_product(&self, ctx: &Context, id: i64, tx: &mut Tx) -> DomainResult<()>;
+    /// Bump `updated_at` on a live product and nothing else, so sync and
+    /// caches see it as changed after an edit stored in another table.
+    async fn touch(&self, ctx: &Context, id: i64, tx: &mut Tx) -> DomainResult<()>;
     async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Product>>;
+    /// Products linked to a category, newest first, only sellable ones when
+    /// `sellable_only` is set. An unknown category has no products, so it
+    /// yields an empty list rather than NotFound.
+    async fn get_by_category(
+        &self,
+        ctx: &Context,
+        category_id: i64,
+        sellable_only: bool,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<Product>>;
+    async fn count_by_category(
+        &self,
+        ctx: &Context,
+        category_id: i64,
+        sellable_only: bool,
+    ) -> DomainResult<u64>;
+    /// Products matching `filter`, newest first, each with its live variants.
+    /// The variants of the whole page are loaded with one query.
+    async fn get_all_with_variants(
+        &self,
+        ctx: &Context,
+        filter: &ProductFilter,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<ProductWithVariants>>;
+    /// Number of products matching `filter`, regardless of pagination
+    async fn count(&self, ctx: &Context, filter: &ProductFilter) -> DomainResult<u64>;
 
+    /// Fails with Conflict if another live variant already has the barcode.
+    /// A variant created with a price gets its first price history row.
     async fn create_variant(
         &self,
         ctx: &Context,
@@ -34,34 +71,154 @@ pub trait ProductRepository<Tx>: Send + Sync {
         variant: &ProductVariantCreate,
         tx: &mut Tx,
     ) -> DomainResult<()>;
+    /// Fails with Conflict if the new barcode belongs to another live variant.
+    /// A changed price is recorded in the price history with the actor of `ctx`.
     async fn update_variant(
         &self,
         ctx: &Context,
         id: i64,
         variant: &ProductVariantUpdate,
+        tx: &mut Tx,
     ) -> DomainResult<()>;
+    /// Update the name and metadata of the live variant with `variant.barcode`,
+    /// or create it under `product_id` with `id` if there is none. Returns the
+    /// variant id and whether it was created. Fails with Conflict if the
+    /// barcode belongs to a variant of another product.
+    async fn upsert_variant_by_barcode(
+        &self,
+        ctx: &Context,
+        id: i64,
+        product_id: i64,
+        variant: &ProductVariantUpsert,
+        tx: &mut Tx,
+    ) -> DomainResult<(i64, bool)>;
     async fn delete_variant(&self, ctx: &Context, id: i64, tx: &mut Tx) -> DomainResult<()>;
+    /// Move a variant to another product, keeping its id and barcode. Fails
+    /// with NotFound if either the variant or the target product does not
+    /// exist or is deleted.
+    async fn reassign_variant(
+        &self,
+        ctx: &Context,
+        variant_id: i64,
+        new_product_id: i64,
+        tx: &mut Tx,
+    ) -> DomainResult<()>;
     async fn delete_variants_by_product_id(
         &self,
         ctx: &Context,
         product_id: i64,
         tx: &mut Tx,
     ) -> DomainResult<()>;
+    /// Live variant with the barcode. Legacy data may hold more than one; the
+    /// one with the highest id (the newest) is returned.
     async fn get_variant_by_barcode(
         &self,
         ctx: &Context,
         barcode: &str,
     ) -> DomainResult<Option<ProductVariant>>;
+    /// All live variants with the barcode, newest first. More than one means
+    /// the barcode is duplicated.
+    async fn get_variants_by_barcode(
+        &self,
+        ctx: &Context,
+        barcode: &str,
+    ) -> DomainResult<Vec<ProductVariant>>;
     async fn get_variant_by_id(
         &self,
         ctx: &Context,
         id: i64,
     ) -> DomainResult<Option<ProductVariant>>;
+    /// Live variants of live products among `ids`, ordered by id. Unknown or
+    /// deleted ids are left out.
+    async fn get_variants_by_ids(
+        &self,
+        ctx: &Context,
+        ids: &[i64],
+    ) -> DomainResult<Vec<ProductVariant>>;
     async fn get_variant_by_product_id(
         &self,
         ctx: &Context,
         product_id: i64,
     ) -> DomainResult<Vec<ProductVariant>>;
+    /// Live variants of live products matching the filter, newest first
+    async fn get_variants(
+        &self,
+        ctx: &Context,
+        filter: &ProductVariantFilter,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<ProductVariant>>;
+    /// Number of live variants of a product, without loading them
+    async fn count_variants(&self, ctx: &Context, product_id: i64) -> DomainResult<u64>;
 
     async fn get_product_category(&self, ctx: &Context, product_id: i64) -> DomainResult<Vec<i64>>;
+    /// Link categories to a product, keeping the existing ones. Categories that
+    /// are already linked are skipped.
+    async fn add_categories(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+        category_ids: &[i64],
+        tx: &mut Tx,
+    ) -> DomainResult<()>;
+    /// Unlink categories from a product. Categories that are not linked are skipped.
+    async fn remove_categories(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+        category_ids: &[i64],
+        tx: &mut Tx,
+    ) -> DomainResult<()>;
+
+    /// Replace the product's suppliers with `suppliers`
+    async fn set_suppliers(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+        suppliers: &[ProductSupplier],
+        tx: &mut Tx,
+    ) -> DomainResult<()>;
+    async fn get_suppliers(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+    ) -> DomainResult<Vec<ProductSupplier>>;
+
+    /// Replace the components of the bundle variant `bundle_id`. Fails with
+    /// NotFound if the bundle variant does not exist or is deleted, and with
+    /// ValidationError if the bundle would end up containing itself through
+    /// its components.
+    async fn set_components(
+        &self,
+        ctx: &Context,
+        bundle_id: i64,
+        components: &[ProductComponent],
+        tx: &mut Tx,
+    ) -> DomainResult<()>;
+    /// Components of a bundle variant, ordered by component variant id
+    async fn get_components(
+        &self,
+        ctx: &Context,
+        bundle_id: i64,
+    ) -> DomainResult<Vec<ProductComponent>>;
+
+    async fn set_level_price(
+        &self,
+        ctx: &Context,
+        variant_id: i64,
+        customer_level: i32,
+        price: &Money,
+    ) -> DomainResult<()>;
+    async fn get_level_price(
+        &self,
+        ctx: &Context,
+        variant_id: i64,
+        customer_level: i32,
+    ) -> DomainResult<Option<Money>>;
+    /// Base price changes of a variant, oldest first
+    async fn get_price_history(
+        &self,
+        ctx: &Context,
+        variant_id: i64,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<VariantPriceChange>>;
 }
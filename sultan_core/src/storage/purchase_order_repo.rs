@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+
+use crate::domain::{
+    Context, DomainResult,
+    model::purchase_order::{
+        PurchaseOrder, PurchaseOrderCreate, PurchaseOrderLine, PurchaseOrderStatus,
+    },
+};
+
+#[async_trait]
+pub trait PurchaseOrderRepository<Tx>: Send + Sync {
+    /// Write the purchase order header and lines with status `Ordered`.
+    async fn create(
+        &self,
+        ctx: &Context,
+        id: i64,
+        order: &PurchaseOrderCreate,
+        tx: &mut Tx,
+    ) -> DomainResult<()>;
+    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<PurchaseOrder>>;
+    /// Move the purchase order from `from` to `to`. Fails with `Conflict` when
+    /// its current status is not `from`, so two concurrent transitions cannot
+    /// both succeed.
+    async fn update_status(
+        &self,
+        ctx: &Context,
+        id: i64,
+        from: PurchaseOrderStatus,
+        to: PurchaseOrderStatus,
+        tx: &mut Tx,
+    ) -> DomainResult<()>;
+    /// Add the line quantities to the branch stock.
+    async fn add_stock(
+        &self,
+        ctx: &Context,
+        branch_id: i64,
+        lines: &[PurchaseOrderLine],
+        tx: &mut Tx,
+    ) -> DomainResult<()>;
+}
@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+
+use crate::domain::{Context, DomainResult, model::pagination::PaginationOptions};
+
+/// Read access shared by every entity repository, so generic code (e.g. a
+/// list handler) can work over any of them. Filtered lookups stay on the
+/// entity-specific traits.
+#[async_trait]
+pub trait ReadRepository<T>: Send + Sync {
+    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<T>>;
+    /// All non-deleted rows, unfiltered.
+    async fn get_all(&self, ctx: &Context, pagination: &PaginationOptions) -> DomainResult<Vec<T>>;
+}
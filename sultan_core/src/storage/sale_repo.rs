@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::domain::{
+    Context, DomainResult,
+    model::sale::{ReversedLine, Sale, SaleCreate, SaleKind, SaleLine, SalesSummary},
+};
+
+#[async_trait]
+pub trait SaleRepository<Tx>: Send + Sync {
+    /// Write the sale header and lines and take the sold quantities out of
+    /// the branch stock. Fails with `ValidationError` when a line has
+    /// insufficient stock; the caller must then roll `tx` back.
+    async fn create(
+        &self,
+        ctx: &Context,
+        id: i64,
+        sale: &SaleCreate,
+        tx: &mut Tx,
+    ) -> DomainResult<()>;
+    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Sale>>;
+    /// Write a void or refund record for `original` and put the returned
+    /// quantities back into the branch stock.
+    async fn create_reversal(
+        &self,
+        ctx: &Context,
+        id: i64,
+        original: &Sale,
+        kind: SaleKind,
+        lines: &[SaleLine],
+        tx: &mut Tx,
+    ) -> DomainResult<()>;
+    /// Quantities already reversed for a sale, per kind and variant.
+    async fn reversed_lines(
+        &self,
+        ctx: &Context,
+        sale_id: i64,
+        tx: &mut Tx,
+    ) -> DomainResult<Vec<ReversedLine>>;
+    /// Totals of the sales made at `branch_id` on the given (UTC) day.
+    async fn summary(
+        &self,
+        ctx: &Context,
+        branch_id: i64,
+        date: NaiveDate,
+    ) -> DomainResult<SalesSummary>;
+
+    /// Sale id recorded for an idempotency key that has not expired at `now`.
+    /// Keys are scoped to the branch and to the user in `ctx`.
+    async fn find_by_idempotency_key(
+        &self,
+        ctx: &Context,
+        branch_id: i64,
+        key: &str,
+        now: DateTime<Utc>,
+    ) -> DomainResult<Option<i64>>;
+    /// Record `key` for `sale_id` under the sale's branch and the user in
+    /// `ctx`, replacing an expired entry for the same key.
+    /// Returns `false` when a live entry already exists, i.e. another request
+    /// with the same key won the race.
+    async fn save_idempotency_key(
+        &self,
+        ctx: &Context,
+        key: &str,
+        sale_id: i64,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+        tx: &mut Tx,
+    ) -> DomainResult<bool>;
+    /// Remove idempotency keys that expired at or before `now`. Returns how
+    /// many were removed.
+    async fn delete_expired_idempotency_keys(
+        &self,
+        ctx: &Context,
+        now: DateTime<Utc>,
+    ) -> DomainResult<u64>;
+}
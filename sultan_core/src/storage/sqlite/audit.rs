@@ -0,0 +1,75 @@
+use sqlx::SqlitePool;
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+use crate::domain::{
+    DomainResult,
+    audit::{AuditSink, PERMISSION_DENIED, PermissionDenial},
+};
+
+/// Denials buffered for the writer before new ones are dropped
+const AUDIT_QUEUE_CAPACITY: usize = 1024;
+
+/// Writes permission denials to the `audit_log` table.
+///
+/// `permission_denied` runs on the request path, so denials are queued on a
+/// bounded channel and inserted one at a time by a single writer task. When
+/// the queue is full the denial is dropped and a warning logged, rather than
+/// letting a flood of denials pile up tasks and connections.
+#[derive(Clone)]
+pub struct SqliteAuditSink {
+    pool: SqlitePool,
+    sender: mpsc::Sender<PermissionDenial>,
+}
+
+impl SqliteAuditSink {
+    /// Create the sink and spawn its writer task, which stops once every
+    /// clone of the sink is dropped. Must be called inside a tokio runtime.
+    pub fn new(pool: SqlitePool) -> Self {
+        let (sender, mut receiver) = mpsc::channel::<PermissionDenial>(AUDIT_QUEUE_CAPACITY);
+        let writer_pool = pool.clone();
+        tokio::spawn(async move {
+            while let Some(denial) = receiver.recv().await {
+                if let Err(e) = write_denial(&writer_pool, &denial).await {
+                    tracing::error!("Failed to write audit_log entry: {}", e);
+                }
+            }
+        });
+        Self { pool, sender }
+    }
+
+    pub async fn write(&self, denial: &PermissionDenial) -> DomainResult<()> {
+        write_denial(&self.pool, denial).await
+    }
+}
+
+async fn write_denial(pool: &SqlitePool, denial: &PermissionDenial) -> DomainResult<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO audit_log (event, actor_id, request_id, resource, action, branch_id)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(PERMISSION_DENIED)
+    .bind(denial.actor_id)
+    .bind(denial.request_id)
+    .bind(denial.resource)
+    .bind(denial.action)
+    .bind(denial.branch_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+impl AuditSink for SqliteAuditSink {
+    fn permission_denied(&self, denial: &PermissionDenial) {
+        match self.sender.try_send(denial.clone()) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                tracing::warn!("audit_log queue is full, permission denial dropped");
+            }
+            Err(TrySendError::Closed(_)) => {
+                tracing::error!("audit_log writer has stopped, permission denial dropped");
+            }
+        }
+    }
+}
@@ -6,9 +6,16 @@ use sqlx::{QueryBuilder, Sqlite, SqlitePool};
 use crate::{
     domain::{
         Context, DomainResult, Error,
-        model::branch::{Branch, BranchCreate, BranchUpdate},
+        model::{
+            branch::{Branch, BranchCreate, BranchUpdate},
+            pagination::PaginationOptions,
+        },
+    },
+    storage::{
+        ReadRepository,
+        branch_repo::BranchRepository,
+        sqlite::{TableName, map_results, soft_delete_by_on_pool},
     },
-    storage::{branch_repo::BranchRepository, sqlite::map_results},
 };
 
 #[derive(Clone)]
@@ -39,13 +46,15 @@ pub struct BranchDbSqlite {
     pub image: Option<String>,
 }
 
-impl From<BranchDbSqlite> for Branch {
-    fn from(branch_db: BranchDbSqlite) -> Self {
-        Branch {
+impl TryFrom<BranchDbSqlite> for Branch {
+    type Error = Error;
+
+    fn try_from(branch_db: BranchDbSqlite) -> Result<Self, Self::Error> {
+        Ok(Branch {
             id: branch_db.id,
-            created_at: super::parse_sqlite_date(&branch_db.created_at),
-            updated_at: super::parse_sqlite_date(&branch_db.updated_at),
-            deleted_at: branch_db.deleted_at.map(|d| super::parse_sqlite_date(&d)),
+            created_at: super::parse_sqlite_date(&branch_db.created_at)?,
+            updated_at: super::parse_sqlite_date(&branch_db.updated_at)?,
+            deleted_at: super::parse_optional_sqlite_date(branch_db.deleted_at.as_deref())?,
             is_deleted: branch_db.is_deleted,
             is_main: branch_db.is_main,
             name: branch_db.name,
@@ -54,7 +63,7 @@ impl From<BranchDbSqlite> for Branch {
             phone: branch_db.phone,
             npwp: branch_db.npwp,
             image: branch_db.image,
-        }
+        })
     }
 }
 
@@ -130,18 +139,8 @@ impl BranchRepository for SqliteBranchRepository {
         Ok(())
     }
 
-    async fn delete(&self, _: &Context, id: i64) -> DomainResult<()> {
-        let query = sqlx::query(
-            r#"
-            UPDATE branches SET
-                is_deleted = 1,
-                deleted_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
-            WHERE id = ? AND is_deleted = 0
-            "#,
-        )
-        .bind(id)
-        .execute(&self.pool);
-
+    async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()> {
+        let query = soft_delete_by_on_pool(&self.pool, TableName::Branches, id, ctx.actor_id());
         let result = query.await?;
 
         if result.rows_affected() == 0 {
@@ -160,7 +159,7 @@ impl BranchRepository for SqliteBranchRepository {
         .fetch_all(&self.pool);
 
         let branches = query.await?;
-        Ok(map_results(branches))
+        map_results(branches)
     }
 
     async fn get_by_id(&self, _: &Context, id: i64) -> DomainResult<Option<Branch>> {
@@ -172,6 +171,91 @@ impl BranchRepository for SqliteBranchRepository {
         .bind(id)
         .fetch_optional(&self.pool);
 
-        Ok(query.await?.map(Branch::from))
+        query.await?.map(Branch::try_from).transpose()
+    }
+    async fn assign_user(&self, _: &Context, branch_id: i64, user_id: i64) -> DomainResult<()> {
+        let user_exists: Option<i64> =
+            sqlx::query_scalar("SELECT 1 FROM users WHERE id = ? AND is_deleted = 0")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        if user_exists.is_none() {
+            return Err(Error::NotFound(format!(
+                "User with id {} not found",
+                user_id
+            )));
+        }
+
+        let query = sqlx::query(
+            r#"
+            INSERT INTO user_branches (user_id, branch_id) VALUES (?, ?)
+            ON CONFLICT (user_id, branch_id) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(branch_id)
+        .execute(&self.pool);
+
+        query.await?;
+        Ok(())
+    }
+
+    async fn unassign_user(&self, _: &Context, branch_id: i64, user_id: i64) -> DomainResult<()> {
+        let query = sqlx::query("DELETE FROM user_branches WHERE user_id = ? AND branch_id = ?")
+            .bind(user_id)
+            .bind(branch_id)
+            .execute(&self.pool);
+
+        let result = query.await?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound(format!(
+                "User {} is not assigned to branch {}",
+                user_id, branch_id
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn list_branches_for_user(&self, _: &Context, user_id: i64) -> DomainResult<Vec<Branch>> {
+        let query = sqlx::query_as::<_, BranchDbSqlite>(
+            r#"
+            SELECT b.* FROM branches b
+            INNER JOIN user_branches ub ON ub.branch_id = b.id
+            WHERE ub.user_id = ? AND b.is_deleted = 0
+            ORDER BY b.id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool);
+
+        let branches = query.await?;
+        map_results(branches)
+    }
+}
+
+#[async_trait]
+impl ReadRepository<Branch> for SqliteBranchRepository {
+    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Branch>> {
+        BranchRepository::get_by_id(self, ctx, id).await
+    }
+
+    async fn get_all(
+        &self,
+        _: &Context,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<Branch>> {
+        let query = sqlx::query_as::<_, BranchDbSqlite>(
+            r#"
+            SELECT * FROM branches WHERE is_deleted = 0 ORDER BY id LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(pagination.limit())
+        .bind(pagination.offset())
+        .fetch_all(&self.pool);
+
+        let branches = query.await?;
+        map_results(branches)
     }
 }
@@ -7,11 +7,22 @@ use sqlx::{QueryBuilder, Sqlite, SqlitePool};
 use crate::{
     domain::{
         Context, DomainResult, Error,
-        model::category::{Category, CategoryCreate, CategoryUpdate},
+        model::{
+            category::{Category, CategoryCreate, CategoryUpdate},
+            pagination::PaginationOptions,
+        },
+    },
+    storage::{
+        CategoryRepository, ReadRepository,
+        sqlite::{TableName, soft_delete_by_on_pool},
     },
-    storage::CategoryRepository,
 };
 
+/// Categories keyed by id, used while assembling the tree
+type CategoryMap = HashMap<i64, Category>;
+/// Parent id -> child ids
+type ChildrenMap = HashMap<i64, Vec<i64>>;
+
 #[derive(Clone)]
 pub struct SqliteCategoryRepository {
     pool: SqlitePool,
@@ -82,27 +93,27 @@ impl SqliteCategoryRepository {
     }
 
     /// Convert CategoryDbSqlite to Category domain model
-    fn to_category(c: &CategoryDbSqlite) -> Category {
-        Category {
+    fn to_category(c: &CategoryDbSqlite) -> DomainResult<Category> {
+        Ok(Category {
             id: c.id,
-            created_at: super::parse_sqlite_date(&c.created_at),
-            updated_at: super::parse_sqlite_date(&c.updated_at),
-            deleted_at: c.deleted_at.as_ref().map(|d| super::parse_sqlite_date(d)),
+            created_at: super::parse_sqlite_date(&c.created_at)?,
+            updated_at: super::parse_sqlite_date(&c.updated_at)?,
+            deleted_at: super::parse_optional_sqlite_date(c.deleted_at.as_deref())?,
             is_deleted: c.is_deleted,
             name: c.name.clone(),
             description: c.description.clone(),
             children: Some(Vec::new()),
-        }
+        })
     }
 
     /// Build maps needed for tree construction from a list of categories
     fn build_tree_maps(
         categories: &[CategoryDbSqlite],
-    ) -> (HashMap<i64, Category>, HashMap<i64, Vec<i64>>) {
+    ) -> DomainResult<(CategoryMap, ChildrenMap)> {
         let category_map: HashMap<i64, Category> = categories
             .iter()
-            .map(|c| (c.id, Self::to_category(c)))
-            .collect();
+            .map(|c| Self::to_category(c).map(|category| (c.id, category)))
+            .collect::<DomainResult<_>>()?;
 
         let mut children_map: HashMap<i64, Vec<i64>> = HashMap::new();
         for c in categories {
@@ -111,7 +122,7 @@ impl SqliteCategoryRepository {
             }
         }
 
-        (category_map, children_map)
+        Ok((category_map, children_map))
     }
 
     /// Recursively build a subtree starting from a given category id
@@ -139,23 +150,28 @@ impl SqliteCategoryRepository {
 
     /// Build a tree structure from a flat list of categories.
     /// Returns only root categories (those with no parent) with their children populated.
-    fn build_category_tree(categories: Vec<CategoryDbSqlite>) -> Vec<Category> {
+    fn build_category_tree(categories: Vec<CategoryDbSqlite>) -> DomainResult<Vec<Category>> {
         let root_ids: Vec<i64> = categories
             .iter()
             .filter(|c| c.parent_id.is_none())
             .map(|c| c.id)
             .collect();
 
-        let (mut category_map, children_map) = Self::build_tree_maps(&categories);
+        let (mut category_map, children_map) = Self::build_tree_maps(&categories)?;
 
-        root_ids
+        Ok(root_ids
             .into_iter()
             .filter_map(|id| Self::build_subtree(id, &mut category_map, &children_map))
-            .collect()
+            .collect())
     }
 
-    /// Fetch all descendants of a category and build the subtree.
-    async fn get_category_with_children(&self, category_id: i64) -> DomainResult<Option<Category>> {
+    /// Fetch all descendants of a category and build the subtree. With a
+    /// branch, only categories assigned to it are followed.
+    async fn get_category_with_children(
+        &self,
+        category_id: i64,
+        branch_id: Option<i64>,
+    ) -> DomainResult<Option<Category>> {
         // Fetch the category and all its descendants using recursive CTE
         let query = sqlx::query_as::<_, CategoryDbSqlite>(
             r#"
@@ -163,6 +179,7 @@ impl SqliteCategoryRepository {
                 SELECT id, created_at, updated_at, deleted_at, is_deleted, name, description, parent_id
                 FROM categories
                 WHERE id = ? AND is_deleted = 0
+                    AND (? IS NULL OR id IN (SELECT category_id FROM branch_categories WHERE branch_id = ?))
                 
                 UNION ALL
                 
@@ -170,11 +187,16 @@ impl SqliteCategoryRepository {
                 FROM categories c
                 INNER JOIN category_tree ct ON c.parent_id = ct.id
                 WHERE c.is_deleted = 0
+                    AND (? IS NULL OR c.id IN (SELECT category_id FROM branch_categories WHERE branch_id = ?))
             )
             SELECT * FROM category_tree
             "#,
         )
         .bind(category_id)
+        .bind(branch_id)
+        .bind(branch_id)
+        .bind(branch_id)
+        .bind(branch_id)
         .fetch_all(&self.pool);
 
         let categories = query.await?;
@@ -183,7 +205,7 @@ impl SqliteCategoryRepository {
             return Ok(None);
         }
 
-        let (mut category_map, children_map) = Self::build_tree_maps(&categories);
+        let (mut category_map, children_map) = Self::build_tree_maps(&categories)?;
         Ok(Self::build_subtree(
             category_id,
             &mut category_map,
@@ -204,18 +226,20 @@ pub struct CategoryDbSqlite {
     pub parent_id: Option<i64>,
 }
 
-impl From<CategoryDbSqlite> for Category {
-    fn from(category_db: CategoryDbSqlite) -> Self {
-        Category {
+impl TryFrom<CategoryDbSqlite> for Category {
+    type Error = Error;
+
+    fn try_from(category_db: CategoryDbSqlite) -> Result<Self, Self::Error> {
+        Ok(Category {
             id: category_db.id,
-            created_at: super::parse_sqlite_date(&category_db.created_at),
-            updated_at: super::parse_sqlite_date(&category_db.updated_at),
-            deleted_at: category_db.deleted_at.map(|d| super::parse_sqlite_date(&d)),
+            created_at: super::parse_sqlite_date(&category_db.created_at)?,
+            updated_at: super::parse_sqlite_date(&category_db.updated_at)?,
+            deleted_at: super::parse_optional_sqlite_date(category_db.deleted_at.as_deref())?,
             is_deleted: category_db.is_deleted,
             name: category_db.name,
             description: category_db.description,
             children: None, // Children can be populated later if needed
-        }
+        })
     }
 }
 
@@ -301,18 +325,8 @@ impl CategoryRepository for SqliteCategoryRepository {
         Ok(())
     }
 
-    async fn delete(&self, _: &Context, id: i64) -> DomainResult<()> {
-        let query = sqlx::query(
-            r#"
-            UPDATE categories SET
-                is_deleted = 1,
-                deleted_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
-            WHERE id = ? AND is_deleted = 0
-            "#,
-        )
-        .bind(id)
-        .execute(&self.pool);
-
+    async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()> {
+        let query = soft_delete_by_on_pool(&self.pool, TableName::Categories, id, ctx.actor_id());
         let result = query.await?;
 
         if result.rows_affected() == 0 {
@@ -325,22 +339,127 @@ impl CategoryRepository for SqliteCategoryRepository {
         Ok(())
     }
 
-    async fn get_all(&self, _: &Context) -> DomainResult<Vec<Category>> {
+    async fn get_all(&self, _: &Context, branch_id: Option<i64>) -> DomainResult<Vec<Category>> {
         let query = sqlx::query_as::<_, CategoryDbSqlite>(
             r#"
             SELECT id, created_at, updated_at, deleted_at, is_deleted, name, description, parent_id
             FROM categories WHERE is_deleted = 0
+                AND (? IS NULL OR id IN (SELECT category_id FROM branch_categories WHERE branch_id = ?))
             "#,
         )
+        .bind(branch_id)
+        .bind(branch_id)
         .fetch_all(&self.pool);
 
         let categories = query.await?;
 
-        // Build tree structure with children populated
-        Ok(Self::build_category_tree(categories))
+        // Build tree structure with children populated. Children of a
+        // category left out by the branch filter are never reached.
+        Self::build_category_tree(categories)
+    }
+
+    async fn count(&self, _: &Context, branch_id: Option<i64>) -> DomainResult<u64> {
+        // Walk down from the roots like build_category_tree, so categories
+        // under a filtered-out or deleted parent are not counted
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            WITH RECURSIVE category_tree AS (
+                SELECT id FROM categories
+                WHERE parent_id IS NULL AND is_deleted = 0
+                    AND (? IS NULL OR id IN (SELECT category_id FROM branch_categories WHERE branch_id = ?))
+
+                UNION ALL
+
+                SELECT c.id FROM categories c
+                INNER JOIN category_tree ct ON c.parent_id = ct.id
+                WHERE c.is_deleted = 0
+                    AND (? IS NULL OR c.id IN (SELECT category_id FROM branch_categories WHERE branch_id = ?))
+            )
+            SELECT COUNT(*) FROM category_tree
+            "#,
+        )
+        .bind(branch_id)
+        .bind(branch_id)
+        .bind(branch_id)
+        .bind(branch_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count as u64)
+    }
+
+    async fn get_by_id(
+        &self,
+        _: &Context,
+        id: i64,
+        branch_id: Option<i64>,
+    ) -> DomainResult<Option<Category>> {
+        self.get_category_with_children(id, branch_id).await
     }
 
-    async fn get_by_id(&self, _: &Context, id: i64) -> DomainResult<Option<Category>> {
-        self.get_category_with_children(id).await
+    async fn assign_to_branch(&self, _: &Context, id: i64, branch_id: i64) -> DomainResult<()> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO branch_categories (branch_id, category_id)
+            SELECT ?, id FROM categories WHERE id = ? AND is_deleted = 0
+            ON CONFLICT (branch_id, category_id) DO NOTHING
+            "#,
+        )
+        .bind(branch_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            let exists: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM categories WHERE id = ? AND is_deleted = 0)",
+            )
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await?;
+            if !exists {
+                return Err(Error::NotFound(format!(
+                    "Category with id {} not found",
+                    id
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn remove_from_branch(&self, _: &Context, id: i64, branch_id: i64) -> DomainResult<()> {
+        sqlx::query("DELETE FROM branch_categories WHERE branch_id = ? AND category_id = ?")
+            .bind(branch_id)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ReadRepository<Category> for SqliteCategoryRepository {
+    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Category>> {
+        CategoryRepository::get_by_id(self, ctx, id, None).await
+    }
+
+    /// A flat page of categories by id, without children
+    async fn get_all(
+        &self,
+        _: &Context,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<Category>> {
+        let categories = sqlx::query_as::<_, CategoryDbSqlite>(
+            r#"
+            SELECT id, created_at, updated_at, deleted_at, is_deleted, name, description, parent_id
+            FROM categories WHERE is_deleted = 0
+            ORDER BY id LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(pagination.limit())
+        .bind(pagination.offset())
+        .fetch_all(&self.pool)
+        .await?;
+        super::map_results(categories)
     }
 }
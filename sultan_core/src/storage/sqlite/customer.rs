@@ -3,18 +3,21 @@ use serde::Serialize;
 use sqlx::{QueryBuilder, Sqlite, SqlitePool};
 
 use super::{
-    QueryBuilderExt, TableName, check_rows_affected, map_results, serialize_metadata_update,
-    soft_delete,
+    QueryBuilderExt, TableName, check_rows_affected, map_results, push_filter_exprs,
+    serialize_metadata_update, soft_delete_by, soft_delete_by_on_pool,
 };
 use crate::{
     domain::{
-        Context, DomainResult,
+        Context, DomainResult, Error,
         model::{
-            customer::{Customer, CustomerCreate, CustomerFilter, CustomerUpdate},
+            bulk::BulkResult,
+            customer::{
+                CUSTOMER_FILTER_FIELDS, Customer, CustomerCreate, CustomerFilter, CustomerUpdate,
+            },
             pagination::PaginationOptions,
         },
     },
-    storage::CustomerRepository,
+    storage::{CustomerRepository, ReadRepository},
 };
 
 #[derive(Clone)]
@@ -43,15 +46,27 @@ pub struct CustomerDbSqlite {
     pub phone: Option<String>,
     pub level: i32,
     pub metadata: Option<String>,
+    pub created_by: Option<i64>,
+    pub updated_by: Option<i64>,
 }
 
-impl From<CustomerDbSqlite> for Customer {
-    fn from(customer_db: CustomerDbSqlite) -> Self {
-        Customer {
+/// A customer row carrying the windowed count of the whole filtered set
+#[derive(sqlx::FromRow)]
+struct CustomerWithTotalDbSqlite {
+    #[sqlx(flatten)]
+    customer: CustomerDbSqlite,
+    total: i64,
+}
+
+impl TryFrom<CustomerDbSqlite> for Customer {
+    type Error = Error;
+
+    fn try_from(customer_db: CustomerDbSqlite) -> Result<Self, Self::Error> {
+        Ok(Customer {
             id: customer_db.id,
-            created_at: super::parse_sqlite_date(&customer_db.created_at),
-            updated_at: super::parse_sqlite_date(&customer_db.updated_at),
-            deleted_at: customer_db.deleted_at.map(|d| super::parse_sqlite_date(&d)),
+            created_at: super::parse_sqlite_date(&customer_db.created_at)?,
+            updated_at: super::parse_sqlite_date(&customer_db.updated_at)?,
+            deleted_at: super::parse_optional_sqlite_date(customer_db.deleted_at.as_deref())?,
             is_deleted: customer_db.is_deleted,
             number: customer_db.number,
             name: customer_db.name,
@@ -62,20 +77,34 @@ impl From<CustomerDbSqlite> for Customer {
             metadata: customer_db
                 .metadata
                 .and_then(|m| serde_json::from_str(&m).ok()),
+            created_by: customer_db.created_by,
+            updated_by: customer_db.updated_by,
+        })
+    }
+}
+
+/// idx_customers_number_unique compares numbers case-insensitively, so
+/// "ABC123" and "abc123" collide.
+fn number_conflict(err: sqlx::Error, number: &str) -> Error {
+    match &err {
+        sqlx::Error::Database(e) if e.is_unique_violation() => {
+            Error::Conflict(format!("Customer number {} is already in use", number))
         }
+        _ => err.into(),
     }
 }
 
 #[async_trait]
 impl CustomerRepository for SqliteCustomerRepository {
-    async fn create(&self, _: &Context, id: i64, customer: &CustomerCreate) -> DomainResult<()> {
+    async fn create(&self, ctx: &Context, id: i64, customer: &CustomerCreate) -> DomainResult<()> {
         let metadata_json = super::serialize_metadata(&customer.metadata);
 
         let query = sqlx::query(
             r#"
             INSERT INTO customers (
-                id, number, name, address, email, phone, level, metadata
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                id, number, name, address, email, phone, level, metadata,
+                created_by, updated_by
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id)
@@ -86,13 +115,17 @@ impl CustomerRepository for SqliteCustomerRepository {
         .bind(&customer.phone)
         .bind(customer.level)
         .bind(&metadata_json)
+        .bind(ctx.actor_id())
+        .bind(ctx.actor_id())
         .execute(&self.pool);
 
-        query.await?;
+        query
+            .await
+            .map_err(|e| number_conflict(e, &customer.number))?;
         Ok(())
     }
 
-    async fn update(&self, _: &Context, id: i64, customer: &CustomerUpdate) -> DomainResult<()> {
+    async fn update(&self, ctx: &Context, id: i64, customer: &CustomerUpdate) -> DomainResult<()> {
         let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new("UPDATE customers SET ");
         let mut separated = builder.separated(", ");
 
@@ -127,26 +160,62 @@ impl CustomerRepository for SqliteCustomerRepository {
                 .push_bind_unseparated(metadata_json);
         }
 
+        separated
+            .push("updated_by = ")
+            .push_bind_unseparated(ctx.actor_id());
         separated.push("updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')");
         builder.push(" WHERE id = ").push_bind(id);
         builder.push(" AND is_deleted = 0");
 
         let query = builder.build();
-        let result = query.execute(&self.pool).await?;
+        let result = query
+            .execute(&self.pool)
+            .await
+            .map_err(|e| match &customer.number {
+                Some(number) => number_conflict(e, number),
+                None => e.into(),
+            })?;
         check_rows_affected(result.rows_affected(), "Customer", id)
     }
 
-    async fn delete(&self, _: &Context, id: i64) -> DomainResult<()> {
-        let query = soft_delete(&self.pool, TableName::Customers, id);
+    async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()> {
+        let query = soft_delete_by_on_pool(&self.pool, TableName::Customers, id, ctx.actor_id());
         let result = query.await?;
         check_rows_affected(result.rows_affected(), "Customer", id)
     }
 
+    async fn delete_many(
+        &self,
+        ctx: &Context,
+        ids: &[i64],
+        all_or_nothing: bool,
+    ) -> DomainResult<BulkResult> {
+        let mut tx = self.pool.begin().await?;
+        let mut result = BulkResult::default();
+        for &id in ids {
+            let deleted =
+                soft_delete_by(&mut *tx, TableName::Customers, id, ctx.actor_id()).await?;
+            if deleted.rows_affected() == 0 {
+                result.not_found.push(id);
+            } else {
+                result.deleted.push(id);
+            }
+        }
+
+        if all_or_nothing && !result.not_found.is_empty() {
+            tx.rollback().await?;
+            result.deleted.clear();
+            return Ok(result);
+        }
+        tx.commit().await?;
+        Ok(result)
+    }
+
     async fn get_by_number(&self, _: &Context, number: &str) -> DomainResult<Option<Customer>> {
         let query = sqlx::query_as::<_, CustomerDbSqlite>(
             r#"
-            SELECT id, created_at, updated_at, deleted_at, is_deleted, number, name, address, email, phone, level, metadata
-            FROM customers WHERE number = ? AND is_deleted = 0
+            SELECT id, created_at, updated_at, deleted_at, is_deleted, number, name, address, email, phone, level, metadata, created_by, updated_by
+            FROM customers WHERE number_normalized = lower(?) AND is_deleted = 0
             "#,
         )
         .bind(number)
@@ -154,20 +223,40 @@ impl CustomerRepository for SqliteCustomerRepository {
 
         let customer = query.await?;
 
-        Ok(customer.map(|c| c.into()))
+        customer.map(Customer::try_from).transpose()
     }
 
     async fn get_by_id(&self, _: &Context, id: i64) -> DomainResult<Option<Customer>> {
         let query = sqlx::query_as::<_, CustomerDbSqlite>(
             r#"
-            SELECT id, created_at, updated_at, deleted_at, is_deleted, number, name, address, email, phone, level, metadata
+            SELECT id, created_at, updated_at, deleted_at, is_deleted, number, name, address, email, phone, level, metadata, created_by, updated_by
             FROM customers WHERE id = ? AND is_deleted = 0
             "#,
         )
         .bind(id)
         .fetch_optional(&self.pool);
 
-        Ok(query.await?.map(Customer::from))
+        query.await?.map(Customer::try_from).transpose()
+    }
+
+    async fn get_by_ids(&self, _: &Context, ids: &[i64]) -> DomainResult<Vec<Customer>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "SELECT id, created_at, updated_at, deleted_at, is_deleted, number, name, address, email, phone, level, metadata, created_by, updated_by FROM customers WHERE is_deleted = 0 AND id IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for id in ids {
+            separated.push_bind(id);
+        }
+        builder.push(") ORDER BY id");
+
+        let customers = builder
+            .build_query_as::<CustomerDbSqlite>()
+            .fetch_all(&self.pool)
+            .await?;
+        customers.into_iter().map(Customer::try_from).collect()
     }
 
     async fn get_all(
@@ -177,19 +266,10 @@ impl CustomerRepository for SqliteCustomerRepository {
         pagination: &PaginationOptions,
     ) -> DomainResult<Vec<Customer>> {
         let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
-            "SELECT id, created_at, updated_at, deleted_at, is_deleted, number, name, address, email, phone, level, metadata FROM customers WHERE is_deleted = 0",
+            "SELECT id, created_at, updated_at, deleted_at, is_deleted, number, name, address, email, phone, level, metadata, created_by, updated_by FROM customers WHERE is_deleted = 0",
         );
 
-        builder
-            .push_like_filter("number", &filter.number)
-            .push_like_filter("name", &filter.name)
-            .push_like_filter("email", &filter.email)
-            .push_like_filter("phone", &filter.phone);
-
-        if let Some(level) = filter.level {
-            builder.push(" AND level = ");
-            builder.push_bind(level);
-        }
+        push_filter(&mut builder, filter)?;
 
         builder.push(" ORDER BY id DESC");
         builder.push(" LIMIT ");
@@ -199,6 +279,92 @@ impl CustomerRepository for SqliteCustomerRepository {
 
         let query = builder.build_query_as::<CustomerDbSqlite>();
         let customers = query.fetch_all(&self.pool).await?;
-        Ok(map_results(customers))
+        map_results(customers)
+    }
+
+    async fn count(&self, _: &Context, filter: &CustomerFilter) -> DomainResult<u64> {
+        let mut builder: QueryBuilder<Sqlite> =
+            QueryBuilder::new("SELECT COUNT(*) FROM customers WHERE is_deleted = 0");
+        push_filter(&mut builder, filter)?;
+
+        let count: i64 = builder.build_query_scalar().fetch_one(&self.pool).await?;
+        Ok(count as u64)
+    }
+
+    async fn get_all_with_total(
+        &self,
+        ctx: &Context,
+        filter: &CustomerFilter,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<(Vec<Customer>, u64)> {
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "SELECT id, created_at, updated_at, deleted_at, is_deleted, number, name, address, email, phone, level, metadata, created_by, updated_by, COUNT(*) OVER () AS total FROM customers WHERE is_deleted = 0",
+        );
+
+        push_filter(&mut builder, filter)?;
+
+        builder.push(" ORDER BY id DESC");
+        builder.push(" LIMIT ");
+        builder.push_bind(pagination.limit());
+        builder.push(" OFFSET ");
+        builder.push_bind(pagination.offset());
+
+        let rows = builder
+            .build_query_as::<CustomerWithTotalDbSqlite>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        // A page past the end has no row to carry the total
+        let total = match rows.first() {
+            Some(row) => row.total as u64,
+            None if pagination.offset() > 0 => self.count(ctx, filter).await?,
+            None => 0,
+        };
+        let customers = map_results(rows.into_iter().map(|row| row.customer).collect())?;
+        Ok((customers, total))
+    }
+}
+
+fn push_filter(
+    builder: &mut QueryBuilder<'_, Sqlite>,
+    filter: &CustomerFilter,
+) -> DomainResult<()> {
+    builder
+        .push_like_filter("number", &filter.number)
+        .push_like_filter("name", &filter.name)
+        .push_like_filter("email", &filter.email)
+        .push_like_filter("phone", &filter.phone);
+    if let Some(search) = &filter.search {
+        let search_phone = filter.search_phone.as_deref().unwrap_or(search);
+        builder.push_like_any_of(&[("name", search), ("phone", search_phone), ("email", search)]);
+    }
+
+    if let Some(level) = filter.level {
+        builder.push(" AND level = ");
+        builder.push_bind(level);
+    }
+    if let Some(level_min) = filter.level_min {
+        builder.push(" AND level >= ");
+        builder.push_bind(level_min);
+    }
+    if let Some(level_max) = filter.level_max {
+        builder.push(" AND level <= ");
+        builder.push_bind(level_max);
+    }
+    push_filter_exprs(builder, &filter.expressions, CUSTOMER_FILTER_FIELDS)
+}
+
+#[async_trait]
+impl ReadRepository<Customer> for SqliteCustomerRepository {
+    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Customer>> {
+        CustomerRepository::get_by_id(self, ctx, id).await
+    }
+
+    async fn get_all(
+        &self,
+        ctx: &Context,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<Customer>> {
+        CustomerRepository::get_all(self, ctx, &CustomerFilter::default(), pagination).await
     }
 }
@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+use super::{check_rows_affected, parse_optional_sqlite_date, parse_sqlite_date};
+use crate::{
+    domain::{
+        Context, DomainResult, Error,
+        model::device::{Device, DeviceCreate},
+    },
+    storage::device_repo::DeviceRepository,
+};
+
+#[derive(sqlx::FromRow, Debug)]
+struct DeviceDbSqlite {
+    pub id: i64,
+    pub created_at: String,
+    pub user_id: i64,
+    pub name: String,
+    pub revoked_at: Option<String>,
+}
+
+impl TryFrom<DeviceDbSqlite> for Device {
+    type Error = Error;
+
+    fn try_from(db: DeviceDbSqlite) -> Result<Self, Self::Error> {
+        Ok(Device {
+            id: db.id,
+            created_at: parse_sqlite_date(&db.created_at)?,
+            user_id: db.user_id,
+            name: db.name,
+            revoked_at: parse_optional_sqlite_date(db.revoked_at.as_deref())?,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct SqliteDeviceRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteDeviceRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl DeviceRepository for SqliteDeviceRepository {
+    async fn create(&self, _: &Context, id: i64, device: &DeviceCreate) -> DomainResult<()> {
+        sqlx::query("INSERT INTO devices (id, user_id, name, key_hash) VALUES (?, ?, ?, ?)")
+            .bind(id)
+            .bind(device.user_id)
+            .bind(&device.name)
+            .bind(&device.key_hash)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(e) if e.is_unique_violation() => {
+                    Error::Conflict("Device key is already registered".to_string())
+                }
+                e => e.into(),
+            })?;
+        Ok(())
+    }
+
+    async fn get_by_id(&self, _: &Context, id: i64) -> DomainResult<Option<Device>> {
+        sqlx::query_as::<_, DeviceDbSqlite>(
+            "SELECT id, created_at, user_id, name, revoked_at FROM devices WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(Device::try_from)
+        .transpose()
+    }
+
+    async fn get_by_key_hash(&self, _: &Context, key_hash: &str) -> DomainResult<Option<Device>> {
+        sqlx::query_as::<_, DeviceDbSqlite>(
+            "SELECT id, created_at, user_id, name, revoked_at FROM devices WHERE key_hash = ?",
+        )
+        .bind(key_hash)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(Device::try_from)
+        .transpose()
+    }
+
+    async fn revoke(&self, _: &Context, id: i64) -> DomainResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE devices
+            SET revoked_at = COALESCE(revoked_at, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+            WHERE id = ?
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        check_rows_affected(result.rows_affected(), "Device", id)
+    }
+}
@@ -0,0 +1,647 @@
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sqlx::{
+    FromRow, QueryBuilder, Sqlite, SqliteConnection, SqlitePool, Transaction, sqlite::SqliteRow,
+};
+
+use super::{
+    category::CategoryDbSqlite,
+    customer::CustomerDbSqlite,
+    product::{
+        PRODUCT_SELECT_COLUMNS, ProductDbSqlite, ProductVariantDbSqlite, VARIANT_SELECT_COLUMNS,
+    },
+    supplier::SupplierDbSqlite,
+    transaction::{BusyRetry, retry_on_busy},
+    unit::UnitOfMeasureDbSqlite,
+};
+use crate::{
+    domain::{
+        Context, DomainResult, Error,
+        model::{
+            export::{ExportPage, ExportTable, ImportMode, Snapshot},
+            product::ProductType,
+        },
+    },
+    storage::{ExportReader, ExportRepository},
+};
+
+const CATEGORY_SELECT_COLUMNS: &str = r#"
+    SELECT id, created_at, updated_at, deleted_at, is_deleted, name, description, parent_id
+    FROM categories
+"#;
+
+const CUSTOMER_SELECT_COLUMNS: &str = r#"
+    SELECT id, created_at, updated_at, deleted_at, is_deleted, number, name, address, email,
+           phone, level, metadata, created_by, updated_by
+    FROM customers
+"#;
+
+const SUPPLIER_SELECT_COLUMNS: &str = r#"
+    SELECT id, created_at, updated_at, deleted_at, is_deleted, name, code, email, address,
+           phone, npwp, npwp_name, metadata, created_by, updated_by
+    FROM suppliers
+"#;
+
+const UNIT_SELECT_COLUMNS: &str = r#"
+    SELECT id, created_at, updated_at, deleted_at, is_deleted, name, description
+    FROM units
+"#;
+
+/// Ids looked up per query when checking references, well below SQLite's
+/// limit on bound parameters
+const LOOKUP_CHUNK_SIZE: usize = 500;
+
+/// Tables an import writes, in order, with the columns it may set. Only these
+/// names reach the SQL; other keys in a snapshot row are ignored.
+const IMPORT_TABLES: [(ExportTable, &str, &[&str]); 6] = [
+    (
+        ExportTable::Units,
+        "units",
+        &[
+            "id",
+            "created_at",
+            "updated_at",
+            "deleted_at",
+            "is_deleted",
+            "name",
+            "description",
+        ],
+    ),
+    (
+        ExportTable::Suppliers,
+        "suppliers",
+        &[
+            "id",
+            "created_at",
+            "updated_at",
+            "deleted_at",
+            "is_deleted",
+            "name",
+            "code",
+            "email",
+            "address",
+            "phone",
+            "npwp",
+            "npwp_name",
+            "metadata",
+            "created_by",
+            "updated_by",
+        ],
+    ),
+    (
+        ExportTable::Customers,
+        "customers",
+        &[
+            "id",
+            "created_at",
+            "updated_at",
+            "deleted_at",
+            "is_deleted",
+            "number",
+            "name",
+            "address",
+            "email",
+            "phone",
+            "level",
+            "metadata",
+            "created_by",
+            "updated_by",
+        ],
+    ),
+    (
+        ExportTable::Categories,
+        "categories",
+        &[
+            "id",
+            "created_at",
+            "updated_at",
+            "deleted_at",
+            "is_deleted",
+            "name",
+            "description",
+            "parent_id",
+        ],
+    ),
+    (
+        ExportTable::Products,
+        "products",
+        &[
+            "id",
+            "created_at",
+            "updated_at",
+            "deleted_at",
+            "is_deleted",
+            "name",
+            "description",
+            "product_type",
+            "main_image",
+            "sellable",
+            "buyable",
+            "editable_price",
+            "has_variant",
+            "metadata",
+            "created_by",
+            "updated_by",
+        ],
+    ),
+    (
+        ExportTable::Variants,
+        "product_variants",
+        &[
+            "id",
+            "created_at",
+            "updated_at",
+            "deleted_at",
+            "is_deleted",
+            "product_id",
+            "barcode",
+            "name",
+            "price",
+            "metadata",
+        ],
+    ),
+];
+
+#[derive(Clone)]
+pub struct SqliteExportRepository {
+    pool: SqlitePool,
+    busy_retry: BusyRetry,
+}
+
+impl SqliteExportRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            busy_retry: BusyRetry::default(),
+        }
+    }
+
+    pub fn with_busy_retry(mut self, busy_retry: BusyRetry) -> Self {
+        self.busy_retry = busy_retry;
+        self
+    }
+}
+
+/// [`ExportReader`] over one read transaction. SQLite pins the snapshot at
+/// the first read, and the transaction is rolled back when the reader is
+/// dropped.
+struct SqliteExportReader {
+    tx: Transaction<'static, Sqlite>,
+}
+
+#[async_trait]
+impl ExportReader for SqliteExportReader {
+    async fn export_rows(
+        &mut self,
+        table: ExportTable,
+        include_deleted: bool,
+        after_id: i64,
+        limit: u32,
+    ) -> DomainResult<ExportPage> {
+        let conn = &mut *self.tx;
+        match table {
+            ExportTable::Products => {
+                let mut page = page::<ProductDbSqlite>(
+                    conn,
+                    PRODUCT_SELECT_COLUMNS,
+                    include_deleted,
+                    after_id,
+                    limit,
+                )
+                .await?;
+                attach_category_ids(conn, &mut page.rows).await?;
+                Ok(page)
+            }
+            ExportTable::Variants => {
+                page::<ProductVariantDbSqlite>(
+                    conn,
+                    VARIANT_SELECT_COLUMNS,
+                    include_deleted,
+                    after_id,
+                    limit,
+                )
+                .await
+            }
+            ExportTable::Categories => {
+                page::<CategoryDbSqlite>(
+                    conn,
+                    CATEGORY_SELECT_COLUMNS,
+                    include_deleted,
+                    after_id,
+                    limit,
+                )
+                .await
+            }
+            ExportTable::Customers => {
+                page::<CustomerDbSqlite>(
+                    conn,
+                    CUSTOMER_SELECT_COLUMNS,
+                    include_deleted,
+                    after_id,
+                    limit,
+                )
+                .await
+            }
+            ExportTable::Suppliers => {
+                page::<SupplierDbSqlite>(
+                    conn,
+                    SUPPLIER_SELECT_COLUMNS,
+                    include_deleted,
+                    after_id,
+                    limit,
+                )
+                .await
+            }
+            ExportTable::Units => {
+                page::<UnitOfMeasureDbSqlite>(
+                    conn,
+                    UNIT_SELECT_COLUMNS,
+                    include_deleted,
+                    after_id,
+                    limit,
+                )
+                .await
+            }
+        }
+    }
+}
+
+/// Read one page of rows with `select` and serialize them as stored
+async fn page<T>(
+    conn: &mut SqliteConnection,
+    select: &str,
+    include_deleted: bool,
+    after_id: i64,
+    limit: u32,
+) -> DomainResult<ExportPage>
+where
+    T: for<'r> FromRow<'r, SqliteRow> + Serialize + Send + Unpin,
+{
+    let sql = format!(
+        "{} WHERE id > ?{} ORDER BY id LIMIT ?",
+        select,
+        if include_deleted {
+            ""
+        } else {
+            " AND is_deleted = 0"
+        }
+    );
+    let rows: Vec<T> = sqlx::query_as(&sql)
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(conn)
+        .await?;
+
+    let rows = rows
+        .iter()
+        .map(row_to_json)
+        .collect::<DomainResult<Vec<_>>>()?;
+    let next_after_id = if rows.len() as u32 == limit {
+        rows.last().and_then(|row| row["id"].as_i64())
+    } else {
+        None
+    };
+    Ok(ExportPage {
+        rows,
+        next_after_id,
+    })
+}
+
+/// Add each product's `category_ids`, so an import can restore the links
+async fn attach_category_ids(conn: &mut SqliteConnection, rows: &mut [Value]) -> DomainResult<()> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+        "SELECT product_id, category_id FROM product_categories WHERE product_id IN (",
+    );
+    let mut separated = builder.separated(", ");
+    for row in rows.iter() {
+        separated.push_bind(row["id"].as_i64());
+    }
+    builder.push(") ORDER BY category_id");
+    let links: Vec<(i64, i64)> = builder.build_query_as().fetch_all(conn).await?;
+
+    let mut by_product: HashMap<i64, Vec<i64>> = HashMap::new();
+    for (product_id, category_id) in links {
+        by_product.entry(product_id).or_default().push(category_id);
+    }
+    for row in rows.iter_mut() {
+        let ids = row["id"]
+            .as_i64()
+            .and_then(|id| by_product.remove(&id))
+            .unwrap_or_default();
+        row["category_ids"] = Value::from(ids);
+    }
+    Ok(())
+}
+
+/// Serialize a row, expanding the stored metadata text into JSON so it
+/// round-trips as an object. Metadata that doesn't parse is kept as text.
+fn row_to_json<T: Serialize>(row: &T) -> DomainResult<Value> {
+    let mut value = serde_json::to_value(row).map_err(|e| Error::Internal(e.to_string()))?;
+    if let Some(metadata) = value.get_mut("metadata")
+        && let Some(parsed) = metadata
+            .as_str()
+            .and_then(|m| serde_json::from_str::<Value>(m).ok())
+    {
+        *metadata = parsed;
+    }
+    Ok(value)
+}
+
+#[async_trait]
+impl ExportRepository for SqliteExportRepository {
+    async fn begin_export(&self, _: &Context) -> DomainResult<Box<dyn ExportReader>> {
+        let tx = self.pool.begin().await?;
+        Ok(Box::new(SqliteExportReader { tx }))
+    }
+
+    async fn import_snapshot(
+        &self,
+        _: &Context,
+        snapshot: &Snapshot,
+        mode: ImportMode,
+    ) -> DomainResult<()> {
+        let mut rows = Vec::with_capacity(IMPORT_TABLES.len());
+        for (table, _, _) in IMPORT_TABLES {
+            let table_rows = import_rows(table, snapshot.rows(table))?;
+            if table == ExportTable::Products {
+                validate_product_types(&table_rows)?;
+            }
+            rows.push(table_rows);
+        }
+
+        // Take the write lock up front, as the transaction manager does, so a
+        // concurrent writer is waited out here instead of failing mid-import
+        let mut tx =
+            retry_on_busy(&self.busy_retry, || self.pool.begin_with("BEGIN IMMEDIATE")).await?;
+        // References are checked below; deferring lets rows arrive in any order
+        sqlx::query("PRAGMA defer_foreign_keys = ON")
+            .execute(&mut *tx)
+            .await?;
+
+        let dangling = dangling_references(&mut tx, snapshot).await?;
+        if !dangling.is_empty() {
+            return Err(Error::ValidationError(format!(
+                "Snapshot has dangling references: {}",
+                dangling.join(", ")
+            )));
+        }
+
+        for ((table, sql_table, columns), rows) in IMPORT_TABLES.iter().zip(&rows) {
+            for (id, row) in rows {
+                insert_row(&mut tx, sql_table, columns, row, mode)
+                    .await
+                    .map_err(|e| import_conflict(e, *table, *id))?;
+            }
+        }
+        for (id, row) in &rows[IMPORT_TABLES
+            .iter()
+            .position(|(table, _, _)| *table == ExportTable::Products)
+            .unwrap_or_default()]
+        {
+            replace_category_links(&mut tx, *id, &category_ids(row)).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+/// Check each row of a table is an object with an integer id
+fn import_rows(
+    table: ExportTable,
+    rows: &[Value],
+) -> DomainResult<Vec<(i64, &Map<String, Value>)>> {
+    rows.iter()
+        .enumerate()
+        .map(|(index, row)| {
+            row.as_object()
+                .and_then(|object| object.get("id")?.as_i64().map(|id| (id, object)))
+                .ok_or_else(|| {
+                    Error::ValidationError(format!(
+                        "Row {} of {} must be an object with an integer id",
+                        index,
+                        table.key()
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// Reject a product whose `product_type` is not one the app can read back
+fn validate_product_types(rows: &[(i64, &Map<String, Value>)]) -> DomainResult<()> {
+    for (id, row) in rows {
+        if let Some(product_type) = row.get("product_type") {
+            product_type
+                .as_str()
+                .and_then(|t| t.parse::<ProductType>().ok())
+                .ok_or_else(|| {
+                    Error::ValidationError(format!(
+                        "Product {} has unknown product_type {}",
+                        id, product_type
+                    ))
+                })?;
+        }
+    }
+    Ok(())
+}
+
+fn category_ids(row: &Map<String, Value>) -> Vec<i64> {
+    row.get("category_ids")
+        .and_then(Value::as_array)
+        .map(|ids| ids.iter().filter_map(Value::as_i64).collect())
+        .unwrap_or_default()
+}
+
+fn ids_of(rows: &[Value]) -> HashSet<i64> {
+    rows.iter().filter_map(|row| row["id"].as_i64()).collect()
+}
+
+/// `(row, referenced id)` pairs whose target is neither in `known` nor stored
+async fn missing_targets(
+    conn: &mut SqliteConnection,
+    sql_table: &str,
+    known: &HashSet<i64>,
+    references: Vec<(i64, i64)>,
+) -> DomainResult<Vec<(i64, i64)>> {
+    let unknown: Vec<(i64, i64)> = references
+        .into_iter()
+        .filter(|(_, target)| !known.contains(target))
+        .collect();
+    if unknown.is_empty() {
+        return Ok(unknown);
+    }
+
+    let targets: Vec<i64> = unknown
+        .iter()
+        .map(|(_, target)| *target)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let mut stored = HashSet::new();
+    for chunk in targets.chunks(LOOKUP_CHUNK_SIZE) {
+        let mut builder: QueryBuilder<Sqlite> =
+            QueryBuilder::new(format!("SELECT id FROM {} WHERE id IN (", sql_table));
+        let mut separated = builder.separated(", ");
+        for target in chunk {
+            separated.push_bind(*target);
+        }
+        builder.push(")");
+        let ids: Vec<i64> = builder.build_query_scalar().fetch_all(&mut *conn).await?;
+        stored.extend(ids);
+    }
+
+    Ok(unknown
+        .into_iter()
+        .filter(|(_, target)| !stored.contains(target))
+        .collect())
+}
+
+/// Describe every category parent, product category and variant product the
+/// snapshot points at but that would not exist after the import
+async fn dangling_references(
+    conn: &mut SqliteConnection,
+    snapshot: &Snapshot,
+) -> DomainResult<Vec<String>> {
+    let categories = ids_of(&snapshot.categories);
+    let products = ids_of(&snapshot.products);
+    let mut dangling = Vec::new();
+
+    let parents = snapshot
+        .categories
+        .iter()
+        .filter_map(|row| Some((row["id"].as_i64()?, row["parent_id"].as_i64()?)))
+        .collect();
+    for (id, parent) in missing_targets(conn, "categories", &categories, parents).await? {
+        dangling.push(format!("category {} -> parent category {}", id, parent));
+    }
+
+    let links = snapshot
+        .products
+        .iter()
+        .filter_map(|row| Some((row["id"].as_i64()?, row.as_object()?)))
+        .flat_map(|(id, row)| category_ids(row).into_iter().map(move |c| (id, c)))
+        .collect();
+    for (id, category) in missing_targets(conn, "categories", &categories, links).await? {
+        dangling.push(format!("product {} -> category {}", id, category));
+    }
+
+    let owners = snapshot
+        .variants
+        .iter()
+        .filter_map(|row| Some((row["id"].as_i64()?, row["product_id"].as_i64()?)))
+        .collect();
+    for (id, product) in missing_targets(conn, "products", &products, owners).await? {
+        dangling.push(format!("variant {} -> product {}", id, product));
+    }
+
+    Ok(dangling)
+}
+
+/// Insert the whitelisted columns present in `row`. Columns the row leaves
+/// out keep their defaults, or their current value when upserting.
+async fn insert_row(
+    conn: &mut SqliteConnection,
+    sql_table: &str,
+    columns: &[&str],
+    row: &Map<String, Value>,
+    mode: ImportMode,
+) -> Result<(), sqlx::Error> {
+    let present: Vec<(&str, &Value)> = columns
+        .iter()
+        .filter_map(|column| row.get(*column).map(|value| (*column, value)))
+        .collect();
+
+    let mut builder: QueryBuilder<Sqlite> =
+        QueryBuilder::new(format!("INSERT INTO {} (", sql_table));
+    let mut separated = builder.separated(", ");
+    for (column, _) in &present {
+        separated.push(*column);
+    }
+    builder.push(") VALUES (");
+    let mut separated = builder.separated(", ");
+    for (_, value) in &present {
+        match value {
+            Value::Null => separated.push_bind(None::<String>),
+            Value::Bool(b) => separated.push_bind(*b),
+            Value::Number(n) => match n.as_i64() {
+                Some(i) => separated.push_bind(i),
+                None => separated.push_bind(n.as_f64()),
+            },
+            Value::String(s) => separated.push_bind(s.clone()),
+            // Metadata is stored as JSON text
+            other => separated.push_bind(other.to_string()),
+        };
+    }
+    builder.push(")");
+
+    if mode == ImportMode::Upsert {
+        let updated: Vec<&str> = present
+            .iter()
+            .map(|(column, _)| *column)
+            .filter(|column| *column != "id")
+            .collect();
+        // A row with nothing but its id has nothing to overwrite. SQLite checks
+        // NOT NULL before the conflict clause, so an existing row is skipped
+        // outright rather than left to DO NOTHING.
+        if updated.is_empty() {
+            let id = row.get("id").and_then(Value::as_i64);
+            let exists: Option<i64> =
+                sqlx::query_scalar(&format!("SELECT 1 FROM {} WHERE id = ?", sql_table))
+                    .bind(id)
+                    .fetch_optional(&mut *conn)
+                    .await?;
+            if exists.is_some() {
+                return Ok(());
+            }
+            builder.push(" ON CONFLICT (id) DO NOTHING");
+        } else {
+            builder.push(" ON CONFLICT (id) DO UPDATE SET ");
+            let mut separated = builder.separated(", ");
+            for column in updated {
+                separated.push(format!("{0} = excluded.{0}", column));
+            }
+        }
+    }
+
+    builder.build().execute(&mut *conn).await?;
+    Ok(())
+}
+
+async fn replace_category_links(
+    conn: &mut SqliteConnection,
+    product_id: i64,
+    category_ids: &[i64],
+) -> DomainResult<()> {
+    sqlx::query("DELETE FROM product_categories WHERE product_id = ?")
+        .bind(product_id)
+        .execute(&mut *conn)
+        .await?;
+    if category_ids.is_empty() {
+        return Ok(());
+    }
+    let mut builder: QueryBuilder<Sqlite> =
+        QueryBuilder::new("INSERT INTO product_categories (product_id, category_id) ");
+    builder.push_values(category_ids, |mut b, category_id| {
+        b.push_bind(product_id).push_bind(category_id);
+    });
+    builder.build().execute(&mut *conn).await?;
+    Ok(())
+}
+
+fn import_conflict(err: sqlx::Error, table: ExportTable, id: i64) -> Error {
+    match &err {
+        sqlx::Error::Database(e) if e.is_unique_violation() => Error::Conflict(format!(
+            "Cannot import {} row {}: {}",
+            table.key(),
+            id,
+            e.message()
+        )),
+        _ => err.into(),
+    }
+}
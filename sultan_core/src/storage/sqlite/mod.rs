@@ -1,19 +1,31 @@
+pub mod audit;
 pub mod branch;
 pub mod category;
 pub mod customer;
+pub mod device;
+pub mod export;
 pub mod product;
+pub mod purchase_order;
+pub mod sale;
 pub mod sell_price;
+pub mod stock;
 pub mod supplier;
 pub mod token;
 pub mod transaction;
 pub mod unit;
 pub mod user;
 
+pub use audit::SqliteAuditSink;
 pub use branch::SqliteBranchRepository;
 pub use category::SqliteCategoryRepository;
 pub use customer::SqliteCustomerRepository;
+pub use device::SqliteDeviceRepository;
+pub use export::SqliteExportRepository;
 pub use product::SqliteProductRepository;
+pub use purchase_order::SqlitePurchaseOrderRepository;
+pub use sale::SqliteSaleRepository;
 pub use sell_price::SqliteSellPriceRepository;
+pub use stock::SqliteStockRepository;
 pub use supplier::SqliteSupplierRepository;
 pub use token::SqliteTokenRepository;
 pub use unit::SqliteUnitOfMeasureRepository;
@@ -21,20 +33,65 @@ pub use user::SqliteUserRepository;
 
 use chrono::{DateTime, NaiveDateTime, Utc};
 use serde_json::Value;
-use sqlx::{Executor, QueryBuilder, Sqlite};
+use sqlx::{Executor, QueryBuilder, Sqlite, SqlitePool};
 
-use crate::domain::{DomainResult, Error};
+use transaction::{BusyRetry, retry_on_busy};
 
-pub fn parse_sqlite_date(date_str: &str) -> DateTime<Utc> {
-    NaiveDateTime::parse_from_str(date_str, "%Y-%m-%dT%H:%M:%fZ")
-        .unwrap_or_default()
-        .and_utc()
+use crate::domain::{
+    DomainResult, Error,
+    model::filter::{FilterExpr, FilterField, FilterOp, FilterValue},
+};
+
+/// Timestamp formats stored by SQLite: ours (`strftime('%Y-%m-%dT%H:%M:%fZ')`,
+/// where SQLite's `%f` is `SS.SSS`) and the one produced by `CURRENT_TIMESTAMP`
+/// / `datetime('now')`.
+const SQLITE_DATE_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.fZ", "%Y-%m-%d %H:%M:%S%.f"];
+
+/// Parse a timestamp read from SQLite, failing instead of guessing on malformed input.
+pub fn parse_sqlite_date(date_str: &str) -> DomainResult<DateTime<Utc>> {
+    SQLITE_DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(date_str, format).ok())
+        .map(|date| date.and_utc())
+        .ok_or_else(|| Error::Database(format!("Invalid date in database: '{}'", date_str)))
+}
+
+/// Format a timestamp the same way our `strftime` defaults store it
+pub fn format_sqlite_date(date: &DateTime<Utc>) -> String {
+    date.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+/// Parse a nullable timestamp column such as `deleted_at`
+pub fn parse_optional_sqlite_date(date_str: Option<&str>) -> DomainResult<Option<DateTime<Utc>>> {
+    date_str.map(parse_sqlite_date).transpose()
+}
+
+/// Escape the LIKE wildcards (`%`, `_`) and the escape character itself so
+/// the value is matched literally. Use together with `ESCAPE '\'`.
+pub fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
 }
 
 /// Extension trait for QueryBuilder to add common filter patterns
 pub trait QueryBuilderExt {
-    /// Add a LIKE filter clause if the value is Some
+    /// Add a LIKE filter clause if the value is Some.
+    /// The value is matched literally as a substring.
     fn push_like_filter(&mut self, column: &str, value: &Option<String>) -> &mut Self;
+
+    /// Add a LIKE filter that matches if any of the columns contains the value.
+    /// The alternatives are parenthesized so they AND with the other filters.
+    fn push_like_any_filter(&mut self, columns: &[&str], value: &Option<String>) -> &mut Self;
+
+    /// Like [`push_like_any_filter`](Self::push_like_any_filter), but each
+    /// column is matched against its own value.
+    fn push_like_any_of(&mut self, terms: &[(&str, &str)]) -> &mut Self;
 }
 
 impl QueryBuilderExt for QueryBuilder<'_, Sqlite> {
@@ -43,10 +100,97 @@ impl QueryBuilderExt for QueryBuilder<'_, Sqlite> {
             self.push(" AND ");
             self.push(column);
             self.push(" LIKE ");
-            self.push_bind(format!("%{}%", v));
+            self.push_bind(format!("%{}%", escape_like(v)));
+            self.push(" ESCAPE '\\'");
+        }
+        self
+    }
+
+    fn push_like_any_filter(&mut self, columns: &[&str], value: &Option<String>) -> &mut Self {
+        if let Some(v) = value {
+            let terms: Vec<(&str, &str)> =
+                columns.iter().map(|column| (*column, v.as_str())).collect();
+            self.push_like_any_of(&terms);
         }
         self
     }
+
+    fn push_like_any_of(&mut self, terms: &[(&str, &str)]) -> &mut Self {
+        if terms.is_empty() {
+            return self;
+        }
+        self.push(" AND (");
+        for (i, (column, value)) in terms.iter().enumerate() {
+            if i > 0 {
+                self.push(" OR ");
+            }
+            self.push(column);
+            self.push(" LIKE ");
+            self.push_bind(format!("%{}%", escape_like(value)));
+            self.push(" ESCAPE '\\'");
+        }
+        self.push(")");
+        self
+    }
+}
+
+/// Append filter DSL expressions as ` AND ...` clauses. Each is checked
+/// against `fields` first; only the whitelisted column name is written into
+/// the SQL and every value is bound.
+pub fn push_filter_exprs(
+    builder: &mut QueryBuilder<'_, Sqlite>,
+    exprs: &[FilterExpr],
+    fields: &[FilterField],
+) -> DomainResult<()> {
+    // Validate everything before touching the builder
+    let resolved = exprs
+        .iter()
+        .map(|expr| Ok((expr.op, expr.resolve(fields)?)))
+        .collect::<DomainResult<Vec<_>>>()?;
+
+    for (op, (field, value)) in resolved {
+        builder.push(" AND ");
+        builder.push(field.name);
+        match (op, value) {
+            (FilterOp::In, FilterValue::List(values)) => {
+                builder.push(" IN (");
+                let mut separated = builder.separated(", ");
+                for value in values {
+                    push_filter_value(&mut separated, value);
+                }
+                builder.push(")");
+            }
+            (FilterOp::Like, FilterValue::Text(text)) => {
+                builder.push(" LIKE ");
+                builder.push_bind(format!("%{}%", escape_like(&text)));
+                builder.push(" ESCAPE '\\'");
+            }
+            (op, value) => {
+                builder.push(match op {
+                    FilterOp::Gt => " > ",
+                    FilterOp::Lt => " < ",
+                    _ => " = ",
+                });
+                match value {
+                    FilterValue::Text(text) => builder.push_bind(text),
+                    FilterValue::Integer(int) => builder.push_bind(int),
+                    FilterValue::List(_) => unreachable!("resolve only allows lists with 'in'"),
+                };
+            }
+        }
+    }
+    Ok(())
+}
+
+fn push_filter_value<'q, Sep: std::fmt::Display>(
+    separated: &mut sqlx::query_builder::Separated<'_, 'q, Sqlite, Sep>,
+    value: FilterValue,
+) {
+    match value {
+        FilterValue::Text(text) => separated.push_bind(text),
+        FilterValue::Integer(int) => separated.push_bind(int),
+        FilterValue::List(_) => unreachable!("resolve flattens 'in' values"),
+    };
 }
 
 /// Check if a query affected rows, return error if not
@@ -66,7 +210,7 @@ pub fn check_rows_affected(
 
 /// Enum representing valid table names in the database.
 /// This prevents SQL injection by ensuring only whitelisted tables can be used.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TableName {
     Branches,
     Categories,
@@ -101,6 +245,39 @@ impl TableName {
             TableName::SellDiscounts => "sell_discounts",
         }
     }
+
+    /// Every whitelisted table. Extend this together with `as_str` when a
+    /// variant is added; `from_str` only accepts names listed here.
+    pub fn all() -> &'static [TableName] {
+        &[
+            TableName::Branches,
+            TableName::Categories,
+            TableName::Customers,
+            TableName::Suppliers,
+            TableName::Users,
+            TableName::Tokens,
+            TableName::Permissions,
+            TableName::Units,
+            TableName::Products,
+            TableName::ProductVariants,
+            TableName::SellPrices,
+            TableName::SellDiscounts,
+        ]
+    }
+}
+
+impl std::str::FromStr for TableName {
+    type Err = Error;
+
+    /// Resolves a table name from untrusted input, rejecting anything that
+    /// is not whitelisted.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        TableName::all()
+            .iter()
+            .copied()
+            .find(|table| table.as_str() == name)
+            .ok_or_else(|| Error::ValidationError(format!("Unknown table: {}", name)))
+    }
 }
 
 /// Execute a soft delete query.
@@ -120,7 +297,8 @@ where
         r#"
         UPDATE {} SET
             is_deleted = 1,
-            deleted_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+            deleted_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+            updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
         WHERE id = ? AND is_deleted = 0
         "#,
         table.as_str()
@@ -128,12 +306,55 @@ where
     sqlx::query(&sql).bind(id).execute(executor).await
 }
 
+/// Like [`soft_delete`], also recording who deleted the row in `deleted_by`.
+/// `actor_id` comes from the request context and is None for system writes.
+pub async fn soft_delete_by<'a, E>(
+    executor: E,
+    table: TableName,
+    id: i64,
+    actor_id: Option<i64>,
+) -> Result<sqlx::sqlite::SqliteQueryResult, sqlx::Error>
+where
+    E: Executor<'a, Database = Sqlite>,
+{
+    let sql = format!(
+        r#"
+        UPDATE {} SET
+            is_deleted = 1,
+            deleted_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+            updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+            deleted_by = ?
+        WHERE id = ? AND is_deleted = 0
+        "#,
+        table.as_str()
+    );
+    sqlx::query(&sql)
+        .bind(actor_id)
+        .bind(id)
+        .execute(executor)
+        .await
+}
+
+/// [`soft_delete_by`] as a single statement on the pool, retried with the
+/// default [`BusyRetry`] while another writer holds the database.
+pub async fn soft_delete_by_on_pool(
+    pool: &SqlitePool,
+    table: TableName,
+    id: i64,
+    actor_id: Option<i64>,
+) -> Result<sqlx::sqlite::SqliteQueryResult, sqlx::Error> {
+    retry_on_busy(&BusyRetry::default(), || {
+        soft_delete_by(pool, table, id, actor_id)
+    })
+    .await
+}
+
 /// Helper to map query results to domain models
-pub fn map_results<DbModel, DomainModel>(results: Vec<DbModel>) -> Vec<DomainModel>
+pub fn map_results<DbModel, DomainModel>(results: Vec<DbModel>) -> DomainResult<Vec<DomainModel>>
 where
-    DbModel: Into<DomainModel>,
+    DbModel: TryInto<DomainModel, Error = Error>,
 {
-    results.into_iter().map(|x| x.into()).collect()
+    results.into_iter().map(|x| x.try_into()).collect()
 }
 
 /// Helper to convert Update<Value> metadata to Option<String> for database binding
@@ -141,8 +362,9 @@ pub fn serialize_metadata_update(
     metadata: &crate::domain::model::update::Update<Value>,
 ) -> Option<String> {
     metadata
-        .to_bind_value()
-        .map(|m: Value| serde_json::to_string(&m).unwrap_or_default())
+        .as_ref()
+        .map(|m| serde_json::to_string(m).unwrap_or_default())
+        .into_bind_value()
 }
 
 /// Serialize metadata JSON to string for database storage
@@ -151,3 +373,82 @@ pub fn serialize_metadata(metadata: &Option<serde_json::Value>) -> Option<String
         .as_ref()
         .map(|m| serde_json::to_string(m).unwrap_or_default())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, Timelike};
+
+    #[test]
+    fn test_parse_sqlite_date_strftime_format() {
+        let date = parse_sqlite_date("2025-12-01T08:30:15.123Z").unwrap();
+        assert_eq!((date.year(), date.month(), date.day()), (2025, 12, 1));
+        assert_eq!((date.hour(), date.minute(), date.second()), (8, 30, 15));
+        assert_eq!(date.timestamp_subsec_millis(), 123);
+    }
+
+    #[test]
+    fn test_parse_sqlite_date_current_timestamp_format() {
+        let date = parse_sqlite_date("2025-12-01 08:30:15").unwrap();
+        assert_eq!((date.year(), date.month(), date.day()), (2025, 12, 1));
+        assert_eq!((date.hour(), date.minute(), date.second()), (8, 30, 15));
+    }
+
+    #[test]
+    fn test_parse_sqlite_date_garbage_is_error() {
+        for input in ["", "not a date", "2025-13-45T99:99:99Z", "2025-12-01"] {
+            let result = parse_sqlite_date(input);
+            assert!(
+                matches!(result, Err(Error::Database(_))),
+                "'{}' should fail to parse",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_table_name_round_trips() {
+        for table in TableName::all() {
+            assert_eq!(table.as_str().parse::<TableName>().unwrap(), *table);
+        }
+
+        let names: std::collections::HashSet<_> =
+            TableName::all().iter().map(TableName::as_str).collect();
+        assert_eq!(names.len(), TableName::all().len());
+    }
+
+    #[test]
+    fn test_table_name_from_str_valid() {
+        assert_eq!(
+            "product_variants".parse::<TableName>().unwrap(),
+            TableName::ProductVariants
+        );
+    }
+
+    #[test]
+    fn test_table_name_from_str_rejects_unknown() {
+        for name in [
+            "sqlite_master",
+            "Customers",
+            "customers; DROP TABLE users",
+            "",
+        ] {
+            assert!(
+                matches!(name.parse::<TableName>(), Err(Error::ValidationError(_))),
+                "'{}' should be rejected",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_optional_sqlite_date() {
+        assert!(parse_optional_sqlite_date(None).unwrap().is_none());
+        assert!(
+            parse_optional_sqlite_date(Some("2025-12-01 08:30:15"))
+                .unwrap()
+                .is_some()
+        );
+        assert!(parse_optional_sqlite_date(Some("garbage")).is_err());
+    }
+}
@@ -1,17 +1,30 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use serde::Serialize;
-use sqlx::{QueryBuilder, Sqlite, SqlitePool, Transaction};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
 
-use super::{TableName, check_rows_affected, serialize_metadata, serialize_metadata_update};
+use super::{
+    QueryBuilderExt, TableName, check_rows_affected, serialize_metadata, serialize_metadata_update,
+};
 use crate::{
     domain::{
-        Context, DomainResult,
-        model::product::{
-            Product, ProductCreate, ProductUpdate, ProductVariant, ProductVariantCreate,
-            ProductVariantUpdate,
+        Context, DomainResult, Error,
+        model::{
+            money::Money,
+            pagination::PaginationOptions,
+            product::{
+                Product, ProductComponent, ProductCreate, ProductFilter, ProductSupplier,
+                ProductType, ProductUpdate, ProductVariant, ProductVariantCreate,
+                ProductVariantFilter, ProductVariantUpdate, ProductVariantUpsert,
+                ProductWithVariants, VariantPriceChange,
+            },
         },
     },
-    storage::{ProductRepository, sqlite::soft_delete},
+    storage::{
+        ProductRepository,
+        sqlite::{soft_delete_by, transaction::TxGuard},
+    },
 };
 
 /// SQLite implementation of the ProductRepository.
@@ -65,13 +78,50 @@ impl SqliteProductRepository {
         let sql = format!("{} WHERE id = ? AND is_deleted = 0", PRODUCT_SELECT_COLUMNS);
         let query = sqlx::query_as::<_, ProductDbSqlite>(&sql).bind(id);
         let product = query.fetch_optional(&self.pool).await?;
-        Ok(product.map(Product::from))
+        product.map(Product::try_from).transpose()
+    }
+
+    /// Pair variants with their live products in one query, dropping variants
+    /// whose product is deleted. The order of `variants_db` is kept.
+    async fn attach_products(
+        &self,
+        variants_db: Vec<ProductVariantDbSqlite>,
+    ) -> DomainResult<Vec<ProductVariant>> {
+        if variants_db.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(PRODUCT_SELECT_COLUMNS);
+        builder.push(" WHERE is_deleted = 0 AND id IN (");
+        let mut separated = builder.separated(", ");
+        for variant in &variants_db {
+            separated.push_bind(variant.product_id);
+        }
+        separated.push_unseparated(")");
+
+        let mut products = HashMap::new();
+        for product in builder
+            .build_query_as::<ProductDbSqlite>()
+            .fetch_all(&self.pool)
+            .await?
+        {
+            let product = Product::try_from(product)?;
+            products.insert(product.id, product);
+        }
+
+        variants_db
+            .into_iter()
+            .filter_map(|v| {
+                let product = products.get(&v.product_id)?.clone();
+                Some(v.into_variant(product))
+            })
+            .collect()
     }
 }
 
 // Database model for Product - SQLite
 #[derive(sqlx::FromRow, Debug, Serialize)]
-struct ProductDbSqlite {
+pub(super) struct ProductDbSqlite {
     pub id: i64,
     pub created_at: String,
     pub updated_at: String,
@@ -86,32 +136,47 @@ struct ProductDbSqlite {
     pub editable_price: bool,
     pub has_variant: bool,
     pub metadata: Option<String>,
+    pub created_by: Option<i64>,
+    pub updated_by: Option<i64>,
+}
+
+fn parse_product_type(product_type: &str) -> DomainResult<ProductType> {
+    product_type.parse().map_err(|_| {
+        Error::Database(format!(
+            "Invalid product type in database: '{}'",
+            product_type
+        ))
+    })
 }
 
-impl From<ProductDbSqlite> for Product {
-    fn from(db: ProductDbSqlite) -> Self {
-        Product {
+impl TryFrom<ProductDbSqlite> for Product {
+    type Error = Error;
+
+    fn try_from(db: ProductDbSqlite) -> Result<Self, Self::Error> {
+        Ok(Product {
             id: db.id,
-            created_at: super::parse_sqlite_date(&db.created_at),
-            updated_at: super::parse_sqlite_date(&db.updated_at),
-            deleted_at: db.deleted_at.map(|d| super::parse_sqlite_date(&d)),
+            created_at: super::parse_sqlite_date(&db.created_at)?,
+            updated_at: super::parse_sqlite_date(&db.updated_at)?,
+            deleted_at: super::parse_optional_sqlite_date(db.deleted_at.as_deref())?,
             is_deleted: db.is_deleted,
             name: db.name,
             description: db.description,
-            product_type: db.product_type,
+            product_type: parse_product_type(&db.product_type)?,
             main_image: db.main_image,
             sellable: db.sellable,
             buyable: db.buyable,
             editable_price: db.editable_price,
             has_variant: db.has_variant,
             metadata: db.metadata.and_then(|m| serde_json::from_str(&m).ok()),
-        }
+            created_by: db.created_by,
+            updated_by: db.updated_by,
+        })
     }
 }
 
 // Database model for ProductVariant - SQLite
 #[derive(sqlx::FromRow, Debug, Serialize)]
-struct ProductVariantDbSqlite {
+pub(super) struct ProductVariantDbSqlite {
     pub id: i64,
     pub created_at: String,
     pub updated_at: String,
@@ -120,48 +185,149 @@ struct ProductVariantDbSqlite {
     pub product_id: i64,
     pub barcode: Option<String>,
     pub name: Option<String>,
+    pub price: Option<i64>,
     pub metadata: Option<String>,
 }
 
 impl ProductVariantDbSqlite {
     /// Converts the database model to a domain ProductVariant with the given Product.
-    fn into_variant(self, product: Product) -> ProductVariant {
-        ProductVariant {
+    fn into_variant(self, product: Product) -> DomainResult<ProductVariant> {
+        Ok(ProductVariant {
             id: self.id,
-            created_at: super::parse_sqlite_date(&self.created_at),
-            updated_at: super::parse_sqlite_date(&self.updated_at),
-            deleted_at: self.deleted_at.map(|d| super::parse_sqlite_date(&d)),
+            created_at: super::parse_sqlite_date(&self.created_at)?,
+            updated_at: super::parse_sqlite_date(&self.updated_at)?,
+            deleted_at: super::parse_optional_sqlite_date(self.deleted_at.as_deref())?,
             is_deleted: self.is_deleted,
             product,
             barcode: self.barcode,
             name: self.name,
+            price: self.price.map(Money::from_minor),
             metadata: self.metadata.and_then(|m| serde_json::from_str(&m).ok()),
-        }
+        })
+    }
+}
+
+/// idx_product_variants_barcode only allows one live variant per barcode
+fn barcode_conflict(err: sqlx::Error, barcode: Option<&str>) -> Error {
+    match &err {
+        sqlx::Error::Database(e) if e.is_unique_violation() => Error::Conflict(format!(
+            "Barcode {} is already used by another variant",
+            barcode.unwrap_or_default()
+        )),
+        _ => err.into(),
+    }
+}
+
+/// Mark a product as changed by the current actor, failing with NotFound if it
+/// does not exist or is deleted.
+async fn touch_product(ctx: &Context, product_id: i64, tx: &mut TxGuard<'_>) -> DomainResult<()> {
+    let result = sqlx::query(
+        r#"
+        UPDATE products SET
+            updated_by = ?,
+            updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+        WHERE id = ? AND is_deleted = 0
+        "#,
+    )
+    .bind(ctx.actor_id())
+    .bind(product_id)
+    .execute(&mut **tx)
+    .await?;
+
+    check_rows_affected(result.rows_affected(), "Product", product_id)
+}
+
+/// Soft-delete every live variant of a product. A product without variants is
+/// not an error.
+async fn soft_delete_variants_of_product(
+    product_id: i64,
+    actor_id: Option<i64>,
+    tx: &mut TxGuard<'_>,
+) -> DomainResult<()> {
+    sqlx::query(
+        r#"
+        UPDATE product_variants SET
+            is_deleted = 1,
+            deleted_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+            updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+            deleted_by = ?
+        WHERE product_id = ? AND is_deleted = 0
+        "#,
+    )
+    .bind(actor_id)
+    .bind(product_id)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// Append a row to the variant's price history
+async fn record_price_change(
+    conn: &mut sqlx::SqliteConnection,
+    variant_id: i64,
+    price: Option<Money>,
+    changed_by: Option<i64>,
+) -> DomainResult<()> {
+    sqlx::query(
+        "INSERT INTO variant_price_history (variant_id, price, changed_by) VALUES (?, ?, ?)",
+    )
+    .bind(variant_id)
+    .bind(price.map(|p| p.minor_units()))
+    .bind(changed_by)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+fn category_filter(category_id: i64, sellable_only: bool) -> ProductFilter {
+    ProductFilter {
+        category_id: Some(category_id),
+        sellable: sellable_only.then_some(true),
+        ..Default::default()
+    }
+}
+
+fn push_product_filter(builder: &mut QueryBuilder<'_, Sqlite>, filter: &ProductFilter) {
+    builder.push_like_filter("name", &filter.name);
+
+    if let Some(product_type) = &filter.product_type {
+        builder.push(" AND product_type = ");
+        builder.push_bind(product_type.as_str());
+    }
+    if let Some(sellable) = filter.sellable {
+        builder.push(" AND sellable = ");
+        builder.push_bind(sellable);
+    }
+    if let Some(category_id) = filter.category_id {
+        builder.push(" AND id IN (SELECT product_id FROM product_categories WHERE category_id = ");
+        builder.push_bind(category_id);
+        builder.push(")");
     }
 }
 
 // SQL query constants to reduce duplication
-const PRODUCT_SELECT_COLUMNS: &str = r#"
+pub(super) const PRODUCT_SELECT_COLUMNS: &str = r#"
     SELECT id, created_at, updated_at, deleted_at, is_deleted,
            name, description, product_type, main_image,
-           sellable, buyable, editable_price, has_variant, metadata
+           sellable, buyable, editable_price, has_variant, metadata,
+           created_by, updated_by
     FROM products
 "#;
 
-const VARIANT_SELECT_COLUMNS: &str = r#"
+pub(super) const VARIANT_SELECT_COLUMNS: &str = r#"
     SELECT id, created_at, updated_at, deleted_at, is_deleted,
-           product_id, barcode, name, metadata
+           product_id, barcode, name, price, metadata
     FROM product_variants
 "#;
 
 #[async_trait]
-impl<'a> ProductRepository<Transaction<'a, Sqlite>> for SqliteProductRepository {
+impl<'a> ProductRepository<TxGuard<'a>> for SqliteProductRepository {
     async fn create_product(
         &self,
-        _: &Context,
+        ctx: &Context,
         id: i64,
         product: &ProductCreate,
-        tx: &mut Transaction<'a, Sqlite>,
+        tx: &mut TxGuard<'a>,
     ) -> DomainResult<()> {
         let metadata_json = serialize_metadata(&product.metadata);
 
@@ -169,20 +335,23 @@ impl<'a> ProductRepository<Transaction<'a, Sqlite>> for SqliteProductRepository
             r#"
             INSERT INTO products (
                 id, name, description, product_type, main_image,
-                sellable, buyable, editable_price, has_variant, metadata
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                sellable, buyable, editable_price, has_variant, metadata,
+                created_by, updated_by
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id)
         .bind(&product.name)
         .bind(&product.description)
-        .bind(&product.product_type)
+        .bind(product.product_type.as_str())
         .bind(&product.main_image)
         .bind(product.sellable)
         .bind(product.buyable)
         .bind(product.editable_price)
         .bind(product.has_variant)
-        .bind(&metadata_json);
+        .bind(&metadata_json)
+        .bind(ctx.actor_id())
+        .bind(ctx.actor_id());
 
         query.execute(&mut **tx).await?;
 
@@ -205,10 +374,10 @@ impl<'a> ProductRepository<Transaction<'a, Sqlite>> for SqliteProductRepository
 
     async fn update_product(
         &self,
-        _: &Context,
+        ctx: &Context,
         id: i64,
         product: &ProductUpdate,
-        tx: &mut Transaction<'a, Sqlite>,
+        tx: &mut TxGuard<'a>,
     ) -> DomainResult<()> {
         let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new("UPDATE products SET ");
         let mut separated = builder.separated(", ");
@@ -224,7 +393,7 @@ impl<'a> ProductRepository<Transaction<'a, Sqlite>> for SqliteProductRepository
         if let Some(product_type) = &product.product_type {
             separated
                 .push("product_type = ")
-                .push_bind_unseparated(product_type);
+                .push_bind_unseparated(product_type.as_str());
         }
         if product.main_image.should_update() {
             separated
@@ -256,6 +425,9 @@ impl<'a> ProductRepository<Transaction<'a, Sqlite>> for SqliteProductRepository
                 .push_bind_unseparated(metadata_json);
         }
 
+        separated
+            .push("updated_by = ")
+            .push_bind_unseparated(ctx.actor_id());
         separated.push("updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')");
         builder.push(" WHERE id = ").push_bind(id);
         builder.push(" AND is_deleted = 0");
@@ -293,12 +465,23 @@ impl<'a> ProductRepository<Transaction<'a, Sqlite>> for SqliteProductRepository
 
     async fn delete_product(
         &self,
-        _: &Context,
+        ctx: &Context,
         id: i64,
-        tx: &mut Transaction<'a, Sqlite>,
+        tx: &mut TxGuard<'a>,
     ) -> DomainResult<()> {
-        let query = soft_delete(&mut **tx, TableName::Products, id);
+        let query = soft_delete_by(&mut **tx, TableName::Products, id, ctx.actor_id());
         let result = query.await?;
+        check_rows_affected(result.rows_affected(), "Product", id)?;
+        soft_delete_variants_of_product(id, ctx.actor_id(), tx).await
+    }
+
+    async fn touch(&self, _: &Context, id: i64, tx: &mut TxGuard<'a>) -> DomainResult<()> {
+        let result = sqlx::query(
+            "UPDATE products SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = ? AND is_deleted = 0",
+        )
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
         check_rows_affected(result.rows_affected(), "Product", id)
     }
 
@@ -307,41 +490,171 @@ impl<'a> ProductRepository<Transaction<'a, Sqlite>> for SqliteProductRepository
         let query = sqlx::query_as::<_, ProductDbSqlite>(&sql).bind(id);
 
         let product = query.fetch_optional(&self.pool).await?;
-        Ok(product.map(Product::from))
+        product.map(Product::try_from).transpose()
     }
 
-    async fn create_variant(
+    async fn get_by_category(
+        &self,
+        _: &Context,
+        category_id: i64,
+        sellable_only: bool,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<Product>> {
+        let mut builder: QueryBuilder<Sqlite> =
+            QueryBuilder::new(format!("{} WHERE is_deleted = 0", PRODUCT_SELECT_COLUMNS));
+        push_product_filter(&mut builder, &category_filter(category_id, sellable_only));
+        builder.push(" ORDER BY id DESC LIMIT ");
+        builder.push_bind(pagination.limit());
+        builder.push(" OFFSET ");
+        builder.push_bind(pagination.offset());
+
+        let products = builder
+            .build_query_as::<ProductDbSqlite>()
+            .fetch_all(&self.pool)
+            .await?;
+        products.into_iter().map(Product::try_from).collect()
+    }
+
+    async fn count_by_category(
+        &self,
+        ctx: &Context,
+        category_id: i64,
+        sellable_only: bool,
+    ) -> DomainResult<u64> {
+        self.count(ctx, &category_filter(category_id, sellable_only))
+            .await
+    }
+
+    async fn count(&self, _: &Context, filter: &ProductFilter) -> DomainResult<u64> {
+        let mut builder: QueryBuilder<Sqlite> =
+            QueryBuilder::new("SELECT COUNT(*) FROM products WHERE is_deleted = 0");
+        push_product_filter(&mut builder, filter);
+
+        let count: i64 = builder.build_query_scalar().fetch_one(&self.pool).await?;
+        Ok(count as u64)
+    }
+
+    async fn get_all_with_variants(
         &self,
         _: &Context,
+        filter: &ProductFilter,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<ProductWithVariants>> {
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(PRODUCT_SELECT_COLUMNS);
+        builder.push(" WHERE is_deleted = 0");
+        push_product_filter(&mut builder, filter);
+        builder.push(" ORDER BY id DESC LIMIT ");
+        builder.push_bind(pagination.limit());
+        builder.push(" OFFSET ");
+        builder.push_bind(pagination.offset());
+
+        let products = builder
+            .build_query_as::<ProductDbSqlite>()
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(Product::try_from)
+            .collect::<DomainResult<Vec<_>>>()?;
+        if products.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // One query for the variants of the whole page, grouped in memory
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(VARIANT_SELECT_COLUMNS);
+        builder.push(" WHERE is_deleted = 0 AND product_id IN (");
+        let mut separated = builder.separated(", ");
+        for product in &products {
+            separated.push_bind(product.id);
+        }
+        separated.push_unseparated(") ORDER BY id");
+
+        let mut variants_by_product: HashMap<i64, Vec<ProductVariantDbSqlite>> = HashMap::new();
+        for variant in builder
+            .build_query_as::<ProductVariantDbSqlite>()
+            .fetch_all(&self.pool)
+            .await?
+        {
+            variants_by_product
+                .entry(variant.product_id)
+                .or_default()
+                .push(variant);
+        }
+
+        products
+            .into_iter()
+            .map(|product| {
+                let variants = variants_by_product
+                    .remove(&product.id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|v| v.into_variant(product.clone()))
+                    .collect::<DomainResult<Vec<_>>>()?;
+                Ok(ProductWithVariants { product, variants })
+            })
+            .collect()
+    }
+
+    async fn create_variant(
+        &self,
+        ctx: &Context,
         id: i64,
         variant: &ProductVariantCreate,
-        tx: &mut Transaction<'a, Sqlite>,
+        tx: &mut TxGuard<'a>,
     ) -> DomainResult<()> {
         let metadata_json = serialize_metadata(&variant.metadata);
 
         let query = sqlx::query(
             r#"
             INSERT INTO product_variants (
-                id, product_id, barcode, name, metadata
-            ) VALUES (?, ?, ?, ?, ?)
+                id, product_id, barcode, name, price, metadata
+            ) VALUES (?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id)
         .bind(variant.product_id)
         .bind(&variant.barcode)
         .bind(&variant.name)
+        .bind(variant.price.map(|p| p.minor_units()))
         .bind(&metadata_json);
 
-        query.execute(&mut **tx).await?;
+        query
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| barcode_conflict(e, variant.barcode.as_deref()))?;
+
+        if let Some(price) = variant.price {
+            record_price_change(tx, id, Some(price), ctx.actor_id()).await?;
+        }
         Ok(())
     }
 
     async fn update_variant(
         &self,
-        _: &Context,
+        ctx: &Context,
         id: i64,
         variant: &ProductVariantUpdate,
+        tx: &mut TxGuard<'a>,
     ) -> DomainResult<()> {
+        // Only a price that actually differs from the stored one is recorded
+        let price_change = if variant.price.should_update() {
+            let new_price = variant
+                .price
+                .as_ref()
+                .map(|p| p.minor_units())
+                .into_bind_value();
+            let current: Option<(Option<i64>,)> = sqlx::query_as(
+                "SELECT price FROM product_variants WHERE id = ? AND is_deleted = 0",
+            )
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await?;
+            current
+                .filter(|(old,)| *old != new_price)
+                .map(|_| new_price.map(Money::from_minor))
+        } else {
+            None
+        };
+
         let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new("UPDATE product_variants SET ");
         let mut separated = builder.separated(", ");
 
@@ -355,6 +668,15 @@ impl<'a> ProductRepository<Transaction<'a, Sqlite>> for SqliteProductRepository
                 .push("name = ")
                 .push_bind_unseparated(variant.name.to_bind_value());
         }
+        if variant.price.should_update() {
+            separated.push("price = ").push_bind_unseparated(
+                variant
+                    .price
+                    .as_ref()
+                    .map(|p| p.minor_units())
+                    .into_bind_value(),
+            );
+        }
         if variant.metadata.should_update() {
             let metadata_json = serialize_metadata_update(&variant.metadata);
             separated
@@ -367,39 +689,132 @@ impl<'a> ProductRepository<Transaction<'a, Sqlite>> for SqliteProductRepository
         builder.push(" AND is_deleted = 0");
 
         let query = builder.build();
-        let result = query.execute(&self.pool).await?;
-        check_rows_affected(result.rows_affected(), "ProductVariant", id)
+        let result = query
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| barcode_conflict(e, variant.barcode.as_value().map(String::as_str)))?;
+        check_rows_affected(result.rows_affected(), "ProductVariant", id)?;
+
+        if let Some(price) = price_change {
+            record_price_change(tx, id, price, ctx.actor_id()).await?;
+        }
+        Ok(())
     }
 
-    async fn delete_variant(
+    async fn upsert_variant_by_barcode(
         &self,
         _: &Context,
         id: i64,
-        tx: &mut Transaction<'a, Sqlite>,
+        product_id: i64,
+        variant: &ProductVariantUpsert,
+        tx: &mut TxGuard<'a>,
+    ) -> DomainResult<(i64, bool)> {
+        let metadata_json = serialize_metadata(&variant.metadata);
+        let existing: Option<(i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT id, product_id FROM product_variants
+            WHERE barcode = ? AND is_deleted = 0
+            ORDER BY id DESC LIMIT 1
+            "#,
+        )
+        .bind(&variant.barcode)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        match existing {
+            Some((_, owner)) if owner != product_id => Err(Error::Conflict(format!(
+                "Barcode {} belongs to a variant of product {}",
+                variant.barcode, owner
+            ))),
+            Some((existing_id, _)) => {
+                sqlx::query(
+                    r#"
+                    UPDATE product_variants SET
+                        name = ?,
+                        metadata = ?,
+                        updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                    WHERE id = ?
+                    "#,
+                )
+                .bind(&variant.name)
+                .bind(&metadata_json)
+                .bind(existing_id)
+                .execute(&mut **tx)
+                .await?;
+                Ok((existing_id, false))
+            }
+            None => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO product_variants (id, product_id, barcode, name, metadata)
+                    VALUES (?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(id)
+                .bind(product_id)
+                .bind(&variant.barcode)
+                .bind(&variant.name)
+                .bind(&metadata_json)
+                .execute(&mut **tx)
+                .await
+                .map_err(|e| barcode_conflict(e, Some(&variant.barcode)))?;
+                Ok((id, true))
+            }
+        }
+    }
+
+    async fn delete_variant(
+        &self,
+        ctx: &Context,
+        id: i64,
+        tx: &mut TxGuard<'a>,
     ) -> DomainResult<()> {
-        let query = soft_delete(&mut **tx, TableName::ProductVariants, id);
+        let query = soft_delete_by(&mut **tx, TableName::ProductVariants, id, ctx.actor_id());
         let result = query.await?;
         check_rows_affected(result.rows_affected(), "ProductVariant", id)
     }
 
-    async fn delete_variants_by_product_id(
+    async fn reassign_variant(
         &self,
         _: &Context,
-        product_id: i64,
-        tx: &mut Transaction<'a, Sqlite>,
+        variant_id: i64,
+        new_product_id: i64,
+        tx: &mut TxGuard<'a>,
     ) -> DomainResult<()> {
-        let query = sqlx::query(
+        let target_exists: Option<i64> =
+            sqlx::query_scalar("SELECT id FROM products WHERE id = ? AND is_deleted = 0")
+                .bind(new_product_id)
+                .fetch_optional(&mut **tx)
+                .await?;
+        if target_exists.is_none() {
+            return Err(Error::NotFound(format!(
+                "Product with id {} not found",
+                new_product_id
+            )));
+        }
+
+        let result = sqlx::query(
             r#"
             UPDATE product_variants SET
-                is_deleted = 1,
-                deleted_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
-            WHERE product_id = ? AND is_deleted = 0
+                product_id = ?,
+                updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+            WHERE id = ? AND is_deleted = 0
             "#,
         )
-        .bind(product_id);
+        .bind(new_product_id)
+        .bind(variant_id)
+        .execute(&mut **tx)
+        .await?;
+        check_rows_affected(result.rows_affected(), "ProductVariant", variant_id)
+    }
 
-        query.execute(&mut **tx).await?;
-        Ok(())
+    async fn delete_variants_by_product_id(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+        tx: &mut TxGuard<'a>,
+    ) -> DomainResult<()> {
+        soft_delete_variants_of_product(product_id, ctx.actor_id(), tx).await
     }
 
     async fn get_variant_by_barcode(
@@ -408,7 +823,7 @@ impl<'a> ProductRepository<Transaction<'a, Sqlite>> for SqliteProductRepository
         barcode: &str,
     ) -> DomainResult<Option<ProductVariant>> {
         let variant_sql = format!(
-            "{} WHERE barcode = ? AND is_deleted = 0",
+            "{} WHERE barcode = ? AND is_deleted = 0 ORDER BY id DESC LIMIT 1",
             VARIANT_SELECT_COLUMNS
         );
         let variant_query = sqlx::query_as::<_, ProductVariantDbSqlite>(&variant_sql).bind(barcode);
@@ -418,12 +833,29 @@ impl<'a> ProductRepository<Transaction<'a, Sqlite>> for SqliteProductRepository
         match variant_db {
             Some(variant_db) => {
                 let product = self.fetch_product_by_id(variant_db.product_id).await?;
-                Ok(product.map(|p| variant_db.into_variant(p)))
+                product.map(|p| variant_db.into_variant(p)).transpose()
             }
             None => Ok(None),
         }
     }
 
+    async fn get_variants_by_barcode(
+        &self,
+        _: &Context,
+        barcode: &str,
+    ) -> DomainResult<Vec<ProductVariant>> {
+        let variant_sql = format!(
+            "{} WHERE barcode = ? AND is_deleted = 0 ORDER BY id DESC",
+            VARIANT_SELECT_COLUMNS
+        );
+        let variants_db = sqlx::query_as::<_, ProductVariantDbSqlite>(&variant_sql)
+            .bind(barcode)
+            .fetch_all(&self.pool)
+            .await?;
+        // Duplicates may belong to different products
+        self.attach_products(variants_db).await
+    }
+
     async fn get_variant_by_id(
         &self,
         _: &Context,
@@ -437,12 +869,35 @@ impl<'a> ProductRepository<Transaction<'a, Sqlite>> for SqliteProductRepository
         match variant_db {
             Some(variant_db) => {
                 let product = self.fetch_product_by_id(variant_db.product_id).await?;
-                Ok(product.map(|p| variant_db.into_variant(p)))
+                product.map(|p| variant_db.into_variant(p)).transpose()
             }
             None => Ok(None),
         }
     }
 
+    async fn get_variants_by_ids(
+        &self,
+        _: &Context,
+        ids: &[i64],
+    ) -> DomainResult<Vec<ProductVariant>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(VARIANT_SELECT_COLUMNS);
+        builder.push(" WHERE is_deleted = 0 AND id IN (");
+        let mut separated = builder.separated(", ");
+        for id in ids {
+            separated.push_bind(id);
+        }
+        builder.push(") ORDER BY id");
+
+        let variants_db = builder
+            .build_query_as::<ProductVariantDbSqlite>()
+            .fetch_all(&self.pool)
+            .await?;
+        self.attach_products(variants_db).await
+    }
+
     async fn get_variant_by_product_id(
         &self,
         _: &Context,
@@ -464,14 +919,56 @@ impl<'a> ProductRepository<Transaction<'a, Sqlite>> for SqliteProductRepository
         let product = self.fetch_product_by_id(product_id).await?;
 
         match product {
-            Some(product) => Ok(variants_db
+            Some(product) => variants_db
                 .into_iter()
                 .map(|v| v.into_variant(product.clone()))
-                .collect()),
+                .collect(),
             None => Ok(Vec::new()),
         }
     }
 
+    async fn get_variants(
+        &self,
+        _: &Context,
+        filter: &ProductVariantFilter,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<ProductVariant>> {
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(VARIANT_SELECT_COLUMNS);
+        // Variants of a deleted product are not listed even if not yet deleted
+        builder.push(
+            " WHERE is_deleted = 0 AND product_id IN (SELECT id FROM products WHERE is_deleted = 0)",
+        );
+        if let Some(product_id) = filter.product_id {
+            builder.push(" AND product_id = ");
+            builder.push_bind(product_id);
+        }
+        if let Some(barcode) = &filter.barcode {
+            builder.push(" AND barcode = ");
+            builder.push_bind(barcode.clone());
+        }
+        builder.push_like_filter("name", &filter.name);
+        builder.push(" ORDER BY id DESC LIMIT ");
+        builder.push_bind(pagination.limit());
+        builder.push(" OFFSET ");
+        builder.push_bind(pagination.offset());
+
+        let variants_db = builder
+            .build_query_as::<ProductVariantDbSqlite>()
+            .fetch_all(&self.pool)
+            .await?;
+        self.attach_products(variants_db).await
+    }
+
+    async fn count_variants(&self, _: &Context, product_id: i64) -> DomainResult<u64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM product_variants WHERE product_id = ? AND is_deleted = 0",
+        )
+        .bind(product_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count as u64)
+    }
+
     async fn get_product_category(&self, _: &Context, product_id: i64) -> DomainResult<Vec<i64>> {
         let query = sqlx::query_as::<_, (i64,)>(
             "SELECT category_id FROM product_categories WHERE product_id = ?",
@@ -484,4 +981,290 @@ impl<'a> ProductRepository<Transaction<'a, Sqlite>> for SqliteProductRepository
 
         Ok(category_ids)
     }
+
+    async fn add_categories(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+        category_ids: &[i64],
+        tx: &mut TxGuard<'a>,
+    ) -> DomainResult<()> {
+        touch_product(ctx, product_id, tx).await?;
+        if category_ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT OR IGNORE INTO product_categories (product_id, category_id) ",
+        );
+        builder.push_values(category_ids, |mut b, category_id| {
+            b.push_bind(product_id).push_bind(category_id);
+        });
+        builder.build().execute(&mut **tx).await?;
+
+        Ok(())
+    }
+
+    async fn remove_categories(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+        category_ids: &[i64],
+        tx: &mut TxGuard<'a>,
+    ) -> DomainResult<()> {
+        touch_product(ctx, product_id, tx).await?;
+        if category_ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut builder: QueryBuilder<Sqlite> =
+            QueryBuilder::new("DELETE FROM product_categories WHERE product_id = ");
+        builder.push_bind(product_id);
+        builder.push(" AND category_id IN (");
+        let mut separated = builder.separated(", ");
+        for category_id in category_ids {
+            separated.push_bind(category_id);
+        }
+        separated.push_unseparated(")");
+        builder.build().execute(&mut **tx).await?;
+
+        Ok(())
+    }
+
+    async fn set_suppliers(
+        &self,
+        ctx: &Context,
+        product_id: i64,
+        suppliers: &[ProductSupplier],
+        tx: &mut TxGuard<'a>,
+    ) -> DomainResult<()> {
+        touch_product(ctx, product_id, tx).await?;
+
+        sqlx::query("DELETE FROM product_suppliers WHERE product_id = ?")
+            .bind(product_id)
+            .execute(&mut **tx)
+            .await?;
+        if suppliers.is_empty() {
+            return Ok(());
+        }
+
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT INTO product_suppliers (product_id, supplier_id, supplier_sku, cost) ",
+        );
+        builder.push_values(suppliers, |mut b, supplier| {
+            b.push_bind(product_id)
+                .push_bind(supplier.supplier_id)
+                .push_bind(&supplier.supplier_sku)
+                .push_bind(supplier.cost.map(|c| c.minor_units()));
+        });
+        let result = builder.build().execute(&mut **tx).await;
+
+        match result {
+            Err(sqlx::Error::Database(e)) if e.is_foreign_key_violation() => Err(
+                Error::ValidationError("One or more suppliers do not exist".to_string()),
+            ),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(
+                Error::ValidationError("A supplier is listed more than once".to_string()),
+            ),
+            result => result.map(|_| ()).map_err(Error::from),
+        }
+    }
+
+    async fn get_suppliers(
+        &self,
+        _: &Context,
+        product_id: i64,
+    ) -> DomainResult<Vec<ProductSupplier>> {
+        let rows = sqlx::query_as::<_, (i64, Option<String>, Option<i64>)>(
+            r#"
+            SELECT supplier_id, supplier_sku, cost
+            FROM product_suppliers
+            WHERE product_id = ?
+            ORDER BY supplier_id
+            "#,
+        )
+        .bind(product_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(supplier_id, supplier_sku, cost)| ProductSupplier {
+                supplier_id,
+                supplier_sku,
+                cost: cost.map(Money::from_minor),
+            })
+            .collect())
+    }
+
+    async fn set_components(
+        &self,
+        ctx: &Context,
+        bundle_id: i64,
+        components: &[ProductComponent],
+        tx: &mut TxGuard<'a>,
+    ) -> DomainResult<()> {
+        let product_id = sqlx::query_scalar::<_, i64>(
+            "SELECT product_id FROM product_variants WHERE id = ? AND is_deleted = 0",
+        )
+        .bind(bundle_id)
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| Error::NotFound(format!("Variant with id {} not found", bundle_id)))?;
+        touch_product(ctx, product_id, tx).await?;
+
+        sqlx::query("DELETE FROM product_components WHERE bundle_id = ?")
+            .bind(bundle_id)
+            .execute(&mut **tx)
+            .await?;
+        if components.is_empty() {
+            return Ok(());
+        }
+
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT INTO product_components (bundle_id, component_variant_id, quantity) ",
+        );
+        builder.push_values(components, |mut b, component| {
+            b.push_bind(bundle_id)
+                .push_bind(component.component_variant_id)
+                .push_bind(component.quantity);
+        });
+        if let Err(err) = builder.build().execute(&mut **tx).await {
+            return Err(match err {
+                sqlx::Error::Database(e) if e.is_foreign_key_violation() => Error::ValidationError(
+                    "One or more component variants do not exist".to_string(),
+                ),
+                sqlx::Error::Database(e) if e.is_unique_violation() => {
+                    Error::ValidationError("A component is listed more than once".to_string())
+                }
+                sqlx::Error::Database(e) if e.is_check_violation() => Error::ValidationError(
+                    "A bundle cannot contain itself and quantities must be positive".to_string(),
+                ),
+                err => err.into(),
+            });
+        }
+
+        // UNION drops rows already seen, so the walk ends even on an existing cycle
+        let cycle: Option<i64> = sqlx::query_scalar(
+            r#"
+            WITH RECURSIVE reachable(id) AS (
+                SELECT component_variant_id FROM product_components WHERE bundle_id = ?
+                UNION
+                SELECT pc.component_variant_id
+                FROM product_components pc
+                JOIN reachable r ON pc.bundle_id = r.id
+            )
+            SELECT 1 FROM reachable WHERE id = ? LIMIT 1
+            "#,
+        )
+        .bind(bundle_id)
+        .bind(bundle_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+        if cycle.is_some() {
+            return Err(Error::ValidationError(
+                "A bundle cannot contain itself through its components".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn get_components(
+        &self,
+        _: &Context,
+        bundle_id: i64,
+    ) -> DomainResult<Vec<ProductComponent>> {
+        let rows = sqlx::query_as::<_, (i64, i64)>(
+            r#"
+            SELECT component_variant_id, quantity
+            FROM product_components
+            WHERE bundle_id = ?
+            ORDER BY component_variant_id
+            "#,
+        )
+        .bind(bundle_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(component_variant_id, quantity)| ProductComponent {
+                component_variant_id,
+                quantity,
+            })
+            .collect())
+    }
+
+    async fn set_level_price(
+        &self,
+        _: &Context,
+        variant_id: i64,
+        customer_level: i32,
+        price: &Money,
+    ) -> DomainResult<()> {
+        let query = sqlx::query(
+            r#"
+            INSERT INTO variant_prices (variant_id, customer_level, price)
+            VALUES (?, ?, ?)
+            ON CONFLICT (variant_id, customer_level) DO UPDATE SET
+                price = excluded.price,
+                updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+            "#,
+        )
+        .bind(variant_id)
+        .bind(customer_level)
+        .bind(price.minor_units());
+
+        query.execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn get_level_price(
+        &self,
+        _: &Context,
+        variant_id: i64,
+        customer_level: i32,
+    ) -> DomainResult<Option<Money>> {
+        let query = sqlx::query_as::<_, (i64,)>(
+            "SELECT price FROM variant_prices WHERE variant_id = ? AND customer_level = ?",
+        )
+        .bind(variant_id)
+        .bind(customer_level);
+
+        let price = query.fetch_optional(&self.pool).await?;
+        Ok(price.map(|(minor,)| Money::from_minor(minor)))
+    }
+
+    async fn get_price_history(
+        &self,
+        _: &Context,
+        variant_id: i64,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<VariantPriceChange>> {
+        let rows = sqlx::query_as::<_, (i64, Option<i64>, String, Option<i64>)>(
+            r#"
+            SELECT variant_id, price, effective_from, changed_by
+            FROM variant_price_history
+            WHERE variant_id = ?
+            ORDER BY id ASC
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(variant_id)
+        .bind(pagination.limit())
+        .bind(pagination.offset())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|(variant_id, price, effective_from, changed_by)| {
+                Ok(VariantPriceChange {
+                    variant_id,
+                    price: price.map(Money::from_minor),
+                    effective_from: super::parse_sqlite_date(&effective_from)?,
+                    changed_by,
+                })
+            })
+            .collect()
+    }
 }
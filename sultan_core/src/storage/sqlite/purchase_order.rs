@@ -0,0 +1,219 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+use crate::{
+    domain::{
+        Context, DomainResult, Error,
+        model::{
+            money::Money,
+            purchase_order::{
+                PurchaseOrder, PurchaseOrderCreate, PurchaseOrderLine, PurchaseOrderStatus,
+            },
+            validate::Validate,
+        },
+    },
+    storage::{PurchaseOrderRepository, sqlite::transaction::TxGuard},
+};
+
+/// SQLite implementation of the PurchaseOrderRepository.
+///
+/// Costs are stored as `INTEGER` minor units. Stock is added through the
+/// caller's transaction so it lands together with the status change.
+#[derive(Clone)]
+pub struct SqlitePurchaseOrderRepository {
+    pool: SqlitePool,
+}
+
+impl SqlitePurchaseOrderRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(sqlx::FromRow, Debug)]
+struct PurchaseOrderDbSqlite {
+    pub id: i64,
+    pub created_at: String,
+    pub supplier_id: i64,
+    pub branch_id: i64,
+    pub status: String,
+}
+
+#[derive(sqlx::FromRow, Debug)]
+struct PurchaseOrderLineDbSqlite {
+    pub variant_id: i64,
+    pub qty: i64,
+    pub cost: i64,
+}
+
+impl From<PurchaseOrderLineDbSqlite> for PurchaseOrderLine {
+    fn from(db: PurchaseOrderLineDbSqlite) -> Self {
+        PurchaseOrderLine {
+            variant_id: db.variant_id,
+            qty: db.qty,
+            cost: Money::from_minor(db.cost),
+        }
+    }
+}
+
+fn parse_status(status: &str) -> DomainResult<PurchaseOrderStatus> {
+    status.parse().map_err(|_| {
+        Error::Database(format!(
+            "Invalid purchase order status in database: '{}'",
+            status
+        ))
+    })
+}
+
+impl PurchaseOrderDbSqlite {
+    fn into_purchase_order(self, lines: Vec<PurchaseOrderLine>) -> DomainResult<PurchaseOrder> {
+        Ok(PurchaseOrder {
+            id: self.id,
+            supplier_id: self.supplier_id,
+            branch_id: self.branch_id,
+            lines,
+            status: parse_status(&self.status)?,
+            created_at: super::parse_sqlite_date(&self.created_at)?,
+        })
+    }
+}
+
+#[async_trait]
+impl<'a> PurchaseOrderRepository<TxGuard<'a>> for SqlitePurchaseOrderRepository {
+    async fn create(
+        &self,
+        _: &Context,
+        id: i64,
+        order: &PurchaseOrderCreate,
+        tx: &mut TxGuard<'a>,
+    ) -> DomainResult<()> {
+        order.validate()?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO purchase_orders (id, supplier_id, branch_id, status)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(order.supplier_id)
+        .bind(order.branch_id)
+        .bind(PurchaseOrderStatus::Ordered.as_str())
+        .execute(&mut **tx)
+        .await?;
+
+        for (line_no, line) in order.lines.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO purchase_order_lines (purchase_order_id, line_no, variant_id, qty, cost)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(id)
+            .bind(line_no as i64)
+            .bind(line.variant_id)
+            .bind(line.qty)
+            .bind(line.cost.minor_units())
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_by_id(&self, _: &Context, id: i64) -> DomainResult<Option<PurchaseOrder>> {
+        let header = sqlx::query_as::<_, PurchaseOrderDbSqlite>(
+            r#"
+            SELECT id, created_at, supplier_id, branch_id, status
+            FROM purchase_orders
+            WHERE id = ?
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(header) = header else {
+            return Ok(None);
+        };
+
+        let lines = sqlx::query_as::<_, PurchaseOrderLineDbSqlite>(
+            r#"
+            SELECT variant_id, qty, cost
+            FROM purchase_order_lines
+            WHERE purchase_order_id = ?
+            ORDER BY line_no
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        header
+            .into_purchase_order(lines.into_iter().map(PurchaseOrderLine::from).collect())
+            .map(Some)
+    }
+
+    async fn update_status(
+        &self,
+        _: &Context,
+        id: i64,
+        from: PurchaseOrderStatus,
+        to: PurchaseOrderStatus,
+        tx: &mut TxGuard<'a>,
+    ) -> DomainResult<()> {
+        let result =
+            sqlx::query("UPDATE purchase_orders SET status = ? WHERE id = ? AND status = ?")
+                .bind(to.as_str())
+                .bind(id)
+                .bind(from.as_str())
+                .execute(&mut **tx)
+                .await?;
+
+        if result.rows_affected() > 0 {
+            return Ok(());
+        }
+
+        let current: Option<String> =
+            sqlx::query_scalar("SELECT status FROM purchase_orders WHERE id = ?")
+                .bind(id)
+                .fetch_optional(&mut **tx)
+                .await?;
+        match current {
+            None => Err(Error::NotFound(format!(
+                "Purchase order with id {} not found",
+                id
+            ))),
+            Some(status) => Err(Error::Conflict(format!(
+                "Purchase order {} is already {}",
+                id, status
+            ))),
+        }
+    }
+
+    async fn add_stock(
+        &self,
+        _: &Context,
+        branch_id: i64,
+        lines: &[PurchaseOrderLine],
+        tx: &mut TxGuard<'a>,
+    ) -> DomainResult<()> {
+        for line in lines {
+            sqlx::query(
+                r#"
+                INSERT INTO stocks (branch_id, variant_id, quantity)
+                VALUES (?, ?, ?)
+                ON CONFLICT (branch_id, variant_id) DO UPDATE SET
+                    quantity = quantity + excluded.quantity,
+                    updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                "#,
+            )
+            .bind(branch_id)
+            .bind(line.variant_id)
+            .bind(line.qty)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,405 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, Utc};
+use sqlx::SqlitePool;
+
+use crate::{
+    domain::{
+        Context, DomainResult, Error,
+        model::{
+            money::Money,
+            sale::{
+                PaymentMethodTotal, ReversedLine, Sale, SaleCreate, SaleKind, SaleLine,
+                SalesSummary,
+            },
+        },
+    },
+    storage::{SaleRepository, sqlite::transaction::TxGuard},
+};
+
+/// SQLite implementation of the SaleRepository.
+///
+/// Amounts are stored as `INTEGER` minor units. Creating a sale writes the
+/// header, its lines and the stock decrements through the caller's
+/// transaction, so a failure on any line leaves nothing behind once the
+/// transaction is rolled back.
+#[derive(Clone)]
+pub struct SqliteSaleRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteSaleRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+#[derive(sqlx::FromRow, Debug)]
+struct SaleDbSqlite {
+    pub id: i64,
+    pub created_at: String,
+    pub branch_id: i64,
+    pub customer_id: Option<i64>,
+    pub payment_method: String,
+    pub kind: String,
+    pub original_sale_id: Option<i64>,
+    pub total: i64,
+}
+
+#[derive(sqlx::FromRow, Debug)]
+struct SaleLineDbSqlite {
+    pub variant_id: i64,
+    pub qty: i64,
+    pub unit_price: i64,
+    pub line_total: i64,
+}
+
+impl From<SaleLineDbSqlite> for SaleLine {
+    fn from(db: SaleLineDbSqlite) -> Self {
+        SaleLine {
+            variant_id: db.variant_id,
+            qty: db.qty,
+            unit_price: Money::from_minor(db.unit_price),
+            line_total: Money::from_minor(db.line_total),
+        }
+    }
+}
+
+fn parse_kind(kind: &str) -> DomainResult<SaleKind> {
+    kind.parse()
+        .map_err(|_| Error::Database(format!("Invalid sale kind in database: '{}'", kind)))
+}
+
+async fn insert_lines(tx: &mut TxGuard<'_>, sale_id: i64, lines: &[SaleLine]) -> DomainResult<()> {
+    for (line_no, line) in lines.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO sale_lines (sale_id, line_no, variant_id, qty, unit_price, line_total)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(sale_id)
+        .bind(line_no as i64)
+        .bind(line.variant_id)
+        .bind(line.qty)
+        .bind(line.unit_price.minor_units())
+        .bind(line.line_total.minor_units())
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
+impl SaleDbSqlite {
+    fn into_sale(self, lines: Vec<SaleLine>) -> DomainResult<Sale> {
+        Ok(Sale {
+            id: self.id,
+            branch_id: self.branch_id,
+            customer_id: self.customer_id,
+            payment_method: self.payment_method,
+            kind: parse_kind(&self.kind)?,
+            original_sale_id: self.original_sale_id,
+            lines,
+            total: Money::from_minor(self.total),
+            created_at: super::parse_sqlite_date(&self.created_at)?,
+        })
+    }
+}
+
+#[async_trait]
+impl<'a> SaleRepository<TxGuard<'a>> for SqliteSaleRepository {
+    async fn create(
+        &self,
+        _: &Context,
+        id: i64,
+        sale: &SaleCreate,
+        tx: &mut TxGuard<'a>,
+    ) -> DomainResult<()> {
+        let (lines, total) = sale.priced_lines()?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO sales (id, branch_id, customer_id, payment_method, total)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(sale.branch_id)
+        .bind(sale.customer_id)
+        .bind(&sale.payment_method)
+        .bind(total.minor_units())
+        .execute(&mut **tx)
+        .await?;
+
+        insert_lines(tx, id, &lines).await?;
+
+        for line in &lines {
+            // The quantity guard makes the check and the decrement a single statement
+            let result = sqlx::query(
+                r#"
+                UPDATE stocks SET
+                    quantity = quantity - ?,
+                    updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                WHERE branch_id = ? AND variant_id = ? AND quantity >= ?
+                "#,
+            )
+            .bind(line.qty)
+            .bind(sale.branch_id)
+            .bind(line.variant_id)
+            .bind(line.qty)
+            .execute(&mut **tx)
+            .await?;
+
+            if result.rows_affected() == 0 {
+                return Err(Error::ValidationError(format!(
+                    "Insufficient stock for variant {}",
+                    line.variant_id
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_by_id(&self, _: &Context, id: i64) -> DomainResult<Option<Sale>> {
+        let header = sqlx::query_as::<_, SaleDbSqlite>(
+            r#"
+            SELECT id, created_at, branch_id, customer_id, payment_method, kind, original_sale_id, total
+            FROM sales
+            WHERE id = ?
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(header) = header else {
+            return Ok(None);
+        };
+
+        let lines = sqlx::query_as::<_, SaleLineDbSqlite>(
+            r#"
+            SELECT variant_id, qty, unit_price, line_total
+            FROM sale_lines
+            WHERE sale_id = ?
+            ORDER BY line_no
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        header
+            .into_sale(lines.into_iter().map(SaleLine::from).collect())
+            .map(Some)
+    }
+
+    async fn create_reversal(
+        &self,
+        _: &Context,
+        id: i64,
+        original: &Sale,
+        kind: SaleKind,
+        lines: &[SaleLine],
+        tx: &mut TxGuard<'a>,
+    ) -> DomainResult<()> {
+        let total = lines
+            .iter()
+            .try_fold(Money::ZERO, |acc, line| acc.checked_add(line.line_total))
+            .ok_or_else(|| Error::ValidationError("Reversal total is out of range".to_string()))?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO sales (id, branch_id, customer_id, payment_method, kind, original_sale_id, total)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(original.branch_id)
+        .bind(original.customer_id)
+        .bind(&original.payment_method)
+        .bind(kind.as_str())
+        .bind(original.id)
+        .bind(total.minor_units())
+        .execute(&mut **tx)
+        .await;
+
+        // idx_sales_single_void catches a void racing another void
+        if let Err(sqlx::Error::Database(e)) = &result
+            && e.is_unique_violation()
+        {
+            return Err(Error::Conflict(format!(
+                "Sale {} is already voided",
+                original.id
+            )));
+        }
+        result?;
+
+        insert_lines(tx, id, lines).await?;
+
+        for line in lines {
+            sqlx::query(
+                r#"
+                INSERT INTO stocks (branch_id, variant_id, quantity)
+                VALUES (?, ?, ?)
+                ON CONFLICT (branch_id, variant_id) DO UPDATE SET
+                    quantity = quantity + excluded.quantity,
+                    updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+                "#,
+            )
+            .bind(original.branch_id)
+            .bind(line.variant_id)
+            .bind(line.qty)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn reversed_lines(
+        &self,
+        _: &Context,
+        sale_id: i64,
+        tx: &mut TxGuard<'a>,
+    ) -> DomainResult<Vec<ReversedLine>> {
+        let rows = sqlx::query_as::<_, (String, i64, i64)>(
+            r#"
+            SELECT s.kind, l.variant_id, SUM(l.qty)
+            FROM sales s
+            JOIN sale_lines l ON l.sale_id = s.id
+            WHERE s.original_sale_id = ?
+            GROUP BY s.kind, l.variant_id
+            "#,
+        )
+        .bind(sale_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        rows.into_iter()
+            .map(|(kind, variant_id, qty)| {
+                Ok(ReversedLine {
+                    kind: parse_kind(&kind)?,
+                    variant_id,
+                    qty,
+                })
+            })
+            .collect()
+    }
+
+    async fn summary(
+        &self,
+        _: &Context,
+        branch_id: i64,
+        date: NaiveDate,
+    ) -> DomainResult<SalesSummary> {
+        let start = date.and_time(NaiveTime::MIN).and_utc();
+        let end = start
+            .checked_add_days(Days::new(1))
+            .ok_or_else(|| Error::ValidationError(format!("Invalid summary date {}", date)))?;
+
+        // created_at is stored in a fixed-width ISO format, so string
+        // comparison matches chronological order and can use the index
+        let rows = sqlx::query_as::<_, (String, i64, i64)>(
+            r#"
+            SELECT payment_method, COUNT(*), COALESCE(SUM(total), 0)
+            FROM sales
+            WHERE branch_id = ? AND kind = 'sale' AND created_at >= ? AND created_at < ?
+            GROUP BY payment_method
+            ORDER BY payment_method
+            "#,
+        )
+        .bind(branch_id)
+        .bind(super::format_sqlite_date(&start))
+        .bind(super::format_sqlite_date(&end))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let by_payment_method: Vec<PaymentMethodTotal> = rows
+            .into_iter()
+            .map(|(payment_method, sale_count, total)| PaymentMethodTotal {
+                payment_method,
+                sale_count,
+                total: Money::from_minor(total),
+            })
+            .collect();
+
+        Ok(SalesSummary {
+            branch_id,
+            date,
+            sale_count: by_payment_method.iter().map(|m| m.sale_count).sum(),
+            gross_total: by_payment_method
+                .iter()
+                .fold(Money::ZERO, |acc, m| acc + m.total),
+            by_payment_method,
+        })
+    }
+
+    async fn find_by_idempotency_key(
+        &self,
+        ctx: &Context,
+        branch_id: i64,
+        key: &str,
+        now: DateTime<Utc>,
+    ) -> DomainResult<Option<i64>> {
+        let sale_id = sqlx::query_scalar::<_, i64>(
+            "SELECT sale_id FROM idempotency_keys WHERE branch_id = ? AND user_id = ? AND key = ? AND expires_at > ?",
+        )
+        .bind(branch_id)
+        .bind(idempotency_user_id(ctx))
+        .bind(key)
+        .bind(super::format_sqlite_date(&now))
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(sale_id)
+    }
+
+    async fn save_idempotency_key(
+        &self,
+        ctx: &Context,
+        key: &str,
+        sale_id: i64,
+        now: DateTime<Utc>,
+        expires_at: DateTime<Utc>,
+        tx: &mut TxGuard<'a>,
+    ) -> DomainResult<bool> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (branch_id, user_id, key, sale_id, expires_at)
+            SELECT branch_id, ?, ?, id, ? FROM sales WHERE id = ?
+            ON CONFLICT (branch_id, user_id, key) DO UPDATE SET
+                sale_id = excluded.sale_id,
+                created_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+                expires_at = excluded.expires_at
+            WHERE idempotency_keys.expires_at <= ?
+            "#,
+        )
+        .bind(idempotency_user_id(ctx))
+        .bind(key)
+        .bind(super::format_sqlite_date(&expires_at))
+        .bind(sale_id)
+        .bind(super::format_sqlite_date(&now))
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_expired_idempotency_keys(
+        &self,
+        _: &Context,
+        now: DateTime<Utc>,
+    ) -> DomainResult<u64> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at <= ?")
+            .bind(super::format_sqlite_date(&now))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Owner of an idempotency key; callers without a user share 0
+fn idempotency_user_id(ctx: &Context) -> i64 {
+    ctx.user_id().unwrap_or(0)
+}
@@ -1,17 +1,20 @@
 use async_trait::async_trait;
 use serde::Serialize;
-use sqlx::{QueryBuilder, Sqlite, SqlitePool, Transaction};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
 
 use super::{TableName, check_rows_affected, serialize_metadata, serialize_metadata_update};
 use crate::{
     domain::{
-        Context, DomainResult,
+        Context, DomainResult, Error,
         model::sell_price::{
             SellDiscount, SellDiscountCreate, SellDiscountUpdate, SellPrice, SellPriceCreate,
             SellPriceUpdate,
         },
     },
-    storage::{sell_price_repo::SellPriceRepository, sqlite::soft_delete},
+    storage::{
+        sell_price_repo::SellPriceRepository,
+        sqlite::{soft_delete_by, soft_delete_by_on_pool, transaction::TxGuard},
+    },
 };
 
 #[derive(sqlx::FromRow, Debug, Serialize)]
@@ -29,13 +32,15 @@ struct SellPriceDbSqlite {
     pub metadata: Option<String>,
 }
 
-impl From<SellPriceDbSqlite> for SellPrice {
-    fn from(db: SellPriceDbSqlite) -> Self {
-        SellPrice {
+impl TryFrom<SellPriceDbSqlite> for SellPrice {
+    type Error = Error;
+
+    fn try_from(db: SellPriceDbSqlite) -> Result<Self, Self::Error> {
+        Ok(SellPrice {
             id: db.id,
-            created_at: super::parse_sqlite_date(&db.created_at),
-            updated_at: super::parse_sqlite_date(&db.updated_at),
-            deleted_at: db.deleted_at.map(|d| super::parse_sqlite_date(&d)),
+            created_at: super::parse_sqlite_date(&db.created_at)?,
+            updated_at: super::parse_sqlite_date(&db.updated_at)?,
+            deleted_at: super::parse_optional_sqlite_date(db.deleted_at.as_deref())?,
             is_deleted: db.is_deleted,
             branch_id: db.branch_id,
             product_variant_id: db.product_variant_id,
@@ -43,7 +48,7 @@ impl From<SellPriceDbSqlite> for SellPrice {
             quantity: db.quantity,
             price: db.price,
             metadata: db.metadata.and_then(|m| serde_json::from_str(&m).ok()),
-        }
+        })
     }
 }
 
@@ -62,13 +67,15 @@ struct SellDiscountDbSqlite {
     pub metadata: Option<String>,
 }
 
-impl From<SellDiscountDbSqlite> for SellDiscount {
-    fn from(db: SellDiscountDbSqlite) -> Self {
-        SellDiscount {
+impl TryFrom<SellDiscountDbSqlite> for SellDiscount {
+    type Error = Error;
+
+    fn try_from(db: SellDiscountDbSqlite) -> Result<Self, Self::Error> {
+        Ok(SellDiscount {
             id: db.id,
-            created_at: super::parse_sqlite_date(&db.created_at),
-            updated_at: super::parse_sqlite_date(&db.updated_at),
-            deleted_at: db.deleted_at.map(|d| super::parse_sqlite_date(&d)),
+            created_at: super::parse_sqlite_date(&db.created_at)?,
+            updated_at: super::parse_sqlite_date(&db.updated_at)?,
+            deleted_at: super::parse_optional_sqlite_date(db.deleted_at.as_deref())?,
             is_deleted: db.is_deleted,
             sell_price_id: db.price_id,
             quantity: db.quantity,
@@ -76,7 +83,7 @@ impl From<SellDiscountDbSqlite> for SellDiscount {
             calculated_price: db.calculated_price,
             customer_level: db.customer_level,
             metadata: db.metadata.and_then(|m| serde_json::from_str(&m).ok()),
-        }
+        })
     }
 }
 
@@ -237,7 +244,7 @@ impl SqliteSellPriceRepository {
 }
 
 #[async_trait]
-impl<'a> SellPriceRepository<Transaction<'a, Sqlite>> for SqliteSellPriceRepository {
+impl<'a> SellPriceRepository<TxGuard<'a>> for SqliteSellPriceRepository {
     async fn create(&self, _: &Context, id: i64, price: &SellPriceCreate) -> DomainResult<()> {
         self.create_impl(id, price, &self.pool).await
     }
@@ -246,7 +253,7 @@ impl<'a> SellPriceRepository<Transaction<'a, Sqlite>> for SqliteSellPriceReposit
         _: &Context,
         id: i64,
         price: &SellPriceCreate,
-        tx: &mut Transaction<'a, Sqlite>,
+        tx: &mut TxGuard<'a>,
     ) -> DomainResult<()> {
         self.create_impl(id, price, &mut **tx).await
     }
@@ -258,21 +265,17 @@ impl<'a> SellPriceRepository<Transaction<'a, Sqlite>> for SqliteSellPriceReposit
         _: &Context,
         id: i64,
         sell_price: &SellPriceUpdate,
-        tx: &mut Transaction<'a, Sqlite>,
+        tx: &mut TxGuard<'a>,
     ) -> DomainResult<()> {
         self.update_impl(id, sell_price, &mut **tx).await
     }
-    async fn delete(&self, _: &Context, id: i64) -> DomainResult<()> {
-        let result = soft_delete(&self.pool, TableName::SellPrices, id).await?;
+    async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()> {
+        let result =
+            soft_delete_by_on_pool(&self.pool, TableName::SellPrices, id, ctx.actor_id()).await?;
         check_rows_affected(result.rows_affected(), "Product", id)
     }
-    async fn delete_tx(
-        &self,
-        _: &Context,
-        id: i64,
-        tx: &mut Transaction<'a, Sqlite>,
-    ) -> DomainResult<()> {
-        let result = soft_delete(&mut **tx, TableName::SellPrices, id).await?;
+    async fn delete_tx(&self, ctx: &Context, id: i64, tx: &mut TxGuard<'a>) -> DomainResult<()> {
+        let result = soft_delete_by(&mut **tx, TableName::SellPrices, id, ctx.actor_id()).await?;
         check_rows_affected(result.rows_affected(), "Product", id)
     }
     async fn get_all_by_product_variant_id(
@@ -287,7 +290,7 @@ impl<'a> SellPriceRepository<Transaction<'a, Sqlite>> for SqliteSellPriceReposit
         "#;
         let rows: Vec<SellPriceDbSqlite> =
             sqlx::query_as(query).bind(id).fetch_all(&self.pool).await?;
-        Ok(super::map_results(rows))
+        super::map_results(rows)
     }
     async fn get_by_id(&self, _: &Context, id: i64) -> DomainResult<Option<SellPrice>> {
         let query = r#"
@@ -299,7 +302,7 @@ impl<'a> SellPriceRepository<Transaction<'a, Sqlite>> for SqliteSellPriceReposit
             .bind(id)
             .fetch_optional(&self.pool)
             .await?;
-        Ok(row.map(|r| r.into()))
+        row.map(TryInto::try_into).transpose()
     }
 
     async fn create_discount(
@@ -315,7 +318,7 @@ impl<'a> SellPriceRepository<Transaction<'a, Sqlite>> for SqliteSellPriceReposit
         _: &Context,
         id: i64,
         price: &SellDiscountCreate,
-        tx: &mut Transaction<'a, Sqlite>,
+        tx: &mut TxGuard<'a>,
     ) -> DomainResult<()> {
         self.create_discount_impl(id, price, &mut **tx).await
     }
@@ -333,32 +336,43 @@ impl<'a> SellPriceRepository<Transaction<'a, Sqlite>> for SqliteSellPriceReposit
         _: &Context,
         id: i64,
         sell_discount: &SellDiscountUpdate,
-        tx: &mut Transaction<'a, Sqlite>,
+        tx: &mut TxGuard<'a>,
     ) -> DomainResult<()> {
         self.update_discount_impl(id, sell_discount, &mut **tx)
             .await
     }
-    async fn delete_discount(&self, _: &Context, id: i64) -> DomainResult<()> {
-        let result = soft_delete(&self.pool, TableName::SellDiscounts, id).await?;
+    async fn delete_discount(&self, ctx: &Context, id: i64) -> DomainResult<()> {
+        let result =
+            soft_delete_by_on_pool(&self.pool, TableName::SellDiscounts, id, ctx.actor_id())
+                .await?;
         check_rows_affected(result.rows_affected(), "SellDiscount", id)
     }
     async fn delete_discount_by_sell_price_id_tx(
         &self,
-        _: &Context,
+        ctx: &Context,
         sell_price_id: i64,
-        tx: &mut Transaction<'a, Sqlite>,
+        tx: &mut TxGuard<'a>,
     ) -> DomainResult<()> {
         let sql = r#"
         UPDATE sell_discounts SET
             is_deleted = 1,
-            deleted_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+            deleted_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+            updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'),
+            deleted_by = ?
         WHERE sell_price_id = ? AND is_deleted = 0
         "#;
         sqlx::query(sql)
+            .bind(ctx.actor_id())
             .bind(sell_price_id)
             .execute(&mut **tx)
             .await?;
-        let result = soft_delete(&mut **tx, TableName::SellDiscounts, sell_price_id).await?;
+        let result = soft_delete_by(
+            &mut **tx,
+            TableName::SellDiscounts,
+            sell_price_id,
+            ctx.actor_id(),
+        )
+        .await?;
         check_rows_affected(result.rows_affected(), "SellDiscount", sell_price_id)
     }
     async fn get_all_discount_by_price_id(
@@ -373,7 +387,7 @@ impl<'a> SellPriceRepository<Transaction<'a, Sqlite>> for SqliteSellPriceReposit
         "#;
         let rows: Vec<SellDiscountDbSqlite> =
             sqlx::query_as(query).bind(id).fetch_all(&self.pool).await?;
-        Ok(super::map_results(rows))
+        super::map_results(rows)
     }
     async fn get_discount_by_id(&self, _: &Context, id: i64) -> DomainResult<Option<SellDiscount>> {
         let query = r#"
@@ -385,6 +399,6 @@ impl<'a> SellPriceRepository<Transaction<'a, Sqlite>> for SqliteSellPriceReposit
             .bind(id)
             .fetch_optional(&self.pool)
             .await?;
-        Ok(row.map(|r| r.into()))
+        row.map(TryInto::try_into).transpose()
     }
 }
@@ -0,0 +1,158 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+use crate::{
+    domain::{
+        Context, DomainResult, Error,
+        model::{
+            pagination::PaginationOptions,
+            stock::{StockAdjustment, StockLevel},
+        },
+    },
+    storage::{StockRepository, sqlite::transaction::TxGuard},
+};
+
+/// SQLite implementation of the StockRepository.
+///
+/// Adjustments update `stocks` and append to `stock_adjustments` through the
+/// caller's transaction, so the quantity and its history never disagree.
+#[derive(Clone)]
+pub struct SqliteStockRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteStockRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Quantity on hand, 0 when the variant has no stock row in the branch
+    pub async fn get_quantity(
+        &self,
+        _: &Context,
+        branch_id: i64,
+        variant_id: i64,
+    ) -> DomainResult<i64> {
+        let quantity = sqlx::query_scalar::<_, i64>(
+            "SELECT quantity FROM stocks WHERE branch_id = ? AND variant_id = ?",
+        )
+        .bind(branch_id)
+        .bind(variant_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(quantity.unwrap_or(0))
+    }
+}
+
+#[derive(sqlx::FromRow, Debug)]
+struct StockLevelDbSqlite {
+    pub branch_id: i64,
+    pub variant_id: i64,
+    pub product_id: i64,
+    pub product_name: String,
+    pub variant_name: Option<String>,
+    pub barcode: Option<String>,
+    pub quantity: i64,
+}
+
+impl From<StockLevelDbSqlite> for StockLevel {
+    fn from(db: StockLevelDbSqlite) -> Self {
+        StockLevel {
+            branch_id: db.branch_id,
+            variant_id: db.variant_id,
+            product_id: db.product_id,
+            product_name: db.product_name,
+            variant_name: db.variant_name,
+            barcode: db.barcode,
+            quantity: db.quantity,
+        }
+    }
+}
+
+#[async_trait]
+impl<'a> StockRepository<TxGuard<'a>> for SqliteStockRepository {
+    async fn adjust(
+        &self,
+        ctx: &Context,
+        adjustment: &StockAdjustment,
+        tx: &mut TxGuard<'a>,
+    ) -> DomainResult<i64> {
+        let result = sqlx::query_scalar::<_, i64>(
+            r#"
+            INSERT INTO stocks (branch_id, variant_id, quantity)
+            VALUES (?, ?, ?)
+            ON CONFLICT (branch_id, variant_id) DO UPDATE SET
+                quantity = quantity + excluded.quantity,
+                updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+            RETURNING quantity
+            "#,
+        )
+        .bind(adjustment.branch_id)
+        .bind(adjustment.variant_id)
+        .bind(adjustment.delta)
+        .fetch_one(&mut **tx)
+        .await;
+
+        let quantity = match result {
+            Err(sqlx::Error::Database(e)) if e.is_foreign_key_violation() => {
+                return Err(Error::ValidationError(
+                    "Branch or variant does not exist".to_string(),
+                ));
+            }
+            result => result?,
+        };
+        if quantity < 0 {
+            return Err(Error::ValidationError(format!(
+                "Insufficient stock for variant {}",
+                adjustment.variant_id
+            )));
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO stock_adjustments
+                (branch_id, variant_id, delta, quantity_after, reason, created_by)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(adjustment.branch_id)
+        .bind(adjustment.variant_id)
+        .bind(adjustment.delta)
+        .bind(quantity)
+        .bind(&adjustment.reason)
+        .bind(ctx.user_id())
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(quantity)
+    }
+
+    async fn list_low(
+        &self,
+        _: &Context,
+        branch_id: i64,
+        threshold: i64,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<StockLevel>> {
+        let rows = sqlx::query_as::<_, StockLevelDbSqlite>(
+            r#"
+            SELECT s.branch_id, s.variant_id, p.id AS product_id, p.name AS product_name,
+                v.name AS variant_name, v.barcode, s.quantity
+            FROM stocks s
+            JOIN product_variants v ON v.id = s.variant_id AND v.is_deleted = 0
+            JOIN products p ON p.id = v.product_id AND p.is_deleted = 0
+            WHERE s.branch_id = ? AND s.quantity <= ?
+            ORDER BY s.quantity, s.variant_id
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(branch_id)
+        .bind(threshold)
+        .bind(pagination.limit())
+        .bind(pagination.offset())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(StockLevel::from).collect())
+    }
+}
@@ -4,17 +4,17 @@ use sqlx::{QueryBuilder, Sqlite, SqlitePool};
 
 use super::{
     QueryBuilderExt, TableName, check_rows_affected, map_results, serialize_metadata_update,
-    soft_delete,
+    soft_delete_by_on_pool,
 };
 use crate::{
     domain::{
-        Context, DomainResult,
+        Context, DomainResult, Error,
         model::{
             pagination::PaginationOptions,
             supplier::{Supplier, SupplierCreate, SupplierFilter, SupplierUpdate},
         },
     },
-    storage::SupplierRepository,
+    storage::{ReadRepository, SupplierRepository},
 };
 
 #[derive(Clone)]
@@ -44,15 +44,19 @@ pub struct SupplierDbSqlite {
     pub npwp: Option<String>,
     pub npwp_name: Option<String>,
     pub metadata: Option<String>,
+    pub created_by: Option<i64>,
+    pub updated_by: Option<i64>,
 }
 
-impl From<SupplierDbSqlite> for Supplier {
-    fn from(supplier_db: SupplierDbSqlite) -> Self {
-        Supplier {
+impl TryFrom<SupplierDbSqlite> for Supplier {
+    type Error = Error;
+
+    fn try_from(supplier_db: SupplierDbSqlite) -> Result<Self, Self::Error> {
+        Ok(Supplier {
             id: supplier_db.id,
-            created_at: super::parse_sqlite_date(&supplier_db.created_at),
-            updated_at: super::parse_sqlite_date(&supplier_db.updated_at),
-            deleted_at: supplier_db.deleted_at.map(|d| super::parse_sqlite_date(&d)),
+            created_at: super::parse_sqlite_date(&supplier_db.created_at)?,
+            updated_at: super::parse_sqlite_date(&supplier_db.updated_at)?,
+            deleted_at: super::parse_optional_sqlite_date(supplier_db.deleted_at.as_deref())?,
             is_deleted: supplier_db.is_deleted,
             name: supplier_db.name,
             code: supplier_db.code,
@@ -64,20 +68,23 @@ impl From<SupplierDbSqlite> for Supplier {
             metadata: supplier_db
                 .metadata
                 .and_then(|m| serde_json::from_str(&m).ok()),
-        }
+            created_by: supplier_db.created_by,
+            updated_by: supplier_db.updated_by,
+        })
     }
 }
 
 #[async_trait]
 impl SupplierRepository for SqliteSupplierRepository {
-    async fn create(&self, _: &Context, id: i64, supplier: &SupplierCreate) -> DomainResult<()> {
+    async fn create(&self, ctx: &Context, id: i64, supplier: &SupplierCreate) -> DomainResult<()> {
         let metadata_json = super::serialize_metadata(&supplier.metadata);
 
         let query = sqlx::query(
             r#"
             INSERT INTO suppliers (
-                id, name, code, email, address, phone, npwp, npwp_name, metadata
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                id, name, code, email, address, phone, npwp, npwp_name, metadata,
+                created_by, updated_by
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(id)
@@ -89,13 +96,15 @@ impl SupplierRepository for SqliteSupplierRepository {
         .bind(&supplier.npwp)
         .bind(&supplier.npwp_name)
         .bind(&metadata_json)
+        .bind(ctx.actor_id())
+        .bind(ctx.actor_id())
         .execute(&self.pool);
 
         query.await?;
         Ok(())
     }
 
-    async fn update(&self, _: &Context, id: i64, supplier: &SupplierUpdate) -> DomainResult<()> {
+    async fn update(&self, ctx: &Context, id: i64, supplier: &SupplierUpdate) -> DomainResult<()> {
         let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new("UPDATE suppliers SET ");
         let mut separated = builder.separated(", ");
 
@@ -139,6 +148,9 @@ impl SupplierRepository for SqliteSupplierRepository {
                 .push_bind_unseparated(metadata_json);
         }
 
+        separated
+            .push("updated_by = ")
+            .push_bind_unseparated(ctx.actor_id());
         separated.push("updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')");
         builder.push(" WHERE id = ").push_bind(id);
         builder.push(" AND is_deleted = 0");
@@ -148,8 +160,8 @@ impl SupplierRepository for SqliteSupplierRepository {
         check_rows_affected(result.rows_affected(), "Supplier", id)
     }
 
-    async fn delete(&self, _: &Context, id: i64) -> DomainResult<()> {
-        let query = soft_delete(&self.pool, TableName::Suppliers, id);
+    async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()> {
+        let query = soft_delete_by_on_pool(&self.pool, TableName::Suppliers, id, ctx.actor_id());
         let result = query.await?;
         check_rows_affected(result.rows_affected(), "Supplier", id)
     }
@@ -161,15 +173,10 @@ impl SupplierRepository for SqliteSupplierRepository {
         pagination: &PaginationOptions,
     ) -> DomainResult<Vec<Supplier>> {
         let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
-            "SELECT id, created_at, updated_at, deleted_at, is_deleted, name, code, email, address, phone, npwp, npwp_name, metadata FROM suppliers WHERE is_deleted = 0",
+            "SELECT id, created_at, updated_at, deleted_at, is_deleted, name, code, email, address, phone, npwp, npwp_name, metadata, created_by, updated_by FROM suppliers WHERE is_deleted = 0",
         );
 
-        builder
-            .push_like_filter("name", &filter.name)
-            .push_like_filter("code", &filter.code)
-            .push_like_filter("email", &filter.email)
-            .push_like_filter("phone", &filter.phone)
-            .push_like_filter("npwp", &filter.npwp);
+        push_filter(&mut builder, filter);
 
         builder.push(" ORDER BY id DESC");
         builder.push(" LIMIT ");
@@ -179,19 +186,52 @@ impl SupplierRepository for SqliteSupplierRepository {
 
         let query = builder.build_query_as::<SupplierDbSqlite>();
         let suppliers = query.fetch_all(&self.pool).await?;
-        Ok(map_results(suppliers))
+        map_results(suppliers)
+    }
+
+    async fn count(&self, _: &Context, filter: &SupplierFilter) -> DomainResult<u64> {
+        let mut builder: QueryBuilder<Sqlite> =
+            QueryBuilder::new("SELECT COUNT(*) FROM suppliers WHERE is_deleted = 0");
+        push_filter(&mut builder, filter);
+
+        let count: i64 = builder.build_query_scalar().fetch_one(&self.pool).await?;
+        Ok(count as u64)
     }
 
     async fn get_by_id(&self, _: &Context, id: i64) -> DomainResult<Option<Supplier>> {
         let query = sqlx::query_as::<_, SupplierDbSqlite>(
             r#"
-            SELECT id, created_at, updated_at, deleted_at, is_deleted, name, code, email, address, phone, npwp, npwp_name, metadata
+            SELECT id, created_at, updated_at, deleted_at, is_deleted, name, code, email, address, phone, npwp, npwp_name, metadata, created_by, updated_by
             FROM suppliers WHERE id = ? AND is_deleted = 0
             "#,
         )
         .bind(id)
         .fetch_optional(&self.pool);
 
-        Ok(query.await?.map(Supplier::from))
+        query.await?.map(Supplier::try_from).transpose()
+    }
+}
+
+fn push_filter(builder: &mut QueryBuilder<'_, Sqlite>, filter: &SupplierFilter) {
+    builder
+        .push_like_filter("name", &filter.name)
+        .push_like_filter("code", &filter.code)
+        .push_like_filter("email", &filter.email)
+        .push_like_filter("phone", &filter.phone)
+        .push_like_filter("npwp", &filter.npwp);
+}
+
+#[async_trait]
+impl ReadRepository<Supplier> for SqliteSupplierRepository {
+    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Supplier>> {
+        SupplierRepository::get_by_id(self, ctx, id).await
+    }
+
+    async fn get_all(
+        &self,
+        ctx: &Context,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<Supplier>> {
+        SupplierRepository::get_all(self, ctx, &SupplierFilter::default(), pagination).await
     }
 }
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::SqlitePool;
 
 use crate::{
@@ -13,6 +14,8 @@ pub struct TokenDbSqlite {
     pub user_id: i64,
     pub expired_at: String,
     pub token: String,
+    pub family_id: String,
+    pub used_at: Option<String>,
 }
 
 impl TryFrom<TokenDbSqlite> for Token {
@@ -22,12 +25,22 @@ impl TryFrom<TokenDbSqlite> for Token {
         let expired_at = chrono::DateTime::parse_from_rfc3339(&db.expired_at)
             .map_err(|e| Error::Internal(format!("Failed to parse expired_at: {}", e)))?
             .with_timezone(&chrono::Utc);
+        let used_at = db
+            .used_at
+            .map(|used_at| {
+                chrono::DateTime::parse_from_rfc3339(&used_at)
+                    .map(|d| d.with_timezone(&chrono::Utc))
+                    .map_err(|e| Error::Internal(format!("Failed to parse used_at: {}", e)))
+            })
+            .transpose()?;
 
         Ok(Token {
             id: db.id,
             user_id: db.user_id,
             expired_at,
             token: db.token,
+            family_id: db.family_id,
+            used_at,
         })
     }
 }
@@ -50,13 +63,15 @@ impl TokenRepository for SqliteTokenRepository {
 
         let query = sqlx::query(
             r#"
-            INSERT INTO refresh_tokens (user_id, expired_at, token)
-            VALUES (?, ?, ?)
+            INSERT INTO refresh_tokens (user_id, expired_at, token, family_id, used_at)
+            VALUES (?, ?, ?, ?, ?)
             "#,
         )
         .bind(token.user_id)
         .bind(&expired_at)
-        .bind(&token.token);
+        .bind(&token.token)
+        .bind(&token.family_id)
+        .bind(token.used_at.map(|used_at| used_at.to_rfc3339()));
 
         query.execute(&self.pool).await?;
 
@@ -77,7 +92,7 @@ impl TokenRepository for SqliteTokenRepository {
 
     async fn get_by_token(&self, _: &Context, token: &str) -> DomainResult<Option<Token>> {
         let query = sqlx::query_as::<_, TokenDbSqlite>(
-            "SELECT id, user_id, expired_at, token FROM refresh_tokens WHERE token = ?",
+            "SELECT id, user_id, expired_at, token, family_id, used_at FROM refresh_tokens WHERE token = ?",
         )
         .bind(token);
 
@@ -88,4 +103,47 @@ impl TokenRepository for SqliteTokenRepository {
             None => Ok(None),
         }
     }
+
+    async fn delete_by_user_id(&self, _: &Context, user_id: i64) -> DomainResult<u64> {
+        let result = sqlx::query("DELETE FROM refresh_tokens WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn mark_used(&self, _: &Context, id: i64) -> DomainResult<bool> {
+        // Conditional update so two requests racing with the same token can't
+        // both rotate it
+        let result =
+            sqlx::query("UPDATE refresh_tokens SET used_at = ? WHERE id = ? AND used_at IS NULL")
+                .bind(chrono::Utc::now().to_rfc3339())
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_family(&self, _: &Context, family_id: &str) -> DomainResult<u64> {
+        let result = sqlx::query("DELETE FROM refresh_tokens WHERE family_id = ?")
+            .bind(family_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_expired(&self, _: &Context, now: DateTime<Utc>) -> DomainResult<u64> {
+        // expired_at is RFC 3339 with a varying number of fractional digits,
+        // so compare as dates rather than strings
+        let result =
+            sqlx::query("DELETE FROM refresh_tokens WHERE julianday(expired_at) < julianday(?)")
+                .bind(now.to_rfc3339())
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected())
+    }
 }
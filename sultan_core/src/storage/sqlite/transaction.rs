@@ -1,18 +1,179 @@
+use std::{
+    future::Future,
+    ops::{Deref, DerefMut},
+    time::{Duration, Instant},
+};
+
 use async_trait::async_trait;
-use sqlx::{Sqlite, SqlitePool, Transaction};
+use rand::Rng;
+use sqlx::{Sqlite, SqliteConnection, SqlitePool, Transaction, TransactionManager as _};
 
 use crate::{
     domain::{DomainResult, Error},
     storage::transaction::TransactionManager,
 };
 
+/// Time spent in transaction begin, commit and rollback; queries run inside
+/// the transaction are not included
+pub const DB_TRANSACTION_OP_DURATION_SECONDS: &str = "db_transaction_op_duration_seconds";
+
+fn record_duration(operation: &'static str, started: Instant) {
+    metrics::histogram!(DB_TRANSACTION_OP_DURATION_SECONDS, "operation" => operation)
+        .record(started.elapsed().as_secs_f64());
+}
+
+/// Transaction handle returned by [`SqliteTransactionManager::begin`].
+///
+/// Derefs to the connection like `sqlx::Transaction`, so repositories use it
+/// the same way (`&mut **tx`). If it is dropped without `commit`/`rollback`,
+/// e.g. on an early return or a panic, the transaction is rolled back and a
+/// warning is logged.
+pub struct TxGuard<'a> {
+    tx: Option<Transaction<'a, Sqlite>>,
+}
+
+impl<'a> TxGuard<'a> {
+    fn new(tx: Transaction<'a, Sqlite>) -> Self {
+        Self { tx: Some(tx) }
+    }
+
+    fn take(mut self) -> Transaction<'a, Sqlite> {
+        self.tx
+            .take()
+            .expect("transaction is only taken by commit or rollback")
+    }
+}
+
+impl Deref for TxGuard<'_> {
+    type Target = SqliteConnection;
+
+    fn deref(&self) -> &Self::Target {
+        self.tx
+            .as_deref()
+            .expect("transaction used after commit or rollback")
+    }
+}
+
+impl DerefMut for TxGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.tx
+            .as_deref_mut()
+            .expect("transaction used after commit or rollback")
+    }
+}
+
+impl Drop for TxGuard<'_> {
+    fn drop(&mut self) {
+        // Dropping the sqlx transaction queues a ROLLBACK on its connection
+        // before the connection goes back to the pool
+        if self.tx.take().is_some() {
+            tracing::warn!("Transaction dropped without commit or rollback, rolling back");
+        }
+    }
+}
+
+/// How often, and how patiently, a write is retried when SQLite reports the
+/// database as busy or locked. Each wait doubles, starting at `base_delay`,
+/// with random jitter so competing writers do not retry in lockstep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusyRetry {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+}
+
+impl BusyRetry {
+    pub const DEFAULT_MAX_RETRIES: u32 = 3;
+    pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(20);
+
+    pub fn new(max_retries: u32, base_delay: Duration) -> Self {
+        Self {
+            max_retries,
+            base_delay,
+        }
+    }
+
+    /// Fail on the first busy error
+    pub fn disabled() -> Self {
+        Self::new(0, Duration::ZERO)
+    }
+
+    /// Wait before retry number `attempt` (starting at 0): the doubled base
+    /// delay plus up to the same amount again of jitter
+    fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self.base_delay.saturating_mul(1 << attempt.min(16));
+        let jitter = rand::thread_rng().gen_range(0..=backoff.as_micros() as u64);
+        backoff + Duration::from_micros(jitter)
+    }
+}
+
+impl Default for BusyRetry {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MAX_RETRIES, Self::DEFAULT_BASE_DELAY)
+    }
+}
+
+/// `SQLITE_BUSY`, `SQLITE_LOCKED` and their extended codes such as
+/// `SQLITE_BUSY_SNAPSHOT`
+pub fn is_busy(err: &sqlx::Error) -> bool {
+    let sqlx::Error::Database(e) = err else {
+        return false;
+    };
+    e.code()
+        .and_then(|code| code.parse::<i32>().ok())
+        .is_some_and(|code| matches!(code & 0xff, 5 | 6))
+}
+
+/// Run `operation` again while it fails with a busy error, up to
+/// `retry.max_retries` more times. Any other error is returned at once. The
+/// operation must be safe to repeat, e.g. a single statement on the pool.
+pub async fn retry_on_busy<T, F, Fut>(retry: &BusyRetry, mut operation: F) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    let mut attempt = 0;
+    loop {
+        match operation().await {
+            Err(e) if attempt < retry.max_retries && is_busy(&e) => {
+                let delay = retry.delay(attempt);
+                attempt += 1;
+                tracing::debug!(attempt, ?delay, "Database busy, retrying");
+                tokio::time::sleep(delay).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Error for a failed transaction step, calling out a database that stayed
+/// busy through every retry
+fn map_tx_error(step: &str, retry: &BusyRetry, e: sqlx::Error) -> Error {
+    if is_busy(&e) {
+        Error::Database(format!(
+            "Failed to {} transaction: database still busy after {} retries: {}",
+            step, retry.max_retries, e
+        ))
+    } else {
+        Error::Database(format!("Failed to {} transaction: {}", step, e))
+    }
+}
+
 pub struct SqliteTransactionManager {
     pool: SqlitePool,
+    busy_retry: BusyRetry,
 }
 
 impl SqliteTransactionManager {
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            busy_retry: BusyRetry::default(),
+        }
+    }
+
+    pub fn with_busy_retry(mut self, busy_retry: BusyRetry) -> Self {
+        self.busy_retry = busy_retry;
+        self
     }
 
     pub fn pool(&self) -> &SqlitePool {
@@ -23,26 +184,54 @@ impl SqliteTransactionManager {
 #[async_trait]
 impl TransactionManager for SqliteTransactionManager {
     type Transaction<'a>
-        = Transaction<'a, Sqlite>
+        = TxGuard<'a>
     where
         Self: 'a;
 
+    /// Transactions take the write lock up front with `BEGIN IMMEDIATE`. A
+    /// deferred transaction would only find out at its first write, when it
+    /// may already have read a snapshot that is stale and cannot be retried.
     async fn begin(&self) -> DomainResult<Self::Transaction<'_>> {
-        self.pool
-            .begin()
+        let started = Instant::now();
+        let result = retry_on_busy(&self.busy_retry, || self.pool.begin_with("BEGIN IMMEDIATE"))
             .await
-            .map_err(|e| Error::Database(format!("Failed to begin transaction: {}", e)))
+            .map(TxGuard::new)
+            .map_err(|e| map_tx_error("begin", &self.busy_retry, e));
+        record_duration("begin", started);
+        result
     }
 
     async fn commit<'a>(&self, tx: Self::Transaction<'a>) -> DomainResult<()> {
-        tx.commit()
-            .await
-            .map_err(|e| Error::Database(format!("Failed to commit transaction: {}", e)))
+        let started = Instant::now();
+        let mut tx = tx.take();
+        // A busy COMMIT leaves the transaction open, so it is retried on the
+        // connection directly; `Transaction::commit` would give it up. Once
+        // committed, dropping `tx` does not roll anything back.
+        let mut attempt = 0;
+        let result = loop {
+            match sqlx::sqlite::SqliteTransactionManager::commit(&mut tx).await {
+                Err(e) if attempt < self.busy_retry.max_retries && is_busy(&e) => {
+                    let delay = self.busy_retry.delay(attempt);
+                    attempt += 1;
+                    tracing::debug!(attempt, ?delay, "Database busy on commit, retrying");
+                    tokio::time::sleep(delay).await;
+                }
+                result => break result.map_err(|e| map_tx_error("commit", &self.busy_retry, e)),
+            }
+        };
+        drop(tx);
+        record_duration("commit", started);
+        result
     }
 
     async fn rollback<'a>(&self, tx: Self::Transaction<'a>) -> DomainResult<()> {
-        tx.rollback()
+        let started = Instant::now();
+        let result = tx
+            .take()
+            .rollback()
             .await
-            .map_err(|e| Error::Database(format!("Failed to rollback transaction: {}", e)))
+            .map_err(|e| Error::Database(format!("Failed to rollback transaction: {}", e)));
+        record_duration("rollback", started);
+        result
     }
 }
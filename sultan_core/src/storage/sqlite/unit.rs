@@ -4,12 +4,12 @@ use sqlx::{QueryBuilder, Sqlite, SqlitePool};
 
 use crate::{
     domain::{
-        Context, DomainResult,
+        Context, DomainResult, Error,
         model::product::{UnitOfMeasure, UnitOfMeasureCreate, UnitOfMeasureUpdate},
     },
     storage::{
         UnitOfMeasureRepository,
-        sqlite::{TableName, check_rows_affected, map_results, soft_delete},
+        sqlite::{TableName, check_rows_affected, map_results, soft_delete_by_on_pool},
     },
 };
 
@@ -36,17 +36,19 @@ pub struct UnitOfMeasureDbSqlite {
     pub description: Option<String>,
 }
 
-impl From<UnitOfMeasureDbSqlite> for UnitOfMeasure {
-    fn from(db: UnitOfMeasureDbSqlite) -> Self {
-        UnitOfMeasure {
+impl TryFrom<UnitOfMeasureDbSqlite> for UnitOfMeasure {
+    type Error = Error;
+
+    fn try_from(db: UnitOfMeasureDbSqlite) -> Result<Self, Self::Error> {
+        Ok(UnitOfMeasure {
             id: db.id,
-            created_at: super::parse_sqlite_date(&db.created_at),
-            updated_at: super::parse_sqlite_date(&db.updated_at),
-            deleted_at: db.deleted_at.map(|d| super::parse_sqlite_date(&d)),
+            created_at: super::parse_sqlite_date(&db.created_at)?,
+            updated_at: super::parse_sqlite_date(&db.updated_at)?,
+            deleted_at: super::parse_optional_sqlite_date(db.deleted_at.as_deref())?,
             is_deleted: db.is_deleted,
             name: db.name,
             description: db.description,
-        }
+        })
     }
 }
 
@@ -95,8 +97,8 @@ impl UnitOfMeasureRepository for SqliteUnitOfMeasureRepository {
         Ok(())
     }
 
-    async fn delete(&self, _: &Context, id: i64) -> DomainResult<()> {
-        let query = soft_delete(&self.pool, TableName::Units, id);
+    async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()> {
+        let query = soft_delete_by_on_pool(&self.pool, TableName::Units, id, ctx.actor_id());
         let result = query.await?;
         check_rows_affected(result.rows_affected(), "Unit of measure", id)
     }
@@ -111,7 +113,7 @@ impl UnitOfMeasureRepository for SqliteUnitOfMeasureRepository {
         )
         .fetch_all(&self.pool);
         let units = query.await?;
-        Ok(map_results(units))
+        map_results(units)
     }
 
     async fn get_by_id(&self, _: &Context, id: i64) -> DomainResult<Option<UnitOfMeasure>> {
@@ -125,6 +127,6 @@ impl UnitOfMeasureRepository for SqliteUnitOfMeasureRepository {
         .bind(id)
         .fetch_optional(&self.pool);
 
-        Ok(query.await?.map(UnitOfMeasure::from))
+        query.await?.map(UnitOfMeasure::try_from).transpose()
     }
 }
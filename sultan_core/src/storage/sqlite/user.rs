@@ -1,7 +1,8 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 use serde::Serialize;
-use sqlx::{QueryBuilder, Sqlite, SqlitePool, Transaction};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
 
 use crate::{
     domain::{
@@ -12,14 +13,18 @@ use crate::{
             user::{User, UserCreate, UserFilter, UserUpdate},
         },
     },
-    storage::user_repo::UserRepository,
+    storage::{
+        ReadRepository,
+        sqlite::{TableName, soft_delete_by, soft_delete_by_on_pool, transaction::TxGuard},
+        user_repo::UserRepository,
+    },
 };
 
 // ============================================================================
 // SQLite User Repository
 // ============================================================================
 
-const USER_COLUMNS: &str = "id, username, email, password, name, created_at, updated_at, deleted_at, is_deleted, photo, pin, address, phone";
+const USER_COLUMNS: &str = "id, username, email, password, name, created_at, updated_at, deleted_at, is_deleted, photo, pin, address, phone, is_enabled, failed_login_count, locked_until";
 
 // Macro to build the create user query to avoid duplication
 macro_rules! build_create_user_query {
@@ -37,21 +42,6 @@ macro_rules! build_create_user_query {
     };
 }
 
-// Macro to build the delete user query to avoid duplication
-macro_rules! build_delete_user_query {
-    ($user_id:expr) => {
-        sqlx::query(
-            r#"
-            UPDATE users SET
-                is_deleted = 1,
-                deleted_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
-            WHERE id = ? AND is_deleted = 0
-            "#,
-        )
-        .bind($user_id)
-    };
-}
-
 #[derive(Clone)]
 pub struct SqliteUserRepository {
     pool: SqlitePool,
@@ -94,26 +84,34 @@ pub struct UserDbSqlite {
     pub pin: Option<String>,
     pub address: Option<String>,
     pub phone: Option<String>,
+    pub is_enabled: bool,
+    pub failed_login_count: i32,
+    pub locked_until: Option<String>,
 }
 
-impl From<UserDbSqlite> for User {
-    fn from(user_db: UserDbSqlite) -> Self {
-        User {
+impl TryFrom<UserDbSqlite> for User {
+    type Error = Error;
+
+    fn try_from(user_db: UserDbSqlite) -> Result<Self, Self::Error> {
+        Ok(User {
             id: user_db.id,
             username: user_db.username,
             email: user_db.email,
             password: user_db.password,
             name: user_db.name,
-            created_at: super::parse_sqlite_date(&user_db.created_at),
-            updated_at: super::parse_sqlite_date(&user_db.updated_at),
-            deleted_at: user_db.deleted_at.map(|d| super::parse_sqlite_date(&d)),
+            created_at: super::parse_sqlite_date(&user_db.created_at)?,
+            updated_at: super::parse_sqlite_date(&user_db.updated_at)?,
+            deleted_at: super::parse_optional_sqlite_date(user_db.deleted_at.as_deref())?,
             is_deleted: user_db.is_deleted,
             photo: user_db.photo,
             pin: user_db.pin,
             address: user_db.address,
             phone: user_db.phone,
+            is_enabled: user_db.is_enabled,
+            failed_login_count: user_db.failed_login_count,
+            locked_until: super::parse_optional_sqlite_date(user_db.locked_until.as_deref())?,
             permissions: None,
-        }
+        })
     }
 }
 
@@ -139,7 +137,7 @@ impl From<PermissionDbSqlite> for Permission {
 
 // Implement the UserRepository trait for SQLite
 #[async_trait]
-impl<'a> UserRepository<Transaction<'a, Sqlite>> for SqliteUserRepository {
+impl<'a> UserRepository<TxGuard<'a>> for SqliteUserRepository {
     async fn create_user(&self, _: &Context, id: i64, user: &UserCreate) -> DomainResult<()> {
         build_create_user_query!(id, user)
             .execute(&self.pool)
@@ -152,7 +150,7 @@ impl<'a> UserRepository<Transaction<'a, Sqlite>> for SqliteUserRepository {
         _: &Context,
         id: i64,
         user: &UserCreate,
-        tx: &mut Transaction<'a, Sqlite>,
+        tx: &mut TxGuard<'a>,
     ) -> DomainResult<()> {
         build_create_user_query!(id, user)
             .execute(&mut **tx)
@@ -173,7 +171,7 @@ impl<'a> UserRepository<Transaction<'a, Sqlite>> for SqliteUserRepository {
             .bind(username)
             .fetch_optional(&self.pool);
 
-        Ok(query.await?.map(User::from))
+        query.await?.map(User::try_from).transpose()
     }
 
     async fn update_user(&self, _: &Context, id: i64, user: &UserUpdate) -> DomainResult<()> {
@@ -213,6 +211,11 @@ impl<'a> UserRepository<Transaction<'a, Sqlite>> for SqliteUserRepository {
                 .push("phone = ")
                 .push_bind_unseparated(user.phone.to_bind_value());
         }
+        if let Some(is_enabled) = user.is_enabled {
+            separated
+                .push("is_enabled = ")
+                .push_bind_unseparated(is_enabled);
+        }
         separated.push("updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')");
         builder.push(" WHERE id = ").push_bind(id);
         builder.push(" AND is_deleted = 0");
@@ -239,20 +242,19 @@ impl<'a> UserRepository<Transaction<'a, Sqlite>> for SqliteUserRepository {
         Self::check_rows_affected(result.rows_affected(), "User", id)
     }
 
-    async fn delete_user(&self, _: &Context, user_id: i64) -> DomainResult<()> {
-        let result = build_delete_user_query!(user_id)
-            .execute(&self.pool)
-            .await?;
+    async fn delete_user(&self, ctx: &Context, user_id: i64) -> DomainResult<()> {
+        let result =
+            soft_delete_by_on_pool(&self.pool, TableName::Users, user_id, ctx.actor_id()).await?;
         Self::check_rows_affected(result.rows_affected(), "User", user_id)
     }
 
     async fn delete_user_tx(
         &self,
-        _: &Context,
+        ctx: &Context,
         user_id: i64,
-        tx: &mut Transaction<'a, Sqlite>,
+        tx: &mut TxGuard<'a>,
     ) -> DomainResult<()> {
-        let result = build_delete_user_query!(user_id).execute(&mut **tx).await?;
+        let result = soft_delete_by(&mut **tx, TableName::Users, user_id, ctx.actor_id()).await?;
         Self::check_rows_affected(result.rows_affected(), "User", user_id)
     }
 
@@ -274,8 +276,8 @@ impl<'a> UserRepository<Transaction<'a, Sqlite>> for SqliteUserRepository {
         }
 
         if let Some(ref name) = filter.name {
-            sql.push_str(" AND name LIKE ?");
-            bindings.push(format!("%{}%", name));
+            sql.push_str(" AND name LIKE ? ESCAPE '\\'");
+            bindings.push(format!("%{}%", super::escape_like(name)));
         }
 
         if let Some(ref email) = filter.email {
@@ -296,7 +298,7 @@ impl<'a> UserRepository<Transaction<'a, Sqlite>> for SqliteUserRepository {
         let query = query.fetch_all(&self.pool);
 
         let users = query.await?;
-        Ok(super::map_results(users))
+        super::map_results(users)
     }
 
     async fn get_by_id(&self, _: &Context, user_id: i64) -> DomainResult<Option<User>> {
@@ -308,7 +310,7 @@ impl<'a> UserRepository<Transaction<'a, Sqlite>> for SqliteUserRepository {
             .bind(user_id)
             .fetch_optional(&self.pool);
 
-        Ok(query.await?.map(User::from))
+        query.await?.map(User::try_from).transpose()
     }
 
     async fn save_user_permission(
@@ -400,6 +402,82 @@ impl<'a> UserRepository<Transaction<'a, Sqlite>> for SqliteUserRepository {
             .fetch_all(&self.pool);
 
         let permissions_db = query.await?;
-        Ok(super::map_results(permissions_db))
+        Ok(permissions_db.into_iter().map(Permission::from).collect())
+    }
+
+    async fn record_failed_login(
+        &self,
+        _: &Context,
+        user_id: i64,
+        max_attempts: i32,
+        lock_until: DateTime<Utc>,
+    ) -> DomainResult<()> {
+        // Once a lock has run out the count starts over, otherwise the next
+        // wrong password would lock the account again straight away
+        let query = sqlx::query(
+            r#"
+            WITH now AS (SELECT strftime('%Y-%m-%dT%H:%M:%fZ', 'now') AS ts)
+            UPDATE users SET
+                failed_login_count = CASE
+                    WHEN locked_until IS NOT NULL AND locked_until <= (SELECT ts FROM now) THEN 1
+                    ELSE failed_login_count + 1
+                END,
+                locked_until = CASE
+                    WHEN (CASE
+                        WHEN locked_until IS NOT NULL AND locked_until <= (SELECT ts FROM now) THEN 1
+                        ELSE failed_login_count + 1
+                    END) >= ? THEN ?
+                    WHEN locked_until IS NOT NULL AND locked_until <= (SELECT ts FROM now) THEN NULL
+                    ELSE locked_until
+                END,
+                updated_at = (SELECT ts FROM now)
+            WHERE id = ? AND is_deleted = 0
+            "#,
+        )
+        .bind(max_attempts)
+        .bind(super::format_sqlite_date(&lock_until))
+        .bind(user_id)
+        .execute(&self.pool);
+
+        let result = query.await?;
+        Self::check_rows_affected(result.rows_affected(), "User", user_id)
+    }
+
+    async fn reset_failed_logins(&self, _: &Context, user_id: i64) -> DomainResult<()> {
+        let query = sqlx::query(
+            r#"
+            UPDATE users SET
+                failed_login_count = 0,
+                locked_until = NULL,
+                updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+            WHERE id = ? AND is_deleted = 0
+            "#,
+        )
+        .bind(user_id)
+        .execute(&self.pool);
+
+        let result = query.await?;
+        Self::check_rows_affected(result.rows_affected(), "User", user_id)
+    }
+}
+
+#[async_trait]
+impl ReadRepository<User> for SqliteUserRepository {
+    async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<User>> {
+        <Self as UserRepository<TxGuard<'_>>>::get_by_id(self, ctx, id).await
+    }
+
+    async fn get_all(
+        &self,
+        ctx: &Context,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<User>> {
+        <Self as UserRepository<TxGuard<'_>>>::get_all(
+            self,
+            ctx,
+            UserFilter::default(),
+            pagination.clone(),
+        )
+        .await
     }
 }
@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+
+use crate::domain::{
+    Context, DomainResult,
+    model::{
+        pagination::PaginationOptions,
+        stock::{StockAdjustment, StockLevel},
+    },
+};
+
+#[async_trait]
+pub trait StockRepository<Tx>: Send + Sync {
+    /// Apply the adjustment to the branch stock and record it. Returns the
+    /// quantity on hand afterwards. Fails with `ValidationError` when the
+    /// stock would go negative; the caller must then roll `tx` back.
+    async fn adjust(
+        &self,
+        ctx: &Context,
+        adjustment: &StockAdjustment,
+        tx: &mut Tx,
+    ) -> DomainResult<i64>;
+    /// Live variants stocked at the branch whose quantity is at or below
+    /// `threshold`, lowest quantity first. Variants with no stock row at the
+    /// branch are not tracked there and are left out.
+    async fn list_low(
+        &self,
+        ctx: &Context,
+        branch_id: i64,
+        threshold: i64,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<StockLevel>>;
+}
@@ -19,5 +19,7 @@ pub trait SupplierRepository: Send + Sync {
         filter: &SupplierFilter,
         pagination: &PaginationOptions,
     ) -> DomainResult<Vec<Supplier>>;
+    /// Number of suppliers matching `filter`, ignoring pagination
+    async fn count(&self, ctx: &Context, filter: &SupplierFilter) -> DomainResult<u64>;
     async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Supplier>>;
 }
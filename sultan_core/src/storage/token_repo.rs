@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 use crate::domain::{Context, DomainResult, model::token::Token};
 
@@ -7,4 +8,14 @@ pub trait TokenRepository: Send + Sync {
     async fn save(&self, ctx: &Context, token: &Token) -> DomainResult<()>;
     async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()>;
     async fn get_by_token(&self, ctx: &Context, token: &str) -> DomainResult<Option<Token>>;
+    /// Revoke every refresh token of a user. Returns how many were removed.
+    async fn delete_by_user_id(&self, ctx: &Context, user_id: i64) -> DomainResult<u64>;
+    /// Mark a token as rotated. Returns false if it already was, meaning the
+    /// token is being presented a second time.
+    async fn mark_used(&self, ctx: &Context, id: i64) -> DomainResult<bool>;
+    /// Revoke every token of a family. Returns how many were removed.
+    async fn delete_family(&self, ctx: &Context, family_id: &str) -> DomainResult<u64>;
+    /// Remove every token that expired before `now`. Returns how many were
+    /// removed.
+    async fn delete_expired(&self, ctx: &Context, now: DateTime<Utc>) -> DomainResult<u64>;
 }
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 use crate::domain::Context;
 use crate::domain::DomainResult;
@@ -57,4 +58,15 @@ pub trait UserRepository<Tx>: Send + Sync {
         ctx: &Context,
         user_id: i64,
     ) -> DomainResult<Vec<Permission>>;
+    /// Count a failed login and lock the account until `lock_until` once the
+    /// count reaches `max_attempts`.
+    async fn record_failed_login(
+        &self,
+        ctx: &Context,
+        user_id: i64,
+        max_attempts: i32,
+        lock_until: DateTime<Utc>,
+    ) -> DomainResult<()>;
+    /// Clear the failed login counter and any lock.
+    async fn reset_failed_logins(&self, ctx: &Context, user_id: i64) -> DomainResult<()>;
 }
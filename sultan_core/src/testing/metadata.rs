@@ -0,0 +1,13 @@
+//! Metadata schemas shared by the validation tests.
+
+use crate::domain::model::metadata::MetadataSchema;
+
+/// Object schema that requires a string `sku`
+pub fn sku_schema() -> MetadataSchema {
+    MetadataSchema::new(&serde_json::json!({
+        "type": "object",
+        "properties": { "sku": { "type": "string" } },
+        "required": ["sku"]
+    }))
+    .unwrap()
+}
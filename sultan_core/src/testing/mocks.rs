@@ -0,0 +1,89 @@
+//! `mockall` mocks of the repository traits, shared by the service unit tests
+//! and integration tests.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use mockall::mock;
+
+use crate::{
+    domain::{
+        Context, DomainResult,
+        model::{
+            bulk::BulkResult,
+            category::{Category, CategoryCreate, CategoryUpdate},
+            customer::{Customer, CustomerCreate, CustomerFilter, CustomerUpdate},
+            pagination::PaginationOptions,
+            permission::Permission,
+            supplier::{Supplier, SupplierCreate, SupplierFilter, SupplierUpdate},
+            user::{User, UserCreate, UserFilter, UserUpdate},
+        },
+    },
+    storage::{CategoryRepository, CustomerRepository, SupplierRepository, UserRepository},
+};
+
+mock! {
+    pub CustomerRepo {}
+    #[async_trait]
+    impl CustomerRepository for CustomerRepo {
+        async fn create(&self, ctx: &Context, id: i64, customer: &CustomerCreate) -> DomainResult<()>;
+        async fn update(&self, ctx: &Context, id: i64, customer: &CustomerUpdate) -> DomainResult<()>;
+        async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()>;
+        async fn delete_many(&self, ctx: &Context, ids: &[i64], all_or_nothing: bool) -> DomainResult<BulkResult>;
+        async fn get_by_number(&self, ctx: &Context, number: &str) -> DomainResult<Option<Customer>>;
+        async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Customer>>;
+        async fn get_by_ids(&self, ctx: &Context, ids: &[i64]) -> DomainResult<Vec<Customer>>;
+        async fn get_all(&self, ctx: &Context, filter: &CustomerFilter, pagination: &PaginationOptions) -> DomainResult<Vec<Customer>>;
+        async fn count(&self, ctx: &Context, filter: &CustomerFilter) -> DomainResult<u64>;
+        async fn get_all_with_total(&self, ctx: &Context, filter: &CustomerFilter, pagination: &PaginationOptions) -> DomainResult<(Vec<Customer>, u64)>;
+    }
+}
+
+mock! {
+    pub CategoryRepo {}
+    #[async_trait]
+    impl CategoryRepository for CategoryRepo {
+        async fn create(&self, ctx: &Context, id: i64, category: &CategoryCreate) -> DomainResult<()>;
+        async fn update(&self, ctx: &Context, id: i64, category: &CategoryUpdate) -> DomainResult<()>;
+        async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()>;
+        async fn get_all(&self, ctx: &Context, branch_id: Option<i64>) -> DomainResult<Vec<Category>>;
+        async fn count(&self, ctx: &Context, branch_id: Option<i64>) -> DomainResult<u64>;
+        async fn get_by_id(&self, ctx: &Context, id: i64, branch_id: Option<i64>) -> DomainResult<Option<Category>>;
+        async fn assign_to_branch(&self, ctx: &Context, id: i64, branch_id: i64) -> DomainResult<()>;
+        async fn remove_from_branch(&self, ctx: &Context, id: i64, branch_id: i64) -> DomainResult<()>;
+    }
+}
+
+mock! {
+    pub SupplierRepo {}
+    #[async_trait]
+    impl SupplierRepository for SupplierRepo {
+        async fn create(&self, ctx: &Context, id: i64, supplier: &SupplierCreate) -> DomainResult<()>;
+        async fn update(&self, ctx: &Context, id: i64, supplier: &SupplierUpdate) -> DomainResult<()>;
+        async fn delete(&self, ctx: &Context, id: i64) -> DomainResult<()>;
+        async fn get_all(&self, ctx: &Context, filter: &SupplierFilter, pagination: &PaginationOptions) -> DomainResult<Vec<Supplier>>;
+        async fn count(&self, ctx: &Context, filter: &SupplierFilter) -> DomainResult<u64>;
+        async fn get_by_id(&self, ctx: &Context, id: i64) -> DomainResult<Option<Supplier>>;
+    }
+}
+
+// Mocks don't use real transactions, so the transaction is the unit type
+mock! {
+    pub UserRepo {}
+    #[async_trait]
+    impl UserRepository<()> for UserRepo {
+        async fn create_user(&self, ctx: &Context, id: i64, user: &UserCreate) -> DomainResult<()>;
+        async fn create_user_tx(&self, ctx: &Context, id: i64, user: &UserCreate, tx: &mut ()) -> DomainResult<()>;
+        async fn get_user_by_username(&self, ctx: &Context, username: &str) -> DomainResult<Option<User>>;
+        async fn update_user(&self, ctx: &Context, id: i64, user: &UserUpdate) -> DomainResult<()>;
+        async fn update_password(&self, ctx: &Context, id: i64, password_hash: &str) -> DomainResult<()>;
+        async fn delete_user(&self, ctx: &Context, user_id: i64) -> DomainResult<()>;
+        async fn delete_user_tx(&self, ctx: &Context, user_id: i64, tx: &mut ()) -> DomainResult<()>;
+        async fn get_all(&self, ctx: &Context, filter: UserFilter, pagination: PaginationOptions) -> DomainResult<Vec<User>>;
+        async fn get_by_id(&self, ctx: &Context, user_id: i64) -> DomainResult<Option<User>>;
+        async fn save_user_permission(&self, ctx: &Context, user_id: i64, branch_id: Option<i64>, resource: i32, action: i32) -> DomainResult<()>;
+        async fn delete_user_permission(&self, ctx: &Context, user_id: i64, branch_id: Option<i64>, permission: i32) -> DomainResult<()>;
+        async fn get_user_permission(&self, ctx: &Context, user_id: i64) -> DomainResult<Vec<Permission>>;
+        async fn record_failed_login(&self, ctx: &Context, user_id: i64, max_attempts: i32, lock_until: DateTime<Utc>) -> DomainResult<()>;
+        async fn reset_failed_logins(&self, ctx: &Context, user_id: i64) -> DomainResult<()>;
+    }
+}
@@ -1 +1,3 @@
+pub mod metadata;
+pub mod mocks;
 pub mod storage;
@@ -4,9 +4,10 @@ use crate::{
         model::{
             Update,
             branch::{BranchCreate, BranchUpdate},
+            user::UserCreate,
         },
     },
-    storage::BranchRepository,
+    storage::{BranchRepository, SqliteUserRepository, UserRepository},
 };
 
 pub async fn create_sqlite_branch_repo() -> (Context, impl BranchRepository) {
@@ -17,6 +18,55 @@ pub async fn create_sqlite_branch_repo() -> (Context, impl BranchRepository) {
     )
 }
 
+pub async fn create_sqlite_branch_repo_with_users()
+-> (Context, impl BranchRepository, SqliteUserRepository) {
+    let pool = super::init_sqlite_pool().await;
+    (
+        Context::new(),
+        crate::storage::sqlite::branch::SqliteBranchRepository::new(pool.clone()),
+        SqliteUserRepository::new(pool),
+    )
+}
+
+async fn create_branch_and_user<B: BranchRepository, Tx, U: UserRepository<Tx>>(
+    ctx: &Context,
+    repo: &B,
+    user_repo: &U,
+    code: &str,
+) -> (i64, i64) {
+    let branch_id = super::generate_test_id().await;
+    let branch = BranchCreate {
+        is_main: false,
+        name: format!("Branch {}", code),
+        code: code.to_string(),
+        address: None,
+        phone: None,
+        npwp: None,
+        image: None,
+    };
+    repo.create(ctx, branch_id, &branch)
+        .await
+        .expect("Failed to create branch");
+
+    let user_id = super::generate_test_id().await;
+    let user = UserCreate {
+        username: format!("user_{}", user_id),
+        password: "hashed_password".to_string(),
+        name: "Branch User".to_string(),
+        email: None,
+        photo: None,
+        pin: None,
+        address: None,
+        phone: None,
+    };
+    user_repo
+        .create_user(ctx, user_id, &user)
+        .await
+        .expect("Failed to create user");
+
+    (branch_id, user_id)
+}
+
 pub async fn branch_test_repo_integration<B: BranchRepository>(ctx: &Context, repo: B) {
     let id = super::generate_test_id().await;
     let branch = BranchCreate {
@@ -353,3 +403,158 @@ pub async fn branch_test_update_address_scenarios<B: BranchRepository>(ctx: &Con
         "Phone should still remain unchanged"
     );
 }
+
+pub async fn branch_test_assign_user<B: BranchRepository, Tx, U: UserRepository<Tx>>(
+    ctx: &Context,
+    repo: B,
+    user_repo: U,
+) {
+    let (branch_id, user_id) = create_branch_and_user(ctx, &repo, &user_repo, "ASSIGN").await;
+
+    let branches = repo
+        .list_branches_for_user(ctx, user_id)
+        .await
+        .expect("Failed to list branches");
+    assert!(branches.is_empty());
+
+    repo.assign_user(ctx, branch_id, user_id)
+        .await
+        .expect("Failed to assign user");
+
+    let branches = repo
+        .list_branches_for_user(ctx, user_id)
+        .await
+        .expect("Failed to list branches");
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].id, branch_id);
+}
+
+pub async fn branch_test_assign_user_duplicate<B: BranchRepository, Tx, U: UserRepository<Tx>>(
+    ctx: &Context,
+    repo: B,
+    user_repo: U,
+) {
+    let (branch_id, user_id) = create_branch_and_user(ctx, &repo, &user_repo, "DUP").await;
+
+    repo.assign_user(ctx, branch_id, user_id)
+        .await
+        .expect("Failed to assign user");
+    // Assigning the same user again is a no-op
+    repo.assign_user(ctx, branch_id, user_id)
+        .await
+        .expect("Duplicate assign should succeed");
+
+    let branches = repo
+        .list_branches_for_user(ctx, user_id)
+        .await
+        .expect("Failed to list branches");
+    assert_eq!(branches.len(), 1);
+}
+
+pub async fn branch_test_assign_unknown_user<B: BranchRepository, Tx, U: UserRepository<Tx>>(
+    ctx: &Context,
+    repo: B,
+    user_repo: U,
+) {
+    let (branch_id, user_id) = create_branch_and_user(ctx, &repo, &user_repo, "UNKNOWN").await;
+
+    let unknown_id = super::generate_test_id().await;
+    let result = repo.assign_user(ctx, branch_id, unknown_id).await;
+    assert!(matches!(result, Err(crate::domain::Error::NotFound(_))));
+
+    // Deleted users can't be assigned either
+    user_repo
+        .delete_user(ctx, user_id)
+        .await
+        .expect("Failed to delete user");
+    let result = repo.assign_user(ctx, branch_id, user_id).await;
+    assert!(matches!(result, Err(crate::domain::Error::NotFound(_))));
+}
+
+pub async fn branch_test_unassign_user<B: BranchRepository, Tx, U: UserRepository<Tx>>(
+    ctx: &Context,
+    repo: B,
+    user_repo: U,
+) {
+    let (branch_id, user_id) = create_branch_and_user(ctx, &repo, &user_repo, "UNASSIGN").await;
+
+    repo.assign_user(ctx, branch_id, user_id)
+        .await
+        .expect("Failed to assign user");
+    repo.unassign_user(ctx, branch_id, user_id)
+        .await
+        .expect("Failed to unassign user");
+
+    let branches = repo
+        .list_branches_for_user(ctx, user_id)
+        .await
+        .expect("Failed to list branches");
+    assert!(branches.is_empty());
+
+    // Removing a membership that no longer exists is NotFound
+    let result = repo.unassign_user(ctx, branch_id, user_id).await;
+    assert!(matches!(result, Err(crate::domain::Error::NotFound(_))));
+}
+
+pub async fn branch_test_list_branches_for_user_skips_deleted<
+    B: BranchRepository,
+    Tx,
+    U: UserRepository<Tx>,
+>(
+    ctx: &Context,
+    repo: B,
+    user_repo: U,
+) {
+    let (branch_id, user_id) = create_branch_and_user(ctx, &repo, &user_repo, "DELETED").await;
+
+    repo.assign_user(ctx, branch_id, user_id)
+        .await
+        .expect("Failed to assign user");
+    repo.delete(ctx, branch_id)
+        .await
+        .expect("Failed to delete branch");
+
+    let branches = repo
+        .list_branches_for_user(ctx, user_id)
+        .await
+        .expect("Failed to list branches");
+    assert!(branches.is_empty());
+}
+
+pub async fn branch_test_update_touches_updated_at<B: BranchRepository>(ctx: &Context, repo: B) {
+    let id = super::generate_test_id().await;
+    let branch = BranchCreate {
+        is_main: false,
+        name: "Touch Branch".to_string(),
+        code: "TOUCH".to_string(),
+        address: None,
+        phone: None,
+        npwp: None,
+        image: None,
+    };
+    repo.create(ctx, id, &branch)
+        .await
+        .expect("Failed to create branch");
+    let before = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get branch")
+        .expect("Branch not found");
+
+    super::wait_for_clock_tick().await;
+    let update = BranchUpdate {
+        name: Some("Touched Branch".to_string()),
+        ..Default::default()
+    };
+    repo.update(ctx, id, &update)
+        .await
+        .expect("Failed to update branch");
+
+    let after = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get branch")
+        .expect("Branch not found");
+    assert!(after.updated_at > before.updated_at);
+    assert_eq!(after.created_at, before.created_at);
+}
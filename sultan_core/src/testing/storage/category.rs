@@ -1,12 +1,13 @@
 use crate::{
     domain::{
-        Context,
+        Context, Error,
         model::{
             Update,
-            category::{CategoryCreate, CategoryUpdate},
+            branch::BranchCreate,
+            category::{Category, CategoryCreate, CategoryUpdate},
         },
     },
-    storage::CategoryRepository,
+    storage::{BranchRepository, CategoryRepository},
 };
 
 pub async fn create_sqlite_category_repo() -> (Context, impl CategoryRepository) {
@@ -17,6 +18,16 @@ pub async fn create_sqlite_category_repo() -> (Context, impl CategoryRepository)
     )
 }
 
+pub async fn create_sqlite_category_repo_with_branches()
+-> (Context, impl CategoryRepository, impl BranchRepository) {
+    let pool = super::init_sqlite_pool().await;
+    (
+        Context::new(),
+        crate::storage::sqlite::category::SqliteCategoryRepository::new(pool.clone()),
+        crate::storage::sqlite::branch::SqliteBranchRepository::new(pool),
+    )
+}
+
 // =============================================================================
 // Basic CRUD Tests
 // =============================================================================
@@ -33,7 +44,7 @@ pub async fn category_test_create<C: CategoryRepository>(ctx: &Context, repo: C)
         .expect("Failed to create category");
 
     let category = repo
-        .get_by_id(ctx, id)
+        .get_by_id(ctx, id, None)
         .await
         .expect("Failed to get category")
         .expect("Category not found");
@@ -59,7 +70,7 @@ pub async fn category_test_create_without_description<C: CategoryRepository>(
         .expect("Failed to create category");
 
     let category = repo
-        .get_by_id(ctx, id)
+        .get_by_id(ctx, id, None)
         .await
         .expect("Failed to get category")
         .expect("Category not found");
@@ -89,7 +100,7 @@ pub async fn category_test_update_name<C: CategoryRepository>(ctx: &Context, rep
         .expect("Failed to update category");
 
     let category = repo
-        .get_by_id(ctx, id)
+        .get_by_id(ctx, id, None)
         .await
         .expect("Failed to get category")
         .expect("Category not found");
@@ -123,7 +134,7 @@ pub async fn category_test_update_description<C: CategoryRepository>(ctx: &Conte
         .expect("Failed to update category");
 
     let category = repo
-        .get_by_id(ctx, id)
+        .get_by_id(ctx, id, None)
         .await
         .expect("Failed to get category")
         .expect("Category not found");
@@ -158,7 +169,10 @@ pub async fn category_test_delete<C: CategoryRepository>(ctx: &Context, repo: C)
         .await
         .expect("Failed to delete category");
 
-    let category = repo.get_by_id(ctx, id).await.expect("Failed to query");
+    let category = repo
+        .get_by_id(ctx, id, None)
+        .await
+        .expect("Failed to query");
     assert!(category.is_none(), "Deleted category should not be found");
 }
 
@@ -170,7 +184,7 @@ pub async fn category_test_delete_non_existent<C: CategoryRepository>(ctx: &Cont
 
 pub async fn category_test_get_by_id_not_found<C: CategoryRepository>(ctx: &Context, repo: C) {
     let result = repo
-        .get_by_id(ctx, 999999)
+        .get_by_id(ctx, 999999, None)
         .await
         .expect("Query should succeed");
 
@@ -178,7 +192,7 @@ pub async fn category_test_get_by_id_not_found<C: CategoryRepository>(ctx: &Cont
 }
 
 pub async fn category_test_get_all_empty<C: CategoryRepository>(ctx: &Context, repo: C) {
-    let categories = repo.get_all(ctx).await.expect("Failed to get all");
+    let categories = repo.get_all(ctx, None).await.expect("Failed to get all");
     // Fresh database should have no categories
     assert!(categories.is_empty());
 }
@@ -222,7 +236,7 @@ pub async fn category_test_get_all_multiple<C: CategoryRepository>(ctx: &Context
     .await
     .expect("Failed to create category 3");
 
-    let categories = repo.get_all(ctx).await.expect("Failed to get all");
+    let categories = repo.get_all(ctx, None).await.expect("Failed to get all");
     assert_eq!(categories.len(), 3);
 }
 
@@ -260,7 +274,7 @@ pub async fn category_test_create_with_parent<C: CategoryRepository>(ctx: &Conte
 
     // Get parent with children
     let parent = repo
-        .get_by_id(ctx, parent_id)
+        .get_by_id(ctx, parent_id, None)
         .await
         .expect("Failed to get parent")
         .expect("Parent not found");
@@ -312,7 +326,7 @@ pub async fn category_test_create_nested<C: CategoryRepository>(ctx: &Context, r
     .expect("Failed to create level 3");
 
     let root = repo
-        .get_by_id(ctx, level1)
+        .get_by_id(ctx, level1, None)
         .await
         .expect("Failed to get root")
         .expect("Root not found");
@@ -378,7 +392,7 @@ pub async fn category_test_get_all_tree_structure<C: CategoryRepository>(ctx: &C
     .await
     .expect("Failed to create child 2");
 
-    let categories = repo.get_all(ctx).await.expect("Failed to get all");
+    let categories = repo.get_all(ctx, None).await.expect("Failed to get all");
 
     // Should only return root categories
     assert_eq!(categories.len(), 2);
@@ -441,7 +455,7 @@ pub async fn category_test_update_parent<C: CategoryRepository>(ctx: &Context, r
 
     // Verify child is now under parent2
     let parent2_cat = repo
-        .get_by_id(ctx, parent2)
+        .get_by_id(ctx, parent2, None)
         .await
         .expect("Failed to get parent 2")
         .expect("Parent 2 not found");
@@ -452,7 +466,7 @@ pub async fn category_test_update_parent<C: CategoryRepository>(ctx: &Context, r
 
     // Verify parent1 has no children
     let parent1_cat = repo
-        .get_by_id(ctx, parent1)
+        .get_by_id(ctx, parent1, None)
         .await
         .expect("Failed to get parent 1")
         .expect("Parent 1 not found");
@@ -512,7 +526,7 @@ pub async fn category_test_multiple_children<C: CategoryRepository>(ctx: &Contex
     .expect("Failed to create child 3");
 
     let parent_cat = repo
-        .get_by_id(ctx, parent)
+        .get_by_id(ctx, parent, None)
         .await
         .expect("Failed to get parent")
         .expect("Parent not found");
@@ -550,7 +564,7 @@ pub async fn category_test_create_at_max_depth<C: CategoryRepository>(ctx: &Cont
     // All 5 levels should be created successfully
     for id in &ids {
         let cat = repo
-            .get_by_id(ctx, *id)
+            .get_by_id(ctx, *id, None)
             .await
             .expect("Failed to get category")
             .expect("Category not found");
@@ -773,7 +787,7 @@ pub async fn category_test_move_within_depth_limit<C: CategoryRepository>(ctx: &
 
     // Verify the structure
     let root = repo
-        .get_by_id(ctx, level1)
+        .get_by_id(ctx, level1, None)
         .await
         .expect("Failed to get root")
         .expect("Root not found");
@@ -822,7 +836,7 @@ pub async fn category_test_deleted_not_in_get_all<C: CategoryRepository>(ctx: &C
         .await
         .expect("Failed to delete category");
 
-    let categories = repo.get_all(ctx).await.expect("Failed to get all");
+    let categories = repo.get_all(ctx, None).await.expect("Failed to get all");
     assert_eq!(categories.len(), 1);
     assert_eq!(categories[0].id, id2);
 }
@@ -874,7 +888,7 @@ pub async fn category_test_deleted_child_not_returned<C: CategoryRepository>(
         .expect("Failed to delete child 1");
 
     let parent_cat = repo
-        .get_by_id(ctx, parent)
+        .get_by_id(ctx, parent, None)
         .await
         .expect("Failed to get parent")
         .expect("Parent not found");
@@ -982,7 +996,7 @@ pub async fn category_test_get_child_by_id<C: CategoryRepository>(ctx: &Context,
 
     // Get child by id - should include its children (grandchild)
     let child_cat = repo
-        .get_by_id(ctx, child)
+        .get_by_id(ctx, child, None)
         .await
         .expect("Failed to get child")
         .expect("Child not found");
@@ -1008,7 +1022,7 @@ pub async fn category_test_without_children<C: CategoryRepository>(ctx: &Context
     .expect("Failed to create category");
 
     let cat = repo
-        .get_by_id(ctx, id)
+        .get_by_id(ctx, id, None)
         .await
         .expect("Failed to get category")
         .expect("Category not found");
@@ -1086,7 +1100,7 @@ pub async fn category_test_deep_nested_tree_retrieval<C: CategoryRepository>(
 
     // Get from root and verify entire tree is returned
     let root = repo
-        .get_by_id(ctx, level1)
+        .get_by_id(ctx, level1, None)
         .await
         .expect("Failed to get root")
         .expect("Root not found");
@@ -1100,3 +1114,232 @@ pub async fn category_test_deep_nested_tree_retrieval<C: CategoryRepository>(
     assert_eq!(l5.name, "Level 5".to_string());
     assert!(l5.children.is_none()); // Level 5 has no children
 }
+
+pub async fn category_test_update_touches_updated_at<C: CategoryRepository>(
+    ctx: &Context,
+    repo: C,
+) {
+    let id = super::generate_test_id().await;
+    let category = CategoryCreate {
+        name: "Touch".to_string(),
+        description: None,
+        parent_id: None,
+    };
+    repo.create(ctx, id, &category)
+        .await
+        .expect("Failed to create category");
+    let before = repo
+        .get_by_id(ctx, id, None)
+        .await
+        .expect("Failed to get category")
+        .expect("Category not found");
+
+    super::wait_for_clock_tick().await;
+    let update = CategoryUpdate {
+        name: Some("Touched".to_string()),
+        description: Update::Unchanged,
+        parent_id: Update::Unchanged,
+    };
+    repo.update(ctx, id, &update)
+        .await
+        .expect("Failed to update category");
+
+    let after = repo
+        .get_by_id(ctx, id, None)
+        .await
+        .expect("Failed to get category")
+        .expect("Category not found");
+    assert!(after.updated_at > before.updated_at);
+    assert_eq!(after.created_at, before.created_at);
+}
+
+// =============================================================================
+// Branch Scoping Tests
+// =============================================================================
+
+async fn create_branch<B: BranchRepository>(ctx: &Context, repo: &B, code: &str) -> i64 {
+    let id = super::generate_test_id().await;
+    let branch = BranchCreate {
+        is_main: false,
+        name: format!("Branch {}", code),
+        code: code.to_string(),
+        address: None,
+        phone: None,
+        npwp: None,
+        image: None,
+    };
+    repo.create(ctx, id, &branch)
+        .await
+        .expect("Failed to create branch");
+    id
+}
+
+async fn create_named<C: CategoryRepository>(
+    ctx: &Context,
+    repo: &C,
+    name: &str,
+    parent_id: Option<i64>,
+) -> i64 {
+    let id = super::generate_test_id().await;
+    let category = CategoryCreate {
+        name: name.to_string(),
+        description: None,
+        parent_id,
+    };
+    repo.create(ctx, id, &category)
+        .await
+        .expect("Failed to create category");
+    id
+}
+
+pub async fn category_test_branch_scoped_reads<C: CategoryRepository, B: BranchRepository>(
+    ctx: &Context,
+    repo: C,
+    branch_repo: B,
+) {
+    let branch_a = create_branch(ctx, &branch_repo, "A").await;
+    let branch_b = create_branch(ctx, &branch_repo, "B").await;
+
+    let food = create_named(ctx, &repo, "Food", None).await;
+    let snacks = create_named(ctx, &repo, "Snacks", Some(food)).await;
+    let frozen = create_named(ctx, &repo, "Frozen", Some(food)).await;
+    let tools = create_named(ctx, &repo, "Tools", None).await;
+    create_named(ctx, &repo, "Unassigned", None).await;
+
+    for id in [food, snacks] {
+        repo.assign_to_branch(ctx, id, branch_a)
+            .await
+            .expect("Failed to assign category");
+    }
+    // Assigned to B, but its parent is not, so B cannot reach it
+    repo.assign_to_branch(ctx, frozen, branch_b)
+        .await
+        .expect("Failed to assign category");
+    repo.assign_to_branch(ctx, tools, branch_b)
+        .await
+        .expect("Failed to assign category");
+
+    let all = repo.get_all(ctx, None).await.expect("Failed to get all");
+    assert_eq!(all.len(), 3);
+
+    let seen_by_a = repo
+        .get_all(ctx, Some(branch_a))
+        .await
+        .expect("Failed to get all");
+    assert_eq!(seen_by_a.len(), 1);
+    assert_eq!(seen_by_a[0].id, food);
+    let children = seen_by_a[0].children.as_ref().expect("Food has children");
+    assert_eq!(
+        children.iter().map(|c| c.id).collect::<Vec<_>>(),
+        vec![snacks]
+    );
+
+    let seen_by_b = repo
+        .get_all(ctx, Some(branch_b))
+        .await
+        .expect("Failed to get all");
+    assert_eq!(
+        seen_by_b.iter().map(|c| c.id).collect::<Vec<_>>(),
+        vec![tools]
+    );
+
+    // get_by_id follows the same rules
+    let food_for_a = repo
+        .get_by_id(ctx, food, Some(branch_a))
+        .await
+        .expect("Failed to get category")
+        .expect("Category not found");
+    assert_eq!(food_for_a.children.map(|c| c.len()), Some(1));
+    assert!(
+        repo.get_by_id(ctx, tools, Some(branch_a))
+            .await
+            .expect("Failed to get category")
+            .is_none()
+    );
+    let tools_global = repo
+        .get_by_id(ctx, tools, None)
+        .await
+        .expect("Failed to get category");
+    assert!(tools_global.is_some());
+}
+
+pub async fn category_test_count<C: CategoryRepository, B: BranchRepository>(
+    ctx: &Context,
+    repo: C,
+    branch_repo: B,
+) {
+    let branch = create_branch(ctx, &branch_repo, "A").await;
+
+    let food = create_named(ctx, &repo, "Food", None).await;
+    let snacks = create_named(ctx, &repo, "Snacks", Some(food)).await;
+    let chips = create_named(ctx, &repo, "Chips", Some(snacks)).await;
+    let tools = create_named(ctx, &repo, "Tools", None).await;
+    let old = create_named(ctx, &repo, "Old", None).await;
+    repo.delete(ctx, old)
+        .await
+        .expect("Failed to delete category");
+
+    // Chips is assigned, but its parent is not
+    for id in [food, chips, tools] {
+        repo.assign_to_branch(ctx, id, branch)
+            .await
+            .expect("Failed to assign category");
+    }
+
+    // Every node of the tree get_all returns is counted
+    fn nodes(categories: &[Category]) -> u64 {
+        categories
+            .iter()
+            .map(|c| 1 + c.children.as_deref().map(nodes).unwrap_or(0))
+            .sum()
+    }
+
+    let all = repo.get_all(ctx, None).await.expect("Failed to get all");
+    let count = repo.count(ctx, None).await.expect("Failed to count");
+    assert_eq!(count, 4);
+    assert_eq!(count, nodes(&all));
+
+    let for_branch = repo
+        .get_all(ctx, Some(branch))
+        .await
+        .expect("Failed to get all");
+    let count = repo
+        .count(ctx, Some(branch))
+        .await
+        .expect("Failed to count");
+    assert_eq!(count, 2);
+    assert_eq!(count, nodes(&for_branch));
+}
+
+pub async fn category_test_branch_assignment<C: CategoryRepository, B: BranchRepository>(
+    ctx: &Context,
+    repo: C,
+    branch_repo: B,
+) {
+    let branch_id = create_branch(ctx, &branch_repo, "A").await;
+    let id = create_named(ctx, &repo, "Drinks", None).await;
+
+    // Assigning twice is fine
+    for _ in 0..2 {
+        repo.assign_to_branch(ctx, id, branch_id)
+            .await
+            .expect("Failed to assign category");
+    }
+    let seen = repo
+        .get_all(ctx, Some(branch_id))
+        .await
+        .expect("Failed to get all");
+    assert_eq!(seen.len(), 1);
+
+    repo.remove_from_branch(ctx, id, branch_id)
+        .await
+        .expect("Failed to remove category");
+    let seen = repo
+        .get_all(ctx, Some(branch_id))
+        .await
+        .expect("Failed to get all");
+    assert!(seen.is_empty());
+
+    let result = repo.assign_to_branch(ctx, 999999, branch_id).await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+}
@@ -1,11 +1,13 @@
 use crate::{
     domain::{
         Context,
-        error::Error::NotFound,
+        error::Error::{self, NotFound},
         model::{
             Update,
-            customer::{CustomerCreate, CustomerFilter, CustomerUpdate},
+            customer::{Customer, CustomerCreate, CustomerFilter, CustomerUpdate},
+            filter::{FilterExpr, FilterOp, FilterValue},
             pagination::PaginationOptions,
+            phone::{PhoneRegion, normalize_phone_filter},
         },
     },
     storage::CustomerRepository,
@@ -27,6 +29,11 @@ pub fn default_filter() -> CustomerFilter {
         phone: None,
         email: None,
         level: None,
+        level_min: None,
+        level_max: None,
+        search: None,
+        search_phone: None,
+        expressions: vec![],
     }
 }
 
@@ -95,6 +102,59 @@ pub async fn customer_test_repo_integration<C: CustomerRepository>(ctx: &Context
     assert!(deleted_customer.is_none());
 }
 
+pub async fn customer_test_records_actor<C: CustomerRepository>(ctx: &Context, repo: C) {
+    let id = super::generate_test_id().await;
+    let customer = CustomerCreate {
+        number: "CUST-AUDIT".to_string(),
+        name: "Audited Customer".to_string(),
+        address: None,
+        email: None,
+        phone: None,
+        level: 1,
+        metadata: None,
+    };
+
+    repo.create(&ctx.clone().with_actor_id(7), id, &customer)
+        .await
+        .expect("Failed to create customer");
+
+    let fetched = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get customer")
+        .expect("Customer not found");
+    assert_eq!(fetched.created_by, Some(7));
+    assert_eq!(fetched.updated_by, Some(7));
+
+    let update = CustomerUpdate {
+        name: Some("Renamed".to_string()),
+        ..Default::default()
+    };
+    repo.update(&ctx.clone().with_actor_id(8), id, &update)
+        .await
+        .expect("Failed to update customer");
+
+    let fetched = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get customer")
+        .expect("Customer not found");
+    assert_eq!(fetched.created_by, Some(7));
+    assert_eq!(fetched.updated_by, Some(8));
+
+    // System writes carry no actor
+    repo.update(ctx, id, &update)
+        .await
+        .expect("Failed to update customer");
+    let fetched = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get customer")
+        .expect("Customer not found");
+    assert_eq!(fetched.created_by, Some(7));
+    assert_eq!(fetched.updated_by, None);
+}
+
 pub async fn customer_test_create_with_all_fields<C: CustomerRepository>(ctx: &Context, repo: C) {
     let id = super::generate_test_id().await;
     let metadata = json!({
@@ -545,6 +605,99 @@ pub async fn customer_test_deleted_not_in_get_all<C: CustomerRepository>(ctx: &C
     assert!(!customers_after.iter().any(|c| c.id == id));
 }
 
+/// Creates `count` customers for the bulk delete tests and returns their ids
+async fn create_bulk_customers<C: CustomerRepository>(
+    ctx: &Context,
+    repo: &C,
+    prefix: &str,
+    count: usize,
+) -> Vec<i64> {
+    let mut ids = Vec::with_capacity(count);
+    for i in 0..count {
+        let id = super::generate_test_id().await;
+        let customer = CustomerCreate {
+            number: format!("{}{:03}", prefix, i),
+            name: format!("Bulk Customer {}", i),
+            address: None,
+            email: None,
+            phone: None,
+            level: 0,
+            metadata: None,
+        };
+        repo.create(ctx, id, &customer)
+            .await
+            .expect("Failed to create customer");
+        ids.push(id);
+    }
+    ids
+}
+
+pub async fn customer_test_delete_many_best_effort<C: CustomerRepository>(ctx: &Context, repo: C) {
+    let ids = create_bulk_customers(ctx, &repo, "BLK", 3).await;
+    let missing = super::generate_test_id().await;
+    repo.delete(ctx, ids[2])
+        .await
+        .expect("Failed to delete customer");
+
+    let result = repo
+        .delete_many(ctx, &[ids[0], missing, ids[1], ids[2]], false)
+        .await
+        .expect("Failed to delete customers");
+
+    assert_eq!(result.deleted, vec![ids[0], ids[1]]);
+    // Already deleted customers are reported like unknown ones
+    assert_eq!(result.not_found, vec![missing, ids[2]]);
+    for id in &ids {
+        let fetched = repo
+            .get_by_id(ctx, *id)
+            .await
+            .expect("Failed to get customer");
+        assert!(fetched.is_none());
+    }
+}
+
+pub async fn customer_test_delete_many_all_or_nothing<C: CustomerRepository>(
+    ctx: &Context,
+    repo: C,
+) {
+    let ids = create_bulk_customers(ctx, &repo, "ATM", 2).await;
+    let missing = super::generate_test_id().await;
+
+    let result = repo
+        .delete_many(ctx, &[ids[0], missing, ids[1]], true)
+        .await
+        .expect("Failed to delete customers");
+
+    assert!(result.deleted.is_empty());
+    assert_eq!(result.not_found, vec![missing]);
+    for id in &ids {
+        let fetched = repo
+            .get_by_id(ctx, *id)
+            .await
+            .expect("Failed to get customer");
+        assert!(
+            fetched.is_some(),
+            "Customer {} should survive the rollback",
+            id
+        );
+    }
+
+    // Without missing ids everything goes through
+    let result = repo
+        .delete_many(ctx, &ids, true)
+        .await
+        .expect("Failed to delete customers");
+    assert_eq!(result.deleted, ids);
+    assert!(result.not_found.is_empty());
+    for id in &ids {
+        let fetched = repo
+            .get_by_id(ctx, *id)
+            .await
+            .expect("Failed to get customer");
+        assert!(fetched.is_none());
+    }
+}
+
 // =============================================================================
 // Get Tests
 // =============================================================================
@@ -613,7 +766,7 @@ pub async fn customer_test_get_by_number_deleted<C: CustomerRepository>(ctx: &Co
     assert!(result.is_none(), "Deleted customer should not be returned");
 }
 
-pub async fn customer_test_get_by_number_case_sensitive<C: CustomerRepository>(
+pub async fn customer_test_get_by_number_case_insensitive<C: CustomerRepository>(
     ctx: &Context,
     repo: C,
 ) {
@@ -639,15 +792,71 @@ pub async fn customer_test_get_by_number_case_sensitive<C: CustomerRepository>(
         .expect("Failed to get customer by number");
     assert!(result.is_some());
 
-    // Different case should not match (SQLite is case-sensitive for text by default)
+    // Different case matches the same customer, original casing is kept
     let result_lower = repo
         .get_by_number(ctx, "custnum123")
         .await
-        .expect("Failed to get customer by number");
+        .expect("Failed to get customer by number")
+        .expect("Should match regardless of case");
+    assert_eq!(result_lower.id, id);
+    assert_eq!(result_lower.number, "CustNum123");
+}
+
+pub async fn customer_test_number_unique_ignoring_case<C: CustomerRepository>(
+    ctx: &Context,
+    repo: C,
+) {
+    let customer = CustomerCreate {
+        number: "ABC123".to_string(),
+        name: "Upper Case".to_string(),
+        address: None,
+        email: None,
+        phone: None,
+        level: 1,
+        metadata: None,
+    };
+    let id = super::generate_test_id().await;
+    repo.create(ctx, id, &customer)
+        .await
+        .expect("Failed to create customer");
+
+    let lower = CustomerCreate {
+        number: "abc123".to_string(),
+        name: "Lower Case".to_string(),
+        ..customer
+    };
+    let result = repo
+        .create(ctx, super::generate_test_id().await, &lower)
+        .await;
     assert!(
-        result_lower.is_none(),
-        "Should be case-sensitive and not match"
+        matches!(result, Err(Error::Conflict(_))),
+        "Expected Conflict, got {:?}",
+        result
     );
+
+    // Renaming another customer onto the number conflicts as well
+    let other_id = super::generate_test_id().await;
+    let other = CustomerCreate {
+        number: "XYZ789".to_string(),
+        ..lower
+    };
+    repo.create(ctx, other_id, &other)
+        .await
+        .expect("Failed to create customer");
+    let update = CustomerUpdate {
+        number: Some("Abc123".to_string()),
+        ..Default::default()
+    };
+    let result = repo.update(ctx, other_id, &update).await;
+    assert!(matches!(result, Err(Error::Conflict(_))));
+
+    let found = repo
+        .get_by_number(ctx, "abc123")
+        .await
+        .expect("Failed to get customer by number")
+        .expect("Customer should exist");
+    assert_eq!(found.id, id);
+    assert_eq!(found.number, "ABC123");
 }
 
 pub async fn customer_test_get_by_id_not_found<C: CustomerRepository>(ctx: &Context, repo: C) {
@@ -658,6 +867,44 @@ pub async fn customer_test_get_by_id_not_found<C: CustomerRepository>(ctx: &Cont
     assert!(result.is_none());
 }
 
+pub async fn customer_test_get_by_ids<C: CustomerRepository>(ctx: &Context, repo: C) {
+    let mut ids = Vec::new();
+    for i in 0..4 {
+        let id = super::generate_test_id().await;
+        ids.push(id);
+        let customer = CustomerCreate {
+            number: format!("RCPT{:03}", i),
+            name: format!("Receipt Customer {}", i),
+            address: None,
+            email: None,
+            phone: None,
+            level: 1,
+            metadata: None,
+        };
+        repo.create(ctx, id, &customer)
+            .await
+            .expect("Failed to create customer");
+    }
+    repo.delete(ctx, ids[1])
+        .await
+        .expect("Failed to delete customer");
+    let missing = super::generate_test_id().await;
+
+    let customers = repo
+        .get_by_ids(ctx, &[ids[3], ids[1], missing, ids[0]])
+        .await
+        .expect("Failed to get customers");
+    let found: Vec<i64> = customers.iter().map(|c| c.id).collect();
+    assert_eq!(found, vec![ids[0], ids[3]]);
+    assert_eq!(customers[1].number, "RCPT003");
+
+    let none = repo
+        .get_by_ids(ctx, &[])
+        .await
+        .expect("Failed to get customers");
+    assert!(none.is_empty());
+}
+
 pub async fn customer_test_get_all<C: CustomerRepository>(ctx: &Context, repo: C) {
     // Create multiple customers
     let mut created_ids = Vec::new();
@@ -735,6 +982,11 @@ pub async fn customer_test_filter_by_name<C: CustomerRepository>(ctx: &Context,
         email: None,
         phone: None,
         level: None,
+        level_min: None,
+        level_max: None,
+        search: None,
+        search_phone: None,
+        expressions: vec![],
     };
 
     let customers = repo
@@ -746,6 +998,156 @@ pub async fn customer_test_filter_by_name<C: CustomerRepository>(ctx: &Context,
     assert!(!customers.iter().any(|c| c.id == id2));
 }
 
+pub async fn customer_test_count_ignores_pagination<C: CustomerRepository>(ctx: &Context, repo: C) {
+    for (number, name) in [
+        ("CNT001", "Counted One"),
+        ("CNT002", "Counted Two"),
+        ("CNT003", "Other"),
+    ] {
+        let customer = CustomerCreate {
+            number: number.to_string(),
+            name: name.to_string(),
+            address: None,
+            email: None,
+            phone: None,
+            level: 0,
+            metadata: None,
+        };
+        repo.create(ctx, super::generate_test_id().await, &customer)
+            .await
+            .expect("Failed to create customer");
+    }
+
+    let filter = CustomerFilter {
+        name: Some("Counted".to_string()),
+        ..Default::default()
+    };
+    let page = repo
+        .get_all(ctx, &filter, &PaginationOptions::new(1, 1, None))
+        .await
+        .expect("Failed to get customers");
+    assert_eq!(page.len(), 1);
+
+    let total = repo.count(ctx, &filter).await.expect("Failed to count");
+    assert_eq!(total, 2);
+    let all = repo
+        .count(ctx, &CustomerFilter::default())
+        .await
+        .expect("Failed to count");
+    assert_eq!(all, 3);
+}
+
+pub async fn customer_test_get_all_with_total<C: CustomerRepository>(ctx: &Context, repo: C) {
+    let ids = create_bulk_customers(ctx, &repo, "WTT", 5).await;
+    create_bulk_customers(ctx, &repo, "OTH", 2).await;
+    repo.delete(ctx, ids[4]).await.expect("Failed to delete");
+
+    let filter = CustomerFilter {
+        number: Some("WTT".to_string()),
+        ..Default::default()
+    };
+    let expected_total = repo.count(ctx, &filter).await.expect("Failed to count");
+    assert_eq!(expected_total, 4);
+
+    // Newest first, so the live ones come back as ids[3], ids[2], ids[1], ids[0]
+    let (page, total) = repo
+        .get_all_with_total(ctx, &filter, &PaginationOptions::new(1, 3, None))
+        .await
+        .expect("Failed to get customers");
+    assert_eq!(total, expected_total);
+    assert_eq!(
+        page.iter().map(|c| c.id).collect::<Vec<_>>(),
+        vec![ids[3], ids[2], ids[1]]
+    );
+
+    let (page, total) = repo
+        .get_all_with_total(ctx, &filter, &PaginationOptions::new(2, 3, None))
+        .await
+        .expect("Failed to get customers");
+    assert_eq!(total, 4);
+    assert_eq!(page.iter().map(|c| c.id).collect::<Vec<_>>(), vec![ids[0]]);
+
+    // Past the end the page is empty but the total is still reported
+    let (page, total) = repo
+        .get_all_with_total(ctx, &filter, &PaginationOptions::new(3, 3, None))
+        .await
+        .expect("Failed to get customers");
+    assert!(page.is_empty());
+    assert_eq!(total, 4);
+
+    let (page, total) = repo
+        .get_all_with_total(ctx, &default_filter(), &super::default_pagination())
+        .await
+        .expect("Failed to get customers");
+    assert_eq!(total, 6);
+    assert_eq!(page.len(), 6);
+
+    let no_match = CustomerFilter {
+        number: Some("NOPE".to_string()),
+        ..Default::default()
+    };
+    let (page, total) = repo
+        .get_all_with_total(ctx, &no_match, &super::default_pagination())
+        .await
+        .expect("Failed to get customers");
+    assert!(page.is_empty());
+    assert_eq!(total, 0);
+}
+
+pub async fn customer_test_filter_by_name_escapes_wildcards<C: CustomerRepository>(
+    ctx: &Context,
+    repo: C,
+) {
+    let names = ["50%", "50x", "a_b", "axb"];
+    let mut ids = Vec::new();
+    for (i, name) in names.iter().enumerate() {
+        let id = super::generate_test_id().await;
+        repo.create(
+            ctx,
+            id,
+            &CustomerCreate {
+                number: format!("LIKE{:03}", i),
+                name: name.to_string(),
+                address: None,
+                email: None,
+                phone: None,
+                level: 0,
+                metadata: None,
+            },
+        )
+        .await
+        .expect("Failed to create customer");
+        ids.push(id);
+    }
+
+    let search = |name: &str| CustomerFilter {
+        name: Some(name.to_string()),
+        ..default_filter()
+    };
+
+    let customers = repo
+        .get_all(ctx, &search("50%"), &super::default_pagination())
+        .await
+        .expect("Failed to get customers");
+    assert_eq!(customers.len(), 1);
+    assert_eq!(customers[0].id, ids[0]);
+
+    let customers = repo
+        .get_all(ctx, &search("a_b"), &super::default_pagination())
+        .await
+        .expect("Failed to get customers");
+    assert_eq!(customers.len(), 1);
+    assert_eq!(customers[0].id, ids[2]);
+
+    // Plain substring search is still case-insensitive
+    let customers = repo
+        .get_all(ctx, &search("AXB"), &super::default_pagination())
+        .await
+        .expect("Failed to get customers");
+    assert_eq!(customers.len(), 1);
+    assert_eq!(customers[0].id, ids[3]);
+}
+
 pub async fn customer_test_filter_by_number<C: CustomerRepository>(ctx: &Context, repo: C) {
     let id1 = super::generate_test_id().await;
     let id2 = super::generate_test_id().await;
@@ -788,6 +1190,11 @@ pub async fn customer_test_filter_by_number<C: CustomerRepository>(ctx: &Context
         email: None,
         phone: None,
         level: None,
+        level_min: None,
+        level_max: None,
+        search: None,
+        search_phone: None,
+        expressions: vec![],
     };
 
     let customers = repo
@@ -841,6 +1248,11 @@ pub async fn customer_test_filter_by_email<C: CustomerRepository>(ctx: &Context,
         email: Some("alpha".to_string()),
         phone: None,
         level: None,
+        level_min: None,
+        level_max: None,
+        search: None,
+        search_phone: None,
+        expressions: vec![],
     };
 
     let customers = repo
@@ -894,6 +1306,11 @@ pub async fn customer_test_filter_by_phone<C: CustomerRepository>(ctx: &Context,
         email: None,
         phone: Some("555".to_string()),
         level: None,
+        level_min: None,
+        level_max: None,
+        search: None,
+        search_phone: None,
+        expressions: vec![],
     };
 
     let customers = repo
@@ -947,6 +1364,11 @@ pub async fn customer_test_filter_by_level<C: CustomerRepository>(ctx: &Context,
         email: None,
         phone: None,
         level: Some(1),
+        level_min: None,
+        level_max: None,
+        search: None,
+        search_phone: None,
+        expressions: vec![],
     };
 
     let customers = repo
@@ -958,6 +1380,64 @@ pub async fn customer_test_filter_by_level<C: CustomerRepository>(ctx: &Context,
     assert!(!customers.iter().any(|c| c.id == id2));
 }
 
+pub async fn customer_test_filter_by_level_range<C: CustomerRepository>(ctx: &Context, repo: C) {
+    let mut ids = Vec::new();
+    for level in 0..4 {
+        let id = super::generate_test_id().await;
+        repo.create(
+            ctx,
+            id,
+            &CustomerCreate {
+                number: format!("RNG{}", level),
+                name: format!("Level {} Customer", level),
+                address: None,
+                email: None,
+                phone: None,
+                level,
+                metadata: None,
+            },
+        )
+        .await
+        .expect("Failed to create customer");
+        ids.push(id);
+    }
+
+    let matching_ids = |customers: Vec<Customer>| {
+        let mut found: Vec<i64> = customers.into_iter().map(|c| c.id).collect();
+        found.sort();
+        found
+    };
+    let sorted = |mut expected: Vec<i64>| {
+        expected.sort();
+        expected
+    };
+
+    // "Level 2 and above"
+    let filter = CustomerFilter {
+        level_min: Some(2),
+        ..default_filter()
+    };
+    let customers = repo
+        .get_all(ctx, &filter, &super::default_pagination())
+        .await
+        .expect("Failed to get customers");
+    assert_eq!(matching_ids(customers), sorted(vec![ids[2], ids[3]]));
+
+    // Both bounds, combined with another filter
+    let filter = CustomerFilter {
+        name: Some("Customer".to_string()),
+        level_min: Some(1),
+        level_max: Some(2),
+        ..default_filter()
+    };
+    let customers = repo
+        .get_all(ctx, &filter, &super::default_pagination())
+        .await
+        .expect("Failed to get customers");
+    assert_eq!(matching_ids(customers), sorted(vec![ids[1], ids[2]]));
+    assert_eq!(repo.count(ctx, &filter).await.expect("Failed to count"), 2);
+}
+
 pub async fn customer_test_filter_multiple_criteria<C: CustomerRepository>(ctx: &Context, repo: C) {
     let id1 = super::generate_test_id().await;
     let id2 = super::generate_test_id().await;
@@ -1018,6 +1498,11 @@ pub async fn customer_test_filter_multiple_criteria<C: CustomerRepository>(ctx:
         email: None,
         phone: None,
         level: Some(1),
+        level_min: None,
+        level_max: None,
+        search: None,
+        search_phone: None,
+        expressions: vec![],
     };
 
     let customers = repo
@@ -1031,6 +1516,239 @@ pub async fn customer_test_filter_multiple_criteria<C: CustomerRepository>(ctx:
     assert!(!customers.iter().any(|c| c.id == id3)); // Has ALP number and level 1 but Beta name
 }
 
+pub async fn customer_test_filter_by_search<C: CustomerRepository>(ctx: &Context, repo: C) {
+    // Each customer carries a search term in a different column
+    let customers = [
+        ("SRC001", "Warung Qzx", None, None, 2),
+        ("SRC002", "Toko Makmur", Some("081277810000"), None, 0),
+        ("SRC003", "Ani", None, Some("qzx.owner@example.com"), 0),
+        ("SRC004", "Qzx Deleted", None, None, 0),
+    ];
+    let mut ids = Vec::new();
+    for (number, name, phone, email, level) in customers {
+        let id = super::generate_test_id().await;
+        repo.create(
+            ctx,
+            id,
+            &CustomerCreate {
+                number: number.to_string(),
+                name: name.to_string(),
+                address: None,
+                email: email.map(str::to_string),
+                phone: phone.map(str::to_string),
+                level,
+                metadata: None,
+            },
+        )
+        .await
+        .expect("Failed to create customer");
+        ids.push(id);
+    }
+    repo.delete(ctx, ids[3])
+        .await
+        .expect("Failed to delete customer");
+
+    let matching_ids = |customers: Vec<Customer>| {
+        let mut found: Vec<i64> = customers.into_iter().map(|c| c.id).collect();
+        found.sort();
+        found
+    };
+    let search = |term: &str| CustomerFilter {
+        search: Some(term.to_string()),
+        ..default_filter()
+    };
+
+    // Name and email, case-insensitive; the deleted customer stays hidden
+    let customers = repo
+        .get_all(ctx, &search("QZX"), &super::default_pagination())
+        .await
+        .expect("Failed to get customers");
+    let mut expected = vec![ids[0], ids[2]];
+    expected.sort();
+    assert_eq!(matching_ids(customers), expected);
+
+    // Phone
+    let customers = repo
+        .get_all(ctx, &search("7781"), &super::default_pagination())
+        .await
+        .expect("Failed to get customers");
+    assert_eq!(matching_ids(customers), vec![ids[1]]);
+
+    // Other filters still apply to every alternative: the name match is
+    // level 2, so only the email match is left
+    let filter = CustomerFilter {
+        level: Some(0),
+        ..search("qzx")
+    };
+    let customers = repo
+        .get_all(ctx, &filter, &super::default_pagination())
+        .await
+        .expect("Failed to get customers");
+    assert_eq!(matching_ids(customers), vec![ids[2]]);
+    assert_eq!(repo.count(ctx, &filter).await.expect("Failed to count"), 1);
+
+    let filter = CustomerFilter {
+        name: Some("Warung".to_string()),
+        ..search("7781")
+    };
+    let customers = repo
+        .get_all(ctx, &filter, &super::default_pagination())
+        .await
+        .expect("Failed to get customers");
+    assert!(customers.is_empty());
+}
+
+pub async fn customer_test_filter_expressions<C: CustomerRepository>(ctx: &Context, repo: C) {
+    let customers = [
+        ("DSL001", "Warung Satu", Some("satu@example.com"), 0),
+        ("DSL002", "Warung 50%", None, 1),
+        ("DSL003", "Toko Tiga", Some("tiga@example.com"), 2),
+        ("DSL004", "Toko Empat", None, 3),
+    ];
+    let mut ids = Vec::new();
+    for (number, name, email, level) in customers {
+        let id = super::generate_test_id().await;
+        repo.create(
+            ctx,
+            id,
+            &CustomerCreate {
+                number: number.to_string(),
+                name: name.to_string(),
+                address: None,
+                email: email.map(str::to_string),
+                phone: None,
+                level,
+                metadata: None,
+            },
+        )
+        .await
+        .expect("Failed to create customer");
+        ids.push(id);
+    }
+
+    let matching = |expressions: Vec<FilterExpr>| {
+        let repo = &repo;
+        async move {
+            let filter = CustomerFilter {
+                // Keep other tests' customers out
+                number: Some("DSL".to_string()),
+                expressions,
+                ..default_filter()
+            };
+            let customers = repo
+                .get_all(ctx, &filter, &super::default_pagination())
+                .await
+                .expect("Failed to get customers");
+            let count = repo.count(ctx, &filter).await.expect("Failed to count");
+            assert_eq!(count as usize, customers.len());
+            let mut found: Vec<i64> = customers.into_iter().map(|c| c.id).collect();
+            found.sort();
+            found
+        }
+    };
+    let text = |s: &str| FilterValue::Text(s.to_string());
+    let sorted = |mut expected: Vec<i64>| {
+        expected.sort();
+        expected
+    };
+
+    let found = matching(vec![FilterExpr::new(
+        "number",
+        FilterOp::Eq,
+        text("DSL003"),
+    )])
+    .await;
+    assert_eq!(found, vec![ids[2]]);
+
+    // Like is a literal, case-insensitive substring match
+    let found = matching(vec![FilterExpr::new(
+        "name",
+        FilterOp::Like,
+        text("warung"),
+    )])
+    .await;
+    assert_eq!(found, sorted(vec![ids[0], ids[1]]));
+    let found = matching(vec![FilterExpr::new("name", FilterOp::Like, text("50%"))]).await;
+    assert_eq!(found, vec![ids[1]]);
+
+    let found = matching(vec![FilterExpr::new(
+        "level",
+        FilterOp::Gt,
+        FilterValue::Integer(1),
+    )])
+    .await;
+    assert_eq!(found, sorted(vec![ids[2], ids[3]]));
+
+    // Text values are converted for integer fields
+    let found = matching(vec![FilterExpr::new("level", FilterOp::Lt, text("1"))]).await;
+    assert_eq!(found, vec![ids[0]]);
+
+    let found = matching(vec![FilterExpr::new(
+        "number",
+        FilterOp::In,
+        FilterValue::List(vec![text("DSL001"), text("DSL004"), text("NOPE")]),
+    )])
+    .await;
+    assert_eq!(found, sorted(vec![ids[0], ids[3]]));
+
+    // Expressions AND with each other
+    let found = matching(vec![
+        FilterExpr::new("name", FilterOp::Like, text("toko")),
+        FilterExpr::new("email", FilterOp::Like, text("@example.com")),
+        FilterExpr::new("level", FilterOp::Lt, FilterValue::Integer(3)),
+    ])
+    .await;
+    assert_eq!(found, vec![ids[2]]);
+}
+
+pub async fn customer_test_filter_expression_rejects_unknown_field<C: CustomerRepository>(
+    ctx: &Context,
+    repo: C,
+) {
+    let customer = CustomerCreate {
+        number: "INJ001".to_string(),
+        name: "Injection Target".to_string(),
+        address: None,
+        email: None,
+        phone: None,
+        level: 0,
+        metadata: None,
+    };
+    let id = super::generate_test_id().await;
+    repo.create(ctx, id, &customer)
+        .await
+        .expect("Failed to create customer");
+
+    for field in ["name; DROP TABLE customers", "password", "is_deleted"] {
+        let filter = CustomerFilter {
+            expressions: vec![FilterExpr::new(
+                field,
+                FilterOp::Eq,
+                FilterValue::Text("x".to_string()),
+            )],
+            ..default_filter()
+        };
+        let result = repo
+            .get_all(ctx, &filter, &super::default_pagination())
+            .await;
+        assert!(
+            matches!(result, Err(Error::ValidationError(_))),
+            "{}: {:?}",
+            field,
+            result
+        );
+        let result = repo.count(ctx, &filter).await;
+        assert!(matches!(result, Err(Error::ValidationError(_))));
+    }
+
+    // The table is still there
+    let fetched = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get customer");
+    assert!(fetched.is_some());
+}
+
 // =============================================================================
 // Pagination Tests
 // =============================================================================
@@ -1072,3 +1790,90 @@ pub async fn customer_test_pagination<C: CustomerRepository>(ctx: &Context, repo
         assert!(!page2.iter().any(|c2| c2.id == c1.id));
     }
 }
+
+pub async fn customer_test_update_touches_updated_at<C: CustomerRepository>(
+    ctx: &Context,
+    repo: C,
+) {
+    let id = super::generate_test_id().await;
+    let customer = CustomerCreate {
+        number: "TOUCH001".to_string(),
+        name: "Touch Customer".to_string(),
+        address: None,
+        email: None,
+        phone: None,
+        level: 1,
+        metadata: None,
+    };
+    repo.create(ctx, id, &customer)
+        .await
+        .expect("Failed to create customer");
+    let before = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get customer")
+        .expect("Customer not found");
+
+    super::wait_for_clock_tick().await;
+    let update = CustomerUpdate {
+        name: Some("Touched Customer".to_string()),
+        ..Default::default()
+    };
+    repo.update(ctx, id, &update)
+        .await
+        .expect("Failed to update customer");
+
+    let after = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get customer")
+        .expect("Customer not found");
+    assert!(after.updated_at > before.updated_at);
+    assert_eq!(after.created_at, before.created_at);
+}
+
+pub async fn customer_test_search_by_local_phone<C: CustomerRepository>(ctx: &Context, repo: C) {
+    let id = super::generate_test_id().await;
+    repo.create(
+        ctx,
+        id,
+        &CustomerCreate {
+            number: "LOC001".to_string(),
+            name: "Budi".to_string(),
+            address: None,
+            email: None,
+            phone: Some("+628123456789".to_string()),
+            level: 0,
+            metadata: None,
+        },
+    )
+    .await
+    .expect("Failed to create customer");
+
+    // Typed in local format, as the service receives it
+    let term = "0812-3456-789";
+    let raw = CustomerFilter {
+        search: Some(term.to_string()),
+        ..default_filter()
+    };
+    let customers = repo
+        .get_all(ctx, &raw, &super::default_pagination())
+        .await
+        .expect("Failed to get customers");
+    assert!(customers.is_empty());
+
+    let normalized = CustomerFilter {
+        search_phone: Some(normalize_phone_filter(term, PhoneRegion::ID)),
+        ..raw
+    };
+    let customers = repo
+        .get_all(ctx, &normalized, &super::default_pagination())
+        .await
+        .expect("Failed to get customers");
+    assert_eq!(customers.len(), 1);
+    assert_eq!(customers[0].id, id);
+    assert_eq!(
+        repo.count(ctx, &normalized).await.expect("Failed to count"),
+        1
+    );
+}
@@ -0,0 +1,445 @@
+use std::time::Duration;
+
+use serde_json::json;
+use sqlx::{
+    SqlitePool,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode},
+};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        Context, DomainResult, Error,
+        model::{
+            customer::CustomerCreate,
+            export::{ExportPage, ExportTable, ImportMode, Snapshot},
+            product::UnitOfMeasureCreate,
+        },
+    },
+    storage::{
+        CustomerRepository, ExportRepository, UnitOfMeasureRepository,
+        sqlite::{
+            SqliteCustomerRepository, SqliteExportRepository, SqliteUnitOfMeasureRepository,
+            transaction::BusyRetry,
+        },
+    },
+};
+
+pub async fn create_sqlite_export_repo() -> (
+    Context,
+    SqliteExportRepository,
+    SqliteCustomerRepository,
+    SqliteUnitOfMeasureRepository,
+) {
+    sqlite_export_repos(super::init_sqlite_pool().await)
+}
+
+/// Same as [`create_sqlite_export_repo`] on a WAL database, so writes can
+/// land while an export transaction is open
+pub async fn create_wal_sqlite_export_repo() -> (
+    Context,
+    SqliteExportRepository,
+    SqliteCustomerRepository,
+    SqliteUnitOfMeasureRepository,
+) {
+    sqlite_export_repos(super::init_wal_sqlite_pool().await)
+}
+
+/// An export repository over a WAL database whose connections report
+/// `SQLITE_BUSY` at once instead of waiting, with the pool for holding locks
+pub async fn create_busy_sqlite_export_repo() -> (Context, SqliteExportRepository, SqlitePool) {
+    let options = SqliteConnectOptions::new()
+        .filename(format!("/tmp/test_{}.db", Uuid::new_v4()))
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .busy_timeout(Duration::ZERO);
+    let pool = SqlitePool::connect_with(options)
+        .await
+        .expect("Failed to create pool");
+    super::run_migrations(&pool).await;
+    let repo = SqliteExportRepository::new(pool.clone())
+        .with_busy_retry(BusyRetry::new(20, Duration::from_millis(5)));
+    (Context::new(), repo, pool)
+}
+
+fn sqlite_export_repos(
+    pool: SqlitePool,
+) -> (
+    Context,
+    SqliteExportRepository,
+    SqliteCustomerRepository,
+    SqliteUnitOfMeasureRepository,
+) {
+    (
+        Context::new(),
+        SqliteExportRepository::new(pool.clone()),
+        SqliteCustomerRepository::new(pool.clone()),
+        SqliteUnitOfMeasureRepository::new(pool),
+    )
+}
+
+async fn create_customer<C: CustomerRepository>(ctx: &Context, repo: &C, number: &str) -> i64 {
+    let id = super::generate_test_id().await;
+    let customer = CustomerCreate {
+        number: number.to_string(),
+        name: format!("Customer {}", number),
+        address: None,
+        email: None,
+        phone: None,
+        level: 1,
+        metadata: Some(json!({"tier": "gold"})),
+    };
+    repo.create(ctx, id, &customer)
+        .await
+        .expect("Failed to create customer");
+    id
+}
+
+/// One page read in its own export transaction
+async fn export_rows<E: ExportRepository>(
+    ctx: &Context,
+    repo: &E,
+    table: ExportTable,
+    include_deleted: bool,
+    after_id: i64,
+    limit: u32,
+) -> DomainResult<ExportPage> {
+    repo.begin_export(ctx)
+        .await?
+        .export_rows(table, include_deleted, after_id, limit)
+        .await
+}
+
+fn ids(page: &ExportPage) -> Vec<i64> {
+    page.rows
+        .iter()
+        .map(|row| row["id"].as_i64().expect("Row without id"))
+        .collect()
+}
+
+pub async fn export_test_excludes_deleted_unless_requested<E, C, U>(
+    ctx: &Context,
+    repo: E,
+    customer_repo: C,
+    unit_repo: U,
+) where
+    E: ExportRepository,
+    C: CustomerRepository,
+    U: UnitOfMeasureRepository,
+{
+    let live = create_customer(ctx, &customer_repo, "C001").await;
+    let deleted = create_customer(ctx, &customer_repo, "C002").await;
+    customer_repo
+        .delete(ctx, deleted)
+        .await
+        .expect("Failed to delete customer");
+    let unit = super::generate_test_id().await;
+    unit_repo
+        .create(
+            ctx,
+            unit,
+            &UnitOfMeasureCreate {
+                name: "pcs".to_string(),
+                description: None,
+            },
+        )
+        .await
+        .expect("Failed to create unit");
+
+    let page = export_rows(ctx, &repo, ExportTable::Customers, false, 0, 100)
+        .await
+        .expect("Failed to export customers");
+    assert_eq!(ids(&page), vec![live]);
+    assert_eq!(page.next_after_id, None);
+    // Rows are written as stored, with metadata expanded
+    assert_eq!(page.rows[0]["number"], "C001");
+    assert_eq!(page.rows[0]["metadata"], json!({"tier": "gold"}));
+
+    let page = export_rows(ctx, &repo, ExportTable::Customers, true, 0, 100)
+        .await
+        .expect("Failed to export customers");
+    assert_eq!(ids(&page), vec![live, deleted]);
+    assert_eq!(page.rows[1]["is_deleted"], true);
+
+    let page = export_rows(ctx, &repo, ExportTable::Units, false, 0, 100)
+        .await
+        .expect("Failed to export units");
+    assert_eq!(ids(&page), vec![unit]);
+
+    let page = export_rows(ctx, &repo, ExportTable::Products, true, 0, 100)
+        .await
+        .expect("Failed to export products");
+    assert!(page.rows.is_empty());
+}
+
+pub async fn export_test_pages_in_id_order<E, C>(ctx: &Context, repo: E, customer_repo: C)
+where
+    E: ExportRepository,
+    C: CustomerRepository,
+{
+    let mut created = Vec::new();
+    for number in ["C001", "C002", "C003"] {
+        created.push(create_customer(ctx, &customer_repo, number).await);
+    }
+
+    let mut seen = Vec::new();
+    let mut after_id = 0;
+    loop {
+        let page = export_rows(ctx, &repo, ExportTable::Customers, false, after_id, 2)
+            .await
+            .expect("Failed to export customers");
+        seen.extend(ids(&page));
+        match page.next_after_id {
+            Some(id) => after_id = id,
+            None => break,
+        }
+    }
+    assert_eq!(seen, created);
+}
+
+fn snapshot_with_category(category_id: i64) -> (Snapshot, i64, i64, i64) {
+    let category = 9_000_000_001;
+    let product = 9_000_000_002;
+    let variant = 9_000_000_003;
+    let snapshot = Snapshot {
+        categories: vec![json!({"id": category, "name": "Drinks", "parent_id": null})],
+        products: vec![json!({
+            "id": product,
+            "name": "Tea",
+            "product_type": "product",
+            "sellable": true,
+            "metadata": {"origin": "local"},
+            "category_ids": [category_id],
+        })],
+        variants: vec![json!({
+            "id": variant,
+            "product_id": product,
+            "barcode": "IMPORT-001",
+            "name": "Tea 250ml",
+            "price": 5000,
+        })],
+        customers: vec![
+            json!({"id": 9_000_000_004_i64, "number": "C100", "name": "Imported", "level": 1}),
+        ],
+        ..Default::default()
+    };
+    (snapshot, category, product, variant)
+}
+
+pub async fn export_test_import_round_trip<E: ExportRepository>(ctx: &Context, repo: E) {
+    let (snapshot, category, product, variant) = snapshot_with_category(9_000_000_001);
+
+    repo.import_snapshot(ctx, &snapshot, ImportMode::Insert)
+        .await
+        .expect("Failed to import snapshot");
+
+    let categories = export_rows(ctx, &repo, ExportTable::Categories, false, 0, 100)
+        .await
+        .expect("Failed to export categories");
+    assert_eq!(ids(&categories), vec![category]);
+
+    let products = export_rows(ctx, &repo, ExportTable::Products, false, 0, 100)
+        .await
+        .expect("Failed to export products");
+    assert_eq!(ids(&products), vec![product]);
+    assert_eq!(products.rows[0]["category_ids"], json!([category]));
+    assert_eq!(products.rows[0]["metadata"], json!({"origin": "local"}));
+
+    let variants = export_rows(ctx, &repo, ExportTable::Variants, false, 0, 100)
+        .await
+        .expect("Failed to export variants");
+    assert_eq!(ids(&variants), vec![variant]);
+    assert_eq!(variants.rows[0]["price"], 5000);
+
+    let customers = export_rows(ctx, &repo, ExportTable::Customers, false, 0, 100)
+        .await
+        .expect("Failed to export customers");
+    assert_eq!(customers.rows[0]["number"], "C100");
+}
+
+/// The import waits for a writer that holds the lock when it starts
+pub async fn export_test_import_waits_for_writer(
+    ctx: &Context,
+    repo: SqliteExportRepository,
+    pool: SqlitePool,
+) {
+    let (snapshot, category, _, _) = snapshot_with_category(9_000_000_001);
+
+    let writer = pool
+        .begin_with("BEGIN IMMEDIATE")
+        .await
+        .expect("Failed to begin writer");
+    let release_writer = async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        writer.commit().await
+    };
+    let (writer_result, import_result) = tokio::join!(
+        release_writer,
+        repo.import_snapshot(ctx, &snapshot, ImportMode::Insert)
+    );
+
+    writer_result.expect("Writer commit failed");
+    import_result.expect("Import should succeed once the writer is done");
+    let categories = export_rows(ctx, &repo, ExportTable::Categories, false, 0, 100)
+        .await
+        .expect("Failed to export categories");
+    assert_eq!(ids(&categories), vec![category]);
+}
+
+pub async fn export_test_import_rolls_back_dangling_category<E: ExportRepository>(
+    ctx: &Context,
+    repo: E,
+) {
+    let (snapshot, _, product, _) = snapshot_with_category(42);
+
+    let result = repo
+        .import_snapshot(ctx, &snapshot, ImportMode::Insert)
+        .await;
+
+    match result {
+        Err(Error::ValidationError(message)) => {
+            assert!(message.contains(&format!("product {} -> category 42", product)));
+        }
+        other => panic!("Expected ValidationError, got {:?}", other),
+    }
+    for table in ExportTable::ALL {
+        let page = export_rows(ctx, &repo, table, true, 0, 100)
+            .await
+            .expect("Failed to export");
+        assert!(page.rows.is_empty(), "{} was written", table.key());
+    }
+}
+
+pub async fn export_test_import_conflict_unless_upsert<E: ExportRepository>(
+    ctx: &Context,
+    repo: E,
+) {
+    let (snapshot, category, _, _) = snapshot_with_category(9_000_000_001);
+    repo.import_snapshot(ctx, &snapshot, ImportMode::Insert)
+        .await
+        .expect("Failed to import snapshot");
+
+    let renamed = Snapshot {
+        categories: vec![json!({"id": category, "name": "Beverages"})],
+        ..Default::default()
+    };
+    let result = repo
+        .import_snapshot(ctx, &renamed, ImportMode::Insert)
+        .await;
+    assert!(matches!(result, Err(Error::Conflict(_))));
+
+    repo.import_snapshot(ctx, &renamed, ImportMode::Upsert)
+        .await
+        .expect("Failed to upsert snapshot");
+    let categories = export_rows(ctx, &repo, ExportTable::Categories, false, 0, 100)
+        .await
+        .expect("Failed to export categories");
+    assert_eq!(categories.rows[0]["name"], "Beverages");
+
+    // A row with only its id leaves the stored row alone
+    let id_only = Snapshot {
+        categories: vec![json!({"id": category})],
+        ..Default::default()
+    };
+    repo.import_snapshot(ctx, &id_only, ImportMode::Upsert)
+        .await
+        .expect("Failed to upsert id-only row");
+    let categories = export_rows(ctx, &repo, ExportTable::Categories, false, 0, 100)
+        .await
+        .expect("Failed to export categories");
+    assert_eq!(categories.rows[0]["name"], "Beverages");
+}
+
+pub async fn export_test_import_checks_many_references<E: ExportRepository>(
+    ctx: &Context,
+    repo: E,
+) {
+    // More missing categories than SQLite takes bound parameters in one query
+    let category_ids: Vec<i64> = (1..=40_000).collect();
+    let snapshot = Snapshot {
+        products: vec![json!({
+            "id": 9_000_000_020_i64,
+            "name": "Everywhere",
+            "product_type": "product",
+            "category_ids": category_ids,
+        })],
+        ..Default::default()
+    };
+
+    let result = repo
+        .import_snapshot(ctx, &snapshot, ImportMode::Insert)
+        .await;
+
+    match result {
+        Err(Error::ValidationError(message)) => {
+            assert!(message.contains("product 9000000020 -> category 40000"));
+        }
+        other => panic!("Expected ValidationError, got {:?}", other),
+    }
+}
+
+pub async fn export_test_import_rejects_unknown_product_type<E: ExportRepository>(
+    ctx: &Context,
+    repo: E,
+) {
+    let snapshot = Snapshot {
+        products: vec![json!({"id": 9_000_000_010_i64, "name": "Odd", "product_type": "gadget"})],
+        units: vec![json!({"id": 9_000_000_011_i64, "name": "Box"})],
+        ..Default::default()
+    };
+
+    let result = repo
+        .import_snapshot(ctx, &snapshot, ImportMode::Insert)
+        .await;
+
+    match result {
+        Err(Error::ValidationError(message)) => assert!(message.contains("gadget")),
+        other => panic!("Expected ValidationError, got {:?}", other),
+    }
+    for table in ExportTable::ALL {
+        let page = export_rows(ctx, &repo, table, true, 0, 100)
+            .await
+            .expect("Failed to export");
+        assert!(page.rows.is_empty(), "{} was written", table.key());
+    }
+}
+
+pub async fn export_test_reader_sees_one_snapshot<E, C>(ctx: &Context, repo: E, customer_repo: C)
+where
+    E: ExportRepository,
+    C: CustomerRepository,
+{
+    let first = create_customer(ctx, &customer_repo, "C200").await;
+    let second = create_customer(ctx, &customer_repo, "C201").await;
+
+    let mut reader = repo
+        .begin_export(ctx)
+        .await
+        .expect("Failed to begin export");
+    let page = reader
+        .export_rows(ExportTable::Customers, false, 0, 1)
+        .await
+        .expect("Failed to export customers");
+    assert_eq!(ids(&page), vec![first]);
+
+    // Written while the export is running
+    customer_repo
+        .delete(ctx, second)
+        .await
+        .expect("Failed to delete customer");
+    let third = create_customer(ctx, &customer_repo, "C202").await;
+
+    let after_id = page.next_after_id.expect("Expected another page");
+    let page = reader
+        .export_rows(ExportTable::Customers, false, after_id, 100)
+        .await
+        .expect("Failed to export customers");
+    assert_eq!(ids(&page), vec![second]);
+    drop(reader);
+
+    // A new export sees the writes
+    let page = export_rows(ctx, &repo, ExportTable::Customers, false, 0, 100)
+        .await
+        .expect("Failed to export customers");
+    assert_eq!(ids(&page), vec![first, third]);
+}
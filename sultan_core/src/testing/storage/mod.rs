@@ -2,8 +2,13 @@
 pub mod branch;
 pub mod category;
 pub mod customer;
+pub mod export;
 pub mod product;
+pub mod purchase_order;
+pub mod read_repo;
+pub mod sale;
 pub mod sell_price;
+pub mod stock;
 pub mod supplier;
 pub mod token;
 pub mod unit;
@@ -11,7 +16,11 @@ pub mod user;
 
 use crate::{domain::model::pagination::PaginationOptions, snowflake::SnowflakeGenerator};
 use once_cell::sync::Lazy;
-use sqlx::SqlitePool;
+use sqlx::{
+    SqlitePool,
+    migrate::{Migrate, Migrator},
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+};
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
@@ -23,6 +32,12 @@ pub async fn generate_test_id() -> i64 {
     generator.generate().unwrap()
 }
 
+/// Stored timestamps have millisecond resolution, so tests comparing
+/// `updated_at` across two writes wait out at least one tick in between.
+pub async fn wait_for_clock_tick() {
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+}
+
 pub fn default_pagination() -> PaginationOptions {
     PaginationOptions::new(1, 100, None)
 }
@@ -33,22 +48,77 @@ pub async fn init_sqlite_pool() -> SqlitePool {
     let new_pool = SqlitePool::connect(&format!("sqlite://{}?mode=rwc", temp_file))
         .await
         .expect("Failed to create pool");
+    run_migrations(&new_pool).await;
+    new_pool
+}
+
+/// Like [`init_sqlite_pool`], but in WAL mode as in production, so readers
+/// and a writer can overlap
+pub async fn init_wal_sqlite_pool() -> SqlitePool {
+    let temp_file = format!("/tmp/test_{}.db", Uuid::new_v4());
+    let options = SqliteConnectOptions::new()
+        .filename(temp_file)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal);
+    let new_pool = SqlitePool::connect_with(options)
+        .await
+        .expect("Failed to create pool");
+    run_migrations(&new_pool).await;
+    new_pool
+}
 
-    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
-    let migrations = std::path::Path::new(&crate_dir).join("../migrations");
+/// A migrated in-memory database for benchmarks. The pool holds a single
+/// connection, since every connection to `sqlite::memory:` is its own
+/// database.
+pub async fn init_memory_sqlite_pool() -> SqlitePool {
+    let new_pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("Failed to create pool");
+    run_migrations(&new_pool).await;
+    new_pool
+}
+
+async fn load_migrator() -> Migrator {
+    let migrations = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../migrations");
     print!(
         "migration folder {}",
         migrations.as_path().to_string_lossy()
     );
 
-    sqlx::migrate::Migrator::new(migrations)
+    Migrator::new(migrations)
         .await
         .expect("Failed to load migrations")
-        .run(&new_pool)
+}
+
+async fn run_migrations(pool: &SqlitePool) {
+    load_migrator()
+        .await
+        .run(pool)
         .await
         .expect("Failed to run SQLite migrations");
+}
 
-    new_pool
+/// A database migrated only up to, not including, `version`, for seeding
+/// legacy rows before a migration runs. Finish with `migrator.run(&pool)`.
+pub async fn init_sqlite_pool_before(version: i64) -> (SqlitePool, Migrator) {
+    let temp_file = format!("/tmp/test_{}.db", Uuid::new_v4());
+    let pool = SqlitePool::connect(&format!("sqlite://{}?mode=rwc", temp_file))
+        .await
+        .expect("Failed to create pool");
+    let migrator = load_migrator().await;
+
+    let mut conn = pool.acquire().await.expect("Failed to acquire connection");
+    conn.ensure_migrations_table()
+        .await
+        .expect("Failed to create migrations table");
+    for migration in migrator.iter().filter(|m| m.version < version) {
+        conn.apply(migration)
+            .await
+            .expect("Failed to apply migration");
+    }
+    (pool, migrator)
 }
 
 /*
@@ -1,15 +1,22 @@
 use crate::{
     domain::{
-        Context,
+        Context, DomainResult,
         error::Error,
         model::{
             Update,
             category::category_create_with_name,
-            product::{ProductCreate, ProductUpdate, ProductVariantCreate, ProductVariantUpdate},
+            money::Money,
+            pagination::PaginationOptions,
+            product::{
+                ProductComponent, ProductCreate, ProductFilter, ProductSupplier, ProductType,
+                ProductUpdate, ProductVariantCreate, ProductVariantFilter, ProductVariantUpdate,
+                ProductVariantUpsert, ProductWithVariants,
+            },
+            supplier::SupplierCreate,
         },
     },
     storage::{
-        CategoryRepository, ProductRepository,
+        CategoryRepository, ProductRepository, SupplierRepository,
         sqlite::{
             SqliteCategoryRepository, SqliteProductRepository,
             transaction::SqliteTransactionManager,
@@ -41,7 +48,7 @@ fn create_test_product() -> ProductCreate {
     ProductCreate {
         name: "Test Product".to_string(),
         description: Some("A test product description".to_string()),
-        product_type: "product".to_string(),
+        product_type: ProductType::Product,
         main_image: Some("https://example.com/image.jpg".to_string()),
         sellable: true,
         buyable: true,
@@ -57,10 +64,37 @@ fn create_test_variant(product_id: i64) -> ProductVariantCreate {
         product_id,
         barcode: Some("1234567890".to_string()),
         name: Some("Default Variant".to_string()),
+        price: None,
         metadata: Some(json!({"sku": "SKU001"})),
+        initial_stock: vec![],
     }
 }
 
+/// Update a variant in its own transaction, rolled back if the update fails
+async fn update_variant<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+    id: i64,
+    update: &ProductVariantUpdate,
+) -> DomainResult<()>
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    let result = repo.update_variant(ctx, id, update, &mut tx).await;
+    if result.is_ok() {
+        tx_manager.commit(tx).await.expect("Failed to commit tx");
+    } else {
+        tx_manager
+            .rollback(tx)
+            .await
+            .expect("Failed to rollback tx");
+    }
+    result
+}
+
 pub async fn product_test_create_success<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
 where
     T: TransactionManager,
@@ -87,7 +121,7 @@ where
         saved.description,
         Some("A test product description".to_string())
     );
-    assert_eq!(saved.product_type, "product");
+    assert_eq!(saved.product_type, ProductType::Product);
     assert_eq!(
         saved.main_image,
         Some("https://example.com/image.jpg".to_string())
@@ -99,6 +133,57 @@ where
     assert!(!saved.is_deleted);
 }
 
+/// `ctx` carries no actor; the audit columns are filled from a context that does.
+pub async fn test_product_records_actor<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let creator = ctx.clone().with_actor_id(7);
+    let editor = ctx.clone().with_actor_id(8);
+    let product_id = super::generate_test_id().await;
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&creator, product_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let saved = repo
+        .get_by_id(ctx, product_id)
+        .await
+        .expect("Failed to get product")
+        .expect("Product not found");
+    assert_eq!(saved.created_by, Some(7));
+    assert_eq!(saved.updated_by, Some(7));
+
+    let update = ProductUpdate {
+        name: Some("Renamed".to_string()),
+        description: Update::Unchanged,
+        product_type: None,
+        main_image: Update::Unchanged,
+        sellable: None,
+        buyable: None,
+        editable_price: None,
+        has_variant: None,
+        metadata: Update::Unchanged,
+        category_ids: None,
+    };
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.update_product(&editor, product_id, &update, &mut tx)
+        .await
+        .expect("Failed to update product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let saved = repo
+        .get_by_id(ctx, product_id)
+        .await
+        .expect("Failed to get product")
+        .expect("Product not found");
+    assert_eq!(saved.created_by, Some(7));
+    assert_eq!(saved.updated_by, Some(8));
+}
+
 pub async fn test_create_product_without_optional_fields<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
@@ -111,7 +196,7 @@ pub async fn test_create_product_without_optional_fields<'a, T, P>(
     let product = ProductCreate {
         name: "Minimal Product".to_string(),
         description: None,
-        product_type: "service".to_string(),
+        product_type: ProductType::Service,
         main_image: None,
         sellable: false,
         buyable: false,
@@ -135,7 +220,7 @@ pub async fn test_create_product_without_optional_fields<'a, T, P>(
 
     assert_eq!(saved.name, "Minimal Product");
     assert_eq!(saved.description, None);
-    assert_eq!(saved.product_type, "service");
+    assert_eq!(saved.product_type, ProductType::Service);
     assert_eq!(saved.main_image, None);
     assert!(!saved.sellable);
     assert!(!saved.buyable);
@@ -171,7 +256,7 @@ pub async fn test_create_product_with_categories<'a, T, P, C>(
     let product = ProductCreate {
         name: "Categorized Product".to_string(),
         description: None,
-        product_type: "product".to_string(),
+        product_type: ProductType::Product,
         main_image: None,
         sellable: true,
         buyable: true,
@@ -305,7 +390,7 @@ where
     let update = ProductUpdate {
         name: Some("Fully Updated Product".to_string()),
         description: Update::Set("New description".to_string()),
-        product_type: Some("service".to_string()),
+        product_type: Some(ProductType::Service),
         main_image: Update::Set("https://new-image.com/img.png".to_string()),
         sellable: Some(false),
         buyable: Some(false),
@@ -329,7 +414,7 @@ where
 
     assert_eq!(saved.name, "Fully Updated Product");
     assert_eq!(saved.description, Some("New description".to_string()));
-    assert_eq!(saved.product_type, "service");
+    assert_eq!(saved.product_type, ProductType::Service);
     assert_eq!(
         saved.main_image,
         Some("https://new-image.com/img.png".to_string())
@@ -373,7 +458,7 @@ pub async fn test_update_product_categories<'a, T, P, C>(
     let product = ProductCreate {
         name: "Product with categories".to_string(),
         description: None,
-        product_type: "product".to_string(),
+        product_type: ProductType::Product,
         main_image: None,
         sellable: true,
         buyable: true,
@@ -421,217 +506,678 @@ pub async fn test_update_product_categories<'a, T, P, C>(
     assert!(!categories.contains(&cat_id1));
 }
 
-pub async fn test_update_product_not_found<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
-where
+pub async fn test_add_and_remove_product_categories<'a, T, P, C>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+    category_repo: &'a C,
+) where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
+    C: CategoryRepository,
 {
-    let update = ProductUpdate {
-        name: Some("Updated".to_string()),
-        description: Update::Unchanged,
-        product_type: None,
-        main_image: Update::Unchanged,
-        sellable: None,
-        buyable: None,
-        editable_price: None,
-        has_variant: None,
-        metadata: Update::Unchanged,
-        category_ids: None,
-    };
+    let mut cat_ids = Vec::new();
+    for name in ["Cat 1", "Cat 2", "Cat 3"] {
+        let id = super::generate_test_id().await;
+        category_repo
+            .create(ctx, id, &category_create_with_name(name))
+            .await
+            .expect("Failed to create category");
+        cat_ids.push(id);
+    }
 
+    let product_id = super::generate_test_id().await;
+    let product = ProductCreate {
+        category_ids: vec![cat_ids[0], cat_ids[1]],
+        ..create_test_product()
+    };
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    let result = repo.update_product(&ctx, 999999, &update, &mut tx).await;
-    tx_manager
-        .rollback(tx)
+    repo.create_product(ctx, product_id, &product, &mut tx)
         .await
-        .expect("Failed to rollback tx");
-
-    assert!(matches!(result, Err(Error::NotFound(_))));
-}
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-pub async fn test_delete_product_success<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
-where
-    T: TransactionManager,
-    P: ProductRepository<T::Transaction<'a>>,
-{
-    let product_id = super::generate_test_id().await;
-    let product = create_test_product();
+    let sorted_categories = |mut ids: Vec<i64>| {
+        ids.sort();
+        ids
+    };
 
+    // Adding one category keeps the existing ones; re-adding a linked one is a no-op
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(&ctx, product_id, &product, &mut tx)
+    repo.add_categories(ctx, product_id, &[cat_ids[2], cat_ids[0]], &mut tx)
         .await
-        .expect("Failed to create product");
+        .expect("Failed to add categories");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
+    let categories = repo
+        .get_product_category(ctx, product_id)
+        .await
+        .expect("Failed to get product categories");
+    assert_eq!(
+        sorted_categories(categories),
+        sorted_categories(cat_ids.clone())
+    );
+
+    // Removing one leaves the others; removing an unlinked one is a no-op
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.delete_product(&ctx, product_id, &mut tx)
+    repo.remove_categories(ctx, product_id, &[cat_ids[1]], &mut tx)
         .await
-        .expect("Failed to delete product");
+        .expect("Failed to remove categories");
+    repo.remove_categories(ctx, product_id, &[cat_ids[1]], &mut tx)
+        .await
+        .expect("Failed to remove unlinked category");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let saved = repo
-        .get_by_id(&ctx, product_id)
+    let categories = repo
+        .get_product_category(ctx, product_id)
         .await
-        .expect("Failed to get product");
-
-    assert!(saved.is_none());
-}
+        .expect("Failed to get product categories");
+    assert_eq!(
+        sorted_categories(categories),
+        sorted_categories(vec![cat_ids[0], cat_ids[2]])
+    );
 
-pub async fn test_delete_product_not_found<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
-where
-    T: TransactionManager,
-    P: ProductRepository<T::Transaction<'a>>,
-{
+    // Unknown products are reported instead of silently ignored
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    let result = repo.delete_product(&ctx, 999999, &mut tx).await;
+    let result = repo
+        .add_categories(ctx, 999_999, &[cat_ids[0]], &mut tx)
+        .await;
     tx_manager
         .rollback(tx)
         .await
         .expect("Failed to rollback tx");
-
     assert!(matches!(result, Err(Error::NotFound(_))));
 }
 
-pub async fn test_get_product_by_id_not_found<'a, T, P>(ctx: &Context, _: &'a T, repo: &'a P)
-where
+pub async fn test_set_and_get_product_suppliers<'a, T, P, S>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+    supplier_repo: &'a S,
+) where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
+    S: SupplierRepository,
 {
-    let result = repo.get_by_id(&ctx, 999999).await.expect("Failed to query");
-
-    assert!(result.is_none());
-}
+    let mut supplier_ids = Vec::new();
+    for name in ["Supplier A", "Supplier B"] {
+        let id = super::generate_test_id().await;
+        let supplier = SupplierCreate {
+            name: name.to_string(),
+            code: None,
+            address: None,
+            phone: None,
+            npwp: None,
+            npwp_name: None,
+            email: None,
+            metadata: None,
+        };
+        supplier_repo
+            .create(ctx, id, &supplier)
+            .await
+            .expect("Failed to create supplier");
+        supplier_ids.push(id);
+    }
 
-pub async fn test_create_variant_success<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
-where
-    T: TransactionManager,
-    P: ProductRepository<T::Transaction<'a>>,
-{
-    // Create product first
     let product_id = super::generate_test_id().await;
-    let product = create_test_product();
-
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(&ctx, product_id, &product, &mut tx)
+    repo.create_product(ctx, product_id, &create_test_product(), &mut tx)
         .await
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Create variant
-    let variant_id = super::generate_test_id().await;
-    let variant = create_test_variant(product_id);
+    let suppliers = vec![
+        ProductSupplier {
+            supplier_id: supplier_ids[0],
+            supplier_sku: Some("A-001".to_string()),
+            cost: Some(Money::from_minor(12_500)),
+        },
+        ProductSupplier {
+            supplier_id: supplier_ids[1],
+            supplier_sku: None,
+            cost: None,
+        },
+    ];
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.set_suppliers(ctx, product_id, &suppliers, &mut tx)
+        .await
+        .expect("Failed to set suppliers");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
 
+    let fetched = repo
+        .get_suppliers(ctx, product_id)
+        .await
+        .expect("Failed to get suppliers");
+    assert_eq!(fetched, suppliers);
+
+    // Setting the list again replaces it, so the dropped supplier is unlinked
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+    repo.set_suppliers(ctx, product_id, &suppliers[..1], &mut tx)
         .await
-        .expect("Failed to create variant");
+        .expect("Failed to set suppliers");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let saved = repo
-        .get_variant_by_id(&ctx, variant_id)
+    let fetched = repo
+        .get_suppliers(ctx, product_id)
         .await
-        .expect("Failed to get variant")
-        .expect("Variant not found");
+        .expect("Failed to get suppliers");
+    assert_eq!(fetched, suppliers[..1]);
 
-    assert_eq!(saved.id, variant_id);
-    assert_eq!(saved.product.id, product_id);
-    assert_eq!(saved.barcode, Some("1234567890".to_string()));
-    assert_eq!(saved.name, Some("Default Variant".to_string()));
-    assert_eq!(saved.metadata, Some(json!({"sku": "SKU001"})));
+    // Unknown products are reported instead of silently ignored
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    let result = repo.set_suppliers(ctx, 999_999, &suppliers, &mut tx).await;
+    tx_manager
+        .rollback(tx)
+        .await
+        .expect("Failed to rollback tx");
+    assert!(matches!(result, Err(Error::NotFound(_))));
 }
 
-pub async fn test_create_variant_without_optional_fields<'a, T, P>(
+pub async fn test_get_products_by_category<'a, T, P, C>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
+    category_repo: &'a C,
 ) where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
+    C: CategoryRepository,
 {
-    let product_id = super::generate_test_id().await;
-    let product = create_test_product();
+    let category_id = super::generate_test_id().await;
+    category_repo
+        .create(ctx, category_id, &category_create_with_name("Drinks"))
+        .await
+        .expect("Failed to create category");
+    let other_category_id = super::generate_test_id().await;
+    category_repo
+        .create(ctx, other_category_id, &category_create_with_name("Snacks"))
+        .await
+        .expect("Failed to create category");
 
+    let mut in_category = Vec::new();
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(&ctx, product_id, &product, &mut tx)
+    for i in 0..3 {
+        let id = super::generate_test_id().await;
+        let product = ProductCreate {
+            name: format!("Drink {}", i),
+            category_ids: vec![category_id],
+            ..create_test_product()
+        };
+        repo.create_product(ctx, id, &product, &mut tx)
+            .await
+            .expect("Failed to create product");
+        in_category.push(id);
+    }
+    let other_id = super::generate_test_id().await;
+    let other = ProductCreate {
+        category_ids: vec![other_category_id],
+        ..create_test_product()
+    };
+    repo.create_product(ctx, other_id, &other, &mut tx)
+        .await
+        .expect("Failed to create product");
+    let deleted_id = super::generate_test_id().await;
+    let deleted = ProductCreate {
+        category_ids: vec![category_id],
+        ..create_test_product()
+    };
+    repo.create_product(ctx, deleted_id, &deleted, &mut tx)
+        .await
+        .expect("Failed to create product");
+    repo.delete_product(ctx, deleted_id, &mut tx)
+        .await
+        .expect("Failed to delete product");
+    let unsellable_id = super::generate_test_id().await;
+    let unsellable = ProductCreate {
+        sellable: false,
+        category_ids: vec![category_id],
+        ..create_test_product()
+    };
+    repo.create_product(ctx, unsellable_id, &unsellable, &mut tx)
         .await
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let variant_id = super::generate_test_id().await;
-    let variant = ProductVariantCreate {
-        product_id,
-        barcode: None, // NULL barcode (no constraint)
-        name: None,
-        metadata: None,
-    };
+    // Only live products of the category, newest first
+    let products = repo
+        .get_by_category(
+            ctx,
+            category_id,
+            false,
+            &PaginationOptions::new(1, 10, None),
+        )
+        .await
+        .expect("Failed to get products by category");
+    let ids: Vec<i64> = products.iter().map(|p| p.id).collect();
+    let mut expected = in_category.clone();
+    expected.push(unsellable_id);
+    expected.reverse();
+    assert_eq!(ids, expected);
 
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+    let page = repo
+        .get_by_category(ctx, category_id, false, &PaginationOptions::new(2, 3, None))
         .await
-        .expect("Failed to create variant");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
+        .expect("Failed to get products by category");
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].id, in_category[0]);
 
-    let saved = repo
-        .get_variant_by_id(&ctx, variant_id)
+    let total = repo
+        .count_by_category(ctx, category_id, false)
         .await
-        .expect("Failed to get variant")
-        .expect("Variant not found");
+        .expect("Failed to count products by category");
+    assert_eq!(total, 4);
 
-    // Barcode is NULL (nullable column)
-    assert_eq!(saved.barcode, None);
-    assert_eq!(saved.name, None);
-    assert_eq!(saved.metadata, None);
+    // Sellable only leaves out the unsellable product
+    let sellable = repo
+        .get_by_category(ctx, category_id, true, &PaginationOptions::new(1, 10, None))
+        .await
+        .expect("Failed to get sellable products by category");
+    let ids: Vec<i64> = sellable.iter().map(|p| p.id).collect();
+    assert_eq!(ids, expected[1..]);
+    let total = repo
+        .count_by_category(ctx, category_id, true)
+        .await
+        .expect("Failed to count sellable products by category");
+    assert_eq!(total, 3);
+
+    let unknown = repo
+        .get_by_category(ctx, 999_999, false, &PaginationOptions::new(1, 10, None))
+        .await
+        .expect("Failed to get products for unknown category");
+    assert!(unknown.is_empty());
 }
 
-pub async fn test_update_variant_barcode<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+pub async fn test_get_all_with_variants<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
 where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
 {
-    let product_id = super::generate_test_id().await;
-    let product = create_test_product();
-
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(&ctx, product_id, &product, &mut tx)
-        .await
-        .expect("Failed to create product");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
-
-    let variant_id = super::generate_test_id().await;
-    let variant = create_test_variant(product_id);
+    let mut product_ids = Vec::new();
+    for i in 0..3 {
+        let id = super::generate_test_id().await;
+        let product = ProductCreate {
+            name: format!("Product {}", i),
+            ..create_test_product()
+        };
+        repo.create_product(ctx, id, &product, &mut tx)
+            .await
+            .expect("Failed to create product");
+        product_ids.push(id);
+    }
 
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+    // First product: two live variants and a deleted one; second: none; third: one
+    let mut first_variants = Vec::new();
+    for _ in 0..3 {
+        let id = super::generate_test_id().await;
+        let variant = ProductVariantCreate {
+            barcode: Some(format!("BC-{}", id)),
+            ..create_test_variant(product_ids[0])
+        };
+        repo.create_variant(ctx, id, &variant, &mut tx)
+            .await
+            .expect("Failed to create variant");
+        first_variants.push(id);
+    }
+    repo.delete_variant(ctx, first_variants[1], &mut tx)
         .await
-        .expect("Failed to create variant");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
-
-    let update = ProductVariantUpdate {
-        barcode: Update::Set("9999999999".to_string()),
-        name: Update::Unchanged,
+        .expect("Failed to delete variant");
+    let third_variant = super::generate_test_id().await;
+    repo.create_variant(
+        ctx,
+        third_variant,
+        &create_test_variant(product_ids[2]),
+        &mut tx,
+    )
+    .await
+    .expect("Failed to create variant");
+
+    // Deleted products are left out along with their variants
+    let deleted_id = super::generate_test_id().await;
+    repo.create_product(ctx, deleted_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    repo.create_variant(
+        ctx,
+        super::generate_test_id().await,
+        &ProductVariantCreate {
+            barcode: None,
+            ..create_test_variant(deleted_id)
+        },
+        &mut tx,
+    )
+    .await
+    .expect("Failed to create variant");
+    repo.delete_product(ctx, deleted_id, &mut tx)
+        .await
+        .expect("Failed to delete product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let products = repo
+        .get_all_with_variants(ctx, &ProductFilter::default(), &super::default_pagination())
+        .await
+        .expect("Failed to get products with variants");
+
+    let ids: Vec<i64> = products.iter().map(|p| p.product.id).collect();
+    assert_eq!(ids, vec![product_ids[2], product_ids[1], product_ids[0]]);
+
+    let variant_ids: Vec<i64> = products[0].variants.iter().map(|v| v.id).collect();
+    assert_eq!(variant_ids, vec![third_variant]);
+    assert!(products[1].variants.is_empty());
+    let variant_ids: Vec<i64> = products[2].variants.iter().map(|v| v.id).collect();
+    assert_eq!(variant_ids, vec![first_variants[0], first_variants[2]]);
+
+    for entry in &products {
+        for variant in &entry.variants {
+            assert_eq!(variant.product.id, entry.product.id);
+        }
+    }
+
+    let page = repo
+        .get_all_with_variants(
+            ctx,
+            &ProductFilter::default(),
+            &PaginationOptions::new(2, 2, None),
+        )
+        .await
+        .expect("Failed to get products with variants");
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].product.id, product_ids[0]);
+    assert_eq!(page[0].variants.len(), 2);
+
+    let empty = repo
+        .get_all_with_variants(
+            ctx,
+            &ProductFilter::default(),
+            &PaginationOptions::new(3, 2, None),
+        )
+        .await
+        .expect("Failed to get products with variants");
+    assert!(empty.is_empty());
+}
+
+pub async fn test_get_all_with_variants_filter<'a, T, P, C>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+    category_repo: &'a C,
+) where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+    C: CategoryRepository,
+{
+    let category_id = super::generate_test_id().await;
+    category_repo
+        .create(ctx, category_id, &category_create_with_name("Drinks"))
+        .await
+        .expect("Failed to create category");
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    let coffee_id = super::generate_test_id().await;
+    let coffee = ProductCreate {
+        name: "Iced Coffee".to_string(),
+        category_ids: vec![category_id],
+        ..create_test_product()
+    };
+    repo.create_product(ctx, coffee_id, &coffee, &mut tx)
+        .await
+        .expect("Failed to create product");
+    let service_id = super::generate_test_id().await;
+    let service = ProductCreate {
+        name: "Coffee Machine Repair".to_string(),
+        product_type: ProductType::Service,
+        sellable: false,
+        ..create_test_product()
+    };
+    repo.create_product(ctx, service_id, &service, &mut tx)
+        .await
+        .expect("Failed to create product");
+    let tea_id = super::generate_test_id().await;
+    let tea = ProductCreate {
+        name: "Green Tea".to_string(),
+        category_ids: vec![category_id],
+        ..create_test_product()
+    };
+    repo.create_product(ctx, tea_id, &tea, &mut tx)
+        .await
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let ids = |products: Vec<ProductWithVariants>| {
+        products.iter().map(|p| p.product.id).collect::<Vec<_>>()
+    };
+
+    let by_name = ProductFilter {
+        name: Some("coffee".to_string()),
+        ..Default::default()
+    };
+    let result = repo
+        .get_all_with_variants(ctx, &by_name, &super::default_pagination())
+        .await
+        .expect("Failed to filter by name");
+    assert_eq!(ids(result), vec![service_id, coffee_id]);
+
+    let by_type = ProductFilter {
+        product_type: Some(ProductType::Service),
+        ..Default::default()
+    };
+    let result = repo
+        .get_all_with_variants(ctx, &by_type, &super::default_pagination())
+        .await
+        .expect("Failed to filter by type");
+    assert_eq!(ids(result), vec![service_id]);
+
+    let by_sellable = ProductFilter {
+        sellable: Some(true),
+        ..Default::default()
+    };
+    let result = repo
+        .get_all_with_variants(ctx, &by_sellable, &super::default_pagination())
+        .await
+        .expect("Failed to filter by sellable");
+    assert_eq!(ids(result), vec![tea_id, coffee_id]);
+
+    let by_category = ProductFilter {
+        category_id: Some(category_id),
+        ..Default::default()
+    };
+    let result = repo
+        .get_all_with_variants(ctx, &by_category, &super::default_pagination())
+        .await
+        .expect("Failed to filter by category");
+    assert_eq!(ids(result), vec![tea_id, coffee_id]);
+
+    let combined = ProductFilter {
+        name: Some("coffee".to_string()),
+        category_id: Some(category_id),
+        ..Default::default()
+    };
+    let result = repo
+        .get_all_with_variants(ctx, &combined, &super::default_pagination())
+        .await
+        .expect("Failed to filter by name and category");
+    assert_eq!(ids(result), vec![coffee_id]);
+}
+
+pub async fn test_count_matches_filter<'a, T, P, C>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+    category_repo: &'a C,
+) where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+    C: CategoryRepository,
+{
+    let category_id = super::generate_test_id().await;
+    category_repo
+        .create(ctx, category_id, &category_create_with_name("Drinks"))
+        .await
+        .expect("Failed to create category");
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    for name in ["Iced Coffee", "Hot Coffee", "Green Tea"] {
+        let product = ProductCreate {
+            name: name.to_string(),
+            category_ids: vec![category_id],
+            ..create_test_product()
+        };
+        repo.create_product(ctx, super::generate_test_id().await, &product, &mut tx)
+            .await
+            .expect("Failed to create product");
+    }
+    repo.create_product(
+        ctx,
+        super::generate_test_id().await,
+        &create_test_product(),
+        &mut tx,
+    )
+    .await
+    .expect("Failed to create product");
+    let deleted_id = super::generate_test_id().await;
+    let deleted = ProductCreate {
+        name: "Old Coffee".to_string(),
+        category_ids: vec![category_id],
+        ..create_test_product()
+    };
+    repo.create_product(ctx, deleted_id, &deleted, &mut tx)
+        .await
+        .expect("Failed to create product");
+    repo.delete_product(ctx, deleted_id, &mut tx)
+        .await
+        .expect("Failed to delete product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let filters = [
+        (ProductFilter::default(), 4),
+        (
+            ProductFilter {
+                category_id: Some(category_id),
+                ..Default::default()
+            },
+            3,
+        ),
+        (
+            ProductFilter {
+                name: Some("coffee".to_string()),
+                category_id: Some(category_id),
+                ..Default::default()
+            },
+            2,
+        ),
+        (
+            ProductFilter {
+                product_type: Some(ProductType::Service),
+                ..Default::default()
+            },
+            0,
+        ),
+    ];
+    for (filter, expected) in filters {
+        let count = repo.count(ctx, &filter).await.expect("Failed to count");
+        assert_eq!(count, expected);
+
+        // A one-item page does not change the count, which matches the list
+        let page = repo
+            .get_all_with_variants(ctx, &filter, &PaginationOptions::new(1, 1, None))
+            .await
+            .expect("Failed to get products");
+        assert_eq!(page.len(), expected.min(1) as usize);
+        let all = repo
+            .get_all_with_variants(ctx, &filter, &super::default_pagination())
+            .await
+            .expect("Failed to get products");
+        assert_eq!(all.len() as u64, count);
+    }
+}
+
+pub async fn test_update_product_not_found<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let update = ProductUpdate {
+        name: Some("Updated".to_string()),
+        description: Update::Unchanged,
+        product_type: None,
+        main_image: Update::Unchanged,
+        sellable: None,
+        buyable: None,
+        editable_price: None,
+        has_variant: None,
         metadata: Update::Unchanged,
+        category_ids: None,
     };
 
-    repo.update_variant(&ctx, variant_id, &update)
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    let result = repo.update_product(&ctx, 999999, &update, &mut tx).await;
+    tx_manager
+        .rollback(tx)
+        .await
+        .expect("Failed to rollback tx");
+
+    assert!(matches!(result, Err(Error::NotFound(_))));
+}
+
+pub async fn test_delete_product_success<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let product = create_test_product();
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &product, &mut tx)
         .await
-        .expect("Failed to update variant");
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.delete_product(&ctx, product_id, &mut tx)
+        .await
+        .expect("Failed to delete product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
 
     let saved = repo
-        .get_variant_by_id(&ctx, variant_id)
+        .get_by_id(&ctx, product_id)
         .await
-        .expect("Failed to get variant")
-        .expect("Variant not found");
+        .expect("Failed to get product");
 
-    assert_eq!(saved.barcode, Some("9999999999".to_string()));
-    // Name should remain unchanged
-    assert_eq!(saved.name, Some("Default Variant".to_string()));
+    assert!(saved.is_none());
 }
 
-pub async fn test_update_variant_clear_name<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+pub async fn test_delete_product_not_found<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    let result = repo.delete_product(&ctx, 999999, &mut tx).await;
+    tx_manager
+        .rollback(tx)
+        .await
+        .expect("Failed to rollback tx");
+
+    assert!(matches!(result, Err(Error::NotFound(_))));
+}
+
+pub async fn test_get_product_by_id_not_found<'a, T, P>(ctx: &Context, _: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let result = repo.get_by_id(&ctx, 999999).await.expect("Failed to query");
+
+    assert!(result.is_none());
+}
+
+pub async fn test_create_variant_success<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
 where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
 {
+    // Create product first
     let product_id = super::generate_test_id().await;
     let product = create_test_product();
 
@@ -641,6 +1187,7 @@ where
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
+    // Create variant
     let variant_id = super::generate_test_id().await;
     let variant = create_test_variant(product_id);
 
@@ -650,15 +1197,51 @@ where
         .expect("Failed to create variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let update = ProductVariantUpdate {
-        barcode: Update::Unchanged,
-        name: Update::Clear,
-        metadata: Update::Unchanged,
+    let saved = repo
+        .get_variant_by_id(&ctx, variant_id)
+        .await
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+
+    assert_eq!(saved.id, variant_id);
+    assert_eq!(saved.product.id, product_id);
+    assert_eq!(saved.barcode, Some("1234567890".to_string()));
+    assert_eq!(saved.name, Some("Default Variant".to_string()));
+    assert_eq!(saved.metadata, Some(json!({"sku": "SKU001"})));
+}
+
+pub async fn test_create_variant_without_optional_fields<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+) where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let product = create_test_product();
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &product, &mut tx)
+        .await
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let variant_id = super::generate_test_id().await;
+    let variant = ProductVariantCreate {
+        product_id,
+        barcode: None, // NULL barcode (no constraint)
+        name: None,
+        price: None,
+        metadata: None,
+        initial_stock: vec![],
     };
 
-    repo.update_variant(&ctx, variant_id, &update)
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
         .await
-        .expect("Failed to update variant");
+        .expect("Failed to create variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
 
     let saved = repo
         .get_variant_by_id(&ctx, variant_id)
@@ -666,10 +1249,13 @@ where
         .expect("Failed to get variant")
         .expect("Variant not found");
 
+    // Barcode is NULL (nullable column)
+    assert_eq!(saved.barcode, None);
     assert_eq!(saved.name, None);
+    assert_eq!(saved.metadata, None);
 }
 
-pub async fn test_update_variant_all_fields<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+pub async fn test_update_variant_barcode<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
 where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
@@ -693,12 +1279,13 @@ where
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
     let update = ProductVariantUpdate {
-        barcode: Update::Set("NEW_BARCODE".to_string()),
-        name: Update::Set("New Variant Name".to_string()),
-        metadata: Update::Set(json!({"new_sku": "SKU999"})),
+        barcode: Update::Set("9999999999".to_string()),
+        name: Update::Unchanged,
+        price: Update::Unchanged,
+        metadata: Update::Unchanged,
     };
 
-    repo.update_variant(&ctx, variant_id, &update)
+    update_variant(&ctx, tx_manager, repo, variant_id, &update)
         .await
         .expect("Failed to update variant");
 
@@ -708,12 +1295,100 @@ where
         .expect("Failed to get variant")
         .expect("Variant not found");
 
-    assert_eq!(saved.barcode, Some("NEW_BARCODE".to_string()));
-    assert_eq!(saved.name, Some("New Variant Name".to_string()));
-    assert_eq!(saved.metadata, Some(json!({"new_sku": "SKU999"})));
+    assert_eq!(saved.barcode, Some("9999999999".to_string()));
+    // Name should remain unchanged
+    assert_eq!(saved.name, Some("Default Variant".to_string()));
 }
 
-pub async fn test_update_variant_not_found<'a, T, P>(ctx: &Context, _: &'a T, repo: &'a P)
+pub async fn test_update_variant_clear_name<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let product = create_test_product();
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &product, &mut tx)
+        .await
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let variant_id = super::generate_test_id().await;
+    let variant = create_test_variant(product_id);
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+        .await
+        .expect("Failed to create variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let update = ProductVariantUpdate {
+        barcode: Update::Unchanged,
+        name: Update::Clear,
+        price: Update::Unchanged,
+        metadata: Update::Unchanged,
+    };
+
+    update_variant(&ctx, tx_manager, repo, variant_id, &update)
+        .await
+        .expect("Failed to update variant");
+
+    let saved = repo
+        .get_variant_by_id(&ctx, variant_id)
+        .await
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+
+    assert_eq!(saved.name, None);
+}
+
+pub async fn test_update_variant_all_fields<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let product = create_test_product();
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &product, &mut tx)
+        .await
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let variant_id = super::generate_test_id().await;
+    let variant = create_test_variant(product_id);
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+        .await
+        .expect("Failed to create variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let update = ProductVariantUpdate {
+        barcode: Update::Set("NEW_BARCODE".to_string()),
+        name: Update::Set("New Variant Name".to_string()),
+        price: Update::Unchanged,
+        metadata: Update::Set(json!({"new_sku": "SKU999"})),
+    };
+
+    update_variant(&ctx, tx_manager, repo, variant_id, &update)
+        .await
+        .expect("Failed to update variant");
+
+    let saved = repo
+        .get_variant_by_id(&ctx, variant_id)
+        .await
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+
+    assert_eq!(saved.barcode, Some("NEW_BARCODE".to_string()));
+    assert_eq!(saved.name, Some("New Variant Name".to_string()));
+    assert_eq!(saved.metadata, Some(json!({"new_sku": "SKU999"})));
+}
+
+pub async fn test_update_variant_not_found<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
 where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
@@ -721,16 +1396,708 @@ where
     let update = ProductVariantUpdate {
         barcode: Update::Set("X".to_string()),
         name: Update::Unchanged,
+        price: Update::Unchanged,
         metadata: Update::Unchanged,
     };
 
-    let result = repo.update_variant(&ctx, 999999, &update).await;
+    let result = update_variant(&ctx, tx_manager, repo, 999999, &update).await;
+
+    assert!(matches!(result, Err(Error::NotFound(_))));
+}
+
+pub async fn test_delete_variant_success<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let product = create_test_product();
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &product, &mut tx)
+        .await
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let variant_id = super::generate_test_id().await;
+    let variant = create_test_variant(product_id);
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+        .await
+        .expect("Failed to create variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.delete_variant(&ctx, variant_id, &mut tx)
+        .await
+        .expect("Failed to delete variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let saved = repo
+        .get_variant_by_id(&ctx, variant_id)
+        .await
+        .expect("Failed to get variant");
+
+    assert!(saved.is_none());
+}
+
+pub async fn test_count_variants<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(ctx, product_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    let mut variant_ids = Vec::new();
+    for _ in 0..3 {
+        let variant_id = super::generate_test_id().await;
+        let variant = ProductVariantCreate {
+            barcode: Some(format!("BC-{}", variant_id)),
+            ..create_test_variant(product_id)
+        };
+        repo.create_variant(ctx, variant_id, &variant, &mut tx)
+            .await
+            .expect("Failed to create variant");
+        variant_ids.push(variant_id);
+    }
+    repo.delete_variant(ctx, variant_ids[0], &mut tx)
+        .await
+        .expect("Failed to delete variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let count = repo
+        .count_variants(ctx, product_id)
+        .await
+        .expect("Failed to count variants");
+    assert_eq!(count, 2);
+
+    let count = repo
+        .count_variants(ctx, super::generate_test_id().await)
+        .await
+        .expect("Failed to count variants");
+    assert_eq!(count, 0);
+}
+
+pub async fn test_touch_product<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let deleted_id = super::generate_test_id().await;
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(ctx, product_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    repo.create_product(ctx, deleted_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    repo.delete_product(ctx, deleted_id, &mut tx)
+        .await
+        .expect("Failed to delete product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let before = repo
+        .get_by_id(ctx, product_id)
+        .await
+        .expect("Failed to get product")
+        .expect("Product not found");
+
+    // Touched by someone other than the creator, who stays the last editor
+    super::wait_for_clock_tick().await;
+    let other_ctx = ctx
+        .clone()
+        .with_actor_id(ctx.actor_id().unwrap_or_default() + 1);
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.touch(&other_ctx, product_id, &mut tx)
+        .await
+        .expect("Failed to touch product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let after = repo
+        .get_by_id(ctx, product_id)
+        .await
+        .expect("Failed to get product")
+        .expect("Product not found");
+    assert!(after.updated_at > before.updated_at);
+    assert_eq!(after.created_at, before.created_at);
+    assert_eq!(after.name, before.name);
+    assert_eq!(after.description, before.description);
+    assert_eq!(after.product_type, before.product_type);
+    assert_eq!(after.main_image, before.main_image);
+    assert_eq!(after.sellable, before.sellable);
+    assert_eq!(after.buyable, before.buyable);
+    assert_eq!(after.editable_price, before.editable_price);
+    assert_eq!(after.has_variant, before.has_variant);
+    assert_eq!(after.metadata, before.metadata);
+    assert_eq!(after.created_by, before.created_by);
+    assert_eq!(after.updated_by, before.updated_by);
+
+    // Deleted and unknown products are not found
+    for id in [deleted_id, super::generate_test_id().await] {
+        let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+        let result = repo.touch(ctx, id, &mut tx).await;
+        tx_manager
+            .rollback(tx)
+            .await
+            .expect("Failed to rollback tx");
+        assert!(matches!(result, Err(Error::NotFound(_))));
+    }
+}
+
+pub async fn test_reassign_variant<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let source_id = super::generate_test_id().await;
+    let target_id = super::generate_test_id().await;
+    let moved_id = super::generate_test_id().await;
+    let staying_id = super::generate_test_id().await;
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(ctx, source_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    repo.create_product(ctx, target_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    repo.create_variant(ctx, moved_id, &create_test_variant(source_id), &mut tx)
+        .await
+        .expect("Failed to create variant");
+    let staying = ProductVariantCreate {
+        barcode: None,
+        ..create_test_variant(source_id)
+    };
+    repo.create_variant(ctx, staying_id, &staying, &mut tx)
+        .await
+        .expect("Failed to create variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let before = repo
+        .get_variant_by_id(ctx, moved_id)
+        .await
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+
+    super::wait_for_clock_tick().await;
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.reassign_variant(ctx, moved_id, target_id, &mut tx)
+        .await
+        .expect("Failed to reassign variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let source_variants: Vec<i64> = repo
+        .get_variant_by_product_id(ctx, source_id)
+        .await
+        .expect("Failed to get variants")
+        .iter()
+        .map(|v| v.id)
+        .collect();
+    assert_eq!(source_variants, vec![staying_id]);
+
+    let target_variants = repo
+        .get_variant_by_product_id(ctx, target_id)
+        .await
+        .expect("Failed to get variants");
+    assert_eq!(target_variants.len(), 1);
+    let moved = &target_variants[0];
+    assert_eq!(moved.id, moved_id);
+    assert_eq!(moved.product.id, target_id);
+    assert_eq!(moved.barcode, before.barcode);
+    assert_eq!(moved.created_at, before.created_at);
+    assert!(moved.updated_at > before.updated_at);
+
+    // Unknown variant
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    let result = repo
+        .reassign_variant(ctx, super::generate_test_id().await, target_id, &mut tx)
+        .await;
+    tx_manager
+        .rollback(tx)
+        .await
+        .expect("Failed to rollback tx");
+    assert!(matches!(result, Err(Error::NotFound(_))));
+}
+
+pub async fn test_reassign_variant_to_deleted_product<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+) where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let source_id = super::generate_test_id().await;
+    let deleted_id = super::generate_test_id().await;
+    let variant_id = super::generate_test_id().await;
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(ctx, source_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    repo.create_product(ctx, deleted_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    repo.delete_product(ctx, deleted_id, &mut tx)
+        .await
+        .expect("Failed to delete product");
+    repo.create_variant(ctx, variant_id, &create_test_variant(source_id), &mut tx)
+        .await
+        .expect("Failed to create variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
 
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    let result = repo
+        .reassign_variant(ctx, variant_id, deleted_id, &mut tx)
+        .await;
+    tx_manager
+        .rollback(tx)
+        .await
+        .expect("Failed to rollback tx");
     assert!(matches!(result, Err(Error::NotFound(_))));
+
+    let variant = repo
+        .get_variant_by_id(ctx, variant_id)
+        .await
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+    assert_eq!(variant.product.id, source_id);
+}
+
+pub async fn test_delete_variant_not_found<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    let result = repo.delete_variant(&ctx, 999999, &mut tx).await;
+    tx_manager
+        .rollback(tx)
+        .await
+        .expect("Failed to rollback tx");
+
+    assert!(matches!(result, Err(Error::NotFound(_))));
+}
+
+pub async fn test_delete_variants_by_product_id<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+) where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let product = create_test_product();
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &product, &mut tx)
+        .await
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    // Create multiple variants
+    let variant_id1 = super::generate_test_id().await;
+    let variant_id2 = super::generate_test_id().await;
+    let variant_id3 = super::generate_test_id().await;
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_variant(
+        &ctx,
+        variant_id1,
+        &ProductVariantCreate {
+            product_id,
+            barcode: Some("V1".to_string()),
+            name: None,
+            price: None,
+            metadata: None,
+            initial_stock: vec![],
+        },
+        &mut tx,
+    )
+    .await
+    .expect("Failed to create variant 1");
+
+    repo.create_variant(
+        &ctx,
+        variant_id2,
+        &ProductVariantCreate {
+            product_id,
+            barcode: Some("V2".to_string()),
+            name: None,
+            price: None,
+            metadata: None,
+            initial_stock: vec![],
+        },
+        &mut tx,
+    )
+    .await
+    .expect("Failed to create variant 2");
+
+    repo.create_variant(
+        &ctx,
+        variant_id3,
+        &ProductVariantCreate {
+            product_id,
+            barcode: Some("V3".to_string()),
+            name: None,
+            price: None,
+            metadata: None,
+            initial_stock: vec![],
+        },
+        &mut tx,
+    )
+    .await
+    .expect("Failed to create variant 3");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    // Verify all variants exist
+    let variants = repo
+        .get_variant_by_product_id(&ctx, product_id)
+        .await
+        .expect("Failed to get variants");
+    assert_eq!(variants.len(), 3);
+
+    // Delete all variants by product_id
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.delete_variants_by_product_id(&ctx, product_id, &mut tx)
+        .await
+        .expect("Failed to delete variants");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    // Verify all variants are deleted
+    let variants = repo
+        .get_variant_by_product_id(&ctx, product_id)
+        .await
+        .expect("Failed to get variants");
+    assert_eq!(variants.len(), 0);
+}
+
+pub async fn test_get_variant_by_barcode_success<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+) where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let product = create_test_product();
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &product, &mut tx)
+        .await
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let variant_id = super::generate_test_id().await;
+    let unique_barcode = format!("BARCODE_{}", variant_id);
+    let variant = ProductVariantCreate {
+        product_id,
+        barcode: Some(unique_barcode.clone()),
+        name: Some("Barcode Test Variant".to_string()),
+        price: None,
+        metadata: None,
+        initial_stock: vec![],
+    };
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+        .await
+        .expect("Failed to create variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let saved = repo
+        .get_variant_by_barcode(&ctx, &unique_barcode)
+        .await
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+
+    assert_eq!(saved.id, variant_id);
+    assert_eq!(saved.barcode, Some(unique_barcode));
+    assert_eq!(saved.product.id, product_id);
+}
+
+pub async fn test_get_variant_by_barcode_not_found<'a, T, P>(ctx: &Context, _: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let result = repo
+        .get_variant_by_barcode(&ctx, "NONEXISTENT_BARCODE")
+        .await
+        .expect("Failed to query");
+
+    assert!(result.is_none());
+}
+
+pub async fn test_get_variant_by_id_not_found<'a, T, P>(ctx: &Context, _: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let result = repo
+        .get_variant_by_id(&ctx, 999999)
+        .await
+        .expect("Failed to query");
+
+    assert!(result.is_none());
+}
+
+pub async fn test_get_variant_by_product_id_success<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+) where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let product = create_test_product();
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &product, &mut tx)
+        .await
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    // Create multiple variants
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    for i in 0..3 {
+        let variant_id = super::generate_test_id().await;
+        let variant = ProductVariantCreate {
+            product_id,
+            barcode: Some(format!("BC_{}", i)),
+            name: Some(format!("Variant {}", i)),
+            price: None,
+            metadata: None,
+            initial_stock: vec![],
+        };
+        repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+            .await
+            .expect("Failed to create variant");
+    }
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let variants = repo
+        .get_variant_by_product_id(&ctx, product_id)
+        .await
+        .expect("Failed to get variants");
+
+    assert_eq!(variants.len(), 3);
+    for variant in &variants {
+        assert_eq!(variant.product.id, product_id);
+    }
+}
+
+pub async fn test_get_variant_by_product_id_empty<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+) where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let product = create_test_product();
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &product, &mut tx)
+        .await
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    // Don't create any variants
+
+    let variants = repo
+        .get_variant_by_product_id(&ctx, product_id)
+        .await
+        .expect("Failed to get variants");
+
+    assert_eq!(variants.len(), 0);
+}
+
+pub async fn test_get_variant_by_product_id_product_not_found<'a, T, P>(
+    ctx: &Context,
+    _: &'a T,
+    repo: &'a P,
+) where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let variants = repo
+        .get_variant_by_product_id(&ctx, 999999)
+        .await
+        .expect("Failed to get variants");
+
+    assert_eq!(variants.len(), 0);
+}
+
+pub async fn test_transaction_rollback_product_creation<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+) where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let product = create_test_product();
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &product, &mut tx)
+        .await
+        .expect("Failed to create product");
+    // Rollback instead of commit
+    tx_manager
+        .rollback(tx)
+        .await
+        .expect("Failed to rollback tx");
+
+    // Product should NOT exist
+    let saved = repo
+        .get_by_id(&ctx, product_id)
+        .await
+        .expect("Failed to query");
+    assert!(saved.is_none());
+}
+
+pub async fn test_transaction_rollback_variant_creation<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+) where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    // Create product first (committed)
+    let product_id = super::generate_test_id().await;
+    let product = create_test_product();
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &product, &mut tx)
+        .await
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    // Create variant but rollback
+    let variant_id = super::generate_test_id().await;
+    let variant = create_test_variant(product_id);
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+        .await
+        .expect("Failed to create variant");
+    tx_manager
+        .rollback(tx)
+        .await
+        .expect("Failed to rollback tx");
+
+    // Variant should NOT exist
+    let saved = repo
+        .get_variant_by_id(&ctx, variant_id)
+        .await
+        .expect("Failed to query");
+    assert!(saved.is_none());
+}
+
+pub async fn test_transaction_product_and_variant_atomic<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+) where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let variant_id = super::generate_test_id().await;
+
+    // Create both in same transaction
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+
+    let product = create_test_product();
+    repo.create_product(&ctx, product_id, &product, &mut tx)
+        .await
+        .expect("Failed to create product");
+
+    let variant = create_test_variant(product_id);
+    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+        .await
+        .expect("Failed to create variant");
+
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    // Both should exist
+    let saved_product = repo
+        .get_by_id(&ctx, product_id)
+        .await
+        .expect("Failed to get product");
+    assert!(saved_product.is_some());
+
+    let saved_variant = repo
+        .get_variant_by_id(&ctx, variant_id)
+        .await
+        .expect("Failed to get variant");
+    assert!(saved_variant.is_some());
+}
+
+pub async fn test_product_with_metadata_json<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+) where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let complex_metadata = json!({
+        "tags": ["electronics", "gadget"],
+        "specs": {
+            "weight": 100,
+            "dimensions": {"width": 10, "height": 20, "depth": 5}
+        },
+        "featured": true
+    });
+
+    let product = ProductCreate {
+        name: "Complex Metadata Product".to_string(),
+        description: None,
+        product_type: ProductType::Product,
+        main_image: None,
+        sellable: true,
+        buyable: true,
+        editable_price: false,
+        has_variant: false,
+        metadata: Some(complex_metadata.clone()),
+        category_ids: vec![],
+    };
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &product, &mut tx)
+        .await
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let saved = repo
+        .get_by_id(&ctx, product_id)
+        .await
+        .expect("Failed to get product")
+        .expect("Product not found");
+
+    assert_eq!(saved.metadata, Some(complex_metadata));
 }
 
-pub async fn test_delete_variant_success<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
-where
+pub async fn test_update_deleted_product_fails<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+) where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
 {
@@ -743,36 +2110,31 @@ where
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let variant_id = super::generate_test_id().await;
-    let variant = create_test_variant(product_id);
-
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
-        .await
-        .expect("Failed to create variant");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
-
+    // Delete the product
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.delete_variant(&ctx, variant_id, &mut tx)
+    repo.delete_product(&ctx, product_id, &mut tx)
         .await
-        .expect("Failed to delete variant");
+        .expect("Failed to delete product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let saved = repo
-        .get_variant_by_id(&ctx, variant_id)
-        .await
-        .expect("Failed to get variant");
-
-    assert!(saved.is_none());
-}
+    // Try to update deleted product
+    let update = ProductUpdate {
+        name: Some("Should Fail".to_string()),
+        description: Update::Unchanged,
+        product_type: None,
+        main_image: Update::Unchanged,
+        sellable: None,
+        buyable: None,
+        editable_price: None,
+        has_variant: None,
+        metadata: Update::Unchanged,
+        category_ids: None,
+    };
 
-pub async fn test_delete_variant_not_found<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
-where
-    T: TransactionManager,
-    P: ProductRepository<T::Transaction<'a>>,
-{
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    let result = repo.delete_variant(&ctx, 999999, &mut tx).await;
+    let result = repo
+        .update_product(&ctx, product_id, &update, &mut tx)
+        .await;
     tx_manager
         .rollback(tx)
         .await
@@ -781,7 +2143,7 @@ where
     assert!(matches!(result, Err(Error::NotFound(_))));
 }
 
-pub async fn test_delete_variants_by_product_id<'a, T, P>(
+pub async fn test_update_deleted_variant_fails<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -798,78 +2160,36 @@ pub async fn test_delete_variants_by_product_id<'a, T, P>(
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Create multiple variants
-    let variant_id1 = super::generate_test_id().await;
-    let variant_id2 = super::generate_test_id().await;
-    let variant_id3 = super::generate_test_id().await;
+    let variant_id = super::generate_test_id().await;
+    let variant = create_test_variant(product_id);
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(
-        &ctx,
-        variant_id1,
-        &ProductVariantCreate {
-            product_id,
-            barcode: Some("V1".to_string()),
-            name: None,
-            metadata: None,
-        },
-        &mut tx,
-    )
-    .await
-    .expect("Failed to create variant 1");
-
-    repo.create_variant(
-        &ctx,
-        variant_id2,
-        &ProductVariantCreate {
-            product_id,
-            barcode: Some("V2".to_string()),
-            name: None,
-            metadata: None,
-        },
-        &mut tx,
-    )
-    .await
-    .expect("Failed to create variant 2");
-
-    repo.create_variant(
-        &ctx,
-        variant_id3,
-        &ProductVariantCreate {
-            product_id,
-            barcode: Some("V3".to_string()),
-            name: None,
-            metadata: None,
-        },
-        &mut tx,
-    )
-    .await
-    .expect("Failed to create variant 3");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
-
-    // Verify all variants exist
-    let variants = repo
-        .get_variant_by_product_id(&ctx, product_id)
+    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
         .await
-        .expect("Failed to get variants");
-    assert_eq!(variants.len(), 3);
+        .expect("Failed to create variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Delete all variants by product_id
+    // Delete the variant
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.delete_variants_by_product_id(&ctx, product_id, &mut tx)
+    repo.delete_variant(&ctx, variant_id, &mut tx)
         .await
-        .expect("Failed to delete variants");
+        .expect("Failed to delete variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Verify all variants are deleted
-    let variants = repo
-        .get_variant_by_product_id(&ctx, product_id)
-        .await
-        .expect("Failed to get variants");
-    assert_eq!(variants.len(), 0);
+    // Try to update deleted variant
+    let update = ProductVariantUpdate {
+        barcode: Update::Set("SHOULD_FAIL".to_string()),
+        name: Update::Unchanged,
+        price: Update::Unchanged,
+        metadata: Update::Unchanged,
+    };
+
+    let result = update_variant(&ctx, tx_manager, repo, variant_id, &update).await;
+
+    assert!(matches!(result, Err(Error::NotFound(_))));
 }
 
-pub async fn test_get_variant_by_barcode_success<'a, T, P>(
+pub async fn test_delete_already_deleted_product_fails<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -886,65 +2206,28 @@ pub async fn test_get_variant_by_barcode_success<'a, T, P>(
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let variant_id = super::generate_test_id().await;
-    let unique_barcode = format!("BARCODE_{}", variant_id);
-    let variant = ProductVariantCreate {
-        product_id,
-        barcode: Some(unique_barcode.clone()),
-        name: Some("Barcode Test Variant".to_string()),
-        metadata: None,
-    };
-
+    // Delete once
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+    repo.delete_product(&ctx, product_id, &mut tx)
         .await
-        .expect("Failed to create variant");
+        .expect("Failed to delete product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let saved = repo
-        .get_variant_by_barcode(&ctx, &unique_barcode)
-        .await
-        .expect("Failed to get variant")
-        .expect("Variant not found");
-
-    assert_eq!(saved.id, variant_id);
-    assert_eq!(saved.barcode, Some(unique_barcode));
-    assert_eq!(saved.product.id, product_id);
-}
-
-pub async fn test_get_variant_by_barcode_not_found<'a, T, P>(ctx: &Context, _: &'a T, repo: &'a P)
-where
-    T: TransactionManager,
-    P: ProductRepository<T::Transaction<'a>>,
-{
-    let result = repo
-        .get_variant_by_barcode(&ctx, "NONEXISTENT_BARCODE")
+    // Try to delete again
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    let result = repo.delete_product(&ctx, product_id, &mut tx).await;
+    tx_manager
+        .rollback(tx)
         .await
-        .expect("Failed to query");
+        .expect("Failed to rollback tx");
 
-    assert!(result.is_none());
+    assert!(matches!(result, Err(Error::NotFound(_))));
 }
 
-pub async fn test_get_variant_by_id_not_found<'a, T, P>(ctx: &Context, _: &'a T, repo: &'a P)
+pub async fn test_get_variant_by_id_success<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
 where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
-{
-    let result = repo
-        .get_variant_by_id(&ctx, 999999)
-        .await
-        .expect("Failed to query");
-
-    assert!(result.is_none());
-}
-
-pub async fn test_get_variant_by_product_id_success<'a, T, P>(
-    ctx: &Context,
-    tx_manager: &'a T,
-    repo: &'a P,
-) where
-    T: TransactionManager,
-    P: ProductRepository<T::Transaction<'a>>,
 {
     let product_id = super::generate_test_id().await;
     let product = create_test_product();
@@ -955,34 +2238,28 @@ pub async fn test_get_variant_by_product_id_success<'a, T, P>(
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Create multiple variants
+    let variant_id = super::generate_test_id().await;
+    let variant = create_test_variant(product_id);
+
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    for i in 0..3 {
-        let variant_id = super::generate_test_id().await;
-        let variant = ProductVariantCreate {
-            product_id,
-            barcode: Some(format!("BC_{}", i)),
-            name: Some(format!("Variant {}", i)),
-            metadata: None,
-        };
-        repo.create_variant(&ctx, variant_id, &variant, &mut tx)
-            .await
-            .expect("Failed to create variant");
-    }
+    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+        .await
+        .expect("Failed to create variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let variants = repo
-        .get_variant_by_product_id(&ctx, product_id)
+    let result = repo
+        .get_variant_by_id(&ctx, variant_id)
         .await
-        .expect("Failed to get variants");
+        .expect("Failed to get variant");
 
-    assert_eq!(variants.len(), 3);
-    for variant in &variants {
-        assert_eq!(variant.product.id, product_id);
-    }
+    assert!(result.is_some());
+    let saved_variant = result.unwrap();
+    assert_eq!(saved_variant.id, variant_id);
+    assert_eq!(saved_variant.product.id, product_id);
+    assert_eq!(saved_variant.barcode, Some("1234567890".to_string()));
 }
 
-pub async fn test_get_variant_by_product_id_empty<'a, T, P>(
+pub async fn test_get_variant_by_id_when_product_deleted<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -999,33 +2276,32 @@ pub async fn test_get_variant_by_product_id_empty<'a, T, P>(
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Don't create any variants
+    let variant_id = super::generate_test_id().await;
+    let variant = create_test_variant(product_id);
 
-    let variants = repo
-        .get_variant_by_product_id(&ctx, product_id)
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
         .await
-        .expect("Failed to get variants");
-
-    assert_eq!(variants.len(), 0);
-}
+        .expect("Failed to create variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-pub async fn test_get_variant_by_product_id_product_not_found<'a, T, P>(
-    ctx: &Context,
-    _: &'a T,
-    repo: &'a P,
-) where
-    T: TransactionManager,
-    P: ProductRepository<T::Transaction<'a>>,
-{
-    let variants = repo
-        .get_variant_by_product_id(&ctx, 999999)
+    // Delete the product
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.delete_product(&ctx, product_id, &mut tx)
+        .await
+        .expect("Failed to delete product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    // Try to get variant - should return None because product is deleted
+    let result = repo
+        .get_variant_by_id(&ctx, variant_id)
         .await
-        .expect("Failed to get variants");
+        .expect("Failed to get variant");
 
-    assert_eq!(variants.len(), 0);
+    assert!(result.is_none());
 }
 
-pub async fn test_transaction_rollback_product_creation<'a, T, P>(
+pub async fn test_get_variant_by_barcode_when_product_deleted<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -1040,100 +2316,168 @@ pub async fn test_transaction_rollback_product_creation<'a, T, P>(
     repo.create_product(&ctx, product_id, &product, &mut tx)
         .await
         .expect("Failed to create product");
-    // Rollback instead of commit
-    tx_manager
-        .rollback(tx)
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let variant_id = super::generate_test_id().await;
+    let variant = create_test_variant(product_id);
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
         .await
-        .expect("Failed to rollback tx");
+        .expect("Failed to create variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Product should NOT exist
-    let saved = repo
-        .get_by_id(&ctx, product_id)
+    // Delete the product
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.delete_product(&ctx, product_id, &mut tx)
         .await
-        .expect("Failed to query");
-    assert!(saved.is_none());
+        .expect("Failed to delete product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    // Try to get variant by barcode - should return None because product is deleted
+    let result = repo
+        .get_variant_by_barcode(&ctx, "1234567890")
+        .await
+        .expect("Failed to get variant");
+
+    assert!(result.is_none());
 }
 
-pub async fn test_transaction_rollback_variant_creation<'a, T, P>(
+pub async fn test_update_product_with_empty_category_update<'a, T, P, C>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
+    category_repo: &'a C,
 ) where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
+    C: CategoryRepository,
 {
-    // Create product first (committed)
+    // Create categories
+    let category_id1 = super::generate_test_id().await;
+    let category_id2 = super::generate_test_id().await;
+
+    category_repo
+        .create(ctx, category_id1, &category_create_with_name("Category 1"))
+        .await
+        .expect("Failed to create category 1");
+    category_repo
+        .create(ctx, category_id2, &category_create_with_name("Category 2"))
+        .await
+        .expect("Failed to create category 2");
+
+    // Create product with categories
     let product_id = super::generate_test_id().await;
-    let product = create_test_product();
+    let product = ProductCreate {
+        category_ids: vec![category_id1, category_id2],
+        ..create_test_product()
+    };
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(&ctx, product_id, &product, &mut tx)
+    repo.create_product(ctx, product_id, &product, &mut tx)
         .await
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Create variant but rollback
-    let variant_id = super::generate_test_id().await;
-    let variant = create_test_variant(product_id);
+    // Update product to remove all categories
+    let update = ProductUpdate {
+        name: Some("Updated Product".to_string()),
+        description: Update::Unchanged,
+        product_type: None,
+        main_image: Update::Unchanged,
+        sellable: None,
+        buyable: None,
+        editable_price: None,
+        has_variant: None,
+        metadata: Update::Unchanged,
+        category_ids: Some(vec![]), // Empty categories
+    };
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
-        .await
-        .expect("Failed to create variant");
-    tx_manager
-        .rollback(tx)
+    repo.update_product(ctx, product_id, &update, &mut tx)
         .await
-        .expect("Failed to rollback tx");
+        .expect("Failed to update product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Variant should NOT exist
-    let saved = repo
-        .get_variant_by_id(&ctx, variant_id)
+    let categories = repo
+        .get_product_category(ctx, product_id)
         .await
-        .expect("Failed to query");
-    assert!(saved.is_none());
+        .expect("Failed to get product categories");
+
+    assert_eq!(categories.len(), 0);
 }
 
-pub async fn test_transaction_product_and_variant_atomic<'a, T, P>(
+pub async fn test_update_product_replace_categories<'a, T, P, C>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
+    category_repo: &'a C,
 ) where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
+    C: CategoryRepository,
 {
+    // Create categories
+    let category_id1 = super::generate_test_id().await;
+    let category_id2 = super::generate_test_id().await;
+    let category_id3 = super::generate_test_id().await;
+
+    category_repo
+        .create(ctx, category_id1, &category_create_with_name("Category 1"))
+        .await
+        .expect("Failed to create category 1");
+    category_repo
+        .create(ctx, category_id2, &category_create_with_name("Category 2"))
+        .await
+        .expect("Failed to create category 2");
+    category_repo
+        .create(ctx, category_id3, &category_create_with_name("Category 3"))
+        .await
+        .expect("Failed to create category 3");
+
+    // Create product with categories
     let product_id = super::generate_test_id().await;
-    let variant_id = super::generate_test_id().await;
+    let product = ProductCreate {
+        category_ids: vec![category_id1, category_id2],
+        ..create_test_product()
+    };
 
-    // Create both in same transaction
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-
-    let product = create_test_product();
-    repo.create_product(&ctx, product_id, &product, &mut tx)
+    repo.create_product(ctx, product_id, &product, &mut tx)
         .await
         .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let variant = create_test_variant(product_id);
-    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
-        .await
-        .expect("Failed to create variant");
+    // Replace with different categories
+    let update = ProductUpdate {
+        name: None,
+        description: Update::Unchanged,
+        product_type: None,
+        main_image: Update::Unchanged,
+        sellable: None,
+        buyable: None,
+        editable_price: None,
+        has_variant: None,
+        metadata: Update::Unchanged,
+        category_ids: Some(vec![category_id3]),
+    };
 
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.update_product(ctx, product_id, &update, &mut tx)
+        .await
+        .expect("Failed to update product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Both should exist
-    let saved_product = repo
-        .get_by_id(&ctx, product_id)
+    // Verify only category_id3 is associated
+    let categories = repo
+        .get_product_category(ctx, product_id)
         .await
-        .expect("Failed to get product");
-    assert!(saved_product.is_some());
+        .expect("Failed to get product categories");
 
-    let saved_variant = repo
-        .get_variant_by_id(&ctx, variant_id)
-        .await
-        .expect("Failed to get variant");
-    assert!(saved_variant.is_some());
+    assert_eq!(categories, vec![category_id3]);
 }
 
-pub async fn test_product_with_metadata_json<'a, T, P>(
+pub async fn test_update_product_only_metadata<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -1142,44 +2486,45 @@ pub async fn test_product_with_metadata_json<'a, T, P>(
     P: ProductRepository<T::Transaction<'a>>,
 {
     let product_id = super::generate_test_id().await;
-    let complex_metadata = json!({
-        "tags": ["electronics", "gadget"],
-        "specs": {
-            "weight": 100,
-            "dimensions": {"width": 10, "height": 20, "depth": 5}
-        },
-        "featured": true
-    });
+    let product = create_test_product();
 
-    let product = ProductCreate {
-        name: "Complex Metadata Product".to_string(),
-        description: None,
-        product_type: "product".to_string(),
-        main_image: None,
-        sellable: true,
-        buyable: true,
-        editable_price: false,
-        has_variant: false,
-        metadata: Some(complex_metadata.clone()),
-        category_ids: vec![],
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(ctx, product_id, &product, &mut tx)
+        .await
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    // Update only metadata
+    let update = ProductUpdate {
+        name: None,
+        description: Update::Unchanged,
+        product_type: None,
+        main_image: Update::Unchanged,
+        sellable: None,
+        buyable: None,
+        editable_price: None,
+        has_variant: None,
+        metadata: Update::Set(json!({"updated": true, "version": 2})),
+        category_ids: None,
     };
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(&ctx, product_id, &product, &mut tx)
+    repo.update_product(ctx, product_id, &update, &mut tx)
         .await
-        .expect("Failed to create product");
+        .expect("Failed to update product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
     let saved = repo
-        .get_by_id(&ctx, product_id)
+        .get_by_id(ctx, product_id)
         .await
         .expect("Failed to get product")
         .expect("Product not found");
 
-    assert_eq!(saved.metadata, Some(complex_metadata));
+    assert_eq!(saved.metadata, Some(json!({"updated": true, "version": 2})));
+    assert_eq!(saved.name, "Test Product"); // Original name unchanged
 }
 
-pub async fn test_update_deleted_product_fails<'a, T, P>(
+pub async fn test_update_product_clear_metadata<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -1191,21 +2536,14 @@ pub async fn test_update_deleted_product_fails<'a, T, P>(
     let product = create_test_product();
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(&ctx, product_id, &product, &mut tx)
+    repo.create_product(ctx, product_id, &product, &mut tx)
         .await
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Delete the product
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.delete_product(&ctx, product_id, &mut tx)
-        .await
-        .expect("Failed to delete product");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
-
-    // Try to update deleted product
+    // Clear metadata
     let update = ProductUpdate {
-        name: Some("Should Fail".to_string()),
+        name: None,
         description: Update::Unchanged,
         product_type: None,
         main_image: Update::Unchanged,
@@ -1213,23 +2551,26 @@ pub async fn test_update_deleted_product_fails<'a, T, P>(
         buyable: None,
         editable_price: None,
         has_variant: None,
-        metadata: Update::Unchanged,
+        metadata: Update::Clear,
         category_ids: None,
     };
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    let result = repo
-        .update_product(&ctx, product_id, &update, &mut tx)
-        .await;
-    tx_manager
-        .rollback(tx)
+    repo.update_product(ctx, product_id, &update, &mut tx)
         .await
-        .expect("Failed to rollback tx");
+        .expect("Failed to update product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    assert!(matches!(result, Err(Error::NotFound(_))));
+    let saved = repo
+        .get_by_id(ctx, product_id)
+        .await
+        .expect("Failed to get product")
+        .expect("Product not found");
+
+    assert_eq!(saved.metadata, None);
 }
 
-pub async fn test_update_deleted_variant_fails<'a, T, P>(
+pub async fn test_update_variant_clear_metadata<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -1241,7 +2582,7 @@ pub async fn test_update_deleted_variant_fails<'a, T, P>(
     let product = create_test_product();
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(&ctx, product_id, &product, &mut tx)
+    repo.create_product(ctx, product_id, &product, &mut tx)
         .await
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
@@ -1250,35 +2591,34 @@ pub async fn test_update_deleted_variant_fails<'a, T, P>(
     let variant = create_test_variant(product_id);
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+    repo.create_variant(ctx, variant_id, &variant, &mut tx)
         .await
         .expect("Failed to create variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Delete the variant
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.delete_variant(&ctx, variant_id, &mut tx)
-        .await
-        .expect("Failed to delete variant");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
-
-    // Try to update deleted variant
+    // Clear metadata
     let update = ProductVariantUpdate {
-        barcode: Update::Set("SHOULD_FAIL".to_string()),
+        barcode: Update::Unchanged,
         name: Update::Unchanged,
-        metadata: Update::Unchanged,
+        price: Update::Unchanged,
+        metadata: Update::Clear,
     };
 
-    let result = repo.update_variant(&ctx, variant_id, &update).await;
+    update_variant(ctx, tx_manager, repo, variant_id, &update)
+        .await
+        .expect("Failed to update variant");
 
-    assert!(matches!(result, Err(Error::NotFound(_))));
+    let saved = repo
+        .get_variant_by_id(ctx, variant_id)
+        .await
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+
+    assert_eq!(saved.metadata, None);
 }
 
-pub async fn test_delete_already_deleted_product_fails<'a, T, P>(
-    ctx: &Context,
-    tx_manager: &'a T,
-    repo: &'a P,
-) where
+pub async fn test_variant_price_round_trip<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
 {
@@ -1286,65 +2626,200 @@ pub async fn test_delete_already_deleted_product_fails<'a, T, P>(
     let product = create_test_product();
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(&ctx, product_id, &product, &mut tx)
+    repo.create_product(ctx, product_id, &product, &mut tx)
         .await
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Delete once
+    let variant_id = super::generate_test_id().await;
+    let variant = ProductVariantCreate {
+        price: Some("12.35".parse().unwrap()),
+        ..create_test_variant(product_id)
+    };
+
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.delete_product(&ctx, product_id, &mut tx)
+    repo.create_variant(ctx, variant_id, &variant, &mut tx)
         .await
-        .expect("Failed to delete product");
+        .expect("Failed to create variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Try to delete again
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    let result = repo.delete_product(&ctx, product_id, &mut tx).await;
-    tx_manager
-        .rollback(tx)
+    let saved = repo
+        .get_variant_by_id(ctx, variant_id)
         .await
-        .expect("Failed to rollback tx");
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+    assert_eq!(saved.price, Some(Money::from_minor(1235)));
 
-    assert!(matches!(result, Err(Error::NotFound(_))));
+    // Change the price
+    let update = ProductVariantUpdate {
+        barcode: Update::Unchanged,
+        name: Update::Unchanged,
+        price: Update::Set(Money::from_major(20)),
+        metadata: Update::Unchanged,
+    };
+    update_variant(ctx, tx_manager, repo, variant_id, &update)
+        .await
+        .expect("Failed to update variant");
+
+    let saved = repo
+        .get_variant_by_id(ctx, variant_id)
+        .await
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+    assert_eq!(saved.price, Some(Money::from_minor(2000)));
+
+    // Clear the price
+    let update = ProductVariantUpdate {
+        barcode: Update::Unchanged,
+        name: Update::Unchanged,
+        price: Update::Clear,
+        metadata: Update::Unchanged,
+    };
+    update_variant(ctx, tx_manager, repo, variant_id, &update)
+        .await
+        .expect("Failed to update variant");
+
+    let saved = repo
+        .get_variant_by_id(ctx, variant_id)
+        .await
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+    assert_eq!(saved.price, None);
 }
 
-pub async fn test_get_variant_by_id_success<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+pub async fn test_variant_level_price<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
 where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
 {
     let product_id = super::generate_test_id().await;
-    let product = create_test_product();
+    let variant_id = super::generate_test_id().await;
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(&ctx, product_id, &product, &mut tx)
+    repo.create_product(ctx, product_id, &create_test_product(), &mut tx)
         .await
         .expect("Failed to create product");
+    repo.create_variant(ctx, variant_id, &create_test_variant(product_id), &mut tx)
+        .await
+        .expect("Failed to create variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
+    let price = repo
+        .get_level_price(ctx, variant_id, 1)
+        .await
+        .expect("Failed to get level price");
+    assert_eq!(price, None);
+
+    repo.set_level_price(ctx, variant_id, 1, &Money::from_minor(900))
+        .await
+        .expect("Failed to set level price");
+    repo.set_level_price(ctx, variant_id, 2, &Money::from_minor(800))
+        .await
+        .expect("Failed to set level price");
+
+    // Setting the same level again replaces the price
+    repo.set_level_price(ctx, variant_id, 1, &Money::from_minor(950))
+        .await
+        .expect("Failed to set level price");
+
+    let level1 = repo
+        .get_level_price(ctx, variant_id, 1)
+        .await
+        .expect("Failed to get level price");
+    let level2 = repo
+        .get_level_price(ctx, variant_id, 2)
+        .await
+        .expect("Failed to get level price");
+    assert_eq!(level1, Some(Money::from_minor(950)));
+    assert_eq!(level2, Some(Money::from_minor(800)));
+}
+
+pub async fn test_variant_price_history<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
     let variant_id = super::generate_test_id().await;
-    let variant = create_test_variant(product_id);
 
+    // Created without a price, so there is nothing to record yet
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+    repo.create_product(ctx, product_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    repo.create_variant(ctx, variant_id, &create_test_variant(product_id), &mut tx)
         .await
         .expect("Failed to create variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let result = repo
-        .get_variant_by_id(&ctx, variant_id)
+    let price_update = |price: i64| ProductVariantUpdate {
+        barcode: Update::Unchanged,
+        name: Update::Unchanged,
+        price: Update::Set(Money::from_minor(price)),
+        metadata: Update::Unchanged,
+    };
+    let first_ctx = ctx.clone().with_actor_id(11);
+    let second_ctx = ctx.clone().with_actor_id(12);
+
+    update_variant(
+        &first_ctx,
+        tx_manager,
+        repo,
+        variant_id,
+        &price_update(1500),
+    )
+    .await
+    .expect("Failed to update variant");
+    update_variant(
+        &second_ctx,
+        tx_manager,
+        repo,
+        variant_id,
+        &price_update(2000),
+    )
+    .await
+    .expect("Failed to update variant");
+    // Same price again and a name-only change are not price changes
+    update_variant(
+        &first_ctx,
+        tx_manager,
+        repo,
+        variant_id,
+        &price_update(2000),
+    )
+    .await
+    .expect("Failed to update variant");
+    let rename = ProductVariantUpdate {
+        barcode: Update::Unchanged,
+        name: Update::Set("Renamed".to_string()),
+        price: Update::Unchanged,
+        metadata: Update::Unchanged,
+    };
+    update_variant(&first_ctx, tx_manager, repo, variant_id, &rename)
         .await
-        .expect("Failed to get variant");
+        .expect("Failed to update variant");
 
-    assert!(result.is_some());
-    let saved_variant = result.unwrap();
-    assert_eq!(saved_variant.id, variant_id);
-    assert_eq!(saved_variant.product.id, product_id);
-    assert_eq!(saved_variant.barcode, Some("1234567890".to_string()));
+    let history = repo
+        .get_price_history(ctx, variant_id, &PaginationOptions::new(1, 10, None))
+        .await
+        .expect("Failed to get price history");
+    assert_eq!(history.len(), 2);
+    assert!(history.iter().all(|h| h.variant_id == variant_id));
+    assert_eq!(history[0].price, Some(Money::from_minor(1500)));
+    assert_eq!(history[0].changed_by, Some(11));
+    assert_eq!(history[1].price, Some(Money::from_minor(2000)));
+    assert_eq!(history[1].changed_by, Some(12));
+    assert!(history[0].effective_from <= history[1].effective_from);
+
+    let second_page = repo
+        .get_price_history(ctx, variant_id, &PaginationOptions::new(2, 1, None))
+        .await
+        .expect("Failed to get price history");
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page[0].changed_by, Some(12));
 }
 
-pub async fn test_get_variant_by_id_when_product_deleted<'a, T, P>(
+pub async fn test_update_variant_clear_barcode<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -1356,7 +2831,7 @@ pub async fn test_get_variant_by_id_when_product_deleted<'a, T, P>(
     let product = create_test_product();
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(&ctx, product_id, &product, &mut tx)
+    repo.create_product(ctx, product_id, &product, &mut tx)
         .await
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
@@ -1365,28 +2840,33 @@ pub async fn test_get_variant_by_id_when_product_deleted<'a, T, P>(
     let variant = create_test_variant(product_id);
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+    repo.create_variant(ctx, variant_id, &variant, &mut tx)
         .await
         .expect("Failed to create variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Delete the product
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.delete_product(&ctx, product_id, &mut tx)
+    // Clear barcode
+    let update = ProductVariantUpdate {
+        barcode: Update::Clear,
+        name: Update::Unchanged,
+        price: Update::Unchanged,
+        metadata: Update::Unchanged,
+    };
+
+    update_variant(ctx, tx_manager, repo, variant_id, &update)
         .await
-        .expect("Failed to delete product");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
+        .expect("Failed to update variant");
 
-    // Try to get variant - should return None because product is deleted
-    let result = repo
-        .get_variant_by_id(&ctx, variant_id)
+    let saved = repo
+        .get_variant_by_id(ctx, variant_id)
         .await
-        .expect("Failed to get variant");
+        .expect("Failed to get variant")
+        .expect("Variant not found");
 
-    assert!(result.is_none());
+    assert_eq!(saved.barcode, None);
 }
 
-pub async fn test_get_variant_by_barcode_when_product_deleted<'a, T, P>(
+pub async fn test_multiple_variants_for_single_product<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -1398,134 +2878,127 @@ pub async fn test_get_variant_by_barcode_when_product_deleted<'a, T, P>(
     let product = create_test_product();
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(&ctx, product_id, &product, &mut tx)
+    repo.create_product(ctx, product_id, &product, &mut tx)
         .await
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let variant_id = super::generate_test_id().await;
-    let variant = create_test_variant(product_id);
+    // Create 5 variants
+    for i in 0..5 {
+        let variant_id = super::generate_test_id().await;
+        let variant = ProductVariantCreate {
+            product_id,
+            barcode: Some(format!("BARCODE{}", i)),
+            name: Some(format!("Variant {}", i)),
+            price: None,
+            metadata: Some(json!({"index": i})),
+            initial_stock: vec![],
+        };
 
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
-        .await
-        .expect("Failed to create variant");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
+        let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+        repo.create_variant(ctx, variant_id, &variant, &mut tx)
+            .await
+            .expect("Failed to create variant");
+        tx_manager.commit(tx).await.expect("Failed to commit tx");
+    }
 
-    // Delete the product
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.delete_product(&ctx, product_id, &mut tx)
+    // Get all variants
+    let variants = repo
+        .get_variant_by_product_id(ctx, product_id)
         .await
-        .expect("Failed to delete product");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
+        .expect("Failed to get variants");
 
-    // Try to get variant by barcode - should return None because product is deleted
-    let result = repo
-        .get_variant_by_barcode(&ctx, "1234567890")
-        .await
-        .expect("Failed to get variant");
+    assert_eq!(variants.len(), 5);
 
-    assert!(result.is_none());
+    // Verify each variant
+    for (i, variant) in variants.iter().enumerate() {
+        assert_eq!(variant.product.id, product_id);
+        assert_eq!(variant.name, Some(format!("Variant {}", i)));
+    }
 }
 
-pub async fn test_update_product_with_empty_category_update<'a, T, P, C>(
+pub async fn test_delete_variants_by_product_id_preserves_other_products<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
-    category_repo: &'a C,
 ) where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
-    C: CategoryRepository,
 {
-    // Create categories
-    let category_id1 = super::generate_test_id().await;
-    let category_id2 = super::generate_test_id().await;
+    // Create two products
+    let product_id1 = super::generate_test_id().await;
+    let product1 = create_test_product();
 
-    category_repo
-        .create(ctx, category_id1, &category_create_with_name("Category 1"))
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(ctx, product_id1, &product1, &mut tx)
         .await
-        .expect("Failed to create category 1");
-    category_repo
-        .create(ctx, category_id2, &category_create_with_name("Category 2"))
+        .expect("Failed to create product1");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let product_id2 = super::generate_test_id().await;
+    let product2 = create_test_product();
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(ctx, product_id2, &product2, &mut tx)
         .await
-        .expect("Failed to create category 2");
+        .expect("Failed to create product2");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Create product with categories
-    let product_id = super::generate_test_id().await;
-    let product = ProductCreate {
-        category_ids: vec![category_id1, category_id2],
-        ..create_test_product()
+    // Create variants for both products
+    let variant_id1 = super::generate_test_id().await;
+    let variant1 = create_test_variant(product_id1);
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_variant(ctx, variant_id1, &variant1, &mut tx)
+        .await
+        .expect("Failed to create variant1");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let variant_id2 = super::generate_test_id().await;
+    let variant2 = ProductVariantCreate {
+        barcode: Some("0987654321".to_string()),
+        ..create_test_variant(product_id2)
     };
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(ctx, product_id, &product, &mut tx)
+    repo.create_variant(ctx, variant_id2, &variant2, &mut tx)
         .await
-        .expect("Failed to create product");
+        .expect("Failed to create variant2");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Update product to remove all categories
-    let update = ProductUpdate {
-        name: Some("Updated Product".to_string()),
-        description: Update::Unchanged,
-        product_type: None,
-        main_image: Update::Unchanged,
-        sellable: None,
-        buyable: None,
-        editable_price: None,
-        has_variant: None,
-        metadata: Update::Unchanged,
-        category_ids: Some(vec![]), // Empty categories
-    };
-
+    // Delete variants for product1 only
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.update_product(ctx, product_id, &update, &mut tx)
+    repo.delete_variants_by_product_id(ctx, product_id1, &mut tx)
         .await
-        .expect("Failed to update product");
+        .expect("Failed to delete variants");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let categories = repo
-        .get_product_category(ctx, product_id)
+    // Verify product1 variants are deleted
+    let variants1 = repo
+        .get_variant_by_product_id(ctx, product_id1)
         .await
-        .expect("Failed to get product categories");
+        .expect("Failed to get variants");
+    assert_eq!(variants1.len(), 0);
 
-    assert_eq!(categories.len(), 0);
+    // Verify product2 variants still exist
+    let variants2 = repo
+        .get_variant_by_product_id(ctx, product_id2)
+        .await
+        .expect("Failed to get variants");
+    assert_eq!(variants2.len(), 1);
+    assert_eq!(variants2[0].id, variant_id2);
 }
 
-pub async fn test_update_product_replace_categories<'a, T, P, C>(
+pub async fn test_update_product_boolean_flags<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
-    category_repo: &'a C,
 ) where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
-    C: CategoryRepository,
 {
-    // Create categories
-    let category_id1 = super::generate_test_id().await;
-    let category_id2 = super::generate_test_id().await;
-    let category_id3 = super::generate_test_id().await;
-
-    category_repo
-        .create(ctx, category_id1, &category_create_with_name("Category 1"))
-        .await
-        .expect("Failed to create category 1");
-    category_repo
-        .create(ctx, category_id2, &category_create_with_name("Category 2"))
-        .await
-        .expect("Failed to create category 2");
-    category_repo
-        .create(ctx, category_id3, &category_create_with_name("Category 3"))
-        .await
-        .expect("Failed to create category 3");
-
-    // Create product with categories
     let product_id = super::generate_test_id().await;
-    let product = ProductCreate {
-        category_ids: vec![category_id1, category_id2],
-        ..create_test_product()
-    };
+    let product = create_test_product();
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
     repo.create_product(ctx, product_id, &product, &mut tx)
@@ -1533,18 +3006,18 @@ pub async fn test_update_product_replace_categories<'a, T, P, C>(
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Replace with different categories
+    // Update all boolean flags
     let update = ProductUpdate {
         name: None,
         description: Update::Unchanged,
         product_type: None,
         main_image: Update::Unchanged,
-        sellable: None,
-        buyable: None,
-        editable_price: None,
-        has_variant: None,
+        sellable: Some(false),
+        buyable: Some(false),
+        editable_price: Some(true),
+        has_variant: Some(true),
         metadata: Update::Unchanged,
-        category_ids: Some(vec![category_id3]),
+        category_ids: None,
     };
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
@@ -1553,20 +3026,20 @@ pub async fn test_update_product_replace_categories<'a, T, P, C>(
         .expect("Failed to update product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Verify only category_id3 is associated
-    let categories = repo
-        .get_product_category(ctx, product_id)
+    let saved = repo
+        .get_by_id(ctx, product_id)
         .await
-        .expect("Failed to get product categories");
+        .expect("Failed to get product")
+        .expect("Product not found");
 
-    assert_eq!(categories, vec![category_id3]);
+    assert!(!saved.sellable);
+    assert!(!saved.buyable);
+    assert!(saved.editable_price);
+    assert!(saved.has_variant);
 }
 
-pub async fn test_update_product_only_metadata<'a, T, P>(
-    ctx: &Context,
-    tx_manager: &'a T,
-    repo: &'a P,
-) where
+pub async fn test_update_product_main_image<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
 {
@@ -1579,17 +3052,17 @@ pub async fn test_update_product_only_metadata<'a, T, P>(
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Update only metadata
+    // Update main_image
     let update = ProductUpdate {
         name: None,
         description: Update::Unchanged,
         product_type: None,
-        main_image: Update::Unchanged,
+        main_image: Update::Set("https://example.com/new-image.jpg".to_string()),
         sellable: None,
         buyable: None,
         editable_price: None,
         has_variant: None,
-        metadata: Update::Set(json!({"updated": true, "version": 2})),
+        metadata: Update::Unchanged,
         category_ids: None,
     };
 
@@ -1605,11 +3078,13 @@ pub async fn test_update_product_only_metadata<'a, T, P>(
         .expect("Failed to get product")
         .expect("Product not found");
 
-    assert_eq!(saved.metadata, Some(json!({"updated": true, "version": 2})));
-    assert_eq!(saved.name, "Test Product"); // Original name unchanged
+    assert_eq!(
+        saved.main_image,
+        Some("https://example.com/new-image.jpg".to_string())
+    );
 }
 
-pub async fn test_update_product_clear_metadata<'a, T, P>(
+pub async fn test_update_product_clear_main_image<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -1626,17 +3101,17 @@ pub async fn test_update_product_clear_metadata<'a, T, P>(
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Clear metadata
+    // Clear main_image
     let update = ProductUpdate {
         name: None,
         description: Update::Unchanged,
         product_type: None,
-        main_image: Update::Unchanged,
+        main_image: Update::Clear,
         sellable: None,
         buyable: None,
         editable_price: None,
         has_variant: None,
-        metadata: Update::Clear,
+        metadata: Update::Unchanged,
         category_ids: None,
     };
 
@@ -1652,14 +3127,11 @@ pub async fn test_update_product_clear_metadata<'a, T, P>(
         .expect("Failed to get product")
         .expect("Product not found");
 
-    assert_eq!(saved.metadata, None);
+    assert_eq!(saved.main_image, None);
 }
 
-pub async fn test_update_variant_clear_metadata<'a, T, P>(
-    ctx: &Context,
-    tx_manager: &'a T,
-    repo: &'a P,
-) where
+pub async fn test_update_product_type<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
 {
@@ -1672,40 +3144,37 @@ pub async fn test_update_variant_clear_metadata<'a, T, P>(
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let variant_id = super::generate_test_id().await;
-    let variant = create_test_variant(product_id);
+    // Update product_type
+    let update = ProductUpdate {
+        name: None,
+        description: Update::Unchanged,
+        product_type: Some(ProductType::Service),
+        main_image: Update::Unchanged,
+        sellable: None,
+        buyable: None,
+        editable_price: None,
+        has_variant: None,
+        metadata: Update::Unchanged,
+        category_ids: None,
+    };
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(ctx, variant_id, &variant, &mut tx)
+    repo.update_product(ctx, product_id, &update, &mut tx)
         .await
-        .expect("Failed to create variant");
+        .expect("Failed to update product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Clear metadata
-    let update = ProductVariantUpdate {
-        barcode: Update::Unchanged,
-        name: Update::Unchanged,
-        metadata: Update::Clear,
-    };
-
-    repo.update_variant(ctx, variant_id, &update)
-        .await
-        .expect("Failed to update variant");
-
     let saved = repo
-        .get_variant_by_id(ctx, variant_id)
+        .get_by_id(ctx, product_id)
         .await
-        .expect("Failed to get variant")
-        .expect("Variant not found");
+        .expect("Failed to get product")
+        .expect("Product not found");
 
-    assert_eq!(saved.metadata, None);
+    assert_eq!(saved.product_type, ProductType::Service);
 }
 
-pub async fn test_update_variant_clear_barcode<'a, T, P>(
-    ctx: &Context,
-    tx_manager: &'a T,
-    repo: &'a P,
-) where
+pub async fn test_variant_without_barcode<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
 {
@@ -1719,7 +3188,14 @@ pub async fn test_update_variant_clear_barcode<'a, T, P>(
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
     let variant_id = super::generate_test_id().await;
-    let variant = create_test_variant(product_id);
+    let variant = ProductVariantCreate {
+        product_id,
+        barcode: None, // No barcode
+        name: Some("No Barcode Variant".to_string()),
+        price: None,
+        metadata: None,
+        initial_stock: vec![],
+    };
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
     repo.create_variant(ctx, variant_id, &variant, &mut tx)
@@ -1727,17 +3203,6 @@ pub async fn test_update_variant_clear_barcode<'a, T, P>(
         .expect("Failed to create variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Clear barcode
-    let update = ProductVariantUpdate {
-        barcode: Update::Clear,
-        name: Update::Unchanged,
-        metadata: Update::Unchanged,
-    };
-
-    repo.update_variant(ctx, variant_id, &update)
-        .await
-        .expect("Failed to update variant");
-
     let saved = repo
         .get_variant_by_id(ctx, variant_id)
         .await
@@ -1745,127 +3210,65 @@ pub async fn test_update_variant_clear_barcode<'a, T, P>(
         .expect("Variant not found");
 
     assert_eq!(saved.barcode, None);
+    assert_eq!(saved.name, Some("No Barcode Variant".to_string()));
 }
 
-pub async fn test_multiple_variants_for_single_product<'a, T, P>(
-    ctx: &Context,
-    tx_manager: &'a T,
-    repo: &'a P,
-) where
-    T: TransactionManager,
-    P: ProductRepository<T::Transaction<'a>>,
-{
-    let product_id = super::generate_test_id().await;
-    let product = create_test_product();
-
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(ctx, product_id, &product, &mut tx)
-        .await
-        .expect("Failed to create product");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
-
-    // Create 5 variants
-    for i in 0..5 {
-        let variant_id = super::generate_test_id().await;
-        let variant = ProductVariantCreate {
-            product_id,
-            barcode: Some(format!("BARCODE{}", i)),
-            name: Some(format!("Variant {}", i)),
-            metadata: Some(json!({"index": i})),
-        };
-
-        let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-        repo.create_variant(ctx, variant_id, &variant, &mut tx)
-            .await
-            .expect("Failed to create variant");
-        tx_manager.commit(tx).await.expect("Failed to commit tx");
-    }
-
-    // Get all variants
-    let variants = repo
-        .get_variant_by_product_id(ctx, product_id)
-        .await
-        .expect("Failed to get variants");
-
-    assert_eq!(variants.len(), 5);
-
-    // Verify each variant
-    for (i, variant) in variants.iter().enumerate() {
-        assert_eq!(variant.product.id, product_id);
-        assert_eq!(variant.name, Some(format!("Variant {}", i)));
-    }
-}
-
-pub async fn test_delete_variants_by_product_id_preserves_other_products<'a, T, P>(
-    ctx: &Context,
-    tx_manager: &'a T,
-    repo: &'a P,
-) where
-    T: TransactionManager,
-    P: ProductRepository<T::Transaction<'a>>,
-{
-    // Create two products
-    let product_id1 = super::generate_test_id().await;
-    let product1 = create_test_product();
-
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(ctx, product_id1, &product1, &mut tx)
-        .await
-        .expect("Failed to create product1");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
-
-    let product_id2 = super::generate_test_id().await;
-    let product2 = create_test_product();
+pub async fn test_get_deleted_variant_returns_none<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+) where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let product = create_test_product();
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(ctx, product_id2, &product2, &mut tx)
+    repo.create_product(ctx, product_id, &product, &mut tx)
         .await
-        .expect("Failed to create product2");
+        .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Create variants for both products
-    let variant_id1 = super::generate_test_id().await;
-    let variant1 = create_test_variant(product_id1);
+    let variant_id = super::generate_test_id().await;
+    let variant = create_test_variant(product_id);
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(ctx, variant_id1, &variant1, &mut tx)
+    repo.create_variant(ctx, variant_id, &variant, &mut tx)
         .await
-        .expect("Failed to create variant1");
+        .expect("Failed to create variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let variant_id2 = super::generate_test_id().await;
-    let variant2 = create_test_variant(product_id2);
-
+    // Delete variant
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(ctx, variant_id2, &variant2, &mut tx)
+    repo.delete_variant(ctx, variant_id, &mut tx)
         .await
-        .expect("Failed to create variant2");
+        .expect("Failed to delete variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Delete variants for product1 only
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.delete_variants_by_product_id(ctx, product_id1, &mut tx)
+    // Try to get by ID
+    let by_id = repo
+        .get_variant_by_id(ctx, variant_id)
         .await
-        .expect("Failed to delete variants");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
+        .expect("Failed to get variant");
+    assert!(by_id.is_none());
 
-    // Verify product1 variants are deleted
-    let variants1 = repo
-        .get_variant_by_product_id(ctx, product_id1)
+    // Try to get by barcode
+    let by_barcode = repo
+        .get_variant_by_barcode(ctx, "1234567890")
         .await
-        .expect("Failed to get variants");
-    assert_eq!(variants1.len(), 0);
+        .expect("Failed to get variant");
+    assert!(by_barcode.is_none());
 
-    // Verify product2 variants still exist
-    let variants2 = repo
-        .get_variant_by_product_id(ctx, product_id2)
+    // Verify not in product variants list
+    let variants = repo
+        .get_variant_by_product_id(ctx, product_id)
         .await
         .expect("Failed to get variants");
-    assert_eq!(variants2.len(), 1);
-    assert_eq!(variants2[0].id, variant_id2);
+    assert_eq!(variants.len(), 0);
 }
 
-pub async fn test_update_product_boolean_flags<'a, T, P>(
+pub async fn test_get_deleted_product_returns_none<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -1882,39 +3285,22 @@ pub async fn test_update_product_boolean_flags<'a, T, P>(
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Update all boolean flags
-    let update = ProductUpdate {
-        name: None,
-        description: Update::Unchanged,
-        product_type: None,
-        main_image: Update::Unchanged,
-        sellable: Some(false),
-        buyable: Some(false),
-        editable_price: Some(true),
-        has_variant: Some(true),
-        metadata: Update::Unchanged,
-        category_ids: None,
-    };
-
+    // Delete product
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.update_product(ctx, product_id, &update, &mut tx)
+    repo.delete_product(ctx, product_id, &mut tx)
         .await
-        .expect("Failed to update product");
+        .expect("Failed to delete product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let saved = repo
+    // Try to get by ID
+    let result = repo
         .get_by_id(ctx, product_id)
         .await
-        .expect("Failed to get product")
-        .expect("Product not found");
-
-    assert!(!saved.sellable);
-    assert!(!saved.buyable);
-    assert!(saved.editable_price);
-    assert!(saved.has_variant);
+        .expect("Failed to get product");
+    assert!(result.is_none());
 }
 
-pub async fn test_update_product_main_image<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+pub async fn test_update_variant_only_name<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
 where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
@@ -1928,39 +3314,38 @@ where
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Update main_image
-    let update = ProductUpdate {
-        name: None,
-        description: Update::Unchanged,
-        product_type: None,
-        main_image: Update::Set("https://example.com/new-image.jpg".to_string()),
-        sellable: None,
-        buyable: None,
-        editable_price: None,
-        has_variant: None,
-        metadata: Update::Unchanged,
-        category_ids: None,
-    };
+    let variant_id = super::generate_test_id().await;
+    let variant = create_test_variant(product_id);
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.update_product(ctx, product_id, &update, &mut tx)
+    repo.create_variant(ctx, variant_id, &variant, &mut tx)
         .await
-        .expect("Failed to update product");
+        .expect("Failed to create variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
+    // Update only name
+    let update = ProductVariantUpdate {
+        barcode: Update::Unchanged,
+        name: Update::Set("New Name".to_string()),
+        price: Update::Unchanged,
+        metadata: Update::Unchanged,
+    };
+
+    update_variant(ctx, tx_manager, repo, variant_id, &update)
+        .await
+        .expect("Failed to update variant");
+
     let saved = repo
-        .get_by_id(ctx, product_id)
+        .get_variant_by_id(ctx, variant_id)
         .await
-        .expect("Failed to get product")
-        .expect("Product not found");
+        .expect("Failed to get variant")
+        .expect("Variant not found");
 
-    assert_eq!(
-        saved.main_image,
-        Some("https://example.com/new-image.jpg".to_string())
-    );
+    assert_eq!(saved.name, Some("New Name".to_string()));
+    assert_eq!(saved.barcode, Some("1234567890".to_string())); // Unchanged
 }
 
-pub async fn test_update_product_clear_main_image<'a, T, P>(
+pub async fn test_update_variant_only_barcode<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -1977,37 +3362,42 @@ pub async fn test_update_product_clear_main_image<'a, T, P>(
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Clear main_image
-    let update = ProductUpdate {
-        name: None,
-        description: Update::Unchanged,
-        product_type: None,
-        main_image: Update::Clear,
-        sellable: None,
-        buyable: None,
-        editable_price: None,
-        has_variant: None,
-        metadata: Update::Unchanged,
-        category_ids: None,
-    };
+    let variant_id = super::generate_test_id().await;
+    let variant = create_test_variant(product_id);
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.update_product(ctx, product_id, &update, &mut tx)
+    repo.create_variant(ctx, variant_id, &variant, &mut tx)
         .await
-        .expect("Failed to update product");
+        .expect("Failed to create variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
+    // Update only barcode
+    let update = ProductVariantUpdate {
+        barcode: Update::Set("NEW-BARCODE-123".to_string()),
+        name: Update::Unchanged,
+        price: Update::Unchanged,
+        metadata: Update::Unchanged,
+    };
+
+    update_variant(ctx, tx_manager, repo, variant_id, &update)
+        .await
+        .expect("Failed to update variant");
+
     let saved = repo
-        .get_by_id(ctx, product_id)
+        .get_variant_by_id(ctx, variant_id)
         .await
-        .expect("Failed to get product")
-        .expect("Product not found");
+        .expect("Failed to get variant")
+        .expect("Variant not found");
 
-    assert_eq!(saved.main_image, None);
+    assert_eq!(saved.barcode, Some("NEW-BARCODE-123".to_string()));
+    assert_eq!(saved.name, Some("Default Variant".to_string())); // Unchanged
 }
 
-pub async fn test_update_product_type<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
-where
+pub async fn test_update_variant_set_metadata<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+) where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
 {
@@ -2015,16 +3405,66 @@ where
     let product = create_test_product();
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(ctx, product_id, &product, &mut tx)
+    repo.create_product(&ctx, product_id, &product, &mut tx)
         .await
         .expect("Failed to create product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Update product_type
+    let variant_id = super::generate_test_id().await;
+    let variant = create_test_variant(product_id);
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+        .await
+        .expect("Failed to create variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    // Set new metadata
+    let update = ProductVariantUpdate {
+        barcode: Update::Unchanged,
+        name: Update::Unchanged,
+        price: Update::Unchanged,
+        metadata: Update::Set(json!({"new": "data", "count": 42})),
+    };
+
+    update_variant(&ctx, tx_manager, repo, variant_id, &update)
+        .await
+        .expect("Failed to update variant");
+
+    let saved = repo
+        .get_variant_by_id(&ctx, variant_id)
+        .await
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+
+    assert_eq!(saved.metadata, Some(json!({"new": "data", "count": 42})));
+}
+
+pub async fn test_update_product_touches_updated_at<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+) where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let product_id = super::generate_test_id().await;
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(ctx, product_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+    let before = repo
+        .get_by_id(ctx, product_id)
+        .await
+        .expect("Failed to get product")
+        .expect("Product not found");
+
+    super::wait_for_clock_tick().await;
     let update = ProductUpdate {
-        name: None,
+        name: Some("Touched Product".to_string()),
         description: Update::Unchanged,
-        product_type: Some("service".to_string()),
+        product_type: None,
         main_image: Update::Unchanged,
         sellable: None,
         buyable: None,
@@ -2033,61 +3473,66 @@ where
         metadata: Update::Unchanged,
         category_ids: None,
     };
-
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
     repo.update_product(ctx, product_id, &update, &mut tx)
         .await
         .expect("Failed to update product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let saved = repo
+    let after = repo
         .get_by_id(ctx, product_id)
         .await
         .expect("Failed to get product")
         .expect("Product not found");
-
-    assert_eq!(saved.product_type, "service");
+    assert!(after.updated_at > before.updated_at);
+    assert_eq!(after.created_at, before.created_at);
 }
 
-pub async fn test_variant_without_barcode<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
-where
+pub async fn test_update_variant_touches_updated_at<'a, T, P>(
+    ctx: &Context,
+    tx_manager: &'a T,
+    repo: &'a P,
+) where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
 {
     let product_id = super::generate_test_id().await;
-    let product = create_test_product();
-
+    let variant_id = super::generate_test_id().await;
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(ctx, product_id, &product, &mut tx)
+    repo.create_product(ctx, product_id, &create_test_product(), &mut tx)
         .await
         .expect("Failed to create product");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
-
-    let variant_id = super::generate_test_id().await;
-    let variant = ProductVariantCreate {
-        product_id,
-        barcode: None, // No barcode
-        name: Some("No Barcode Variant".to_string()),
-        metadata: None,
-    };
-
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(ctx, variant_id, &variant, &mut tx)
+    repo.create_variant(ctx, variant_id, &create_test_variant(product_id), &mut tx)
         .await
         .expect("Failed to create variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
-
-    let saved = repo
+    let before = repo
         .get_variant_by_id(ctx, variant_id)
         .await
         .expect("Failed to get variant")
         .expect("Variant not found");
 
-    assert_eq!(saved.barcode, None);
-    assert_eq!(saved.name, Some("No Barcode Variant".to_string()));
+    super::wait_for_clock_tick().await;
+    let update = ProductVariantUpdate {
+        barcode: Update::Unchanged,
+        name: Update::Set("Touched Variant".to_string()),
+        price: Update::Unchanged,
+        metadata: Update::Unchanged,
+    };
+    update_variant(ctx, tx_manager, repo, variant_id, &update)
+        .await
+        .expect("Failed to update variant");
+
+    let after = repo
+        .get_variant_by_id(ctx, variant_id)
+        .await
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+    assert!(after.updated_at > before.updated_at);
+    assert_eq!(after.created_at, before.created_at);
 }
 
-pub async fn test_get_deleted_variant_returns_none<'a, T, P>(
+pub async fn test_variant_duplicate_barcode_conflicts<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -2096,53 +3541,49 @@ pub async fn test_get_deleted_variant_returns_none<'a, T, P>(
     P: ProductRepository<T::Transaction<'a>>,
 {
     let product_id = super::generate_test_id().await;
-    let product = create_test_product();
+    let first_id = super::generate_test_id().await;
+    let second_id = super::generate_test_id().await;
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(ctx, product_id, &product, &mut tx)
+    repo.create_product(ctx, product_id, &create_test_product(), &mut tx)
         .await
         .expect("Failed to create product");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
-
-    let variant_id = super::generate_test_id().await;
-    let variant = create_test_variant(product_id);
-
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(ctx, variant_id, &variant, &mut tx)
+    repo.create_variant(ctx, first_id, &create_test_variant(product_id), &mut tx)
         .await
         .expect("Failed to create variant");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
+    let result = repo
+        .create_variant(ctx, second_id, &create_test_variant(product_id), &mut tx)
+        .await;
+    assert!(matches!(result, Err(Error::Conflict(_))));
 
-    // Delete variant
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.delete_variant(ctx, variant_id, &mut tx)
+    let other = ProductVariantCreate {
+        barcode: Some("0987654321".to_string()),
+        ..create_test_variant(product_id)
+    };
+    repo.create_variant(ctx, second_id, &other, &mut tx)
         .await
-        .expect("Failed to delete variant");
+        .expect("Failed to create variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Try to get by ID
-    let by_id = repo
-        .get_variant_by_id(ctx, variant_id)
-        .await
-        .expect("Failed to get variant");
-    assert!(by_id.is_none());
-
-    // Try to get by barcode
-    let by_barcode = repo
-        .get_variant_by_barcode(ctx, "1234567890")
-        .await
-        .expect("Failed to get variant");
-    assert!(by_barcode.is_none());
+    // Changing a barcode to one in use is rejected the same way
+    let update = ProductVariantUpdate {
+        barcode: Update::Set("1234567890".to_string()),
+        name: Update::Unchanged,
+        price: Update::Unchanged,
+        metadata: Update::Unchanged,
+    };
+    let result = update_variant(ctx, tx_manager, repo, second_id, &update).await;
+    assert!(matches!(result, Err(Error::Conflict(_))));
 
-    // Verify not in product variants list
-    let variants = repo
-        .get_variant_by_product_id(ctx, product_id)
+    let unchanged = repo
+        .get_variant_by_id(ctx, second_id)
         .await
-        .expect("Failed to get variants");
-    assert_eq!(variants.len(), 0);
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+    assert_eq!(unchanged.barcode, Some("0987654321".to_string()));
 }
 
-pub async fn test_get_deleted_product_returns_none<'a, T, P>(
+pub async fn test_variant_reuses_deleted_barcode<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -2151,74 +3592,86 @@ pub async fn test_get_deleted_product_returns_none<'a, T, P>(
     P: ProductRepository<T::Transaction<'a>>,
 {
     let product_id = super::generate_test_id().await;
-    let product = create_test_product();
+    let old_id = super::generate_test_id().await;
+    let new_id = super::generate_test_id().await;
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(ctx, product_id, &product, &mut tx)
+    repo.create_product(ctx, product_id, &create_test_product(), &mut tx)
         .await
         .expect("Failed to create product");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
-
-    // Delete product
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.delete_product(ctx, product_id, &mut tx)
+    repo.create_variant(ctx, old_id, &create_test_variant(product_id), &mut tx)
         .await
-        .expect("Failed to delete product");
+        .expect("Failed to create variant");
+    repo.delete_variant(ctx, old_id, &mut tx)
+        .await
+        .expect("Failed to delete variant");
+    repo.create_variant(ctx, new_id, &create_test_variant(product_id), &mut tx)
+        .await
+        .expect("Failed to reuse barcode of deleted variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Try to get by ID
-    let result = repo
-        .get_by_id(ctx, product_id)
+    let found = repo
+        .get_variant_by_barcode(ctx, "1234567890")
         .await
-        .expect("Failed to get product");
-    assert!(result.is_none());
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+    assert_eq!(found.id, new_id);
 }
 
-pub async fn test_update_variant_only_name<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+pub async fn test_upsert_variant_by_barcode<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
 where
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
 {
     let product_id = super::generate_test_id().await;
-    let product = create_test_product();
+    let new_id = super::generate_test_id().await;
+    let unused_id = super::generate_test_id().await;
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(ctx, product_id, &product, &mut tx)
+    repo.create_product(ctx, product_id, &create_test_product(), &mut tx)
         .await
         .expect("Failed to create product");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
-
-    let variant_id = super::generate_test_id().await;
-    let variant = create_test_variant(product_id);
-
-    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(ctx, variant_id, &variant, &mut tx)
+    let variant = ProductVariantUpsert {
+        barcode: "FEED-001".to_string(),
+        name: Some("Feed 250ml".to_string()),
+        metadata: Some(json!({"feed": "supplier-a"})),
+    };
+    let created = repo
+        .upsert_variant_by_barcode(ctx, new_id, product_id, &variant, &mut tx)
         .await
-        .expect("Failed to create variant");
-    tx_manager.commit(tx).await.expect("Failed to commit tx");
+        .expect("Failed to upsert variant");
+    assert_eq!(created, (new_id, true));
 
-    // Update only name
-    let update = ProductVariantUpdate {
-        barcode: Update::Unchanged,
-        name: Update::Set("New Name".to_string()),
-        metadata: Update::Unchanged,
+    // Same barcode again updates in place and ignores the fresh id
+    let renamed = ProductVariantUpsert {
+        name: Some("Feed 300ml".to_string()),
+        metadata: None,
+        ..variant
     };
-
-    repo.update_variant(ctx, variant_id, &update)
+    let updated = repo
+        .upsert_variant_by_barcode(ctx, unused_id, product_id, &renamed, &mut tx)
         .await
-        .expect("Failed to update variant");
+        .expect("Failed to upsert variant");
+    assert_eq!(updated, (new_id, false));
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let saved = repo
-        .get_variant_by_id(ctx, variant_id)
+    let stored = repo
+        .get_variant_by_barcode(ctx, "FEED-001")
         .await
         .expect("Failed to get variant")
         .expect("Variant not found");
-
-    assert_eq!(saved.name, Some("New Name".to_string()));
-    assert_eq!(saved.barcode, Some("1234567890".to_string())); // Unchanged
+    assert_eq!(stored.id, new_id);
+    assert_eq!(stored.name, Some("Feed 300ml".to_string()));
+    assert_eq!(stored.metadata, None);
+    assert!(
+        repo.get_variant_by_id(ctx, unused_id)
+            .await
+            .expect("Failed to get variant")
+            .is_none()
+    );
 }
 
-pub async fn test_update_variant_only_barcode<'a, T, P>(
+pub async fn test_upsert_variant_by_barcode_other_product_conflicts<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -2226,46 +3679,148 @@ pub async fn test_update_variant_only_barcode<'a, T, P>(
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
 {
-    let product_id = super::generate_test_id().await;
-    let product = create_test_product();
+    let owner_id = super::generate_test_id().await;
+    let other_id = super::generate_test_id().await;
+    let variant_id = super::generate_test_id().await;
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(ctx, product_id, &product, &mut tx)
+    repo.create_product(ctx, owner_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    repo.create_product(ctx, other_id, &create_test_product(), &mut tx)
         .await
         .expect("Failed to create product");
+    repo.create_variant(ctx, variant_id, &create_test_variant(owner_id), &mut tx)
+        .await
+        .expect("Failed to create variant");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let variant_id = super::generate_test_id().await;
-    let variant = create_test_variant(product_id);
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    let variant = ProductVariantUpsert {
+        barcode: "1234567890".to_string(),
+        name: Some("Taken".to_string()),
+        metadata: None,
+    };
+    let id = super::generate_test_id().await;
+    let result = repo
+        .upsert_variant_by_barcode(ctx, id, other_id, &variant, &mut tx)
+        .await;
+    assert!(matches!(result, Err(Error::Conflict(_))));
+    tx_manager
+        .rollback(tx)
+        .await
+        .expect("Failed to rollback tx");
+
+    let untouched = repo
+        .get_variant_by_id(ctx, variant_id)
+        .await
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+    assert_eq!(untouched.product.id, owner_id);
+    assert_ne!(untouched.name, Some("Taken".to_string()));
+}
+
+pub async fn test_get_variants_with_filter<'a, T, P>(ctx: &Context, tx_manager: &'a T, repo: &'a P)
+where
+    T: TransactionManager,
+    P: ProductRepository<T::Transaction<'a>>,
+{
+    let shirt_id = super::generate_test_id().await;
+    let mug_id = super::generate_test_id().await;
+    let deleted_id = super::generate_test_id().await;
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(ctx, variant_id, &variant, &mut tx)
+    for product_id in [shirt_id, mug_id, deleted_id] {
+        repo.create_product(ctx, product_id, &create_test_product(), &mut tx)
+            .await
+            .expect("Failed to create product");
+    }
+    let variants = [
+        (shirt_id, "VAR-S", "Shirt Small"),
+        (shirt_id, "VAR-M", "Shirt Medium"),
+        (shirt_id, "VAR-L", "Shirt Large"),
+        (shirt_id, "VAR-XL", "Shirt Extra Large"),
+        (mug_id, "VAR-MUG-L", "Mug Large"),
+        (deleted_id, "VAR-OLD-L", "Old Large"),
+    ];
+    let mut ids = Vec::new();
+    for (product_id, barcode, name) in variants {
+        let id = super::generate_test_id().await;
+        let variant = ProductVariantCreate {
+            barcode: Some(barcode.to_string()),
+            name: Some(name.to_string()),
+            ..create_test_variant(product_id)
+        };
+        repo.create_variant(ctx, id, &variant, &mut tx)
+            .await
+            .expect("Failed to create variant");
+        ids.push(id);
+    }
+    // A deleted variant and the variants of a deleted product are left out
+    repo.delete_variant(ctx, ids[3], &mut tx)
         .await
-        .expect("Failed to create variant");
+        .expect("Failed to delete variant");
+    repo.delete_product(ctx, deleted_id, &mut tx)
+        .await
+        .expect("Failed to delete product");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Update only barcode
-    let update = ProductVariantUpdate {
-        barcode: Update::Set("NEW-BARCODE-123".to_string()),
-        name: Update::Unchanged,
-        metadata: Update::Unchanged,
+    let names = |variants: Vec<crate::domain::model::product::ProductVariant>| {
+        variants
+            .into_iter()
+            .map(|v| v.name.unwrap())
+            .collect::<Vec<_>>()
     };
 
-    repo.update_variant(ctx, variant_id, &update)
+    let by_name = ProductVariantFilter {
+        name: Some("large".to_string()),
+        ..Default::default()
+    };
+    let found = repo
+        .get_variants(ctx, &by_name, &PaginationOptions::new(1, 10, None))
         .await
-        .expect("Failed to update variant");
+        .expect("Failed to get variants");
+    assert_eq!(names(found.clone()), vec!["Mug Large", "Shirt Large"]);
+    assert_eq!(found[0].product.id, mug_id);
+    assert_eq!(found[1].product.id, shirt_id);
 
-    let saved = repo
-        .get_variant_by_id(ctx, variant_id)
+    let by_product = ProductVariantFilter {
+        product_id: Some(shirt_id),
+        ..Default::default()
+    };
+    let first = repo
+        .get_variants(ctx, &by_product, &PaginationOptions::new(1, 2, None))
         .await
-        .expect("Failed to get variant")
-        .expect("Variant not found");
+        .expect("Failed to get variants");
+    assert_eq!(names(first), vec!["Shirt Large", "Shirt Medium"]);
+    let second = repo
+        .get_variants(ctx, &by_product, &PaginationOptions::new(2, 2, None))
+        .await
+        .expect("Failed to get variants");
+    assert_eq!(names(second), vec!["Shirt Small"]);
 
-    assert_eq!(saved.barcode, Some("NEW-BARCODE-123".to_string()));
-    assert_eq!(saved.name, Some("Default Variant".to_string())); // Unchanged
+    let by_barcode = ProductVariantFilter {
+        barcode: Some("VAR-M".to_string()),
+        ..Default::default()
+    };
+    let found = repo
+        .get_variants(ctx, &by_barcode, &PaginationOptions::new(1, 10, None))
+        .await
+        .expect("Failed to get variants");
+    assert_eq!(names(found), vec!["Shirt Medium"]);
+
+    let deleted_product = ProductVariantFilter {
+        product_id: Some(deleted_id),
+        ..Default::default()
+    };
+    let found = repo
+        .get_variants(ctx, &deleted_product, &PaginationOptions::new(1, 10, None))
+        .await
+        .expect("Failed to get variants");
+    assert!(found.is_empty());
 }
 
-pub async fn test_update_variant_set_metadata<'a, T, P>(
+pub async fn test_set_and_get_product_components<'a, T, P>(
     ctx: &Context,
     tx_manager: &'a T,
     repo: &'a P,
@@ -2273,40 +3828,167 @@ pub async fn test_update_variant_set_metadata<'a, T, P>(
     T: TransactionManager,
     P: ProductRepository<T::Transaction<'a>>,
 {
-    let product_id = super::generate_test_id().await;
-    let product = create_test_product();
+    let bundle_product_id = super::generate_test_id().await;
+    let component_product_id = super::generate_test_id().await;
+    let bundle_id = super::generate_test_id().await;
+    let component_ids = [
+        super::generate_test_id().await,
+        super::generate_test_id().await,
+    ];
 
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_product(&ctx, product_id, &product, &mut tx)
+    let bundle_product = ProductCreate {
+        product_type: ProductType::Bundle,
+        ..create_test_product()
+    };
+    repo.create_product(ctx, bundle_product_id, &bundle_product, &mut tx)
+        .await
+        .expect("Failed to create product");
+    repo.create_product(ctx, component_product_id, &create_test_product(), &mut tx)
         .await
         .expect("Failed to create product");
+    let bundle = ProductVariantCreate {
+        barcode: Some("BUNDLE-1".to_string()),
+        ..create_test_variant(bundle_product_id)
+    };
+    repo.create_variant(ctx, bundle_id, &bundle, &mut tx)
+        .await
+        .expect("Failed to create variant");
+    for (id, barcode) in component_ids.iter().zip(["PART-1", "PART-2"]) {
+        let variant = ProductVariantCreate {
+            barcode: Some(barcode.to_string()),
+            ..create_test_variant(component_product_id)
+        };
+        repo.create_variant(ctx, *id, &variant, &mut tx)
+            .await
+            .expect("Failed to create variant");
+    }
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    let variant_id = super::generate_test_id().await;
-    let variant = create_test_variant(product_id);
+    let components = vec![
+        ProductComponent {
+            component_variant_id: component_ids[0],
+            quantity: 2,
+        },
+        ProductComponent {
+            component_variant_id: component_ids[1],
+            quantity: 1,
+        },
+    ];
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.set_components(ctx, bundle_id, &components, &mut tx)
+        .await
+        .expect("Failed to set components");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let fetched = repo
+        .get_components(ctx, bundle_id)
+        .await
+        .expect("Failed to get components");
+    assert_eq!(fetched, components);
 
+    // Setting the list again replaces it
     let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
-    repo.create_variant(&ctx, variant_id, &variant, &mut tx)
+    repo.set_components(ctx, bundle_id, &components[1..], &mut tx)
         .await
-        .expect("Failed to create variant");
+        .expect("Failed to set components");
     tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    // Set new metadata
-    let update = ProductVariantUpdate {
-        barcode: Update::Unchanged,
-        name: Update::Unchanged,
-        metadata: Update::Set(json!({"new": "data", "count": 42})),
+    let fetched = repo
+        .get_components(ctx, bundle_id)
+        .await
+        .expect("Failed to get components");
+    assert_eq!(fetched, components[1..]);
+
+    // A bundle cannot contain itself
+    let self_reference = [ProductComponent {
+        component_variant_id: bundle_id,
+        quantity: 1,
+    }];
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    let result = repo
+        .set_components(ctx, bundle_id, &self_reference, &mut tx)
+        .await;
+    tx_manager
+        .rollback(tx)
+        .await
+        .expect("Failed to rollback tx");
+    assert!(matches!(result, Err(Error::ValidationError(_))));
+
+    // A bundle cannot contain itself through another bundle
+    let inner_product_id = super::generate_test_id().await;
+    let inner_id = super::generate_test_id().await;
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(ctx, inner_product_id, &bundle_product, &mut tx)
+        .await
+        .expect("Failed to create product");
+    let inner = ProductVariantCreate {
+        barcode: Some("BUNDLE-2".to_string()),
+        ..create_test_variant(inner_product_id)
     };
+    repo.create_variant(ctx, inner_id, &inner, &mut tx)
+        .await
+        .expect("Failed to create variant");
+    let outer = [ProductComponent {
+        component_variant_id: inner_id,
+        quantity: 1,
+    }];
+    repo.set_components(ctx, bundle_id, &outer, &mut tx)
+        .await
+        .expect("Failed to set components");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
 
-    repo.update_variant(&ctx, variant_id, &update)
+    let back = [ProductComponent {
+        component_variant_id: bundle_id,
+        quantity: 1,
+    }];
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    let result = repo.set_components(ctx, inner_id, &back, &mut tx).await;
+    tx_manager
+        .rollback(tx)
         .await
-        .expect("Failed to update variant");
+        .expect("Failed to rollback tx");
+    assert!(matches!(result, Err(Error::ValidationError(_))));
+    let fetched = repo
+        .get_components(ctx, inner_id)
+        .await
+        .expect("Failed to get components");
+    assert!(fetched.is_empty());
 
-    let saved = repo
-        .get_variant_by_id(&ctx, variant_id)
+    // Unknown component variants are rejected
+    let unknown = [ProductComponent {
+        component_variant_id: super::generate_test_id().await,
+        quantity: 1,
+    }];
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    let result = repo.set_components(ctx, bundle_id, &unknown, &mut tx).await;
+    tx_manager
+        .rollback(tx)
         .await
-        .expect("Failed to get variant")
-        .expect("Variant not found");
+        .expect("Failed to rollback tx");
+    assert!(matches!(result, Err(Error::ValidationError(_))));
 
-    assert_eq!(saved.metadata, Some(json!({"new": "data", "count": 42})));
+    // Unknown bundles are reported instead of silently ignored
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    let result = repo
+        .set_components(ctx, super::generate_test_id().await, &components, &mut tx)
+        .await;
+    tx_manager
+        .rollback(tx)
+        .await
+        .expect("Failed to rollback tx");
+    assert!(matches!(result, Err(Error::NotFound(_))));
+
+    // Deleted components are left out of a batch lookup
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.delete_variant(ctx, component_ids[0], &mut tx)
+        .await
+        .expect("Failed to delete variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+    let variants = repo
+        .get_variants_by_ids(ctx, &component_ids)
+        .await
+        .expect("Failed to get variants");
+    let ids: Vec<i64> = variants.iter().map(|v| v.id).collect();
+    assert_eq!(ids, vec![component_ids[1]]);
 }
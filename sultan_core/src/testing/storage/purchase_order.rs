@@ -0,0 +1,268 @@
+use crate::{
+    domain::{
+        Context,
+        error::Error,
+        model::{
+            branch::BranchCreate,
+            money::Money,
+            product::{ProductCreate, ProductType, ProductVariantCreate},
+            purchase_order::{PurchaseOrderCreate, PurchaseOrderLine, PurchaseOrderStatus},
+            supplier::SupplierCreate,
+        },
+    },
+    storage::{
+        BranchRepository, ProductRepository, PurchaseOrderRepository, SupplierRepository,
+        sqlite::{
+            SqliteBranchRepository, SqliteProductRepository, SqlitePurchaseOrderRepository,
+            SqliteSupplierRepository, transaction::SqliteTransactionManager,
+        },
+        transaction::TransactionManager,
+    },
+};
+use sqlx::SqlitePool;
+
+pub struct PurchaseOrderTestData {
+    pub ctx: Context,
+    pub pool: SqlitePool,
+    pub tx_manager: SqliteTransactionManager,
+    pub repo: SqlitePurchaseOrderRepository,
+    pub branch_id: i64,
+    pub supplier_id: i64,
+    pub variant_ids: Vec<i64>,
+}
+
+/// Sets up a branch, a supplier and a product with two variants. Only the
+/// first variant has a stock row (5 units), the second has none yet.
+pub async fn create_sqlite_purchase_order_repo() -> PurchaseOrderTestData {
+    let pool = super::init_sqlite_pool().await;
+    let ctx = Context::new();
+    let tx_manager = SqliteTransactionManager::new(pool.clone());
+
+    let branch_id = super::generate_test_id().await;
+    SqliteBranchRepository::new(pool.clone())
+        .create(
+            &ctx,
+            branch_id,
+            &BranchCreate {
+                is_main: true,
+                name: "Main".to_string(),
+                code: "MAIN".to_string(),
+                address: None,
+                phone: None,
+                npwp: None,
+                image: None,
+            },
+        )
+        .await
+        .expect("Failed to create branch");
+
+    let supplier_id = super::generate_test_id().await;
+    SqliteSupplierRepository::new(pool.clone())
+        .create(
+            &ctx,
+            supplier_id,
+            &SupplierCreate {
+                name: "Main Supplier".to_string(),
+                code: None,
+                address: None,
+                phone: None,
+                npwp: None,
+                npwp_name: None,
+                email: None,
+                metadata: None,
+            },
+        )
+        .await
+        .expect("Failed to create supplier");
+
+    let product_repo = SqliteProductRepository::new(pool.clone());
+    let product_id = super::generate_test_id().await;
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    product_repo
+        .create_product(
+            &ctx,
+            product_id,
+            &ProductCreate {
+                name: "Test Product".to_string(),
+                description: None,
+                product_type: ProductType::Product,
+                main_image: None,
+                sellable: true,
+                buyable: true,
+                editable_price: false,
+                has_variant: true,
+                metadata: None,
+                category_ids: vec![],
+            },
+            &mut tx,
+        )
+        .await
+        .expect("Failed to create product");
+
+    let mut variant_ids = Vec::new();
+    for barcode in ["PO-1", "PO-2"] {
+        let variant_id = super::generate_test_id().await;
+        product_repo
+            .create_variant(
+                &ctx,
+                variant_id,
+                &ProductVariantCreate {
+                    product_id,
+                    barcode: Some(barcode.to_string()),
+                    name: None,
+                    price: None,
+                    metadata: None,
+                    initial_stock: vec![],
+                },
+                &mut tx,
+            )
+            .await
+            .expect("Failed to create variant");
+        variant_ids.push(variant_id);
+    }
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    sqlx::query("INSERT INTO stocks (branch_id, variant_id, quantity) VALUES (?, ?, 5)")
+        .bind(branch_id)
+        .bind(variant_ids[0])
+        .execute(&pool)
+        .await
+        .expect("Failed to seed stock");
+
+    PurchaseOrderTestData {
+        ctx,
+        repo: SqlitePurchaseOrderRepository::new(pool.clone()),
+        pool,
+        tx_manager,
+        branch_id,
+        supplier_id,
+        variant_ids,
+    }
+}
+
+/// Quantity on hand, 0 when the variant has no stock row
+pub async fn stock_quantity(pool: &SqlitePool, branch_id: i64, variant_id: i64) -> i64 {
+    sqlx::query_scalar("SELECT quantity FROM stocks WHERE branch_id = ? AND variant_id = ?")
+        .bind(branch_id)
+        .bind(variant_id)
+        .fetch_optional(pool)
+        .await
+        .expect("Failed to read stock")
+        .unwrap_or(0)
+}
+
+pub fn create_test_purchase_order(data: &PurchaseOrderTestData) -> PurchaseOrderCreate {
+    PurchaseOrderCreate {
+        supplier_id: data.supplier_id,
+        branch_id: data.branch_id,
+        lines: vec![
+            PurchaseOrderLine {
+                variant_id: data.variant_ids[0],
+                qty: 12,
+                cost: Money::from_minor(2_500),
+            },
+            PurchaseOrderLine {
+                variant_id: data.variant_ids[1],
+                qty: 3,
+                cost: Money::from_minor(9_900),
+            },
+        ],
+    }
+}
+
+pub async fn purchase_order_test_create(data: &PurchaseOrderTestData) {
+    let id = super::generate_test_id().await;
+    let order = create_test_purchase_order(data);
+
+    let mut tx = data.tx_manager.begin().await.expect("Failed to begin tx");
+    data.repo
+        .create(&data.ctx, id, &order, &mut tx)
+        .await
+        .expect("Failed to create purchase order");
+    data.tx_manager
+        .commit(tx)
+        .await
+        .expect("Failed to commit tx");
+
+    let fetched = data
+        .repo
+        .get_by_id(&data.ctx, id)
+        .await
+        .expect("Failed to get purchase order")
+        .expect("Purchase order not found");
+    assert_eq!(fetched.supplier_id, data.supplier_id);
+    assert_eq!(fetched.branch_id, data.branch_id);
+    assert_eq!(fetched.status, PurchaseOrderStatus::Ordered);
+    assert_eq!(fetched.lines, order.lines);
+
+    // Creating an order does not touch stock
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[0]).await,
+        5
+    );
+}
+
+pub async fn purchase_order_test_get_by_id_not_found(data: &PurchaseOrderTestData) {
+    let result = data
+        .repo
+        .get_by_id(&data.ctx, 999_999)
+        .await
+        .expect("Failed to query purchase order");
+    assert!(result.is_none());
+}
+
+pub async fn purchase_order_test_update_status(data: &PurchaseOrderTestData) {
+    let id = super::generate_test_id().await;
+    let mut tx = data.tx_manager.begin().await.expect("Failed to begin tx");
+    data.repo
+        .create(&data.ctx, id, &create_test_purchase_order(data), &mut tx)
+        .await
+        .expect("Failed to create purchase order");
+    data.repo
+        .update_status(
+            &data.ctx,
+            id,
+            PurchaseOrderStatus::Ordered,
+            PurchaseOrderStatus::Cancelled,
+            &mut tx,
+        )
+        .await
+        .expect("Failed to cancel purchase order");
+
+    // The order is no longer Ordered, so it cannot be received
+    let result = data
+        .repo
+        .update_status(
+            &data.ctx,
+            id,
+            PurchaseOrderStatus::Ordered,
+            PurchaseOrderStatus::Received,
+            &mut tx,
+        )
+        .await;
+    assert!(matches!(result, Err(Error::Conflict(_))));
+
+    let result = data
+        .repo
+        .update_status(
+            &data.ctx,
+            999_999,
+            PurchaseOrderStatus::Ordered,
+            PurchaseOrderStatus::Received,
+            &mut tx,
+        )
+        .await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+    data.tx_manager
+        .commit(tx)
+        .await
+        .expect("Failed to commit tx");
+
+    let fetched = data
+        .repo
+        .get_by_id(&data.ctx, id)
+        .await
+        .expect("Failed to get purchase order")
+        .expect("Purchase order not found");
+    assert_eq!(fetched.status, PurchaseOrderStatus::Cancelled);
+}
@@ -0,0 +1,210 @@
+use crate::{
+    domain::{
+        Context,
+        model::{
+            branch::BranchCreate, category::CategoryCreate, customer::CustomerCreate,
+            pagination::PaginationOptions, supplier::SupplierCreate, user::UserCreate,
+        },
+    },
+    storage::{
+        BranchRepository, CategoryRepository, CustomerRepository, ReadRepository,
+        SupplierRepository, UserRepository,
+        sqlite::{
+            SqliteBranchRepository, SqliteCategoryRepository, SqliteCustomerRepository,
+            SqliteSupplierRepository, SqliteUserRepository, transaction::TxGuard,
+        },
+    },
+};
+
+/// Customer and supplier repositories sharing one database, each holding
+/// three rows. The ids are returned in insertion order.
+pub async fn create_sqlite_read_repos() -> (
+    Context,
+    SqliteCustomerRepository,
+    Vec<i64>,
+    SqliteSupplierRepository,
+    Vec<i64>,
+) {
+    let pool = super::init_sqlite_pool().await;
+    let ctx = Context::new();
+    let customer_repo = SqliteCustomerRepository::new(pool.clone());
+    let supplier_repo = SqliteSupplierRepository::new(pool);
+
+    let mut customer_ids = Vec::new();
+    let mut supplier_ids = Vec::new();
+    for i in 1..=3 {
+        let id = super::generate_test_id().await;
+        customer_repo
+            .create(
+                &ctx,
+                id,
+                &CustomerCreate {
+                    number: format!("CUST{:03}", i),
+                    name: format!("Customer {}", i),
+                    address: None,
+                    email: None,
+                    phone: None,
+                    level: 1,
+                    metadata: None,
+                },
+            )
+            .await
+            .expect("Failed to create customer");
+        customer_ids.push(id);
+
+        let id = super::generate_test_id().await;
+        supplier_repo
+            .create(
+                &ctx,
+                id,
+                &SupplierCreate {
+                    name: format!("Supplier {}", i),
+                    code: None,
+                    email: None,
+                    address: None,
+                    phone: None,
+                    npwp: None,
+                    npwp_name: None,
+                    metadata: None,
+                },
+            )
+            .await
+            .expect("Failed to create supplier");
+        supplier_ids.push(id);
+    }
+
+    (
+        ctx,
+        customer_repo,
+        customer_ids,
+        supplier_repo,
+        supplier_ids,
+    )
+}
+
+/// User, branch and category repositories sharing one database, each with the
+/// ids of its three rows in insertion order
+pub struct SqliteAdminReadRepos {
+    pub user_repo: SqliteUserRepository,
+    pub user_ids: Vec<i64>,
+    pub branch_repo: SqliteBranchRepository,
+    pub branch_ids: Vec<i64>,
+    pub category_repo: SqliteCategoryRepository,
+    pub category_ids: Vec<i64>,
+}
+
+pub async fn create_sqlite_admin_read_repos() -> (Context, SqliteAdminReadRepos) {
+    let pool = super::init_sqlite_pool().await;
+    let ctx = Context::new();
+    let mut repos = SqliteAdminReadRepos {
+        user_repo: SqliteUserRepository::new(pool.clone()),
+        user_ids: Vec::new(),
+        branch_repo: SqliteBranchRepository::new(pool.clone()),
+        branch_ids: Vec::new(),
+        category_repo: SqliteCategoryRepository::new(pool),
+        category_ids: Vec::new(),
+    };
+
+    for i in 1..=3 {
+        let id = super::generate_test_id().await;
+        <SqliteUserRepository as UserRepository<TxGuard<'_>>>::create_user(
+            &repos.user_repo,
+            &ctx,
+            id,
+            &UserCreate {
+                username: format!("user{}", i),
+                password: "hashed_password".to_string(),
+                name: format!("User {}", i),
+                email: None,
+                photo: None,
+                pin: None,
+                address: None,
+                phone: None,
+            },
+        )
+        .await
+        .expect("Failed to create user");
+        repos.user_ids.push(id);
+
+        let id = super::generate_test_id().await;
+        repos
+            .branch_repo
+            .create(
+                &ctx,
+                id,
+                &BranchCreate {
+                    is_main: false,
+                    name: format!("Branch {}", i),
+                    code: format!("BR{:03}", i),
+                    address: None,
+                    phone: None,
+                    npwp: None,
+                    image: None,
+                },
+            )
+            .await
+            .expect("Failed to create branch");
+        repos.branch_ids.push(id);
+
+        let id = super::generate_test_id().await;
+        repos
+            .category_repo
+            .create(
+                &ctx,
+                id,
+                &CategoryCreate {
+                    parent_id: None,
+                    name: format!("Category {}", i),
+                    description: None,
+                },
+            )
+            .await
+            .expect("Failed to create category");
+        repos.category_ids.push(id);
+    }
+
+    (ctx, repos)
+}
+
+/// Exercises any `ReadRepository` the same way: every id resolves, an unknown
+/// id does not, and pagination splits the full list.
+pub async fn read_repo_test_get_by_id_and_all<T, R>(
+    ctx: &Context,
+    repo: &R,
+    ids: &[i64],
+    id_of: fn(&T) -> i64,
+) where
+    R: ReadRepository<T> + ?Sized,
+{
+    for id in ids {
+        let entity = repo
+            .get_by_id(ctx, *id)
+            .await
+            .expect("Failed to get entity")
+            .expect("Entity not found");
+        assert_eq!(id_of(&entity), *id);
+    }
+
+    let missing = repo
+        .get_by_id(ctx, 999_999)
+        .await
+        .expect("Failed to get entity");
+    assert!(missing.is_none());
+
+    let all = repo
+        .get_all(ctx, &super::default_pagination())
+        .await
+        .expect("Failed to get all");
+    assert_eq!(all.len(), ids.len());
+
+    let first_page = repo
+        .get_all(ctx, &PaginationOptions::new(1, 2, None))
+        .await
+        .expect("Failed to get first page");
+    let second_page = repo
+        .get_all(ctx, &PaginationOptions::new(2, 2, None))
+        .await
+        .expect("Failed to get second page");
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(second_page.len(), ids.len() - 2);
+}
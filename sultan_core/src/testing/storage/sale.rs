@@ -0,0 +1,452 @@
+use crate::{
+    domain::{
+        Context,
+        error::Error,
+        model::{
+            branch::BranchCreate,
+            money::Money,
+            product::{ProductCreate, ProductType, ProductVariantCreate},
+            sale::{PaymentMethodTotal, SaleCreate, SaleLineCreate},
+        },
+    },
+    storage::{
+        BranchRepository, ProductRepository, SaleRepository,
+        sqlite::{
+            SqliteBranchRepository, SqliteProductRepository, SqliteSaleRepository,
+            transaction::SqliteTransactionManager,
+        },
+        transaction::TransactionManager,
+    },
+};
+use chrono::{Duration, NaiveDate, Utc};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+pub struct SaleTestData {
+    pub ctx: Context,
+    pub pool: SqlitePool,
+    pub tx_manager: SqliteTransactionManager,
+    pub repo: SqliteSaleRepository,
+    pub branch_id: i64,
+    pub variant_ids: Vec<i64>,
+}
+
+/// Sets up a branch and a product with two variants, each stocked with 10 units.
+pub async fn create_sqlite_sale_repo() -> SaleTestData {
+    let pool = super::init_sqlite_pool().await;
+    let ctx = Context::new();
+    let tx_manager = SqliteTransactionManager::new(pool.clone());
+
+    let branch_id = super::generate_test_id().await;
+    SqliteBranchRepository::new(pool.clone())
+        .create(
+            &ctx,
+            branch_id,
+            &BranchCreate {
+                is_main: true,
+                name: "Main".to_string(),
+                code: "MAIN".to_string(),
+                address: None,
+                phone: None,
+                npwp: None,
+                image: None,
+            },
+        )
+        .await
+        .expect("Failed to create branch");
+
+    let product_repo = SqliteProductRepository::new(pool.clone());
+    let product_id = super::generate_test_id().await;
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    product_repo
+        .create_product(
+            &ctx,
+            product_id,
+            &ProductCreate {
+                name: "Test Product".to_string(),
+                description: None,
+                product_type: ProductType::Product,
+                main_image: None,
+                sellable: true,
+                buyable: true,
+                editable_price: false,
+                has_variant: true,
+                metadata: None,
+                category_ids: vec![],
+            },
+            &mut tx,
+        )
+        .await
+        .expect("Failed to create product");
+
+    let mut variant_ids = Vec::new();
+    for barcode in ["SALE-1", "SALE-2"] {
+        let variant_id = super::generate_test_id().await;
+        product_repo
+            .create_variant(
+                &ctx,
+                variant_id,
+                &ProductVariantCreate {
+                    product_id,
+                    barcode: Some(barcode.to_string()),
+                    name: None,
+                    price: None,
+                    metadata: None,
+                    initial_stock: vec![],
+                },
+                &mut tx,
+            )
+            .await
+            .expect("Failed to create variant");
+        variant_ids.push(variant_id);
+    }
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    for variant_id in &variant_ids {
+        sqlx::query("INSERT INTO stocks (branch_id, variant_id, quantity) VALUES (?, ?, 10)")
+            .bind(branch_id)
+            .bind(variant_id)
+            .execute(&pool)
+            .await
+            .expect("Failed to seed stock");
+    }
+
+    SaleTestData {
+        ctx,
+        repo: SqliteSaleRepository::new(pool.clone()),
+        pool,
+        tx_manager,
+        branch_id,
+        variant_ids,
+    }
+}
+
+pub async fn stock_quantity(pool: &SqlitePool, branch_id: i64, variant_id: i64) -> i64 {
+    sqlx::query_scalar("SELECT quantity FROM stocks WHERE branch_id = ? AND variant_id = ?")
+        .bind(branch_id)
+        .bind(variant_id)
+        .fetch_one(pool)
+        .await
+        .expect("Failed to read stock")
+}
+
+pub async fn sale_test_create_multi_line(data: &SaleTestData) {
+    let sale_id = super::generate_test_id().await;
+    let sale = SaleCreate {
+        branch_id: data.branch_id,
+        customer_id: None,
+        payment_method: "cash".to_string(),
+        lines: vec![
+            SaleLineCreate {
+                variant_id: data.variant_ids[0],
+                qty: 3,
+                unit_price: "2.50".parse().unwrap(),
+            },
+            SaleLineCreate {
+                variant_id: data.variant_ids[1],
+                qty: 10,
+                unit_price: "0.99".parse().unwrap(),
+            },
+        ],
+    };
+
+    let mut tx = data.tx_manager.begin().await.expect("Failed to begin tx");
+    data.repo
+        .create(&data.ctx, sale_id, &sale, &mut tx)
+        .await
+        .expect("Failed to create sale");
+    data.tx_manager
+        .commit(tx)
+        .await
+        .expect("Failed to commit tx");
+
+    let saved = data
+        .repo
+        .get_by_id(&data.ctx, sale_id)
+        .await
+        .expect("Failed to get sale")
+        .expect("Sale not found");
+    assert_eq!(saved.branch_id, data.branch_id);
+    assert_eq!(saved.customer_id, None);
+    assert_eq!(saved.lines.len(), 2);
+    assert_eq!(saved.lines[0].variant_id, data.variant_ids[0]);
+    assert_eq!(saved.lines[0].line_total, Money::from_minor(750));
+    assert_eq!(saved.lines[1].line_total, Money::from_minor(990));
+    assert_eq!(saved.total, Money::from_minor(1740));
+
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[0]).await,
+        7
+    );
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[1]).await,
+        0
+    );
+}
+
+pub async fn sale_test_insufficient_stock_rolls_back(data: &SaleTestData) {
+    let sale_id = super::generate_test_id().await;
+    let sale = SaleCreate {
+        branch_id: data.branch_id,
+        customer_id: None,
+        payment_method: "cash".to_string(),
+        lines: vec![
+            SaleLineCreate {
+                variant_id: data.variant_ids[0],
+                qty: 2,
+                unit_price: Money::from_major(1),
+            },
+            SaleLineCreate {
+                variant_id: data.variant_ids[1],
+                qty: 11,
+                unit_price: Money::from_major(1),
+            },
+        ],
+    };
+
+    let mut tx = data.tx_manager.begin().await.expect("Failed to begin tx");
+    let result = data.repo.create(&data.ctx, sale_id, &sale, &mut tx).await;
+    assert!(matches!(result, Err(Error::ValidationError(_))));
+    data.tx_manager
+        .rollback(tx)
+        .await
+        .expect("Failed to rollback tx");
+
+    let saved = data
+        .repo
+        .get_by_id(&data.ctx, sale_id)
+        .await
+        .expect("Failed to get sale");
+    assert!(saved.is_none());
+
+    // The first line's decrement must not survive the rollback
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[0]).await,
+        10
+    );
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[1]).await,
+        10
+    );
+}
+
+pub async fn sale_test_get_by_id_not_found(data: &SaleTestData) {
+    let result = data
+        .repo
+        .get_by_id(&data.ctx, 999_999)
+        .await
+        .expect("Failed to get sale");
+    assert!(result.is_none());
+}
+
+async fn create_sale_at(data: &SaleTestData, payment_method: &str, qty: i64, created_at: &str) {
+    let sale_id = super::generate_test_id().await;
+    let sale = SaleCreate {
+        branch_id: data.branch_id,
+        customer_id: None,
+        payment_method: payment_method.to_string(),
+        lines: vec![SaleLineCreate {
+            variant_id: data.variant_ids[0],
+            qty,
+            unit_price: Money::from_major(2),
+        }],
+    };
+
+    let mut tx = data.tx_manager.begin().await.expect("Failed to begin tx");
+    data.repo
+        .create(&data.ctx, sale_id, &sale, &mut tx)
+        .await
+        .expect("Failed to create sale");
+    data.tx_manager
+        .commit(tx)
+        .await
+        .expect("Failed to commit tx");
+
+    sqlx::query("UPDATE sales SET created_at = ? WHERE id = ?")
+        .bind(created_at)
+        .bind(sale_id)
+        .execute(&data.pool)
+        .await
+        .expect("Failed to backdate sale");
+}
+
+pub async fn sale_test_daily_summary(data: &SaleTestData) {
+    create_sale_at(data, "cash", 1, "2025-12-01T08:15:00.000Z").await;
+    create_sale_at(data, "card", 2, "2025-12-01T12:00:00.000Z").await;
+    create_sale_at(data, "cash", 3, "2025-12-01T23:59:59.999Z").await;
+    // The next day must not be counted
+    create_sale_at(data, "cash", 4, "2025-12-02T00:00:00.000Z").await;
+
+    let summary = data
+        .repo
+        .summary(
+            &data.ctx,
+            data.branch_id,
+            NaiveDate::from_ymd_opt(2025, 12, 1).unwrap(),
+        )
+        .await
+        .expect("Failed to get summary");
+
+    assert_eq!(summary.sale_count, 3);
+    assert_eq!(summary.gross_total, Money::from_major(12));
+    assert_eq!(
+        summary.by_payment_method,
+        vec![
+            PaymentMethodTotal {
+                payment_method: "card".to_string(),
+                sale_count: 1,
+                total: Money::from_major(4),
+            },
+            PaymentMethodTotal {
+                payment_method: "cash".to_string(),
+                sale_count: 2,
+                total: Money::from_major(8),
+            },
+        ]
+    );
+
+    let next_day = data
+        .repo
+        .summary(
+            &data.ctx,
+            data.branch_id,
+            NaiveDate::from_ymd_opt(2025, 12, 2).unwrap(),
+        )
+        .await
+        .expect("Failed to get summary");
+    assert_eq!(next_day.sale_count, 1);
+    assert_eq!(next_day.gross_total, Money::from_major(8));
+}
+
+pub async fn sale_test_summary_empty_day(data: &SaleTestData) {
+    let summary = data
+        .repo
+        .summary(
+            &data.ctx,
+            data.branch_id,
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+        )
+        .await
+        .expect("Failed to get summary");
+
+    assert_eq!(summary.sale_count, 0);
+    assert_eq!(summary.gross_total, Money::ZERO);
+    assert!(summary.by_payment_method.is_empty());
+}
+
+pub async fn sale_test_delete_expired_idempotency_keys(data: &SaleTestData) {
+    create_sale_at(data, "cash", 1, "2025-12-01T08:15:00.000Z").await;
+    let sale_id: i64 = sqlx::query_scalar("SELECT id FROM sales LIMIT 1")
+        .fetch_one(&data.pool)
+        .await
+        .expect("Failed to read sale");
+
+    let now = Utc::now();
+    let mut tx = data.tx_manager.begin().await.expect("Failed to begin tx");
+    for (key, expires_at) in [
+        ("expired-1", now - Duration::hours(2)),
+        ("expired-2", now - Duration::seconds(1)),
+        ("live", now + Duration::hours(24)),
+    ] {
+        let saved = data
+            .repo
+            .save_idempotency_key(
+                &data.ctx,
+                key,
+                sale_id,
+                now - Duration::days(1),
+                expires_at,
+                &mut tx,
+            )
+            .await
+            .expect("Failed to save idempotency key");
+        assert!(saved);
+    }
+    data.tx_manager
+        .commit(tx)
+        .await
+        .expect("Failed to commit tx");
+
+    let removed = data
+        .repo
+        .delete_expired_idempotency_keys(&data.ctx, now)
+        .await
+        .expect("Failed to delete expired keys");
+    assert_eq!(removed, 2);
+
+    let keys: Vec<String> = sqlx::query_scalar("SELECT key FROM idempotency_keys")
+        .fetch_all(&data.pool)
+        .await
+        .expect("Failed to read keys");
+    assert_eq!(keys, vec!["live".to_string()]);
+}
+
+pub async fn sale_test_idempotency_key_scoped_to_branch_and_user(data: &SaleTestData) {
+    let other_branch_id = super::generate_test_id().await;
+    SqliteBranchRepository::new(data.pool.clone())
+        .create(
+            &data.ctx,
+            other_branch_id,
+            &BranchCreate {
+                is_main: false,
+                name: "Second".to_string(),
+                code: "SECOND".to_string(),
+                address: None,
+                phone: None,
+                npwp: None,
+                image: None,
+            },
+        )
+        .await
+        .expect("Failed to create branch");
+
+    let cashier = Context::new_with_all(Some(7), HashMap::new(), HashMap::new());
+    let now = Utc::now();
+    let expires_at = now + Duration::hours(24);
+    let mut sale_ids = Vec::new();
+    for branch_id in [data.branch_id, other_branch_id] {
+        let sale_id = super::generate_test_id().await;
+        sqlx::query("INSERT INTO sales (id, branch_id, total) VALUES (?, ?, 0)")
+            .bind(sale_id)
+            .bind(branch_id)
+            .execute(&data.pool)
+            .await
+            .expect("Failed to seed sale");
+
+        let mut tx = data.tx_manager.begin().await.expect("Failed to begin tx");
+        let saved = data
+            .repo
+            .save_idempotency_key(&cashier, "shared", sale_id, now, expires_at, &mut tx)
+            .await
+            .expect("Failed to save idempotency key");
+        assert!(saved, "the same key is free in another branch");
+        data.tx_manager
+            .commit(tx)
+            .await
+            .expect("Failed to commit tx");
+        sale_ids.push(sale_id);
+    }
+
+    for (branch_id, sale_id) in [
+        (data.branch_id, sale_ids[0]),
+        (other_branch_id, sale_ids[1]),
+    ] {
+        let found = data
+            .repo
+            .find_by_idempotency_key(&cashier, branch_id, "shared", now)
+            .await
+            .expect("Failed to find idempotency key");
+        assert_eq!(found, Some(sale_id));
+    }
+
+    // Another user, or a caller without one, does not see the cashier's key
+    let other_user = Context::new_with_all(Some(8), HashMap::new(), HashMap::new());
+    for ctx in [&other_user, &data.ctx] {
+        let found = data
+            .repo
+            .find_by_idempotency_key(ctx, data.branch_id, "shared", now)
+            .await
+            .expect("Failed to find idempotency key");
+        assert_eq!(found, None);
+    }
+}
@@ -603,3 +603,53 @@ pub async fn sell_price_test_get_discount_not_found<'a, T: TransactionManager +
 
     assert!(result.is_none());
 }
+
+pub async fn sell_price_test_update_touches_updated_at<'a, T: TransactionManager + 'a>(
+    test_data: &SellPriceTestData<'a, T>,
+) {
+    let id = super::generate_test_id().await;
+    let sell_price = SellPriceCreate {
+        branch_id: None,
+        product_variant_id: test_data.variant_id[0],
+        price: 1000,
+        quantity: 1,
+        uom_id: test_data.unit_id,
+        metadata: None,
+    };
+    test_data
+        .sell_price_repo
+        .create(&test_data.ctx, id, &sell_price)
+        .await
+        .expect("Failed to create sell price");
+    let before = test_data
+        .sell_price_repo
+        .get_by_id(&test_data.ctx, id)
+        .await
+        .expect("Failed to get sell price")
+        .expect("Price not found");
+
+    super::wait_for_clock_tick().await;
+    test_data
+        .sell_price_repo
+        .update(
+            &test_data.ctx,
+            id,
+            &SellPriceUpdate {
+                price: Some(1100),
+                quantity: None,
+                uom_id: None,
+                metadata: Update::Unchanged,
+            },
+        )
+        .await
+        .expect("Failed to update sell price");
+
+    let after = test_data
+        .sell_price_repo
+        .get_by_id(&test_data.ctx, id)
+        .await
+        .expect("Failed to get sell price")
+        .expect("Price not found");
+    assert!(after.updated_at > before.updated_at);
+    assert_eq!(after.created_at, before.created_at);
+}
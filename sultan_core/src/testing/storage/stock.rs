@@ -0,0 +1,177 @@
+use crate::{
+    domain::{
+        Context,
+        error::Error,
+        model::{pagination::PaginationOptions, stock::StockAdjustment},
+    },
+    storage::{
+        StockRepository,
+        sqlite::{SqliteStockRepository, transaction::SqliteTransactionManager},
+        transaction::TransactionManager,
+    },
+};
+use sqlx::SqlitePool;
+
+use super::purchase_order::create_sqlite_purchase_order_repo;
+
+pub struct StockTestData {
+    pub ctx: Context,
+    pub pool: SqlitePool,
+    pub tx_manager: SqliteTransactionManager,
+    pub repo: SqliteStockRepository,
+    pub branch_id: i64,
+    /// The first variant starts with 5 units, the second with no stock row
+    pub variant_ids: Vec<i64>,
+}
+
+/// Reuses the purchase order fixture for its branch and seeded stock
+pub async fn create_sqlite_stock_repo() -> StockTestData {
+    let data = create_sqlite_purchase_order_repo().await;
+    StockTestData {
+        repo: SqliteStockRepository::new(data.pool.clone()),
+        ctx: data.ctx,
+        pool: data.pool,
+        tx_manager: data.tx_manager,
+        branch_id: data.branch_id,
+        variant_ids: data.variant_ids,
+    }
+}
+
+fn adjustment(data: &StockTestData, variant_id: i64, delta: i64) -> StockAdjustment {
+    StockAdjustment {
+        branch_id: data.branch_id,
+        variant_id,
+        delta,
+        reason: "recount".to_string(),
+    }
+}
+
+async fn adjust(data: &StockTestData, adjustment: &StockAdjustment) -> Result<i64, Error> {
+    let mut tx = data.tx_manager.begin().await.expect("Failed to begin tx");
+    let result = data.repo.adjust(&data.ctx, adjustment, &mut tx).await;
+    match result {
+        Ok(_) => data.tx_manager.commit(tx).await.expect("Failed to commit"),
+        Err(_) => data
+            .tx_manager
+            .rollback(tx)
+            .await
+            .expect("Failed to rollback"),
+    }
+    result
+}
+
+async fn adjustment_count(pool: &SqlitePool) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM stock_adjustments")
+        .fetch_one(pool)
+        .await
+        .expect("Failed to count adjustments")
+}
+
+pub async fn stock_test_adjust(data: &StockTestData) {
+    let [stocked, unstocked] = [data.variant_ids[0], data.variant_ids[1]];
+
+    assert_eq!(
+        adjust(data, &adjustment(data, stocked, 3)).await.unwrap(),
+        8
+    );
+    assert_eq!(
+        adjust(data, &adjustment(data, stocked, -8)).await.unwrap(),
+        0
+    );
+    // A variant without a stock row starts from zero
+    assert_eq!(
+        adjust(data, &adjustment(data, unstocked, 4)).await.unwrap(),
+        4
+    );
+
+    let quantity = data
+        .repo
+        .get_quantity(&data.ctx, data.branch_id, unstocked)
+        .await
+        .unwrap();
+    assert_eq!(quantity, 4);
+
+    let rows = sqlx::query_as::<_, (i64, i64, String)>(
+        "SELECT delta, quantity_after, reason FROM stock_adjustments ORDER BY id",
+    )
+    .fetch_all(&data.pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            (3, 8, "recount".to_string()),
+            (-8, 0, "recount".to_string()),
+            (4, 4, "recount".to_string()),
+        ]
+    );
+}
+
+pub async fn stock_test_adjust_insufficient(data: &StockTestData) {
+    let stocked = data.variant_ids[0];
+
+    let result = adjust(data, &adjustment(data, stocked, -6)).await;
+    assert!(matches!(result, Err(Error::ValidationError(_))));
+
+    // Rolled back: quantity unchanged and nothing recorded
+    let quantity = data
+        .repo
+        .get_quantity(&data.ctx, data.branch_id, stocked)
+        .await
+        .unwrap();
+    assert_eq!(quantity, 5);
+    assert_eq!(adjustment_count(&data.pool).await, 0);
+}
+
+pub async fn stock_test_adjust_unknown_variant(data: &StockTestData) {
+    let result = adjust(data, &adjustment(data, 999_999, 1)).await;
+    assert!(matches!(result, Err(Error::ValidationError(_))));
+    assert_eq!(adjustment_count(&data.pool).await, 0);
+}
+
+pub async fn stock_test_list_low(data: &StockTestData) {
+    let [stocked, unstocked] = [data.variant_ids[0], data.variant_ids[1]];
+    // Levels: unstocked -> 2, stocked stays at 5
+    adjust(data, &adjustment(data, unstocked, 2)).await.unwrap();
+
+    let low = |threshold: i64, page_size: u32| async move {
+        data.repo
+            .list_low(
+                &data.ctx,
+                data.branch_id,
+                threshold,
+                &PaginationOptions::new(1, page_size, None),
+            )
+            .await
+            .expect("Failed to list low stock")
+            .into_iter()
+            .map(|level| (level.variant_id, level.quantity))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(low(1, 20).await, vec![]);
+    assert_eq!(low(2, 20).await, vec![(unstocked, 2)]);
+    assert_eq!(low(5, 20).await, vec![(unstocked, 2), (stocked, 5)]);
+    assert_eq!(low(5, 1).await, vec![(unstocked, 2)]);
+
+    let levels = data
+        .repo
+        .list_low(&data.ctx, data.branch_id, 2, &super::default_pagination())
+        .await
+        .unwrap();
+    assert_eq!(levels[0].product_name, "Test Product");
+    assert_eq!(levels[0].barcode.as_deref(), Some("PO-2"));
+
+    // Another branch sees nothing
+    let other = data
+        .repo
+        .list_low(
+            &data.ctx,
+            data.branch_id + 1,
+            100,
+            &super::default_pagination(),
+        )
+        .await
+        .unwrap();
+    assert!(other.is_empty());
+}
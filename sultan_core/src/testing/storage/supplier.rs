@@ -95,6 +95,75 @@ pub async fn supplier_test_repo_integration<S: SupplierRepository>(ctx: &Context
     assert!(deleted_supplier.is_none());
 }
 
+pub async fn supplier_test_records_actor<S: SupplierRepository>(ctx: &Context, repo: S) {
+    let id = super::generate_test_id().await;
+    let supplier = SupplierCreate {
+        name: "Audited Supplier".to_string(),
+        code: None,
+        email: None,
+        address: None,
+        phone: None,
+        npwp: None,
+        npwp_name: None,
+        metadata: None,
+    };
+
+    repo.create(&ctx.clone().with_actor_id(7), id, &supplier)
+        .await
+        .expect("Failed to create supplier");
+
+    let fetched = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get supplier")
+        .expect("Supplier not found");
+    assert_eq!(fetched.created_by, Some(7));
+    assert_eq!(fetched.updated_by, Some(7));
+
+    let update = SupplierUpdate {
+        name: Some("Renamed".to_string()),
+        ..Default::default()
+    };
+    repo.update(&ctx.clone().with_actor_id(8), id, &update)
+        .await
+        .expect("Failed to update supplier");
+
+    let fetched = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get supplier")
+        .expect("Supplier not found");
+    assert_eq!(fetched.created_by, Some(7));
+    assert_eq!(fetched.updated_by, Some(8));
+}
+
+/// Rows written without an actor (system tasks) keep both columns NULL.
+pub async fn supplier_test_without_actor<S: SupplierRepository>(ctx: &Context, repo: S) {
+    let id = super::generate_test_id().await;
+    let supplier = SupplierCreate {
+        name: "System Supplier".to_string(),
+        code: None,
+        email: None,
+        address: None,
+        phone: None,
+        npwp: None,
+        npwp_name: None,
+        metadata: None,
+    };
+
+    repo.create(ctx, id, &supplier)
+        .await
+        .expect("Failed to create supplier");
+
+    let fetched = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get supplier")
+        .expect("Supplier not found");
+    assert_eq!(fetched.created_by, None);
+    assert_eq!(fetched.updated_by, None);
+}
+
 pub async fn supplier_test_create_with_all_fields<S: SupplierRepository>(ctx: &Context, repo: S) {
     let id = super::generate_test_id().await;
     let metadata = json!({
@@ -625,6 +694,37 @@ pub async fn supplier_test_filter_by_name<S: SupplierRepository>(ctx: &Context,
     assert!(!suppliers.iter().any(|s| s.id == id2));
 }
 
+pub async fn supplier_test_count_ignores_pagination<S: SupplierRepository>(ctx: &Context, repo: S) {
+    for name in ["Counted One", "Counted Two", "Other"] {
+        let supplier = SupplierCreate {
+            name: name.to_string(),
+            code: None,
+            email: None,
+            address: None,
+            phone: None,
+            npwp: None,
+            npwp_name: None,
+            metadata: None,
+        };
+        repo.create(ctx, super::generate_test_id().await, &supplier)
+            .await
+            .expect("Failed to create supplier");
+    }
+
+    let filter = SupplierFilter {
+        name: Some("Counted".to_string()),
+        ..Default::default()
+    };
+    let page = repo
+        .get_all(ctx, &filter, &PaginationOptions::new(1, 1, None))
+        .await
+        .expect("Failed to get suppliers");
+    assert_eq!(page.len(), 1);
+
+    let total = repo.count(ctx, &filter).await.expect("Failed to count");
+    assert_eq!(total, 2);
+}
+
 pub async fn supplier_test_filter_by_code<S: SupplierRepository>(ctx: &Context, repo: S) {
     let id1 = super::generate_test_id().await;
     let id2 = super::generate_test_id().await;
@@ -963,3 +1063,45 @@ pub async fn supplier_test_pagination<S: SupplierRepository>(ctx: &Context, repo
         assert!(!page2.iter().any(|s2| s2.id == s1.id));
     }
 }
+
+pub async fn supplier_test_update_touches_updated_at<S: SupplierRepository>(
+    ctx: &Context,
+    repo: S,
+) {
+    let id = super::generate_test_id().await;
+    let supplier = SupplierCreate {
+        name: "Touch Supplier".to_string(),
+        code: None,
+        email: None,
+        address: None,
+        phone: None,
+        npwp: None,
+        npwp_name: None,
+        metadata: None,
+    };
+    repo.create(ctx, id, &supplier)
+        .await
+        .expect("Failed to create supplier");
+    let before = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get supplier")
+        .expect("Supplier not found");
+
+    super::wait_for_clock_tick().await;
+    let update = SupplierUpdate {
+        name: Some("Touched Supplier".to_string()),
+        ..Default::default()
+    };
+    repo.update(ctx, id, &update)
+        .await
+        .expect("Failed to update supplier");
+
+    let after = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get supplier")
+        .expect("Supplier not found");
+    assert!(after.updated_at > before.updated_at);
+    assert_eq!(after.created_at, before.created_at);
+}
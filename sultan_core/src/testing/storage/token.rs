@@ -59,6 +59,8 @@ pub async fn token_test_save_and_get_token<Tx, U: UserRepository<Tx>>(
         user_id,
         expired_at,
         token: token_value.clone(),
+        family_id: "test_family".to_string(),
+        used_at: None,
     };
 
     // Save the token
@@ -111,6 +113,8 @@ pub async fn token_test_delete_token<Tx, U: UserRepository<Tx>>(
         user_id,
         expired_at: Utc::now() + Duration::hours(24),
         token: token_value.clone(),
+        family_id: "test_family".to_string(),
+        used_at: None,
     };
 
     token_repo
@@ -167,6 +171,8 @@ pub async fn token_test_multiple_tokens_same_user<Tx, U: UserRepository<Tx>>(
         user_id,
         expired_at: Utc::now() + Duration::hours(24),
         token: token1_value.clone(),
+        family_id: "test_family".to_string(),
+        used_at: None,
     };
 
     let token2_id = super::generate_test_id().await;
@@ -176,6 +182,8 @@ pub async fn token_test_multiple_tokens_same_user<Tx, U: UserRepository<Tx>>(
         user_id,
         expired_at: Utc::now() + Duration::hours(48),
         token: token2_value.clone(),
+        family_id: "test_family".to_string(),
+        used_at: None,
     };
 
     // Save both tokens
@@ -244,6 +252,8 @@ pub async fn token_test_token_with_expired_time<Tx, U: UserRepository<Tx>>(
         user_id,
         expired_at,
         token: token_value.clone(),
+        family_id: "test_family".to_string(),
+        used_at: None,
     };
 
     // Save the expired token (repository doesn't check expiration)
@@ -264,3 +274,182 @@ pub async fn token_test_token_with_expired_time<Tx, U: UserRepository<Tx>>(
     assert!(fetched.id > 0);
     assert!(fetched.expired_at < Utc::now(), "Token should be expired");
 }
+
+pub async fn token_test_delete_by_user_id<Tx, U: UserRepository<Tx>>(
+    ctx: &Context,
+    token_repo: impl TokenRepository,
+    user_repo: U,
+) {
+    let user_id = create_test_user(&user_repo, ctx).await;
+    let other_user_id = create_test_user(&user_repo, ctx).await;
+
+    let mut values = Vec::new();
+    for owner in [user_id, user_id, other_user_id] {
+        let token_id = super::generate_test_id().await;
+        let value = format!("revoke_test_token_{}", token_id);
+        token_repo
+            .save(
+                ctx,
+                &Token {
+                    id: token_id,
+                    user_id: owner,
+                    expired_at: Utc::now() + Duration::hours(24),
+                    token: value.clone(),
+                    family_id: "test_family".to_string(),
+                    used_at: None,
+                },
+            )
+            .await
+            .expect("Failed to save token");
+        values.push(value);
+    }
+
+    let removed = token_repo
+        .delete_by_user_id(ctx, user_id)
+        .await
+        .expect("Failed to delete tokens");
+    assert_eq!(removed, 2);
+
+    for value in &values[..2] {
+        let fetched = token_repo
+            .get_by_token(ctx, value)
+            .await
+            .expect("Query should succeed");
+        assert!(fetched.is_none(), "Token should be deleted");
+    }
+    // Other users keep their sessions
+    let fetched = token_repo
+        .get_by_token(ctx, &values[2])
+        .await
+        .expect("Query should succeed");
+    assert!(fetched.is_some(), "Other user's token should remain");
+
+    // Nothing left to revoke is not an error
+    let removed = token_repo
+        .delete_by_user_id(ctx, user_id)
+        .await
+        .expect("Failed to delete tokens");
+    assert_eq!(removed, 0);
+}
+
+pub async fn token_test_mark_used_and_delete_family<Tx, U: UserRepository<Tx>>(
+    ctx: &Context,
+    token_repo: impl TokenRepository,
+    user_repo: U,
+) {
+    let user_id = create_test_user(&user_repo, ctx).await;
+
+    let mut values = Vec::new();
+    for family_id in ["family_a", "family_a", "family_b"] {
+        let token_id = super::generate_test_id().await;
+        let value = format!("family_test_token_{}", token_id);
+        token_repo
+            .save(
+                ctx,
+                &Token {
+                    id: token_id,
+                    user_id,
+                    expired_at: Utc::now() + Duration::hours(24),
+                    token: value.clone(),
+                    family_id: family_id.to_string(),
+                    used_at: None,
+                },
+            )
+            .await
+            .expect("Failed to save token");
+        values.push(value);
+    }
+
+    let first = token_repo
+        .get_by_token(ctx, &values[0])
+        .await
+        .expect("Failed to get token")
+        .expect("Token should exist");
+    assert_eq!(first.family_id, "family_a");
+    assert!(first.used_at.is_none());
+
+    // Only the first call rotates the token
+    assert!(token_repo.mark_used(ctx, first.id).await.unwrap());
+    assert!(!token_repo.mark_used(ctx, first.id).await.unwrap());
+    let first = token_repo
+        .get_by_token(ctx, &values[0])
+        .await
+        .expect("Failed to get token")
+        .expect("Used token should be kept");
+    assert!(first.used_at.is_some());
+
+    let removed = token_repo
+        .delete_family(ctx, "family_a")
+        .await
+        .expect("Failed to delete family");
+    assert_eq!(removed, 2);
+    for value in &values[..2] {
+        let fetched = token_repo.get_by_token(ctx, value).await.unwrap();
+        assert!(fetched.is_none(), "Family token should be deleted");
+    }
+    let fetched = token_repo.get_by_token(ctx, &values[2]).await.unwrap();
+    assert!(fetched.is_some(), "Other family should remain");
+}
+
+pub async fn token_test_delete_expired<Tx, U: UserRepository<Tx>>(
+    ctx: &Context,
+    token_repo: impl TokenRepository,
+    user_repo: U,
+) {
+    let user_id = create_test_user(&user_repo, ctx).await;
+    let now = Utc::now();
+
+    let mut values = Vec::new();
+    for expired_at in [
+        now - Duration::days(3),
+        now - Duration::seconds(1),
+        now + Duration::seconds(1),
+        now + Duration::days(7),
+    ] {
+        let token_id = super::generate_test_id().await;
+        let value = format!("sweep_test_token_{}", token_id);
+        token_repo
+            .save(
+                ctx,
+                &Token {
+                    id: token_id,
+                    user_id,
+                    expired_at,
+                    token: value.clone(),
+                    family_id: "test_family".to_string(),
+                    used_at: None,
+                },
+            )
+            .await
+            .expect("Failed to save token");
+        values.push(value);
+    }
+
+    let removed = token_repo
+        .delete_expired(ctx, now)
+        .await
+        .expect("Failed to delete expired tokens");
+    assert_eq!(removed, 2);
+
+    for value in &values[..2] {
+        let fetched = token_repo
+            .get_by_token(ctx, value)
+            .await
+            .expect("Query should succeed");
+        assert!(fetched.is_none(), "Expired token should be deleted");
+    }
+    for value in &values[2..] {
+        let fetched = token_repo
+            .get_by_token(ctx, value)
+            .await
+            .expect("Query should succeed");
+        assert!(fetched.is_some(), "Active token should remain");
+    }
+
+    // A second sweep has nothing left to remove
+    let removed = token_repo
+        .delete_expired(ctx, now)
+        .await
+        .expect("Failed to delete expired tokens");
+    assert_eq!(removed, 0);
+}
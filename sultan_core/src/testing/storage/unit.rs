@@ -284,3 +284,39 @@ pub async fn unit_test_get_by_id_non_existent<U: UnitOfMeasureRepository>(ctx: &
 
     assert!(result.is_none());
 }
+
+pub async fn unit_test_update_touches_updated_at<U: UnitOfMeasureRepository>(
+    ctx: &Context,
+    repo: U,
+) {
+    let id = super::generate_test_id().await;
+    let unit = UnitOfMeasureCreate {
+        name: "Touch".to_string(),
+        description: None,
+    };
+    repo.create(ctx, id, &unit)
+        .await
+        .expect("Failed to create unit of measure");
+    let before = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get unit of measure")
+        .expect("Unit of measure not found");
+
+    super::wait_for_clock_tick().await;
+    let update = UnitOfMeasureUpdate {
+        name: Some("Touched".to_string()),
+        description: Update::Unchanged,
+    };
+    repo.update(ctx, id, &update)
+        .await
+        .expect("Failed to update unit of measure");
+
+    let after = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get unit of measure")
+        .expect("Unit of measure not found");
+    assert!(after.updated_at > before.updated_at);
+    assert_eq!(after.created_at, before.created_at);
+}
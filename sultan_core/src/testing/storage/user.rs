@@ -236,6 +236,7 @@ pub async fn user_test_update<Tx, U: UserRepository<Tx>>(ctx: &Context, repo: U)
         pin: Update::Unchanged,
         address: Update::Unchanged,
         phone: Update::Unchanged,
+        is_enabled: None,
     };
     repo.update_user(ctx, saved_user.id, &updated_user)
         .await
@@ -250,6 +251,51 @@ pub async fn user_test_update<Tx, U: UserRepository<Tx>>(ctx: &Context, repo: U)
     assert_eq!(updated_user.name, "Updated");
 }
 
+pub async fn user_test_disable_user<Tx, U: UserRepository<Tx>>(ctx: &Context, repo: U) {
+    let id = super::generate_test_id().await;
+    let user = UserCreate {
+        username: Uuid::new_v4().to_string(),
+        name: "Disable Test".to_string(),
+        email: None,
+        password: "hashed_password".to_string(),
+        photo: None,
+        pin: None,
+        address: None,
+        phone: None,
+    };
+    repo.create_user(ctx, id, &user)
+        .await
+        .expect("Failed to create user");
+
+    let created = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get user")
+        .expect("User not found");
+    assert!(created.is_enabled, "New users should be enabled");
+
+    let update = UserUpdate {
+        username: None,
+        name: None,
+        email: Update::Unchanged,
+        photo: Update::Unchanged,
+        pin: Update::Unchanged,
+        address: Update::Unchanged,
+        phone: Update::Unchanged,
+        is_enabled: Some(false),
+    };
+    repo.update_user(ctx, id, &update)
+        .await
+        .expect("Failed to disable user");
+
+    let disabled = repo
+        .get_user_by_username(ctx, &user.username)
+        .await
+        .expect("Failed to get user")
+        .expect("User not found");
+    assert!(!disabled.is_enabled);
+}
+
 pub async fn user_test_update_not_found<Tx, U: UserRepository<Tx>>(ctx: &Context, repo: U) {
     let user = UserUpdate {
         username: Some("non_existent".to_string()),
@@ -259,6 +305,7 @@ pub async fn user_test_update_not_found<Tx, U: UserRepository<Tx>>(ctx: &Context
         pin: Update::Unchanged,
         address: Update::Unchanged,
         phone: Update::Unchanged,
+        is_enabled: None,
     };
 
     let result = repo.update_user(ctx, 999, &user).await;
@@ -1065,3 +1112,138 @@ pub async fn user_test_delete_permission_null_vs_non_null_branch<Tx, U: UserRepo
     assert_eq!(permissions.len(), 1);
     assert_eq!(permissions[0].resource, 8);
 }
+
+pub async fn user_test_update_touches_updated_at<Tx, U: UserRepository<Tx>>(
+    ctx: &Context,
+    repo: U,
+) {
+    let id = super::generate_test_id().await;
+    let user = UserCreate {
+        username: Uuid::new_v4().to_string(),
+        name: "Touch".to_string(),
+        email: None,
+        password: "pass".to_string(),
+        photo: None,
+        pin: None,
+        address: None,
+        phone: None,
+    };
+    repo.create_user(ctx, id, &user)
+        .await
+        .expect("Failed to create user");
+    let created = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get user")
+        .expect("User not found");
+
+    super::wait_for_clock_tick().await;
+    let update = UserUpdate {
+        username: None,
+        name: Some("Touched".to_string()),
+        email: Update::Unchanged,
+        photo: Update::Unchanged,
+        pin: Update::Unchanged,
+        address: Update::Unchanged,
+        phone: Update::Unchanged,
+        is_enabled: None,
+    };
+    repo.update_user(ctx, id, &update)
+        .await
+        .expect("Failed to update user");
+    let updated = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get user")
+        .expect("User not found");
+    assert!(updated.updated_at > created.updated_at);
+
+    // Lockout bookkeeping is a write too and must bump the timestamp.
+    super::wait_for_clock_tick().await;
+    repo.record_failed_login(ctx, id, 5, chrono::Utc::now())
+        .await
+        .expect("Failed to record failed login");
+    let failed = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get user")
+        .expect("User not found");
+    assert!(failed.updated_at > updated.updated_at);
+
+    super::wait_for_clock_tick().await;
+    repo.reset_failed_logins(ctx, id)
+        .await
+        .expect("Failed to reset failed logins");
+    let reset = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get user")
+        .expect("User not found");
+    assert!(reset.updated_at > failed.updated_at);
+}
+
+pub async fn user_test_failed_login_after_lock_expired<Tx, U: UserRepository<Tx>>(
+    ctx: &Context,
+    repo: U,
+) {
+    let id = super::generate_test_id().await;
+    let user = UserCreate {
+        username: Uuid::new_v4().to_string(),
+        name: "Locked".to_string(),
+        email: None,
+        password: "pass".to_string(),
+        photo: None,
+        pin: None,
+        address: None,
+        phone: None,
+    };
+    repo.create_user(ctx, id, &user)
+        .await
+        .expect("Failed to create user");
+
+    // Reach the limit with a lock that has already run out
+    let expired = chrono::Utc::now() - chrono::Duration::minutes(1);
+    for _ in 0..3 {
+        repo.record_failed_login(ctx, id, 3, expired)
+            .await
+            .expect("Failed to record failed login");
+    }
+    let locked = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get user")
+        .expect("User not found");
+    assert_eq!(locked.failed_login_count, 3);
+    assert!(locked.locked_until.is_some());
+
+    // One more wrong password starts a new count instead of relocking
+    let future = chrono::Utc::now() + chrono::Duration::minutes(15);
+    repo.record_failed_login(ctx, id, 3, future)
+        .await
+        .expect("Failed to record failed login");
+    let after = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get user")
+        .expect("User not found");
+    assert_eq!(after.failed_login_count, 1);
+    assert!(after.locked_until.is_none());
+
+    // The count still locks the account once it reaches the limit again
+    for _ in 0..2 {
+        repo.record_failed_login(ctx, id, 3, future)
+            .await
+            .expect("Failed to record failed login");
+    }
+    let relocked = repo
+        .get_by_id(ctx, id)
+        .await
+        .expect("Failed to get user")
+        .expect("User not found");
+    assert_eq!(relocked.failed_login_count, 3);
+    assert!(
+        relocked
+            .locked_until
+            .is_some_and(|until| until > chrono::Utc::now())
+    );
+}
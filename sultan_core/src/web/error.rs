@@ -5,44 +5,73 @@ use axum::{
 };
 use serde_json::json;
 
+use super::i18n::ErrorMessage;
 use crate::domain::Error;
 
+/// nginx's "client closed request"; there is no standard code for a request
+/// the server gave up on because its caller went away
+const CLIENT_CLOSED_REQUEST: u16 = 499;
+
+impl Error {
+    /// HTTP status for this error. Every variant maps to exactly one status.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Error::ValidationError(_) => StatusCode::BAD_REQUEST,
+            Error::InvalidCredentials | Error::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Error::Forbidden(_) => StatusCode::FORBIDDEN,
+            Error::NotFound(_) => StatusCode::NOT_FOUND,
+            Error::Conflict(_) => StatusCode::CONFLICT,
+            Error::Cancelled(_) => StatusCode::from_u16(CLIENT_CLOSED_REQUEST)
+                .expect("499 is within the valid status range"),
+            Error::Database(_) | Error::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Machine-readable code for this error, sent as `code` next to the
+    /// human `error` message and used to look up localized messages
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::ValidationError(_) => "validation",
+            Error::Database(_) => "database",
+            Error::InvalidCredentials => "invalid_credentials",
+            Error::NotFound(_) => "not_found",
+            Error::Internal(_) => "internal",
+            Error::Unauthorized(_) => "unauthorized",
+            Error::Forbidden(_) => "forbidden",
+            Error::Cancelled(_) => "cancelled",
+            Error::Conflict(_) => "conflict",
+        }
+    }
+
+    /// Message safe to show to clients. Server-side failures are not echoed
+    /// back since they can leak SQL or internal details.
+    fn public_message(self) -> String {
+        match self {
+            Error::ValidationError(msg)
+            | Error::Unauthorized(msg)
+            | Error::Forbidden(msg)
+            | Error::NotFound(msg)
+            | Error::Conflict(msg)
+            | Error::Cancelled(msg) => msg,
+            Error::InvalidCredentials => "Invalid credentials".to_string(),
+            Error::Database(_) => "Database error".to_string(),
+            Error::Internal(_) => "Internal error".to_string(),
+        }
+    }
+}
+
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
         tracing::error!(error = ?self, "Request failed");
 
-        match self {
-            Error::ValidationError(msg) => {
-                (StatusCode::BAD_REQUEST, Json(json!({"error": msg}))).into_response()
-            }
-            Error::Database(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Database error"})),
-            )
-                .into_response(),
-            Error::InvalidCredentials => (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "Invalid credentials"})),
-            )
-                .into_response(),
-            Error::NotFound(msg) => {
-                (StatusCode::NOT_FOUND, Json(json!({"error": msg}))).into_response()
-            }
-            Error::Internal(_) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({"error": "Internal error"})),
-            )
-                .into_response(),
-            Error::Unauthorized(msg) => {
-                (StatusCode::UNAUTHORIZED, Json(json!({"error": msg}))).into_response()
-            }
-            Error::Forbidden(msg) => {
-                (StatusCode::FORBIDDEN, Json(json!({"error": msg}))).into_response()
-            }
-            Error::Cancelled(msg) => {
-                (StatusCode::REQUEST_TIMEOUT, Json(json!({"error": msg}))).into_response()
-            }
-        }
+        let status = self.status_code();
+        let code = self.code();
+        let message = self.public_message();
+        let mut response = (status, Json(json!({"error": message, "code": code}))).into_response();
+        response
+            .extensions_mut()
+            .insert(ErrorMessage { code, message });
+        response
     }
 }
 
@@ -66,6 +95,7 @@ mod tests {
 
         let json = response_to_json(response).await;
         assert_eq!(json["error"], "Invalid input");
+        assert_eq!(json["code"], "validation");
     }
 
     #[tokio::test]
@@ -77,6 +107,7 @@ mod tests {
 
         let json = response_to_json(response).await;
         assert_eq!(json["error"], "Database error");
+        assert_eq!(json["code"], "database");
     }
 
     #[tokio::test]
@@ -88,6 +119,7 @@ mod tests {
 
         let json = response_to_json(response).await;
         assert_eq!(json["error"], "Invalid credentials");
+        assert_eq!(json["code"], "invalid_credentials");
     }
 
     #[tokio::test]
@@ -99,6 +131,7 @@ mod tests {
 
         let json = response_to_json(response).await;
         assert_eq!(json["error"], "User not found");
+        assert_eq!(json["code"], "not_found");
     }
 
     #[tokio::test]
@@ -110,6 +143,7 @@ mod tests {
 
         let json = response_to_json(response).await;
         assert_eq!(json["error"], "Internal error");
+        assert_eq!(json["code"], "internal");
     }
 
     #[tokio::test]
@@ -121,6 +155,7 @@ mod tests {
 
         let json = response_to_json(response).await;
         assert_eq!(json["error"], "Not authenticated");
+        assert_eq!(json["code"], "unauthorized");
     }
 
     #[tokio::test]
@@ -132,6 +167,7 @@ mod tests {
 
         let json = response_to_json(response).await;
         assert_eq!(json["error"], "Access denied");
+        assert_eq!(json["code"], "forbidden");
     }
 
     #[tokio::test]
@@ -139,9 +175,45 @@ mod tests {
         let error = Error::Cancelled("Operation cancelled".to_string());
         let response = error.into_response();
 
-        assert_eq!(response.status(), StatusCode::REQUEST_TIMEOUT);
+        assert_eq!(response.status().as_u16(), 499);
 
         let json = response_to_json(response).await;
         assert_eq!(json["error"], "Operation cancelled");
+        assert_eq!(json["code"], "cancelled");
+    }
+
+    #[tokio::test]
+    async fn test_not_found_localized() {
+        use crate::web::i18n::localize_response;
+
+        let en = localize_response(
+            Error::NotFound("User not found".to_string()).into_response(),
+            Some("en"),
+        );
+        let id = localize_response(
+            Error::NotFound("User not found".to_string()).into_response(),
+            Some("id"),
+        );
+
+        assert_eq!(en.status(), StatusCode::NOT_FOUND);
+        assert_eq!(id.status(), StatusCode::NOT_FOUND);
+        let en = response_to_json(en).await;
+        let id = response_to_json(id).await;
+        assert_eq!(en["error"], "User not found");
+        assert_eq!(id["error"], "Data tidak ditemukan");
+        assert_eq!(id["code"], "not_found");
+        assert_eq!(id["detail"], "User not found");
+    }
+
+    #[tokio::test]
+    async fn test_conflict_response() {
+        let error = Error::Conflict("Sale already voided".to_string());
+        let response = error.into_response();
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let json = response_to_json(response).await;
+        assert_eq!(json["error"], "Sale already voided");
+        assert_eq!(json["code"], "conflict");
     }
 }
@@ -0,0 +1,135 @@
+use axum::{
+    Json,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+/// Locales with a message catalog. Error details are written in English, so
+/// English responses are rendered as they are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    Id,
+}
+
+impl Locale {
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Id => "id",
+        }
+    }
+
+    /// Match a language tag such as `id-ID` on its primary subtag
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split(['-', '_']).next()?.trim();
+        if primary.eq_ignore_ascii_case("en") {
+            Some(Locale::En)
+        } else if primary.eq_ignore_ascii_case("id") {
+            Some(Locale::Id)
+        } else {
+            None
+        }
+    }
+
+    /// Pick the supported locale the client prefers most from an
+    /// `Accept-Language` header. Ties keep the header order.
+    pub fn negotiate(accept_language: &str) -> Option<Self> {
+        let mut best: Option<(Self, f32)> = None;
+        for range in accept_language.split(',') {
+            let mut parts = range.split(';');
+            let Some(locale) = parts.next().and_then(Self::from_tag) else {
+                continue;
+            };
+            let quality = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if quality > 0.0 && best.is_none_or(|(_, q)| quality > q) {
+                best = Some((locale, quality));
+            }
+        }
+        best.map(|(locale, _)| locale)
+    }
+
+    /// Catalog entry for an error code, None where the English detail is used
+    pub fn error_message(&self, code: &str) -> Option<&'static str> {
+        match (self, code) {
+            (Locale::En, _) => None,
+            (Locale::Id, "validation") => Some("Data yang dikirim tidak valid"),
+            (Locale::Id, "not_found") => Some("Data tidak ditemukan"),
+            (Locale::Id, "invalid_credentials") => Some("Nama pengguna atau kata sandi salah"),
+            (Locale::Id, "unauthorized") => Some("Autentikasi diperlukan"),
+            (Locale::Id, "forbidden") => Some("Akses ditolak"),
+            (Locale::Id, "conflict") => Some("Data bentrok dengan data yang sudah ada"),
+            (Locale::Id, "cancelled") => Some("Permintaan dibatalkan"),
+            (Locale::Id, "database") => Some("Terjadi kesalahan basis data"),
+            (Locale::Id, "internal") => Some("Terjadi kesalahan internal"),
+            (Locale::Id, _) => None,
+        }
+    }
+}
+
+/// Attached to every error response so the body can be localized once the
+/// caller's locale is known
+#[derive(Debug, Clone)]
+pub struct ErrorMessage {
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// Render an error response in `locale`. The catalog message replaces
+/// `error` and the English detail moves to `detail`. Other responses, and
+/// locales without an entry for the code, pass through unchanged.
+pub fn localize_response(response: Response, locale: Option<&str>) -> Response {
+    let Some(locale) = locale.and_then(Locale::from_tag) else {
+        return response;
+    };
+    let Some(error) = response.extensions().get::<ErrorMessage>() else {
+        return response;
+    };
+    let Some(localized) = locale.error_message(error.code) else {
+        return response;
+    };
+
+    let body = json!({
+        "error": localized,
+        "code": error.code,
+        "detail": error.message,
+    });
+    let (parts, _) = response.into_parts();
+    let mut localized = (parts.status, Json(body)).into_response();
+    *localized.extensions_mut() = parts.extensions;
+    localized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_prefers_highest_quality() {
+        assert_eq!(
+            Locale::negotiate("id-ID,id;q=0.9,en;q=0.8"),
+            Some(Locale::Id)
+        );
+        assert_eq!(
+            Locale::negotiate("fr, en;q=0.5, id;q=0.7"),
+            Some(Locale::Id)
+        );
+        assert_eq!(Locale::negotiate("en-US"), Some(Locale::En));
+        assert_eq!(Locale::negotiate("id;q=0, en;q=0.1"), Some(Locale::En));
+        assert_eq!(Locale::negotiate("fr-FR, de"), None);
+        assert_eq!(Locale::negotiate(""), None);
+    }
+
+    #[test]
+    fn test_error_message_catalog() {
+        assert_eq!(Locale::En.error_message("not_found"), None);
+        assert_eq!(
+            Locale::Id.error_message("not_found"),
+            Some("Data tidak ditemukan")
+        );
+        assert_eq!(Locale::Id.error_message("unknown"), None);
+    }
+}
@@ -1 +1,2 @@
 pub mod error;
+pub mod i18n;
@@ -0,0 +1,137 @@
+mod common;
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use common::init_sqlite_pool;
+use sqlx::SqlitePool;
+use sultan_core::{
+    application::{ProductService, ProductServiceTrait},
+    domain::{
+        AuditSink, Context, Error, PermissionDenial,
+        model::{
+            permission::{action, resource},
+            product::{ProductCreate, ProductType},
+        },
+    },
+    snowflake::SnowflakeGenerator,
+    storage::sqlite::{
+        SqliteAuditSink, SqliteProductRepository, SqliteStockRepository,
+        transaction::SqliteTransactionManager,
+    },
+};
+
+#[derive(sqlx::FromRow, Debug)]
+struct AuditRow {
+    event: String,
+    actor_id: Option<i64>,
+    request_id: Option<i64>,
+    resource: i32,
+    action: i32,
+    branch_id: Option<i64>,
+}
+
+/// The sink writes from its writer task, so give `expected` rows a moment to
+/// land
+async fn wait_for_audit_rows(pool: &SqlitePool, expected: usize) -> Vec<AuditRow> {
+    for _ in 0..50 {
+        let rows: Vec<AuditRow> = sqlx::query_as(
+            "SELECT event, actor_id, request_id, resource, action, branch_id FROM audit_log",
+        )
+        .fetch_all(pool)
+        .await
+        .expect("Failed to read audit_log");
+        if rows.len() >= expected {
+            return rows;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    Vec::new()
+}
+
+fn product_create() -> ProductCreate {
+    ProductCreate {
+        name: "Audited Product".to_string(),
+        description: None,
+        product_type: ProductType::Product,
+        main_image: None,
+        sellable: true,
+        buyable: true,
+        editable_price: false,
+        has_variant: false,
+        metadata: None,
+        category_ids: vec![],
+    }
+}
+
+#[tokio::test]
+async fn test_denied_product_create_writes_one_audit_record() {
+    let pool = init_sqlite_pool().await;
+    let service = ProductService::new(
+        SqliteProductRepository::new(pool.clone()),
+        SqliteStockRepository::new(pool.clone()),
+        SqliteTransactionManager::new(pool.clone()),
+        SnowflakeGenerator::new(1).unwrap(),
+    );
+    // Can read products but not create them
+    let permissions = HashMap::from([((resource::PRODUCT, None), action::READ)]);
+    let ctx = Context::new_with_all(Some(42), permissions, HashMap::new())
+        .with_actor_id(42)
+        .with_request_id(7)
+        .with_audit_sink(Arc::new(SqliteAuditSink::new(pool.clone())));
+
+    let result = service.create_product(&ctx, &product_create(), &[]).await;
+    assert!(matches!(result, Err(Error::Forbidden(_))));
+
+    let rows = wait_for_audit_rows(&pool, 1).await;
+    assert_eq!(rows.len(), 1);
+    let row = &rows[0];
+    assert_eq!(row.event, "permission_denied");
+    assert_eq!(row.actor_id, Some(42));
+    assert_eq!(row.request_id, Some(7));
+    assert_eq!(row.resource, resource::PRODUCT);
+    assert_eq!(row.action, action::CREATE);
+    assert_eq!(row.branch_id, None);
+}
+
+#[tokio::test]
+async fn test_write_stores_branch() {
+    let pool = init_sqlite_pool().await;
+    let sink = SqliteAuditSink::new(pool.clone());
+
+    sink.write(&PermissionDenial {
+        actor_id: None,
+        request_id: None,
+        resource: resource::SALE,
+        action: action::UPDATE,
+        branch_id: Some(3),
+    })
+    .await
+    .expect("Failed to write audit record");
+
+    let rows = wait_for_audit_rows(&pool, 1).await;
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].actor_id, None);
+    assert_eq!(rows[0].resource, resource::SALE);
+    assert_eq!(rows[0].branch_id, Some(3));
+}
+
+#[tokio::test]
+async fn test_queued_denials_are_all_written() {
+    let pool = init_sqlite_pool().await;
+    let sink = SqliteAuditSink::new(pool.clone());
+
+    for request_id in 0..20 {
+        sink.permission_denied(&PermissionDenial {
+            actor_id: Some(42),
+            request_id: Some(request_id),
+            resource: resource::PRODUCT,
+            action: action::DELETE,
+            branch_id: None,
+        });
+    }
+
+    let rows = wait_for_audit_rows(&pool, 20).await;
+    let mut request_ids: Vec<i64> = rows.iter().filter_map(|row| row.request_id).collect();
+    request_ids.sort();
+    assert_eq!(request_ids, (0..20).collect::<Vec<i64>>());
+}
@@ -0,0 +1,351 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::Duration;
+use sultan_core::{
+    application::{AuthService, AuthServiceTrait},
+    crypto::{Argon2PasswordHasher, DefaultJwtManager, JwtConfig, JwtManager, PasswordHash},
+    domain::{
+        Context, Error,
+        model::{
+            device::{DeviceCreate, hash_device_key},
+            permission::{action, resource},
+            user::UserCreate,
+        },
+    },
+    storage::{
+        DeviceRepository, SqliteUserRepository, UserRepository,
+        sqlite::{SqliteBranchRepository, SqliteDeviceRepository, SqliteTokenRepository},
+    },
+    testing::storage::{generate_test_id, init_sqlite_pool},
+};
+
+const PASSWORD: &str = "correct-horse-1";
+
+async fn setup(
+    max_failed_logins: i32,
+    lockout: Duration,
+) -> (impl AuthServiceTrait, SqliteUserRepository, i64) {
+    let pool = init_sqlite_pool().await;
+    let user_repo = SqliteUserRepository::new(pool.clone());
+    let hasher = Argon2PasswordHasher::default();
+
+    let id = generate_test_id().await;
+    let user = UserCreate {
+        username: "lockout".to_string(),
+        password: hasher.hash_password(PASSWORD).unwrap(),
+        name: "Lockout User".to_string(),
+        email: None,
+        photo: None,
+        pin: None,
+        address: None,
+        phone: None,
+    };
+    user_repo
+        .create_user(&Context::new(), id, &user)
+        .await
+        .expect("Failed to create user");
+
+    let service = AuthService::new(
+        user_repo.clone(),
+        SqliteTokenRepository::new(pool.clone()),
+        SqliteBranchRepository::new(pool),
+        hasher,
+        DefaultJwtManager::new(JwtConfig::new("test_secret_key_for_testing_only", 60)),
+    )
+    .with_lockout(max_failed_logins, lockout);
+
+    (service, user_repo, id)
+}
+
+#[tokio::test]
+async fn test_bad_passwords_lock_account() {
+    let (service, user_repo, id) = setup(3, Duration::minutes(15)).await;
+    let ctx = Context::new();
+
+    for _ in 0..3 {
+        let result = service.login(&ctx, "lockout", "wrong-password-1").await;
+        assert!(matches!(result, Err(Error::InvalidCredentials)));
+    }
+
+    let user = user_repo.get_by_id(&ctx, id).await.unwrap().unwrap();
+    assert_eq!(user.failed_login_count, 3);
+    assert!(user.locked_until.is_some());
+
+    let result = service.login(&ctx, "lockout", "wrong-password-1").await;
+    assert!(matches!(result, Err(Error::Forbidden(_))));
+}
+
+#[tokio::test]
+async fn test_correct_password_while_locked_fails() {
+    let (service, _, _) = setup(2, Duration::minutes(15)).await;
+    let ctx = Context::new();
+
+    for _ in 0..2 {
+        let _ = service.login(&ctx, "lockout", "wrong-password-1").await;
+    }
+
+    let result = service.login(&ctx, "lockout", PASSWORD).await;
+    assert!(matches!(result, Err(Error::Forbidden(_))));
+}
+
+#[tokio::test]
+async fn test_login_after_cooldown_resets_counters() {
+    let (service, user_repo, id) = setup(2, Duration::milliseconds(200)).await;
+    let ctx = Context::new();
+
+    for _ in 0..2 {
+        let _ = service.login(&ctx, "lockout", "wrong-password-1").await;
+    }
+    let user = user_repo.get_by_id(&ctx, id).await.unwrap().unwrap();
+    assert!(user.locked_until.is_some());
+
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    service
+        .login(&ctx, "lockout", PASSWORD)
+        .await
+        .expect("Login should succeed after cooldown");
+
+    let user = user_repo.get_by_id(&ctx, id).await.unwrap().unwrap();
+    assert_eq!(user.failed_login_count, 0);
+    assert!(user.locked_until.is_none());
+}
+
+#[tokio::test]
+async fn test_successful_login_resets_failed_count() {
+    let (service, user_repo, id) = setup(5, Duration::minutes(15)).await;
+    let ctx = Context::new();
+
+    let _ = service.login(&ctx, "lockout", "wrong-password-1").await;
+    let user = user_repo.get_by_id(&ctx, id).await.unwrap().unwrap();
+    assert_eq!(user.failed_login_count, 1);
+
+    service.login(&ctx, "lockout", PASSWORD).await.unwrap();
+
+    let user = user_repo.get_by_id(&ctx, id).await.unwrap().unwrap();
+    assert_eq!(user.failed_login_count, 0);
+}
+
+// =============================================================================
+// Sessions
+// =============================================================================
+
+#[tokio::test]
+async fn test_logout_all_revokes_every_refresh_token() {
+    let (service, _, id) = setup(5, Duration::minutes(15)).await;
+    let mut refresh_tokens = Vec::new();
+    for _ in 0..3 {
+        let tokens = service
+            .login(&Context::new(), "lockout", PASSWORD)
+            .await
+            .unwrap();
+        refresh_tokens.push(tokens.refresh_token);
+    }
+
+    let ctx = Context::new_with_all(Some(id), HashMap::new(), HashMap::new());
+    service
+        .logout_all(&ctx, id)
+        .await
+        .expect("Logout should succeed");
+
+    for refresh_token in &refresh_tokens {
+        let result = service.refresh(&ctx, refresh_token).await;
+        assert!(matches!(result, Err(Error::Unauthorized(_))));
+    }
+
+    // Logging in again starts a fresh session
+    let tokens = service.login(&ctx, "lockout", PASSWORD).await.unwrap();
+    service
+        .refresh(&ctx, &tokens.refresh_token)
+        .await
+        .expect("New session should refresh");
+}
+
+#[tokio::test]
+async fn test_reused_refresh_token_revokes_family() {
+    let (service, _, _) = setup(5, Duration::minutes(15)).await;
+    let ctx = Context::new();
+    let first = service.login(&ctx, "lockout", PASSWORD).await.unwrap();
+    let second = service.refresh(&ctx, &first.refresh_token).await.unwrap();
+    let third = service.refresh(&ctx, &second.refresh_token).await.unwrap();
+
+    // An attacker replays a token that was already rotated
+    let result = service.refresh(&ctx, &first.refresh_token).await;
+    assert!(matches!(result, Err(Error::Unauthorized(_))));
+
+    // The legitimate client's latest token went with it
+    let result = service.refresh(&ctx, &third.refresh_token).await;
+    assert!(matches!(result, Err(Error::Unauthorized(_))));
+    let result = service.refresh(&ctx, &second.refresh_token).await;
+    assert!(matches!(result, Err(Error::Unauthorized(_))));
+}
+
+#[tokio::test]
+async fn test_admin_can_logout_all_for_another_user() {
+    let (service, _, id) = setup(5, Duration::minutes(15)).await;
+    let tokens = service
+        .login(&Context::new(), "lockout", PASSWORD)
+        .await
+        .unwrap();
+
+    let other = Context::new_with_all(Some(id + 1), HashMap::new(), HashMap::new());
+    let result = service.logout_all(&other, id).await;
+    assert!(matches!(result, Err(Error::Forbidden(_))));
+
+    let admin = Context::new_with_all(
+        Some(id + 1),
+        HashMap::from([((resource::ADMIN, None), action::UPDATE)]),
+        HashMap::new(),
+    );
+    service
+        .logout_all(&admin, id)
+        .await
+        .expect("Admin logout should succeed");
+    let result = service.refresh(&admin, &tokens.refresh_token).await;
+    assert!(matches!(result, Err(Error::Unauthorized(_))));
+}
+
+// =============================================================================
+// Password change
+// =============================================================================
+
+#[tokio::test]
+async fn test_change_password_invalidates_sessions() {
+    let (service, _, id) = setup(5, Duration::minutes(15)).await;
+    let tokens = service
+        .login(&Context::new(), "lockout", PASSWORD)
+        .await
+        .unwrap();
+
+    let ctx = Context::new_with_all(Some(id), HashMap::new(), HashMap::new());
+    service
+        .change_password(&ctx, id, PASSWORD, "battery-staple-2")
+        .await
+        .expect("Password change should succeed");
+
+    let result = service.refresh(&ctx, &tokens.refresh_token).await;
+    assert!(matches!(result, Err(Error::Unauthorized(_))));
+
+    let result = service.login(&ctx, "lockout", PASSWORD).await;
+    assert!(matches!(result, Err(Error::InvalidCredentials)));
+    service
+        .login(&ctx, "lockout", "battery-staple-2")
+        .await
+        .expect("New password should work");
+}
+
+#[tokio::test]
+async fn test_change_password_wrong_old_password() {
+    let (service, _, id) = setup(5, Duration::minutes(15)).await;
+    let ctx = Context::new_with_all(Some(id), HashMap::new(), HashMap::new());
+
+    let result = service
+        .change_password(&ctx, id, "wrong-password-1", "battery-staple-2")
+        .await;
+    assert!(matches!(result, Err(Error::InvalidCredentials)));
+
+    service
+        .login(&ctx, "lockout", PASSWORD)
+        .await
+        .expect("Old password should still work");
+}
+
+// =============================================================================
+// Device login
+// =============================================================================
+
+const DEVICE_KEY: &str = "kiosk-key-0123456789";
+
+fn jwt_manager() -> DefaultJwtManager {
+    DefaultJwtManager::new(JwtConfig::new("test_secret_key_for_testing_only", 60))
+}
+
+async fn setup_device() -> (impl AuthServiceTrait, SqliteDeviceRepository, i64, i64) {
+    let pool = init_sqlite_pool().await;
+    let user_repo = SqliteUserRepository::new(pool.clone());
+    let device_repo = SqliteDeviceRepository::new(pool.clone());
+    let ctx = Context::new();
+
+    let user_id = generate_test_id().await;
+    let user = UserCreate {
+        username: "kiosk".to_string(),
+        password: "unused".to_string(),
+        name: "Kiosk".to_string(),
+        email: None,
+        photo: None,
+        pin: None,
+        address: None,
+        phone: None,
+    };
+    user_repo
+        .create_user(&ctx, user_id, &user)
+        .await
+        .expect("Failed to create user");
+
+    let device_id = generate_test_id().await;
+    let device = DeviceCreate {
+        user_id,
+        name: "Front counter".to_string(),
+        key_hash: hash_device_key(DEVICE_KEY),
+    };
+    device_repo
+        .create(&ctx, device_id, &device)
+        .await
+        .expect("Failed to create device");
+
+    let service = AuthService::new(
+        user_repo,
+        SqliteTokenRepository::new(pool.clone()),
+        SqliteBranchRepository::new(pool),
+        Argon2PasswordHasher::default(),
+        jwt_manager(),
+    )
+    .with_device_repository(Arc::new(device_repo.clone()));
+
+    (service, device_repo, user_id, device_id)
+}
+
+#[tokio::test]
+async fn test_device_login_returns_token_with_device_claim() {
+    let (service, _, user_id, device_id) = setup_device().await;
+    let ctx = Context::new();
+
+    let token = service
+        .login_device(&ctx, DEVICE_KEY)
+        .await
+        .expect("Device login should succeed");
+
+    let claims = jwt_manager().validate_token(&token).unwrap();
+    assert_eq!(claims.user_id, user_id);
+    assert_eq!(claims.device_id, Some(device_id));
+    service
+        .check_device(&ctx, device_id)
+        .await
+        .expect("Device should be active");
+}
+
+#[tokio::test]
+async fn test_revoked_device_is_rejected() {
+    let (service, device_repo, _, device_id) = setup_device().await;
+    let ctx = Context::new();
+
+    service.login_device(&ctx, DEVICE_KEY).await.unwrap();
+    device_repo.revoke(&ctx, device_id).await.unwrap();
+
+    // Tokens already issued stop working, and it can't log in again
+    let result = service.check_device(&ctx, device_id).await;
+    assert!(matches!(result, Err(Error::Unauthorized(_))));
+    let result = service.login_device(&ctx, DEVICE_KEY).await;
+    assert!(matches!(result, Err(Error::InvalidCredentials)));
+}
+
+#[tokio::test]
+async fn test_unknown_device_key_is_rejected() {
+    let (service, _, _, _) = setup_device().await;
+    let ctx = Context::new();
+
+    let result = service.login_device(&ctx, "not-a-registered-key").await;
+    assert!(matches!(result, Err(Error::InvalidCredentials)));
+    let result = service.check_device(&ctx, 12345).await;
+    assert!(matches!(result, Err(Error::Unauthorized(_))));
+}
@@ -12,6 +12,12 @@ async fn test_partial_update_branch() {
     branch::branch_test_partial_update(&ctx, repo).await;
 }
 
+#[tokio::test]
+async fn test_update_touches_updated_at() {
+    let (ctx, repo) = branch::create_sqlite_branch_repo().await;
+    branch::branch_test_update_touches_updated_at(&ctx, repo).await;
+}
+
 #[tokio::test]
 async fn test_update_non_existent_branch() {
     let (ctx, repo) = branch::create_sqlite_branch_repo().await;
@@ -59,3 +65,33 @@ async fn test_update_address_scenarios() {
     let (ctx, repo) = branch::create_sqlite_branch_repo().await;
     branch::branch_test_update_address_scenarios(&ctx, repo).await;
 }
+
+#[tokio::test]
+async fn test_assign_user() {
+    let (ctx, repo, user_repo) = branch::create_sqlite_branch_repo_with_users().await;
+    branch::branch_test_assign_user(&ctx, repo, user_repo).await;
+}
+
+#[tokio::test]
+async fn test_assign_user_duplicate() {
+    let (ctx, repo, user_repo) = branch::create_sqlite_branch_repo_with_users().await;
+    branch::branch_test_assign_user_duplicate(&ctx, repo, user_repo).await;
+}
+
+#[tokio::test]
+async fn test_assign_unknown_user() {
+    let (ctx, repo, user_repo) = branch::create_sqlite_branch_repo_with_users().await;
+    branch::branch_test_assign_unknown_user(&ctx, repo, user_repo).await;
+}
+
+#[tokio::test]
+async fn test_unassign_user() {
+    let (ctx, repo, user_repo) = branch::create_sqlite_branch_repo_with_users().await;
+    branch::branch_test_unassign_user(&ctx, repo, user_repo).await;
+}
+
+#[tokio::test]
+async fn test_list_branches_for_user_skips_deleted() {
+    let (ctx, repo, user_repo) = branch::create_sqlite_branch_repo_with_users().await;
+    branch::branch_test_list_branches_for_user_skips_deleted(&ctx, repo, user_repo).await;
+}
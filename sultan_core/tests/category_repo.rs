@@ -1,4 +1,8 @@
-use sultan_core::testing::storage::category;
+use sultan_core::domain::Context;
+use sultan_core::domain::model::category::CategoryCreate;
+use sultan_core::storage::CategoryRepository;
+use sultan_core::storage::sqlite::SqliteCategoryRepository;
+use sultan_core::testing::storage::{self, category};
 
 // =============================================================================
 // Basic CRUD Tests
@@ -22,6 +26,12 @@ async fn test_update_category_name() {
     category::category_test_update_name(&ctx, repo).await;
 }
 
+#[tokio::test]
+async fn test_update_touches_updated_at() {
+    let (ctx, repo) = category::create_sqlite_category_repo().await;
+    category::category_test_update_touches_updated_at(&ctx, repo).await;
+}
+
 #[tokio::test]
 async fn test_update_category_description() {
     let (ctx, repo) = category::create_sqlite_category_repo().await;
@@ -175,3 +185,52 @@ async fn test_deep_nested_tree_retrieval() {
     let (ctx, repo) = category::create_sqlite_category_repo().await;
     category::category_test_deep_nested_tree_retrieval(&ctx, repo).await;
 }
+
+// =============================================================================
+// Branch Scoping Tests
+// =============================================================================
+
+#[tokio::test]
+async fn test_branch_scoped_reads() {
+    let (ctx, repo, branch_repo) = category::create_sqlite_category_repo_with_branches().await;
+    category::category_test_branch_scoped_reads(&ctx, repo, branch_repo).await;
+}
+
+#[tokio::test]
+async fn test_count() {
+    let (ctx, repo, branch_repo) = category::create_sqlite_category_repo_with_branches().await;
+    category::category_test_count(&ctx, repo, branch_repo).await;
+}
+
+#[tokio::test]
+async fn test_branch_assignment() {
+    let (ctx, repo, branch_repo) = category::create_sqlite_category_repo_with_branches().await;
+    category::category_test_branch_assignment(&ctx, repo, branch_repo).await;
+}
+
+#[tokio::test]
+async fn test_delete_records_deleting_actor() {
+    let pool = storage::init_sqlite_pool().await;
+    let repo = SqliteCategoryRepository::new(pool.clone());
+    let id = storage::generate_test_id().await;
+    let category = CategoryCreate {
+        name: "To Delete".to_string(),
+        description: None,
+        parent_id: None,
+    };
+    repo.create(&Context::new(), id, &category)
+        .await
+        .expect("Failed to create category");
+
+    repo.delete(&Context::new().with_actor_id(42), id)
+        .await
+        .expect("Failed to delete category");
+
+    let deleted_by: Option<i64> =
+        sqlx::query_scalar("SELECT deleted_by FROM categories WHERE id = ?")
+            .bind(id)
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to query");
+    assert_eq!(deleted_by, Some(42));
+}
@@ -10,6 +10,12 @@ async fn test_customer_repo_integration() {
     customer::customer_test_repo_integration(&ctx, repo).await;
 }
 
+#[tokio::test]
+async fn test_customer_records_actor() {
+    let (ctx, repo) = customer::create_sqlite_customer_repo().await;
+    customer::customer_test_records_actor(&ctx, repo).await;
+}
+
 #[tokio::test]
 async fn test_create_customer_with_all_fields() {
     let (ctx, repo) = customer::create_sqlite_customer_repo().await;
@@ -32,6 +38,12 @@ async fn test_partial_update_customer() {
     customer::customer_test_partial_update(&ctx, repo).await;
 }
 
+#[tokio::test]
+async fn test_update_touches_updated_at() {
+    let (ctx, repo) = customer::create_sqlite_customer_repo().await;
+    customer::customer_test_update_touches_updated_at(&ctx, repo).await;
+}
+
 #[tokio::test]
 async fn test_update_address_scenarios() {
     let (ctx, repo) = customer::create_sqlite_customer_repo().await;
@@ -72,6 +84,18 @@ async fn test_delete_non_existent_customer() {
     customer::customer_test_delete_non_existent(&ctx, repo).await;
 }
 
+#[tokio::test]
+async fn test_delete_many_customers_best_effort() {
+    let (ctx, repo) = customer::create_sqlite_customer_repo().await;
+    customer::customer_test_delete_many_best_effort(&ctx, repo).await;
+}
+
+#[tokio::test]
+async fn test_delete_many_customers_all_or_nothing() {
+    let (ctx, repo) = customer::create_sqlite_customer_repo().await;
+    customer::customer_test_delete_many_all_or_nothing(&ctx, repo).await;
+}
+
 #[tokio::test]
 async fn test_get_deleted_customer() {
     let (ctx, repo) = customer::create_sqlite_customer_repo().await;
@@ -107,9 +131,15 @@ async fn test_get_by_number_deleted_customer() {
 }
 
 #[tokio::test]
-async fn test_get_by_number_case_sensitive() {
+async fn test_get_by_number_case_insensitive() {
     let (ctx, repo) = customer::create_sqlite_customer_repo().await;
-    customer::customer_test_get_by_number_case_sensitive(&ctx, repo).await;
+    customer::customer_test_get_by_number_case_insensitive(&ctx, repo).await;
+}
+
+#[tokio::test]
+async fn test_number_unique_ignoring_case() {
+    let (ctx, repo) = customer::create_sqlite_customer_repo().await;
+    customer::customer_test_number_unique_ignoring_case(&ctx, repo).await;
 }
 
 #[tokio::test]
@@ -118,6 +148,12 @@ async fn test_get_by_id_not_found() {
     customer::customer_test_get_by_id_not_found(&ctx, repo).await;
 }
 
+#[tokio::test]
+async fn test_get_customers_by_ids() {
+    let (ctx, repo) = customer::create_sqlite_customer_repo().await;
+    customer::customer_test_get_by_ids(&ctx, repo).await;
+}
+
 #[tokio::test]
 async fn test_get_all_customers() {
     let (ctx, repo) = customer::create_sqlite_customer_repo().await;
@@ -128,12 +164,30 @@ async fn test_get_all_customers() {
 // Filter Tests
 // =============================================================================
 
+#[tokio::test]
+async fn test_count_ignores_pagination() {
+    let (ctx, repo) = customer::create_sqlite_customer_repo().await;
+    customer::customer_test_count_ignores_pagination(&ctx, repo).await;
+}
+
+#[tokio::test]
+async fn test_get_all_with_total() {
+    let (ctx, repo) = customer::create_sqlite_customer_repo().await;
+    customer::customer_test_get_all_with_total(&ctx, repo).await;
+}
+
 #[tokio::test]
 async fn test_filter_by_name() {
     let (ctx, repo) = customer::create_sqlite_customer_repo().await;
     customer::customer_test_filter_by_name(&ctx, repo).await;
 }
 
+#[tokio::test]
+async fn test_filter_by_name_escapes_wildcards() {
+    let (ctx, repo) = customer::create_sqlite_customer_repo().await;
+    customer::customer_test_filter_by_name_escapes_wildcards(&ctx, repo).await;
+}
+
 #[tokio::test]
 async fn test_filter_by_number() {
     let (ctx, repo) = customer::create_sqlite_customer_repo().await;
@@ -158,12 +212,42 @@ async fn test_filter_by_level() {
     customer::customer_test_filter_by_level(&ctx, repo).await;
 }
 
+#[tokio::test]
+async fn test_filter_by_level_range() {
+    let (ctx, repo) = customer::create_sqlite_customer_repo().await;
+    customer::customer_test_filter_by_level_range(&ctx, repo).await;
+}
+
 #[tokio::test]
 async fn test_filter_multiple_criteria() {
     let (ctx, repo) = customer::create_sqlite_customer_repo().await;
     customer::customer_test_filter_multiple_criteria(&ctx, repo).await;
 }
 
+#[tokio::test]
+async fn test_filter_expressions() {
+    let (ctx, repo) = customer::create_sqlite_customer_repo().await;
+    customer::customer_test_filter_expressions(&ctx, repo).await;
+}
+
+#[tokio::test]
+async fn test_filter_expression_rejects_unknown_field() {
+    let (ctx, repo) = customer::create_sqlite_customer_repo().await;
+    customer::customer_test_filter_expression_rejects_unknown_field(&ctx, repo).await;
+}
+
+#[tokio::test]
+async fn test_filter_by_search() {
+    let (ctx, repo) = customer::create_sqlite_customer_repo().await;
+    customer::customer_test_filter_by_search(&ctx, repo).await;
+}
+
+#[tokio::test]
+async fn test_search_by_local_phone() {
+    let (ctx, repo) = customer::create_sqlite_customer_repo().await;
+    customer::customer_test_search_by_local_phone(&ctx, repo).await;
+}
+
 // =============================================================================
 // Pagination Tests
 // =============================================================================
@@ -0,0 +1,56 @@
+use sultan_core::testing::storage::export;
+
+#[tokio::test]
+async fn test_export_excludes_deleted_unless_requested() {
+    let (ctx, repo, customer_repo, unit_repo) = export::create_sqlite_export_repo().await;
+    export::export_test_excludes_deleted_unless_requested(&ctx, repo, customer_repo, unit_repo)
+        .await;
+}
+
+#[tokio::test]
+async fn test_export_pages_in_id_order() {
+    let (ctx, repo, customer_repo, _) = export::create_sqlite_export_repo().await;
+    export::export_test_pages_in_id_order(&ctx, repo, customer_repo).await;
+}
+
+#[tokio::test]
+async fn test_export_reader_sees_one_snapshot() {
+    let (ctx, repo, customer_repo, _) = export::create_wal_sqlite_export_repo().await;
+    export::export_test_reader_sees_one_snapshot(&ctx, repo, customer_repo).await;
+}
+
+#[tokio::test]
+async fn test_export_import_round_trip() {
+    let (ctx, repo, _, _) = export::create_sqlite_export_repo().await;
+    export::export_test_import_round_trip(&ctx, repo).await;
+}
+
+#[tokio::test]
+async fn test_export_import_waits_for_writer() {
+    let (ctx, repo, pool) = export::create_busy_sqlite_export_repo().await;
+    export::export_test_import_waits_for_writer(&ctx, repo, pool).await;
+}
+
+#[tokio::test]
+async fn test_export_import_rolls_back_dangling_category() {
+    let (ctx, repo, _, _) = export::create_sqlite_export_repo().await;
+    export::export_test_import_rolls_back_dangling_category(&ctx, repo).await;
+}
+
+#[tokio::test]
+async fn test_export_import_conflict_unless_upsert() {
+    let (ctx, repo, _, _) = export::create_sqlite_export_repo().await;
+    export::export_test_import_conflict_unless_upsert(&ctx, repo).await;
+}
+
+#[tokio::test]
+async fn test_export_import_rejects_unknown_product_type() {
+    let (ctx, repo, _, _) = export::create_sqlite_export_repo().await;
+    export::export_test_import_rejects_unknown_product_type(&ctx, repo).await;
+}
+
+#[tokio::test]
+async fn test_export_import_checks_many_references() {
+    let (ctx, repo, _, _) = export::create_sqlite_export_repo().await;
+    export::export_test_import_checks_many_references(&ctx, repo).await;
+}
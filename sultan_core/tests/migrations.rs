@@ -0,0 +1,167 @@
+use sultan_core::testing::storage::init_sqlite_pool_before;
+
+/// Version of `customer_number_dedupe.sql`
+const CUSTOMER_NUMBER_DEDUPE: i64 = 20251231080000;
+/// Version of `variant_barcode_dedupe.sql`
+const BARCODE_DEDUPE: i64 = 20260105080000;
+/// Version of `product_type_check.sql`
+const PRODUCT_TYPE_CHECK: i64 = 20260111100000;
+
+#[tokio::test]
+async fn test_duplicate_barcodes_are_renamed_before_unique_index() {
+    let (pool, migrator) = init_sqlite_pool_before(BARCODE_DEDUPE).await;
+
+    sqlx::query("INSERT INTO products (id, name, product_type) VALUES (1, 'Legacy', 'product')")
+        .execute(&pool)
+        .await
+        .expect("Failed to seed product");
+    for (id, barcode, created_at, is_deleted) in [
+        (10, "DUP", "2025-01-02T00:00:00.000Z", 0),
+        // Oldest live variant keeps the barcode
+        (11, "DUP", "2025-01-01T00:00:00.000Z", 0),
+        (12, "DUP", "2025-01-03T00:00:00.000Z", 0),
+        // Deleted variants are outside the unique index and left alone
+        (13, "DUP", "2024-12-01T00:00:00.000Z", 1),
+        (14, "SOLO", "2025-01-01T00:00:00.000Z", 0),
+    ] {
+        sqlx::query(
+            "INSERT INTO product_variants (id, product_id, barcode, created_at, is_deleted) VALUES (?, 1, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(barcode)
+        .bind(created_at)
+        .bind(is_deleted)
+        .execute(&pool)
+        .await
+        .expect("Failed to seed variant");
+    }
+
+    migrator
+        .run(&pool)
+        .await
+        .expect("Migrations should succeed on duplicate barcodes");
+
+    let barcodes: Vec<(i64, String)> =
+        sqlx::query_as("SELECT id, barcode FROM product_variants ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to read variants");
+    assert_eq!(
+        barcodes,
+        vec![
+            (10, "DUP#10".to_string()),
+            (11, "DUP".to_string()),
+            (12, "DUP#12".to_string()),
+            (13, "DUP".to_string()),
+            (14, "SOLO".to_string()),
+        ]
+    );
+
+    // The unique index is in place afterwards
+    let duplicate = sqlx::query(
+        "INSERT INTO product_variants (id, product_id, barcode) VALUES (15, 1, 'SOLO')",
+    )
+    .execute(&pool)
+    .await;
+    assert!(duplicate.is_err());
+}
+
+#[tokio::test]
+async fn test_product_types_are_normalized_and_checked() {
+    let (pool, migrator) = init_sqlite_pool_before(PRODUCT_TYPE_CHECK).await;
+
+    for (id, product_type) in [
+        (1, "product"),
+        (2, " Service "),
+        (3, "BUNDLE"),
+        (4, "gadget"),
+    ] {
+        sqlx::query("INSERT INTO products (id, name, product_type) VALUES (?, 'Legacy', ?)")
+            .bind(id)
+            .bind(product_type)
+            .execute(&pool)
+            .await
+            .expect("Failed to seed product");
+    }
+
+    migrator.run(&pool).await.expect("Failed to run migrations");
+
+    let types: Vec<(i64, String)> =
+        sqlx::query_as("SELECT id, product_type FROM products ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to read products");
+    assert_eq!(
+        types,
+        vec![
+            (1, "product".to_string()),
+            (2, "service".to_string()),
+            (3, "bundle".to_string()),
+            (4, "product".to_string()),
+        ]
+    );
+
+    let insert =
+        sqlx::query("INSERT INTO products (id, name, product_type) VALUES (5, 'New', 'gadget')")
+            .execute(&pool)
+            .await;
+    assert!(insert.is_err());
+    let update = sqlx::query("UPDATE products SET product_type = 'Product' WHERE id = 1")
+        .execute(&pool)
+        .await;
+    assert!(update.is_err());
+}
+
+#[tokio::test]
+async fn test_case_duplicate_customer_numbers_are_renamed_before_unique_index() {
+    let (pool, migrator) = init_sqlite_pool_before(CUSTOMER_NUMBER_DEDUPE).await;
+
+    for (id, number, created_at, is_deleted) in [
+        (10, "abc123", "2025-01-02T00:00:00.000Z", 0),
+        // Oldest live customer keeps the number
+        (11, "ABC123", "2025-01-01T00:00:00.000Z", 0),
+        (12, "Abc123", "2025-01-03T00:00:00.000Z", 0),
+        // Deleted customers are outside the unique index and left alone
+        (13, "abc123", "2024-12-01T00:00:00.000Z", 1),
+        (14, "SOLO", "2025-01-01T00:00:00.000Z", 0),
+    ] {
+        sqlx::query(
+            "INSERT INTO customers (id, number, name, created_at, is_deleted) VALUES (?, ?, 'Legacy', ?, ?)",
+        )
+        .bind(id)
+        .bind(number)
+        .bind(created_at)
+        .bind(is_deleted)
+        .execute(&pool)
+        .await
+        .expect("Failed to seed customer");
+    }
+
+    migrator
+        .run(&pool)
+        .await
+        .expect("Migrations should succeed on case-duplicate numbers");
+
+    let numbers: Vec<(i64, String)> =
+        sqlx::query_as("SELECT id, number FROM customers ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to read customers");
+    assert_eq!(
+        numbers,
+        vec![
+            (10, "abc123#10".to_string()),
+            (11, "ABC123".to_string()),
+            (12, "Abc123#12".to_string()),
+            (13, "abc123".to_string()),
+            (14, "SOLO".to_string()),
+        ]
+    );
+
+    // The case-insensitive unique index is in place afterwards
+    let duplicate =
+        sqlx::query("INSERT INTO customers (id, number, name) VALUES (15, 'solo', 'New')")
+            .execute(&pool)
+            .await;
+    assert!(duplicate.is_err());
+}
@@ -0,0 +1,69 @@
+use sultan_core::domain::Context;
+use sultan_core::domain::model::customer::CustomerFilter;
+use sultan_core::domain::model::pagination::PaginationOptions;
+use sultan_core::storage::{
+    CategoryRepository, CustomerRepository, SupplierRepository, UserRepository,
+};
+use sultan_core::testing::mocks::{
+    MockCategoryRepo, MockCustomerRepo, MockSupplierRepo, MockUserRepo,
+};
+
+// =============================================================================
+// Shared repository mocks
+// =============================================================================
+
+#[tokio::test]
+async fn test_customer_repo_mock() {
+    let mut repo = MockCustomerRepo::new();
+    repo.expect_get_all_with_total()
+        .times(1)
+        .returning(|_, _, _| Ok((vec![], 7)));
+
+    let (customers, total) = repo
+        .get_all_with_total(
+            &Context::new(),
+            &CustomerFilter::default(),
+            &PaginationOptions::new(1, 20, None),
+        )
+        .await
+        .unwrap();
+    assert!(customers.is_empty());
+    assert_eq!(total, 7);
+}
+
+#[tokio::test]
+async fn test_category_repo_mock() {
+    let mut repo = MockCategoryRepo::new();
+    repo.expect_count()
+        .withf(|_, branch_id| *branch_id == Some(2))
+        .times(1)
+        .returning(|_, _| Ok(4));
+
+    assert_eq!(repo.count(&Context::new(), Some(2)).await.unwrap(), 4);
+}
+
+#[tokio::test]
+async fn test_supplier_repo_mock() {
+    let mut repo = MockSupplierRepo::new();
+    repo.expect_get_by_id()
+        .withf(|_, id| *id == 3)
+        .times(1)
+        .returning(|_, _| Ok(None));
+
+    assert!(repo.get_by_id(&Context::new(), 3).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_user_repo_mock() {
+    let mut repo = MockUserRepo::new();
+    repo.expect_delete_user_tx()
+        .withf(|_, user_id, _| *user_id == 5)
+        .times(1)
+        .returning(|_, _, _| Ok(()));
+
+    assert!(
+        repo.delete_user_tx(&Context::new(), 5, &mut ())
+            .await
+            .is_ok()
+    );
+}
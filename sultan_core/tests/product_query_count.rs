@@ -0,0 +1,136 @@
+//! Counts the SQL statements issued by `get_all_with_variants`.
+//!
+//! sqlx reports every executed statement as a tracing event with the
+//! `sqlx::query` target. Statements run on the SQLite worker thread, so the
+//! counter has to be installed as the global subscriber, which is why this
+//! test lives in its own binary. The PRAGMAs sqlx runs when the pool opens a
+//! new connection are not counted.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use sultan_core::{
+    domain::model::{
+        pagination::PaginationOptions,
+        product::{ProductCreate, ProductFilter, ProductType, ProductVariantCreate},
+    },
+    storage::{ProductRepository, transaction::TransactionManager},
+    testing::storage::{generate_test_id, product::create_sqlite_product_repo},
+};
+use tracing::{
+    Event, Subscriber,
+    field::{Field, Visit},
+};
+use tracing_subscriber::{Layer, layer::Context as LayerContext, prelude::*};
+
+#[derive(Clone, Default)]
+struct QueryCounter(Arc<AtomicUsize>);
+
+impl QueryCounter {
+    fn reset(&self) {
+        self.0.store(0, Ordering::SeqCst);
+    }
+
+    fn get(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Default)]
+struct Statement(String);
+
+impl Visit for Statement {
+    fn record_debug(&mut self, _: &Field, _: &dyn std::fmt::Debug) {}
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "db.statement" {
+            self.0 = value.to_string();
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for QueryCounter {
+    fn on_event(&self, event: &Event<'_>, _: LayerContext<'_, S>) {
+        if event.metadata().target() != "sqlx::query" {
+            return;
+        }
+        let mut statement = Statement::default();
+        event.record(&mut statement);
+        if !statement.0.trim().starts_with("PRAGMA") {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_get_all_with_variants_runs_two_queries() {
+    let counter = QueryCounter::default();
+    tracing_subscriber::registry().with(counter.clone()).init();
+
+    let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    for i in 0..5 {
+        let product_id = generate_test_id().await;
+        let product = ProductCreate {
+            name: format!("Product {}", i),
+            description: None,
+            product_type: ProductType::Product,
+            main_image: None,
+            sellable: true,
+            buyable: true,
+            editable_price: false,
+            has_variant: true,
+            metadata: None,
+            category_ids: vec![],
+        };
+        repo.create_product(&ctx, product_id, &product, &mut tx)
+            .await
+            .expect("Failed to create product");
+        for j in 0..3 {
+            let variant = ProductVariantCreate {
+                product_id,
+                barcode: Some(format!("{}-{}", i, j)),
+                name: Some(format!("Variant {}", j)),
+                price: None,
+                metadata: None,
+                initial_stock: vec![],
+            };
+            repo.create_variant(&ctx, generate_test_id().await, &variant, &mut tx)
+                .await
+                .expect("Failed to create variant");
+        }
+    }
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    // Make sure the counter actually sees statements before trusting it
+    assert!(counter.get() > 0);
+
+    counter.reset();
+    let products = repo
+        .get_all_with_variants(
+            &ctx,
+            &ProductFilter::default(),
+            &PaginationOptions::new(1, 5, None),
+        )
+        .await
+        .expect("Failed to get products with variants");
+    assert_eq!(products.len(), 5);
+    assert!(products.iter().all(|p| p.variants.len() == 3));
+    assert_eq!(counter.get(), 2);
+
+    // An empty page stops after the product query
+    counter.reset();
+    let products = repo
+        .get_all_with_variants(
+            &ctx,
+            &ProductFilter::default(),
+            &PaginationOptions::new(2, 5, None),
+        )
+        .await
+        .expect("Failed to get products with variants");
+    assert!(products.is_empty());
+    assert_eq!(counter.get(), 1);
+}
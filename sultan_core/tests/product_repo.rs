@@ -3,15 +3,17 @@ use serde_json::json;
 use sultan_core::testing::storage::product;
 use sultan_core::testing::storage::product::create_sqlite_product_repo;
 use sultan_core::{
-    domain::model::product::{ProductCreate, ProductVariantCreate},
-    storage::{ProductRepository, transaction::TransactionManager},
+    domain::model::product::{ProductCreate, ProductType, ProductVariantCreate},
+    storage::{
+        ProductRepository, sqlite::SqliteSupplierRepository, transaction::TransactionManager,
+    },
 };
 
 fn create_test_product() -> ProductCreate {
     ProductCreate {
         name: "Test Product".to_string(),
         description: Some("A test product description".to_string()),
-        product_type: "product".to_string(),
+        product_type: ProductType::Product,
         main_image: Some("https://example.com/image.jpg".to_string()),
         sellable: true,
         buyable: true,
@@ -27,7 +29,9 @@ fn create_test_variant(product_id: i64) -> ProductVariantCreate {
         product_id,
         barcode: Some("1234567890".to_string()),
         name: Some("Default Variant".to_string()),
+        price: None,
         metadata: Some(json!({"sku": "SKU001"})),
+        initial_stock: vec![],
     }
 }
 
@@ -41,6 +45,12 @@ async fn test_create_product_success() {
     product::product_test_create_success(&ctx, &tx_manager, &repo).await;
 }
 
+#[tokio::test]
+async fn test_product_records_actor() {
+    let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
+    product::test_product_records_actor(&ctx, &tx_manager, &repo).await;
+}
+
 #[tokio::test]
 async fn test_create_product_without_optional_fields() {
     let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
@@ -60,6 +70,12 @@ async fn test_update_product_name() {
     product::test_update_product_name(&ctx, &tx_manager, &repo).await;
 }
 
+#[tokio::test]
+async fn test_update_product_touches_updated_at() {
+    let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
+    product::test_update_product_touches_updated_at(&ctx, &tx_manager, &repo).await;
+}
+
 #[tokio::test]
 async fn test_update_product_clear_description() {
     let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
@@ -81,6 +97,97 @@ async fn test_update_product_categories() {
     product::test_update_product_categories(&ctx, &tx_manager, &repo, &category_repo).await;
 }
 
+#[tokio::test]
+async fn test_get_products_by_category() {
+    let (ctx, tx_manager, repo, category_repo, _) = create_sqlite_product_repo().await;
+    product::test_get_products_by_category(&ctx, &tx_manager, &repo, &category_repo).await;
+}
+
+#[tokio::test]
+async fn test_touch_product() {
+    let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
+    product::test_touch_product(&ctx, &tx_manager, &repo).await;
+}
+
+#[tokio::test]
+async fn test_variant_duplicate_barcode_conflicts() {
+    let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
+    product::test_variant_duplicate_barcode_conflicts(&ctx, &tx_manager, &repo).await;
+}
+
+#[tokio::test]
+async fn test_upsert_variant_by_barcode() {
+    let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
+    product::test_upsert_variant_by_barcode(&ctx, &tx_manager, &repo).await;
+}
+
+#[tokio::test]
+async fn test_upsert_variant_by_barcode_other_product_conflicts() {
+    let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
+    product::test_upsert_variant_by_barcode_other_product_conflicts(&ctx, &tx_manager, &repo).await;
+}
+
+#[tokio::test]
+async fn test_variant_reuses_deleted_barcode() {
+    let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
+    product::test_variant_reuses_deleted_barcode(&ctx, &tx_manager, &repo).await;
+}
+
+#[tokio::test]
+async fn test_reassign_variant() {
+    let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
+    product::test_reassign_variant(&ctx, &tx_manager, &repo).await;
+}
+
+#[tokio::test]
+async fn test_reassign_variant_to_deleted_product() {
+    let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
+    product::test_reassign_variant_to_deleted_product(&ctx, &tx_manager, &repo).await;
+}
+
+#[tokio::test]
+async fn test_get_all_with_variants() {
+    let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
+    product::test_get_all_with_variants(&ctx, &tx_manager, &repo).await;
+}
+
+#[tokio::test]
+async fn test_get_all_with_variants_filter() {
+    let (ctx, tx_manager, repo, category_repo, _) = create_sqlite_product_repo().await;
+    product::test_get_all_with_variants_filter(&ctx, &tx_manager, &repo, &category_repo).await;
+}
+
+#[tokio::test]
+async fn test_count_matches_filter() {
+    let (ctx, tx_manager, repo, category_repo, _) = create_sqlite_product_repo().await;
+    product::test_count_matches_filter(&ctx, &tx_manager, &repo, &category_repo).await;
+}
+
+#[tokio::test]
+async fn test_get_variants_with_filter() {
+    let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
+    product::test_get_variants_with_filter(&ctx, &tx_manager, &repo).await;
+}
+
+#[tokio::test]
+async fn test_add_and_remove_product_categories() {
+    let (ctx, tx_manager, repo, category_repo, _) = create_sqlite_product_repo().await;
+    product::test_add_and_remove_product_categories(&ctx, &tx_manager, &repo, &category_repo).await;
+}
+
+#[tokio::test]
+async fn test_set_and_get_product_suppliers() {
+    let (ctx, tx_manager, repo, _, pool) = create_sqlite_product_repo().await;
+    let supplier_repo = SqliteSupplierRepository::new(pool);
+    product::test_set_and_get_product_suppliers(&ctx, &tx_manager, &repo, &supplier_repo).await;
+}
+
+#[tokio::test]
+async fn test_set_and_get_product_components() {
+    let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
+    product::test_set_and_get_product_components(&ctx, &tx_manager, &repo).await;
+}
+
 #[tokio::test]
 async fn test_update_product_not_found() {
     let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
@@ -127,6 +234,12 @@ async fn test_update_variant_barcode() {
     product::test_update_variant_barcode(&ctx, &tx_manager, &repo).await;
 }
 
+#[tokio::test]
+async fn test_update_variant_touches_updated_at() {
+    let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
+    product::test_update_variant_touches_updated_at(&ctx, &tx_manager, &repo).await;
+}
+
 #[tokio::test]
 async fn test_update_variant_clear_name() {
     let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
@@ -191,6 +304,12 @@ async fn test_get_variant_by_product_id_success() {
     product::test_get_variant_by_product_id_success(&ctx, &tx_manager, &repo).await;
 }
 
+#[tokio::test]
+async fn test_count_variants() {
+    let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
+    product::test_count_variants(&ctx, &tx_manager, &repo).await;
+}
+
 #[tokio::test]
 async fn test_get_variant_by_product_id_empty() {
     let (ctx, tx_manager, repo, _, _) = create_sqlite_product_repo().await;
@@ -311,6 +430,189 @@ async fn test_soft_delete_variant_preserves_data() {
     assert!(is_deleted);
 }
 
+#[tokio::test]
+async fn test_soft_delete_records_deleting_actor() {
+    let (ctx, tx_manager, repo, _, pool) = create_sqlite_product_repo().await;
+    let product_id = common::generate_test_id().await;
+    let variant_id = common::generate_test_id().await;
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    repo.create_variant(&ctx, variant_id, &create_test_variant(product_id), &mut tx)
+        .await
+        .expect("Failed to create variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let actor = ctx.clone().with_actor_id(42);
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.delete_product(&actor, product_id, &mut tx)
+        .await
+        .expect("Failed to delete product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let product_deleted_by: Option<i64> =
+        sqlx::query_scalar("SELECT deleted_by FROM products WHERE id = ?")
+            .bind(product_id)
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to query");
+    assert_eq!(product_deleted_by, Some(42));
+    // Variants deleted along with the product carry the same actor
+    let variant_deleted_by: Option<i64> =
+        sqlx::query_scalar("SELECT deleted_by FROM product_variants WHERE id = ?")
+            .bind(variant_id)
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to query");
+    assert_eq!(variant_deleted_by, Some(42));
+
+    // A system context leaves it empty
+    let other_id = common::generate_test_id().await;
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, other_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    repo.delete_product(&ctx, other_id, &mut tx)
+        .await
+        .expect("Failed to delete product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let deleted_by: Option<i64> =
+        sqlx::query_scalar("SELECT deleted_by FROM products WHERE id = ?")
+            .bind(other_id)
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to query");
+    assert_eq!(deleted_by, None);
+}
+
+#[tokio::test]
+async fn test_delete_product_without_variants() {
+    let (ctx, tx_manager, repo, _, pool) = create_sqlite_product_repo().await;
+    let product_id = common::generate_test_id().await;
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.delete_product(&ctx, product_id, &mut tx)
+        .await
+        .expect("Deleting a product without variants should succeed");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let is_deleted: bool = sqlx::query_scalar("SELECT is_deleted FROM products WHERE id = ?")
+        .bind(product_id)
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to query");
+    assert!(is_deleted);
+}
+
+#[tokio::test]
+async fn test_delete_product_soft_deletes_variants() {
+    let (ctx, tx_manager, repo, _, pool) = create_sqlite_product_repo().await;
+    let product_id = common::generate_test_id().await;
+    let other_product_id = common::generate_test_id().await;
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    repo.create_product(&ctx, other_product_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    for _ in 0..3 {
+        let variant = ProductVariantCreate {
+            barcode: None,
+            ..create_test_variant(product_id)
+        };
+        repo.create_variant(&ctx, common::generate_test_id().await, &variant, &mut tx)
+            .await
+            .expect("Failed to create variant");
+    }
+    let other_variant_id = common::generate_test_id().await;
+    repo.create_variant(
+        &ctx,
+        other_variant_id,
+        &create_test_variant(other_product_id),
+        &mut tx,
+    )
+    .await
+    .expect("Failed to create variant");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.delete_product(&ctx, product_id, &mut tx)
+        .await
+        .expect("Failed to delete product");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let rows: Vec<(bool, Option<String>)> =
+        sqlx::query_as("SELECT is_deleted, deleted_at FROM product_variants WHERE product_id = ?")
+            .bind(product_id)
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to query");
+    assert_eq!(rows.len(), 3);
+    assert!(
+        rows.iter()
+            .all(|(is_deleted, deleted_at)| *is_deleted && deleted_at.is_some())
+    );
+
+    // Variants of other products are left alone
+    let other_deleted: bool =
+        sqlx::query_scalar("SELECT is_deleted FROM product_variants WHERE id = ?")
+            .bind(other_variant_id)
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to query");
+    assert!(!other_deleted);
+}
+
+#[tokio::test]
+async fn test_get_variant_by_barcode_with_legacy_duplicates() {
+    let (ctx, tx_manager, repo, _, pool) = create_sqlite_product_repo().await;
+    // Stand in for data written before barcodes had to be unique
+    sqlx::query("DROP INDEX idx_product_variants_barcode")
+        .execute(&pool)
+        .await
+        .expect("Failed to drop index");
+
+    let product_id = common::generate_test_id().await;
+    let older_id = common::generate_test_id().await;
+    let newer_id = common::generate_test_id().await;
+
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.create_product(&ctx, product_id, &create_test_product(), &mut tx)
+        .await
+        .expect("Failed to create product");
+    for id in [older_id, newer_id] {
+        repo.create_variant(&ctx, id, &create_test_variant(product_id), &mut tx)
+            .await
+            .expect("Failed to create variant");
+    }
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    let variant = repo
+        .get_variant_by_barcode(&ctx, "1234567890")
+        .await
+        .expect("Failed to get variant")
+        .expect("Variant not found");
+    assert_eq!(variant.id, newer_id);
+
+    let variants = repo
+        .get_variants_by_barcode(&ctx, "1234567890")
+        .await
+        .expect("Failed to get variants");
+    let ids: Vec<i64> = variants.iter().map(|v| v.id).collect();
+    assert_eq!(ids, vec![newer_id, older_id]);
+}
+
 // =============================================================================
 // Edge Cases
 // =============================================================================
@@ -397,6 +699,12 @@ async fn test_update_variant_clear_metadata() {
     product::test_update_variant_clear_metadata(&ctx, &tx_manager, &repo).await;
 }
 
+#[tokio::test]
+async fn test_variant_price_round_trip() {
+    let (ctx, tx_manager, repo, _, _) = product::create_sqlite_product_repo().await;
+    product::test_variant_price_round_trip(&ctx, &tx_manager, &repo).await;
+}
+
 #[tokio::test]
 async fn test_update_variant_clear_barcode() {
     let (ctx, tx_manager, repo, _, _) = product::create_sqlite_product_repo().await;
@@ -475,3 +783,15 @@ async fn test_update_variant_set_metadata() {
     let (ctx, tx_manager, repo, _, _) = product::create_sqlite_product_repo().await;
     product::test_update_variant_set_metadata(&ctx, &tx_manager, &repo).await;
 }
+
+#[tokio::test]
+async fn test_variant_level_price() {
+    let (ctx, tx_manager, repo, _, _) = product::create_sqlite_product_repo().await;
+    product::test_variant_level_price(&ctx, &tx_manager, &repo).await;
+}
+
+#[tokio::test]
+async fn test_variant_price_history() {
+    let (ctx, tx_manager, repo, _, _) = product::create_sqlite_product_repo().await;
+    product::test_variant_price_history(&ctx, &tx_manager, &repo).await;
+}
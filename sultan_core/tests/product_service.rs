@@ -0,0 +1,153 @@
+use sultan_core::{
+    application::{ProductService, ProductServiceTrait},
+    domain::{
+        Context, Error,
+        model::{
+            product::{ProductCreate, ProductType, ProductVariantCreate},
+            stock::InitialStock,
+        },
+    },
+    snowflake::SnowflakeGenerator,
+    storage::sqlite::{
+        SqliteProductRepository, SqliteStockRepository, transaction::SqliteTransactionManager,
+    },
+    testing::storage::{
+        purchase_order::stock_quantity,
+        stock::{StockTestData, create_sqlite_stock_repo},
+    },
+};
+
+fn create_service(data: &StockTestData) -> impl ProductServiceTrait {
+    ProductService::new(
+        SqliteProductRepository::new(data.pool.clone()),
+        SqliteStockRepository::new(data.pool.clone()),
+        SqliteTransactionManager::new(data.pool.clone()),
+        SnowflakeGenerator::new(1).unwrap(),
+    )
+}
+
+fn product() -> ProductCreate {
+    ProductCreate {
+        name: "Teh Manis".to_string(),
+        description: None,
+        product_type: ProductType::Product,
+        main_image: None,
+        sellable: true,
+        buyable: true,
+        editable_price: false,
+        has_variant: true,
+        metadata: None,
+        category_ids: vec![],
+    }
+}
+
+fn variant(barcode: &str, initial_stock: Vec<InitialStock>) -> ProductVariantCreate {
+    ProductVariantCreate {
+        product_id: 0,
+        barcode: Some(barcode.to_string()),
+        name: None,
+        price: None,
+        metadata: None,
+        initial_stock,
+    }
+}
+
+async fn count(data: &StockTestData, sql: &str) -> i64 {
+    sqlx::query_scalar(sql)
+        .fetch_one(&data.pool)
+        .await
+        .expect("Failed to count rows")
+}
+
+#[tokio::test]
+async fn test_create_product_with_initial_stock() {
+    let data = create_sqlite_stock_repo().await;
+    let service = create_service(&data);
+    let ctx = Context::new_internal();
+    let stock = |quantity| InitialStock {
+        branch_id: data.branch_id,
+        quantity,
+    };
+
+    let id = service
+        .create_product(
+            &ctx,
+            &product(),
+            &[variant("TEH-1", vec![stock(12)]), variant("TEH-2", vec![])],
+        )
+        .await
+        .expect("Failed to create product");
+
+    let variants = service.get_variant_by_product_id(&ctx, id).await.unwrap();
+    assert_eq!(variants.len(), 2);
+    for variant in variants {
+        let expected = match variant.barcode.as_deref() {
+            Some("TEH-1") => 12,
+            _ => 0,
+        };
+        assert_eq!(
+            stock_quantity(&data.pool, data.branch_id, variant.id).await,
+            expected
+        );
+    }
+    assert_eq!(
+        count(
+            &data,
+            "SELECT COUNT(*) FROM stock_adjustments WHERE reason = 'initial stock'"
+        )
+        .await,
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_create_product_with_stock_in_unknown_branch_rolls_back() {
+    let data = create_sqlite_stock_repo().await;
+    let service = create_service(&data);
+    let ctx = Context::new_internal();
+    let products_before = count(&data, "SELECT COUNT(*) FROM products").await;
+    let variants_before = count(&data, "SELECT COUNT(*) FROM product_variants").await;
+    let stocks_before = count(&data, "SELECT COUNT(*) FROM stocks").await;
+
+    let result = service
+        .create_product(
+            &ctx,
+            &product(),
+            &[
+                variant(
+                    "TEH-1",
+                    vec![InitialStock {
+                        branch_id: data.branch_id,
+                        quantity: 12,
+                    }],
+                ),
+                variant(
+                    "TEH-2",
+                    vec![InitialStock {
+                        branch_id: 999_999,
+                        quantity: 3,
+                    }],
+                ),
+            ],
+        )
+        .await;
+
+    assert!(matches!(result, Err(Error::ValidationError(_))));
+    // Nothing from the failed create is left behind
+    assert_eq!(
+        count(&data, "SELECT COUNT(*) FROM products").await,
+        products_before
+    );
+    assert_eq!(
+        count(&data, "SELECT COUNT(*) FROM product_variants").await,
+        variants_before
+    );
+    assert_eq!(
+        count(&data, "SELECT COUNT(*) FROM stocks").await,
+        stocks_before
+    );
+    assert_eq!(
+        count(&data, "SELECT COUNT(*) FROM stock_adjustments").await,
+        0
+    );
+}
@@ -0,0 +1,19 @@
+use sultan_core::testing::storage::purchase_order;
+
+#[tokio::test]
+async fn test_create_purchase_order() {
+    let data = purchase_order::create_sqlite_purchase_order_repo().await;
+    purchase_order::purchase_order_test_create(&data).await;
+}
+
+#[tokio::test]
+async fn test_get_purchase_order_by_id_not_found() {
+    let data = purchase_order::create_sqlite_purchase_order_repo().await;
+    purchase_order::purchase_order_test_get_by_id_not_found(&data).await;
+}
+
+#[tokio::test]
+async fn test_update_purchase_order_status() {
+    let data = purchase_order::create_sqlite_purchase_order_repo().await;
+    purchase_order::purchase_order_test_update_status(&data).await;
+}
@@ -0,0 +1,84 @@
+use sultan_core::{
+    application::{PurchaseOrderService, PurchaseOrderServiceTrait},
+    domain::{Context, Error, model::purchase_order::PurchaseOrderStatus},
+    snowflake::SnowflakeGenerator,
+    storage::sqlite::{SqlitePurchaseOrderRepository, transaction::SqliteTransactionManager},
+    testing::storage::purchase_order::{
+        PurchaseOrderTestData, create_sqlite_purchase_order_repo, create_test_purchase_order,
+        stock_quantity,
+    },
+};
+
+fn create_service(data: &PurchaseOrderTestData) -> impl PurchaseOrderServiceTrait {
+    PurchaseOrderService::new(
+        SqlitePurchaseOrderRepository::new(data.pool.clone()),
+        SqliteTransactionManager::new(data.pool.clone()),
+        SnowflakeGenerator::new(1).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_receive_adds_stock() {
+    let data = create_sqlite_purchase_order_repo().await;
+    let service = create_service(&data);
+    let ctx = Context::new_internal();
+
+    let id = service
+        .create(&ctx, &create_test_purchase_order(&data))
+        .await
+        .expect("Failed to create purchase order");
+    service
+        .receive(&ctx, id)
+        .await
+        .expect("Failed to receive purchase order");
+
+    // Existing stock is topped up and missing stock rows are created
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[0]).await,
+        17
+    );
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[1]).await,
+        3
+    );
+    let order = service.get_by_id(&ctx, id).await.unwrap().unwrap();
+    assert_eq!(order.status, PurchaseOrderStatus::Received);
+}
+
+#[tokio::test]
+async fn test_receive_twice_conflicts() {
+    let data = create_sqlite_purchase_order_repo().await;
+    let service = create_service(&data);
+    let ctx = Context::new_internal();
+
+    let id = service
+        .create(&ctx, &create_test_purchase_order(&data))
+        .await
+        .expect("Failed to create purchase order");
+    service
+        .receive(&ctx, id)
+        .await
+        .expect("Failed to receive purchase order");
+
+    let result = service.receive(&ctx, id).await;
+    assert!(matches!(result, Err(Error::Conflict(_))));
+
+    // Stock is only added once
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[0]).await,
+        17
+    );
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[1]).await,
+        3
+    );
+}
+
+#[tokio::test]
+async fn test_receive_unknown_purchase_order() {
+    let data = create_sqlite_purchase_order_repo().await;
+    let service = create_service(&data);
+
+    let result = service.receive(&Context::new_internal(), 999_999).await;
+    assert!(matches!(result, Err(Error::NotFound(_))));
+}
@@ -0,0 +1,73 @@
+use sultan_core::{
+    domain::model::{
+        branch::Branch, category::Category, customer::Customer, supplier::Supplier, user::User,
+    },
+    storage::ReadRepository,
+    testing::storage::read_repo,
+};
+
+#[tokio::test]
+async fn test_read_repo_generic_customer_and_supplier() {
+    let (ctx, customer_repo, customer_ids, supplier_repo, supplier_ids) =
+        read_repo::create_sqlite_read_repos().await;
+
+    read_repo::read_repo_test_get_by_id_and_all(
+        &ctx,
+        &customer_repo,
+        &customer_ids,
+        |c: &Customer| c.id,
+    )
+    .await;
+    read_repo::read_repo_test_get_by_id_and_all(
+        &ctx,
+        &supplier_repo,
+        &supplier_ids,
+        |s: &Supplier| s.id,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_read_repo_trait_objects() {
+    let (ctx, customer_repo, customer_ids, supplier_repo, supplier_ids) =
+        read_repo::create_sqlite_read_repos().await;
+
+    let customers: &dyn ReadRepository<Customer> = &customer_repo;
+    let suppliers: &dyn ReadRepository<Supplier> = &supplier_repo;
+
+    read_repo::read_repo_test_get_by_id_and_all(&ctx, customers, &customer_ids, |c: &Customer| {
+        c.id
+    })
+    .await;
+    read_repo::read_repo_test_get_by_id_and_all(&ctx, suppliers, &supplier_ids, |s: &Supplier| {
+        s.id
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_read_repo_generic_user_branch_and_category() {
+    let (ctx, repos) = read_repo::create_sqlite_admin_read_repos().await;
+
+    read_repo::read_repo_test_get_by_id_and_all(
+        &ctx,
+        &repos.user_repo,
+        &repos.user_ids,
+        |u: &User| u.id,
+    )
+    .await;
+    read_repo::read_repo_test_get_by_id_and_all(
+        &ctx,
+        &repos.branch_repo,
+        &repos.branch_ids,
+        |b: &Branch| b.id,
+    )
+    .await;
+    read_repo::read_repo_test_get_by_id_and_all(
+        &ctx,
+        &repos.category_repo,
+        &repos.category_ids,
+        |c: &Category| c.id,
+    )
+    .await;
+}
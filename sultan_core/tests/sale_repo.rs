@@ -0,0 +1,43 @@
+use sultan_core::testing::storage::sale;
+
+#[tokio::test]
+async fn test_create_multi_line_sale() {
+    let data = sale::create_sqlite_sale_repo().await;
+    sale::sale_test_create_multi_line(&data).await;
+}
+
+#[tokio::test]
+async fn test_insufficient_stock_rolls_back() {
+    let data = sale::create_sqlite_sale_repo().await;
+    sale::sale_test_insufficient_stock_rolls_back(&data).await;
+}
+
+#[tokio::test]
+async fn test_get_sale_by_id_not_found() {
+    let data = sale::create_sqlite_sale_repo().await;
+    sale::sale_test_get_by_id_not_found(&data).await;
+}
+
+#[tokio::test]
+async fn test_daily_summary() {
+    let data = sale::create_sqlite_sale_repo().await;
+    sale::sale_test_daily_summary(&data).await;
+}
+
+#[tokio::test]
+async fn test_summary_empty_day() {
+    let data = sale::create_sqlite_sale_repo().await;
+    sale::sale_test_summary_empty_day(&data).await;
+}
+
+#[tokio::test]
+async fn test_delete_expired_idempotency_keys() {
+    let data = sale::create_sqlite_sale_repo().await;
+    sale::sale_test_delete_expired_idempotency_keys(&data).await;
+}
+
+#[tokio::test]
+async fn test_idempotency_key_scoped_to_branch_and_user() {
+    let data = sale::create_sqlite_sale_repo().await;
+    sale::sale_test_idempotency_key_scoped_to_branch_and_user(&data).await;
+}
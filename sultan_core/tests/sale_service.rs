@@ -0,0 +1,207 @@
+use chrono::Duration;
+use sultan_core::{
+    application::{SaleService, SaleServiceTrait},
+    domain::{
+        Context, Error,
+        model::{
+            money::Money,
+            sale::{SaleCreate, SaleKind, SaleLineCreate, SaleRefundLine},
+        },
+    },
+    snowflake::SnowflakeGenerator,
+    storage::sqlite::{SqliteSaleRepository, transaction::SqliteTransactionManager},
+    testing::storage::sale::{SaleTestData, create_sqlite_sale_repo, stock_quantity},
+};
+
+fn create_service(data: &SaleTestData, ttl: Duration) -> impl SaleServiceTrait {
+    SaleService::new(
+        SqliteSaleRepository::new(data.pool.clone()),
+        SqliteTransactionManager::new(data.pool.clone()),
+        SnowflakeGenerator::new(1).unwrap(),
+    )
+    .with_idempotency_ttl(ttl)
+}
+
+fn create_test_sale(data: &SaleTestData) -> SaleCreate {
+    SaleCreate {
+        branch_id: data.branch_id,
+        customer_id: None,
+        payment_method: "cash".to_string(),
+        lines: vec![SaleLineCreate {
+            variant_id: data.variant_ids[0],
+            qty: 4,
+            unit_price: Money::from_major(3),
+        }],
+    }
+}
+
+#[tokio::test]
+async fn test_same_idempotency_key_returns_same_sale() {
+    let data = create_sqlite_sale_repo().await;
+    let service = create_service(&data, Duration::hours(1));
+    let ctx = Context::new_internal();
+    let sale = create_test_sale(&data);
+
+    let first = service
+        .create(&ctx, &sale, Some("pos-1-receipt-17"))
+        .await
+        .expect("Failed to create sale");
+    let second = service
+        .create(&ctx, &sale, Some("pos-1-receipt-17"))
+        .await
+        .expect("Retry failed");
+
+    assert_eq!(first, second);
+    // Stock is only taken once
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[0]).await,
+        6
+    );
+}
+
+#[tokio::test]
+async fn test_different_idempotency_keys_create_separate_sales() {
+    let data = create_sqlite_sale_repo().await;
+    let service = create_service(&data, Duration::hours(1));
+    let ctx = Context::new_internal();
+    let sale = create_test_sale(&data);
+
+    let first = service.create(&ctx, &sale, Some("a")).await.unwrap();
+    let second = service.create(&ctx, &sale, Some("b")).await.unwrap();
+
+    assert_ne!(first, second);
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[0]).await,
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_expired_idempotency_key_creates_new_sale() {
+    let data = create_sqlite_sale_repo().await;
+    let service = create_service(&data, Duration::zero());
+    let ctx = Context::new_internal();
+    let sale = create_test_sale(&data);
+
+    let first = service.create(&ctx, &sale, Some("stale")).await.unwrap();
+    let second = service.create(&ctx, &sale, Some("stale")).await.unwrap();
+
+    assert_ne!(first, second);
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[0]).await,
+        2
+    );
+}
+
+fn create_two_line_sale(data: &SaleTestData) -> SaleCreate {
+    SaleCreate {
+        branch_id: data.branch_id,
+        customer_id: None,
+        payment_method: "cash".to_string(),
+        lines: vec![
+            SaleLineCreate {
+                variant_id: data.variant_ids[0],
+                qty: 4,
+                unit_price: Money::from_major(3),
+            },
+            SaleLineCreate {
+                variant_id: data.variant_ids[1],
+                qty: 2,
+                unit_price: Money::from_major(5),
+            },
+        ],
+    }
+}
+
+#[tokio::test]
+async fn test_void_restores_all_stock() {
+    let data = create_sqlite_sale_repo().await;
+    let service = create_service(&data, Duration::hours(1));
+    let ctx = Context::new_internal();
+
+    let sale_id = service
+        .create(&ctx, &create_two_line_sale(&data), None)
+        .await
+        .unwrap();
+    let void_id = service.void(&ctx, sale_id).await.expect("Failed to void");
+
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[0]).await,
+        10
+    );
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[1]).await,
+        10
+    );
+
+    let void = service.get_by_id(&ctx, void_id).await.unwrap().unwrap();
+    assert_eq!(void.kind, SaleKind::Void);
+    assert_eq!(void.original_sale_id, Some(sale_id));
+    assert_eq!(void.total, Money::from_major(22));
+
+    let again = service.void(&ctx, sale_id).await;
+    assert!(matches!(again, Err(Error::Conflict(_))));
+}
+
+#[tokio::test]
+async fn test_partial_refund_restores_refunded_quantity() {
+    let data = create_sqlite_sale_repo().await;
+    let service = create_service(&data, Duration::hours(1));
+    let ctx = Context::new_internal();
+
+    let sale_id = service
+        .create(&ctx, &create_two_line_sale(&data), None)
+        .await
+        .unwrap();
+    let refund_id = service
+        .refund_lines(
+            &ctx,
+            sale_id,
+            &[SaleRefundLine {
+                variant_id: data.variant_ids[0],
+                qty: 1,
+            }],
+        )
+        .await
+        .expect("Failed to refund");
+
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[0]).await,
+        7
+    );
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[1]).await,
+        8
+    );
+    let refund = service.get_by_id(&ctx, refund_id).await.unwrap().unwrap();
+    assert_eq!(refund.kind, SaleKind::Refund);
+    assert_eq!(refund.total, Money::from_major(3));
+
+    // Only 3 of the 4 units are left to refund
+    let over = service
+        .refund_lines(
+            &ctx,
+            sale_id,
+            &[SaleRefundLine {
+                variant_id: data.variant_ids[0],
+                qty: 4,
+            }],
+        )
+        .await;
+    assert!(matches!(over, Err(Error::ValidationError(_))));
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[0]).await,
+        7
+    );
+
+    // A void after a partial refund only puts back what is left
+    service.void(&ctx, sale_id).await.expect("Failed to void");
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[0]).await,
+        10
+    );
+    assert_eq!(
+        stock_quantity(&data.pool, data.branch_id, data.variant_ids[1]).await,
+        10
+    );
+}
@@ -10,7 +10,7 @@ use sultan_core::testing::storage::sell_price::{
     SellPriceTestData, sell_price_test_repo_integration,
 };
 use sultan_core::{
-    domain::model::product::{ProductCreate, ProductVariantCreate},
+    domain::model::product::{ProductCreate, ProductType, ProductVariantCreate},
     storage::{ProductRepository, transaction::TransactionManager},
 };
 
@@ -18,7 +18,7 @@ fn create_test_product() -> ProductCreate {
     ProductCreate {
         name: "Test Product".to_string(),
         description: Some("A test product description".to_string()),
-        product_type: "product".to_string(),
+        product_type: ProductType::Product,
         main_image: Some("https://example.com/image.jpg".to_string()),
         sellable: true,
         buyable: true,
@@ -34,7 +34,9 @@ fn create_test_variant(product_id: i64, barcode: &str) -> ProductVariantCreate {
         product_id,
         barcode: Some(barcode.to_string()),
         name: Some("Default Variant".to_string()),
+        price: None,
         metadata: Some(json!({"sku": "SKU001"})),
+        initial_stock: vec![],
     }
 }
 
@@ -47,7 +49,6 @@ async fn create_sell_price_test_data() -> SellPriceTestData<'static, SqliteTrans
 
     let product = create_test_product();
     let unit_repo = SqliteUnitOfMeasureRepository::new(pool.clone());
-    let mut tx = tx_manager.begin().await.unwrap();
 
     let unit_id = sultan_core::testing::storage::generate_test_id().await;
     unit_repo
@@ -62,6 +63,7 @@ async fn create_sell_price_test_data() -> SellPriceTestData<'static, SqliteTrans
         .await
         .expect("unable to create unit");
 
+    let mut tx = tx_manager.begin().await.unwrap();
     let product_id = sultan_core::testing::storage::generate_test_id().await;
     product_repo
         .create_product(&ctx, product_id, &product, &mut tx)
@@ -163,6 +165,14 @@ async fn test_update_price_only() {
     .await;
 }
 
+#[tokio::test]
+async fn test_update_touches_updated_at() {
+    sultan_core::testing::storage::sell_price::sell_price_test_update_touches_updated_at(
+        &create_sell_price_test_data().await,
+    )
+    .await;
+}
+
 #[tokio::test]
 async fn test_update_quantity_only() {
     sultan_core::testing::storage::sell_price::sell_price_test_update_quantity_only(
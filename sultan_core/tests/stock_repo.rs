@@ -0,0 +1,25 @@
+use sultan_core::testing::storage::stock;
+
+#[tokio::test]
+async fn test_adjust_stock() {
+    let data = stock::create_sqlite_stock_repo().await;
+    stock::stock_test_adjust(&data).await;
+}
+
+#[tokio::test]
+async fn test_adjust_stock_insufficient() {
+    let data = stock::create_sqlite_stock_repo().await;
+    stock::stock_test_adjust_insufficient(&data).await;
+}
+
+#[tokio::test]
+async fn test_adjust_stock_unknown_variant() {
+    let data = stock::create_sqlite_stock_repo().await;
+    stock::stock_test_adjust_unknown_variant(&data).await;
+}
+
+#[tokio::test]
+async fn test_list_low_stock() {
+    let data = stock::create_sqlite_stock_repo().await;
+    stock::stock_test_list_low(&data).await;
+}
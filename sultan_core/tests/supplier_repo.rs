@@ -10,6 +10,18 @@ async fn test_supplier_repo_integration() {
     supplier::supplier_test_repo_integration(&ctx, repo).await;
 }
 
+#[tokio::test]
+async fn test_supplier_records_actor() {
+    let (ctx, repo) = supplier::create_sqlite_supplier_repo().await;
+    supplier::supplier_test_records_actor(&ctx, repo).await;
+}
+
+#[tokio::test]
+async fn test_supplier_without_actor() {
+    let (ctx, repo) = supplier::create_sqlite_supplier_repo().await;
+    supplier::supplier_test_without_actor(&ctx, repo).await;
+}
+
 #[tokio::test]
 async fn test_create_supplier_with_all_fields() {
     let (ctx, repo) = supplier::create_sqlite_supplier_repo().await;
@@ -32,6 +44,12 @@ async fn test_partial_update_supplier() {
     supplier::supplier_test_partial_update(&ctx, repo).await;
 }
 
+#[tokio::test]
+async fn test_update_touches_updated_at() {
+    let (ctx, repo) = supplier::create_sqlite_supplier_repo().await;
+    supplier::supplier_test_update_touches_updated_at(&ctx, repo).await;
+}
+
 #[tokio::test]
 async fn test_update_address_scenarios() {
     let (ctx, repo) = supplier::create_sqlite_supplier_repo().await;
@@ -98,6 +116,12 @@ async fn test_get_all_suppliers() {
 // Filter Tests
 // =============================================================================
 
+#[tokio::test]
+async fn test_count_ignores_pagination() {
+    let (ctx, repo) = supplier::create_sqlite_supplier_repo().await;
+    supplier::supplier_test_count_ignores_pagination(&ctx, repo).await;
+}
+
 #[tokio::test]
 async fn test_filter_by_name() {
     let (ctx, repo) = supplier::create_sqlite_supplier_repo().await;
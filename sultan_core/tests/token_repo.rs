@@ -35,3 +35,21 @@ async fn test_token_with_expired_time() {
     let (ctx, token_repo, user_repo) = token::create_sqlite_user_and_token_repo().await;
     token::token_test_token_with_expired_time(&ctx, token_repo, user_repo).await;
 }
+
+#[tokio::test]
+async fn test_delete_tokens_by_user_id() {
+    let (ctx, token_repo, user_repo) = token::create_sqlite_user_and_token_repo().await;
+    token::token_test_delete_by_user_id(&ctx, token_repo, user_repo).await;
+}
+
+#[tokio::test]
+async fn test_mark_used_and_delete_family() {
+    let (ctx, token_repo, user_repo) = token::create_sqlite_user_and_token_repo().await;
+    token::token_test_mark_used_and_delete_family(&ctx, token_repo, user_repo).await;
+}
+
+#[tokio::test]
+async fn test_delete_expired_tokens() {
+    let (ctx, token_repo, user_repo) = token::create_sqlite_user_and_token_repo().await;
+    token::token_test_delete_expired(&ctx, token_repo, user_repo).await;
+}
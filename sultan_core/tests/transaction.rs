@@ -1,10 +1,16 @@
 mod common;
 
+use std::panic::{self, AssertUnwindSafe};
+use std::str::FromStr;
+use std::time::Duration;
+
 use common::init_sqlite_pool;
-use sultan_core::domain::Context;
+use sqlx::SqlitePool;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
+use sultan_core::domain::{Context, Error};
 use sultan_core::snowflake::SnowflakeGenerator;
 use sultan_core::storage::UserRepository;
-use sultan_core::storage::sqlite::transaction::SqliteTransactionManager;
+use sultan_core::storage::sqlite::transaction::{BusyRetry, SqliteTransactionManager};
 use sultan_core::storage::sqlite::user::SqliteUserRepository;
 use sultan_core::storage::transaction::TransactionManager;
 
@@ -165,6 +171,60 @@ async fn test_implicit_rollback_on_drop() {
     assert!(user.is_none());
 }
 
+#[tokio::test]
+async fn test_panic_rolls_back_transaction() {
+    let pool = init_sqlite_pool().await;
+    let tx_manager = SqliteTransactionManager::new(pool.clone());
+    let user_repo = SqliteUserRepository::new(pool);
+    let ctx = Context::new();
+
+    let user_id = generate_test_id();
+
+    let mut tx = tx_manager
+        .begin()
+        .await
+        .expect("Failed to begin transaction");
+
+    sqlx::query(
+        r#"
+        INSERT INTO users (id, username, password, name, email, phone)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(user_id)
+    .bind("testuser_panic")
+    .bind("hash123")
+    .bind("Test User Panic")
+    .bind(Some("panic@example.com"))
+    .bind(Some("2222222222"))
+    .execute(&mut *tx)
+    .await
+    .expect("Failed to insert user");
+
+    // The guard is dropped while unwinding, before commit is reached
+    let result = panic::catch_unwind(AssertUnwindSafe(move || {
+        let _tx = tx;
+        panic!("service failed mid-transaction");
+    }));
+    assert!(result.is_err());
+
+    let user = user_repo
+        .get_by_id(&ctx, user_id)
+        .await
+        .expect("Failed to get user");
+    assert!(user.is_none());
+
+    // The connection went back to the pool usable
+    let tx = tx_manager
+        .begin()
+        .await
+        .expect("Failed to begin transaction");
+    tx_manager
+        .commit(tx)
+        .await
+        .expect("Failed to commit transaction");
+}
+
 // =============================================================================
 // Transaction with Multiple Operations
 // =============================================================================
@@ -445,3 +505,154 @@ async fn test_transaction_manager_pool_access() {
     let pool_ref = tx_manager.pool();
     assert_eq!(pool_ref.size() as u32, pool.size() as u32);
 }
+
+// =============================================================================
+// Busy Retry Tests
+// =============================================================================
+
+/// File database in rollback-journal mode whose connections report
+/// `SQLITE_BUSY` at once instead of waiting, so contention reaches the retry
+/// logic quickly. A writer cannot commit while another transaction is reading.
+async fn init_contended_pool() -> SqlitePool {
+    let path = std::env::temp_dir().join(format!("sultan_busy_{}.db", uuid::Uuid::new_v4()));
+    let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", path.display()))
+        .unwrap()
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Delete)
+        .busy_timeout(Duration::ZERO);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(2)
+        .connect_with(options)
+        .await
+        .expect("Failed to open database");
+    sqlx::query("CREATE TABLE items (id INTEGER PRIMARY KEY)")
+        .execute(&pool)
+        .await
+        .expect("Failed to create table");
+    pool
+}
+
+/// Open a transaction that holds a read lock until it is committed
+async fn begin_reader(pool: &SqlitePool) -> sqlx::Transaction<'static, sqlx::Sqlite> {
+    let mut reader = pool.begin().await.expect("Failed to begin reader");
+    sqlx::query("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut *reader)
+        .await
+        .expect("Failed to read");
+    reader
+}
+
+#[tokio::test]
+async fn test_commit_retries_while_another_transaction_reads() {
+    let pool = init_contended_pool().await;
+    let tx_manager = SqliteTransactionManager::new(pool.clone())
+        .with_busy_retry(BusyRetry::new(20, Duration::from_millis(5)));
+
+    let reader = begin_reader(&pool).await;
+    let mut writer = tx_manager.begin().await.expect("Failed to begin");
+    sqlx::query("INSERT INTO items (id) VALUES (1)")
+        .execute(&mut *writer)
+        .await
+        .expect("Failed to insert");
+
+    // The commit starts while the reader still holds its lock
+    let release_reader = async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        reader.commit().await
+    };
+    let (reader_result, writer_result) = tokio::join!(release_reader, tx_manager.commit(writer));
+
+    reader_result.expect("Reader commit failed");
+    writer_result.expect("Writer should commit after retrying");
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[tokio::test]
+async fn test_commit_gives_up_when_busy_without_retries() {
+    let pool = init_contended_pool().await;
+    let tx_manager =
+        SqliteTransactionManager::new(pool.clone()).with_busy_retry(BusyRetry::disabled());
+
+    let reader = begin_reader(&pool).await;
+    let mut writer = tx_manager.begin().await.expect("Failed to begin");
+    sqlx::query("INSERT INTO items (id) VALUES (1)")
+        .execute(&mut *writer)
+        .await
+        .expect("Failed to insert");
+
+    match tx_manager.commit(writer).await {
+        Err(Error::Database(message)) => assert!(message.contains("busy"), "{}", message),
+        Err(e) => panic!("Expected a database error, got {:?}", e),
+        Ok(()) => panic!("Writer should not commit while the reader holds its lock"),
+    }
+    reader.commit().await.unwrap();
+
+    // The failed commit rolled the insert back
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+/// File database with the production settings (WAL, normal sync), except
+/// that connections report `SQLITE_BUSY` at once instead of waiting
+async fn init_wal_pool() -> SqlitePool {
+    let path = std::env::temp_dir().join(format!("sultan_wal_{}.db", uuid::Uuid::new_v4()));
+    let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", path.display()))
+        .unwrap()
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .synchronous(SqliteSynchronous::Normal)
+        .busy_timeout(Duration::ZERO);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(4)
+        .connect_with(options)
+        .await
+        .expect("Failed to open database");
+    sqlx::query("CREATE TABLE items (id INTEGER PRIMARY KEY)")
+        .execute(&pool)
+        .await
+        .expect("Failed to create table");
+    pool
+}
+
+/// Read the current count, then insert the next id, as a read-modify-write
+async fn insert_next_item(tx_manager: &SqliteTransactionManager) -> Result<(), Error> {
+    let mut tx = tx_manager.begin().await?;
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    sqlx::query("INSERT INTO items (id) VALUES (?)")
+        .bind(count + 1)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+    tx_manager.commit(tx).await
+}
+
+#[tokio::test]
+async fn test_concurrent_writers_in_wal_mode() {
+    let pool = init_wal_pool().await;
+    let tx_manager = SqliteTransactionManager::new(pool.clone())
+        .with_busy_retry(BusyRetry::new(20, Duration::from_millis(5)));
+
+    // Deferred transactions would both read count 0 and the second would hit
+    // SQLITE_BUSY_SNAPSHOT on its insert, which a retry cannot recover
+    let (first, second) =
+        tokio::join!(insert_next_item(&tx_manager), insert_next_item(&tx_manager));
+
+    first.expect("First writer failed");
+    second.expect("Second writer failed");
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+}
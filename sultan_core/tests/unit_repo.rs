@@ -22,6 +22,12 @@ async fn test_update_unit_name() {
     unit::unit_test_update_name(&ctx, repo).await;
 }
 
+#[tokio::test]
+async fn test_update_touches_updated_at() {
+    let (ctx, repo) = unit::create_sqlite_unit_repo().await;
+    unit::unit_test_update_touches_updated_at(&ctx, repo).await;
+}
+
 #[tokio::test]
 async fn test_update_unit_description() {
     let (ctx, repo) = unit::create_sqlite_unit_repo().await;
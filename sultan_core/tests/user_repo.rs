@@ -1,4 +1,10 @@
-use sultan_core::testing::storage::user;
+use sultan_core::domain::Context;
+use sultan_core::domain::model::user::UserCreate;
+use sultan_core::storage::UserRepository;
+use sultan_core::storage::sqlite::SqliteUserRepository;
+use sultan_core::storage::sqlite::transaction::{SqliteTransactionManager, TxGuard};
+use sultan_core::storage::transaction::TransactionManager;
+use sultan_core::testing::storage::{self, user};
 
 // =============================================================================
 // Basic CRUD Tests
@@ -34,12 +40,30 @@ async fn test_update_user() {
     user::user_test_update(&ctx, repo).await;
 }
 
+#[tokio::test]
+async fn test_update_user_touches_updated_at() {
+    let (ctx, repo) = user::create_sqlite_user_repo().await;
+    user::user_test_update_touches_updated_at(&ctx, repo).await;
+}
+
+#[tokio::test]
+async fn test_failed_login_after_lock_expired() {
+    let (ctx, repo) = user::create_sqlite_user_repo().await;
+    user::user_test_failed_login_after_lock_expired(&ctx, repo).await;
+}
+
 #[tokio::test]
 async fn test_update_user_not_found() {
     let (ctx, repo) = user::create_sqlite_user_repo().await;
     user::user_test_update_not_found(&ctx, repo).await;
 }
 
+#[tokio::test]
+async fn test_disable_user() {
+    let (ctx, repo) = user::create_sqlite_user_repo().await;
+    user::user_test_disable_user(&ctx, repo).await;
+}
+
 #[tokio::test]
 async fn test_update_password() {
     let (ctx, repo) = user::create_sqlite_user_repo().await;
@@ -187,3 +211,54 @@ async fn test_delete_permission_null_vs_non_null_branch() {
     let (ctx, repo) = user::create_sqlite_user_repo().await;
     user::user_test_delete_permission_null_vs_non_null_branch(&ctx, repo).await;
 }
+
+#[tokio::test]
+async fn test_delete_user_records_deleting_actor() {
+    let pool = storage::init_sqlite_pool().await;
+    let repo = SqliteUserRepository::new(pool.clone());
+    let tx_manager = SqliteTransactionManager::new(pool.clone());
+    let actor = Context::new().with_actor_id(42);
+
+    let mut ids = Vec::new();
+    for username in ["deleted_on_pool", "deleted_in_tx"] {
+        let id = storage::generate_test_id().await;
+        let user = UserCreate {
+            username: username.to_string(),
+            name: "Delete Test".to_string(),
+            email: None,
+            password: "pass".to_string(),
+            photo: None,
+            pin: None,
+            address: None,
+            phone: None,
+        };
+        <SqliteUserRepository as UserRepository<TxGuard<'_>>>::create_user(
+            &repo,
+            &Context::new(),
+            id,
+            &user,
+        )
+        .await
+        .expect("Failed to create user");
+        ids.push(id);
+    }
+
+    <SqliteUserRepository as UserRepository<TxGuard<'_>>>::delete_user(&repo, &actor, ids[0])
+        .await
+        .expect("Failed to delete user");
+    let mut tx = tx_manager.begin().await.expect("Failed to begin tx");
+    repo.delete_user_tx(&actor, ids[1], &mut tx)
+        .await
+        .expect("Failed to delete user");
+    tx_manager.commit(tx).await.expect("Failed to commit tx");
+
+    for id in ids {
+        let deleted_by: Option<i64> =
+            sqlx::query_scalar("SELECT deleted_by FROM users WHERE id = ?")
+                .bind(id)
+                .fetch_one(&pool)
+                .await
+                .expect("Failed to query");
+        assert_eq!(deleted_by, Some(42));
+    }
+}
@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use sultan_core::{
+    application::{InMemoryCache, UserService, UserServiceTrait},
+    crypto::Argon2PasswordHasher,
+    domain::{
+        Context, Error,
+        model::{
+            permission::{action, resource},
+            user::UserCreate,
+        },
+    },
+    snowflake::SnowflakeGenerator,
+    storage::UserRepository,
+    testing::storage::user,
+};
+
+#[tokio::test]
+async fn test_create_user_rejects_weak_password_before_db() {
+    let (_, repo) = user::create_sqlite_user_repo().await;
+    let service = UserService::new(
+        repo.clone(),
+        Arc::new(Argon2PasswordHasher::default()),
+        SnowflakeGenerator::new(1).unwrap(),
+        Arc::new(InMemoryCache::<i64>::new()),
+    );
+
+    let mut permissions = std::collections::HashMap::new();
+    permissions.insert((resource::USER, None), action::CREATE);
+    let ctx = Context::new_with_all(None, permissions, std::collections::HashMap::new());
+
+    let weak = UserCreate {
+        username: "weakling".to_string(),
+        password: "password".to_string(),
+        name: "Weak Password".to_string(),
+        email: None,
+        photo: None,
+        pin: None,
+        address: None,
+        phone: None,
+    };
+
+    let result = service.create(&ctx, &weak).await;
+    assert!(matches!(result, Err(Error::ValidationError(_))));
+
+    let stored = repo
+        .get_user_by_username(&Context::new(), "weakling")
+        .await
+        .expect("Failed to query user");
+    assert!(stored.is_none());
+}
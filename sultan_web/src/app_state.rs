@@ -5,10 +5,12 @@ use std::{
     collections::HashMap,
 };
 use sultan_core::application::{
-    AuthServiceTrait, CategoryServiceTrait, CustomerServiceTrait, SupplierServiceTrait,
-    UserServiceTrait,
+    AuthServiceTrait, CategoryServiceTrait, CustomerServiceTrait, ExportServiceTrait,
+    ProductServiceTrait, SaleServiceTrait, SupplierServiceTrait, UserServiceTrait,
 };
 use sultan_core::crypto::JwtManager;
+use sultan_core::domain::AuditSink;
+use sultan_core::snowflake::IdGenerator;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -16,8 +18,17 @@ pub struct AppState {
     pub jwt_manager: Arc<dyn JwtManager>,
     pub category_service: Arc<dyn CategoryServiceTrait>,
     pub customer_service: Arc<dyn CustomerServiceTrait>,
+    pub export_service: Arc<dyn ExportServiceTrait>,
+    pub product_service: Arc<dyn ProductServiceTrait>,
+    pub sale_service: Arc<dyn SaleServiceTrait>,
     pub supplier_service: Arc<dyn SupplierServiceTrait>,
     pub user_service: Arc<dyn UserServiceTrait>,
+    /// Shared by every service so ids stay unique for this node
+    pub id_generator: Arc<dyn IdGenerator>,
+    /// Attached to authenticated contexts to record permission denials
+    pub audit_sink: Option<Arc<dyn AuditSink>>,
+    /// Largest `page_size` list endpoints hand to a service
+    pub max_page_size: u32,
     pub extensions: Arc<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
 }
 
@@ -29,6 +40,16 @@ impl AppState {
     }
 }
 
+/// Cap on client-requested page sizes, extracted with `State<MaxPageSize>`
+#[derive(Clone, Copy, Debug)]
+pub struct MaxPageSize(pub u32);
+
+impl FromRef<AppState> for MaxPageSize {
+    fn from_ref(app_state: &AppState) -> Self {
+        MaxPageSize(app_state.max_page_size)
+    }
+}
+
 impl FromRef<AppState> for Arc<dyn AuthServiceTrait> {
     fn from_ref(app_state: &AppState) -> Self {
         app_state.auth_service.clone()
@@ -47,6 +68,24 @@ impl FromRef<AppState> for Arc<dyn CustomerServiceTrait> {
     }
 }
 
+impl FromRef<AppState> for Arc<dyn ExportServiceTrait> {
+    fn from_ref(app_state: &AppState) -> Self {
+        app_state.export_service.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn ProductServiceTrait> {
+    fn from_ref(app_state: &AppState) -> Self {
+        app_state.product_service.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<dyn SaleServiceTrait> {
+    fn from_ref(app_state: &AppState) -> Self {
+        app_state.sale_service.clone()
+    }
+}
+
 impl FromRef<AppState> for Arc<dyn SupplierServiceTrait> {
     fn from_ref(app_state: &AppState) -> Self {
         app_state.supplier_service.clone()
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sultan_core::domain::model::Update;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
 /// Request to create a new category
@@ -25,6 +25,14 @@ pub struct CategoryCreateRequest {
     pub parent_id: Option<i64>,
 }
 
+/// Optional branch scope for category reads
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct CategoryQueryParams {
+    /// Only return categories assigned to this branch
+    #[schema(example = 1)]
+    pub branch_id: Option<i64>,
+}
+
 /// Response after creating a category
 #[derive(Debug, Serialize, ToSchema)]
 pub struct CategoryCreateResponse {
@@ -46,10 +54,12 @@ pub struct CategoryUpdateRequest {
     pub name: String,
 
     /// Category description (optional)
+    #[serde(default)]
     #[schema(example = "Electronic devices and accessories", value_type = Option<String>)]
     pub description: Update<String>,
 
     /// Parent category ID (optional, for subcategories)
+    #[serde(default)]
     #[schema(example = 1, value_type = Option<i64>)]
     pub parent_id: Update<i64>,
 }
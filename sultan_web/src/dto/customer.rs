@@ -5,7 +5,7 @@ use sultan_core::domain::model::Update;
 use utoipa::ToSchema;
 use validator::Validate;
 
-use super::{default_page, default_page_size};
+use super::PaginationQuery;
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CustomerCreateRequest {
@@ -35,13 +35,17 @@ pub struct CustomerUpdateRequest {
     #[schema(example = "CV. Sultan Pos")]
     pub name: Option<String>,
     pub number: Option<String>,
+    #[serde(default)]
     #[schema(value_type = Option<String>)]
     pub address: Update<String>,
+    #[serde(default)]
     #[schema(value_type = Option<String>)]
     pub email: Update<String>,
+    #[serde(default)]
     #[schema(value_type = Option<String>)]
     pub phone: Update<String>,
     pub level: Option<i32>,
+    #[serde(default)]
     #[schema(value_type = Option<Value>)]
     pub metadata: Update<Value>,
 }
@@ -89,12 +93,17 @@ pub struct CustomerQueryParams {
     pub email: Option<String>,
     /// Customer level filter
     pub level: Option<i32>,
-    /// Page number (default: 1)
-    #[serde(default = "default_page")]
-    pub page: u32,
-    /// Page size (default: 20, max: 100)
-    #[serde(default = "default_page_size")]
-    pub page_size: u32,
+    /// Minimum customer level (inclusive)
+    pub level_min: Option<i32>,
+    /// Maximum customer level (inclusive)
+    pub level_max: Option<i32>,
+    /// Matches name, phone or email (partial match)
+    pub search: Option<String>,
+    /// Filter expressions such as `level:gt:1;number:in:C001|C002`
+    pub filter: Option<String>,
+    /// Page and page size (capped at the server's `MAX_PAGE_SIZE`)
+    #[serde(flatten)]
+    pub pagination: PaginationQuery,
     /// Order by field
     pub order_by: Option<String>,
     /// Order direction (asc/desc)
@@ -102,22 +111,44 @@ pub struct CustomerQueryParams {
 }
 
 impl CustomerQueryParams {
-    /// Convert to CustomerFilter
-    pub fn to_filter(&self) -> sultan_core::domain::model::customer::CustomerFilter {
-        sultan_core::domain::model::customer::CustomerFilter {
+    /// Convert to CustomerFilter, rejecting filter expressions on fields
+    /// customers can't be filtered on
+    pub fn to_filter(
+        &self,
+    ) -> sultan_core::domain::DomainResult<sultan_core::domain::model::customer::CustomerFilter>
+    {
+        use sultan_core::domain::model::{customer::CUSTOMER_FILTER_FIELDS, filter::FilterExpr};
+
+        let expressions = match &self.filter {
+            Some(filter) => FilterExpr::parse_list(filter)?,
+            None => Vec::new(),
+        };
+        for expr in &expressions {
+            expr.resolve(CUSTOMER_FILTER_FIELDS)?;
+        }
+
+        Ok(sultan_core::domain::model::customer::CustomerFilter {
             number: self.number.clone(),
             name: self.name.clone(),
             phone: self.phone.clone(),
             email: self.email.clone(),
             level: self.level,
-        }
+            level_min: self.level_min,
+            level_max: self.level_max,
+            search: self.search.clone(),
+            search_phone: None,
+            expressions,
+        })
     }
 
     /// Convert to PaginationOptions
-    pub fn to_pagination(&self) -> sultan_core::domain::model::pagination::PaginationOptions {
+    pub fn to_pagination(
+        &self,
+        max_page_size: u32,
+    ) -> sultan_core::domain::DomainResult<sultan_core::domain::model::pagination::PaginationOptions>
+    {
         use sultan_core::domain::model::pagination::{PaginationOptions, PaginationOrder};
 
-        let page_size = self.page_size.min(100); // Cap at 100
         let order = match (self.order_by.as_ref(), self.order_direction.as_ref()) {
             (Some(field), direction) => Some(PaginationOrder {
                 field: field.clone(),
@@ -126,7 +157,11 @@ impl CustomerQueryParams {
             _ => None,
         };
 
-        PaginationOptions::new(self.page, page_size, order)
+        PaginationOptions {
+            order,
+            ..self.pagination.clone().into()
+        }
+        .normalize(max_page_size)
     }
 }
 
@@ -134,3 +169,94 @@ impl CustomerQueryParams {
 pub struct CustomerListResponse {
     pub customers: Vec<CustomerResponse>,
 }
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CustomerBulkDeleteRequest {
+    #[schema(example = json!([1, 2, 3]))]
+    pub ids: Vec<i64>,
+    /// Delete nothing if any id does not exist (default: false)
+    #[serde(default)]
+    pub all_or_nothing: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CustomerBulkDeleteResponse {
+    pub deleted: Vec<i64>,
+    pub not_found: Vec<i64>,
+}
+
+impl From<sultan_core::domain::model::bulk::BulkResult> for CustomerBulkDeleteResponse {
+    fn from(result: sultan_core::domain::model::bulk::BulkResult) -> Self {
+        Self {
+            deleted: result.deleted,
+            not_found: result.not_found,
+        }
+    }
+}
+
+/// A single row of a customer CSV file (`number,name,email,phone,level`)
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CustomerCsvRecord {
+    pub number: Option<String>,
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Name must be between 1 and 100 characters"
+    ))]
+    pub name: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub level: Option<i32>,
+}
+
+impl From<CustomerCsvRecord> for sultan_core::domain::model::customer::CustomerCreate {
+    fn from(record: CustomerCsvRecord) -> Self {
+        Self {
+            number: record.number.unwrap_or_default(),
+            name: record.name,
+            address: None,
+            email: record.email,
+            phone: record.phone,
+            level: record.level.unwrap_or_default(),
+            metadata: None,
+        }
+    }
+}
+
+impl From<sultan_core::domain::model::customer::Customer> for CustomerCsvRecord {
+    fn from(customer: sultan_core::domain::model::customer::Customer) -> Self {
+        Self {
+            number: Some(customer.number),
+            name: customer.name,
+            email: customer.email,
+            phone: customer.phone,
+            level: Some(customer.level),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CustomerImportParams {
+    /// Stop at the first invalid row instead of skipping it (default: false)
+    #[serde(default)]
+    pub abort_on_error: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CustomerImportRowError {
+    /// Line number in the uploaded CSV (the header is line 1)
+    #[schema(example = 3)]
+    pub line: u64,
+    #[schema(example = "Name must be between 1 and 100 characters")]
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CustomerImportResponse {
+    /// Ids of the customers that were created
+    pub imported: Vec<i64>,
+    /// Rows that could not be imported
+    pub errors: Vec<CustomerImportRowError>,
+    /// Whether the import stopped early because `abort_on_error` was set
+    pub aborted: bool,
+}
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sultan_core::domain::model::export::Snapshot;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ExportQueryParams {
+    /// Include soft-deleted rows (default: false)
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ImportQueryParams {
+    /// `insert` fails on ids that already exist, `upsert` overwrites them
+    /// (default: insert)
+    pub mode: Option<String>,
+}
+
+/// A document in the shape written by `GET /api/export/full`. Missing tables
+/// are treated as empty.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImportSnapshotRequest {
+    #[serde(default)]
+    pub products: Vec<Value>,
+    #[serde(default)]
+    pub variants: Vec<Value>,
+    #[serde(default)]
+    pub categories: Vec<Value>,
+    #[serde(default)]
+    pub customers: Vec<Value>,
+    #[serde(default)]
+    pub suppliers: Vec<Value>,
+    #[serde(default)]
+    pub units: Vec<Value>,
+}
+
+impl From<ImportSnapshotRequest> for Snapshot {
+    fn from(req: ImportSnapshotRequest) -> Self {
+        Snapshot {
+            products: req.products,
+            variants: req.variants,
+            categories: req.categories,
+            customers: req.customers,
+            suppliers: req.suppliers,
+            units: req.units,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportSnapshotResponse {
+    /// Rows written per table
+    #[schema(example = json!({"products": 2, "units": 1}))]
+    pub imported: BTreeMap<String, usize>,
+}
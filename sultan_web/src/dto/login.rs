@@ -1,4 +1,6 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sultan_core::application::AuthTokens;
 use utoipa::ToSchema;
 use validator::Validate;
 
@@ -20,6 +22,25 @@ pub struct LoginResponse {
 
     #[schema(example = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...")]
     pub refresh_token: String,
+
+    /// When the access token expires (RFC 3339)
+    #[schema(example = "2025-01-01T08:15:00Z")]
+    pub access_expires_at: DateTime<Utc>,
+
+    /// When the refresh token expires (RFC 3339)
+    #[schema(example = "2025-01-31T08:00:00Z")]
+    pub refresh_expires_at: DateTime<Utc>,
+}
+
+impl From<AuthTokens> for LoginResponse {
+    fn from(tokens: AuthTokens) -> Self {
+        LoginResponse {
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+            access_expires_at: tokens.access_expires_at,
+            refresh_expires_at: tokens.refresh_expires_at,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
@@ -35,3 +56,24 @@ pub struct LogoutRequest {
     #[schema(example = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...")]
     pub refresh_token: String,
 }
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct DeviceLoginRequest {
+    #[validate(length(min = 1, message = "Device key cannot be empty"))]
+    #[schema(example = "9f86d081884c7d659a2feaa0c55ad015")]
+    pub device_key: String,
+}
+
+/// Devices get no refresh token; they log in again when the access token expires
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DeviceLoginResponse {
+    #[schema(example = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...")]
+    pub access_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PurgeExpiredTokensResponse {
+    /// Number of expired refresh tokens deleted
+    #[schema(example = 42)]
+    pub purged: u64,
+}
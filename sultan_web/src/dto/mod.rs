@@ -1,14 +1,26 @@
 pub mod category;
 pub mod customer;
+pub mod export;
 pub mod login;
+pub mod pagination;
+pub mod product;
+pub mod sale;
+pub mod stats;
+pub mod stock;
 pub mod supplier;
 
 pub use category::{CategoryCreateRequest, CategoryCreateResponse};
 pub use customer::{CustomerCreateRequest, CustomerCreateResponse};
-pub use login::{LoginRequest, LoginResponse, LogoutRequest, RefreshTokenRequest};
+pub use login::{
+    DeviceLoginRequest, DeviceLoginResponse, LoginRequest, LoginResponse, LogoutRequest,
+    RefreshTokenRequest,
+};
+pub use pagination::PaginationQuery;
+pub use sale::{SaleCreateRequest, SaleCreateResponse};
 pub use supplier::{SupplierCreateRequest, SupplierCreateResponse};
 
 use serde::Serialize;
+use sultan_core::domain::model::pagination::DEFAULT_PAGE_SIZE;
 use utoipa::ToSchema;
 
 /// Standard error response
@@ -17,6 +29,14 @@ pub struct ErrorResponse {
     /// Error message describing what went wrong
     #[schema(example = "Username cannot be empty")]
     pub error: String,
+    /// Machine-readable error code: `validation`, `invalid_credentials`,
+    /// `unauthorized`, `forbidden`, `not_found`, `conflict`, `cancelled`,
+    /// `payload_too_large`, `timeout`, `database` or `internal`
+    #[schema(example = "validation")]
+    pub code: String,
+    /// The English message, present when `error` was localized
+    #[schema(example = "Username cannot be empty")]
+    pub detail: Option<String>,
 }
 
 pub fn default_page() -> u32 {
@@ -24,7 +44,7 @@ pub fn default_page() -> u32 {
 }
 
 pub fn default_page_size() -> u32 {
-    20
+    DEFAULT_PAGE_SIZE
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Deserializer};
+use sultan_core::domain::model::pagination::{
+    DEFAULT_MAX_PAGE_SIZE, DEFAULT_PAGE_SIZE, PaginationOptions,
+};
+
+use super::{default_page, default_page_size};
+
+/// `page` and `page_size` query parameters, meant to be flattened into a
+/// list endpoint's query struct
+#[derive(Debug, Clone, Deserialize)]
+pub struct PaginationQuery {
+    /// Page number (default: 1)
+    #[serde(default = "default_page", deserialize_with = "number_or_string")]
+    pub page: u32,
+    /// Page size (default: 20)
+    #[serde(default = "default_page_size", deserialize_with = "number_or_string")]
+    pub page_size: u32,
+}
+
+impl Default for PaginationQuery {
+    fn default() -> Self {
+        Self {
+            page: default_page(),
+            page_size: default_page_size(),
+        }
+    }
+}
+
+/// Clamps `page` to at least 1 and `page_size` to `1..=DEFAULT_MAX_PAGE_SIZE`,
+/// with zero meaning the default size. Handlers still call
+/// [`PaginationOptions::normalize`] to apply the configured maximum.
+impl From<PaginationQuery> for PaginationOptions {
+    fn from(query: PaginationQuery) -> Self {
+        let page_size = match query.page_size {
+            0 => DEFAULT_PAGE_SIZE,
+            size => size.min(DEFAULT_MAX_PAGE_SIZE),
+        };
+        PaginationOptions::new(query.page.max(1), page_size, None)
+    }
+}
+
+/// Flattened fields reach serde as strings when they come from a query
+/// string, so accept both forms
+fn number_or_string<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Number(u32),
+        String(String),
+    }
+
+    match Raw::deserialize(deserializer)? {
+        Raw::Number(n) => Ok(n),
+        Raw::String(s) => s.trim().parse().map_err(serde::de::Error::custom),
+    }
+}
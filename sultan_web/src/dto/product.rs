@@ -0,0 +1,134 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sultan_core::domain::{
+    DomainResult,
+    model::{
+        money::Money,
+        pagination::PaginationOptions,
+        product::{Product, ProductDetail, ProductType, ProductVariant},
+    },
+};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{default_page, default_page_size};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProductResponse {
+    pub id: i64,
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
+    #[schema(example = "Kopi Susu")]
+    pub name: String,
+    pub description: Option<String>,
+    pub product_type: ProductType,
+    pub main_image: Option<String>,
+    pub sellable: bool,
+    pub buyable: bool,
+    pub editable_price: bool,
+    pub has_variant: bool,
+    pub metadata: Option<Value>,
+}
+
+impl From<Product> for ProductResponse {
+    fn from(product: Product) -> Self {
+        Self {
+            id: product.id,
+            created_at: product.created_at,
+            updated_at: product.updated_at,
+            name: product.name,
+            description: product.description,
+            product_type: product.product_type,
+            main_image: product.main_image,
+            sellable: product.sellable,
+            buyable: product.buyable,
+            editable_price: product.editable_price,
+            has_variant: product.has_variant,
+            metadata: product.metadata,
+        }
+    }
+}
+
+/// Single product with details that list responses leave out
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProductDetailResponse {
+    #[serde(flatten)]
+    pub product: ProductResponse,
+    /// Number of live variants
+    #[schema(example = 2)]
+    pub variant_count: u64,
+    /// Ids of the categories the product is linked to
+    #[schema(example = json!([3, 7]))]
+    pub category_ids: Vec<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProductVariantResponse {
+    pub id: i64,
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
+    #[schema(example = "8991234567890")]
+    pub barcode: Option<String>,
+    #[schema(example = "Large")]
+    pub name: Option<String>,
+    #[schema(value_type = Option<String>, example = "18.00")]
+    pub price: Option<Money>,
+    pub metadata: Option<Value>,
+}
+
+impl From<ProductVariant> for ProductVariantResponse {
+    fn from(variant: ProductVariant) -> Self {
+        Self {
+            id: variant.id,
+            created_at: variant.created_at,
+            updated_at: variant.updated_at,
+            barcode: variant.barcode,
+            name: variant.name,
+            price: variant.price,
+            metadata: variant.metadata,
+        }
+    }
+}
+
+/// Product with its live variants and the ids of its categories
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProductWithVariantsResponse {
+    #[serde(flatten)]
+    pub product: ProductResponse,
+    pub variants: Vec<ProductVariantResponse>,
+    #[schema(example = json!([3, 7]))]
+    pub category_ids: Vec<i64>,
+}
+
+impl From<ProductDetail> for ProductWithVariantsResponse {
+    fn from(detail: ProductDetail) -> Self {
+        Self {
+            product: ProductResponse::from(detail.product),
+            variants: detail
+                .variants
+                .into_iter()
+                .map(ProductVariantResponse::from)
+                .collect(),
+            category_ids: detail.category_ids,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct ProductListQueryParams {
+    /// Page number (default: 1)
+    #[serde(default = "default_page")]
+    #[schema(example = 1, default = 1)]
+    pub page: u32,
+    /// Page size (default: 20, capped at the server's `MAX_PAGE_SIZE`)
+    #[serde(default = "default_page_size")]
+    #[schema(example = 20, default = 20)]
+    pub page_size: u32,
+}
+
+impl ProductListQueryParams {
+    /// Convert to PaginationOptions
+    pub fn to_pagination(&self, max_page_size: u32) -> DomainResult<PaginationOptions> {
+        PaginationOptions::new(self.page, self.page_size, None).normalize(max_page_size)
+    }
+}
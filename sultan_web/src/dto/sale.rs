@@ -0,0 +1,124 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sultan_core::domain::model::{
+    money::Money,
+    sale::{Sale, SaleLine, SaleLineCreate, SaleRefundLine},
+};
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct SaleCreateRequest {
+    pub branch_id: i64,
+    pub customer_id: Option<i64>,
+    #[validate(length(
+        min = 1,
+        max = 50,
+        message = "Payment method must be between 1 and 50 characters"
+    ))]
+    #[schema(example = "cash")]
+    pub payment_method: String,
+    #[validate(length(min = 1, message = "Sale must have at least one line"))]
+    pub lines: Vec<SaleLineRequest>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SaleLineRequest {
+    pub variant_id: i64,
+    #[schema(example = 2)]
+    pub qty: i64,
+    #[schema(value_type = String, example = "12.50")]
+    pub unit_price: Money,
+}
+
+impl From<SaleLineRequest> for SaleLineCreate {
+    fn from(line: SaleLineRequest) -> Self {
+        Self {
+            variant_id: line.variant_id,
+            qty: line.qty,
+            unit_price: line.unit_price,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct SaleRefundRequest {
+    #[validate(length(min = 1, message = "Refund must have at least one line"))]
+    pub lines: Vec<SaleRefundLineRequest>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SaleRefundLineRequest {
+    pub variant_id: i64,
+    #[schema(example = 1)]
+    pub qty: i64,
+}
+
+impl From<SaleRefundLineRequest> for SaleRefundLine {
+    fn from(line: SaleRefundLineRequest) -> Self {
+        Self {
+            variant_id: line.variant_id,
+            qty: line.qty,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SaleCreateResponse {
+    #[schema(example = 1)]
+    pub id: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SaleLineResponse {
+    pub variant_id: i64,
+    pub qty: i64,
+    #[schema(value_type = String, example = "12.50")]
+    pub unit_price: Money,
+    #[schema(value_type = String, example = "25.00")]
+    pub line_total: Money,
+}
+
+impl From<SaleLine> for SaleLineResponse {
+    fn from(line: SaleLine) -> Self {
+        Self {
+            variant_id: line.variant_id,
+            qty: line.qty,
+            unit_price: line.unit_price,
+            line_total: line.line_total,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SaleResponse {
+    pub id: i64,
+    pub created_at: chrono::DateTime<Utc>,
+    pub branch_id: i64,
+    pub customer_id: Option<i64>,
+    pub payment_method: String,
+    /// `sale`, `void` or `refund`
+    #[schema(example = "sale")]
+    pub kind: String,
+    /// The sale a void or refund reverses
+    pub original_sale_id: Option<i64>,
+    pub lines: Vec<SaleLineResponse>,
+    #[schema(value_type = String, example = "25.00")]
+    pub total: Money,
+}
+
+impl From<Sale> for SaleResponse {
+    fn from(sale: Sale) -> Self {
+        Self {
+            id: sale.id,
+            created_at: sale.created_at,
+            branch_id: sale.branch_id,
+            customer_id: sale.customer_id,
+            payment_method: sale.payment_method,
+            kind: sale.kind.as_str().to_string(),
+            original_sale_id: sale.original_sale_id,
+            lines: sale.lines.into_iter().map(SaleLineResponse::from).collect(),
+            total: sale.total,
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Live record totals for the dashboard
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CountsResponse {
+    #[schema(example = 120)]
+    pub products: u64,
+    #[schema(example = 57)]
+    pub customers: u64,
+    #[schema(example = 8)]
+    pub suppliers: u64,
+    /// Every category in the tree, children included
+    #[schema(example = 14)]
+    pub categories: u64,
+}
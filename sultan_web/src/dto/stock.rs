@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use sultan_core::domain::{
+    DomainResult,
+    model::{pagination::PaginationOptions, stock::StockLevel},
+};
+use utoipa::{IntoParams, ToSchema};
+
+use super::{default_page, default_page_size};
+
+#[derive(Debug, Deserialize, IntoParams, ToSchema)]
+pub struct LowStockQueryParams {
+    /// Branch to report on
+    #[schema(example = 1)]
+    pub branch_id: i64,
+    /// Include variants with at most this many units on hand
+    #[schema(example = 5)]
+    pub below: i64,
+    /// Page number (default: 1)
+    #[serde(default = "default_page")]
+    #[schema(example = 1, default = 1)]
+    pub page: u32,
+    /// Page size (default: 20, capped at the server's `MAX_PAGE_SIZE`)
+    #[serde(default = "default_page_size")]
+    #[schema(example = 20, default = 20)]
+    pub page_size: u32,
+}
+
+impl LowStockQueryParams {
+    /// Convert to PaginationOptions
+    pub fn to_pagination(&self, max_page_size: u32) -> DomainResult<PaginationOptions> {
+        PaginationOptions::new(self.page, self.page_size, None).normalize(max_page_size)
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StockLevelResponse {
+    #[schema(example = 1)]
+    pub branch_id: i64,
+    #[schema(example = 100)]
+    pub variant_id: i64,
+    #[schema(example = 10)]
+    pub product_id: i64,
+    #[schema(example = "Kopi Susu")]
+    pub product_name: String,
+    #[schema(example = "Large")]
+    pub variant_name: Option<String>,
+    #[schema(example = "8991234567890")]
+    pub barcode: Option<String>,
+    /// Units on hand
+    #[schema(example = 3)]
+    pub quantity: i64,
+}
+
+impl From<StockLevel> for StockLevelResponse {
+    fn from(level: StockLevel) -> Self {
+        Self {
+            branch_id: level.branch_id,
+            variant_id: level.variant_id,
+            product_id: level.product_id,
+            product_name: level.product_name,
+            variant_name: level.variant_name,
+            barcode: level.barcode,
+            quantity: level.quantity,
+        }
+    }
+}
@@ -33,18 +33,25 @@ pub struct SupplierCreateResponse {
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct SupplierUpdateRequest {
     pub name: Option<String>,
+    #[serde(default)]
     #[schema(value_type = Option<String>)]
     pub code: Update<String>,
+    #[serde(default)]
     #[schema(value_type = Option<String>)]
     pub email: Update<String>,
+    #[serde(default)]
     #[schema(value_type = Option<String>)]
     pub address: Update<String>,
+    #[serde(default)]
     #[schema(value_type = Option<String>)]
     pub phone: Update<String>,
+    #[serde(default)]
     #[schema(value_type = Option<String>)]
     pub npwp: Update<String>,
+    #[serde(default)]
     #[schema(value_type = Option<String>)]
     pub npwp_name: Update<String>,
+    #[serde(default)]
     #[schema(value_type = Option<Value>)]
     pub metadata: Update<Value>,
 }
@@ -98,7 +105,7 @@ pub struct SupplierQueryParams {
     #[serde(default = "default_page")]
     #[schema(example = 1, default = 1)]
     pub page: u32,
-    /// Page size (default: 20, max: 100)
+    /// Page size (default: 20, capped at the server's `MAX_PAGE_SIZE`)
     #[serde(default = "default_page_size")]
     #[schema(example = 20, default = 20)]
     pub page_size: u32,
@@ -123,10 +130,13 @@ impl SupplierQueryParams {
     }
 
     /// Convert to PaginationOptions
-    pub fn to_pagination(&self) -> sultan_core::domain::model::pagination::PaginationOptions {
+    pub fn to_pagination(
+        &self,
+        max_page_size: u32,
+    ) -> sultan_core::domain::DomainResult<sultan_core::domain::model::pagination::PaginationOptions>
+    {
         use sultan_core::domain::model::pagination::{PaginationOptions, PaginationOrder};
 
-        let page_size = self.page_size.min(100); // Cap at 100
         let order = match (self.order_by.as_ref(), self.order_direction.as_ref()) {
             (Some(field), direction) => Some(PaginationOrder {
                 field: field.clone(),
@@ -135,6 +145,6 @@ impl SupplierQueryParams {
             _ => None,
         };
 
-        PaginationOptions::new(self.page, page_size, order)
+        PaginationOptions::new(self.page, self.page_size, order).normalize(max_page_size)
     }
 }
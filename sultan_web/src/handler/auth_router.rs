@@ -9,7 +9,10 @@ use utoipa::OpenApi;
 use validator::Validate;
 
 use crate::AppState;
-use crate::dto::{ErrorResponse, LoginRequest, LoginResponse, LogoutRequest, RefreshTokenRequest};
+use crate::dto::{
+    DeviceLoginRequest, DeviceLoginResponse, ErrorResponse, LoginRequest, LoginResponse,
+    LogoutRequest, RefreshTokenRequest,
+};
 
 // ============================================================================
 // OpenAPI Documentation
@@ -17,8 +20,16 @@ use crate::dto::{ErrorResponse, LoginRequest, LoginResponse, LogoutRequest, Refr
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(login, refresh, logout),
-    components(schemas(LoginRequest, LoginResponse, RefreshTokenRequest, LogoutRequest, ErrorResponse)),
+    paths(login, refresh, logout, login_device),
+    components(schemas(
+        LoginRequest,
+        LoginResponse,
+        RefreshTokenRequest,
+        LogoutRequest,
+        DeviceLoginRequest,
+        DeviceLoginResponse,
+        ErrorResponse
+    )),
     tags(
         (name = "auth", description = "Authentication endpoints")
     )
@@ -40,7 +51,8 @@ pub struct AuthApiDoc;
     responses(
         (status = 200, description = "Login successful", body = LoginResponse),
         (status = 400, description = "Bad request - validation error", body = ErrorResponse),
-        (status = 401, description = "Unauthorized - invalid credentials", body = ErrorResponse)
+        (status = 401, description = "Unauthorized - invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Forbidden - account temporarily locked", body = ErrorResponse)
     )
 )]
 #[instrument(skip(auth_service, payload))]
@@ -56,13 +68,7 @@ async fn login(
         .login(&ctx, &payload.username, &payload.password)
         .await?;
 
-    Ok((
-        StatusCode::OK,
-        Json(LoginResponse {
-            access_token: tokens.access_token,
-            refresh_token: tokens.refresh_token,
-        }),
-    ))
+    Ok((StatusCode::OK, Json(LoginResponse::from(tokens))))
 }
 
 /// Refresh access token
@@ -90,13 +96,7 @@ async fn refresh(
     let ctx = Context::new();
     let tokens = auth_service.refresh(&ctx, &payload.refresh_token).await?;
 
-    Ok((
-        StatusCode::OK,
-        Json(LoginResponse {
-            access_token: tokens.access_token,
-            refresh_token: tokens.refresh_token,
-        }),
-    ))
+    Ok((StatusCode::OK, Json(LoginResponse::from(tokens))))
 }
 
 /// Logout user
@@ -127,6 +127,35 @@ async fn logout(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Login a kiosk device
+///
+/// Exchange a registered device key for a short-lived access token. No refresh
+/// token is issued, and the token stops working once the device is revoked.
+#[utoipa::path(
+    post,
+    path = "/api/auth/device",
+    tag = "auth",
+    request_body = DeviceLoginRequest,
+    responses(
+        (status = 200, description = "Login successful", body = DeviceLoginResponse),
+        (status = 400, description = "Bad request - validation error", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - unknown or revoked device", body = ErrorResponse)
+    )
+)]
+#[instrument(skip(auth_service, payload))]
+async fn login_device(
+    State(auth_service): State<Arc<dyn AuthServiceTrait>>,
+    Json(payload): Json<DeviceLoginRequest>,
+) -> DomainResult<impl IntoResponse> {
+    payload
+        .validate()
+        .map_err(|e| Error::ValidationError(format!("{}", e)))?;
+    let ctx = Context::new();
+    let access_token = auth_service.login_device(&ctx, &payload.device_key).await?;
+
+    Ok((StatusCode::OK, Json(DeviceLoginResponse { access_token })))
+}
+
 // ============================================================================
 // Router
 // ============================================================================
@@ -135,5 +164,6 @@ pub fn auth_router() -> Router<AppState> {
     Router::new()
         .route("/", post(login))
         .route("/refresh", post(refresh))
+        .route("/device", post(login_device))
         .route("/", delete(logout))
 }
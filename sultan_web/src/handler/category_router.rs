@@ -1,12 +1,12 @@
 use axum::Extension;
-use axum::extract::Path;
+use axum::extract::{Path, Query};
 use axum::routing::get;
 use axum::{
     Json, Router, extract::State, http::StatusCode, response::IntoResponse, routing::delete,
     routing::post, routing::put,
 };
 use std::sync::Arc;
-use sultan_core::application::CategoryServiceTrait;
+use sultan_core::application::{CategoryServiceTrait, ProductServiceTrait};
 use sultan_core::domain::context::Context;
 use sultan_core::domain::model::category::{CategoryCreate, CategoryUpdate};
 use sultan_core::domain::{DomainResult, Error};
@@ -15,8 +15,13 @@ use utoipa::OpenApi;
 use validator::Validate;
 
 use crate::AppState;
-use crate::dto::category::{CategoryChildResponse, CategoryResponse, CategoryUpdateRequest};
-use crate::dto::{CategoryCreateRequest, CategoryCreateResponse, ErrorResponse};
+use crate::app_state::MaxPageSize;
+use crate::dto::category::{
+    CategoryChildResponse, CategoryQueryParams, CategoryResponse, CategoryUpdateRequest,
+};
+use crate::dto::product::{ProductListQueryParams, ProductResponse};
+use crate::dto::{CategoryCreateRequest, CategoryCreateResponse, ErrorResponse, ListResponse};
+use crate::handler::total_count::WithTotalCount;
 
 // ============================================================================
 // OpenAPI Documentation
@@ -24,13 +29,23 @@ use crate::dto::{CategoryCreateRequest, CategoryCreateResponse, ErrorResponse};
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(create, update, delete_category, get_by_id, get_all),
+    paths(
+        create,
+        update,
+        delete_category,
+        get_by_id,
+        get_all,
+        get_products,
+        assign_to_branch,
+        remove_from_branch
+    ),
     components(schemas(
         CategoryCreateRequest,
         CategoryCreateResponse,
         CategoryUpdateRequest,
         CategoryResponse,
         CategoryChildResponse,
+        ListResponse<ProductResponse>,
         ErrorResponse
     )),
     tags(
@@ -175,7 +190,8 @@ async fn delete_category(
     path = "/api/category/{id}",
     tag = "category",
     params(
-        ("id" = i64, Path, description = "Category ID to retrieve")
+        ("id" = i64, Path, description = "Category ID to retrieve"),
+        CategoryQueryParams
     ),
     responses(
         (status = 200, description = "Category retrieved successfully", body = CategoryResponse),
@@ -191,8 +207,11 @@ async fn get_by_id(
     State(category_service): State<Arc<dyn CategoryServiceTrait>>,
     Extension(ctx): Extension<Context>,
     Path(id): Path<i64>,
+    Query(params): Query<CategoryQueryParams>,
 ) -> DomainResult<impl IntoResponse> {
-    let result = category_service.get_by_id(&ctx, id).await?;
+    let result = category_service
+        .get_by_id(&ctx, id, params.branch_id)
+        .await?;
     match result {
         Some(category) => Ok((
             StatusCode::OK,
@@ -219,11 +238,13 @@ async fn get_by_id(
 /// Get all categories
 ///
 /// Retrieves a list of all categories with their hierarchical structure.
-/// Each category includes its immediate children. Requires authentication.
+/// Each category includes its immediate children. With `branch_id`, only
+/// categories assigned to that branch are returned. Requires authentication.
 #[utoipa::path(
     get,
     path = "/api/category",
     tag = "category",
+    params(CategoryQueryParams),
     responses(
         (status = 200, description = "Categories retrieved successfully", body = Vec<CategoryResponse>),
         (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse)
@@ -236,8 +257,9 @@ async fn get_by_id(
 async fn get_all(
     State(category_service): State<Arc<dyn CategoryServiceTrait>>,
     Extension(ctx): Extension<Context>,
+    Query(params): Query<CategoryQueryParams>,
 ) -> DomainResult<impl IntoResponse> {
-    let result = category_service.get_all(&ctx).await?;
+    let result = category_service.get_all(&ctx, params.branch_id).await?;
     Ok((
         StatusCode::OK,
         Json(
@@ -263,6 +285,117 @@ async fn get_all(
     ))
 }
 
+/// Assign a category to a branch
+///
+/// Makes the category visible in the branch. Assigning it again is a no-op.
+/// Requires category update permission in that branch.
+#[utoipa::path(
+    put,
+    path = "/api/category/{id}/branch/{branch_id}",
+    tag = "category",
+    params(
+        ("id" = i64, Path, description = "Category ID"),
+        ("branch_id" = i64, Path, description = "Branch ID")
+    ),
+    responses(
+        (status = 204, description = "Category assigned to the branch"),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - no category update permission in the branch", body = ErrorResponse),
+        (status = 404, description = "Category not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(category_service, ctx))]
+async fn assign_to_branch(
+    State(category_service): State<Arc<dyn CategoryServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+    Path((id, branch_id)): Path<(i64, i64)>,
+) -> DomainResult<impl IntoResponse> {
+    category_service
+        .assign_to_branch(&ctx, id, branch_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Remove a category from a branch
+///
+/// Hides the category from the branch. Removing a category that is not
+/// assigned is a no-op. Requires category update permission in that branch.
+#[utoipa::path(
+    delete,
+    path = "/api/category/{id}/branch/{branch_id}",
+    tag = "category",
+    params(
+        ("id" = i64, Path, description = "Category ID"),
+        ("branch_id" = i64, Path, description = "Branch ID")
+    ),
+    responses(
+        (status = 204, description = "Category removed from the branch"),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - no category update permission in the branch", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(category_service, ctx))]
+async fn remove_from_branch(
+    State(category_service): State<Arc<dyn CategoryServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+    Path((id, branch_id)): Path<(i64, i64)>,
+) -> DomainResult<impl IntoResponse> {
+    category_service
+        .remove_from_branch(&ctx, id, branch_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get products in a category
+///
+/// Retrieves the non-deleted products linked to a category, newest first.
+/// An unknown category has no products, so it returns an empty list rather
+/// than 404. Requires authentication.
+#[utoipa::path(
+    get,
+    path = "/api/category/{id}/products",
+    tag = "category",
+    params(
+        ("id" = i64, Path, description = "Category ID"),
+        ProductListQueryParams
+    ),
+    responses(
+        (status = 200, description = "Products retrieved successfully", body = ListResponse<ProductResponse>,
+            headers(("X-Total-Count" = u64, description = "Number of matching rows across all pages"))),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - no product read permission", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(product_service, ctx))]
+async fn get_products(
+    State(product_service): State<Arc<dyn ProductServiceTrait>>,
+    State(MaxPageSize(max_page_size)): State<MaxPageSize>,
+    Extension(ctx): Extension<Context>,
+    Path(id): Path<i64>,
+    Query(params): Query<ProductListQueryParams>,
+) -> DomainResult<impl IntoResponse> {
+    let products = product_service
+        .get_by_category(&ctx, id, &params.to_pagination(max_page_size)?)
+        .await?;
+    let total = product_service.count_by_category(&ctx, id).await?;
+
+    Ok(WithTotalCount::new(
+        total,
+        ListResponse {
+            data: products.into_iter().map(ProductResponse::from).collect(),
+        },
+    ))
+}
+
 // ============================================================================
 // Router
 // ============================================================================
@@ -274,4 +407,7 @@ pub fn category_router() -> Router<AppState> {
         .route("/{id}", delete(delete_category))
         .route("/{id}", get(get_by_id))
         .route("/", get(get_all))
+        .route("/{id}/products", get(get_products))
+        .route("/{id}/branch/{branch_id}", put(assign_to_branch))
+        .route("/{id}/branch/{branch_id}", delete(remove_from_branch))
 }
@@ -1,24 +1,37 @@
 use axum::Extension;
-use axum::extract::{Path, Query};
+use axum::body::{Body, Bytes};
+use axum::extract::{DefaultBodyLimit, Path, Query};
+use axum::http::{HeaderMap, header};
 use axum::routing::get;
 use axum::{
     Json, Router, extract::State, http::StatusCode, response::IntoResponse, routing::delete,
     routing::post, routing::put,
 };
+use futures::{Stream, StreamExt};
 use std::sync::Arc;
 use sultan_core::application::CustomerServiceTrait;
 use sultan_core::domain::context::Context;
-use sultan_core::domain::model::customer::{CustomerCreate, CustomerUpdate};
+use sultan_core::domain::model::customer::{
+    Customer, CustomerCreate, CustomerFilter, CustomerUpdate,
+};
+use sultan_core::domain::model::filter::{FilterExpr, FilterOp, FilterValue};
+use sultan_core::domain::model::pagination::PaginationOptions;
 use sultan_core::domain::{DomainResult, Error};
 use tracing::instrument;
 use utoipa::OpenApi;
 use validator::Validate;
 
 use crate::AppState;
+use crate::app_state::MaxPageSize;
 use crate::dto::customer::{
-    CustomerListResponse, CustomerQueryParams, CustomerResponse, CustomerUpdateRequest,
+    CustomerBulkDeleteRequest, CustomerBulkDeleteResponse, CustomerCsvRecord, CustomerImportParams,
+    CustomerImportResponse, CustomerImportRowError, CustomerListResponse, CustomerQueryParams,
+    CustomerResponse, CustomerUpdateRequest,
 };
 use crate::dto::{CustomerCreateRequest, CustomerCreateResponse, ErrorResponse};
+use crate::handler::etag::conditional_json;
+use crate::handler::ndjson::NdjsonListResponse;
+use crate::handler::total_count::WithTotalCount;
 
 // ============================================================================
 // OpenAPI Documentation
@@ -26,13 +39,27 @@ use crate::dto::{CustomerCreateRequest, CustomerCreateResponse, ErrorResponse};
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(create, update, delete_customer, get_by_id, get_all),
+    paths(
+        create,
+        update,
+        delete_customer,
+        bulk_delete,
+        get_by_id,
+        get_all,
+        import_csv,
+        export_csv,
+        stream
+    ),
     components(schemas(
         CustomerCreateRequest,
         CustomerCreateResponse,
         CustomerUpdateRequest,
         CustomerResponse,
         CustomerListResponse,
+        CustomerBulkDeleteRequest,
+        CustomerBulkDeleteResponse,
+        CustomerImportResponse,
+        CustomerImportRowError,
         ErrorResponse,
     )),
     tags(
@@ -162,15 +189,44 @@ async fn delete_customer(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/customer/bulk-delete",
+    tag = "customer",
+    request_body = CustomerBulkDeleteRequest,
+    responses(
+        (status = 200, description = "Deleted and missing ids; with all_or_nothing set and any id missing, nothing is deleted", body = CustomerBulkDeleteResponse),
+        (status = 400, description = "Bad request - empty or too many ids", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(customer_service, payload, ctx))]
+async fn bulk_delete(
+    State(customer_service): State<Arc<dyn CustomerServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+    Json(payload): Json<CustomerBulkDeleteRequest>,
+) -> DomainResult<impl IntoResponse> {
+    let result = customer_service
+        .delete_many(&ctx, &payload.ids, payload.all_or_nothing)
+        .await?;
+    Ok(Json(CustomerBulkDeleteResponse::from(result)))
+}
+
 #[utoipa::path(
     get,
     path = "/api/customer/{id}",
     tag = "customer",
     params(
-        ("id" = i64, Path, description = "Customer ID to retrieve")
+        ("id" = i64, Path, description = "Customer ID to retrieve"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag from a previous response")
     ),
     responses(
-        (status = 200, description = "Customer retrieved successfully", body = CustomerResponse),
+        (status = 200, description = "Customer retrieved successfully", body = CustomerResponse,
+            headers(("ETag" = String, description = "Weak validator for If-None-Match"))),
+        (status = 304, description = "Not modified - If-None-Match matches the current ETag"),
         (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
         (status = 404, description = "Customer not found", body = ErrorResponse)
     ),
@@ -178,11 +234,12 @@ async fn delete_customer(
         ("bearer_auth" = [])
     )
 )]
-#[instrument(skip(customer_service, ctx))]
+#[instrument(skip(customer_service, ctx, headers))]
 async fn get_by_id(
     State(customer_service): State<Arc<dyn CustomerServiceTrait>>,
     Extension(ctx): Extension<Context>,
     Path(id): Path<i64>,
+    headers: HeaderMap,
 ) -> DomainResult<impl IntoResponse> {
     let customer = customer_service
         .get_by_id(&ctx, id)
@@ -191,7 +248,13 @@ async fn get_by_id(
             "Customer with id {} not found",
             id
         )))?;
-    Ok((StatusCode::OK, Json(CustomerResponse::from(customer))))
+    let updated_at = customer.updated_at;
+    Ok(conditional_json(
+        &headers,
+        id,
+        &updated_at,
+        CustomerResponse::from(customer),
+    ))
 }
 
 #[utoipa::path(
@@ -204,13 +267,18 @@ async fn get_by_id(
         ("phone" = Option<String>, Query, description = "Filter by phone number"),
         ("email" = Option<String>, Query, description = "Filter by email"),
         ("level" = Option<i32>, Query, description = "Filter by customer level"),
+        ("level_min" = Option<i32>, Query, description = "Minimum customer level (inclusive)"),
+        ("level_max" = Option<i32>, Query, description = "Maximum customer level (inclusive)"),
+        ("search" = Option<String>, Query, description = "Match name, phone or email (partial match)"),
+        ("filter" = Option<String>, Query, description = "Filter expressions `field:op:value` separated by `;`. Ops: eq, like, gt, lt, in (values separated by `|`). Fields: id, number, name, address, email, phone, level, created_at, updated_at"),
         ("page" = u32, Query, description = "Page number (default: 1)"),
-        ("page_size" = u32, Query, description = "Page size (default: 20, max: 100)"),
+        ("page_size" = u32, Query, description = "Page size (default: 20, capped at the server's MAX_PAGE_SIZE)"),
         ("order_by" = Option<String>, Query, description = "Order by field"),
         ("order_direction" = Option<String>, Query, description = "Order direction (asc/desc)")
     ),
     responses(
-        (status = 200, description = "Customers retrieved successfully", body = CustomerListResponse),
+        (status = 200, description = "Customers retrieved successfully", body = CustomerListResponse,
+            headers(("X-Total-Count" = u64, description = "Number of matching rows across all pages"))),
         (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse)
     ),
     security(
@@ -220,29 +288,339 @@ async fn get_by_id(
 #[instrument(skip(customer_service, ctx))]
 async fn get_all(
     State(customer_service): State<Arc<dyn CustomerServiceTrait>>,
+    State(MaxPageSize(max_page_size)): State<MaxPageSize>,
     Extension(ctx): Extension<Context>,
     Query(query): Query<CustomerQueryParams>,
 ) -> DomainResult<impl IntoResponse> {
-    let filter = query.to_filter();
-    let pagination = query.to_pagination();
-    let customer = customer_service.get_all(&ctx, &filter, &pagination).await?;
+    let filter = query.to_filter()?;
+    let pagination = query.to_pagination(max_page_size)?;
+    let (customer, total) = customer_service
+        .get_all_with_total(&ctx, &filter, &pagination)
+        .await?;
+    Ok(WithTotalCount::new(
+        total,
+        CustomerListResponse {
+            customers: customer.into_iter().map(CustomerResponse::from).collect(),
+        },
+    ))
+}
+
+/// Page size used when walking the customer list for CSV and NDJSON export
+const EXPORT_PAGE_SIZE: u32 = 100;
+
+/// CSV uploads can be much larger than regular JSON payloads. The server
+/// raises it to its global body limit when that is larger.
+pub const IMPORT_BODY_LIMIT: usize = 10 * 1024 * 1024;
+
+#[utoipa::path(
+    post,
+    path = "/api/customer/import",
+    tag = "customer",
+    request_body(
+        content = String,
+        content_type = "text/csv",
+        description = "CSV with header `number,name,email,phone,level`"
+    ),
+    params(
+        ("abort_on_error" = Option<bool>, Query, description = "Import nothing if any row is invalid and stop at the first failed insert (default: false)")
+    ),
+    responses(
+        (status = 200, description = "Import finished, see per-row errors", body = CustomerImportResponse),
+        (status = 400, description = "Bad request - malformed CSV header", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - missing create permission", body = ErrorResponse),
+        (status = 413, description = "CSV larger than 10 MiB", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(customer_service, ctx, body))]
+async fn import_csv(
+    State(customer_service): State<Arc<dyn CustomerServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+    Query(params): Query<CustomerImportParams>,
+    body: String,
+) -> DomainResult<impl IntoResponse> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(body.as_bytes());
+    let headers = reader
+        .headers()
+        .map_err(|e| Error::ValidationError(format!("Invalid CSV header: {}", e)))?
+        .clone();
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    for result in reader.records() {
+        let parsed = result
+            .map_err(|e| {
+                let line = e.position().map(|p| p.line()).unwrap_or_default();
+                (line, e.to_string())
+            })
+            .and_then(|record| {
+                let line = record.position().map(|p| p.line()).unwrap_or_default();
+                record
+                    .deserialize::<CustomerCsvRecord>(Some(&headers))
+                    .map_err(|e| e.to_string())
+                    .and_then(|row| row.validate().map(|_| row).map_err(|e| e.to_string()))
+                    .map(|row| (line, row))
+                    .map_err(|message| (line, message))
+            });
+        match parsed {
+            Ok(row) => rows.push(row),
+            Err((line, message)) => errors.push(CustomerImportRowError { line, message }),
+        }
+    }
+
+    if params.abort_on_error && !errors.is_empty() {
+        return Ok((
+            StatusCode::OK,
+            Json(CustomerImportResponse {
+                imported: vec![],
+                errors,
+                aborted: true,
+            }),
+        ));
+    }
+
+    let mut imported = Vec::with_capacity(rows.len());
+    let mut aborted = false;
+    for (line, row) in rows {
+        match customer_service
+            .create(&ctx, &CustomerCreate::from(row))
+            .await
+        {
+            Ok(id) => imported.push(id),
+            // Every remaining row would fail the same way
+            Err(e @ Error::Forbidden(_)) => return Err(e),
+            Err(e) => {
+                errors.push(CustomerImportRowError {
+                    line,
+                    message: e.to_string(),
+                });
+                if params.abort_on_error {
+                    aborted = true;
+                    break;
+                }
+            }
+        }
+    }
+    errors.sort_by_key(|e| e.line);
+
     Ok((
         StatusCode::OK,
-        Json(CustomerListResponse {
-            customers: customer.into_iter().map(CustomerResponse::from).collect(),
+        Json(CustomerImportResponse {
+            imported,
+            errors,
+            aborted,
         }),
     ))
 }
 
+/// Serialize one page of customers to CSV, optionally preceded by the header row
+fn customers_to_csv(customers: Vec<Customer>, with_header: bool) -> DomainResult<Bytes> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(with_header)
+        .from_writer(vec![]);
+    if with_header && customers.is_empty() {
+        writer
+            .write_record(["number", "name", "email", "phone", "level"])
+            .map_err(|e| Error::Internal(e.to_string()))?;
+    }
+    for customer in customers {
+        writer
+            .serialize(CustomerCsvRecord::from(customer))
+            .map_err(|e| Error::Internal(e.to_string()))?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| Error::Internal(e.to_string()))?;
+    Ok(Bytes::from(bytes))
+}
+
+/// `filter` narrowed to customers below `id`. The list is ordered by id
+/// descending, so this continues after a page ending at `id`.
+fn below_id(filter: &CustomerFilter, id: i64) -> CustomerFilter {
+    let mut filter = filter.clone();
+    filter.expressions.push(FilterExpr {
+        field: "id".to_string(),
+        op: FilterOp::Lt,
+        value: FilterValue::Integer(id),
+    });
+    filter
+}
+
+/// Id the next export page continues below, or None after a short page
+fn next_cursor(page: &[Customer]) -> Option<i64> {
+    if page.len() as u32 == EXPORT_PAGE_SIZE {
+        page.last().map(|customer| customer.id)
+    } else {
+        None
+    }
+}
+
+/// Pages after the one ending at `cursor`, stopping after the first short
+/// page or the first error. Pages are keyed on the last id seen rather than
+/// an offset, so customers created or deleted mid-export do not shift rows
+/// into the next page or out of it.
+fn remaining_pages(
+    service: Arc<dyn CustomerServiceTrait>,
+    ctx: Context,
+    filter: CustomerFilter,
+    cursor: Option<i64>,
+) -> impl Stream<Item = DomainResult<Vec<Customer>>> + Send + 'static {
+    futures::stream::unfold(
+        (service, ctx, filter, cursor),
+        |(service, ctx, filter, cursor)| async move {
+            let last_id = cursor?;
+            let pagination = PaginationOptions::new(1, EXPORT_PAGE_SIZE, None);
+            match service
+                .get_all(&ctx, &below_id(&filter, last_id), &pagination)
+                .await
+            {
+                Ok(customers) => {
+                    let cursor = next_cursor(&customers);
+                    Some((Ok(customers), (service, ctx, filter, cursor)))
+                }
+                Err(e) => Some((Err(e), (service, ctx, filter, None))),
+            }
+        },
+    )
+}
+
+/// Every customer matching `filter` a page at a time, newest first. The first
+/// page is fetched before returning so permission and database errors surface
+/// as a proper error response instead of a truncated download.
+async fn export_pages(
+    service: Arc<dyn CustomerServiceTrait>,
+    ctx: Context,
+    filter: CustomerFilter,
+) -> DomainResult<impl Stream<Item = DomainResult<Vec<Customer>>> + Send + 'static> {
+    let first_page = service
+        .get_all(
+            &ctx,
+            &filter,
+            &PaginationOptions::new(1, EXPORT_PAGE_SIZE, None),
+        )
+        .await?;
+    let cursor = next_cursor(&first_page);
+    Ok(futures::stream::once(async move { Ok(first_page) })
+        .chain(remaining_pages(service, ctx, filter, cursor)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/customer/export.csv",
+    tag = "customer",
+    params(
+        ("number" = Option<String>, Query, description = "Filter by customer number"),
+        ("name" = Option<String>, Query, description = "Filter by customer name (partial match)"),
+        ("phone" = Option<String>, Query, description = "Filter by phone number"),
+        ("email" = Option<String>, Query, description = "Filter by email"),
+        ("level" = Option<i32>, Query, description = "Filter by customer level"),
+        ("level_min" = Option<i32>, Query, description = "Minimum customer level (inclusive)"),
+        ("level_max" = Option<i32>, Query, description = "Maximum customer level (inclusive)"),
+        ("search" = Option<String>, Query, description = "Match name, phone or email (partial match)"),
+        ("filter" = Option<String>, Query, description = "Filter expressions `field:op:value` separated by `;`, as for the customer list")
+    ),
+    responses(
+        (status = 200, description = "Filtered customer list as CSV", content_type = "text/csv", body = String),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - missing read permission", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(customer_service, ctx))]
+async fn export_csv(
+    State(customer_service): State<Arc<dyn CustomerServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+    Query(query): Query<CustomerQueryParams>,
+) -> DomainResult<impl IntoResponse> {
+    let filter = query.to_filter()?;
+    // Only the first chunk carries the header row
+    let stream = export_pages(customer_service, ctx, filter)
+        .await?
+        .enumerate()
+        .map(|(index, page)| page.and_then(|customers| customers_to_csv(customers, index == 0)));
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"customers.csv\"",
+            ),
+        ],
+        Body::from_stream(stream),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/customer/stream",
+    tag = "customer",
+    params(
+        ("number" = Option<String>, Query, description = "Filter by customer number"),
+        ("name" = Option<String>, Query, description = "Filter by customer name (partial match)"),
+        ("phone" = Option<String>, Query, description = "Filter by phone number"),
+        ("email" = Option<String>, Query, description = "Filter by email"),
+        ("level" = Option<i32>, Query, description = "Filter by customer level"),
+        ("level_min" = Option<i32>, Query, description = "Minimum customer level (inclusive)"),
+        ("level_max" = Option<i32>, Query, description = "Maximum customer level (inclusive)"),
+        ("search" = Option<String>, Query, description = "Match name, phone or email (partial match)"),
+        ("filter" = Option<String>, Query, description = "Filter expressions `field:op:value` separated by `;`, as for the customer list")
+    ),
+    responses(
+        (status = 200, description = "Every matching customer as NDJSON, one `CustomerResponse` per line", content_type = "application/x-ndjson", body = String),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - missing read permission", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(customer_service, ctx))]
+async fn stream(
+    State(customer_service): State<Arc<dyn CustomerServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+    Query(query): Query<CustomerQueryParams>,
+) -> DomainResult<impl IntoResponse> {
+    let filter = query.to_filter()?;
+    let pages = export_pages(customer_service, ctx, filter)
+        .await?
+        .map(|page| {
+            page.map(|customers| {
+                customers
+                    .into_iter()
+                    .map(CustomerResponse::from)
+                    .collect::<Vec<_>>()
+            })
+        });
+
+    Ok(NdjsonListResponse::new(pages))
+}
+
 // ============================================================================
 // Router
 // ============================================================================
 
-pub fn customer_router() -> Router<AppState> {
+/// `import_body_limit` caps CSV uploads, normally [`IMPORT_BODY_LIMIT`]
+pub fn customer_router(import_body_limit: usize) -> Router<AppState> {
     Router::new()
         .route("/", post(create))
         .route("/{id}", put(update))
         .route("/{id}", delete(delete_customer))
+        .route("/bulk-delete", post(bulk_delete))
         .route("/{id}", get(get_by_id))
         .route("/", get(get_all))
+        .route(
+            "/import",
+            post(import_csv).layer(DefaultBodyLimit::max(import_body_limit)),
+        )
+        .route("/export.csv", get(export_csv))
+        .route("/stream", get(stream))
 }
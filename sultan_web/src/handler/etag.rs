@@ -0,0 +1,65 @@
+use axum::{
+    Json,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Weak ETag for an entity revision. `updated_at` changes on every write,
+/// so together with the id it identifies the representation.
+pub fn weak_etag(id: i64, updated_at: &DateTime<Utc>) -> String {
+    format!("W/\"{:x}-{:x}\"", id, updated_at.timestamp_micros())
+}
+
+/// Whether the request's `If-None-Match` matches `etag`. Comparison is weak,
+/// so `W/` prefixes are ignored on both sides.
+pub fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    let strip = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = strip(etag);
+
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || strip(tag) == etag)
+}
+
+/// Respond with `body` and its ETag, or with `304 Not Modified` when the
+/// client already holds this revision.
+pub fn conditional_json<T: Serialize>(
+    headers: &HeaderMap,
+    id: i64,
+    updated_at: &DateTime<Utc>,
+    body: T,
+) -> Response {
+    conditional_json_with_etag(headers, weak_etag(id, updated_at), body)
+}
+
+/// Weak ETag for a representation that also carries a value which changes
+/// without touching the entity's `updated_at`, such as a child count.
+pub fn weak_etag_with(id: i64, updated_at: &DateTime<Utc>, extra: u64) -> String {
+    format!(
+        "W/\"{:x}-{:x}-{:x}\"",
+        id,
+        updated_at.timestamp_micros(),
+        extra
+    )
+}
+
+/// Like [`conditional_json`], with a caller-built `etag`
+pub fn conditional_json_with_etag<T: Serialize>(
+    headers: &HeaderMap,
+    etag: String,
+    body: T,
+) -> Response {
+    // The tag is built from hex digits only, so it is always a valid header value
+    let etag_header = HeaderValue::from_str(&etag).expect("ETag is a valid header value");
+
+    if if_none_match(headers, &etag) {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag_header)]).into_response();
+    }
+
+    (StatusCode::OK, [(header::ETAG, etag_header)], Json(body)).into_response()
+}
@@ -0,0 +1,239 @@
+use axum::Extension;
+use axum::body::{Body, Bytes};
+use axum::extract::{DefaultBodyLimit, Query, State};
+use axum::http::{StatusCode, header};
+use axum::response::IntoResponse;
+use axum::{
+    Json, Router,
+    routing::{get, post},
+};
+use futures::StreamExt;
+use std::sync::Arc;
+use sultan_core::application::ExportServiceTrait;
+use sultan_core::domain::context::Context;
+use sultan_core::domain::model::export::{ExportTable, ImportMode, Snapshot};
+use sultan_core::domain::{DomainResult, Error};
+use sultan_core::storage::ExportReader;
+use tracing::instrument;
+use utoipa::OpenApi;
+
+use crate::AppState;
+use crate::dto::ErrorResponse;
+use crate::dto::export::{
+    ExportQueryParams, ImportQueryParams, ImportSnapshotRequest, ImportSnapshotResponse,
+};
+
+// ============================================================================
+// OpenAPI Documentation
+// ============================================================================
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(export_full, import_full),
+    components(schemas(ErrorResponse, ImportSnapshotRequest, ImportSnapshotResponse)),
+    tags(
+        (name = "export", description = "Data export and import endpoints")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub struct ExportApiDoc;
+
+// ============================================================================
+// HTTP Handlers
+// ============================================================================
+
+/// Rows read per query while streaming the export
+const EXPORT_PAGE_SIZE: u32 = 500;
+
+/// Walks the export tables page by page, producing the JSON document in chunks.
+/// All pages come from one read transaction, held until the cursor is dropped.
+struct ExportCursor {
+    reader: Box<dyn ExportReader>,
+    include_deleted: bool,
+    /// Index into `ExportTable::ALL`
+    table: usize,
+    /// None until the current table's array has been opened
+    after_id: Option<i64>,
+    /// Whether the current array already holds a row, so the next needs a comma
+    wrote_row: bool,
+}
+
+impl ExportCursor {
+    async fn next_chunk(&mut self) -> Option<DomainResult<Bytes>> {
+        let table = *ExportTable::ALL.get(self.table)?;
+        let page = match self
+            .reader
+            .export_rows(
+                table,
+                self.include_deleted,
+                self.after_id.unwrap_or(i64::MIN),
+                EXPORT_PAGE_SIZE,
+            )
+            .await
+        {
+            Ok(page) => page,
+            Err(e) => {
+                self.table = ExportTable::ALL.len();
+                return Some(Err(e));
+            }
+        };
+
+        let mut chunk = Vec::new();
+        if self.after_id.is_none() {
+            chunk.extend_from_slice(if self.table == 0 { b"{\"" } else { b",\"" });
+            chunk.extend_from_slice(table.key().as_bytes());
+            chunk.extend_from_slice(b"\":[");
+        }
+        for row in &page.rows {
+            if self.wrote_row {
+                chunk.push(b',');
+            }
+            if let Err(e) = serde_json::to_writer(&mut chunk, row) {
+                self.table = ExportTable::ALL.len();
+                return Some(Err(Error::Internal(e.to_string())));
+            }
+            self.wrote_row = true;
+        }
+
+        match page.next_after_id {
+            Some(id) => self.after_id = Some(id),
+            None => {
+                chunk.push(b']');
+                self.table += 1;
+                self.after_id = None;
+                self.wrote_row = false;
+                if self.table == ExportTable::ALL.len() {
+                    chunk.push(b'}');
+                }
+            }
+        }
+        Some(Ok(Bytes::from(chunk)))
+    }
+}
+
+/// Export all data
+///
+/// Streams products, variants, categories, customers, suppliers and units as
+/// one JSON object of arrays, rows as stored. Every row is read from one
+/// snapshot of the database, so writes during the download don't tear it.
+/// Soft-deleted rows are left out unless `include_deleted` is set. Requires
+/// admin permission.
+#[utoipa::path(
+    get,
+    path = "/api/export/full",
+    tag = "export",
+    params(ExportQueryParams),
+    responses(
+        (status = 200, description = "Export document", content_type = "application/json", body = Object),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - admin permission required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(export_service, ctx))]
+async fn export_full(
+    State(export_service): State<Arc<dyn ExportServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+    Query(params): Query<ExportQueryParams>,
+) -> DomainResult<impl IntoResponse> {
+    let reader = export_service.begin_export(&ctx).await?;
+    let mut cursor = ExportCursor {
+        reader,
+        include_deleted: params.include_deleted,
+        table: 0,
+        after_id: None,
+        wrote_row: false,
+    };
+
+    // Read the first page eagerly so database errors surface
+    // as a proper error response instead of a truncated download
+    let first_chunk = cursor.next_chunk().await.transpose()?;
+
+    let rest = futures::stream::unfold(cursor, |mut cursor| async move {
+        cursor.next_chunk().await.map(|chunk| (chunk, cursor))
+    });
+    let stream = futures::stream::iter(first_chunk.map(Ok)).chain(rest);
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/json"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"export.json\"",
+            ),
+        ],
+        Body::from_stream(stream),
+    ))
+}
+
+/// Full snapshots are far larger than regular JSON payloads. The server
+/// raises it to its global body limit when that is larger.
+pub const IMPORT_BODY_LIMIT: usize = 100 * 1024 * 1024;
+
+/// Import all data
+///
+/// Restores a document written by `GET /api/export/full` in one transaction,
+/// keeping the ids. Category parents, product categories and variant products
+/// must exist in the document or in the database; otherwise nothing is
+/// written. Phones are normalized and metadata checked against the configured
+/// schemas, as on create. Requires admin permission.
+#[utoipa::path(
+    post,
+    path = "/api/import/full",
+    tag = "export",
+    params(ImportQueryParams),
+    request_body = ImportSnapshotRequest,
+    responses(
+        (status = 200, description = "Snapshot imported", body = ImportSnapshotResponse),
+        (status = 400, description = "Bad request - malformed rows, invalid phone or metadata, unknown mode or dangling references", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - admin permission required", body = ErrorResponse),
+        (status = 409, description = "Conflict - id already exists in insert mode", body = ErrorResponse),
+        (status = 413, description = "Document larger than 100 MiB", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(export_service, ctx, payload))]
+async fn import_full(
+    State(export_service): State<Arc<dyn ExportServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+    Query(params): Query<ImportQueryParams>,
+    Json(payload): Json<ImportSnapshotRequest>,
+) -> DomainResult<impl IntoResponse> {
+    let mode = match params.mode {
+        Some(mode) => mode.parse::<ImportMode>()?,
+        None => ImportMode::default(),
+    };
+    let snapshot = Snapshot::from(payload);
+    let imported = ExportTable::ALL
+        .iter()
+        .map(|table| (table.key().to_string(), snapshot.rows(*table).len()))
+        .collect();
+
+    export_service.import_snapshot(&ctx, snapshot, mode).await?;
+
+    Ok((StatusCode::OK, Json(ImportSnapshotResponse { imported })))
+}
+
+// ============================================================================
+// Router
+// ============================================================================
+
+pub fn export_router() -> Router<AppState> {
+    Router::new().route("/full", get(export_full))
+}
+
+/// `body_limit` caps snapshot uploads, normally [`IMPORT_BODY_LIMIT`]
+pub fn import_router(body_limit: usize) -> Router<AppState> {
+    Router::new().route(
+        "/full",
+        post(import_full).layer(DefaultBodyLimit::max(body_limit)),
+    )
+}
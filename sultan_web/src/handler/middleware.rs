@@ -1,4 +1,6 @@
+use std::any::{Any, TypeId};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use axum::{
     Json,
@@ -8,7 +10,9 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use serde_json::json;
-use sultan_core::domain::Context;
+use sultan_core::domain::{BranchContext, Context};
+use sultan_core::web::i18n::{Locale, localize_response};
+use tokio_util::sync::CancellationToken;
 
 use crate::AppState;
 
@@ -29,7 +33,7 @@ pub async fn verify_jwt(
         _ => {
             return Ok((
                 StatusCode::UNAUTHORIZED,
-                Json(json!({"error": "Missing or invalid authorization header"})),
+                Json(json!({"error": "Missing or invalid authorization header", "code": "unauthorized"})),
             )
                 .into_response());
         }
@@ -38,6 +42,20 @@ pub async fn verify_jwt(
     // Verify token
     match state.jwt_manager.validate_token(token) {
         Ok(claims) => {
+            // Device tokens have no refresh step, so revocation is checked here
+            if let Some(device_id) = claims.device_id
+                && state
+                    .auth_service
+                    .check_device(&Context::new_internal(), device_id)
+                    .await
+                    .is_err()
+            {
+                return Ok((
+                    StatusCode::UNAUTHORIZED,
+                    Json(json!({"error": "Device has been revoked", "code": "unauthorized"})),
+                )
+                    .into_response());
+            }
             let permission = state
                 .user_service
                 .get_user_permission(&Context::new_internal(), claims.user_id)
@@ -47,21 +65,94 @@ pub async fn verify_jwt(
                 .into_iter()
                 .map(|p| ((p.resource, p.branch_id), p.action))
                 .collect();
-            let ctx = Context::new_with_all(Some(claims.user_id), permission_hash, HashMap::new());
+            // Tokens without embedded branches need a lookup. A failed lookup
+            // must not pass as "no branches"
+            let branch_ctx = match claims.branch_context() {
+                Some(branch_ctx) => branch_ctx,
+                None => match state
+                    .auth_service
+                    .branch_context(&Context::new_internal(), claims.user_id)
+                    .await
+                {
+                    Ok(branch_ctx) => branch_ctx,
+                    Err(e) => return Ok(e.into_response()),
+                },
+            };
+            let mut extensions: HashMap<TypeId, Arc<dyn Any + Send + Sync>> = HashMap::new();
+            extensions.insert(TypeId::of::<BranchContext>(), Arc::new(branch_ctx));
+            let mut ctx = Context::new_with_all(Some(claims.user_id), permission_hash, extensions)
+                .with_actor_id(claims.user_id);
+            if let Some(sink) = &state.audit_sink {
+                ctx = ctx.with_audit_sink(sink.clone());
+            }
+            // Keep the id, locale and cancellation token context_middleware assigned to this request
+            if let Some(request_ctx) = req.extensions().get::<Context>() {
+                if let Some(request_id) = request_ctx.request_id() {
+                    ctx = ctx.with_request_id(request_id);
+                }
+                if let Some(locale) = request_ctx.locale() {
+                    ctx = ctx.with_locale(locale);
+                }
+                ctx = ctx.with_cancellation_token(request_ctx.cancellation_token().clone());
+            }
             req.extensions_mut().insert(ctx);
             Ok(next.run(req).await)
         }
         Err(_) => Ok((
             StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "Invalid or expired token"})),
+            Json(json!({"error": "Invalid or expired token", "code": "unauthorized"})),
         )
             .into_response()),
     }
 }
 
-/// Middleware to verify JWT Bearer token
-pub async fn context_middleware(mut req: Request, next: Next) -> Result<Response, StatusCode> {
-    let ctx = Context::new();
+/// Middleware that gives every request an anonymous context with its own
+/// request id and the locale from `Accept-Language`, and renders error
+/// responses in that locale. Request ids come from the app's id generator,
+/// so they carry this instance's node id.
+pub async fn context_middleware(
+    State(state): State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let token = CancellationToken::new();
+    let mut ctx = Context::new().with_cancellation_token(token.clone());
+    if let Ok(request_id) = state.id_generator.generate() {
+        ctx = ctx.with_request_id(request_id);
+    }
+    let locale = req
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|h| h.to_str().ok())
+        .and_then(Locale::negotiate);
+    if let Some(locale) = locale {
+        ctx = ctx.with_locale(locale.tag());
+    }
     req.extensions_mut().insert(ctx);
-    Ok(next.run(req).await)
+
+    // When the client disconnects, hyper drops this future before the guard
+    // is disarmed, which cancels the token for any work still holding it
+    let guard = token.drop_guard();
+    let response = next.run(req).await;
+    guard.disarm();
+    Ok(localize_response(response, locale.map(|l| l.tag())))
+}
+
+/// Middleware that turns the plain-text 413 from body limit rejections into
+/// the usual JSON error body
+pub async fn payload_too_large_json(req: Request, next: Next) -> Response {
+    let response = next.run(req).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE && !is_json {
+        return (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(json!({"error": "Request body too large", "code": "payload_too_large"})),
+        )
+            .into_response();
+    }
+    response
 }
@@ -1,5 +1,15 @@
 pub mod auth_router;
 pub mod category_router;
 pub mod customer_router;
+pub mod etag;
+pub mod export_router;
+pub mod metrics;
 pub mod middleware;
+pub mod ndjson;
+pub mod product_router;
+pub mod sale_router;
+pub mod stats_router;
+pub mod stock_router;
 pub mod supplier_routes;
+pub mod token_router;
+pub mod total_count;
@@ -0,0 +1,49 @@
+use axum::{
+    body::{Body, Bytes},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use futures::{Stream, StreamExt};
+use serde::Serialize;
+use sultan_core::domain::{DomainResult, Error};
+
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Streaming counterpart of [`ListResponse`](crate::dto::ListResponse) for
+/// exports too large to buffer. Each item of the stream is one page of rows,
+/// written as one JSON object per line as soon as it arrives.
+pub struct NdjsonListResponse<S> {
+    pub pages: S,
+}
+
+impl<S> NdjsonListResponse<S> {
+    pub fn new(pages: S) -> Self {
+        Self { pages }
+    }
+}
+
+/// Serialize a page of rows as newline-terminated JSON objects
+pub fn to_ndjson<T: Serialize>(rows: impl IntoIterator<Item = T>) -> DomainResult<Bytes> {
+    let mut chunk = Vec::new();
+    for row in rows {
+        serde_json::to_writer(&mut chunk, &row).map_err(|e| Error::Internal(e.to_string()))?;
+        chunk.push(b'\n');
+    }
+    Ok(Bytes::from(chunk))
+}
+
+impl<S, T> IntoResponse for NdjsonListResponse<S>
+where
+    S: Stream<Item = DomainResult<Vec<T>>> + Send + 'static,
+    T: Serialize,
+{
+    fn into_response(self) -> Response {
+        let body = self.pages.map(|page| page.and_then(to_ndjson));
+        (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+            Body::from_stream(body),
+        )
+            .into_response()
+    }
+}
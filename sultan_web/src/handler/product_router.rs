@@ -0,0 +1,128 @@
+use axum::Extension;
+use axum::extract::Path;
+use axum::http::HeaderMap;
+use axum::routing::get;
+use axum::{Json, Router, extract::State, response::IntoResponse};
+use std::sync::Arc;
+use sultan_core::application::ProductServiceTrait;
+use sultan_core::domain::context::Context;
+use sultan_core::domain::{DomainResult, Error};
+use tracing::instrument;
+use utoipa::OpenApi;
+
+use crate::AppState;
+use crate::dto::ErrorResponse;
+use crate::dto::product::{
+    ProductDetailResponse, ProductResponse, ProductVariantResponse, ProductWithVariantsResponse,
+};
+use crate::handler::etag::{conditional_json_with_etag, weak_etag_with};
+
+// ============================================================================
+// OpenAPI Documentation
+// ============================================================================
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_by_id, get_with_variants),
+    components(schemas(
+        ProductDetailResponse,
+        ProductResponse,
+        ProductVariantResponse,
+        ProductWithVariantsResponse,
+        ErrorResponse,
+    )),
+    tags(
+        (name = "product", description = "Product catalog endpoints")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub struct ProductApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "/api/product/{id}",
+    tag = "product",
+    params(
+        ("id" = i64, Path, description = "Product ID to retrieve"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag from a previous response")
+    ),
+    responses(
+        (status = 200, description = "Product retrieved successfully", body = ProductDetailResponse,
+            headers(("ETag" = String, description = "Weak validator for If-None-Match"))),
+        (status = 304, description = "Not modified - If-None-Match matches the current ETag"),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - no product read permission", body = ErrorResponse),
+        (status = 404, description = "Product not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(product_service, ctx, headers))]
+async fn get_by_id(
+    State(product_service): State<Arc<dyn ProductServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+    Path(id): Path<i64>,
+    headers: HeaderMap,
+) -> DomainResult<impl IntoResponse> {
+    let product = product_service
+        .get_by_id(&ctx, id)
+        .await?
+        .ok_or(Error::NotFound(format!("Product with id {} not found", id)))?;
+    let variant_count = product_service.count_variants(&ctx, id).await?;
+    let category_ids = product_service.get_categories(&ctx, id).await?;
+    // Adding or removing a variant leaves the product's `updated_at` alone;
+    // category changes touch it
+    let etag = weak_etag_with(id, &product.updated_at, variant_count);
+    Ok(conditional_json_with_etag(
+        &headers,
+        etag,
+        ProductDetailResponse {
+            product: ProductResponse::from(product),
+            variant_count,
+            category_ids,
+        },
+    ))
+}
+
+/// Get a product with its variants
+///
+/// Returns the product together with its live variants and category ids, so
+/// a detail screen needs one request.
+#[utoipa::path(
+    get,
+    path = "/api/product/{id}/detail",
+    tag = "product",
+    params(
+        ("id" = i64, Path, description = "Product ID to retrieve")
+    ),
+    responses(
+        (status = 200, description = "Product retrieved successfully", body = ProductWithVariantsResponse),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - no product read permission", body = ErrorResponse),
+        (status = 404, description = "Product not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(product_service, ctx))]
+async fn get_with_variants(
+    State(product_service): State<Arc<dyn ProductServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+    Path(id): Path<i64>,
+) -> DomainResult<Json<ProductWithVariantsResponse>> {
+    let detail = product_service
+        .get_product_with_variants(&ctx, id)
+        .await?
+        .ok_or(Error::NotFound(format!("Product with id {} not found", id)))?;
+    Ok(Json(ProductWithVariantsResponse::from(detail)))
+}
+
+pub fn product_router() -> Router<AppState> {
+    Router::new()
+        .route("/{id}", get(get_by_id))
+        .route("/{id}/detail", get(get_with_variants))
+}
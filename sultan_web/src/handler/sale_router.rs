@@ -0,0 +1,208 @@
+use axum::Extension;
+use axum::extract::Path;
+use axum::http::HeaderMap;
+use axum::routing::get;
+use axum::{Json, Router, extract::State, http::StatusCode, response::IntoResponse, routing::post};
+use std::sync::Arc;
+use sultan_core::application::SaleServiceTrait;
+use sultan_core::domain::context::Context;
+use sultan_core::domain::model::sale::SaleCreate;
+use sultan_core::domain::{DomainResult, Error};
+use tracing::instrument;
+use utoipa::OpenApi;
+use validator::Validate;
+
+use crate::AppState;
+use crate::dto::sale::{
+    SaleLineRequest, SaleLineResponse, SaleRefundLineRequest, SaleRefundRequest, SaleResponse,
+};
+use crate::dto::{ErrorResponse, SaleCreateRequest, SaleCreateResponse};
+
+/// Header a POS client sets to make retries of the same sale safe
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+// ============================================================================
+// OpenAPI Documentation
+// ============================================================================
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(create, get_by_id, void, refund),
+    components(schemas(
+        SaleCreateRequest,
+        SaleLineRequest,
+        SaleRefundRequest,
+        SaleRefundLineRequest,
+        SaleCreateResponse,
+        SaleResponse,
+        SaleLineResponse,
+        ErrorResponse,
+    )),
+    tags(
+        (name = "sale", description = "Point of sale transactions")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub struct SaleApiDoc;
+
+fn idempotency_key(headers: &HeaderMap) -> DomainResult<Option<&str>> {
+    headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .map(|value| {
+            value.to_str().map_err(|_| {
+                Error::ValidationError("Idempotency-Key must be visible ASCII".to_string())
+            })
+        })
+        .transpose()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sale",
+    tag = "sale",
+    request_body = SaleCreateRequest,
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Retrying with the same key returns the original sale instead of creating another one")
+    ),
+    responses(
+        (status = 201, description = "Sale created (or the original sale for a repeated Idempotency-Key)", body = SaleCreateResponse),
+        (status = 400, description = "Bad request - validation error or insufficient stock", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - no access to the branch", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(sale_service, payload, ctx, headers))]
+async fn create(
+    State(sale_service): State<Arc<dyn SaleServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+    headers: HeaderMap,
+    Json(payload): Json<SaleCreateRequest>,
+) -> DomainResult<impl IntoResponse> {
+    // Validate input
+    payload
+        .validate()
+        .map_err(|e| Error::ValidationError(format!("{}", e)))?;
+
+    let key = idempotency_key(&headers)?;
+    let id = sale_service
+        .create(
+            &ctx,
+            &SaleCreate {
+                branch_id: payload.branch_id,
+                customer_id: payload.customer_id,
+                payment_method: payload.payment_method,
+                lines: payload.lines.into_iter().map(Into::into).collect(),
+            },
+            key,
+        )
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(SaleCreateResponse { id })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/sale/{id}",
+    tag = "sale",
+    params(
+        ("id" = i64, Path, description = "Sale ID to retrieve")
+    ),
+    responses(
+        (status = 200, description = "Sale retrieved successfully", body = SaleResponse),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - no access to the branch", body = ErrorResponse),
+        (status = 404, description = "Sale not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(sale_service, ctx))]
+async fn get_by_id(
+    State(sale_service): State<Arc<dyn SaleServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+    Path(id): Path<i64>,
+) -> DomainResult<impl IntoResponse> {
+    let sale = sale_service
+        .get_by_id(&ctx, id)
+        .await?
+        .ok_or(Error::NotFound(format!("Sale with id {} not found", id)))?;
+    Ok((StatusCode::OK, Json(SaleResponse::from(sale))))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sale/{id}/void",
+    tag = "sale",
+    params(
+        ("id" = i64, Path, description = "Sale ID to void")
+    ),
+    responses(
+        (status = 201, description = "Sale voided, returns the void record id", body = SaleCreateResponse),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - no access to the branch", body = ErrorResponse),
+        (status = 404, description = "Sale not found", body = ErrorResponse),
+        (status = 409, description = "Sale already voided or fully refunded", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(sale_service, ctx))]
+async fn void(
+    State(sale_service): State<Arc<dyn SaleServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+    Path(id): Path<i64>,
+) -> DomainResult<impl IntoResponse> {
+    let id = sale_service.void(&ctx, id).await?;
+    Ok((StatusCode::CREATED, Json(SaleCreateResponse { id })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sale/{id}/refund",
+    tag = "sale",
+    request_body = SaleRefundRequest,
+    params(
+        ("id" = i64, Path, description = "Sale ID to refund")
+    ),
+    responses(
+        (status = 201, description = "Lines refunded, returns the refund record id", body = SaleCreateResponse),
+        (status = 400, description = "Bad request - refund exceeds the sold quantity", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - no access to the branch", body = ErrorResponse),
+        (status = 404, description = "Sale not found", body = ErrorResponse),
+        (status = 409, description = "Sale already voided", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(sale_service, payload, ctx))]
+async fn refund(
+    State(sale_service): State<Arc<dyn SaleServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+    Path(id): Path<i64>,
+    Json(payload): Json<SaleRefundRequest>,
+) -> DomainResult<impl IntoResponse> {
+    payload
+        .validate()
+        .map_err(|e| Error::ValidationError(format!("{}", e)))?;
+
+    let lines: Vec<_> = payload.lines.into_iter().map(Into::into).collect();
+    let id = sale_service.refund_lines(&ctx, id, &lines).await?;
+    Ok((StatusCode::CREATED, Json(SaleCreateResponse { id })))
+}
+
+pub fn sale_router() -> Router<AppState> {
+    Router::new()
+        .route("/", post(create))
+        .route("/{id}", get(get_by_id))
+        .route("/{id}/void", post(void))
+        .route("/{id}/refund", post(refund))
+}
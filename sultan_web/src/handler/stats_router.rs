@@ -0,0 +1,92 @@
+use axum::Extension;
+use axum::extract::State;
+use axum::{Json, Router, routing::get};
+use std::sync::Arc;
+use sultan_core::application::{
+    CategoryServiceTrait, CustomerServiceTrait, ProductServiceTrait, SupplierServiceTrait,
+};
+use sultan_core::domain::DomainResult;
+use sultan_core::domain::context::Context;
+use sultan_core::domain::model::{
+    customer::CustomerFilter, product::ProductFilter, supplier::SupplierFilter,
+};
+use tracing::instrument;
+use utoipa::OpenApi;
+
+use crate::AppState;
+use crate::dto::ErrorResponse;
+use crate::dto::stats::CountsResponse;
+
+// ============================================================================
+// OpenAPI Documentation
+// ============================================================================
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_counts),
+    components(schemas(CountsResponse, ErrorResponse)),
+    tags(
+        (name = "stats", description = "Dashboard statistics endpoints")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub struct StatsApiDoc;
+
+// ============================================================================
+// HTTP Handlers
+// ============================================================================
+
+/// Count records
+///
+/// Number of live products, customers, suppliers and categories. Each total
+/// is computed like the matching list endpoint without filters. Requires read
+/// permission on all four resources.
+#[utoipa::path(
+    get,
+    path = "/api/stats/counts",
+    tag = "stats",
+    responses(
+        (status = 200, description = "Record totals", body = CountsResponse),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - read permission missing for one of the resources", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip_all)]
+async fn get_counts(
+    State(product_service): State<Arc<dyn ProductServiceTrait>>,
+    State(customer_service): State<Arc<dyn CustomerServiceTrait>>,
+    State(supplier_service): State<Arc<dyn SupplierServiceTrait>>,
+    State(category_service): State<Arc<dyn CategoryServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+) -> DomainResult<Json<CountsResponse>> {
+    let product_filter = ProductFilter::default();
+    let customer_filter = CustomerFilter::default();
+    let supplier_filter = SupplierFilter::default();
+
+    let (products, customers, suppliers, categories) = tokio::try_join!(
+        product_service.count(&ctx, &product_filter),
+        customer_service.count(&ctx, &customer_filter),
+        supplier_service.count(&ctx, &supplier_filter),
+        category_service.count(&ctx, None),
+    )?;
+
+    Ok(Json(CountsResponse {
+        products,
+        customers,
+        suppliers,
+        categories,
+    }))
+}
+
+// ============================================================================
+// Router
+// ============================================================================
+
+pub fn stats_router() -> Router<AppState> {
+    Router::new().route("/counts", get(get_counts))
+}
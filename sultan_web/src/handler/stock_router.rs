@@ -0,0 +1,85 @@
+use axum::Extension;
+use axum::extract::Query;
+use axum::routing::get;
+use axum::{Json, Router, extract::State};
+use std::sync::Arc;
+use sultan_core::application::ProductServiceTrait;
+use sultan_core::domain::DomainResult;
+use sultan_core::domain::context::Context;
+use tracing::instrument;
+use utoipa::OpenApi;
+
+use crate::AppState;
+use crate::app_state::MaxPageSize;
+use crate::dto::stock::{LowStockQueryParams, StockLevelResponse};
+use crate::dto::{ErrorResponse, ListResponse};
+
+// ============================================================================
+// OpenAPI Documentation
+// ============================================================================
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_low_stock),
+    components(schemas(ListResponse<StockLevelResponse>, StockLevelResponse, ErrorResponse)),
+    tags(
+        (name = "stock", description = "Stock level endpoints")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub struct StockApiDoc;
+
+// ============================================================================
+// HTTP Handlers
+// ============================================================================
+
+/// Low-stock report
+///
+/// Variants stocked at the branch with at most `below` units on hand, lowest
+/// quantity first, for building a reorder list. Requires product read
+/// permission for the branch.
+#[utoipa::path(
+    get,
+    path = "/api/stock",
+    tag = "stock",
+    params(LowStockQueryParams),
+    responses(
+        (status = 200, description = "Low stock variants", body = ListResponse<StockLevelResponse>),
+        (status = 400, description = "Missing or invalid query parameters", body = ErrorResponse),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - no product read permission for the branch", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip(product_service, ctx))]
+async fn get_low_stock(
+    State(product_service): State<Arc<dyn ProductServiceTrait>>,
+    State(MaxPageSize(max_page_size)): State<MaxPageSize>,
+    Extension(ctx): Extension<Context>,
+    Query(params): Query<LowStockQueryParams>,
+) -> DomainResult<Json<ListResponse<StockLevelResponse>>> {
+    let levels = product_service
+        .list_low_stock(
+            &ctx,
+            params.branch_id,
+            params.below,
+            &params.to_pagination(max_page_size)?,
+        )
+        .await?;
+
+    Ok(Json(ListResponse {
+        data: levels.into_iter().map(StockLevelResponse::from).collect(),
+    }))
+}
+
+// ============================================================================
+// Router
+// ============================================================================
+
+pub fn stock_router() -> Router<AppState> {
+    Router::new().route("/", get(get_low_stock))
+}
@@ -15,8 +15,10 @@ use utoipa::OpenApi;
 use validator::Validate;
 
 use crate::AppState;
+use crate::app_state::MaxPageSize;
 use crate::dto::supplier::{SupplierQueryParams, SupplierResponse, SupplierUpdateRequest};
 use crate::dto::{ErrorResponse, ListResponse, SupplierCreateRequest, SupplierCreateResponse};
+use crate::handler::total_count::WithTotalCount;
 
 // ============================================================================
 // OpenAPI Documentation
@@ -218,7 +220,8 @@ async fn get_one(
     tag = "supplier",
     params(SupplierQueryParams),
     responses(
-        (status = 200, description = "Suppliers retrieved successfully", body = ListResponse<SupplierResponse>),
+        (status = 200, description = "Suppliers retrieved successfully", body = ListResponse<SupplierResponse>,
+            headers(("X-Total-Count" = u64, description = "Number of matching rows across all pages"))),
         (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse)
     ),
     security(
@@ -227,18 +230,21 @@ async fn get_one(
 )]
 async fn get_many(
     State(supplier_service): State<Arc<dyn SupplierServiceTrait>>,
+    State(MaxPageSize(max_page_size)): State<MaxPageSize>,
     Extension(ctx): Extension<Context>,
     Query(params): Query<SupplierQueryParams>,
 ) -> DomainResult<impl IntoResponse> {
+    let filter = params.to_filter();
     let supplier = supplier_service
-        .get_all(&ctx, &params.to_filter(), &params.to_pagination())
+        .get_all(&ctx, &filter, &params.to_pagination(max_page_size)?)
         .await?;
+    let total = supplier_service.count(&ctx, &filter).await?;
 
-    Ok((
-        StatusCode::OK,
-        Json(ListResponse {
+    Ok(WithTotalCount::new(
+        total,
+        ListResponse {
             data: supplier.into_iter().map(SupplierResponse::from).collect(),
-        }),
+        },
     ))
 }
 
@@ -0,0 +1,68 @@
+use axum::Extension;
+use axum::extract::State;
+use axum::{Json, Router, routing::delete};
+use std::sync::Arc;
+use sultan_core::application::AuthServiceTrait;
+use sultan_core::domain::DomainResult;
+use sultan_core::domain::context::Context;
+use tracing::instrument;
+use utoipa::OpenApi;
+
+use crate::AppState;
+use crate::dto::ErrorResponse;
+use crate::dto::login::PurgeExpiredTokensResponse;
+
+// ============================================================================
+// OpenAPI Documentation
+// ============================================================================
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(purge_expired),
+    components(schemas(PurgeExpiredTokensResponse, ErrorResponse)),
+    tags(
+        (name = "tokens", description = "Refresh token maintenance endpoints")
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+pub struct TokenApiDoc;
+
+// ============================================================================
+// HTTP Handlers
+// ============================================================================
+
+/// Purge expired refresh tokens
+///
+/// Deletes every refresh token past its expiry and returns how many were
+/// removed. Requires admin permission.
+#[utoipa::path(
+    delete,
+    path = "/api/tokens/expired",
+    tag = "tokens",
+    responses(
+        (status = 200, description = "Expired tokens deleted", body = PurgeExpiredTokensResponse),
+        (status = 401, description = "Unauthorized - missing or invalid token", body = ErrorResponse),
+        (status = 403, description = "Forbidden - admin permission required", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[instrument(skip_all)]
+async fn purge_expired(
+    State(auth_service): State<Arc<dyn AuthServiceTrait>>,
+    Extension(ctx): Extension<Context>,
+) -> DomainResult<Json<PurgeExpiredTokensResponse>> {
+    let purged = auth_service.purge_expired_tokens(&ctx).await?;
+    Ok(Json(PurgeExpiredTokensResponse { purged }))
+}
+
+// ============================================================================
+// Router
+// ============================================================================
+
+pub fn token_router() -> Router<AppState> {
+    Router::new().route("/expired", delete(purge_expired))
+}
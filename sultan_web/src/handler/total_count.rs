@@ -0,0 +1,35 @@
+use axum::{
+    Json,
+    http::{HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+pub const TOTAL_COUNT_HEADER: &str = "x-total-count";
+
+/// A page of a list response. `X-Total-Count` carries the number of rows
+/// matching the query, not just the ones on this page.
+pub struct WithTotalCount<T> {
+    pub total: u64,
+    pub body: T,
+}
+
+impl<T> WithTotalCount<T> {
+    pub fn new(total: u64, body: T) -> Self {
+        Self { total, body }
+    }
+}
+
+impl<T: Serialize> IntoResponse for WithTotalCount<T> {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::OK,
+            [(
+                HeaderName::from_static(TOTAL_COUNT_HEADER),
+                HeaderValue::from(self.total),
+            )],
+            Json(self.body),
+        )
+            .into_response()
+    }
+}
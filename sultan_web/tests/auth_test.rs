@@ -42,6 +42,15 @@ async fn test_login_success() {
         response["refresh_token"].as_str().unwrap(),
         "mock_refresh_token_67890"
     );
+    // Expiry timestamps are RFC 3339, access first
+    let access_expires_at =
+        chrono::DateTime::parse_from_rfc3339(response["access_expires_at"].as_str().unwrap())
+            .unwrap();
+    let refresh_expires_at =
+        chrono::DateTime::parse_from_rfc3339(response["refresh_expires_at"].as_str().unwrap())
+            .unwrap();
+    assert!(access_expires_at > chrono::Utc::now());
+    assert!(refresh_expires_at > access_expires_at);
 }
 
 #[tokio::test]
@@ -325,3 +334,47 @@ async fn test_logout_success() {
     // NO_CONTENT means empty body
     assert!(response.is_null() || response.as_object().is_none_or(|o| o.is_empty()));
 }
+
+#[tokio::test]
+async fn test_device_login_returns_access_token_only() {
+    let app_state = MockAppStateBuilder::new()
+        .with_auth_service(Arc::new(MockAuthService::new_success()))
+        .build();
+    let app = Router::new()
+        .nest("/api/auth", auth_router())
+        .with_state(app_state);
+
+    let body = json!({ "device_key": "valid_device_key" });
+    let (status, response) = make_request(app, "POST", "/api/auth/device", Some(body))
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        response["access_token"].as_str().unwrap(),
+        "mock_access_token_12345"
+    );
+    assert!(response.get("refresh_token").is_none());
+}
+
+#[tokio::test]
+async fn test_device_login_unknown_key() {
+    let app_state = MockAppStateBuilder::new()
+        .with_auth_service(Arc::new(MockAuthService::new_success()))
+        .build();
+    let app = Router::new()
+        .nest("/api/auth", auth_router())
+        .with_state(app_state);
+
+    let body = json!({ "device_key": "unknown" });
+    let (status, _) = make_request(app.clone(), "POST", "/api/auth/device", Some(body))
+        .await
+        .expect("Request failed");
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let body = json!({ "device_key": "" });
+    let (status, _) = make_request(app, "POST", "/api/auth/device", Some(body))
+        .await
+        .expect("Request failed");
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -2,11 +2,16 @@ mod common;
 
 use axum::Router;
 use axum::http::StatusCode;
-use axum::middleware::from_fn;
+use axum::middleware::from_fn_with_state;
 use serde_json::json;
 use std::sync::Arc;
 
-use common::{MockAppStateBuilder, make_request, mock_category_service::MockCategoryService};
+use common::{
+    MockAppStateBuilder, MockProductService, make_conditional_get, make_request,
+    mock_category_service::MockCategoryService,
+};
+use sultan_core::domain::model::Update;
+use sultan_web::dto::category::CategoryUpdateRequest;
 use sultan_web::{handler::category_router::category_router, middleware::context_middleware};
 
 // ============================================================================
@@ -15,10 +20,11 @@ use sultan_web::{handler::category_router::category_router, middleware::context_
 
 /// Helper function to build a test router with the context middleware
 fn build_test_router(app_state: common::MockAppStateBuilder) -> Router {
+    let state = app_state.build();
     Router::new()
         .nest("/api/category", category_router())
-        .layer(from_fn(context_middleware))
-        .with_state(app_state.build())
+        .layer(from_fn_with_state(state.clone(), context_middleware))
+        .with_state(state)
 }
 
 #[tokio::test]
@@ -457,6 +463,22 @@ async fn test_get_all_categories_success() {
     );
 }
 
+#[tokio::test]
+async fn test_get_all_categories_for_branch() {
+    let app_state = MockAppStateBuilder::new()
+        .with_category_service(Arc::new(MockCategoryService::new_success()));
+    let app = build_test_router(app_state);
+
+    let (status, response) = make_request(app, "GET", "/api/category?branch_id=10", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    let categories = response.as_array().unwrap();
+    assert_eq!(categories.len(), 1);
+    assert_eq!(categories[0]["name"].as_str().unwrap(), "Electronics");
+}
+
 #[tokio::test]
 async fn test_get_all_categories_empty() {
     // Setup - create a custom mock that returns empty list
@@ -492,19 +514,47 @@ async fn test_get_all_categories_empty() {
         async fn get_all(
             &self,
             _ctx: &sultan_core::domain::context::Context,
+            _branch_id: Option<i64>,
         ) -> sultan_core::domain::DomainResult<Vec<sultan_core::domain::model::category::Category>>
         {
             Ok(vec![])
         }
 
+        async fn count(
+            &self,
+            _ctx: &sultan_core::domain::context::Context,
+            _branch_id: Option<i64>,
+        ) -> sultan_core::domain::DomainResult<u64> {
+            Ok(0)
+        }
+
         async fn get_by_id(
             &self,
             _ctx: &sultan_core::domain::context::Context,
             _id: i64,
+            _branch_id: Option<i64>,
         ) -> sultan_core::domain::DomainResult<Option<sultan_core::domain::model::category::Category>>
         {
             Ok(None)
         }
+
+        async fn assign_to_branch(
+            &self,
+            _ctx: &sultan_core::domain::context::Context,
+            _id: i64,
+            _branch_id: i64,
+        ) -> sultan_core::domain::DomainResult<()> {
+            Ok(())
+        }
+
+        async fn remove_from_branch(
+            &self,
+            _ctx: &sultan_core::domain::context::Context,
+            _id: i64,
+            _branch_id: i64,
+        ) -> sultan_core::domain::DomainResult<()> {
+            Ok(())
+        }
     }
 
     let app_state =
@@ -747,3 +797,167 @@ async fn test_get_all_categories_service_error() {
         error_msg
     );
 }
+
+// ============================================================================
+// GET /api/category/{id}/products - Products In Category Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_get_category_products_success() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(app, "GET", "/api/category/1/products", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    let data = response["data"].as_array().unwrap();
+    assert_eq!(data.len(), 3);
+    assert_eq!(data[0]["id"], 1);
+    assert_eq!(data[0]["name"], "Kopi Susu");
+}
+
+#[tokio::test]
+async fn test_get_category_products_pagination() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(
+        app,
+        "GET",
+        "/api/category/1/products?page=2&page_size=2",
+        None,
+    )
+    .await
+    .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    let data = response["data"].as_array().unwrap();
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0]["id"], 3);
+}
+
+#[tokio::test]
+async fn test_get_category_products_rejects_page_zero() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(app, "GET", "/api/category/1/products?page=0", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(response.get("error").is_some());
+}
+
+#[tokio::test]
+async fn test_get_category_products_total_count_header() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, headers, _body) =
+        make_conditional_get(app, "/api/category/1/products?page=2&page_size=2", None)
+            .await
+            .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers["x-total-count"], "3");
+}
+
+#[tokio::test]
+async fn test_get_category_products_unknown_category_is_empty() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(app, "GET", "/api/category/999/products", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(response["data"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_get_category_products_service_error() {
+    let app_state = MockAppStateBuilder::new()
+        .with_product_service(Arc::new(MockProductService::new_failure()));
+    let app = build_test_router(app_state);
+
+    let (status, _) = make_request(app, "GET", "/api/category/1/products", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[test]
+fn test_category_update_request_patch_semantics() {
+    let request: CategoryUpdateRequest =
+        serde_json::from_str(r#"{"name": "Drinks", "description": null}"#).unwrap();
+
+    assert_eq!(request.parent_id, Update::Unchanged);
+    assert_eq!(request.description, Update::Clear);
+
+    let request: CategoryUpdateRequest =
+        serde_json::from_str(r#"{"name": "Drinks", "parent_id": 7}"#).unwrap();
+    assert_eq!(request.parent_id, Update::Set(7));
+    assert_eq!(request.description, Update::Unchanged);
+}
+
+// ============================================================================
+// PUT/DELETE /api/category/{id}/branch/{branch_id} - Branch Assignment Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_assign_category_to_branch_success() {
+    let app_state = MockAppStateBuilder::new()
+        .with_category_service(Arc::new(MockCategoryService::new_success()));
+    let app = build_test_router(app_state);
+
+    let (status, _) = make_request(app, "PUT", "/api/category/1/branch/10", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn test_assign_category_to_branch_not_found() {
+    let app_state = MockAppStateBuilder::new()
+        .with_category_service(Arc::new(MockCategoryService::new_success()));
+    let app = build_test_router(app_state);
+
+    let (status, response) = make_request(app, "PUT", "/api/category/999/branch/10", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(
+        response["error"]
+            .as_str()
+            .unwrap()
+            .contains("Category with id 999 not found")
+    );
+}
+
+#[tokio::test]
+async fn test_remove_category_from_branch_success() {
+    let app_state = MockAppStateBuilder::new()
+        .with_category_service(Arc::new(MockCategoryService::new_success()));
+    let app = build_test_router(app_state);
+
+    let (status, _) = make_request(app, "DELETE", "/api/category/1/branch/10", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn test_remove_category_from_branch_service_error() {
+    let app_state = MockAppStateBuilder::new()
+        .with_category_service(Arc::new(MockCategoryService::new_failure()));
+    let app = build_test_router(app_state);
+
+    let (status, _) = make_request(app, "DELETE", "/api/category/1/branch/10", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+}
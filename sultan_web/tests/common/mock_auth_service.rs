@@ -1,11 +1,16 @@
 use async_trait::async_trait;
+use chrono::{Duration, Utc};
 use sultan_core::application::{AuthServiceTrait, AuthTokens};
-use sultan_core::domain::{DomainResult, Error, context::Context};
+use sultan_core::domain::{BranchContext, DomainResult, Error, context::Context};
 
 pub struct MockAuthService {
     pub should_succeed: bool,
     pub access_token: String,
     pub refresh_token: String,
+    pub branch_context: BranchContext,
+    pub branch_lookup_fails: bool,
+    pub revoked_device_ids: Vec<i64>,
+    pub expired_tokens: u64,
 }
 
 impl MockAuthService {
@@ -14,6 +19,10 @@ impl MockAuthService {
             should_succeed: true,
             access_token: "mock_access_token_12345".to_string(),
             refresh_token: "mock_refresh_token_67890".to_string(),
+            branch_context: BranchContext::default(),
+            branch_lookup_fails: false,
+            revoked_device_ids: Vec::new(),
+            expired_tokens: 0,
         }
     }
 
@@ -23,8 +32,36 @@ impl MockAuthService {
             should_succeed: false,
             access_token: String::new(),
             refresh_token: String::new(),
+            branch_context: BranchContext::default(),
+            branch_lookup_fails: false,
+            revoked_device_ids: Vec::new(),
+            expired_tokens: 0,
         }
     }
+
+    #[allow(dead_code)]
+    pub fn with_branch_context(mut self, branch_context: BranchContext) -> Self {
+        self.branch_context = branch_context;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_failing_branch_lookup(mut self) -> Self {
+        self.branch_lookup_fails = true;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_expired_tokens(mut self, count: u64) -> Self {
+        self.expired_tokens = count;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_revoked_device(mut self, device_id: i64) -> Self {
+        self.revoked_device_ids.push(device_id);
+        self
+    }
 }
 
 #[async_trait]
@@ -44,6 +81,8 @@ impl AuthServiceTrait for MockAuthService {
             Ok(AuthTokens {
                 access_token: self.access_token.clone(),
                 refresh_token: self.refresh_token.clone(),
+                access_expires_at: Utc::now() + Duration::minutes(15),
+                refresh_expires_at: Utc::now() + Duration::days(30),
             })
         } else {
             Err(Error::InvalidCredentials)
@@ -58,6 +97,8 @@ impl AuthServiceTrait for MockAuthService {
         Ok(AuthTokens {
             access_token: self.access_token.clone(),
             refresh_token: self.refresh_token.clone(),
+            access_expires_at: Utc::now() + Duration::minutes(15),
+            refresh_expires_at: Utc::now() + Duration::days(30),
         })
     }
 
@@ -67,4 +108,54 @@ impl AuthServiceTrait for MockAuthService {
         }
         Ok(())
     }
+
+    async fn logout_all(&self, _ctx: &Context, _user_id: i64) -> DomainResult<()> {
+        if !self.should_succeed {
+            return Err(Error::Forbidden("Access denied".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn login_device(&self, _ctx: &Context, device_key: &str) -> DomainResult<String> {
+        if self.should_succeed && device_key == "valid_device_key" {
+            Ok(self.access_token.clone())
+        } else {
+            Err(Error::InvalidCredentials)
+        }
+    }
+
+    async fn check_device(&self, _ctx: &Context, device_id: i64) -> DomainResult<()> {
+        if self.revoked_device_ids.contains(&device_id) {
+            return Err(Error::Unauthorized("Device has been revoked".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn branch_context(&self, _ctx: &Context, _user_id: i64) -> DomainResult<BranchContext> {
+        if self.branch_lookup_fails {
+            return Err(Error::Database("Branch lookup failed".to_string()));
+        }
+        Ok(self.branch_context.clone())
+    }
+
+    async fn change_password(
+        &self,
+        _ctx: &Context,
+        _user_id: i64,
+        old_password: &str,
+        _new_password: &str,
+    ) -> DomainResult<()> {
+        if self.should_succeed && old_password == "testpassword123" {
+            Ok(())
+        } else {
+            Err(Error::InvalidCredentials)
+        }
+    }
+
+    async fn purge_expired_tokens(&self, _ctx: &Context) -> DomainResult<u64> {
+        if !self.should_succeed {
+            return Err(Error::Forbidden("Access denied".to_string()));
+        }
+        Ok(self.expired_tokens)
+    }
 }
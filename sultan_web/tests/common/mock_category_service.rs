@@ -70,13 +70,13 @@ impl CategoryServiceTrait for MockCategoryService {
         Ok(())
     }
 
-    async fn get_all(&self, _ctx: &Context) -> DomainResult<Vec<Category>> {
+    async fn get_all(&self, _ctx: &Context, branch_id: Option<i64>) -> DomainResult<Vec<Category>> {
         if !self.should_succeed {
             return Err(Error::Internal("Failed to get categories".to_string()));
         }
 
-        // Return mock categories
-        Ok(vec![
+        // Return mock categories; branch 10 only sees the first one
+        let categories = vec![
             Category {
                 id: 1,
                 created_at: chrono::Utc::now(),
@@ -97,10 +97,24 @@ impl CategoryServiceTrait for MockCategoryService {
                 description: Some("Books and magazines".to_string()),
                 children: None,
             },
-        ])
+        ];
+        Ok(match branch_id {
+            Some(10) => categories.into_iter().take(1).collect(),
+            Some(_) => Vec::new(),
+            None => categories,
+        })
     }
 
-    async fn get_by_id(&self, _ctx: &Context, id: i64) -> DomainResult<Option<Category>> {
+    async fn count(&self, ctx: &Context, branch_id: Option<i64>) -> DomainResult<u64> {
+        Ok(self.get_all(ctx, branch_id).await?.len() as u64)
+    }
+
+    async fn get_by_id(
+        &self,
+        _ctx: &Context,
+        id: i64,
+        _branch_id: Option<i64>,
+    ) -> DomainResult<Option<Category>> {
         if !self.should_succeed {
             return Err(Error::Internal("Failed to get category".to_string()));
         }
@@ -121,4 +135,30 @@ impl CategoryServiceTrait for MockCategoryService {
             Ok(None)
         }
     }
+
+    async fn assign_to_branch(&self, _ctx: &Context, id: i64, _branch_id: i64) -> DomainResult<()> {
+        if !self.should_succeed {
+            return Err(Error::Internal("Failed to assign category".to_string()));
+        }
+        // Return NotFound if id is not 1
+        if id != 1 {
+            return Err(Error::NotFound(format!(
+                "Category with id {} not found",
+                id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn remove_from_branch(
+        &self,
+        _ctx: &Context,
+        _id: i64,
+        _branch_id: i64,
+    ) -> DomainResult<()> {
+        if !self.should_succeed {
+            return Err(Error::Internal("Failed to remove category".to_string()));
+        }
+        Ok(())
+    }
 }
@@ -1,16 +1,34 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
 use sultan_core::application::CustomerServiceTrait;
 use sultan_core::domain::model::pagination::PaginationOptions;
 use sultan_core::domain::{
     DomainResult, Error,
     context::Context,
+    model::bulk::BulkResult,
     model::customer::{Customer, CustomerCreate, CustomerFilter, CustomerUpdate},
+    model::filter::{FilterOp, FilterValue},
 };
 
+/// Total reported by `count`, larger than the page `get_all` returns
+pub const MOCK_CUSTOMER_TOTAL: u64 = 57;
+
+/// Customer 1 keeps a fixed `updated_at` that moves forward on every update,
+/// so ETags behave like they do against the database.
 pub struct MockCustomerService {
     pub should_succeed: bool,
     pub id: i64,
     pub return_empty: bool,
+    /// When set, `get_all` pages through this many customers, newest id
+    /// first and honouring `id` lt filters, and `count` reports it
+    pub paged_total: Option<u64>,
+    /// Customers created after each `get_all` call in paged mode, as if
+    /// another client were writing during an export
+    pub inserts_per_page: u64,
+    get_all_calls: AtomicU64,
+    updated_at: Mutex<DateTime<Utc>>,
 }
 
 impl MockCustomerService {
@@ -19,6 +37,10 @@ impl MockCustomerService {
             should_succeed: true,
             id: 1,
             return_empty: false,
+            paged_total: None,
+            inserts_per_page: 0,
+            get_all_calls: AtomicU64::new(0),
+            updated_at: Mutex::new(Utc::now()),
         }
     }
 
@@ -28,6 +50,10 @@ impl MockCustomerService {
             should_succeed: false,
             id: 1,
             return_empty: false,
+            paged_total: None,
+            inserts_per_page: 0,
+            get_all_calls: AtomicU64::new(0),
+            updated_at: Mutex::new(Utc::now()),
         }
     }
 
@@ -37,8 +63,26 @@ impl MockCustomerService {
             should_succeed: true,
             id: 1,
             return_empty: true,
+            paged_total: None,
+            inserts_per_page: 0,
+            get_all_calls: AtomicU64::new(0),
+            updated_at: Mutex::new(Utc::now()),
         }
     }
+
+    #[allow(dead_code)]
+    pub fn new_paged(total: u64) -> Self {
+        Self {
+            paged_total: Some(total),
+            ..Self::new_success()
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn with_inserts_per_page(mut self, inserts_per_page: u64) -> Self {
+        self.inserts_per_page = inserts_per_page;
+        self
+    }
 }
 
 #[async_trait]
@@ -65,6 +109,7 @@ impl CustomerServiceTrait for MockCustomerService {
                 id
             )));
         }
+        *self.updated_at.lock().unwrap() += Duration::seconds(1);
         Ok(())
     }
 
@@ -81,6 +126,25 @@ impl CustomerServiceTrait for MockCustomerService {
         Ok(())
     }
 
+    async fn delete_many(
+        &self,
+        _ctx: &Context,
+        ids: &[i64],
+        all_or_nothing: bool,
+    ) -> DomainResult<BulkResult> {
+        if !self.should_succeed {
+            return Err(Error::Internal("Failed to delete customers".to_string()));
+        }
+        let (deleted, not_found): (Vec<i64>, Vec<i64>) = ids.iter().partition(|id| **id == 1);
+        if all_or_nothing && !not_found.is_empty() {
+            return Ok(BulkResult {
+                deleted: vec![],
+                not_found,
+            });
+        }
+        Ok(BulkResult { deleted, not_found })
+    }
+
     async fn get_by_number(&self, _ctx: &Context, number: &str) -> DomainResult<Option<Customer>> {
         if !self.should_succeed {
             return Err(Error::Internal("Failed to get customer".to_string()));
@@ -97,7 +161,9 @@ impl CustomerServiceTrait for MockCustomerService {
             return Err(Error::Internal("Failed to get customer".to_string()));
         }
         if id == 1 {
-            Ok(Some(create_mock_customer(self.id, "CUST001", "John Doe")))
+            let mut customer = create_mock_customer(self.id, "CUST001", "John Doe");
+            customer.updated_at = *self.updated_at.lock().unwrap();
+            Ok(Some(customer))
         } else {
             Ok(None)
         }
@@ -106,12 +172,33 @@ impl CustomerServiceTrait for MockCustomerService {
     async fn get_all(
         &self,
         _ctx: &Context,
-        _filter: &CustomerFilter,
-        _pagination: &PaginationOptions,
+        filter: &CustomerFilter,
+        pagination: &PaginationOptions,
     ) -> DomainResult<Vec<Customer>> {
         if !self.should_succeed {
             return Err(Error::Internal("Failed to get customers".to_string()));
         }
+        if let Some(total) = self.paged_total {
+            let calls = self.get_all_calls.fetch_add(1, Ordering::SeqCst);
+            let newest = (total + calls * self.inserts_per_page) as i64;
+            let below = filter
+                .expressions
+                .iter()
+                .filter(|expr| expr.field == "id" && expr.op == FilterOp::Lt)
+                .filter_map(|expr| match expr.value {
+                    FilterValue::Integer(id) => Some(id),
+                    _ => None,
+                })
+                .min()
+                .unwrap_or(i64::MAX);
+            return Ok((1..=newest)
+                .rev()
+                .filter(|id| *id < below)
+                .skip(pagination.offset() as usize)
+                .take(pagination.limit() as usize)
+                .map(|id| create_mock_customer(id, &format!("CUST{:05}", id), "Paged Customer"))
+                .collect());
+        }
         if self.return_empty {
             return Ok(vec![]);
         }
@@ -120,6 +207,32 @@ impl CustomerServiceTrait for MockCustomerService {
             create_mock_customer(2, "CUST002", "Jane Smith"),
         ])
     }
+
+    async fn count(&self, _ctx: &Context, _filter: &CustomerFilter) -> DomainResult<u64> {
+        if !self.should_succeed {
+            return Err(Error::Internal("Failed to count customers".to_string()));
+        }
+        if let Some(total) = self.paged_total {
+            return Ok(total);
+        }
+        // More customers match than get_all returns on one page
+        Ok(if self.return_empty {
+            0
+        } else {
+            MOCK_CUSTOMER_TOTAL
+        })
+    }
+
+    async fn get_all_with_total(
+        &self,
+        ctx: &Context,
+        filter: &CustomerFilter,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<(Vec<Customer>, u64)> {
+        let customers = self.get_all(ctx, filter, pagination).await?;
+        let total = self.count(ctx, filter).await?;
+        Ok((customers, total))
+    }
 }
 
 fn create_mock_customer(id: i64, number: &str, name: &str) -> Customer {
@@ -136,5 +249,7 @@ fn create_mock_customer(id: i64, number: &str, name: &str) -> Customer {
         phone: Some("555-1234".to_string()),
         level: 1,
         metadata: None,
+        created_by: None,
+        updated_by: None,
     }
 }
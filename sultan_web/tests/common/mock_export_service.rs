@@ -0,0 +1,117 @@
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use sultan_core::application::ExportServiceTrait;
+use sultan_core::domain::{
+    DomainResult, Error,
+    context::Context,
+    model::export::{ExportPage, ExportTable, ImportMode, Snapshot},
+};
+use sultan_core::storage::ExportReader;
+
+/// Serves two products (one deleted) and one customer, one row per page so
+/// the handler has to follow `next_after_id`. Other tables are empty.
+/// Imports are accepted unless a variant points at a product missing from
+/// the snapshot.
+pub struct MockExportService {
+    pub should_succeed: bool,
+}
+
+impl MockExportService {
+    pub fn new_success() -> Self {
+        Self {
+            should_succeed: true,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn new_failure() -> Self {
+        Self {
+            should_succeed: false,
+        }
+    }
+
+    fn rows(table: ExportTable) -> Vec<Value> {
+        match table {
+            ExportTable::Products => vec![
+                json!({"id": 1, "name": "Kopi Susu", "is_deleted": false}),
+                json!({"id": 2, "name": "Teh Manis", "is_deleted": true}),
+            ],
+            ExportTable::Customers => {
+                vec![json!({"id": 3, "name": "Warung Sultan", "is_deleted": false})]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+/// Reader handed out by [`MockExportService`]
+struct MockExportReader;
+
+#[async_trait]
+impl ExportReader for MockExportReader {
+    async fn export_rows(
+        &mut self,
+        table: ExportTable,
+        include_deleted: bool,
+        after_id: i64,
+        _limit: u32,
+    ) -> DomainResult<ExportPage> {
+        let mut rows = MockExportService::rows(table)
+            .into_iter()
+            .filter(|row| row["id"].as_i64().unwrap() > after_id)
+            .filter(|row| include_deleted || row["is_deleted"] == false);
+        let row = rows.next();
+        let has_more = rows.next().is_some();
+        Ok(ExportPage {
+            next_after_id: row
+                .as_ref()
+                .filter(|_| has_more)
+                .map(|row| row["id"].as_i64().unwrap()),
+            rows: row.into_iter().collect(),
+        })
+    }
+}
+
+#[async_trait]
+impl ExportServiceTrait for MockExportService {
+    async fn begin_export(&self, _ctx: &Context) -> DomainResult<Box<dyn ExportReader>> {
+        if !self.should_succeed {
+            return Err(Error::Forbidden("Access denied".to_string()));
+        }
+        Ok(Box::new(MockExportReader))
+    }
+
+    async fn import_snapshot(
+        &self,
+        _ctx: &Context,
+        snapshot: Snapshot,
+        _mode: ImportMode,
+    ) -> DomainResult<()> {
+        if !self.should_succeed {
+            return Err(Error::Forbidden("Access denied".to_string()));
+        }
+        let dangling: Vec<String> = snapshot
+            .variants
+            .iter()
+            .filter(|variant| {
+                !snapshot
+                    .products
+                    .iter()
+                    .any(|product| product["id"] == variant["product_id"])
+            })
+            .map(|variant| {
+                format!(
+                    "variant {} -> product {}",
+                    variant["id"], variant["product_id"]
+                )
+            })
+            .collect();
+        if !dangling.is_empty() {
+            return Err(Error::ValidationError(format!(
+                "Snapshot has dangling references: {}",
+                dangling.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
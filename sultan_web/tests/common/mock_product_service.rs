@@ -0,0 +1,407 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Mutex;
+use sultan_core::application::ProductServiceTrait;
+use sultan_core::domain::{
+    DomainResult, Error,
+    context::Context,
+    model::{
+        money::Money,
+        pagination::PaginationOptions,
+        product::{
+            BundleComponent, Product, ProductComponent, ProductCreate, ProductDetail,
+            ProductFilter, ProductSupplier, ProductType, ProductUpdate, ProductVariant,
+            ProductVariantCreate, ProductVariantFilter, ProductVariantUpdate, ProductVariantUpsert,
+            ProductWithVariants, VariantPriceChange,
+        },
+        stock::StockLevel,
+    },
+};
+
+/// Serves product 1 only. Its `updated_at` is fixed until `update_product`
+/// is called, which moves it forward like a real write would. It starts with
+/// two variants; `create_variant` adds one without touching `updated_at`.
+pub struct MockProductService {
+    pub should_succeed: bool,
+    updated_at: Mutex<DateTime<Utc>>,
+    variant_count: Mutex<u64>,
+}
+
+impl MockProductService {
+    pub fn new_success() -> Self {
+        Self {
+            should_succeed: true,
+            updated_at: Mutex::new(Utc::now()),
+            variant_count: Mutex::new(2),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn new_failure() -> Self {
+        Self {
+            should_succeed: false,
+            ..Self::new_success()
+        }
+    }
+
+    fn product(&self, id: i64) -> Product {
+        let updated_at = *self.updated_at.lock().unwrap();
+        Product {
+            id,
+            created_at: updated_at,
+            updated_at,
+            deleted_at: None,
+            is_deleted: false,
+            name: "Kopi Susu".to_string(),
+            description: None,
+            product_type: ProductType::Product,
+            main_image: None,
+            sellable: true,
+            buyable: true,
+            editable_price: false,
+            has_variant: false,
+            metadata: None,
+            created_by: None,
+            updated_by: None,
+        }
+    }
+
+    fn unsupported<T>() -> DomainResult<T> {
+        Err(Error::Internal(
+            "Not supported by MockProductService".to_string(),
+        ))
+    }
+}
+
+#[async_trait]
+impl ProductServiceTrait for MockProductService {
+    async fn create_product(
+        &self,
+        _ctx: &Context,
+        _product: &ProductCreate,
+        _variants: &[ProductVariantCreate],
+    ) -> DomainResult<i64> {
+        Self::unsupported()
+    }
+
+    async fn update_product(
+        &self,
+        _ctx: &Context,
+        id: i64,
+        _product: &ProductUpdate,
+    ) -> DomainResult<()> {
+        if !self.should_succeed {
+            return Err(Error::Internal("Failed to update product".to_string()));
+        }
+        if id != 1 {
+            return Err(Error::NotFound(format!("Product with id {} not found", id)));
+        }
+        *self.updated_at.lock().unwrap() += Duration::seconds(1);
+        Ok(())
+    }
+
+    async fn delete_product(&self, _ctx: &Context, _id: i64) -> DomainResult<()> {
+        Self::unsupported()
+    }
+
+    async fn add_categories(
+        &self,
+        _ctx: &Context,
+        _product_id: i64,
+        _category_ids: &[i64],
+    ) -> DomainResult<()> {
+        Self::unsupported()
+    }
+
+    async fn remove_categories(
+        &self,
+        _ctx: &Context,
+        _product_id: i64,
+        _category_ids: &[i64],
+    ) -> DomainResult<()> {
+        Self::unsupported()
+    }
+
+    async fn set_suppliers(
+        &self,
+        _ctx: &Context,
+        _product_id: i64,
+        _suppliers: &[ProductSupplier],
+    ) -> DomainResult<()> {
+        Self::unsupported()
+    }
+
+    async fn get_suppliers(
+        &self,
+        _ctx: &Context,
+        _product_id: i64,
+    ) -> DomainResult<Vec<ProductSupplier>> {
+        Self::unsupported()
+    }
+
+    async fn set_components(
+        &self,
+        _ctx: &Context,
+        _bundle_variant_id: i64,
+        _components: &[ProductComponent],
+    ) -> DomainResult<()> {
+        Self::unsupported()
+    }
+
+    async fn explode_bundle(
+        &self,
+        _ctx: &Context,
+        _bundle_variant_id: i64,
+    ) -> DomainResult<Vec<BundleComponent>> {
+        Self::unsupported()
+    }
+
+    async fn adjust_stock(
+        &self,
+        _ctx: &Context,
+        _variant_id: i64,
+        _branch_id: i64,
+        _delta: i64,
+        _reason: &str,
+    ) -> DomainResult<i64> {
+        Self::unsupported()
+    }
+
+    /// Branch 1 stocks variants 101 (2 units) and 102 (8 units)
+    async fn list_low_stock(
+        &self,
+        _ctx: &Context,
+        branch_id: i64,
+        threshold: i64,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<StockLevel>> {
+        if !self.should_succeed {
+            return Err(Error::Internal("Failed to list stock".to_string()));
+        }
+        let levels = [(101, 2), (102, 8)]
+            .into_iter()
+            .filter(|(_, quantity)| branch_id == 1 && *quantity <= threshold)
+            .map(|(variant_id, quantity)| StockLevel {
+                branch_id,
+                variant_id,
+                product_id: 1,
+                product_name: "Kopi Susu".to_string(),
+                variant_name: None,
+                barcode: None,
+                quantity,
+            })
+            .skip(pagination.offset() as usize)
+            .take(pagination.limit() as usize)
+            .collect();
+        Ok(levels)
+    }
+
+    async fn get_by_id(&self, _ctx: &Context, id: i64) -> DomainResult<Option<Product>> {
+        if !self.should_succeed {
+            return Err(Error::Internal("Failed to get product".to_string()));
+        }
+        if id != 1 {
+            return Ok(None);
+        }
+        Ok(Some(self.product(id)))
+    }
+
+    async fn get_product_with_variants(
+        &self,
+        _ctx: &Context,
+        id: i64,
+    ) -> DomainResult<Option<ProductDetail>> {
+        if !self.should_succeed {
+            return Err(Error::Internal("Failed to get product".to_string()));
+        }
+        if id != 1 {
+            return Ok(None);
+        }
+        let product = self.product(id);
+        let variant = |variant_id: i64, name: &str, price: i64| ProductVariant {
+            id: variant_id,
+            created_at: product.created_at,
+            updated_at: product.updated_at,
+            deleted_at: None,
+            is_deleted: false,
+            product: product.clone(),
+            barcode: Some(format!("89912345678{}", variant_id)),
+            name: Some(name.to_string()),
+            price: Some(Money::from_major(price)),
+            metadata: None,
+        };
+        let variants = vec![variant(10, "Regular", 18), variant(11, "Large", 22)];
+        Ok(Some(ProductDetail {
+            product,
+            variants,
+            category_ids: vec![1],
+        }))
+    }
+
+    async fn get_by_category(
+        &self,
+        _ctx: &Context,
+        category_id: i64,
+        pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<Product>> {
+        if !self.should_succeed {
+            return Err(Error::Internal("Failed to get products".to_string()));
+        }
+        // Category 1 holds products 1..=3, every other category is empty
+        if category_id != 1 {
+            return Ok(Vec::new());
+        }
+        Ok((1..=3)
+            .skip(pagination.offset() as usize)
+            .take(pagination.limit() as usize)
+            .map(|id| self.product(id))
+            .collect())
+    }
+
+    async fn count_by_category(&self, _ctx: &Context, category_id: i64) -> DomainResult<u64> {
+        if !self.should_succeed {
+            return Err(Error::Internal("Failed to count products".to_string()));
+        }
+        Ok(if category_id == 1 { 3 } else { 0 })
+    }
+
+    async fn get_all(
+        &self,
+        _ctx: &Context,
+        _filter: &ProductFilter,
+        _pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<ProductWithVariants>> {
+        Self::unsupported()
+    }
+
+    async fn count(&self, _ctx: &Context, _filter: &ProductFilter) -> DomainResult<u64> {
+        if !self.should_succeed {
+            return Err(Error::Internal("Failed to count products".to_string()));
+        }
+        Ok(3)
+    }
+
+    async fn create_variant(
+        &self,
+        _ctx: &Context,
+        variant: &ProductVariantCreate,
+    ) -> DomainResult<i64> {
+        if variant.product_id != 1 {
+            return Err(Error::NotFound(format!(
+                "Product with id {} not found",
+                variant.product_id
+            )));
+        }
+        let mut count = self.variant_count.lock().unwrap();
+        *count += 1;
+        Ok(100 + *count as i64)
+    }
+
+    async fn update_variant(
+        &self,
+        _ctx: &Context,
+        _id: i64,
+        _variant: &ProductVariantUpdate,
+    ) -> DomainResult<()> {
+        Self::unsupported()
+    }
+
+    async fn upsert_variant_by_barcode(
+        &self,
+        _ctx: &Context,
+        _product_id: i64,
+        _variant: &ProductVariantUpsert,
+    ) -> DomainResult<(i64, bool)> {
+        Self::unsupported()
+    }
+
+    async fn delete_variant(&self, _ctx: &Context, _id: i64) -> DomainResult<()> {
+        Self::unsupported()
+    }
+
+    async fn reassign_variant(
+        &self,
+        _ctx: &Context,
+        _variant_id: i64,
+        _new_product_id: i64,
+    ) -> DomainResult<()> {
+        Self::unsupported()
+    }
+
+    async fn delete_variants_by_product_id(
+        &self,
+        _ctx: &Context,
+        _product_id: i64,
+    ) -> DomainResult<()> {
+        Self::unsupported()
+    }
+
+    async fn get_variant_by_barcode(
+        &self,
+        _ctx: &Context,
+        _barcode: &str,
+    ) -> DomainResult<Option<ProductVariant>> {
+        Self::unsupported()
+    }
+
+    async fn get_variant_by_id(
+        &self,
+        _ctx: &Context,
+        _id: i64,
+    ) -> DomainResult<Option<ProductVariant>> {
+        Self::unsupported()
+    }
+
+    async fn get_variant_by_product_id(
+        &self,
+        _ctx: &Context,
+        _product_id: i64,
+    ) -> DomainResult<Vec<ProductVariant>> {
+        Self::unsupported()
+    }
+
+    async fn get_variants(
+        &self,
+        _ctx: &Context,
+        _filter: &ProductVariantFilter,
+        _pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<ProductVariant>> {
+        Self::unsupported()
+    }
+
+    async fn count_variants(&self, _ctx: &Context, product_id: i64) -> DomainResult<u64> {
+        if !self.should_succeed {
+            return Err(Error::Internal("Failed to count variants".to_string()));
+        }
+        Ok(if product_id == 1 {
+            *self.variant_count.lock().unwrap()
+        } else {
+            0
+        })
+    }
+
+    async fn get_categories(&self, _ctx: &Context, product_id: i64) -> DomainResult<Vec<i64>> {
+        if !self.should_succeed {
+            return Err(Error::Internal("Failed to get categories".to_string()));
+        }
+        Ok(if product_id == 1 { vec![1] } else { Vec::new() })
+    }
+
+    async fn price_for(
+        &self,
+        _ctx: &Context,
+        _variant_id: i64,
+        _level: i32,
+    ) -> DomainResult<Money> {
+        Self::unsupported()
+    }
+
+    async fn get_price_history(
+        &self,
+        _ctx: &Context,
+        _variant_id: i64,
+        _pagination: &PaginationOptions,
+    ) -> DomainResult<Vec<VariantPriceChange>> {
+        Self::unsupported()
+    }
+}
@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use sultan_core::application::SaleServiceTrait;
+use sultan_core::domain::{
+    DomainResult, Error,
+    context::Context,
+    model::{
+        money::Money,
+        sale::{Sale, SaleCreate, SaleKind, SaleLine, SaleRefundLine, SalesSummary},
+    },
+};
+
+/// Hands out increasing sale ids and, like the real service, returns the
+/// original id when an idempotency key is repeated. Sale 999 does not exist
+/// and sale 998 is already voided.
+pub struct MockSaleService {
+    pub should_succeed: bool,
+    next_id: Mutex<i64>,
+    keys: Mutex<HashMap<String, i64>>,
+}
+
+impl MockSaleService {
+    pub fn new_success() -> Self {
+        Self {
+            should_succeed: true,
+            next_id: Mutex::new(1),
+            keys: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn next_id(&self) -> i64 {
+        let mut next_id = self.next_id.lock().unwrap();
+        let id = *next_id;
+        *next_id += 1;
+        id
+    }
+
+    fn check_reversible(sale_id: i64) -> DomainResult<()> {
+        match sale_id {
+            999 => Err(Error::NotFound(format!(
+                "Sale with id {} not found",
+                sale_id
+            ))),
+            998 => Err(Error::Conflict(format!(
+                "Sale {} is already voided",
+                sale_id
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn new_failure() -> Self {
+        Self {
+            should_succeed: false,
+            ..Self::new_success()
+        }
+    }
+}
+
+#[async_trait]
+impl SaleServiceTrait for MockSaleService {
+    async fn create(
+        &self,
+        _ctx: &Context,
+        _sale: &SaleCreate,
+        idempotency_key: Option<&str>,
+    ) -> DomainResult<i64> {
+        if !self.should_succeed {
+            return Err(Error::ValidationError(
+                "Insufficient stock for variant 1".to_string(),
+            ));
+        }
+
+        let mut keys = self.keys.lock().unwrap();
+        if let Some(id) = idempotency_key.and_then(|key| keys.get(key)) {
+            return Ok(*id);
+        }
+
+        let id = self.next_id();
+        if let Some(key) = idempotency_key {
+            keys.insert(key.to_string(), id);
+        }
+        Ok(id)
+    }
+
+    async fn get_by_id(&self, _ctx: &Context, id: i64) -> DomainResult<Option<Sale>> {
+        if !self.should_succeed || id == 999 {
+            return Ok(None);
+        }
+        Ok(Some(Sale {
+            id,
+            branch_id: 1,
+            customer_id: None,
+            payment_method: "cash".to_string(),
+            kind: SaleKind::Sale,
+            original_sale_id: None,
+            lines: vec![SaleLine {
+                variant_id: 10,
+                qty: 2,
+                unit_price: Money::from_minor(1250),
+                line_total: Money::from_minor(2500),
+            }],
+            total: Money::from_minor(2500),
+            created_at: Utc::now(),
+        }))
+    }
+
+    async fn void(&self, _ctx: &Context, sale_id: i64) -> DomainResult<i64> {
+        Self::check_reversible(sale_id)?;
+        Ok(self.next_id())
+    }
+
+    /// The mock sale sold 2 units of variant 10.
+    async fn refund_lines(
+        &self,
+        _ctx: &Context,
+        sale_id: i64,
+        lines: &[SaleRefundLine],
+    ) -> DomainResult<i64> {
+        Self::check_reversible(sale_id)?;
+        if lines.iter().any(|l| l.variant_id != 10 || l.qty > 2) {
+            return Err(Error::ValidationError(
+                "Refund quantity exceeds the quantity sold".to_string(),
+            ));
+        }
+        Ok(self.next_id())
+    }
+
+    async fn summary(
+        &self,
+        _ctx: &Context,
+        branch_id: i64,
+        date: NaiveDate,
+    ) -> DomainResult<SalesSummary> {
+        Ok(SalesSummary {
+            branch_id,
+            date,
+            sale_count: 0,
+            gross_total: Money::ZERO,
+            by_payment_method: vec![],
+        })
+    }
+}
@@ -94,6 +94,8 @@ impl SupplierServiceTrait for MockSupplierService {
                 npwp: Some("12.345.678.9-012.000".to_string()),
                 npwp_name: Some("PT Test Supplier".to_string()),
                 metadata: None,
+                created_by: None,
+                updated_by: None,
             }))
         } else {
             Ok(None)
@@ -104,7 +106,7 @@ impl SupplierServiceTrait for MockSupplierService {
         &self,
         _ctx: &Context,
         filter: &SupplierFilter,
-        _pagination: &PaginationOptions,
+        pagination: &PaginationOptions,
     ) -> DomainResult<Vec<Supplier>> {
         if !self.should_succeed {
             return Err(Error::Internal("Failed to get suppliers".to_string()));
@@ -133,6 +135,8 @@ impl SupplierServiceTrait for MockSupplierService {
                 npwp: Some("12.345.678.9-012.000".to_string()),
                 npwp_name: Some("PT Test Supplier".to_string()),
                 metadata: None,
+                created_by: None,
+                updated_by: None,
             },
             Supplier {
                 id: 2,
@@ -148,6 +152,8 @@ impl SupplierServiceTrait for MockSupplierService {
                 npwp: None,
                 npwp_name: None,
                 metadata: None,
+                created_by: None,
+                updated_by: None,
             },
         ];
 
@@ -168,6 +174,15 @@ impl SupplierServiceTrait for MockSupplierService {
             suppliers.retain(|s| s.npwp.as_ref().is_some_and(|n| n.contains(npwp)));
         }
 
-        Ok(suppliers)
+        Ok(suppliers
+            .into_iter()
+            .skip(pagination.offset() as usize)
+            .take(pagination.limit() as usize)
+            .collect())
+    }
+
+    async fn count(&self, ctx: &Context, filter: &SupplierFilter) -> DomainResult<u64> {
+        let all = PaginationOptions::new(1, u32::MAX, None);
+        Ok(self.get_all(ctx, filter, &all).await?.len() as u64)
     }
 }
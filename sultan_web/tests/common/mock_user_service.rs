@@ -63,6 +63,9 @@ impl UserServiceTrait for MockUserService {
                 pin: None,
                 address: None,
                 phone: None,
+                is_enabled: true,
+                failed_login_count: 0,
+                locked_until: None,
                 permissions: None,
             }))
         } else {
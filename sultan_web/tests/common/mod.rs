@@ -1,28 +1,36 @@
 pub mod mock_auth_service;
 pub mod mock_category_service;
 pub mod mock_customer_service;
+pub mod mock_export_service;
+pub mod mock_product_service;
+pub mod mock_sale_service;
 pub mod mock_supplier_service;
 pub mod mock_user_service;
 
 pub use mock_auth_service::MockAuthService;
 pub use mock_category_service::MockCategoryService;
 pub use mock_customer_service::MockCustomerService;
+pub use mock_export_service::MockExportService;
+pub use mock_product_service::MockProductService;
+pub use mock_sale_service::MockSaleService;
 pub use mock_supplier_service::MockSupplierService;
 pub use mock_user_service::MockUserService;
 
 use anyhow::Result;
 use axum::Router;
 use axum::body::Body;
-use axum::http::{Request, StatusCode};
+use axum::http::{HeaderMap, Request, StatusCode};
 use serde_json::Value;
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::sync::Arc;
 use sultan_core::application::{
-    AuthServiceTrait, CategoryServiceTrait, CustomerServiceTrait, SupplierServiceTrait,
-    UserServiceTrait,
+    AuthServiceTrait, CategoryServiceTrait, CustomerServiceTrait, ExportServiceTrait,
+    ProductServiceTrait, SaleServiceTrait, SupplierServiceTrait, UserServiceTrait,
 };
 use sultan_core::crypto::{DefaultJwtManager, JwtConfig};
+use sultan_core::domain::{AuditSink, model::pagination::DEFAULT_MAX_PAGE_SIZE};
+use sultan_core::snowflake::{IdGenerator, SnowflakeGenerator};
 use sultan_web::AppState;
 use tower::ServiceExt;
 
@@ -31,8 +39,14 @@ pub struct MockAppStateBuilder {
     auth_service: Option<Arc<dyn AuthServiceTrait>>,
     category_service: Option<Arc<dyn CategoryServiceTrait>>,
     customer_service: Option<Arc<dyn CustomerServiceTrait>>,
+    export_service: Option<Arc<dyn ExportServiceTrait>>,
+    product_service: Option<Arc<dyn ProductServiceTrait>>,
+    sale_service: Option<Arc<dyn SaleServiceTrait>>,
     supplier_service: Option<Arc<dyn SupplierServiceTrait>>,
     user_service: Option<Arc<dyn UserServiceTrait>>,
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    id_generator: Option<Arc<dyn IdGenerator>>,
+    max_page_size: u32,
     extensions: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
 }
 
@@ -43,8 +57,14 @@ impl MockAppStateBuilder {
             auth_service: None,
             category_service: None,
             customer_service: None,
+            export_service: None,
+            product_service: None,
+            sale_service: None,
             supplier_service: None,
             user_service: None,
+            audit_sink: None,
+            id_generator: None,
+            max_page_size: DEFAULT_MAX_PAGE_SIZE,
             extensions: HashMap::new(),
         }
     }
@@ -70,6 +90,27 @@ impl MockAppStateBuilder {
         self
     }
 
+    /// Override the export service
+    #[allow(dead_code)]
+    pub fn with_export_service(mut self, service: Arc<dyn ExportServiceTrait>) -> Self {
+        self.export_service = Some(service);
+        self
+    }
+
+    /// Override the product service
+    #[allow(dead_code)]
+    pub fn with_product_service(mut self, service: Arc<dyn ProductServiceTrait>) -> Self {
+        self.product_service = Some(service);
+        self
+    }
+
+    /// Override the sale service
+    #[allow(dead_code)]
+    pub fn with_sale_service(mut self, service: Arc<dyn SaleServiceTrait>) -> Self {
+        self.sale_service = Some(service);
+        self
+    }
+
     /// Override the supplier service
     #[allow(dead_code)]
     pub fn with_supplier_service(mut self, service: Arc<dyn SupplierServiceTrait>) -> Self {
@@ -84,6 +125,27 @@ impl MockAppStateBuilder {
         self
     }
 
+    /// Set the sink authenticated contexts report permission denials to
+    #[allow(dead_code)]
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
+    }
+
+    /// Override the id generator shared by the app
+    #[allow(dead_code)]
+    pub fn with_id_generator(mut self, id_generator: Arc<dyn IdGenerator>) -> Self {
+        self.id_generator = Some(id_generator);
+        self
+    }
+
+    /// Override the cap on client-requested page sizes
+    #[allow(dead_code)]
+    pub fn with_max_page_size(mut self, max_page_size: u32) -> Self {
+        self.max_page_size = max_page_size;
+        self
+    }
+
     /// Add an extension to the AppState
     #[allow(dead_code)]
     pub fn add_extension<T: Send + Sync + 'static>(mut self, value: Arc<T>) -> Self {
@@ -109,12 +171,26 @@ impl MockAppStateBuilder {
             customer_service: self
                 .customer_service
                 .unwrap_or_else(|| Arc::new(MockCustomerService::new_success())),
+            export_service: self
+                .export_service
+                .unwrap_or_else(|| Arc::new(MockExportService::new_success())),
+            product_service: self
+                .product_service
+                .unwrap_or_else(|| Arc::new(MockProductService::new_success())),
+            sale_service: self
+                .sale_service
+                .unwrap_or_else(|| Arc::new(MockSaleService::new_success())),
             supplier_service: self
                 .supplier_service
                 .unwrap_or_else(|| Arc::new(MockSupplierService::new_success())),
             user_service: self
                 .user_service
                 .unwrap_or_else(|| Arc::new(MockUserService::new_success())),
+            id_generator: self
+                .id_generator
+                .unwrap_or_else(|| Arc::new(SnowflakeGenerator::new(1).unwrap())),
+            audit_sink: self.audit_sink,
+            max_page_size: self.max_page_size,
             extensions: Arc::new(self.extensions),
         }
     }
@@ -159,3 +235,47 @@ pub async fn make_request(
 
     Ok((status, json))
 }
+
+/// Make a GET request, optionally with `If-None-Match`, and return the raw response
+#[allow(dead_code)]
+pub async fn make_conditional_get(
+    app: Router,
+    uri: &str,
+    if_none_match: Option<&str>,
+) -> Result<(StatusCode, HeaderMap, String)> {
+    let mut request = Request::builder().method("GET").uri(uri);
+    if let Some(etag) = if_none_match {
+        request = request.header("if-none-match", etag);
+    }
+    let request = request.body(Body::empty())?;
+
+    let response = app.oneshot(request).await?;
+    let status = response.status();
+    let headers = response.headers().clone();
+
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, headers, String::from_utf8(body_bytes.to_vec())?))
+}
+
+/// Make an HTTP request with a raw text body and return the raw response
+#[allow(dead_code)]
+pub async fn make_raw_request(
+    app: Router,
+    method: &str,
+    uri: &str,
+    content_type: &str,
+    body: &str,
+) -> Result<(StatusCode, HeaderMap, String)> {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", content_type)
+        .body(Body::from(body.to_string()))?;
+
+    let response = app.oneshot(request).await?;
+    let status = response.status();
+    let headers = response.headers().clone();
+
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, headers, String::from_utf8(body_bytes.to_vec())?))
+}
@@ -1,13 +1,19 @@
 mod common;
 
 use axum::Router;
+use axum::extract::DefaultBodyLimit;
 use axum::http::StatusCode;
-use axum::middleware::from_fn;
+use axum::middleware::from_fn_with_state;
 use serde_json::json;
 use std::sync::Arc;
 
-use common::{MockAppStateBuilder, make_request, mock_customer_service::MockCustomerService};
-use sultan_web::handler::customer_router::customer_router;
+use common::{
+    MockAppStateBuilder, make_conditional_get, make_raw_request, make_request,
+    mock_customer_service::MockCustomerService,
+};
+use sultan_core::domain::model::{Update, filter::FilterOp};
+use sultan_web::dto::customer::{CustomerQueryParams, CustomerUpdateRequest};
+use sultan_web::handler::customer_router::{IMPORT_BODY_LIMIT, customer_router};
 use sultan_web::handler::middleware::context_middleware;
 
 // ============================================================================
@@ -16,10 +22,11 @@ use sultan_web::handler::middleware::context_middleware;
 
 /// Helper function to build a test router with the context middleware
 fn build_test_router(app_state: MockAppStateBuilder) -> Router {
+    let state = app_state.build();
     Router::new()
-        .nest("/api/customer", customer_router())
-        .layer(from_fn(context_middleware))
-        .with_state(app_state.build())
+        .nest("/api/customer", customer_router(IMPORT_BODY_LIMIT))
+        .layer(from_fn_with_state(state.clone(), context_middleware))
+        .with_state(state)
 }
 
 // ============================================================================
@@ -195,6 +202,53 @@ async fn test_delete_customer_success() {
     assert_eq!(status, StatusCode::NO_CONTENT);
 }
 
+// ============================================================================
+// POST /api/customer/bulk-delete - Bulk Delete Customer Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_bulk_delete_customers_best_effort() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let body = json!({ "ids": [1, 999] });
+    let (status, response) = make_request(app, "POST", "/api/customer/bulk-delete", Some(body))
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["deleted"], json!([1]));
+    assert_eq!(response["not_found"], json!([999]));
+}
+
+#[tokio::test]
+async fn test_bulk_delete_customers_all_or_nothing() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let body = json!({ "ids": [1, 999], "all_or_nothing": true });
+    let (status, response) = make_request(app, "POST", "/api/customer/bulk-delete", Some(body))
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["deleted"], json!([]));
+    assert_eq!(response["not_found"], json!([999]));
+}
+
+#[tokio::test]
+async fn test_bulk_delete_customers_service_error() {
+    let mock_service = Arc::new(MockCustomerService::new_failure());
+    let app_state = MockAppStateBuilder::new().with_customer_service(mock_service);
+    let app = build_test_router(app_state);
+
+    let body = json!({ "ids": [1] });
+    let (status, response) = make_request(app, "POST", "/api/customer/bulk-delete", Some(body))
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(response.get("error").is_some());
+}
+
 // ============================================================================
 // GET /api/customer/{id} - Get Customer by ID Tests
 // ============================================================================
@@ -239,6 +293,44 @@ async fn test_get_customer_by_id_success() {
     assert!(response.get("email").is_some());
 }
 
+#[tokio::test]
+async fn test_get_customer_by_id_conditional() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, headers, body) = make_conditional_get(app.clone(), "/api/customer/1", None)
+        .await
+        .expect("Request failed");
+    assert_eq!(status, StatusCode::OK);
+    assert!(!body.is_empty());
+    let etag = headers["etag"].to_str().unwrap().to_string();
+    assert!(etag.starts_with("W/\""));
+
+    let (status, headers, body) = make_conditional_get(app.clone(), "/api/customer/1", Some(&etag))
+        .await
+        .expect("Request failed");
+    assert_eq!(status, StatusCode::NOT_MODIFIED);
+    assert!(body.is_empty());
+    assert_eq!(headers["etag"], etag.as_str());
+
+    let (status, _) = make_request(
+        app.clone(),
+        "PUT",
+        "/api/customer/1",
+        Some(json!({ "name": "Updated Name" })),
+    )
+    .await
+    .expect("Request failed");
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, headers, body) = make_conditional_get(app, "/api/customer/1", Some(&etag))
+        .await
+        .expect("Request failed");
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(headers["etag"], etag.as_str());
+    let response: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(response["id"], 1);
+}
+
 // ============================================================================
 // GET /api/customer - Get All Customers Tests
 // ============================================================================
@@ -300,6 +392,38 @@ async fn test_get_all_customers_with_pagination() {
     assert_eq!(response["customers"].as_array().unwrap().len(), 2);
 }
 
+#[tokio::test]
+async fn test_get_all_customers_total_count_header() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, headers, body) = make_conditional_get(app, "/api/customer?page_size=2", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    let response: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(response["customers"].as_array().unwrap().len(), 2);
+    // Counts every matching customer, not just this page
+    assert_eq!(
+        headers["x-total-count"],
+        common::mock_customer_service::MOCK_CUSTOMER_TOTAL.to_string()
+    );
+}
+
+#[tokio::test]
+async fn test_get_all_customers_total_count_header_empty() {
+    let app_state = MockAppStateBuilder::new()
+        .with_customer_service(Arc::new(MockCustomerService::new_empty()));
+    let app = build_test_router(app_state);
+
+    let (status, headers, _body) = make_conditional_get(app, "/api/customer", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers["x-total-count"], "0");
+}
+
 #[tokio::test]
 async fn test_get_all_customers_with_name_filter() {
     let app = build_test_router(MockAppStateBuilder::new());
@@ -327,6 +451,56 @@ async fn test_get_all_customers_with_level_filter() {
     assert_eq!(response["customers"].as_array().unwrap().len(), 2);
 }
 
+#[tokio::test]
+async fn test_get_all_customers_with_search() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(app, "GET", "/api/customer?search=0812&level=1", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["customers"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_customer_query_search_maps_to_filter() {
+    let query: CustomerQueryParams =
+        serde_json::from_value(json!({ "search": "0812", "level": 1 })).unwrap();
+
+    let filter = query.to_filter().unwrap();
+    assert_eq!(filter.search.as_deref(), Some("0812"));
+    assert_eq!(filter.level, Some(1));
+}
+
+#[test]
+fn test_customer_query_filter_expressions() {
+    let query: CustomerQueryParams =
+        serde_json::from_value(json!({ "filter": "level:gt:1;number:in:C001|C002" })).unwrap();
+
+    let filter = query.to_filter().unwrap();
+    assert_eq!(filter.expressions.len(), 2);
+    assert_eq!(filter.expressions[0].op, FilterOp::Gt);
+    assert_eq!(filter.expressions[1].op, FilterOp::In);
+}
+
+#[tokio::test]
+async fn test_get_all_customers_rejects_unknown_filter_field() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(
+        app,
+        "GET",
+        "/api/customer?filter=name%3B%20DROP%20TABLE%20customers:eq:x",
+        None,
+    )
+    .await
+    .expect("Request failed");
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(response.get("error").is_some());
+}
+
 #[tokio::test]
 async fn test_get_all_customers_with_multiple_filters() {
     let app = build_test_router(MockAppStateBuilder::new());
@@ -370,3 +544,322 @@ async fn test_get_all_customers_with_phone_filter() {
     assert!(response["customers"].is_array());
     assert_eq!(response["customers"].as_array().unwrap().len(), 2);
 }
+
+// ============================================================================
+// POST /api/customer/import - CSV Import Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_import_customers_csv_success() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let csv = "number,name,email,phone,level\n\
+               CUST001,John Doe,john@example.com,555-1234,1\n\
+               CUST002,Jane Smith,,,2\n";
+
+    let (status, _headers, body) =
+        make_raw_request(app, "POST", "/api/customer/import", "text/csv", csv)
+            .await
+            .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    let response: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(response["imported"].as_array().unwrap().len(), 2);
+    assert!(response["errors"].as_array().unwrap().is_empty());
+    assert_eq!(response["aborted"], false);
+}
+
+#[tokio::test]
+async fn test_import_customers_csv_partially_invalid() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let csv = "number,name,email,phone,level\n\
+               CUST001,John Doe,john@example.com,555-1234,1\n\
+               CUST002,,jane@example.com,,2\n\
+               CUST003,Bob,,,not-a-number\n\
+               CUST004,Alice,,,3\n";
+
+    let (status, _headers, body) =
+        make_raw_request(app, "POST", "/api/customer/import", "text/csv", csv)
+            .await
+            .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    let response: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(response["imported"].as_array().unwrap().len(), 2);
+    assert_eq!(response["aborted"], false);
+
+    let errors = response["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0]["line"], 3);
+    assert!(errors[0]["message"].as_str().unwrap().contains("Name"));
+    assert_eq!(errors[1]["line"], 4);
+}
+
+#[tokio::test]
+async fn test_import_customers_csv_abort_on_error() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let csv = "number,name,email,phone,level\n\
+               CUST001,John Doe,,,1\n\
+               CUST002,,,,2\n";
+
+    let (status, _headers, body) = make_raw_request(
+        app,
+        "POST",
+        "/api/customer/import?abort_on_error=true",
+        "text/csv",
+        csv,
+    )
+    .await
+    .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    let response: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert!(response["imported"].as_array().unwrap().is_empty());
+    assert_eq!(response["errors"].as_array().unwrap().len(), 1);
+    assert_eq!(response["aborted"], true);
+}
+
+#[tokio::test]
+async fn test_import_customers_csv_service_error_reported_per_row() {
+    let mock_service = Arc::new(MockCustomerService::new_failure());
+    let app_state = MockAppStateBuilder::new().with_customer_service(mock_service);
+    let app = build_test_router(app_state);
+
+    let csv = "number,name,email,phone,level\nCUST001,John Doe,,,1\n";
+
+    let (status, _headers, body) =
+        make_raw_request(app, "POST", "/api/customer/import", "text/csv", csv)
+            .await
+            .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    let response: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let errors = response["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["line"], 2);
+}
+
+#[tokio::test]
+async fn test_import_customers_csv_exceeds_default_body_limit() {
+    // A tight app-wide limit applies to JSON routes but not to CSV import
+    let app = build_test_router(MockAppStateBuilder::new()).layer(DefaultBodyLimit::max(1024));
+
+    let mut csv = "number,name,email,phone,level\n".to_string();
+    for i in 0..100 {
+        csv.push_str(&format!("CUST{:03},Customer Number {},,,1\n", i, i));
+    }
+    assert!(csv.len() > 1024);
+
+    let (status, _headers, body) = make_raw_request(
+        app.clone(),
+        "POST",
+        "/api/customer/import",
+        "text/csv",
+        &csv,
+    )
+    .await
+    .expect("Request failed");
+    assert_eq!(status, StatusCode::OK);
+    let response: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(response["imported"].as_array().unwrap().len(), 100);
+
+    let payload = json!({
+        "number": "CUST001",
+        "name": "x".repeat(2048),
+        "level": 1
+    });
+    let (status, _headers, _body) = make_raw_request(
+        app,
+        "POST",
+        "/api/customer",
+        "application/json",
+        &payload.to_string(),
+    )
+    .await
+    .expect("Request failed");
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+// ============================================================================
+// GET /api/customer/export.csv - CSV Export Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_export_customers_csv_content() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, headers, body) = make_raw_request(
+        app,
+        "GET",
+        "/api/customer/export.csv?name=J",
+        "text/csv",
+        "",
+    )
+    .await
+    .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(
+        headers["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/csv")
+    );
+    let lines: Vec<&str> = body.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "number,name,email,phone,level",
+            "CUST001,John Doe,test@customer.com,555-1234,1",
+            "CUST002,Jane Smith,test@customer.com,555-1234,1",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_export_customers_csv_empty() {
+    let mock_service = Arc::new(MockCustomerService::new_empty());
+    let app_state = MockAppStateBuilder::new().with_customer_service(mock_service);
+    let app = build_test_router(app_state);
+
+    let (status, _headers, body) =
+        make_raw_request(app, "GET", "/api/customer/export.csv", "text/csv", "")
+            .await
+            .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.trim_end(), "number,name,email,phone,level");
+}
+
+#[tokio::test]
+async fn test_export_customers_csv_not_shifted_by_concurrent_inserts() {
+    let total = 250;
+    let mock_service = Arc::new(MockCustomerService::new_paged(total).with_inserts_per_page(3));
+    let app_state = MockAppStateBuilder::new().with_customer_service(mock_service);
+    let app = build_test_router(app_state);
+
+    let (status, _headers, body) =
+        make_raw_request(app, "GET", "/api/customer/export.csv", "text/csv", "")
+            .await
+            .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    let lines: Vec<&str> = body.lines().collect();
+    assert_eq!(lines[0], "number,name,email,phone,level");
+    let numbers: Vec<&str> = lines[1..]
+        .iter()
+        .map(|line| line.split(',').next().unwrap())
+        .collect();
+    let expected: Vec<String> = (1..=total)
+        .rev()
+        .map(|id| format!("CUST{:05}", id))
+        .collect();
+    assert_eq!(numbers, expected);
+}
+
+#[tokio::test]
+async fn test_export_customers_csv_service_error() {
+    let mock_service = Arc::new(MockCustomerService::new_failure());
+    let app_state = MockAppStateBuilder::new().with_customer_service(mock_service);
+    let app = build_test_router(app_state);
+
+    let (status, _headers, _body) =
+        make_raw_request(app, "GET", "/api/customer/export.csv", "text/csv", "")
+            .await
+            .expect("Request failed");
+
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+// ============================================================================
+// GET /api/customer/stream - NDJSON Stream Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_stream_customers_ndjson_spans_pages() {
+    // More than two export pages, the last one short
+    let total = 250;
+    let mock_service = Arc::new(MockCustomerService::new_paged(total));
+    let app_state = MockAppStateBuilder::new().with_customer_service(mock_service);
+    let app = build_test_router(app_state);
+
+    let (status, headers, body) = make_conditional_get(app, "/api/customer/stream", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers["content-type"], "application/x-ndjson");
+    let lines: Vec<serde_json::Value> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line is a JSON object"))
+        .collect();
+    assert_eq!(lines.len() as u64, total);
+    assert_eq!(lines[0]["id"], 250);
+    assert_eq!(lines[249]["id"], 1);
+    assert_eq!(lines[249]["number"], "CUST00001");
+}
+
+#[tokio::test]
+async fn test_stream_customers_not_shifted_by_concurrent_inserts() {
+    let total = 250;
+    let mock_service = Arc::new(MockCustomerService::new_paged(total).with_inserts_per_page(3));
+    let app_state = MockAppStateBuilder::new().with_customer_service(mock_service);
+    let app = build_test_router(app_state);
+
+    let (status, _headers, body) = make_conditional_get(app, "/api/customer/stream", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    // Customers created after the first page sort before it and are not
+    // part of the export; every original customer appears exactly once
+    let ids: Vec<i64> = body
+        .lines()
+        .map(|line| {
+            let row: serde_json::Value = serde_json::from_str(line).unwrap();
+            row["id"].as_i64().unwrap()
+        })
+        .collect();
+    assert_eq!(ids, (1..=total as i64).rev().collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_stream_customers_empty() {
+    let mock_service = Arc::new(MockCustomerService::new_empty());
+    let app_state = MockAppStateBuilder::new().with_customer_service(mock_service);
+    let app = build_test_router(app_state);
+
+    let (status, _headers, body) = make_conditional_get(app, "/api/customer/stream", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.is_empty());
+}
+
+#[tokio::test]
+async fn test_stream_customers_service_error() {
+    let mock_service = Arc::new(MockCustomerService::new_failure());
+    let app_state = MockAppStateBuilder::new().with_customer_service(mock_service);
+    let app = build_test_router(app_state);
+
+    let (status, _headers, _body) = make_conditional_get(app, "/api/customer/stream", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[test]
+fn test_customer_update_request_patch_semantics() {
+    let request: CustomerUpdateRequest =
+        serde_json::from_str(r#"{"name": "Renamed", "email": null, "phone": "+6281234567890"}"#)
+            .unwrap();
+
+    assert_eq!(request.address, Update::Unchanged);
+    assert_eq!(request.metadata, Update::Unchanged);
+    assert_eq!(request.email, Update::Clear);
+    assert_eq!(request.phone, Update::Set("+6281234567890".to_string()));
+}
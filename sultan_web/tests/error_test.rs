@@ -0,0 +1,218 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use axum::middleware::from_fn_with_state;
+use axum::{Router, extract::Path, routing::get};
+use serde_json::Value;
+use tower::ServiceExt;
+
+use common::{MockAppStateBuilder, make_request};
+use sultan_core::domain::{DomainResult, Error};
+use sultan_web::handler::middleware::context_middleware;
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+async fn failing_handler(Path(kind): Path<String>) -> DomainResult<&'static str> {
+    let err = match kind.as_str() {
+        "validation" => Error::ValidationError("Name is required".to_string()),
+        "credentials" => Error::InvalidCredentials,
+        "unauthorized" => Error::Unauthorized("Token expired".to_string()),
+        "forbidden" => Error::Forbidden("No access to branch".to_string()),
+        "not_found" => Error::NotFound("Product not found".to_string()),
+        "conflict" => Error::Conflict("Sale already voided".to_string()),
+        "cancelled" => Error::Cancelled("Client went away".to_string()),
+        "database" => Error::Database("UNIQUE constraint failed: users.username".to_string()),
+        "internal" => Error::Internal("lock poisoned".to_string()),
+        _ => return Ok("ok"),
+    };
+    Err(err)
+}
+
+fn build_test_router() -> Router {
+    Router::new()
+        .route("/fail/{kind}", get(failing_handler))
+        .with_state(MockAppStateBuilder::new().build())
+}
+
+/// Request `/fail/{kind}` through context_middleware with `Accept-Language`
+async fn localized_error(kind: &str, accept_language: &str) -> (StatusCode, Value) {
+    let app = build_test_router().layer(from_fn_with_state(
+        MockAppStateBuilder::new().build(),
+        context_middleware,
+    ));
+    let request = Request::builder()
+        .uri(format!("/fail/{}", kind))
+        .header(header::ACCEPT_LANGUAGE, accept_language)
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+async fn assert_error(kind: &str, status: StatusCode, code: &str, message: &str) {
+    let (actual_status, body) =
+        make_request(build_test_router(), "GET", &format!("/fail/{}", kind), None)
+            .await
+            .expect("Request failed");
+
+    assert_eq!(actual_status, status, "status for {}", kind);
+    let object = body
+        .as_object()
+        .expect("error body should be a JSON object");
+    assert_eq!(
+        object.len(),
+        2,
+        "only the error and code fields are returned: {}",
+        body
+    );
+    assert_eq!(object["error"], Value::String(message.to_string()));
+    assert_eq!(object["code"], Value::String(code.to_string()));
+}
+
+// ============================================================================
+// Error variant mapping
+// ============================================================================
+
+#[tokio::test]
+async fn test_validation_error_is_bad_request() {
+    assert_error(
+        "validation",
+        StatusCode::BAD_REQUEST,
+        "validation",
+        "Name is required",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_auth_errors_are_unauthorized() {
+    assert_error(
+        "credentials",
+        StatusCode::UNAUTHORIZED,
+        "invalid_credentials",
+        "Invalid credentials",
+    )
+    .await;
+    assert_error(
+        "unauthorized",
+        StatusCode::UNAUTHORIZED,
+        "unauthorized",
+        "Token expired",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_forbidden_error() {
+    assert_error(
+        "forbidden",
+        StatusCode::FORBIDDEN,
+        "forbidden",
+        "No access to branch",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_not_found_error() {
+    assert_error(
+        "not_found",
+        StatusCode::NOT_FOUND,
+        "not_found",
+        "Product not found",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_conflict_error() {
+    assert_error(
+        "conflict",
+        StatusCode::CONFLICT,
+        "conflict",
+        "Sale already voided",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_cancelled_error_is_client_closed_request() {
+    assert_error(
+        "cancelled",
+        StatusCode::from_u16(499).unwrap(),
+        "cancelled",
+        "Client went away",
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_server_errors_hide_details() {
+    assert_error(
+        "database",
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "database",
+        "Database error",
+    )
+    .await;
+    assert_error(
+        "internal",
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "internal",
+        "Internal error",
+    )
+    .await;
+}
+
+// ============================================================================
+// Localization
+// ============================================================================
+
+#[tokio::test]
+async fn test_not_found_localized_by_accept_language() {
+    let (en_status, en) = localized_error("not_found", "en-US,en;q=0.9").await;
+    let (id_status, id) = localized_error("not_found", "id-ID,id;q=0.9,en;q=0.8").await;
+
+    assert_eq!(en_status, StatusCode::NOT_FOUND);
+    assert_eq!(id_status, StatusCode::NOT_FOUND);
+    assert_eq!(
+        en,
+        serde_json::json!({"error": "Product not found", "code": "not_found"})
+    );
+    assert_eq!(id["error"], "Data tidak ditemukan");
+    assert_eq!(id["code"], "not_found");
+    assert_eq!(id["detail"], "Product not found");
+}
+
+#[tokio::test]
+async fn test_validation_error_localized() {
+    let (status, body) = localized_error("validation", "id").await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "Data yang dikirim tidak valid");
+    assert_eq!(body["detail"], "Name is required");
+}
+
+#[tokio::test]
+async fn test_unsupported_locale_falls_back_to_english() {
+    let (status, body) = localized_error("not_found", "fr-FR").await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"], "Product not found");
+}
+
+#[tokio::test]
+async fn test_localized_server_error_keeps_details_hidden() {
+    let (_, body) = localized_error("database", "id").await;
+
+    assert_eq!(body["error"], "Terjadi kesalahan basis data");
+    assert_eq!(body["detail"], "Database error");
+}
@@ -0,0 +1,52 @@
+use axum::http::{HeaderMap, HeaderValue, header};
+use chrono::{Duration, TimeZone, Utc};
+use sultan_web::handler::etag::{if_none_match, weak_etag, weak_etag_with};
+
+fn headers_with(value: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(value).unwrap());
+    headers
+}
+
+#[test]
+fn test_weak_etag_changes_with_updated_at() {
+    let first = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let second = first + Duration::milliseconds(1);
+
+    assert!(weak_etag(1, &first).starts_with("W/\""));
+    assert_eq!(weak_etag(1, &first), weak_etag(1, &first));
+    assert_ne!(weak_etag(1, &first), weak_etag(1, &second));
+    assert_ne!(weak_etag(1, &first), weak_etag(2, &first));
+}
+
+#[test]
+fn test_weak_etag_with_changes_with_extra() {
+    let updated_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+
+    assert!(weak_etag_with(1, &updated_at, 2).starts_with("W/\""));
+    assert_eq!(
+        weak_etag_with(1, &updated_at, 2),
+        weak_etag_with(1, &updated_at, 2)
+    );
+    assert_ne!(
+        weak_etag_with(1, &updated_at, 2),
+        weak_etag_with(1, &updated_at, 3)
+    );
+}
+
+#[test]
+fn test_if_none_match() {
+    let updated_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let etag = weak_etag(1, &updated_at);
+    let strong = etag.trim_start_matches("W/").to_string();
+
+    assert!(if_none_match(&headers_with(&etag), &etag));
+    assert!(if_none_match(&headers_with(&strong), &etag));
+    assert!(if_none_match(
+        &headers_with(&format!("W/\"other\", {}", etag)),
+        &etag
+    ));
+    assert!(if_none_match(&headers_with("*"), &etag));
+    assert!(!if_none_match(&headers_with("W/\"other\""), &etag));
+    assert!(!if_none_match(&HeaderMap::new(), &etag));
+}
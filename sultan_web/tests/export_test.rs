@@ -0,0 +1,159 @@
+mod common;
+
+use axum::Router;
+use axum::http::StatusCode;
+use axum::middleware::from_fn_with_state;
+use serde_json::json;
+use std::sync::Arc;
+
+use common::{MockAppStateBuilder, MockExportService, make_request};
+use sultan_web::handler::export_router::{IMPORT_BODY_LIMIT, export_router, import_router};
+use sultan_web::handler::middleware::context_middleware;
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn build_test_router(app_state: MockAppStateBuilder) -> Router {
+    let state = app_state.build();
+    Router::new()
+        .nest("/api/export", export_router())
+        .nest("/api/import", import_router(IMPORT_BODY_LIMIT))
+        .layer(from_fn_with_state(state.clone(), context_middleware))
+        .with_state(state)
+}
+
+fn ids(value: &serde_json::Value) -> Vec<i64> {
+    value
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| row["id"].as_i64().unwrap())
+        .collect()
+}
+
+// ============================================================================
+// GET /api/export/full
+// ============================================================================
+
+#[tokio::test]
+async fn test_export_full_writes_every_table() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(app, "GET", "/api/export/full", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    let object = response.as_object().unwrap();
+    assert_eq!(
+        object.keys().map(String::as_str).collect::<Vec<_>>(),
+        vec![
+            "categories",
+            "customers",
+            "products",
+            "suppliers",
+            "units",
+            "variants"
+        ]
+    );
+    // The deleted product is left out
+    assert_eq!(ids(&response["products"]), vec![1]);
+    assert_eq!(ids(&response["customers"]), vec![3]);
+    assert!(response["variants"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_export_full_includes_deleted_when_requested() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) =
+        make_request(app, "GET", "/api/export/full?include_deleted=true", None)
+            .await
+            .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    // Both pages of products are joined into one array
+    assert_eq!(ids(&response["products"]), vec![1, 2]);
+    assert_eq!(response["products"][1]["name"], "Teh Manis");
+}
+
+#[tokio::test]
+async fn test_export_full_forbidden() {
+    let app = build_test_router(
+        MockAppStateBuilder::new().with_export_service(Arc::new(MockExportService::new_failure())),
+    );
+
+    let (status, response) = make_request(app, "GET", "/api/export/full", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(response["error"].is_string());
+}
+
+// ============================================================================
+// POST /api/import/full
+// ============================================================================
+
+#[tokio::test]
+async fn test_import_full_success() {
+    let app = build_test_router(MockAppStateBuilder::new());
+    let body = json!({
+        "products": [{"id": 1, "name": "Kopi Susu"}],
+        "variants": [{"id": 2, "product_id": 1}],
+        "units": [{"id": 3, "name": "pcs"}]
+    });
+
+    let (status, response) = make_request(app, "POST", "/api/import/full?mode=upsert", Some(body))
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["imported"]["products"], 1);
+    assert_eq!(response["imported"]["variants"], 1);
+    assert_eq!(response["imported"]["units"], 1);
+    assert_eq!(response["imported"]["customers"], 0);
+}
+
+#[tokio::test]
+async fn test_import_full_dangling_reference() {
+    let app = build_test_router(MockAppStateBuilder::new());
+    let body = json!({"variants": [{"id": 2, "product_id": 99}]});
+
+    let (status, response) = make_request(app, "POST", "/api/import/full", Some(body))
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(
+        response["error"]
+            .as_str()
+            .unwrap()
+            .contains("variant 2 -> product 99")
+    );
+}
+
+#[tokio::test]
+async fn test_import_full_unknown_mode() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, _) = make_request(app, "POST", "/api/import/full?mode=merge", Some(json!({})))
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_import_full_forbidden() {
+    let app = build_test_router(
+        MockAppStateBuilder::new().with_export_service(Arc::new(MockExportService::new_failure())),
+    );
+
+    let (status, _) = make_request(app, "POST", "/api/import/full", Some(json!({})))
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
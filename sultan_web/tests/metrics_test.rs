@@ -0,0 +1,37 @@
+mod common;
+
+use axum::{Router, http::StatusCode, middleware::from_fn, routing::get};
+use common::*;
+use sultan_web::handler::metrics::{metrics_handler, prometheus_handle, track_metrics};
+
+fn create_test_app() -> Router {
+    Router::new()
+        .route("/ping/{id}", get(|| async { "pong" }))
+        .route("/metrics", get(metrics_handler))
+        .route_layer(from_fn(track_metrics))
+        .with_state(MockAppStateBuilder::new().build())
+}
+
+#[tokio::test]
+async fn test_metrics_counts_requests_by_route() {
+    prometheus_handle();
+    let app = create_test_app();
+
+    let (status, _, _) = make_conditional_get(app.clone(), "/ping/42", None)
+        .await
+        .unwrap();
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, headers, body) = make_conditional_get(app, "/metrics", None).await.unwrap();
+    assert_eq!(status, StatusCode::OK);
+    assert!(
+        headers["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain")
+    );
+    assert!(body.contains("http_requests_total"), "{}", body);
+    // Labelled with the route template, not the concrete path
+    assert!(body.contains(r#"route="/ping/{id}""#), "{}", body);
+    assert!(!body.contains("/ping/42"), "{}", body);
+}
@@ -10,10 +10,14 @@ use axum::{
     routing::get,
 };
 use serde_json::{Value, json};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use sultan_core::crypto::{DefaultJwtManager, JwtConfig, JwtManager};
-use sultan_core::domain::Context;
+use sultan_core::domain::model::permission::{action, resource};
+use sultan_core::domain::{AuditSink, BranchContext, Context, PermissionDenial};
+use sultan_core::snowflake::{IdGenerator, SnowflakeError};
 use sultan_web::handler::middleware::{context_middleware, verify_jwt};
+use tokio_util::sync::CancellationToken;
 use tower::ServiceExt;
 
 use common::{MockAppStateBuilder, mock_auth_service::MockAuthService};
@@ -37,6 +41,23 @@ async fn test_handler_with_context(Extension(ctx): Extension<Context>) -> impl I
     }))
 }
 
+// Test handler that echoes the branch context
+async fn test_handler_with_branches(Extension(ctx): Extension<Context>) -> impl IntoResponse {
+    let branch_ctx = ctx.branch_context().cloned().unwrap_or_default();
+    axum::Json(json!({
+        "branch_ids": branch_ctx.branch_ids,
+        "default_branch_id": branch_ctx.default_branch_id,
+    }))
+}
+
+// Test handler that echoes the request metadata
+async fn test_handler_with_metadata(Extension(ctx): Extension<Context>) -> impl IntoResponse {
+    axum::Json(json!({
+        "request_id": ctx.request_id(),
+        "actor_id": ctx.actor_id(),
+    }))
+}
+
 // Test handler without context
 async fn test_handler_no_auth() -> impl IntoResponse {
     axum::Json(json!({
@@ -49,7 +70,10 @@ async fn test_context_middleware_adds_context() {
     // Create a simple router with context middleware
     let app = Router::new()
         .route("/test", get(test_handler_no_auth))
-        .layer(middleware::from_fn(context_middleware));
+        .layer(middleware::from_fn_with_state(
+            MockAppStateBuilder::new().build(),
+            context_middleware,
+        ));
 
     let request = Request::builder().uri("/test").body(Body::empty()).unwrap();
 
@@ -62,6 +86,33 @@ async fn test_context_middleware_adds_context() {
     assert_eq!(json["message"], "no auth required");
 }
 
+#[tokio::test]
+async fn test_context_middleware_sets_locale() {
+    let app = Router::new()
+        .route(
+            "/test",
+            get(|Extension(ctx): Extension<Context>| async move {
+                axum::Json(json!({"locale": ctx.locale()}))
+            }),
+        )
+        .layer(middleware::from_fn_with_state(
+            MockAppStateBuilder::new().build(),
+            context_middleware,
+        ));
+
+    let request = Request::builder()
+        .uri("/test")
+        .header(header::ACCEPT_LANGUAGE, "fr;q=0.9, id-ID;q=0.8")
+        .body(Body::empty())
+        .unwrap();
+    let (_, json) = get_json_response(app.clone().oneshot(request).await.unwrap()).await;
+    assert_eq!(json["locale"], "id");
+
+    let request = Request::builder().uri("/test").body(Body::empty()).unwrap();
+    let (_, json) = get_json_response(app.oneshot(request).await.unwrap()).await;
+    assert_eq!(json["locale"], Value::Null);
+}
+
 #[tokio::test]
 async fn test_verify_jwt_missing_authorization_header() {
     // Setup
@@ -85,6 +136,7 @@ async fn test_verify_jwt_missing_authorization_header() {
     let (status, json) = get_json_response(response).await;
     assert_eq!(status, StatusCode::UNAUTHORIZED);
     assert_eq!(json["error"], "Missing or invalid authorization header");
+    assert_eq!(json["code"], "unauthorized");
 }
 
 #[tokio::test]
@@ -153,7 +205,9 @@ async fn test_verify_jwt_valid_token() {
         3600,
     ));
 
-    let token = jwt_manager.generate_token(123456, "testuser").unwrap();
+    let token = jwt_manager
+        .generate_token(123456, "testuser", None)
+        .unwrap();
 
     let app_state = MockAppStateBuilder::new()
         .with_auth_service(Arc::new(MockAuthService::new_success()))
@@ -253,10 +307,365 @@ async fn test_context_middleware_integration() {
                 axum::Json(json!({"status": "ok"}))
             }),
         )
-        .layer(middleware::from_fn(context_middleware));
+        .layer(middleware::from_fn_with_state(
+            MockAppStateBuilder::new().build(),
+            context_middleware,
+        ));
+
+    let request = Request::builder().uri("/test").body(Body::empty()).unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_verify_jwt_populates_branch_context_from_claims() {
+    let jwt_manager = DefaultJwtManager::new(JwtConfig::new(
+        "test_secret_key_which_is_long_enough".to_string(),
+        3600,
+    ));
+    let branches = BranchContext::new(vec![10, 20], Some(20));
+    let token = jwt_manager
+        .generate_token(123456, "testuser", Some(&branches))
+        .unwrap();
+
+    // The auth service would return a different set; claims must win
+    let app_state = MockAppStateBuilder::new()
+        .with_auth_service(Arc::new(
+            MockAuthService::new_success().with_branch_context(BranchContext::new(vec![99], None)),
+        ))
+        .build();
+
+    let app = Router::new()
+        .route("/test", get(test_handler_with_branches))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            verify_jwt,
+        ))
+        .with_state(app_state);
+
+    let request = Request::builder()
+        .uri("/test")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    let (status, json) = get_json_response(response).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["branch_ids"], json!([10, 20]));
+    assert_eq!(json["default_branch_id"], 20);
+}
+
+#[tokio::test]
+async fn test_verify_jwt_falls_back_to_branch_lookup() {
+    let jwt_manager = DefaultJwtManager::new(JwtConfig::new(
+        "test_secret_key_which_is_long_enough".to_string(),
+        3600,
+    ));
+    // Token issued without embedded branches
+    let token = jwt_manager
+        .generate_token(123456, "testuser", None)
+        .unwrap();
+
+    let app_state = MockAppStateBuilder::new()
+        .with_auth_service(Arc::new(
+            MockAuthService::new_success()
+                .with_branch_context(BranchContext::new(vec![7], Some(7))),
+        ))
+        .build();
+
+    let app = Router::new()
+        .route("/test", get(test_handler_with_branches))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            verify_jwt,
+        ))
+        .with_state(app_state);
+
+    let request = Request::builder()
+        .uri("/test")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    let (status, json) = get_json_response(response).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["branch_ids"], json!([7]));
+    assert_eq!(json["default_branch_id"], 7);
+}
+
+#[tokio::test]
+async fn test_verify_jwt_fails_when_branch_lookup_fails() {
+    let jwt_manager = DefaultJwtManager::new(JwtConfig::new(
+        "test_secret_key_which_is_long_enough".to_string(),
+        3600,
+    ));
+    let token = jwt_manager
+        .generate_token(123456, "testuser", None)
+        .unwrap();
+
+    let app_state = MockAppStateBuilder::new()
+        .with_auth_service(Arc::new(
+            MockAuthService::new_success().with_failing_branch_lookup(),
+        ))
+        .build();
+
+    let app = Router::new()
+        .route("/test", get(test_handler_with_branches))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            verify_jwt,
+        ))
+        .with_state(app_state);
+
+    let request = Request::builder()
+        .uri("/test")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+
+    let (status, json) = get_json_response(response).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(json["code"], "database");
+}
+
+#[tokio::test]
+async fn test_verify_jwt_sets_actor_and_keeps_request_id() {
+    let jwt_manager = DefaultJwtManager::new(JwtConfig::new(
+        "test_secret_key_which_is_long_enough".to_string(),
+        3600,
+    ));
+    let token = jwt_manager
+        .generate_token(123456, "testuser", None)
+        .unwrap();
+
+    let app_state = MockAppStateBuilder::new().build();
+    let app = Router::new()
+        .route("/test", get(test_handler_with_metadata))
+        .route_layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            verify_jwt,
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            context_middleware,
+        ))
+        .with_state(app_state);
+
+    let request = |token: &str| {
+        Request::builder()
+            .uri("/test")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let (status, first) =
+        get_json_response(app.clone().oneshot(request(&token)).await.unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(first["actor_id"], 123456);
+    assert!(first["request_id"].is_i64());
+
+    // Every request gets its own id
+    let (_, second) = get_json_response(app.oneshot(request(&token)).await.unwrap()).await;
+    assert_ne!(first["request_id"], second["request_id"]);
+}
+
+struct FixedIdGenerator(i64);
+
+impl IdGenerator for FixedIdGenerator {
+    fn generate(&self) -> Result<i64, SnowflakeError> {
+        Ok(self.0)
+    }
+}
+
+#[tokio::test]
+async fn test_context_middleware_takes_request_id_from_app_state() {
+    let app_state = MockAppStateBuilder::new()
+        .with_id_generator(Arc::new(FixedIdGenerator(4242)))
+        .build();
+    let app = Router::new()
+        .route("/test", get(test_handler_with_metadata))
+        .layer(middleware::from_fn_with_state(
+            app_state,
+            context_middleware,
+        ));
 
     let request = Request::builder().uri("/test").body(Body::empty()).unwrap();
+    let (status, json) = get_json_response(app.oneshot(request).await.unwrap()).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["request_id"], 4242);
+}
+
+#[tokio::test]
+async fn test_context_middleware_has_no_actor() {
+    let app = Router::new()
+        .route("/test", get(test_handler_with_metadata))
+        .layer(middleware::from_fn_with_state(
+            MockAppStateBuilder::new().build(),
+            context_middleware,
+        ));
+
+    let request = Request::builder().uri("/test").body(Body::empty()).unwrap();
+    let (status, json) = get_json_response(app.oneshot(request).await.unwrap()).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(json["request_id"].is_i64());
+    assert!(json["actor_id"].is_null());
+}
+
+/// Router whose handler hands its cancellation token out through `tx`
+fn token_capturing_app(
+    tx: tokio::sync::oneshot::Sender<CancellationToken>,
+    wait_forever: bool,
+) -> Router {
+    let tx = Arc::new(Mutex::new(Some(tx)));
+    Router::new()
+        .route(
+            "/work",
+            get(move |Extension(ctx): Extension<Context>| async move {
+                if let Some(tx) = tx.lock().unwrap().take() {
+                    let _ = tx.send(ctx.cancellation_token().clone());
+                }
+                if wait_forever {
+                    std::future::pending::<()>().await;
+                }
+            }),
+        )
+        .layer(middleware::from_fn_with_state(
+            MockAppStateBuilder::new().build(),
+            context_middleware,
+        ))
+}
+
+#[tokio::test]
+async fn test_context_middleware_cancels_token_when_request_is_dropped() {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let app = token_capturing_app(tx, true);
+
+    // Dropping the in-flight request is what hyper does when the client disconnects
+    let request = Request::builder().uri("/work").body(Body::empty()).unwrap();
+    let result = tokio::time::timeout(Duration::from_millis(50), app.oneshot(request)).await;
+    assert!(result.is_err());
+
+    assert!(rx.await.unwrap().is_cancelled());
+}
+
+#[tokio::test]
+async fn test_context_middleware_keeps_token_for_completed_request() {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let app = token_capturing_app(tx, false);
+
+    let request = Request::builder().uri("/work").body(Body::empty()).unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert!(!rx.await.unwrap().is_cancelled());
+}
+
+#[derive(Default)]
+struct RecordingSink {
+    denials: Mutex<Vec<PermissionDenial>>,
+}
+
+impl AuditSink for RecordingSink {
+    fn permission_denied(&self, denial: &PermissionDenial) {
+        self.denials.lock().unwrap().push(denial.clone());
+    }
+}
+
+#[tokio::test]
+async fn test_verify_jwt_attaches_audit_sink() {
+    let jwt_manager = DefaultJwtManager::new(JwtConfig::new(
+        "test_secret_key_which_is_long_enough".to_string(),
+        3600,
+    ));
+    let token = jwt_manager
+        .generate_token(123456, "testuser", None)
+        .unwrap();
 
+    let sink = Arc::new(RecordingSink::default());
+    let app_state = MockAppStateBuilder::new()
+        .with_audit_sink(sink.clone())
+        .build();
+    let app = Router::new()
+        .route(
+            "/test",
+            get(|Extension(ctx): Extension<Context>| async move {
+                // The mock user service grants no permissions
+                ctx.require_access(None, resource::PRODUCT, action::CREATE)
+                    .is_err()
+                    .to_string()
+            }),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            verify_jwt,
+        ))
+        .with_state(app_state);
+
+    let request = Request::builder()
+        .uri("/test")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .unwrap();
     let response = app.oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::OK);
+
+    let denials = sink.denials.lock().unwrap();
+    assert_eq!(denials.len(), 1);
+    assert_eq!(denials[0].actor_id, Some(123456));
+    assert_eq!(denials[0].resource, resource::PRODUCT);
+    assert_eq!(denials[0].action, action::CREATE);
+}
+
+#[tokio::test]
+async fn test_verify_jwt_rejects_revoked_device_token() {
+    let jwt_manager = DefaultJwtManager::new(JwtConfig::new(
+        "test_secret_key_which_is_long_enough".to_string(),
+        3600,
+    ));
+    let revoked = jwt_manager
+        .generate_device_token(123456, "kiosk", 7, None)
+        .unwrap();
+    let active = jwt_manager
+        .generate_device_token(123456, "kiosk", 8, None)
+        .unwrap();
+
+    let app_state = MockAppStateBuilder::new()
+        .with_auth_service(Arc::new(
+            MockAuthService::new_success().with_revoked_device(7),
+        ))
+        .build();
+    let app = Router::new()
+        .route("/test", get(test_handler_with_context))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            verify_jwt,
+        ))
+        .with_state(app_state);
+
+    let request = |token: &str| {
+        Request::builder()
+            .uri("/test")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let (status, json) =
+        get_json_response(app.clone().oneshot(request(&revoked)).await.unwrap()).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(json["error"], "Device has been revoked");
+
+    let (status, json) = get_json_response(app.oneshot(request(&active)).await.unwrap()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["user_id"], 123456);
 }
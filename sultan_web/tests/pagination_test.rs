@@ -0,0 +1,73 @@
+use axum::extract::Query;
+use axum::http::Uri;
+use sultan_core::domain::model::pagination::{
+    DEFAULT_MAX_PAGE_SIZE, DEFAULT_PAGE_SIZE, PaginationOptions,
+};
+use sultan_web::dto::PaginationQuery;
+use sultan_web::dto::customer::CustomerQueryParams;
+
+fn parse<T: serde::de::DeserializeOwned>(uri: &str) -> T {
+    let uri: Uri = uri.parse().unwrap();
+    Query::<T>::try_from_uri(&uri)
+        .expect("Query should parse")
+        .0
+}
+
+#[test]
+fn test_pagination_query_with_both_params() {
+    let query: PaginationQuery = parse("/items?page=3&page_size=50");
+    assert_eq!(query.page, 3);
+    assert_eq!(query.page_size, 50);
+}
+
+#[test]
+fn test_pagination_query_with_one_param() {
+    let query: PaginationQuery = parse("/items?page=4");
+    assert_eq!(query.page, 4);
+    assert_eq!(query.page_size, DEFAULT_PAGE_SIZE);
+
+    let query: PaginationQuery = parse("/items?page_size=5");
+    assert_eq!(query.page, 1);
+    assert_eq!(query.page_size, 5);
+}
+
+#[test]
+fn test_pagination_query_with_neither_param() {
+    let query: PaginationQuery = parse("/items");
+    assert_eq!(query.page, 1);
+    assert_eq!(query.page_size, DEFAULT_PAGE_SIZE);
+}
+
+#[test]
+fn test_pagination_query_rejects_non_numbers() {
+    let uri: Uri = "/items?page=two".parse().unwrap();
+    assert!(Query::<PaginationQuery>::try_from_uri(&uri).is_err());
+}
+
+#[test]
+fn test_pagination_query_into_clamps_bounds() {
+    let pagination: PaginationOptions =
+        parse::<PaginationQuery>("/items?page=0&page_size=0").into();
+    assert_eq!(pagination.page, 1);
+    assert_eq!(pagination.page_size, DEFAULT_PAGE_SIZE);
+
+    let pagination: PaginationOptions =
+        parse::<PaginationQuery>("/items?page=2&page_size=1000000").into();
+    assert_eq!(pagination.page, 2);
+    assert_eq!(pagination.page_size, DEFAULT_MAX_PAGE_SIZE);
+}
+
+#[test]
+fn test_flattened_into_customer_query() {
+    let query: CustomerQueryParams = parse("/api/customer?name=John&page=2&page_size=10");
+    assert_eq!(query.name.as_deref(), Some("John"));
+    assert_eq!(query.pagination.page, 2);
+    assert_eq!(query.pagination.page_size, 10);
+
+    let query: CustomerQueryParams = parse("/api/customer?name=John");
+    assert_eq!(query.pagination.page, 1);
+    assert_eq!(query.pagination.page_size, DEFAULT_PAGE_SIZE);
+
+    let pagination = query.to_pagination(5).unwrap();
+    assert_eq!(pagination.limit(), 5);
+}
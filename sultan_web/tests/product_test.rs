@@ -0,0 +1,179 @@
+mod common;
+
+use axum::Router;
+use axum::http::StatusCode;
+use axum::middleware::from_fn_with_state;
+use serde_json::Value;
+use std::sync::Arc;
+
+use common::{MockAppStateBuilder, MockProductService, make_conditional_get, make_request};
+use sultan_core::application::ProductServiceTrait;
+use sultan_core::domain::{
+    Context,
+    model::{
+        Update,
+        product::{ProductUpdate, ProductVariantCreate},
+    },
+};
+use sultan_web::handler::middleware::context_middleware;
+use sultan_web::handler::product_router::product_router;
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn build_test_router(app_state: MockAppStateBuilder) -> Router {
+    let state = app_state.build();
+    Router::new()
+        .nest("/api/product", product_router())
+        .layer(from_fn_with_state(state.clone(), context_middleware))
+        .with_state(state)
+}
+
+fn rename(name: &str) -> ProductUpdate {
+    ProductUpdate {
+        name: Some(name.to_string()),
+        description: Update::Unchanged,
+        product_type: None,
+        main_image: Update::Unchanged,
+        sellable: None,
+        buyable: None,
+        editable_price: None,
+        has_variant: None,
+        metadata: Update::Unchanged,
+        category_ids: None,
+    }
+}
+
+// ============================================================================
+// GET /api/product/{id} - Get Product Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_get_product_success() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(app, "GET", "/api/product/1", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["name"], "Kopi Susu");
+    assert_eq!(response["variant_count"], 2);
+    assert_eq!(response["category_ids"], serde_json::json!([1]));
+}
+
+#[tokio::test]
+async fn test_get_product_not_found() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(app, "GET", "/api/product/999", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(response.get("error").is_some());
+}
+
+#[tokio::test]
+async fn test_get_product_conditional() {
+    let service = Arc::new(MockProductService::new_success());
+    let app = build_test_router(MockAppStateBuilder::new().with_product_service(service.clone()));
+
+    let (status, headers, _) = make_conditional_get(app.clone(), "/api/product/1", None)
+        .await
+        .expect("Request failed");
+    assert_eq!(status, StatusCode::OK);
+    let etag = headers["etag"].to_str().unwrap().to_string();
+
+    let (status, _, body) = make_conditional_get(app.clone(), "/api/product/1", Some(&etag))
+        .await
+        .expect("Request failed");
+    assert_eq!(status, StatusCode::NOT_MODIFIED);
+    assert!(body.is_empty());
+
+    service
+        .update_product(&Context::new(), 1, &rename("Kopi Hitam"))
+        .await
+        .expect("Failed to update product");
+
+    let (status, headers, body) = make_conditional_get(app, "/api/product/1", Some(&etag))
+        .await
+        .expect("Request failed");
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(headers["etag"], etag.as_str());
+    let response: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(response["id"], 1);
+}
+
+#[tokio::test]
+async fn test_get_product_conditional_tracks_variant_count() {
+    let service = Arc::new(MockProductService::new_success());
+    let app = build_test_router(MockAppStateBuilder::new().with_product_service(service.clone()));
+
+    let (_, headers, _) = make_conditional_get(app.clone(), "/api/product/1", None)
+        .await
+        .expect("Request failed");
+    let etag = headers["etag"].to_str().unwrap().to_string();
+
+    // A new variant does not touch the product's updated_at
+    service
+        .create_variant(
+            &Context::new(),
+            &ProductVariantCreate {
+                product_id: 1,
+                barcode: None,
+                name: Some("Large".to_string()),
+                price: None,
+                metadata: None,
+                initial_stock: vec![],
+            },
+        )
+        .await
+        .expect("Failed to create variant");
+
+    let (status, headers, body) = make_conditional_get(app, "/api/product/1", Some(&etag))
+        .await
+        .expect("Request failed");
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(headers["etag"], etag.as_str());
+    let response: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(response["variant_count"], 3);
+}
+
+// ============================================================================
+// GET /api/product/{id}/detail - Get Product With Variants Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_get_product_with_variants_success() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(app, "GET", "/api/product/1/detail", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["name"], "Kopi Susu");
+    let variants = response["variants"].as_array().unwrap();
+    assert_eq!(variants.len(), 2);
+    assert_eq!(variants[0]["id"], 10);
+    assert_eq!(variants[0]["name"], "Regular");
+    assert_eq!(variants[0]["price"], "18.00");
+    assert_eq!(variants[1]["name"], "Large");
+    assert_eq!(response["category_ids"], serde_json::json!([1]));
+}
+
+#[tokio::test]
+async fn test_get_product_with_variants_not_found() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(app, "GET", "/api/product/999/detail", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(response.get("error").is_some());
+}
@@ -0,0 +1,235 @@
+mod common;
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::middleware::from_fn_with_state;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use common::{MockAppStateBuilder, MockSaleService, make_request};
+use sultan_web::handler::middleware::context_middleware;
+use sultan_web::handler::sale_router::sale_router;
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn build_test_router(app_state: MockAppStateBuilder) -> Router {
+    let state = app_state.build();
+    Router::new()
+        .nest("/api/sale", sale_router())
+        .layer(from_fn_with_state(state.clone(), context_middleware))
+        .with_state(state)
+}
+
+fn sale_body() -> Value {
+    json!({
+        "branch_id": 1,
+        "payment_method": "cash",
+        "lines": [
+            { "variant_id": 10, "qty": 2, "unit_price": "12.50" }
+        ]
+    })
+}
+
+async fn post_sale(app: Router, idempotency_key: Option<&str>) -> (StatusCode, Value) {
+    let mut request = Request::builder()
+        .method("POST")
+        .uri("/api/sale")
+        .header("content-type", "application/json");
+    if let Some(key) = idempotency_key {
+        request = request.header("Idempotency-Key", key);
+    }
+    let request = request
+        .body(Body::from(serde_json::to_vec(&sale_body()).unwrap()))
+        .unwrap();
+
+    let response = app.oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+// ============================================================================
+// POST /api/sale - Create Sale Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_create_sale_success() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = post_sale(app, None).await;
+
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(response["id"], 1);
+}
+
+#[tokio::test]
+async fn test_create_sale_repeated_idempotency_key_returns_same_id() {
+    let app = build_test_router(
+        MockAppStateBuilder::new().with_sale_service(Arc::new(MockSaleService::new_success())),
+    );
+
+    let (status, first) = post_sale(app.clone(), Some("pos-1-receipt-17")).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, second) = post_sale(app.clone(), Some("pos-1-receipt-17")).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (_, other) = post_sale(app, Some("pos-1-receipt-18")).await;
+
+    assert_eq!(first["id"], second["id"]);
+    assert_ne!(first["id"], other["id"]);
+}
+
+#[tokio::test]
+async fn test_create_sale_without_key_creates_each_time() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (_, first) = post_sale(app.clone(), None).await;
+    let (_, second) = post_sale(app, None).await;
+
+    assert_ne!(first["id"], second["id"]);
+}
+
+#[tokio::test]
+async fn test_create_sale_validation_error_no_lines() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let body = json!({
+        "branch_id": 1,
+        "payment_method": "cash",
+        "lines": []
+    });
+
+    let (status, response) = make_request(app, "POST", "/api/sale", Some(body))
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(response["error"].as_str().unwrap().contains("line"));
+}
+
+#[tokio::test]
+async fn test_create_sale_insufficient_stock() {
+    let app = build_test_router(
+        MockAppStateBuilder::new().with_sale_service(Arc::new(MockSaleService::new_failure())),
+    );
+
+    let (status, response) = post_sale(app, None).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(response["error"].as_str().unwrap().contains("stock"));
+}
+
+// ============================================================================
+// GET /api/sale/{id} - Get Sale Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_get_sale_success() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(app, "GET", "/api/sale/5", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["id"], 5);
+    assert_eq!(response["total"], "25.00");
+    assert_eq!(response["kind"], "sale");
+    assert_eq!(response["lines"][0]["unit_price"], "12.50");
+}
+
+#[tokio::test]
+async fn test_get_sale_not_found() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, _) = make_request(app, "GET", "/api/sale/999", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+// ============================================================================
+// POST /api/sale/{id}/void - Void Sale Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_void_sale_success() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(app, "POST", "/api/sale/5/void", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(response["id"], 1);
+}
+
+#[tokio::test]
+async fn test_void_sale_already_voided() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(app, "POST", "/api/sale/998/void", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(response["error"].as_str().unwrap().contains("voided"));
+}
+
+#[tokio::test]
+async fn test_void_sale_not_found() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, _) = make_request(app, "POST", "/api/sale/999/void", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+// ============================================================================
+// POST /api/sale/{id}/refund - Refund Sale Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_refund_sale_success() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let body = json!({ "lines": [{ "variant_id": 10, "qty": 1 }] });
+    let (status, response) = make_request(app, "POST", "/api/sale/5/refund", Some(body))
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(response["id"], 1);
+}
+
+#[tokio::test]
+async fn test_refund_sale_exceeds_quantity() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let body = json!({ "lines": [{ "variant_id": 10, "qty": 3 }] });
+    let (status, _) = make_request(app, "POST", "/api/sale/5/refund", Some(body))
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_refund_sale_validation_error_no_lines() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let body = json!({ "lines": [] });
+    let (status, _) = make_request(app, "POST", "/api/sale/5/refund", Some(body))
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
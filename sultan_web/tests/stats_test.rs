@@ -0,0 +1,51 @@
+mod common;
+
+use axum::Router;
+use axum::http::StatusCode;
+use axum::middleware::from_fn_with_state;
+use std::sync::Arc;
+
+use common::{
+    MockAppStateBuilder, make_request,
+    mock_customer_service::{MOCK_CUSTOMER_TOTAL, MockCustomerService},
+    mock_product_service::MockProductService,
+};
+use sultan_web::{handler::stats_router::stats_router, middleware::context_middleware};
+
+fn build_test_router(app_state: MockAppStateBuilder) -> Router {
+    let state = app_state.build();
+    Router::new()
+        .nest("/api/stats", stats_router())
+        .layer(from_fn_with_state(state.clone(), context_middleware))
+        .with_state(state)
+}
+
+#[tokio::test]
+async fn test_get_counts() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(app, "GET", "/api/stats/counts", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["products"], 3);
+    assert_eq!(response["customers"], MOCK_CUSTOMER_TOTAL);
+    assert_eq!(response["suppliers"], 2);
+    assert_eq!(response["categories"], 2);
+}
+
+#[tokio::test]
+async fn test_get_counts_fails_when_any_count_fails() {
+    let app_state = MockAppStateBuilder::new()
+        .with_product_service(Arc::new(MockProductService::new_success()))
+        .with_customer_service(Arc::new(MockCustomerService::new_failure()));
+    let app = build_test_router(app_state);
+
+    let (status, response) = make_request(app, "GET", "/api/stats/counts", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(response["code"], "internal");
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use axum::Router;
+use axum::http::StatusCode;
+use axum::middleware::from_fn_with_state;
+use serde_json::json;
+
+use common::{MockAppStateBuilder, make_conditional_get, make_request};
+use sultan_web::handler::middleware::context_middleware;
+use sultan_web::handler::stock_router::stock_router;
+
+fn build_test_router(app_state: MockAppStateBuilder) -> Router {
+    let state = app_state.build();
+    Router::new()
+        .nest("/api/stock", stock_router())
+        .layer(from_fn_with_state(state.clone(), context_middleware))
+        .with_state(state)
+}
+
+// ============================================================================
+// GET /api/stock - Low Stock Report Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_low_stock_filters_by_threshold() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) =
+        make_request(app.clone(), "GET", "/api/stock?branch_id=1&below=5", None)
+            .await
+            .expect("Request failed");
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["data"].as_array().unwrap().len(), 1);
+    assert_eq!(response["data"][0]["variant_id"], 101);
+    assert_eq!(response["data"][0]["quantity"], 2);
+    assert_eq!(response["data"][0]["product_name"], "Kopi Susu");
+
+    let (status, response) = make_request(app, "GET", "/api/stock?branch_id=1&below=8", None)
+        .await
+        .expect("Request failed");
+    assert_eq!(status, StatusCode::OK);
+    let quantities: Vec<_> = response["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|level| level["quantity"].clone())
+        .collect();
+    assert_eq!(quantities, vec![json!(2), json!(8)]);
+}
+
+#[tokio::test]
+async fn test_low_stock_paginates() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(
+        app,
+        "GET",
+        "/api/stock?branch_id=1&below=10&page=2&page_size=1",
+        None,
+    )
+    .await
+    .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["data"].as_array().unwrap().len(), 1);
+    assert_eq!(response["data"][0]["variant_id"], 102);
+}
+
+#[tokio::test]
+async fn test_low_stock_requires_branch_and_threshold() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    for uri in ["/api/stock?below=5", "/api/stock?branch_id=1"] {
+        // Query rejections are plain text, not JSON
+        let (status, _, _) = make_conditional_get(app.clone(), uri, None)
+            .await
+            .expect("Request failed");
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+    }
+}
@@ -2,11 +2,16 @@ mod common;
 
 use axum::Router;
 use axum::http::StatusCode;
-use axum::middleware::from_fn;
+use axum::middleware::from_fn_with_state;
 use serde_json::json;
 use std::sync::Arc;
 
-use common::{MockAppStateBuilder, make_request, mock_supplier_service::MockSupplierService};
+use common::{
+    MockAppStateBuilder, make_conditional_get, make_request,
+    mock_supplier_service::MockSupplierService,
+};
+use sultan_core::domain::model::Update;
+use sultan_web::dto::supplier::SupplierUpdateRequest;
 use sultan_web::{handler::supplier_routes::supplier_router, middleware::context_middleware};
 
 // ============================================================================
@@ -15,10 +20,11 @@ use sultan_web::{handler::supplier_routes::supplier_router, middleware::context_
 
 /// Helper function to build a test router with the context middleware
 fn build_test_router(app_state: MockAppStateBuilder) -> Router {
+    let state = app_state.build();
     Router::new()
         .nest("/api/supplier", supplier_router())
-        .layer(from_fn(context_middleware))
-        .with_state(app_state.build())
+        .layer(from_fn_with_state(state.clone(), context_middleware))
+        .with_state(state)
 }
 
 // ============================================================================
@@ -504,6 +510,58 @@ async fn test_get_all_suppliers_with_pagination() {
     assert!(data.len() <= 10); // Should respect page_size
 }
 
+#[tokio::test]
+async fn test_get_all_suppliers_total_count_header() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, headers, body) =
+        make_conditional_get(app, "/api/supplier?page=1&page_size=1", None)
+            .await
+            .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    let response: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(response["data"].as_array().unwrap().len(), 1);
+    assert_eq!(headers["x-total-count"], "2");
+}
+
+#[tokio::test]
+async fn test_get_all_suppliers_clamps_page_size() {
+    let app = build_test_router(MockAppStateBuilder::new().with_max_page_size(1));
+
+    let (status, response) = make_request(app, "GET", "/api/supplier?page_size=1000000", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["data"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_get_all_suppliers_rejects_page_zero() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, response) = make_request(app, "GET", "/api/supplier?page=0", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(response["error"], "page must be at least 1");
+}
+
+#[tokio::test]
+async fn test_get_all_suppliers_total_count_header_with_filter() {
+    let app = build_test_router(MockAppStateBuilder::new());
+
+    let (status, headers, _body) =
+        make_conditional_get(app, "/api/supplier?name=Another&page_size=1", None)
+            .await
+            .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers["x-total-count"], "1");
+}
+
 #[tokio::test]
 async fn test_get_all_suppliers_service_error() {
     // Setup - use mock supplier service that returns error
@@ -526,3 +584,17 @@ async fn test_get_all_suppliers_service_error() {
         error_msg
     );
 }
+
+#[test]
+fn test_supplier_update_request_patch_semantics() {
+    let request: SupplierUpdateRequest =
+        serde_json::from_str(r#"{"code": null, "npwp": "01.234.567.8-901.000"}"#).unwrap();
+
+    assert_eq!(request.address, Update::Unchanged);
+    assert_eq!(request.email, Update::Unchanged);
+    assert_eq!(request.code, Update::Clear);
+    assert_eq!(
+        request.npwp,
+        Update::Set("01.234.567.8-901.000".to_string())
+    );
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use axum::Router;
+use axum::http::StatusCode;
+use axum::middleware::from_fn_with_state;
+use std::sync::Arc;
+
+use common::{MockAppStateBuilder, make_request, mock_auth_service::MockAuthService};
+use sultan_web::handler::middleware::context_middleware;
+use sultan_web::handler::token_router::token_router;
+
+fn build_test_router(app_state: MockAppStateBuilder) -> Router {
+    let state = app_state.build();
+    Router::new()
+        .nest("/api/tokens", token_router())
+        .layer(from_fn_with_state(state.clone(), context_middleware))
+        .with_state(state)
+}
+
+#[tokio::test]
+async fn test_purge_expired_tokens() {
+    let app = build_test_router(MockAppStateBuilder::new().with_auth_service(Arc::new(
+        MockAuthService::new_success().with_expired_tokens(7),
+    )));
+
+    let (status, response) = make_request(app, "DELETE", "/api/tokens/expired", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response["purged"], 7);
+}
+
+#[tokio::test]
+async fn test_purge_expired_tokens_forbidden() {
+    let app = build_test_router(
+        MockAppStateBuilder::new().with_auth_service(Arc::new(MockAuthService::new_failure())),
+    );
+
+    let (status, response) = make_request(app, "DELETE", "/api/tokens/expired", None)
+        .await
+        .expect("Request failed");
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(response.get("error").is_some());
+}
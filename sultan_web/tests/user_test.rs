@@ -104,7 +104,7 @@ async fn test_verify_jwt_middleware_sets_correct_permissions() {
         3600,
     ));
 
-    let token = jwt_manager.generate_token(1, "testuser").unwrap();
+    let token = jwt_manager.generate_token(1, "testuser", None).unwrap();
 
     // Setup app state with mock user service
     let mock_user_service = Arc::new(MockUserService::new_success());
@@ -159,7 +159,7 @@ async fn test_verify_jwt_middleware_different_user_no_permissions() {
         3600,
     ));
 
-    let token = jwt_manager.generate_token(999, "otheruser").unwrap();
+    let token = jwt_manager.generate_token(999, "otheruser", None).unwrap();
 
     // Setup app state with mock user service
     let mock_user_service = Arc::new(MockUserService::new_success());
@@ -214,7 +214,7 @@ async fn test_verify_jwt_middleware_permission_caching() {
         3600,
     ));
 
-    let token = jwt_manager.generate_token(1, "testuser").unwrap();
+    let token = jwt_manager.generate_token(1, "testuser", None).unwrap();
 
     // Setup app state with mock user service
     let mock_user_service = Arc::new(MockUserService::new_success());
@@ -245,7 +245,7 @@ async fn test_verify_jwt_middleware_permission_caching() {
     assert_eq!(json1["has_user_read"], true);
 
     // Second request with same token - should get same permissions
-    let token2 = jwt_manager.generate_token(1, "testuser").unwrap();
+    let token2 = jwt_manager.generate_token(1, "testuser", None).unwrap();
     let request2 = Request::builder()
         .method("POST")
         .uri("/test")